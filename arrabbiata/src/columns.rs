@@ -1,3 +1,4 @@
+use crate::logup::LookupTable;
 use ark_ff::Field;
 use kimchi::circuits::expr::{AlphaChallengeTerm, CacheId, ConstantExpr, Expr, FormattedOutput};
 use serde::{Deserialize, Serialize};
@@ -6,6 +7,7 @@ use std::{
     fmt::{Display, Formatter, Result},
     ops::Index,
 };
+use strum::EnumCount as _;
 use strum_macros::{EnumCount as EnumCountMacro, EnumIter};
 
 /// This enum represents the different gadgets that can be used in the circuit.
@@ -24,16 +26,121 @@ pub enum Gadget {
     /// This gadget implement the Poseidon hash instance described in the
     /// top-level documentation. This implementation does use the "next row"
     /// to allow the computation of one additional round per row. In the current
-    /// setup, with [crate::NUMBER_OF_COLUMNS] columns, we can compute 5 full
+    /// setup, with [crate::NUMBER_OF_COLUMNS] columns, we can compute 4 full
     /// rounds per row.
     Poseidon,
+    /// This gadget exposes the hash of the public IO, computed by the gadget
+    /// [Gadget::Poseidon], as the only public output of the step. See the
+    /// [public_io](crate::public_io) module.
+    PublicIOHash,
+    /// This gadget loads the sponge state of the stream hash from the IVC
+    /// state, or saves it into the IVC state. See
+    /// [run_stream_hash](crate::interpreter::run_stream_hash).
+    StreamHashState,
+    /// This gadget updates the program counter carried in the IVC state, to
+    /// follow a [Schedule](crate::schedule::Schedule).
+    ProgramCounter,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Column {
     Selector(Gadget),
+    /// The public inputs of the circuit. The only public input is the hash of
+    /// the public IO, see [crate::NUMBER_OF_PUBLIC_INPUTS].
     PublicInput(usize),
+    /// A column whose values are defined at setup time, like the selectors.
+    /// It is used for the round constants of Poseidon and for the lengths of
+    /// the phases of the [schedule](crate::schedule).
+    Fixed(usize),
     X(usize),
+    /// The inverse `1 / (β + f_i)` of the i-th looked-up value of the row.
+    /// See the [logup](crate::logup) module.
+    LookupInverse(usize),
+    /// The multiplicities of the entries of the given table.
+    LookupMultiplicity(LookupTable),
+    /// The fixed column containing the entries of the given table, see
+    /// [LookupTable::fixed_column]. It is not committed by the prover, and it
+    /// is not folded, see the [logup](crate::logup) module.
+    LookupTable(LookupTable),
+    /// The term `m / (β + t)` for the given table.
+    LookupTableInverse(LookupTable),
+    /// The running sum of the lookup terms.
+    LookupAccumulator,
+    /// The challenge β of the lookup argument. The column has the same value
+    /// on all the rows, and is folded like the other columns, as the
+    /// constraints can not contain challenges when they are converted into
+    /// multivariate polynomials.
+    LookupChallenge,
+}
+
+impl Column {
+    /// Return the index of the variable describing the column, for a circuit
+    /// using the given number of columns. It is used when converting the
+    /// constraints into multivariate polynomials, see the
+    /// [cross_terms](crate::cross_terms) module.
+    ///
+    /// The variables are ordered as follow:
+    /// - the private columns,
+    /// - the public inputs,
+    /// - the fixed columns, whose number depends on the number of columns, see
+    /// [crate::number_of_fixed_columns],
+    /// - the selectors,
+    /// - the lookup columns, i.e. the inverses of the looked-up values, the
+    /// multiplicities, the entries and the inverses of each table, the
+    /// accumulator and the challenge β. See [crate::NUMBER_OF_LOOKUP_COLUMNS].
+    pub fn index(self, number_of_columns: usize) -> usize {
+        let number_of_fixed_columns = crate::number_of_fixed_columns(number_of_columns);
+        let first_fixed_column = number_of_columns + crate::NUMBER_OF_PUBLIC_INPUTS;
+        let first_selector = first_fixed_column + number_of_fixed_columns;
+        let first_lookup_column = first_selector + crate::NUMBER_OF_SELECTORS;
+        let first_table_column = first_lookup_column + crate::MAX_NUMBER_OF_LOOKUPS_PER_ROW;
+        match self {
+            Column::X(i) => {
+                assert!(
+                    i < number_of_columns,
+                    "Invalid column index {i}, only {number_of_columns} columns are supported"
+                );
+                i
+            }
+            Column::PublicInput(i) => {
+                assert!(
+                    i < crate::NUMBER_OF_PUBLIC_INPUTS,
+                    "Invalid public input index {i}, only {} public inputs are supported",
+                    crate::NUMBER_OF_PUBLIC_INPUTS
+                );
+                number_of_columns + i
+            }
+            Column::Fixed(i) => {
+                assert!(
+                    i < number_of_fixed_columns,
+                    "Invalid fixed column index {i}, only {number_of_fixed_columns} fixed columns are supported"
+                );
+                first_fixed_column + i
+            }
+            Column::Selector(gadget) => first_selector + gadget as usize,
+            Column::LookupInverse(i) => {
+                assert!(
+                    i < crate::MAX_NUMBER_OF_LOOKUPS_PER_ROW,
+                    "Invalid lookup index {i}, only {} lookups per row are supported",
+                    crate::MAX_NUMBER_OF_LOOKUPS_PER_ROW
+                );
+                first_lookup_column + i
+            }
+            Column::LookupMultiplicity(table) => first_table_column + 3 * table as usize,
+            Column::LookupTable(table) => first_table_column + 3 * table as usize + 1,
+            Column::LookupTableInverse(table) => first_table_column + 3 * table as usize + 2,
+            Column::LookupAccumulator => first_table_column + 3 * LookupTable::COUNT,
+            Column::LookupChallenge => first_table_column + 3 * LookupTable::COUNT + 1,
+        }
+    }
+}
+
+/// Convert a column into the index of a variable, using the default number of
+/// columns [crate::NUMBER_OF_COLUMNS]. See [Column::index] for the layout.
+impl From<Column> for usize {
+    fn from(col: Column) -> usize {
+        col.index(crate::NUMBER_OF_COLUMNS)
+    }
 }
 
 pub struct Challenges<F: Field> {
@@ -104,9 +211,19 @@ impl FormattedOutput for Column {
                 Gadget::EllipticCurveAddition => "q_ec_add".to_string(),
                 Gadget::EllipticCurveScaling => "q_ec_mul".to_string(),
                 Gadget::Poseidon => "q_pos".to_string(),
+                Gadget::PublicIOHash => "q_pub_io".to_string(),
+                Gadget::StreamHashState => "q_stream_state".to_string(),
+                Gadget::ProgramCounter => "q_pc".to_string(),
             },
             Column::PublicInput(i) => format!("pi_{{{i}}}").to_string(),
+            Column::Fixed(i) => format!("f_{{{i}}}").to_string(),
             Column::X(i) => format!("x_{{{i}}}").to_string(),
+            Column::LookupInverse(i) => format!("\\phi_{{{i}}}").to_string(),
+            Column::LookupMultiplicity(t) => format!("m_{{{t:?}}}").to_string(),
+            Column::LookupTable(t) => format!("t_{{{t:?}}}").to_string(),
+            Column::LookupTableInverse(t) => format!("\\psi_{{{t:?}}}").to_string(),
+            Column::LookupAccumulator => "acc_{lookup}".to_string(),
+            Column::LookupChallenge => "\\beta_{lookup}".to_string(),
         }
     }
 
@@ -117,9 +234,19 @@ impl FormattedOutput for Column {
                 Gadget::EllipticCurveAddition => "q_ec_add".to_string(),
                 Gadget::EllipticCurveScaling => "q_ec_mul".to_string(),
                 Gadget::Poseidon => "q_pos_next_row".to_string(),
+                Gadget::PublicIOHash => "q_pub_io".to_string(),
+                Gadget::StreamHashState => "q_stream_state".to_string(),
+                Gadget::ProgramCounter => "q_pc".to_string(),
             },
             Column::PublicInput(i) => format!("pi[{i}]"),
+            Column::Fixed(i) => format!("f[{i}]"),
             Column::X(i) => format!("x[{i}]"),
+            Column::LookupInverse(i) => format!("phi[{i}]"),
+            Column::LookupMultiplicity(t) => format!("m[{t:?}]"),
+            Column::LookupTable(t) => format!("t[{t:?}]"),
+            Column::LookupTableInverse(t) => format!("psi[{t:?}]"),
+            Column::LookupAccumulator => "acc_lookup".to_string(),
+            Column::LookupChallenge => "beta_lookup".to_string(),
         }
     }
 
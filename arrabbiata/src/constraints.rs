@@ -1,8 +1,9 @@
 use super::{columns::Column, interpreter::InterpreterEnv};
 use crate::{
     columns::{Gadget, E},
-    interpreter::{self, Instruction, Side},
-    MAX_DEGREE, NUMBER_OF_COLUMNS, NUMBER_OF_PUBLIC_INPUTS,
+    interpreter::{self, Hint, Instruction, Side},
+    logup::{Lookup, LookupTable},
+    MAX_DEGREE, MAX_NUMBER_OF_LOOKUPS_PER_ROW, NUMBER_OF_COLUMNS, NUMBER_OF_PUBLIC_INPUTS,
 };
 use ark_ff::{Field, PrimeField};
 use kimchi::circuits::{
@@ -12,6 +13,19 @@ use kimchi::circuits::{
 use log::debug;
 use num_bigint::BigInt;
 use o1_utils::FieldHelpers;
+use strum::IntoEnumIterator;
+
+/// One instruction activating each gadget, used to build the constraints of
+/// the gadgets. The constraints are the same whatever the value given in
+/// parameter of the instructions, therefore picking 0.
+const GADGET_INSTRUCTIONS: [Instruction; 6] = [
+    Instruction::Poseidon(0),
+    Instruction::EllipticCurveScaling(0, 0),
+    Instruction::EllipticCurveAddition(0),
+    Instruction::PublicIOHash,
+    Instruction::StreamHashLoadState,
+    Instruction::ProgramCounter(0),
+];
 
 #[derive(Clone, Debug)]
 pub struct Env<Fp: Field> {
@@ -20,24 +34,50 @@ pub struct Env<Fp: Field> {
     /// coordinates.
     // FIXME: this is ugly. Let use the curve as a parameter. Only lazy for now.
     pub a: BigInt,
+    /// The number of columns the circuit uses.
+    pub number_of_columns: usize,
+    /// The number of fixed columns the circuit uses, per row. It depends on
+    /// the number of columns, see [crate::number_of_fixed_columns].
+    pub number_of_fixed_columns: usize,
     pub idx_var: usize,
     pub idx_var_next_row: usize,
     pub idx_var_pi: usize,
+    pub idx_var_fixed: usize,
     pub constraints: Vec<E<Fp>>,
+    /// The lookups requested by the gadget, in the order they have been
+    /// performed.
+    pub lookups: Vec<Lookup<E<Fp>>>,
     pub activated_gadget: Option<Gadget>,
 }
 
 impl<Fp: PrimeField> Env<Fp> {
     pub fn new(poseidon_mds: Vec<Vec<Fp>>, a: BigInt) -> Self {
+        Self::new_with_number_of_columns(poseidon_mds, a, NUMBER_OF_COLUMNS)
+    }
+
+    /// Create a new environment building the constraints for a circuit using
+    /// `number_of_columns` columns. The layout of the gadgets adapts to the
+    /// number of columns, see [crate::check_number_of_columns] for the
+    /// requirements.
+    pub fn new_with_number_of_columns(
+        poseidon_mds: Vec<Vec<Fp>>,
+        a: BigInt,
+        number_of_columns: usize,
+    ) -> Self {
         // This check might not be useful
         assert!(a < Fp::modulus_biguint().into(), "a is too large");
+        crate::check_number_of_columns(number_of_columns);
         Self {
             poseidon_mds,
             a,
+            number_of_columns,
+            number_of_fixed_columns: crate::number_of_fixed_columns(number_of_columns),
             idx_var: 0,
             idx_var_next_row: 0,
             idx_var_pi: 0,
+            idx_var_fixed: 0,
             constraints: Vec::new(),
+            lookups: Vec::new(),
             activated_gadget: None,
         }
     }
@@ -53,15 +93,27 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
 
     type Variable = E<Fp>;
 
+    fn number_of_columns(&self) -> usize {
+        self.number_of_columns
+    }
+
     fn allocate(&mut self) -> Self::Position {
-        assert!(self.idx_var < NUMBER_OF_COLUMNS, "Maximum number of columns reached ({NUMBER_OF_COLUMNS}), increase the number of columns");
+        assert!(
+            self.idx_var < self.number_of_columns,
+            "Maximum number of columns reached ({}), increase the number of columns",
+            self.number_of_columns
+        );
         let pos = Column::X(self.idx_var);
         self.idx_var += 1;
         (pos, CurrOrNext::Curr)
     }
 
     fn allocate_next_row(&mut self) -> Self::Position {
-        assert!(self.idx_var_next_row < NUMBER_OF_COLUMNS, "Maximum number of columns reached ({NUMBER_OF_COLUMNS}), increase the number of columns");
+        assert!(
+            self.idx_var_next_row < self.number_of_columns,
+            "Maximum number of columns reached ({}), increase the number of columns",
+            self.number_of_columns
+        );
         let pos = Column::X(self.idx_var_next_row);
         self.idx_var_next_row += 1;
         (pos, CurrOrNext::Next)
@@ -73,12 +125,26 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
     }
 
     fn allocate_public_input(&mut self) -> Self::Position {
-        assert!(self.idx_var_pi < NUMBER_OF_PUBLIC_INPUTS, "Maximum number of public inputs reached ({NUMBER_OF_PUBLIC_INPUTS}), increase the number of public inputs");
+        assert!(
+            self.idx_var_pi < NUMBER_OF_PUBLIC_INPUTS,
+            "Maximum number of public inputs reached ({NUMBER_OF_PUBLIC_INPUTS})"
+        );
         let pos = Column::PublicInput(self.idx_var_pi);
         self.idx_var_pi += 1;
         (pos, CurrOrNext::Curr)
     }
 
+    fn allocate_fixed(&mut self) -> Self::Position {
+        assert!(
+            self.idx_var_fixed < self.number_of_fixed_columns,
+            "Maximum number of fixed columns reached ({}), increase the number of columns",
+            self.number_of_fixed_columns
+        );
+        let pos = Column::Fixed(self.idx_var_fixed);
+        self.idx_var_fixed += 1;
+        (pos, CurrOrNext::Curr)
+    }
+
     fn constant(&self, value: BigInt) -> Self::Variable {
         let v = value.to_biguint().unwrap();
         let v = Fp::from_biguint(&v).unwrap();
@@ -91,6 +157,11 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
         self.read_position(pos)
     }
 
+    /// Return the corresponding expression regarding the selected fixed column
+    fn write_fixed(&mut self, pos: Self::Position, _v: BigInt) -> Self::Variable {
+        self.read_position(pos)
+    }
+
     /// Return the corresponding expression regarding the selected column
     fn write_column(&mut self, pos: Self::Position, v: Self::Variable) -> Self::Variable {
         let (col, row) = pos;
@@ -115,6 +186,64 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
         let c = x.clone() * (x.clone() - one);
         self.constraints.push(c)
     }
+
+    fn range_check16(&mut self, pos: Self::Position) -> Self::Variable {
+        assert!(self.lookups.len() < MAX_NUMBER_OF_LOOKUPS_PER_ROW, "Maximum number of lookups reached ({MAX_NUMBER_OF_LOOKUPS_PER_ROW}), increase the number of lookups per row");
+        let value = self.read_position(pos);
+        self.lookups.push(Lookup {
+            table: LookupTable::RangeCheck16,
+            value: value.clone(),
+        });
+        value
+    }
+
+    // The index only selects the value in the witness. The cell is bound to
+    // the state by the public IO hash, which absorbs both `z_i` and
+    // `z_(i + 1)`, see [crate::public_io].
+    fn read_ivc_state(&mut self, pos: Self::Position, _i: usize) -> Self::Variable {
+        self.read_position(pos)
+    }
+
+    fn write_ivc_state(
+        &mut self,
+        pos: Self::Position,
+        _i: usize,
+        v: Self::Variable,
+    ) -> Self::Variable {
+        self.write_column(pos, v)
+    }
+
+    fn write_public_io_hash(&mut self, v: Self::Variable) -> Self::Variable {
+        let pos = self.allocate_public_input();
+        let res = self.read_position(pos);
+        self.assert_equal(res.clone(), v);
+        res
+    }
+
+    fn get_phase_length(&mut self, pos: Self::Position, _phase: usize) -> Self::Variable {
+        let (col, row) = pos;
+        match col {
+            Column::Fixed(_) => (),
+            _ => panic!("Only fixed columns can be used as phase lengths"),
+        };
+        Expr::Atom(ExprInner::Cell(Variable { col, row }))
+    }
+
+    fn load_stream_hash_state(&mut self, pos: Self::Position, _i: usize) -> Self::Variable {
+        self.read_position(pos)
+    }
+
+    // Witness-only
+    unsafe fn save_stream_hash_state(&mut self, _v: Self::Variable, _i: usize) {}
+
+    unsafe fn fetch_stream_value_to_absorb(
+        &mut self,
+        pos: Self::Position,
+        _curr_round: usize,
+    ) -> Self::Variable {
+        self.read_position(pos)
+    }
+
     fn assert_zero(&mut self, x: Self::Variable) {
         self.add_constraint(x);
     }
@@ -133,6 +262,14 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
         self.read_position(pos)
     }
 
+    unsafe fn compute_hint(
+        &mut self,
+        pos: Self::Position,
+        _hint: Hint<Self::Variable>,
+    ) -> Self::Variable {
+        self.read_position(pos)
+    }
+
     fn square(&mut self, pos: Self::Position, x: Self::Variable) -> Self::Variable {
         let v = self.read_position(pos);
         let x = x.square();
@@ -150,7 +287,9 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
         self.idx_var = 0;
         self.idx_var_next_row = 0;
         self.idx_var_pi = 0;
+        self.idx_var_fixed = 0;
         self.constraints.clear();
+        self.lookups.clear();
         self.activated_gadget = None;
     }
 
@@ -173,8 +312,8 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
     ) -> Self::Variable {
         let (col, row) = pos;
         match col {
-            Column::PublicInput(_) => (),
-            _ => panic!("Only public inputs can be used as round constants"),
+            Column::Fixed(_) => (),
+            _ => panic!("Only fixed columns can be used as round constants"),
         };
         Expr::Atom(ExprInner::Cell(Variable { col, row }))
     }
@@ -204,6 +343,15 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
         (x, y)
     }
 
+    // witness only
+    unsafe fn save_scaled_commitment(
+        &mut self,
+        _i_comm: usize,
+        _x: Self::Variable,
+        _y: Self::Variable,
+    ) {
+    }
+
     // witness only
     unsafe fn save_temporary_accumulators(
         &mut self,
@@ -305,6 +453,89 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
 }
 
 impl<F: PrimeField> Env<F> {
+    /// Build the constraints of the lookup argument for the lookups performed
+    /// by the latest gadget, i.e. `φ_i (β + f_i) - 1 = 0` for each looked-up
+    /// value `f_i`. See the [logup](crate::logup) module for a description of
+    /// the argument.
+    ///
+    /// The constraints only involve the current row, and are selected by the
+    /// gadget like the other constraints of the gadget. The constraints
+    /// involving the tables and the accumulator are given by
+    /// [Env::get_global_lookup_constraints].
+    ///
+    /// An empty list is returned if no lookup has been performed.
+    pub fn get_lookup_constraints(&self) -> Vec<E<F>> {
+        let beta = E::<F>::cell(Column::LookupChallenge, CurrOrNext::Curr);
+        let one = self.one();
+        // φ_i (β + f_i) - 1 = 0
+        self.lookups
+            .iter()
+            .enumerate()
+            .map(|(i, lookup)| {
+                let phi = E::<F>::cell(Column::LookupInverse(i), CurrOrNext::Curr);
+                phi * (beta.clone() + lookup.value.clone()) - one.clone()
+            })
+            .collect()
+    }
+
+    /// Build the constraints of the lookup argument that must hold on every
+    /// row, whatever the gadget activated on the row:
+    /// - the challenge β is the same on all the rows,
+    /// - `ψ_t (β + t) - m_t = 0` for each table `t`,
+    /// - `acc(ωX) - acc(X) - Σ_g q_g(X) Σ_(i < n_g) φ_i(X) + Σ_t ψ_t(X) = 0`,
+    /// where `q_g` is the selector of the gadget `g` and `n_g` the number of
+    /// lookups it performs. Only the inverses of the values actually looked
+    /// up on the row are accumulated, as the other ones are not constrained.
+    ///
+    /// The accumulator constraint also holds between the last row and the
+    /// first one, therefore the sum of the inverses of the looked-up values
+    /// over the whole domain must be equal to the sum of the terms of the
+    /// tables. The columns `t` containing the entries of the tables are fixed,
+    /// see the [logup](crate::logup) module.
+    pub fn get_global_lookup_constraints(&self) -> Vec<E<F>> {
+        let beta = E::<F>::cell(Column::LookupChallenge, CurrOrNext::Curr);
+        let beta_next = E::<F>::cell(Column::LookupChallenge, CurrOrNext::Next);
+        let mut constraints = vec![beta_next - beta.clone()];
+
+        // ψ_t (β + t) - m_t = 0
+        LookupTable::iter().for_each(|table| {
+            let psi = E::<F>::cell(Column::LookupTableInverse(table), CurrOrNext::Curr);
+            let t = E::<F>::cell(Column::LookupTable(table), CurrOrNext::Curr);
+            let m = E::<F>::cell(Column::LookupMultiplicity(table), CurrOrNext::Curr);
+            constraints.push(psi * (beta.clone() + t) - m);
+        });
+
+        // acc(ωX) - acc(X) - Σ_g q_g(X) Σ_(i < n_g) φ_i(X) + Σ_t ψ_t(X) = 0
+        let mut env = self.clone();
+        env.reset();
+        let sum_phi = GADGET_INSTRUCTIONS
+            .into_iter()
+            .fold(self.zero(), |acc, instr| {
+                interpreter::run_ivc(&mut env, instr);
+                let gadget = env
+                    .activated_gadget
+                    .expect("The instruction must activate a gadget");
+                let number_of_lookups = env.lookups.len();
+                env.reset();
+                if number_of_lookups == 0 {
+                    return acc;
+                }
+                let selector = E::<F>::cell(Column::Selector(gadget), CurrOrNext::Curr);
+                let phi = (0..number_of_lookups).fold(self.zero(), |acc, i| {
+                    acc + E::<F>::cell(Column::LookupInverse(i), CurrOrNext::Curr)
+                });
+                acc + selector * phi
+            });
+        let sum_psi = LookupTable::iter().fold(self.zero(), |acc, table| {
+            acc + E::<F>::cell(Column::LookupTableInverse(table), CurrOrNext::Curr)
+        });
+        let acc = E::<F>::cell(Column::LookupAccumulator, CurrOrNext::Curr);
+        let acc_next = E::<F>::cell(Column::LookupAccumulator, CurrOrNext::Next);
+        constraints.push(acc_next - acc - sum_phi + sum_psi);
+
+        constraints
+    }
+
     /// Get all the constraints for the IVC circuit, only.
     ///
     /// The following gadgets are used in the IVC circuit:
@@ -312,6 +543,7 @@ impl<F: PrimeField> Env<F> {
     /// IO
     /// - [Instruction::EllipticCurveScaling] and
     /// [Instruction::EllipticCurveAddition] to accumulate the commitments
+    /// - [Instruction::PublicIOHash] to expose the hash of the public IO
     // FIXME: the IVC circuit might not be complete, yet. For instance, we might
     // need to accumulate the challenges and add a row to verify the output of
     // the computation of the challenges.
@@ -331,6 +563,7 @@ impl<F: PrimeField> Env<F> {
         // therefore picking 0
         interpreter::run_ivc(&mut env, Instruction::Poseidon(0));
         constraints.extend(env.constraints.clone());
+        constraints.extend(env.get_lookup_constraints());
         env.reset();
 
         // EC scaling
@@ -338,6 +571,7 @@ impl<F: PrimeField> Env<F> {
         // therefore picking 0, 0
         interpreter::run_ivc(&mut env, Instruction::EllipticCurveScaling(0, 0));
         constraints.extend(env.constraints.clone());
+        constraints.extend(env.get_lookup_constraints());
         env.reset();
 
         // EC addition
@@ -345,11 +579,44 @@ impl<F: PrimeField> Env<F> {
         // therefore picking 0
         interpreter::run_ivc(&mut env, Instruction::EllipticCurveAddition(0));
         constraints.extend(env.constraints.clone());
+        constraints.extend(env.get_lookup_constraints());
+        env.reset();
+
+        // Public IO hash
+        interpreter::run_ivc(&mut env, Instruction::PublicIOHash);
+        constraints.extend(env.constraints.clone());
+        constraints.extend(env.get_lookup_constraints());
         env.reset();
 
         constraints
     }
 
+    /// Get the constraints of each gadget, i.e. the constraints of the IVC
+    /// circuit and of the gadgets available to the application, grouped by
+    /// the gadget activated when building them. The constraints of the
+    /// application given by [interpreter::run_app] are not included, as it
+    /// does not activate any gadget.
+    ///
+    /// The constraints can be multiplied by the selectors using
+    /// [crate::cross_terms::selected_constraints].
+    pub fn get_all_constraints_per_gadget(&self) -> Vec<(Gadget, Vec<E<F>>)> {
+        let mut env = self.clone();
+        env.reset();
+        GADGET_INSTRUCTIONS
+            .into_iter()
+            .map(|instr| {
+                interpreter::run_ivc(&mut env, instr);
+                let gadget = env
+                    .activated_gadget
+                    .expect("The instruction must activate a gadget");
+                let mut constraints = env.constraints.clone();
+                constraints.extend(env.get_lookup_constraints());
+                env.reset();
+                (gadget, constraints)
+            })
+            .collect()
+    }
+
     /// Get all the constraints for the IVC circuit and the application.
     // FIXME: the application should be given as an argument to handle Rust
     // zkApp. It is only for the PoC.
@@ -366,6 +633,26 @@ impl<F: PrimeField> Env<F> {
         // Get the constraints for the application
         interpreter::run_app(&mut env);
         constraints.extend(env.constraints.clone());
+        constraints.extend(env.get_lookup_constraints());
+        env.reset();
+
+        // Stream hash, available to the application. The state is loaded and
+        // saved using the same constraints, and the values are absorbed using
+        // the constraints of the Poseidon gadget, already included.
+        interpreter::run_ivc(&mut env, Instruction::StreamHashLoadState);
+        constraints.extend(env.constraints.clone());
+        constraints.extend(env.get_lookup_constraints());
+        env.reset();
+
+        // Program counter, used when the iterations follow a schedule. The
+        // constraints are the same whatever the index of the program counter
+        // in the IVC state, therefore picking 0.
+        interpreter::run_ivc(&mut env, Instruction::ProgramCounter(0));
+        constraints.extend(env.constraints.clone());
+        constraints.extend(env.get_lookup_constraints());
+
+        // The lookup argument
+        constraints.extend(self.get_global_lookup_constraints());
 
         constraints
     }
@@ -0,0 +1,249 @@
+//! This module implements the computation of the cross-terms required when
+//! folding two instances of the circuit, as described in the top-level
+//! documentation of the [interpreter](crate::interpreter) module.
+//!
+//! The constraints are converted into multivariate polynomials (see
+//! [mvpoly]), and the cross-terms are computed row by row using
+//! [MVPoly::compute_cross_terms]. The constraints are combined using a
+//! challenge `α`, i.e. the cross-terms of the combined constraint
+//! `C = Σ_i α^i C_i` are `Σ_i α^i T_(i, k)` for each power `k` of the folding
+//! combiner `r`.
+//!
+//! Computing the cross-terms is the dominant cost of each step. Therefore:
+//! - the conversion of the constraints into polynomials can be cached between
+//! two iterations, as the circuit does not change.
+//! - the rows can be processed in parallel.
+//!
+//! Both can be enabled using the [ProverConfig].
+//!
+//! The variables of the polynomials are the columns of the current row,
+//! followed by the columns of the next row, using the layout given by
+//! [Column::index] for the number of columns of the circuit. As the number of
+//! variables must be known at compile time, the variables of each row are
+//! padded to the number of variables of a circuit using
+//! [MAX_NUMBER_OF_COLUMNS] columns, the padding variables being set to zero.
+//!
+//! The relation checked by the verifier is derived from the same constraints,
+//! see [crate::verifier::FoldedRelation].
+
+use ark_ff::PrimeField;
+use kimchi::circuits::{expr::Variable, gate::CurrOrNext};
+use mvpoly::{monomials::Sparse, MVPoly};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::collections::HashMap;
+
+use crate::{
+    columns::{Column, Gadget, E},
+    number_of_fixed_columns,
+    prover::ProverConfig,
+    MAX_DEGREE, MAX_NUMBER_OF_COLUMNS, NUMBER_OF_COLUMNS, NUMBER_OF_LOOKUP_COLUMNS,
+    NUMBER_OF_PUBLIC_INPUTS, NUMBER_OF_SELECTORS,
+};
+
+/// The number of variables describing a row of a circuit using the given
+/// number of columns: the private columns, the public inputs, the fixed
+/// columns, the selectors and the columns of the lookup argument.
+pub const fn number_of_variables_per_row(number_of_columns: usize) -> usize {
+    number_of_columns
+        + NUMBER_OF_PUBLIC_INPUTS
+        + number_of_fixed_columns(number_of_columns)
+        + NUMBER_OF_SELECTORS
+        + NUMBER_OF_LOOKUP_COLUMNS
+}
+
+/// The number of variables describing a row of the circuit, when using the
+/// default number of columns [NUMBER_OF_COLUMNS].
+pub const NUMBER_OF_VARIABLES_PER_ROW: usize = number_of_variables_per_row(NUMBER_OF_COLUMNS);
+
+/// The number of variables reserved for each row in the polynomials describing
+/// the constraints, i.e. the number of variables of a row of a circuit using
+/// [MAX_NUMBER_OF_COLUMNS] columns. The variables of the next row start at
+/// this offset.
+pub const MAX_NUMBER_OF_VARIABLES_PER_ROW: usize =
+    number_of_variables_per_row(MAX_NUMBER_OF_COLUMNS);
+
+/// The number of variables of the polynomials describing the constraints, i.e.
+/// the variables of the current row and of the next row.
+pub const NUMBER_OF_VARIABLES: usize = 2 * MAX_NUMBER_OF_VARIABLES_PER_ROW;
+
+/// The degree the constraints are homogenized to. It is one more than
+/// [MAX_DEGREE] as the constraints of each gadget can be multiplied by the
+/// selector of the gadget, see [selected_constraints].
+pub const HOMOGENEOUS_DEGREE: usize = MAX_DEGREE as usize + 1;
+
+/// A constraint, as a multivariate polynomial.
+pub type ConstraintPolynomial<F> = Sparse<F, NUMBER_OF_VARIABLES, HOMOGENEOUS_DEGREE>;
+
+/// Multiply the constraints of each gadget by the selector of the gadget.
+///
+/// The selectors are folded like any other column. Therefore, the resulting
+/// constraints can be used to fold instances whose rows activate different
+/// gadgets, like instances coming from different step circuits, see
+/// [crate::folding].
+pub fn selected_constraints<F: PrimeField>(
+    constraints_per_gadget: &[(Gadget, Vec<E<F>>)],
+) -> Vec<E<F>> {
+    constraints_per_gadget
+        .iter()
+        .flat_map(|(gadget, constraints)| {
+            let selector = E::<F>::cell(Column::Selector(*gadget), CurrOrNext::Curr);
+            constraints
+                .iter()
+                .map(move |c| selector.clone() * c.clone())
+        })
+        .collect()
+}
+
+/// Convert the given constraints of a circuit using the given number of
+/// columns into multivariate polynomials.
+pub fn constraints_to_polynomials<F: PrimeField>(
+    constraints: &[E<F>],
+    number_of_columns: usize,
+) -> Vec<ConstraintPolynomial<F>> {
+    let column_map = |var: &Variable<Column>| {
+        let idx = var.col.index(number_of_columns);
+        match var.row {
+            CurrOrNext::Curr => idx,
+            CurrOrNext::Next => MAX_NUMBER_OF_VARIABLES_PER_ROW + idx,
+        }
+    };
+    constraints
+        .iter()
+        .map(|c| mvpoly::expr::from_expr(c, column_map))
+        .collect()
+}
+
+/// Return the evaluations of the variables of the given row of the witness,
+/// given column by column, i.e. the values of the row followed by the values
+/// of the next row. The next row of the last row is the first one.
+/// The variables not used by the witness, as it has fewer columns than
+/// [MAX_NUMBER_OF_COLUMNS], are set to zero.
+pub fn evaluations_at_row<F: PrimeField>(
+    witness: &[Vec<F>],
+    row: usize,
+) -> [F; NUMBER_OF_VARIABLES] {
+    let next_row = (row + 1) % witness[0].len();
+    std::array::from_fn(|i| {
+        let (col, row) = if i < MAX_NUMBER_OF_VARIABLES_PER_ROW {
+            (i, row)
+        } else {
+            (i - MAX_NUMBER_OF_VARIABLES_PER_ROW, next_row)
+        };
+        witness.get(col).map_or(F::zero(), |c| c[row])
+    })
+}
+
+/// Compute the cross-terms of two instances of the circuit.
+///
+/// The conversion of the constraints into polynomials is kept between two
+/// calls to [CrossTermsComputer::compute_cross_terms] if it is enabled in the
+/// configuration.
+pub struct CrossTermsComputer<F: PrimeField> {
+    pub config: ProverConfig,
+
+    /// The number of columns of the circuit, giving the layout of the
+    /// variables, see [Column::index].
+    pub number_of_columns: usize,
+
+    /// The constraints converted into polynomials, if the cache is enabled and
+    /// the cross-terms have already been computed once.
+    pub cached_polynomials: Option<Vec<ConstraintPolynomial<F>>>,
+}
+
+impl<F: PrimeField> CrossTermsComputer<F> {
+    pub fn new(config: ProverConfig) -> Self {
+        Self::new_with_number_of_columns(config, NUMBER_OF_COLUMNS)
+    }
+
+    pub fn new_with_number_of_columns(config: ProverConfig, number_of_columns: usize) -> Self {
+        crate::check_number_of_columns(number_of_columns);
+        Self {
+            config,
+            number_of_columns,
+            cached_polynomials: None,
+        }
+    }
+
+    /// Compute the cross-terms of the combined constraint for each row.
+    ///
+    /// The witnesses are given column by column, using the layout described
+    /// in [Column::index], and must contain
+    /// [number_of_variables_per_row] columns. The values `u1` and `u2` are the
+    /// homogenizing variables of the two instances, and `alpha` is the
+    /// challenge used to combine the constraints.
+    ///
+    /// The output maps each power of the folding combiner `r`, between `1` and
+    /// [HOMOGENEOUS_DEGREE] - 1, to the evaluations of the cross-term over the
+    /// rows. The next row of the last row is the first one.
+    pub fn compute_cross_terms(
+        &mut self,
+        constraints: &[E<F>],
+        witness1: &[Vec<F>],
+        witness2: &[Vec<F>],
+        u1: F,
+        u2: F,
+        alpha: F,
+    ) -> HashMap<usize, Vec<F>> {
+        let number_of_variables_per_row = number_of_variables_per_row(self.number_of_columns);
+        assert_eq!(
+            witness1.len(),
+            number_of_variables_per_row,
+            "The witness must contain {number_of_variables_per_row} columns"
+        );
+        assert_eq!(
+            witness2.len(),
+            number_of_variables_per_row,
+            "The witness must contain {number_of_variables_per_row} columns"
+        );
+        let domain_size = witness1[0].len();
+        let parallel = self.config.parallel;
+        let number_of_columns = self.number_of_columns;
+        let uncached_polynomials;
+        let polynomials: &[ConstraintPolynomial<F>] = if self.config.cache_constraints {
+            self.cached_polynomials
+                .get_or_insert_with(|| constraints_to_polynomials(constraints, number_of_columns))
+        } else {
+            uncached_polynomials = constraints_to_polynomials(constraints, number_of_columns);
+            &uncached_polynomials
+        };
+        let alphas: Vec<F> = (0..polynomials.len())
+            .scan(F::one(), |acc, _| {
+                let res = *acc;
+                *acc *= alpha;
+                Some(res)
+            })
+            .collect();
+
+        let compute_row = |row: usize| -> HashMap<usize, F> {
+            let eval1 = evaluations_at_row(witness1, row);
+            let eval2 = evaluations_at_row(witness2, row);
+            polynomials
+                .iter()
+                .zip(alphas.iter())
+                .fold(HashMap::new(), |mut acc, (p, alpha_i)| {
+                    p.compute_cross_terms(&eval1, &eval2, u1, u2)
+                        .into_iter()
+                        .for_each(|(power_r, v)| {
+                            *acc.entry(power_r).or_insert(F::zero()) += *alpha_i * v
+                        });
+                    acc
+                })
+        };
+
+        let cross_terms_per_row: Vec<HashMap<usize, F>> = if parallel {
+            (0..domain_size).into_par_iter().map(compute_row).collect()
+        } else {
+            (0..domain_size).map(compute_row).collect()
+        };
+
+        (1..HOMOGENEOUS_DEGREE)
+            .map(|power_r| {
+                let evals = cross_terms_per_row
+                    .iter()
+                    .map(|cross_terms| *cross_terms.get(&power_r).unwrap_or(&F::zero()))
+                    .collect();
+                (power_r, evals)
+            })
+            .collect()
+    }
+}
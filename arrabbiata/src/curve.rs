@@ -0,0 +1,124 @@
+//! This module defines the curves that can be used to instantiate the cycle of
+//! curves of the IVC, and the information each curve must provide.
+//!
+//! Two cycles are supported for now:
+//! - Pallas/Vesta, the Pasta curves, used by default.
+//! - BN254/Grumpkin, for users targeting Ethereum-adjacent tooling, see
+//! [mina_curves::grumpkin].
+//!
+//! FIXME: the scalar field of Grumpkin has a 2-adicity of 1. Therefore, no
+//! evaluation domain can be created, and the witness of the iterations over
+//! Grumpkin can be built but not committed to. See
+//! [Env::domain_fq](crate::witness::Env::domain_fq). Only the first iteration
+//! can be run, and the cycle is not usable to run the IVC yet.
+
+use ark_ec::models::short_weierstrass::Affine;
+use ark_ff::PrimeField;
+use mina_curves::{
+    grumpkin::Grumpkin,
+    pasta::{Fp, Fq, Pallas, Vesta},
+};
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
+use once_cell::sync::Lazy;
+use poly_commitment::{
+    commitment::{CommitmentCurve, EndoCurve},
+    ipa::endos,
+};
+
+use crate::{
+    poseidon_3_60_0_5_5_bn254_fq, poseidon_3_60_0_5_5_bn254_fr, poseidon_3_60_0_5_5_fp,
+    poseidon_3_60_0_5_5_fq,
+};
+
+/// Represents the additional information a curve must provide to be used in
+/// the cycle of curves of the IVC.
+///
+/// The sponge parameters are the parameters of the Poseidon instance described
+/// in the top-level documentation of the [interpreter](crate::interpreter)
+/// module, over the scalar field of the curve.
+pub trait ArrabbiataCurve: CommitmentCurve + EndoCurve
+where
+    Self::BaseField: PrimeField,
+{
+    /// A human readable name.
+    const NAME: &'static str;
+
+    /// Provides the sponge params to be used with this curve.
+    fn sponge_params() -> &'static ArithmeticSpongeParams<Self::ScalarField>;
+
+    /// Provides the sponge params to be used with the other curve.
+    fn other_curve_sponge_params() -> &'static ArithmeticSpongeParams<Self::BaseField>;
+
+    /// Provides the coefficients for the curve endomorphism, called (q,r) in
+    /// some places.
+    fn endos() -> &'static (Self::BaseField, Self::ScalarField);
+}
+
+impl ArrabbiataCurve for Pallas {
+    const NAME: &'static str = "pallas";
+
+    fn sponge_params() -> &'static ArithmeticSpongeParams<Self::ScalarField> {
+        poseidon_3_60_0_5_5_fq::static_params()
+    }
+
+    fn other_curve_sponge_params() -> &'static ArithmeticSpongeParams<Self::BaseField> {
+        poseidon_3_60_0_5_5_fp::static_params()
+    }
+
+    fn endos() -> &'static (Self::BaseField, Self::ScalarField) {
+        static ENDOS: Lazy<(Fp, Fq)> = Lazy::new(endos::<Pallas>);
+        &ENDOS
+    }
+}
+
+impl ArrabbiataCurve for Vesta {
+    const NAME: &'static str = "vesta";
+
+    fn sponge_params() -> &'static ArithmeticSpongeParams<Self::ScalarField> {
+        poseidon_3_60_0_5_5_fp::static_params()
+    }
+
+    fn other_curve_sponge_params() -> &'static ArithmeticSpongeParams<Self::BaseField> {
+        poseidon_3_60_0_5_5_fq::static_params()
+    }
+
+    fn endos() -> &'static (Self::BaseField, Self::ScalarField) {
+        static ENDOS: Lazy<(Fq, Fp)> = Lazy::new(endos::<Vesta>);
+        &ENDOS
+    }
+}
+
+impl ArrabbiataCurve for Affine<ark_bn254::g1::Config> {
+    const NAME: &'static str = "bn254";
+
+    fn sponge_params() -> &'static ArithmeticSpongeParams<Self::ScalarField> {
+        poseidon_3_60_0_5_5_bn254_fr::static_params()
+    }
+
+    fn other_curve_sponge_params() -> &'static ArithmeticSpongeParams<Self::BaseField> {
+        poseidon_3_60_0_5_5_bn254_fq::static_params()
+    }
+
+    fn endos() -> &'static (Self::BaseField, Self::ScalarField) {
+        static ENDOS: Lazy<(ark_bn254::Fq, ark_bn254::Fr)> =
+            Lazy::new(endos::<ark_bn254::G1Affine>);
+        &ENDOS
+    }
+}
+
+impl ArrabbiataCurve for Grumpkin {
+    const NAME: &'static str = "grumpkin";
+
+    fn sponge_params() -> &'static ArithmeticSpongeParams<Self::ScalarField> {
+        poseidon_3_60_0_5_5_bn254_fq::static_params()
+    }
+
+    fn other_curve_sponge_params() -> &'static ArithmeticSpongeParams<Self::BaseField> {
+        poseidon_3_60_0_5_5_bn254_fr::static_params()
+    }
+
+    fn endos() -> &'static (Self::BaseField, Self::ScalarField) {
+        static ENDOS: Lazy<(ark_bn254::Fr, ark_bn254::Fq)> = Lazy::new(endos::<Grumpkin>);
+        &ENDOS
+    }
+}
@@ -0,0 +1,184 @@
+//! This module implements the folding of relaxed instances of the circuit.
+//!
+//! A relaxed instance is a witness `w`, a homogenizing value `u` and an error
+//! term `E`, satisfying the relation described in the [verifier](crate::verifier)
+//! module. Two relaxed instances are folded using a random combiner `r`:
+//!
+//! ```text
+//! w = w1 + r w2
+//! u = u1 + r u2
+//! E = E1 + Σ_k r^k T_k + r^D E2
+//! ```
+//!
+//! where `T_k` are the cross-terms computed by
+//! [CrossTermsComputer::compute_cross_terms] and `D` is
+//! [HOMOGENEOUS_DEGREE].
+//!
+//! The witness contains all the variables of a row, including the selectors.
+//! Therefore, the selectors are folded like any other column, and an instance
+//! whose rows activate different gadgets than the accumulator can be folded
+//! into it, as long as the constraints are multiplied by the selectors (see
+//! [selected_constraints](crate::cross_terms::selected_constraints)) and the
+//! instances have the same shape, i.e. the same number of columns and rows.
+//! It allows heterogeneous pipelines, like alternating hash and elliptic curve
+//! steps, to share one accumulator.
+//!
+//! The columns of the lookup tables are the exception: they are fixed, and are
+//! set to `u t` from the entries `t` of the tables instead of being folded, see
+//! the [logup](crate::logup) module.
+
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+
+use crate::{
+    columns::{Column, Gadget, E},
+    cross_terms::{number_of_variables_per_row, CrossTermsComputer, HOMOGENEOUS_DEGREE},
+    logup::LookupTable,
+    NUMBER_OF_COLUMNS,
+};
+
+/// A relaxed instance of the circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelaxedInstance<F: PrimeField> {
+    /// The number of columns of the circuit, giving the layout of the
+    /// variables, see [Column::index].
+    pub number_of_columns: usize,
+
+    /// The values of all the variables, given column by column, using the
+    /// layout described in [crate::cross_terms].
+    pub witness: Vec<Vec<F>>,
+
+    /// The homogenizing value.
+    pub u: F,
+
+    /// The error term, for each row.
+    pub error: Vec<F>,
+}
+
+impl<F: PrimeField> RelaxedInstance<F> {
+    /// Create a fresh instance from a witness satisfying the constraints, i.e.
+    /// with `u = 1` and no error.
+    pub fn new(witness: Vec<Vec<F>>) -> Self {
+        Self::new_with_number_of_columns(witness, NUMBER_OF_COLUMNS)
+    }
+
+    /// Create a fresh instance of a circuit using the given number of
+    /// columns. The columns of the lookup tables are set to the entries of the
+    /// tables, whatever the values given in the witness.
+    pub fn new_with_number_of_columns(witness: Vec<Vec<F>>, number_of_columns: usize) -> Self {
+        let number_of_variables_per_row = number_of_variables_per_row(number_of_columns);
+        assert_eq!(
+            witness.len(),
+            number_of_variables_per_row,
+            "The witness must contain {number_of_variables_per_row} columns"
+        );
+        let domain_size = witness[0].len();
+        let mut instance = Self {
+            number_of_columns,
+            witness,
+            u: F::one(),
+            error: vec![F::zero(); domain_size],
+        };
+        instance.set_lookup_tables();
+        instance
+    }
+
+    /// Set the column of each lookup table to `u t`, where `t` is the fixed
+    /// column of the table, see [LookupTable::fixed_column].
+    fn set_lookup_tables(&mut self) {
+        let domain_size = self.domain_size();
+        let u = self.u;
+        LookupTable::iter().for_each(|table| {
+            let idx = Column::LookupTable(table).index(self.number_of_columns);
+            self.witness[idx] = table
+                .fixed_column::<F>(domain_size)
+                .into_iter()
+                .map(|t| u * t)
+                .collect();
+        });
+    }
+
+    /// Return the number of rows of the instance.
+    pub fn domain_size(&self) -> usize {
+        self.witness[0].len()
+    }
+
+    /// Return the values of the selector of the given gadget. After folding,
+    /// the values are the combination of the selectors of the folded
+    /// instances.
+    pub fn selector(&self, gadget: Gadget) -> &[F] {
+        &self.witness[Column::Selector(gadget).index(self.number_of_columns)]
+    }
+
+    /// Fold the given instance into this one, using the cross-terms of the
+    /// two instances and the combiner `r`. The columns of the lookup tables
+    /// are not folded, but set to `u t` for the folded `u`.
+    pub fn fold(&self, other: &Self, cross_terms: &HashMap<usize, Vec<F>>, r: F) -> Self {
+        assert_eq!(
+            self.number_of_columns, other.number_of_columns,
+            "The instances must have the same number of columns"
+        );
+        assert_eq!(
+            self.domain_size(),
+            other.domain_size(),
+            "The instances must have the same number of rows"
+        );
+        let witness = self
+            .witness
+            .iter()
+            .zip(other.witness.iter())
+            .map(|(c1, c2)| c1.iter().zip(c2.iter()).map(|(x, y)| *x + r * y).collect())
+            .collect();
+        let u = self.u + r * other.u;
+        let r_pow_d = r.pow([HOMOGENEOUS_DEGREE as u64]);
+        let error = (0..self.domain_size())
+            .map(|row| {
+                let (cross_terms_eval, _) =
+                    (1..HOMOGENEOUS_DEGREE).fold((F::zero(), F::one()), |(acc, r_pow), power_r| {
+                        let r_pow = r_pow * r;
+                        (acc + r_pow * cross_terms[&power_r][row], r_pow)
+                    });
+                self.error[row] + cross_terms_eval + r_pow_d * other.error[row]
+            })
+            .collect();
+        let mut folded = Self {
+            number_of_columns: self.number_of_columns,
+            witness,
+            u,
+            error,
+        };
+        folded.set_lookup_tables();
+        folded
+    }
+}
+
+/// Fold the incoming instance into the accumulator, computing the cross-terms
+/// of the given constraints with the challenge `alpha`. The selectors of the
+/// two instances can differ.
+///
+/// The folded instance and the cross-terms, to be sent to the verifier, are
+/// returned.
+pub fn fold_instances<F: PrimeField>(
+    computer: &mut CrossTermsComputer<F>,
+    constraints: &[E<F>],
+    accumulator: &RelaxedInstance<F>,
+    incoming: &RelaxedInstance<F>,
+    alpha: F,
+    r: F,
+) -> (RelaxedInstance<F>, HashMap<usize, Vec<F>>) {
+    assert_eq!(
+        computer.number_of_columns, accumulator.number_of_columns,
+        "The cross-terms must be computed for the number of columns of the instances"
+    );
+    let cross_terms = computer.compute_cross_terms(
+        constraints,
+        &accumulator.witness,
+        &incoming.witness,
+        accumulator.u,
+        incoming.u,
+        alpha,
+    );
+    let folded = accumulator.fold(incoming, &cross_terms, r);
+    (folded, cross_terms)
+}
@@ -1,6 +1,7 @@
 //! This module contains the implementation of the IVC scheme in addition to
-//! running an arbitrary function that can use up to [crate::NUMBER_OF_COLUMNS]
-//! columns.
+//! running an arbitrary function that can use up to
+//! [InterpreterEnv::number_of_columns] columns, [crate::NUMBER_OF_COLUMNS] by
+//! default.
 //! At the moment, all constraints must be of maximum degree
 //! [crate::MAX_DEGREE], but it might change in the future.
 //!
@@ -25,8 +26,12 @@
 //!     - [Gadget layout](#gadget-layout-1)
 //!   - [Elliptic curve scalar multiplication](#elliptic-curve-scalar-multiplication)
 //!     - [Gadget layout](#gadget-layout-2)
+//!   - [Public IO hash](#public-io-hash)
+//!   - [Stream hash](#stream-hash)
+//!   - [Program counter](#program-counter)
 //! - [Handle the combinaison of constraints](#handle-the-combinaison-of-constraints)
 //! - [Permutation argument](#permutation-argument)
+//! - [Lookup argument](#lookup-argument)
 //! - [Fiat-Shamir challenges](#fiat-shamir-challenges)
 //!
 //! ## Gadgets implemented
@@ -142,30 +147,32 @@
 //! (o1, o2, o3) = PoseidonRound(c1, c2, c3)
 //! ```
 //!
-//! The layout for the one using the "next row" is as follow (5 full rounds):
+//! The values to absorb, `(v1, v2)`, are private and share the row with the
+//! rounds. The layout for the one using the "next row" is as follow, with
+//! [crate::NUMBER_OF_COLUMNS] columns (4 full rounds):
 //! ```text
 //! | C1 | C2 | C3 | C4 | C5 | C6 | C7 | C8 | C9 | C10 | C11 | C12 | C13 | C14 | C15 |
 //! | -- | -- | -- | -- | -- | -- | -- | -- | -- | --- | --- | --- | --- | --- | --- |
-//! | x  | y  | z  | a1 | a2 | a3 | b1 | b2 | b3 | c1  | c2  | c3  | d1  | d2  | d3  |
-//! | o1 | o2 | o2
+//! | x  | y  | z  | v1 | v2 | a1 | a2 | a3 | b1 | b2  | b3  | c1  | c2  | c3  |     |
+//! | o1 | o2 | o3 |
 //! ```
 //! where (x, y, z) is the input of the current step, (o1, o2, o3) is the
-//! output, and the other values are intermediary values. And we have the
-//! following equalities:
+//! output, and the other values are intermediary values. When a new hash
+//! starts, the values `v1` and `v2` are added to `x` and `y`, and they are zero
+//! otherwise. And we have the following equalities:
 //! ```text
-//! (a1, a2, a3) = PoseidonRound(x, y, z)
+//! (a1, a2, a3) = PoseidonRound(x + v1, y + v2, z)
 //! (b1, b2, b3) = PoseidonRound(a1, a2, a3)
 //! (c1, c2, c3) = PoseidonRound(b1, b2, b3)
-//! (d1, d2, d3) = PoseidonRound(c1, c2, c3)
-//! (o1, o2, o3) = PoseidonRound(d1, d2, d3)
+//! (o1, o2, o3) = PoseidonRound(c1, c2, c3)
 //! ```
 //!
-//! For both implementations, round constants are passed as public inputs. As a
-//! reminder, public inputs are simply additional columns known by the prover
-//! and verifier.
+//! For both implementations, round constants are passed in fixed columns,
+//! defined at setup time like the selectors and known by the prover and the
+//! verifier.
 //! Also, the elements to absorb are added to the initial state at the beginning
-//! of the call of the Poseidon full hash. The elements to absorb are supposed
-//! to be passed as public inputs.
+//! of the call of the Poseidon full hash. The elements to absorb are private
+//! values, bound to the public IO by the resulting hash.
 //!
 //! ### Elliptic curve scalar multiplication
 //!
@@ -204,11 +211,114 @@
 //! | o'_x |  o'_y  | double_tmp'_x | double_tmp'_y |  r_(i+1)  |
 //! ```
 //!
+//! The gadget is used on [MAXIMUM_FIELD_SIZE_IN_BITS] consecutive rows per
+//! commitment, processing the bits from the least significant one, which is
+//! enough to handle any scalar of the fields we support. When the last bit is
+//! processed, the result is saved in the environment using
+//! [InterpreterEnv::save_scaled_commitment].
+//!
 //! FIXME: an optimisation can be implemented using "a bucket" style algorithm,
 //! as described in [Efficient MSMs in Kimchi
 //! Circuits](https://github.com/o1-labs/rfcs/blob/main/0013-efficient-msms-for-non-native-pickles-verification.md).
 //! We leave this for future work.
 //!
+//! ### Public IO hash
+//!
+//! Each step of the IVC exposes a single public output: the hash of the
+//! running accumulators, of the previous hash, of the iteration index, of the
+//! initial, current and output IVC states and of the challenges. The values are
+//! absorbed by successive calls to the gadget
+//! [Gadget::Poseidon](crate::columns::Gadget::Poseidon), and the digest, i.e.
+//! the first element of the sponge state, is then loaded in a column and
+//! constrained to be equal to the only public input of the circuit, see
+//! [crate::NUMBER_OF_PUBLIC_INPUTS]:
+//!
+//! ```text
+//! | C1 |
+//! | -- |
+//! | h  |
+//! ```
+//!
+//! with the constraint `h - pi_0 = 0`. The same value can be recomputed by the
+//! verifier using the helpers of the [public_io](crate::public_io) module.
+//!
+//! ### Stream hash
+//!
+//! Applications like "hash this stream of unknown length" must absorb a
+//! different number of values per iteration into a sponge whose state survives
+//! between iterations. The sponge state of the stream hash is carried in the
+//! first [POSEIDON_STATE_SIZE] elements of the IVC state `z_i`, and the values
+//! are absorbed using [run_stream_hash]:
+//! - the state is loaded from `z_i` on one row, activating the gadget
+//! [Gadget::StreamHashState](crate::columns::Gadget::StreamHashState),
+//! - the values are absorbed two by two using the rounds of the gadget
+//! [Gadget::Poseidon](crate::columns::Gadget::Poseidon), the values of the
+//! stream being private values,
+//! - the state is saved into `z_(i + 1)` on one row, activating again the
+//! gadget [Gadget::StreamHashState](crate::columns::Gadget::StreamHashState).
+//!
+//! The layout of the rows loading and saving the state is:
+//!
+//! ```text
+//! | C1 | C2 | C3 | C4 | C5 | C6 |
+//! | -- | -- | -- | -- | -- | -- |
+//! | a1 | b1 | a2 | b2 | a3 | b3 |
+//! ```
+//!
+//! with the constraints `b_i - a_i = 0`. When loading the state, `a_i` is the
+//! element of the IVC state `z_i` and `b_i` the element of the sponge state.
+//! When saving the state, `a_i` is the element of the sponge state and `b_i`
+//! the element of the IVC state `z_(i + 1)`. The elements of the IVC state are
+//! bound to the public IO by its hash.
+//!
+//! When the number of values absorbed in an iteration is odd, the last value is
+//! absorbed with a zero. Applications must therefore absorb the length of the
+//! stream at the end to avoid trivial collisions.
+//!
+//! FIXME: the permutation used at the iteration `i` is over the field of the
+//! circuit of the iteration `i`, which alternates between the two fields of
+//! the cycle. The state must be reduced when moving from one field to the
+//! other.
+//!
+//! ### Program counter
+//!
+//! Multi-phase computations are described by a
+//! [Schedule](crate::schedule::Schedule), and the position in the schedule is
+//! carried in the IVC state, see the [schedule](crate::schedule) module. The
+//! gadget [Gadget::ProgramCounter](crate::columns::Gadget::ProgramCounter)
+//! updates the program counter `(p, c)` once per iteration, using the
+//! following layout:
+//!
+//! ```text
+//! | C1 | C2 | C3  | C4  | C5 | ... | C(4 + K) | C(5 + K) | C(6 + K) |
+//! | -- | -- | --- | --- | -- | --- | -------- | -------- | -------- |
+//! | p  | c  | inv | e   | b0 | ... | b(K - 1) | p'       | c'       |
+//! ```
+//!
+//! where `K` is [crate::MAX_NUMBER_OF_PHASES], `b_k` is a boolean equal to
+//! `1` if and only if `p = k`, and `e` is equal to `1` if the current phase
+//! ends, i.e. if `d = c + 1 - Σ_k b_k L_k` is zero. The lengths `L_k` of the
+//! phases are fixed columns, and `inv` is the inverse of `d`, or zero. The
+//! constraints are:
+//!
+//! ```text
+//! - b_k (b_k - 1) = 0
+//! - Σ_k b_k - 1 = 0
+//! - Σ_k k b_k - p = 0
+//! - e - 1 + d * inv = 0
+//! - d * e = 0
+//! - p' - (p + e) = 0
+//! - c' - (1 - e) (c + 1) = 0
+//! ```
+//!
+//! The values `p` and `c` are loaded from the IVC state `z_i`, and the values
+//! `p'` and `c'` are saved in the IVC state `z_(i + 1)`. The counter
+//! `c` is range-checked on 16 bits using a lookup, see the section [Lookup
+//! argument](#lookup-argument), which bounds the length of the phases.
+//!
+//! FIXME: the selectors of the rows executed during the iteration must be
+//! checked against the flags `b_k`. It requires the permutation argument.
+//!
 //! ## Handle the combinaison of constraints
 //!
 //! The prover will have to combine the constraints to generate the
@@ -293,6 +403,32 @@
 //! can have a permutation argument for the columns 0, 1, 2, 3 and one for the
 //! columns 4, 5, 6, 7. It can help to decrease the degree.
 //!
+//! ## Lookup argument
+//!
+//! Some checks are cheaper when performed using a lookup in a fixed table. For
+//! instance, checking that a value fits in 16 bits using a bit decomposition
+//! requires 17 cells and 17 constraints, when a single lookup in a table
+//! containing the values `[0, 2^16)` is enough.
+//!
+//! The gadgets can request a lookup using the methods of the interpreter, like
+//! [InterpreterEnv::range_check16]. At most
+//! [crate::MAX_NUMBER_OF_LOOKUPS_PER_ROW] lookups can be performed per row.
+//! The argument is based on the log-derivative lookup argument, and the
+//! description of the constraints can be found in the module
+//! [logup](crate::logup). The constraints of the argument are of degree at most
+//! 2, and they are folded like any other gadget constraints. For now, the
+//! gadget [Gadget::ProgramCounter](crate::columns::Gadget::ProgramCounter)
+//! performs a 16-bits range check on the counter of the current phase.
+//!
+//! The columns of the argument are computed once all the rows of the iteration
+//! have been built and the challenge β has been coined from the commitments to
+//! the witness and to the multiplicities, see
+//! [Env::compute_lookup_columns](crate::witness::Env::compute_lookup_columns).
+//! They are committed and folded like the other columns, except the tables,
+//! which are fixed. The tables are written over the whole domain, i.e. over
+//! the rows of the application and of the IVC circuit. Therefore, the domain must contain at least as many rows as
+//! the largest table, see [crate::MIN_SRS_LOG2_SIZE].
+//!
 //! ## Fiat-Shamir challenges
 //!
 //! The challenges sent by the verifier must also be simulated by the IVC
@@ -338,8 +474,8 @@
 //! there.
 
 use crate::{
-    columns::Gadget, MAXIMUM_FIELD_SIZE_IN_BITS, NUMBER_OF_COLUMNS, POSEIDON_ROUNDS_FULL,
-    POSEIDON_STATE_SIZE,
+    columns::Gadget, public_io::number_of_poseidon_calls, MAXIMUM_FIELD_SIZE_IN_BITS,
+    MAX_NUMBER_OF_PHASES, POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE,
 };
 use ark_ff::{One, Zero};
 use log::debug;
@@ -367,11 +503,29 @@ pub enum Instruction {
     /// top-level documentation. Compared to the previous one (that might be
     /// deprecated in the future), this implementation does use the "next row"
     /// to allow the computation of one additional round per row. In the current
-    /// setup, with [NUMBER_OF_COLUMNS] columns, we can compute 5 full rounds
-    /// per row.
+    /// setup, with [crate::NUMBER_OF_COLUMNS] columns, we can compute 4 full
+    /// rounds per row. The number of rounds per row is given by
+    /// [InterpreterEnv::poseidon_rounds_per_row].
     Poseidon(usize),
     EllipticCurveScaling(usize, u64),
     EllipticCurveAddition(usize),
+    /// Expose the digest of the sponge as the hash of the public IO. It is
+    /// supposed to be executed after the values of the public IO have been
+    /// absorbed by [Instruction::Poseidon].
+    PublicIOHash,
+    /// Load the sponge state of the stream hash from the IVC state `z_i`. See
+    /// [run_stream_hash].
+    StreamHashLoadState,
+    /// Same as [Instruction::Poseidon], but the state is the sponge state of
+    /// the stream hash, and the values absorbed are the values of the stream
+    /// given by the application.
+    StreamHashAbsorb(usize),
+    /// Save the sponge state of the stream hash into the IVC state
+    /// `z_(i + 1)`.
+    StreamHashStoreState,
+    /// Update the program counter, carried in the IVC state at the given
+    /// index and the next one. See the [schedule](crate::schedule) module.
+    ProgramCounter(usize),
     // The NoOp will simply do nothing
     NoOp,
 }
@@ -385,6 +539,29 @@ pub enum Side {
     Right,
 }
 
+/// A value computed by the prover, and written in the witness without any
+/// constraint. Gadgets should request hints using the methods prefixed by
+/// `hint_` of [InterpreterEnv], which add the constraints checking the value
+/// is correct.
+#[derive(Clone, Debug)]
+pub enum Hint<V> {
+    /// The inverse of the value, which must not be zero.
+    Inverse(V),
+    /// The inverse of the value, or zero if the value is zero.
+    InverseOrZero(V),
+    /// `1` if the value is zero, `0` otherwise.
+    IsZero(V),
+    /// A square root of the value, which must be a quadratic residue.
+    SquareRoot(V),
+    /// The bits of the value between `lowest_bit` (included) and
+    /// `highest_bit` (excluded), interpreted as an integer.
+    Bitmask {
+        value: V,
+        highest_bit: u32,
+        lowest_bit: u32,
+    },
+}
+
 /// An abstract interpreter that provides some functionality on the circuit. The
 /// interpreter should be seen as a state machine with some built-in
 /// functionality whose state is a matrix, and whose transitions are described
@@ -405,6 +582,15 @@ pub trait InterpreterEnv {
         + Zero
         + One;
 
+    /// Return the number of columns the circuit uses
+    fn number_of_columns(&self) -> usize;
+
+    /// Return the number of full rounds of Poseidon computed per row, which
+    /// depends on the number of columns.
+    fn poseidon_rounds_per_row(&self) -> usize {
+        crate::poseidon_rounds_per_row(self.number_of_columns())
+    }
+
     /// Allocate a new variable in the circuit for the current row
     fn allocate(&mut self) -> Self::Position;
 
@@ -414,8 +600,14 @@ pub trait InterpreterEnv {
     /// Return the corresponding variable at the given position
     fn read_position(&self, pos: Self::Position) -> Self::Variable;
 
+    /// Allocate a new public input. The only public input of the circuit is
+    /// the hash of the public IO, see [InterpreterEnv::write_public_io_hash].
     fn allocate_public_input(&mut self) -> Self::Position;
 
+    /// Allocate a new fixed column for the current row. The values of the
+    /// fixed columns are defined at setup time, like the selectors.
+    fn allocate_fixed(&mut self) -> Self::Position;
+
     /// Set the value of the variable at the given position for the current row
     fn write_column(&mut self, col: Self::Position, v: Self::Variable) -> Self::Variable;
 
@@ -424,6 +616,10 @@ pub trait InterpreterEnv {
     // better solution. The PI should be static for all witnesses
     fn write_public_input(&mut self, x: Self::Position, v: BigInt) -> Self::Variable;
 
+    /// Write the value of the fixed column at the given position, for the
+    /// current row.
+    fn write_fixed(&mut self, x: Self::Position, v: BigInt) -> Self::Variable;
+
     /// Activate the gadget for the row.
     fn activate_gadget(&mut self, gadget: Gadget);
 
@@ -445,9 +641,142 @@ pub trait InterpreterEnv {
 
     fn constrain_boolean(&mut self, x: Self::Variable);
 
+    /// Check that the value at the given position fits in 16 bits, using a
+    /// lookup in the table [LookupTable::RangeCheck16](crate::logup::LookupTable::RangeCheck16).
+    ///
+    /// The returned value is the value at the given position.
+    fn range_check16(&mut self, pos: Self::Position) -> Self::Variable;
+
+    // ---- IVC state -----
+    /// Read the `i`-th element of the state `z_i` given as input to the
+    /// current iteration, and write it into the given position.
+    ///
+    /// No constraint is added. The state is absorbed in the hash of the public
+    /// IO of the current iteration, which is the only public input, therefore
+    /// the verifier is able to check the value corresponds to the output of
+    /// the previous iteration, or to the initial state `z_0` for the first
+    /// one.
+    fn read_ivc_state(&mut self, pos: Self::Position, i: usize) -> Self::Variable;
+
+    /// Write the `i`-th element of the state `z_(i + 1)` computed by the
+    /// current iteration into the given position.
+    ///
+    /// The value is constrained to be equal to the given variable. The state
+    /// is absorbed in the hash of the public IO of the current iteration, see
+    /// the [public_io](crate::public_io) module, and will be given as input to
+    /// the next iteration, which absorbs it again as its state `z_i`.
+    fn write_ivc_state(
+        &mut self,
+        pos: Self::Position,
+        i: usize,
+        v: Self::Variable,
+    ) -> Self::Variable;
+
+    /// Write the hash of the public IO of the current step.
+    ///
+    /// The value is saved into a newly allocated public input, and it is
+    /// constrained to be equal to the given variable. It is the only value
+    /// the next iteration receives from the current one.
+    fn write_public_io_hash(&mut self, v: Self::Variable) -> Self::Variable;
+    // -------------------------
+
+    /// Write the number of steps of the given phase of the schedule into the
+    /// given fixed column. See the [schedule](crate::schedule) module.
+    fn get_phase_length(&mut self, pos: Self::Position, phase: usize) -> Self::Variable;
+
+    // ---- Stream hash -----
+    /// Load the `i`-th element of the sponge state of the stream hash into
+    /// the given position. See [run_stream_hash].
+    fn load_stream_hash_state(&mut self, pos: Self::Position, i: usize) -> Self::Variable;
+
+    /// Save the `i`-th element of the sponge state of the stream hash.
+    ///
+    /// # Safety
+    ///
+    /// No constraint is added. The value must be loaded back using
+    /// [InterpreterEnv::load_stream_hash_state], and the caller must ensure
+    /// the value has been constrained.
+    unsafe fn save_stream_hash_state(&mut self, v: Self::Variable, i: usize);
+
+    /// Fetch the next value of the stream to absorb, and write it into the
+    /// given position. Like for
+    /// [InterpreterEnv::fetch_value_to_absorb], zero is returned if the
+    /// current round is not zero, or if all the values have been absorbed.
+    ///
+    /// # Safety
+    ///
+    /// No constraint is added on the returned value.
+    unsafe fn fetch_stream_value_to_absorb(
+        &mut self,
+        pos: Self::Position,
+        curr_round: usize,
+    ) -> Self::Variable;
+    // -------------------------
+
     /// Compute the square a field element
     fn square(&mut self, res: Self::Position, x: Self::Variable) -> Self::Variable;
 
+    // ---- Hints -----
+    /// Compute the given hint and write it at the given position.
+    ///
+    /// # Safety
+    ///
+    /// No constraint is added on the returned value. Callers must constrain
+    /// it, or use one of the methods prefixed by `hint_` instead.
+    unsafe fn compute_hint(
+        &mut self,
+        pos: Self::Position,
+        hint: Hint<Self::Variable>,
+    ) -> Self::Variable;
+
+    /// Write the inverse of `x` at the given position, and constrain it with
+    /// `x * x^(-1) - 1 = 0`.
+    fn hint_inverse(&mut self, pos: Self::Position, x: Self::Variable) -> Self::Variable {
+        let res = unsafe { self.compute_hint(pos, Hint::Inverse(x.clone())) };
+        let one = self.one();
+        self.assert_equal(x * res.clone(), one);
+        res
+    }
+
+    /// Write a square root `r` of `x` at the given position, and constrain it
+    /// with `r^2 - x = 0`.
+    fn hint_square_root(&mut self, pos: Self::Position, x: Self::Variable) -> Self::Variable {
+        let res = unsafe { self.compute_hint(pos, Hint::SquareRoot(x.clone())) };
+        self.assert_equal(res.clone() * res.clone(), x);
+        res
+    }
+
+    /// Decompose `x` into bits, the least significant bit being written at the
+    /// first position. Each bit is constrained to be boolean, and `x` is
+    /// constrained to be equal to `Σ_i 2^i b_i`. Therefore, `x` must fit in as
+    /// many bits as positions given.
+    fn hint_bit_decomposition(
+        &mut self,
+        x: Self::Variable,
+        positions: &[Self::Position],
+    ) -> Vec<Self::Variable> {
+        let bits: Vec<Self::Variable> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, pos)| {
+                let hint = Hint::Bitmask {
+                    value: x.clone(),
+                    highest_bit: i as u32 + 1,
+                    lowest_bit: i as u32,
+                };
+                let bit = unsafe { self.compute_hint(*pos, hint) };
+                self.constrain_boolean(bit.clone());
+                bit
+            })
+            .collect();
+        let recomposed = bits.iter().enumerate().fold(self.zero(), |acc, (i, bit)| {
+            acc + self.constant(BigInt::from(1_u64) << i) * bit.clone()
+        });
+        self.assert_equal(recomposed, x);
+        bits
+    }
+    // -------------------------
+
     /// Flagged as unsafe as it does require an additional range check
     ///
     /// # Safety
@@ -491,6 +820,8 @@ pub trait InterpreterEnv {
     /// It does not have any effect on the constraints
     unsafe fn save_poseidon_state(&mut self, v: Self::Variable, i: usize);
 
+    /// Write the `i`-th round constant of the given round into the given fixed
+    /// column.
     fn get_poseidon_round_constant(
         &mut self,
         pos: Self::Position,
@@ -501,8 +832,9 @@ pub trait InterpreterEnv {
     /// Return the requested MDS matrix coefficient
     fn get_poseidon_mds_matrix(&mut self, i: usize, j: usize) -> Self::Variable;
 
-    /// Load the public value to absorb at the current step.
-    /// The position should be a public column.
+    /// Load the value to absorb at the current step into the given position.
+    /// The values are absorbed in the hash of the public IO, see
+    /// [crate::public_io].
     ///
     /// IMPROVEME: we could have in the environment an heterogeneous typed list,
     /// and we pop values call after call. However, we try to keep the
@@ -603,6 +935,21 @@ pub trait InterpreterEnv {
         _v2: Self::Variable,
         _side: Side,
     );
+
+    /// Save the result of the scaling of the commitment `i_comm`, given by
+    /// the affine coordinates `(x, y)`, into the environment. It is called by
+    /// the elliptic curve scaling gadget when the last bit of the scalar has
+    /// been processed.
+    ///
+    /// # Safety
+    ///
+    /// It does not have any effect on the constraints.
+    unsafe fn save_scaled_commitment(
+        &mut self,
+        i_comm: usize,
+        x: Self::Variable,
+        y: Self::Variable,
+    );
 }
 
 /// Run the application
@@ -639,7 +986,7 @@ pub fn run_ivc<E: InterpreterEnv>(env: &mut E, instr: Instruction) {
     match instr {
         Instruction::EllipticCurveScaling(i_comm, processing_bit) => {
             assert!(processing_bit < MAXIMUM_FIELD_SIZE_IN_BITS, "Invalid bit index. The fields are maximum on {MAXIMUM_FIELD_SIZE_IN_BITS} bits, therefore we cannot process the bit {processing_bit}");
-            assert!(i_comm < NUMBER_OF_COLUMNS, "Invalid index. We do only support the scaling of the commitments to the columns, for now. We must additionally support the scaling of cross-terms and error terms");
+            assert!(i_comm < env.number_of_columns(), "Invalid index. We do only support the scaling of the commitments to the columns, for now. We must additionally support the scaling of cross-terms and error terms");
             debug!("Processing scaling of commitment {i_comm}, bit {processing_bit}");
             env.activate_gadget(Gadget::EllipticCurveScaling);
             // When processing the first bit, we must load the scalar, and it
@@ -687,7 +1034,7 @@ pub fn run_ivc<E: InterpreterEnv>(env: &mut E, instr: Instruction) {
             // FIXME: the initial value of the result should be a non-zero
             // point. However, it must be a public value otherwise the prover
             // might lie on the initial value.
-            // We do have 15 public inputs on each row, we could use some of them.
+            // It could be passed in a fixed column.
             let (res_x, res_y) = if processing_bit == 0 {
                 // Load the commitment
                 unsafe { env.load_temporary_accumulators(res_col_x, res_col_y, Side::Right) }
@@ -773,20 +1120,28 @@ pub fn run_ivc<E: InterpreterEnv>(env: &mut E, instr: Instruction) {
                 scalar.clone(),
                 bit.clone() + env.constant(BigInt::from(2)) * next_scalar.clone(),
             );
-            let _x3 = {
+            let x3 = {
                 let res = bit.clone() * res_plus_tmp_x.clone()
                     + (env.one() - bit.clone()) * res_x.clone();
                 env.write_column(next_row_res_col_x, res)
             };
-            let _y3 = {
+            let y3 = {
                 let res = bit.clone() * res_plus_tmp_y.clone()
                     + (env.one() - bit.clone()) * res_y.clone();
                 env.write_column(next_row_res_col_y, res)
             };
+            // When the last bit has been processed, the accumulator contains
+            // the scaled commitment, and it can be used by the next gadgets.
+            // As the fields are at most MAXIMUM_FIELD_SIZE_IN_BITS bits, the
+            // scalar has been fully decomposed at this point, i.e. the
+            // remaining scalar written on the next row is zero.
+            if processing_bit == MAXIMUM_FIELD_SIZE_IN_BITS - 1 {
+                unsafe { env.save_scaled_commitment(i_comm, x3, y3) };
+            }
         }
         Instruction::EllipticCurveAddition(i_comm) => {
             env.activate_gadget(Gadget::EllipticCurveAddition);
-            assert!(i_comm < NUMBER_OF_COLUMNS, "Invalid index. We do only support the addition of the commitments to the columns, for now. We must additionally support the scaling of cross-terms and error terms");
+            assert!(i_comm < env.number_of_columns(), "Invalid index. We do only support the addition of the commitments to the columns, for now. We must additionally support the scaling of cross-terms and error terms");
             let (x1, y1) = {
                 let x1 = env.allocate();
                 let y1 = env.allocate();
@@ -830,102 +1185,91 @@ pub fn run_ivc<E: InterpreterEnv>(env: &mut E, instr: Instruction) {
         Instruction::Poseidon(curr_round) => {
             env.activate_gadget(Gadget::Poseidon);
             debug!("Executing instruction Poseidon({curr_round})");
-            if curr_round < POSEIDON_ROUNDS_FULL {
-                // Values to be absorbed are 0 when when the round is not zero,
-                // i.e. when we are processing the rounds.
-                let values_to_absorb: Vec<E::Variable> = (0..POSEIDON_STATE_SIZE - 1)
-                    .map(|_i| {
-                        let pos = env.allocate_public_input();
-                        // fetch_value_to_absorb is supposed to return 0 if curr_round != 0.
-                        unsafe { env.fetch_value_to_absorb(pos, curr_round) }
-                    })
-                    .collect();
-                let round_input_positions: Vec<E::Position> =
-                    (0..POSEIDON_STATE_SIZE).map(|_i| env.allocate()).collect();
-                let round_output_positions: Vec<E::Position> = (0..POSEIDON_STATE_SIZE)
-                    .map(|_i| env.allocate_next_row())
-                    .collect();
-                // If we are at the first round, we load the state from the environment.
-                // The permutation argument is used to load the state the
-                // current call to Poseidon might be a succession of Poseidon
-                // calls, like when we need to hash the public inputs, and the
-                // state might be from a previous place in the execution trace.
-                let state: Vec<E::Variable> = if curr_round == 0 {
-                    round_input_positions
-                        .iter()
-                        .enumerate()
-                        .map(|(i, pos)| {
-                            let res = env.load_poseidon_state(*pos, i);
-                            // Absorb value. The capacity is POSEIDON_STATE_SIZE - 1
-                            if i < POSEIDON_STATE_SIZE - 1 {
-                                res + values_to_absorb[i].clone()
-                            } else {
-                                res
-                            }
-                        })
-                        .collect()
-                } else {
-                    // Otherwise, as we do use the "next row" trick, the current
-                    // state has been loaded in the "next_row" state during the
-                    // previous call, and we can simply load it. No permutation
-                    // argument needed.
-                    round_input_positions
-                        .iter()
-                        .map(|pos| env.read_position(*pos))
-                        .collect()
-                };
-
-                // 5 is the number of rounds we treat per row
-                (0..5).fold(state, |state, idx_round| {
-                    let state: Vec<E::Variable> =
-                        state.iter().map(|x| env.compute_x5(x.clone())).collect();
-
-                    let round = curr_round + idx_round;
-
-                    let rcs: Vec<E::Variable> = (0..POSEIDON_STATE_SIZE)
-                        .map(|i| {
-                            let pos = env.allocate_public_input();
-                            env.get_poseidon_round_constant(pos, round, i)
-                        })
-                        .collect();
-
-                    let state: Vec<E::Variable> = rcs
-                        .iter()
-                        .enumerate()
-                        .map(|(i, rc)| {
-                            let acc: E::Variable =
-                                state.iter().enumerate().fold(env.zero(), |acc, (j, x)| {
-                                    acc + env.get_poseidon_mds_matrix(i, j) * x.clone()
-                                });
-                            // The last iteration is written on the next row.
-                            if idx_round == 4 {
-                                env.write_column(round_output_positions[i], acc + rc.clone())
-                            } else {
-                                // Otherwise, we simply allocate a new position
-                                // in the circuit.
-                                let pos = env.allocate();
-                                env.write_column(pos, acc + rc.clone())
-                            }
-                        })
-                        .collect();
-                    // If we are at the last round, we save the state in the
-                    // environment.
-                    // FIXME/IMPROVEME: we might want to execute more Poseidon
-                    // full hash in sequentially, and then save one row. For
-                    // now, we will save the state at the end of the last round
-                    // and reload it at the beginning of the next Poseidon full
-                    // hash.
-                    if round == POSEIDON_ROUNDS_FULL - 1 {
-                        state.iter().enumerate().for_each(|(i, x)| {
-                            unsafe { env.save_poseidon_state(x.clone(), i) };
-                        });
-                        env.reset();
-                    };
-                    state
-                });
-            } else {
-                panic!("Invalid index: it is supposed to be less than {POSEIDON_ROUNDS_FULL}");
-            }
+            run_poseidon_rounds(env, curr_round, Sponge::PublicIO);
+        }
+        Instruction::StreamHashLoadState => {
+            env.activate_gadget(Gadget::StreamHashState);
+            debug!("Executing instruction StreamHashLoadState");
+            (0..POSEIDON_STATE_SIZE).for_each(|i| {
+                let pos_z = env.allocate();
+                let v = env.read_ivc_state(pos_z, i);
+                let pos = env.allocate();
+                let x = env.write_column(pos, v);
+                unsafe { env.save_stream_hash_state(x, i) };
+            });
+        }
+        Instruction::StreamHashAbsorb(curr_round) => {
+            env.activate_gadget(Gadget::Poseidon);
+            debug!("Executing instruction StreamHashAbsorb({curr_round})");
+            run_poseidon_rounds(env, curr_round, Sponge::StreamHash);
+        }
+        Instruction::StreamHashStoreState => {
+            env.activate_gadget(Gadget::StreamHashState);
+            debug!("Executing instruction StreamHashStoreState");
+            (0..POSEIDON_STATE_SIZE).for_each(|i| {
+                let pos = env.allocate();
+                let x = env.load_stream_hash_state(pos, i);
+                let pos_z = env.allocate();
+                env.write_ivc_state(pos_z, i, x);
+            });
+        }
+        Instruction::ProgramCounter(idx_state) => {
+            env.activate_gadget(Gadget::ProgramCounter);
+            debug!("Executing instruction ProgramCounter({idx_state})");
+            let p = {
+                let pos = env.allocate();
+                env.read_ivc_state(pos, idx_state)
+            };
+            // The number of steps executed in the current phase fits in 16
+            // bits, see [crate::schedule::Schedule::new].
+            let c = {
+                let pos = env.allocate();
+                env.read_ivc_state(pos, idx_state + 1);
+                env.range_check16(pos)
+            };
+            let pos_inv = env.allocate();
+            let pos_e = env.allocate();
+            // One-hot encoding of the current phase
+            let flags: Vec<E::Variable> = (0..MAX_NUMBER_OF_PHASES)
+                .map(|k| {
+                    let pos = env.allocate();
+                    let k = env.constant(BigInt::from(k));
+                    let b = unsafe { env.compute_hint(pos, Hint::IsZero(p.clone() - k)) };
+                    env.constrain_boolean(b.clone());
+                    b
+                })
+                .collect();
+            let sum_flags = flags.iter().fold(env.zero(), |acc, b| acc + b.clone());
+            let one = env.one();
+            env.assert_equal(sum_flags, one.clone());
+            let phase = flags.iter().enumerate().fold(env.zero(), |acc, (k, b)| {
+                acc + env.constant(BigInt::from(k)) * b.clone()
+            });
+            env.assert_equal(phase, p.clone());
+            // Length of the current phase
+            let length = flags.iter().enumerate().fold(env.zero(), |acc, (k, b)| {
+                let pos = env.allocate_fixed();
+                acc + env.get_phase_length(pos, k) * b.clone()
+            });
+            // e = 1 if and only if d = c + 1 - L is zero
+            let d = c.clone() + one.clone() - length;
+            let inv = unsafe { env.compute_hint(pos_inv, Hint::InverseOrZero(d.clone())) };
+            let e = env.write_column(pos_e, one.clone() - d.clone() * inv);
+            env.assert_zero(d * e.clone());
+            let pos_p = env.allocate();
+            env.write_ivc_state(pos_p, idx_state, p + e.clone());
+            let pos_c = env.allocate();
+            env.write_ivc_state(pos_c, idx_state + 1, (one - e) * (c + env.one()));
+        }
+        Instruction::PublicIOHash => {
+            env.activate_gadget(Gadget::PublicIOHash);
+            debug!("Executing instruction PublicIOHash");
+            // The values have been absorbed by the previous calls to the
+            // Poseidon gadget, therefore the digest is the first element of
+            // the sponge state.
+            let pos = env.allocate();
+            let digest = env.load_poseidon_state(pos, 0);
+            env.write_public_io_hash(digest);
         }
         Instruction::NoOp => {}
     }
@@ -933,3 +1277,150 @@ pub fn run_ivc<E: InterpreterEnv>(env: &mut E, instr: Instruction) {
     // Compute the hash of the public input
     // FIXME: add the verification key. We should have a hash of it.
 }
+
+/// The sponges the Poseidon rounds can be applied to.
+#[derive(Copy, Clone, Debug)]
+enum Sponge {
+    /// The sponge used to compute the hash of the public IO, see
+    /// [Instruction::Poseidon].
+    PublicIO,
+    /// The sponge of the stream hash, see [Instruction::StreamHashAbsorb].
+    StreamHash,
+}
+
+/// Compute [InterpreterEnv::poseidon_rounds_per_row] full rounds of Poseidon,
+/// starting at the round `curr_round`, on the given sponge. The gadget
+/// [Gadget::Poseidon] must be activated by the caller.
+fn run_poseidon_rounds<E: InterpreterEnv>(env: &mut E, curr_round: usize, sponge: Sponge) {
+    if curr_round < POSEIDON_ROUNDS_FULL {
+        // The input of the round is allocated first, as it is written on the
+        // "next row" by the previous call.
+        let round_input_positions: Vec<E::Position> =
+            (0..POSEIDON_STATE_SIZE).map(|_i| env.allocate()).collect();
+        // Values to be absorbed are 0 when when the round is not zero,
+        // i.e. when we are processing the rounds.
+        let values_to_absorb: Vec<E::Variable> = (0..POSEIDON_STATE_SIZE - 1)
+            .map(|_i| {
+                let pos = env.allocate();
+                // The values are supposed to be 0 if curr_round != 0.
+                match sponge {
+                    Sponge::PublicIO => unsafe { env.fetch_value_to_absorb(pos, curr_round) },
+                    Sponge::StreamHash => unsafe {
+                        env.fetch_stream_value_to_absorb(pos, curr_round)
+                    },
+                }
+            })
+            .collect();
+        let round_output_positions: Vec<E::Position> = (0..POSEIDON_STATE_SIZE)
+            .map(|_i| env.allocate_next_row())
+            .collect();
+        // If we are at the first round, we load the state from the environment.
+        // The permutation argument is used to load the state the
+        // current call to Poseidon might be a succession of Poseidon
+        // calls, like when we need to hash the public inputs, and the
+        // state might be from a previous place in the execution trace.
+        let state: Vec<E::Variable> = if curr_round == 0 {
+            round_input_positions
+                .iter()
+                .enumerate()
+                .map(|(i, pos)| {
+                    let res = match sponge {
+                        Sponge::PublicIO => env.load_poseidon_state(*pos, i),
+                        Sponge::StreamHash => env.load_stream_hash_state(*pos, i),
+                    };
+                    // Absorb value. The capacity is POSEIDON_STATE_SIZE - 1
+                    if i < POSEIDON_STATE_SIZE - 1 {
+                        res + values_to_absorb[i].clone()
+                    } else {
+                        res
+                    }
+                })
+                .collect()
+        } else {
+            // Otherwise, as we do use the "next row" trick, the current
+            // state has been loaded in the "next_row" state during the
+            // previous call, and we can simply load it. No permutation
+            // argument needed.
+            round_input_positions
+                .iter()
+                .map(|pos| env.read_position(*pos))
+                .collect()
+        };
+
+        let rounds_per_row = env.poseidon_rounds_per_row();
+        (0..rounds_per_row).fold(state, |state, idx_round| {
+            let state: Vec<E::Variable> = state.iter().map(|x| env.compute_x5(x.clone())).collect();
+
+            let round = curr_round + idx_round;
+
+            let rcs: Vec<E::Variable> = (0..POSEIDON_STATE_SIZE)
+                .map(|i| {
+                    let pos = env.allocate_fixed();
+                    env.get_poseidon_round_constant(pos, round, i)
+                })
+                .collect();
+
+            let state: Vec<E::Variable> = rcs
+                .iter()
+                .enumerate()
+                .map(|(i, rc)| {
+                    let acc: E::Variable =
+                        state.iter().enumerate().fold(env.zero(), |acc, (j, x)| {
+                            acc + env.get_poseidon_mds_matrix(i, j) * x.clone()
+                        });
+                    // The last iteration is written on the next row.
+                    if idx_round == rounds_per_row - 1 {
+                        env.write_column(round_output_positions[i], acc + rc.clone())
+                    } else {
+                        // Otherwise, we simply allocate a new position
+                        // in the circuit.
+                        let pos = env.allocate();
+                        env.write_column(pos, acc + rc.clone())
+                    }
+                })
+                .collect();
+            // If we are at the last round, we save the state in the
+            // environment.
+            // FIXME/IMPROVEME: we might want to execute more Poseidon
+            // full hash in sequentially, and then save one row. For
+            // now, we will save the state at the end of the last round
+            // and reload it at the beginning of the next Poseidon full
+            // hash.
+            if round == POSEIDON_ROUNDS_FULL - 1 {
+                state.iter().enumerate().for_each(|(i, x)| match sponge {
+                    Sponge::PublicIO => unsafe { env.save_poseidon_state(x.clone(), i) },
+                    Sponge::StreamHash => unsafe { env.save_stream_hash_state(x.clone(), i) },
+                });
+                env.reset();
+            };
+            state
+        });
+    } else {
+        panic!("Invalid index: it is supposed to be less than {POSEIDON_ROUNDS_FULL}");
+    }
+}
+
+/// Absorb `nb_values` values of a stream into the sponge of the stream hash,
+/// whose state is carried between iterations in the IVC state. See the
+/// section [Stream hash](#stream-hash) of the top-level documentation.
+///
+/// The values are fetched using
+/// [InterpreterEnv::fetch_stream_value_to_absorb]. The number of values can
+/// be different at each iteration. One row is used to load the state, one to
+/// save it, and `POSEIDON_ROUNDS_FULL / rounds_per_row + 1` rows per pair of
+/// values.
+pub fn run_stream_hash<E: InterpreterEnv>(env: &mut E, nb_values: usize) {
+    run_ivc(env, Instruction::StreamHashLoadState);
+    env.reset();
+    let rounds_per_row = env.poseidon_rounds_per_row();
+    (0..number_of_poseidon_calls(nb_values)).for_each(|_| {
+        (0..POSEIDON_ROUNDS_FULL)
+            .step_by(rounds_per_row)
+            .for_each(|curr_round| {
+                run_ivc(env, Instruction::StreamHashAbsorb(curr_round));
+                env.reset();
+            })
+    });
+    run_ivc(env, Instruction::StreamHashStoreState);
+    env.reset();
+}
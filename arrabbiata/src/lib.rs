@@ -3,12 +3,20 @@ use strum::EnumCount as _;
 pub mod column_env;
 pub mod columns;
 pub mod constraints;
+pub mod cross_terms;
+pub mod curve;
+pub mod folding;
 pub mod interpreter;
 pub mod logup;
+pub mod poseidon_3_60_0_5_5_bn254_fq;
+pub mod poseidon_3_60_0_5_5_bn254_fr;
 pub mod poseidon_3_60_0_5_5_fp;
 pub mod poseidon_3_60_0_5_5_fq;
 pub mod proof;
 pub mod prover;
+pub mod public_io;
+pub mod schedule;
+pub mod trace;
 pub mod verifier;
 pub mod witness;
 
@@ -24,15 +32,45 @@ pub const MIN_SRS_LOG2_SIZE: usize = 16;
 // FIXME: that might change. We use a vertical layout for now.
 pub const IVC_CIRCUIT_SIZE: usize = 1 << 13;
 
-/// The maximum number of columns that can be used in the circuit.
+/// The default number of columns that can be used in the circuit.
+/// A different number of columns can be chosen when creating the environments,
+/// see for instance [witness::Env::new_with_number_of_columns]. Using more
+/// columns reduces the number of rows, but increases the number of commitments.
 pub const NUMBER_OF_COLUMNS: usize = 15;
 
-/// The maximum number of public inputs the circuit can use per row
-/// We do have 15 for now as we want to compute 5 rounds of poseidon per row
-/// using the gadget [crate::columns::Gadget::Poseidon]. In addition to
-/// the 12 public inputs required for the rounds, we add 2 more for the values
-/// to absorb.
-pub const NUMBER_OF_PUBLIC_INPUTS: usize = 15 + 2;
+/// The maximum number of columns the circuit can use. The constraints are
+/// converted into multivariate polynomials whose number of variables must be
+/// known at compile time, see [cross_terms::NUMBER_OF_VARIABLES]. The
+/// variables of the columns not used by a circuit with fewer columns are set
+/// to zero.
+pub const MAX_NUMBER_OF_COLUMNS: usize = 32;
+
+/// The minimum number of columns the circuit can use. It is the number of
+/// columns required by the gadget
+/// [crate::columns::Gadget::EllipticCurveScaling].
+pub const MIN_NUMBER_OF_COLUMNS: usize = 10;
+
+/// The maximum number of lookups that can be performed per row.
+/// See the [logup] module for more information.
+pub const MAX_NUMBER_OF_LOOKUPS_PER_ROW: usize = 4;
+
+/// The number of public inputs of the circuit. The only public input is the
+/// hash of the public IO of the current step, exposed by the gadget
+/// [crate::columns::Gadget::PublicIOHash]. All the other values of the step
+/// are absorbed in the hash, see the [public_io] module.
+pub const NUMBER_OF_PUBLIC_INPUTS: usize = 1;
+
+/// The number of fixed columns the circuit uses per row, when using the
+/// default number of columns [NUMBER_OF_COLUMNS].
+/// See [number_of_fixed_columns] for other number of columns.
+pub const NUMBER_OF_FIXED_COLUMNS: usize = number_of_fixed_columns(NUMBER_OF_COLUMNS);
+
+/// The maximum number of phases a [schedule::Schedule] can contain, including
+/// the final phase reached when all the phases have been executed. It is
+/// bounded by the number of columns used by the gadget
+/// [crate::columns::Gadget::ProgramCounter], which requires 6 columns in
+/// addition to one column per phase.
+pub const MAX_NUMBER_OF_PHASES: usize = MIN_NUMBER_OF_COLUMNS - 6;
 
 /// The low-exponentiation value used by the Poseidon hash function for the
 /// substitution box.
@@ -51,17 +89,63 @@ pub const POSEIDON_STATE_SIZE: usize = 3;
 /// IVC.
 pub const MAXIMUM_FIELD_SIZE_IN_BITS: u64 = 255;
 
-/// Define the number of values we must absorb when computating the hash to the
-/// public IO.
-///
-/// FIXME:
-/// For now, it is the number of columns as we are only absorbing the
-/// accumulators, which consists of 2 native field elements. However, it doesn't
-/// make the protocol sound. We must absorb, in addition to that the index,
-/// the application inputs/outputs.
-/// It is left for the future as at this time, we're still sketching the IVC
-/// circuit.
-pub const NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO: usize = NUMBER_OF_COLUMNS * 2;
+/// Define the number of values we must absorb when computing the hash of the
+/// public IO, for the given number of columns, size of the IVC state and number
+/// of challenges. See the [public_io] module for the order in which the values
+/// are absorbed.
+/// The two coordinates of the accumulators of each column are absorbed, in
+/// addition to the previous hash (2 chunks of 128 bits), the iteration index,
+/// the initial, current and output states, and the challenges.
+pub const fn number_of_values_to_absorb_public_io(
+    number_of_columns: usize,
+    ivc_state_size: usize,
+    number_of_challenges: usize,
+) -> usize {
+    number_of_columns * 2 + 2 + 1 + ivc_state_size * 3 + number_of_challenges
+}
+
+/// The number of full rounds of the Poseidon hash function computed per row
+/// by the gadget [crate::columns::Gadget::Poseidon], for the given number of
+/// columns. Each round requires [POSEIDON_STATE_SIZE] columns, the output of
+/// the last round being written on the next row, and
+/// [POSEIDON_STATE_SIZE] - 1 columns are used for the values to absorb.
+pub const fn poseidon_rounds_per_row(number_of_columns: usize) -> usize {
+    (number_of_columns - (POSEIDON_STATE_SIZE - 1)) / POSEIDON_STATE_SIZE
+}
+
+/// The number of fixed columns the circuit uses per row, for the given number
+/// of columns: one round constant per state element and per Poseidon round.
+/// It is always larger than [MAX_NUMBER_OF_PHASES], the number of lengths of
+/// phases used by the gadget [crate::columns::Gadget::ProgramCounter].
+pub const fn number_of_fixed_columns(number_of_columns: usize) -> usize {
+    poseidon_rounds_per_row(number_of_columns) * POSEIDON_STATE_SIZE
+}
+
+/// Check the number of columns can be used to build the circuit, i.e. that
+/// all the gadgets fit in a row, that the variables fit in the constraint
+/// polynomials, and that the number of Poseidon rounds per row divides the
+/// total number of rounds.
+pub fn check_number_of_columns(number_of_columns: usize) {
+    assert!(
+        number_of_columns >= MIN_NUMBER_OF_COLUMNS,
+        "The circuit requires at least {MIN_NUMBER_OF_COLUMNS} columns, got {number_of_columns}"
+    );
+    assert!(
+        number_of_columns <= MAX_NUMBER_OF_COLUMNS,
+        "The circuit supports at most {MAX_NUMBER_OF_COLUMNS} columns, got {number_of_columns}"
+    );
+    let rounds_per_row = poseidon_rounds_per_row(number_of_columns);
+    assert!(
+        POSEIDON_ROUNDS_FULL % rounds_per_row == 0,
+        "With {number_of_columns} columns, {rounds_per_row} Poseidon rounds are computed per row, which does not divide the number of rounds {POSEIDON_ROUNDS_FULL}"
+    );
+}
 
 /// The number of selectors used in the circuit.
 pub const NUMBER_OF_SELECTORS: usize = columns::Gadget::COUNT;
+
+/// The number of columns used by the lookup argument: one inverse per lookup
+/// performed in a row, the multiplicities, the entries and the inverses of each
+/// table, the accumulator and the challenge β. See the [logup] module.
+pub const NUMBER_OF_LOOKUP_COLUMNS: usize =
+    MAX_NUMBER_OF_LOOKUPS_PER_ROW + 3 * logup::LookupTable::COUNT + 2;
@@ -1 +1,119 @@
 //! This file will implement a logup argument to allow users performing lookup in their circuits.
+//! This module will be used by the prover to evaluate at a certain point.
+//!
+//! For now, only fixed tables are supported, and the only table available is a
+//! 16-bits range check table, see [LookupTable::RangeCheck16].
+//!
+//! The argument follows the log-derivative approach described in
+//! ["Multivariate lookups based on logarithmic
+//! derivatives"](https://eprint.iacr.org/2022/1530.pdf). For each row, and each
+//! looked-up value `f_i`, the prover commits to an additional column
+//! `φ_i = 1 / (β + f_i)`. For each table `t`, the prover commits to the
+//! multiplicities `m` and to `ψ = m / (β + t)`. Finally, an accumulator column
+//! `acc` is used to sum the terms over the whole domain:
+//!
+//! ```text
+//! - φ_i (β + f_i) - 1 = 0                                 (degree 2)
+//! - ψ (β + t) - m = 0                                     (degree 2)
+//! - acc(ωX) - acc(X) - Σ_g q_g(X) Σ_i φ_i(X) + ψ(X) = 0   (degree 2)
+//! ```
+//!
+//! where `q_g` is the selector of a gadget performing lookups. The first
+//! constraints are selected by the gadget performing the lookups, and the
+//! others must hold on every row, see
+//! [get_global_lookup_constraints](crate::constraints::Env::get_global_lookup_constraints).
+//! The accumulator wraps around the domain, therefore the sum of the terms of
+//! the looked-up values and of the tables are equal.
+//!
+//! The challenge β is coined after absorbing the commitments to the witness
+//! and to the multiplicities, see
+//! [Env::coin_lookup_challenge](crate::witness::Env::coin_lookup_challenge).
+//! The inverses and the accumulator depend on β, and are committed afterwards.
+//!
+//! Except for the tables, all the columns above are regular witness columns,
+//! committed and folded like the other columns, see
+//! [crate::NUMBER_OF_LOOKUP_COLUMNS]. As the constraints are of degree at most
+//! 2, they are homogenized and folded like any other gadget constraint. The
+//! challenge β is also stored in a column,
+//! [Column::LookupChallenge](crate::columns::Column::LookupChallenge), to be
+//! accumulated like the other columns. The multiplicities are simply added
+//! when folding two instances, as it is a linear operation.
+//!
+//! The tables are fixed columns, given by [LookupTable::fixed_column]. They are
+//! not committed by the prover, and are not folded: the column of a relaxed
+//! instance with the homogenizing value `u` is `u t`, computed from the fixed
+//! table, see [crate::folding::RelaxedInstance]. The verifier checks it, see
+//! [crate::verifier::FoldedRelation::check], therefore the prover can not pick
+//! the entries of the tables.
+//!
+//! The tables are written over the whole domain. When the domain is larger than
+//! a table, the remaining rows contain the first entry of the table, with a
+//! multiplicity of zero.
+
+use ark_ff::PrimeField;
+use num_bigint::BigInt;
+use o1_utils::field_helpers::FieldHelpers;
+use strum_macros::{EnumCount as EnumCountMacro, EnumIter};
+
+/// The fixed tables that can be used by the gadgets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumCountMacro, EnumIter)]
+pub enum LookupTable {
+    /// Table containing all the values in `[0, 2^16)`.
+    RangeCheck16,
+}
+
+impl LookupTable {
+    /// Return the number of entries of the table
+    pub fn length(&self) -> usize {
+        match self {
+            LookupTable::RangeCheck16 => 1 << 16,
+        }
+    }
+
+    /// Return the index of the given value in the table, if the value is
+    /// contained in the table.
+    pub fn index_of(&self, v: &BigInt) -> Option<usize> {
+        match self {
+            LookupTable::RangeCheck16 => {
+                if *v >= BigInt::from(0_usize) && *v < BigInt::from(self.length()) {
+                    Some(v.try_into().unwrap())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Return the `i`-th entry of the table
+    pub fn entry(&self, i: usize) -> BigInt {
+        assert!(
+            i < self.length(),
+            "The table {self:?} has only {} entries",
+            self.length()
+        );
+        match self {
+            LookupTable::RangeCheck16 => BigInt::from(i),
+        }
+    }
+
+    /// Return the fixed column containing the entries of the table, over a
+    /// domain of the given size. The rows after the last entry contain the
+    /// first entry. If the domain is smaller than the table, only the first
+    /// entries are contained in the column.
+    pub fn fixed_column<F: PrimeField>(&self, domain_size: usize) -> Vec<F> {
+        (0..domain_size)
+            .map(|i| {
+                let entry = self.entry(if i < self.length() { i } else { 0 });
+                F::from_biguint(&entry.to_biguint().unwrap()).unwrap()
+            })
+            .collect()
+    }
+}
+
+/// A value looked up in a table.
+/// The type `T` is usually the variable type of the interpreter environment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup<T> {
+    pub table: LookupTable,
+    pub value: T,
+}
@@ -1,12 +1,14 @@
+use ark_ff::PrimeField;
 use arrabbiata::{
+    curve::ArrabbiataCurve,
     interpreter::{self, InterpreterEnv},
     witness::Env,
-    IVC_CIRCUIT_SIZE, MIN_SRS_LOG2_SIZE, POSEIDON_STATE_SIZE,
+    IVC_CIRCUIT_SIZE, MIN_SRS_LOG2_SIZE, NUMBER_OF_COLUMNS, POSEIDON_STATE_SIZE,
 };
 use log::{debug, info};
 use mina_curves::pasta::{Fp, Fq, Pallas, Vesta};
 use num_bigint::BigInt;
-use std::time::Instant;
+use std::{fs::File, io::BufWriter, path::PathBuf, time::Instant};
 
 pub fn main() {
     // See https://github.com/rust-lang/log
@@ -18,6 +20,14 @@ pub fn main() {
     let arg_srs_size = clap::arg!(--"srs-size" <U64> "Size of the SRS in base 2")
         .value_parser(clap::value_parser!(usize));
 
+    let arg_n_columns = clap::arg!(--"n-columns" <USIZE> "Number of columns of the circuit")
+        .value_parser(clap::value_parser!(usize));
+
+    let arg_parallel = clap::arg!(--"parallel" "Build the witness of the application in parallel");
+
+    let arg_trace_dir = clap::arg!(--"trace-dir" <PATH> "Directory to write the execution trace of each iteration to, in CSV and JSON, for debugging")
+        .value_parser(clap::value_parser!(PathBuf));
+
     let cmd = clap::Command::new("cargo")
         .bin_name("cargo")
         .subcommand_required(true)
@@ -25,6 +35,9 @@ pub fn main() {
             clap::Command::new("square-root")
                 .arg(arg_n)
                 .arg(arg_srs_size)
+                .arg(arg_n_columns)
+                .arg(arg_parallel)
+                .arg(arg_trace_dir)
                 .arg_required_else_help(true),
         );
     let matches = cmd.get_matches();
@@ -36,6 +49,11 @@ pub fn main() {
     let srs_log2_size = matches
         .get_one::<usize>("srs-size")
         .unwrap_or(&MIN_SRS_LOG2_SIZE);
+    let n_columns = matches
+        .get_one::<usize>("n-columns")
+        .unwrap_or(&NUMBER_OF_COLUMNS);
+    let parallel = matches.get_flag("parallel");
+    let trace_dir = matches.get_one::<PathBuf>("trace-dir");
 
     assert!(
         *srs_log2_size >= MIN_SRS_LOG2_SIZE,
@@ -44,30 +62,74 @@ pub fn main() {
 
     info!("Instantiating environment to execute square-root {n_iteration} times with SRS of size 2^{srs_log2_size}");
 
+    run::<Fp, Fq, Vesta, Pallas>(
+        *n_iteration,
+        *srs_log2_size,
+        *n_columns,
+        parallel,
+        trace_dir,
+    )
+}
+
+/// Run the IVC over the cycle of curves (E1, E2).
+fn run<
+    Fp: PrimeField,
+    Fq: PrimeField,
+    E1: ArrabbiataCurve<ScalarField = Fp, BaseField = Fq>,
+    E2: ArrabbiataCurve<ScalarField = Fq, BaseField = Fp>,
+>(
+    n_iteration: u64,
+    srs_log2_size: usize,
+    n_columns: usize,
+    parallel: bool,
+    trace_dir: Option<&PathBuf>,
+) where
+    <E1::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+    <E2::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+{
     let domain_size = 1 << srs_log2_size;
 
     // FIXME: setup correctly the initial sponge state
     let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
     // FIXME: make a setup phase to build the selectors
-    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
-        *srs_log2_size,
-        BigInt::from(1u64),
+    let mut env = Env::<Fp, Fq, E1, E2>::new_with_number_of_columns(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
         sponge_e1.clone(),
         sponge_e1.clone(),
+        n_columns,
     );
 
     let n_iteration_per_fold = domain_size - IVC_CIRCUIT_SIZE;
 
-    while env.current_iteration < *n_iteration {
+    while env.current_iteration < n_iteration {
         let start_iteration = Instant::now();
 
         info!("Run iteration: {}/{}", env.current_iteration, n_iteration);
 
         // Build the application circuit
         info!("Running N iterations of the application circuit");
-        for _i in 0..n_iteration_per_fold {
-            interpreter::run_app(&mut env);
-            env.reset();
+        if parallel {
+            // The rows of the application are independent, therefore we split
+            // them in one block per thread.
+            let nb_blocks = rayon::current_num_threads();
+            let block_sizes: Vec<usize> = (0..nb_blocks)
+                .map(|i| {
+                    n_iteration_per_fold / nb_blocks
+                        + usize::from(i < n_iteration_per_fold % nb_blocks)
+                })
+                .collect();
+            env.build_blocks_in_parallel(&block_sizes, |env, nb_rows| {
+                for _i in 0..nb_rows {
+                    interpreter::run_app(env);
+                    env.reset();
+                }
+            });
+        } else {
+            for _i in 0..n_iteration_per_fold {
+                interpreter::run_app(&mut env);
+                env.reset();
+            }
         }
 
         info!("Building the IVC circuit");
@@ -85,6 +147,21 @@ pub fn main() {
             elapsed = start_iteration.elapsed().as_micros()
         );
 
+        if let Some(trace_dir) = trace_dir {
+            let i = env.current_iteration;
+            info!("Writing the execution trace of iteration {i} in {trace_dir:?}");
+            let trace = env.trace();
+            let mut csv =
+                BufWriter::new(File::create(trace_dir.join(format!("trace_{i}.csv"))).unwrap());
+            trace.write_csv(&mut csv).unwrap();
+            let mut ranges =
+                BufWriter::new(File::create(trace_dir.join(format!("gadgets_{i}.csv"))).unwrap());
+            trace.write_gadget_ranges_csv(&mut ranges).unwrap();
+            let mut json =
+                BufWriter::new(File::create(trace_dir.join(format!("trace_{i}.json"))).unwrap());
+            trace.write_json(&mut json).unwrap();
+        }
+
         // FIXME:
         // update current instance with the previous "next" commitments (i.e.
         // env.next_commitments)
@@ -92,6 +169,13 @@ pub fn main() {
         // FIXME: Check twice the updated commitments
         env.compute_and_update_previous_commitments();
 
+        // The challenge β of the lookup argument is coined after absorbing the
+        // commitments to the witness and to the multiplicities. The other
+        // columns of the lookup argument depend on β.
+        let beta = env.coin_lookup_challenge();
+        env.compute_lookup_columns(beta);
+        env.compute_lookup_commitments();
+
         // FIXME:
         // Absorb all commitments in the sponge.
 
@@ -134,6 +218,9 @@ pub fn main() {
             elapsed = start_iteration.elapsed().as_micros()
         );
 
+        // The output of the current iteration becomes the input of the next one
+        env.compute_output();
+
         env.reset_for_next_iteration();
         env.current_iteration += 1;
     }
@@ -0,0 +1,908 @@
+use ark_bn254::Fq;
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
+use once_cell::sync::Lazy;
+
+/* Generated by params.sage, where the Pasta fields have been replaced by the BN254
+fields, and the prefix `Pasta_q` by `Bn254_fq`:
+```shell
+sage params.sage --rounds 60 rust 3 arrabiata
+```
+ */
+
+use std::str::FromStr;
+
+fn params() -> ArithmeticSpongeParams<Fq> {
+    ArithmeticSpongeParams {
+        mds: vec![
+            vec![
+                Fq::from_str(
+                    "1137537165407035596133248633339006180682169989332114188536947817700970152112",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "11032345558341963485232508316113561207451888927305190536192964379269431013978",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "17125092002390489538068241331689016076726582083181535062012782688306537282743",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "6167739151485077337244503316101640205240624070026304596854636393389683310540",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "17699185340476509263631570536607300983949361258078069898709658654468492370430",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "4894581941639822472358358843689617141790330729968009301392041188969438802464",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "125287200689623573333017322087884468100771234935490079218340231617492786640",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "8838325984924342959509127450613342437785809651519887026634039502415688700270",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "13651029291250040770743287585796922664793783290621221834864990838412280874246",
+                )
+                .unwrap(),
+            ],
+        ],
+        round_constants: vec![
+            vec![
+                Fq::from_str(
+                    "21641825924849421791515518276569838639575448895871038774779737919433538150591",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "20194812546689066769841399873262553052763590913332633746678632882291473632289",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "298876929207873480671103620675534653172173037201591521605712766505291789206",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "9696304765746176602816812227632590351853595007564246275840706080584923338380",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "4538454868845757147858242847060030337433662197001670980300695222229550144778",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16234729777667374460213736736594055636215478759147663079278223181725979084781",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "1523097538939730350142791944632110216847299422995942708597556750258812440445",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "20246786471542456017152771073325439520420391294047912054309568963558160647102",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "939797447849459548454564333846664747462434017113029334639790071333213609240",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "19456346467475775681531587844771092214023974356741322125229159699974264061564",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "4896989278748909165147362689857308727255089638330812312295358724675995998867",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "17568302556999210338994891050118764866847254463044516006831073855409755217855",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "9896328472945290377261438212450278821275006685286790946586390673041298539700",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "10160288434687681411228915733781668811441959254107036426975425584345754817060",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "3175621539296373637765407221577472634264126996863095271358294925760472454826",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "9686698536063833309140521670965861193307466293564411236282785366488811739263",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "15688496773656864412882184818563942783672995284088305350997994791360287288494",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "19449714726894397122716299703743395394698522015637984191176994579711513218028",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "17573966181390239270262436506209225240822978874998378064902796727268201928858",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "5741740825325010718770515948318741354817105100730284233553204184473787494597",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6027628240626682900865049833185377463836886012287461466160311439228628170712",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "14764900568147131803102098156295875316377470106989046215888589074994518733850",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16708882792502109265081374205492895310061487475200790580987512384383931410469",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "350901964703005873375809755362629193457185527099497814869911861311684488246",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "6346034439073580169052339371157096728320541598702507126052511136866839839621",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "3192672277756236659284475820402404555030714138902636757439190322897838002710",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "13492133099647456830349119103004342225693835024332203514942602640279660746261",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "14847357631991859140099263137094618097773167272430711572052455452785640452657",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "13687975219755442787125212751923725506008457961481941301719695090306885504953",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "21239906501529831121680460539072680743787496431026784679845324843432643775995",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "19942205180891341164684872189299042059532694084210300229487071611076335356482",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "14387615608838306366832351428606390953573761777021341327169309479069971780257",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "18993943800214172475968551776113230197995457758508404930539906183841069986046",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "3016105975894291701761290518939088631466766350470039877201628994101179301119",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "21388140988578546943276090079513848502257875120642560034029583700333546459560",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "10149351442736590242191537870904067386004543070307419651700226056991837492618",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "8604185160311975347244760741056850282714839497975384916959290636303061783902",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "7790469364926511366637283715583547912455716363239503962191402298208230870809",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6077130462911033183247128543465375477375651221653721587396757776230717084237",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "913961276445039394440192840229985953910947307279595662395564126359085054741",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "14578492387816703190097081939756544955782835412308712561159257694387035856368",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "17398778817781623264164504893507953742789508102919631845211375142813808760383",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "15909042912366596744078926572640051900523992402341679723020165822126220804338",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "9066480314461770285593672863527212046388650590056550025046649768135685876027",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "4743759970596950277416108892993493723607281681841951602826335639087325727252",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "190715944919971654436033287309092569476300328850598385246562901184360267511",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "12869379106866403541503168766532905866157975004065463126704854760940112855009",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6340334233507783193080835063162877147974995661659298894975454095309133243884",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "17081269932836841558938847808683817647575837442870215392374911494327676066105",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16262323690776332054454890689641472769872990571364138714241483128682772538994",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "15393786097336160717660617323540172697322173930528448161132783230418291567728",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "17525733785774164749369858346108833818383738891118268131394506363945205513316",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "17285481584086006681386939050148474660005990866453846286894147296614465952234",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "12035738379986630418663828046997733753533756459790414108824406238071123863290",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "4726414341883412579745381963430835037023727727798635449120913637475773511757",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "19128830728915492941167266282536098684076841141160578823508412420576441654451",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "8646094766469167477972986391073979222535764447701414589256954498415857003793",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "19865081367436363307218687392983635085665510083893365185919227489645057064018",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "2313206013112501053905232985387383119138347225569928492251270604287035975516",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "1987206115627577386252561895318816703807507848706581985183275367964421654848",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "13325140470819883542386718999421563874258653378261103276338137314981401137904",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "14981846406427818698866121687321457209231773748072479167687676567047347418786",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "10296084571427456143726883811355182123046281934370113726369032332863069771077",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "6971544334779580949761160835836745727524390089316400990809253312282331843129",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "1541256155891923498385121430010627238139140437562365852543595153901637117650",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "15100440032872915723534633360520568779831931608556626492733620886257603209231",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "7647844157185740281690142559242063609274488258142587933570562388859377201013",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "8162790394276323997966887758889067972567284924567893369985306270465098054782",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "9578505407685994205706248326400987453339597910824114601611493621976514480334",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "19182158588480584925307017268517850538634631851673294357956488607240831522843",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6851190154588461333989401386393777511017628886705804534510494481602103906836",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "19363710766684074851676662311727511283007088632649970925032169536612198683722",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "744278901986873112656228051822666728915253920106616684317374977631854267507",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "3119339185231314162627979053589513346619597936320071651888191934801502007571",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "297298251866304030225659625333490609051658187947522293315127650813143068731",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "12934035671217703908511741623509909403531267426091997050373888990135931574840",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "11418326473290038643521650952637243587284445105208721095299475408648853566003",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16952789707515585006754557212973304070388675229197676825229948092429813748749",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "12969166140116121712725615336333437179348327646862963705388767702820618500352",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "8339118501240624888728120853720336999281302778721281696562868869690752006530",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "1002515699129564023246319254519149535499495597286031894812396255894670648109",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "4071221990931918779436031202939454366582400444013511451137880602720408545458",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "11937555054495939901099939306830000330963860712256181781984933276151482014887",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "18682368725530542058533495948458560484844972661542699677014129748477288903314",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "7109153675705496587317878193976512450347739981115888758044918335575442711770",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "14965487991171938718143280302163230429259698202141647938950698734217244694742",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "21650610525873197918585909463764606174347758717961159827535117802064522565290",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "16920018356184842826307419368685518640092795120753043347155578236842658738264",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "10146550857080794255361102520125383846335103842709286979661597847451801681694",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "4409435934003052593046969739875437569680367815103827342199793034250838780997",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "20448595953757748790774722797406442286527289437996587185197956314092167159536",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "19815817458212110282357530467335832703556077841060418155381560672320324766260",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "17055807480097020455817716083733790952135251293842101517592493238621388847273",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "19181371395088749465424957014721670162958097376178078214155134348564030641835",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "4028576109932671404802131722668342758039766807208516216507680281363530855502",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "2251904458052282190029521383850689740537565950670002805507946277508895190049",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "10796816490095034732491601351795825435157626765811234937996172295698132930536",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "5349021960517221386456783841992744161119839456100394397570542608849012733749",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "21337933668295442763110030272338067274488042863643087855351170820556902100246",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "4335504256727451370067180991969644923267892883481629165780969815792360093038",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "13294973131374831071933575556468676582680413942920763113960987436168710891738",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "10746442470029137058884891979679723185139785446444328315871692797080986529873",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "17607020676289488866285157020227818374892892679641144601121747230989766369122",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "7746845795537060259995884991372428947981887751409137540030568378990370810267",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "9170315180517769614595517174065920104642306816166037876340836382058665867736",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "4620110982863736135801009087170261360529912964987283930200620482087803817572",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "10696445688438692940605235196558192776935610698904832491798677659537957994834",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6600425652345106541763975683846956264655022345004258984034359142464942729751",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "1912774092405136711450220060326509122146546400764183352084497744239102745803",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "1952535265843761034999347645500125504716141361900020455220623432022111464956",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6168736707850602133810246481761241832644412939910343027580535761041116231357",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "15316058801362965145911285664669518101845828887330291814935351079602172990494",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "11710459960134858466307008807545421155784443795144314325211057368077453621289",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "20974039945130958349664016193859738738894096578989253363246428948060761762470",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "18003652189303305754234261853454907050320490187508648828496025928984190494043",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "11355608200824774643182090377834454591495396307260035780744289314129433491895",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "9810705831162386079642167584419490541513292469026141191339827236901499020124",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "5961849267578332081278068606262106510001085777578305878407036713215937323724",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "9027407407468999059321798481965609851659884941800243031186505524256251030598",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "15338065207809046817381398632941307428240236048101682562517300343899698974010",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "20875064050560207538938055287293026801506653393278546026628370246016041078281",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "18040092628918537986177624626042978845611880122093381629819180695874604111009",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "5659891757319643759634118615532305954385116779912114465817715156688960184636",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "7359550030400197659068543062227781670064336700960554720538965860844036278777",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6070533135596759038371305850123337379853313839956260171807345991584769753615",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "5123114870415014483712550301650362307565687485702912276901947756058473561649",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "14467650238372732974482198478462008983322079938331659978075896901180040331968",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "9209689366951139301848685376763494508897615856515033483787381168390034190467",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "7960886539991806604536383617454043590900867135129571682513401664024900089667",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "17928981130323489490742684542940644525743182251185213232450354467917706197246",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "20139963027753512206052135782390333236518291545185015433051146481306609247731",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "9222617298719735042476863644785907811201758569880346468399791253501582098019",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "15654010538283458332080467585670958964913842426570435300432392816724047894311",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "9267239753497642745751466468991753005740214152430457754864058100499864198322",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "14354092995698965989781945631844976479638240508763845833249437806952674029911",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "12175800144525429101711112436375479793158395141238133191391220637213181529690",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6515433628678175260570981552366979988400136207179625380624981270104513863939",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "10436904003466491873636544168255162072350152658982817227012786069909624463085",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "2855814036503510450788900654941323606094608971716799072905638276391651409061",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16049507033297806751132280362277794698870532493214311239469945668244135710986",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "18349445017320660643439557711877104449943912266599701182302998542633854105",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "14492179127062308241071165524243437369949364706617523106100488443412940175032",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "14537889013535585714865347323168299832023876161488598941340164091387832471253",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "5636990014355524024923262191673945504971189243993959097948705301243362006515",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "5955288146264296067970114471132426595736495969609240529070837719134184612217",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "20454071111558562199372475630985491554920377545367167424011972621154171619805",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6025114060080799631650863366021681516822471253318654907832637114622715128129",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "14104893536547052047392860439439330381767281362844632076784734418573441267745",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16109667816413935023750205654253906429418683344897793607540998977926555961741",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "732376984270789815311545384523935453284807889780771487715449884105520972283",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "1865975806057983392124616957441463749203947113304879009275279530265401804469",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "60070245455028731352808910924946715887048442544404045790677632031531102542",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "1892266377149367809857613637074097391824167124401142496680042439241604557014",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "14027265094437545341966727039027391506317864313321954879181388644676887356818",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "3926151088632845291480322411321995204935166370995903995105404762502904011635",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "2845993925722828390288163431824022710826824315382636831884461569425635896625",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "16536415099975613502765270871558709914113991972714049291236414931934586077113",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "15427159427211997296952076488394791882693409912263671354907708468333082899304",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "4981203353312763758512382453839015051282256031184604642619069618691506848924",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "16412725493024792840022529961763382551450396034351521935777558667324222880948",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "2575097609020616709069137520715573565452937064409068923318170350415819674828",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "12537739753568098578046856022483736890805899115643350200575870128901107534024",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "7783447719200136121184174537221602817450322791109892841215551865990250707142",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6200980372206439302737698067540568163991521688464125328728899809148833898041",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6668268138049052845789461641252482793063466344031167873227471168513658855558",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "19342025524452851592437746579638840274752999153513667703887542806721922847762",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "1034538705308858492914793876167091482182105923613918630211003428549006481113",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16548503300873990166310872926763801718816758297670394701019645399434504370337",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "4386635179051846768727846470820265797200891098784716421560923865602729362221",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "113112020141069628238555450604658978354374494179863892379158479608087273125",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16273313526684892269418417133253098176370371367702470380684045272050615329779",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "9917116379743869051758751543636735746200343813387082682937433214463257870672",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "21347437395519871559013511683222625134578047988505027138922098010986663366085",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "6073412577272650916035164498461261115098549149671972436712971971174041965461",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "16000743393243364513879992036641409546772476702897812379606887701115786790877",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "13516006820623093859164373094769720753622854384270853749294309113792276500880",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "14327678901278271808256572874205694654053121078939259270905607658448709372565",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fq::from_str(
+                    "6898205721908867057302632138206136914618271517142960358384109101441315482990",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "1547668509933997089713975030755360269787076821002435736821985119540611525056",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "21137395357552264982627729354617813116933972134269172616501642314040236331333",
+                )
+                .unwrap(),
+            ],
+        ],
+    }
+}
+
+pub fn static_params() -> &'static ArithmeticSpongeParams<Fq> {
+    static PARAMS: Lazy<ArithmeticSpongeParams<Fq>> = Lazy::new(params);
+    &PARAMS
+}
@@ -0,0 +1,908 @@
+use ark_bn254::Fr;
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
+use once_cell::sync::Lazy;
+
+/* Generated by params.sage, where the Pasta fields have been replaced by the BN254
+fields, and the prefix `Pasta_p` by `Bn254_fr`:
+```shell
+sage params.sage --rounds 60 rust 3 arrabiata
+```
+ */
+
+use std::str::FromStr;
+
+fn params() -> ArithmeticSpongeParams<Fr> {
+    ArithmeticSpongeParams {
+        mds: vec![
+            vec![
+                Fr::from_str(
+                    "17560560263292369430704600989009627766278182302169707762296488780103846449746",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "3393758765110587721122658827583381959515539941077750713181985617561388683263",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12243188066905106532204529764614338779382066362400228597379672729341639932990",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "2978894859455416992250693793786940649331364416115355481858945206602142162437",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "5669911451183396592623909673951609766524521356698387913769824049757956232788",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12764543414985809635549652978033573085822428428072871748921469816551011724626",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "6468348774592948441194393852242415680859715697718046684183708356132261141574",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "19126723682580252058593636911401340851393620029244474919182097243770238924620",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "4025186473179386670011100033127428320910156595318032526466620686647178503952",
+                )
+                .unwrap(),
+            ],
+        ],
+        round_constants: vec![
+            vec![
+                Fr::from_str(
+                    "17660789524701597831760080527219503645065732612722336791655091548840954720018",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "5500104914592930205353248117934801899683437846821448616649646884644098956162",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "19823051840399054714064263611148265507977886396532807424263940670485497179382",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "9201604937412762421878675406698927117257679917133401150018557554321290344337",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "7580528312263022553292830901389186855590776336304348437335026567191449981811",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "9603466190959581107711946140450074430768894730290436866020960707555877969685",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "4981502057989193819481950821120089265755165989068771198028124758022313929313",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "13999768678811039134993608930747758546813766847277848166539549249174119285131",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "11903154572652839391781012989590833683268815076779676580441957431164765723406",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "17509009778813230787370660448623971760635183735633847935653244334946619334312",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12906919320652112374072773042374845347533685458381072187017005974906710990614",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "5888856867173342849667485624554936983774521704713242050556929275699162670429",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "4308987496986727790909108938882127722424420818208642291158142042001595686965",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "13115636244009382702195806488186204095675892974864270133294421261826898613191",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "3660511470207367733377615911455465316161522040815856665064883204551680019229",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "3333193947474559763284986431841311728341856658914754496817309619057156924520",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "1549985985531673982892748811365809533962468748687484068667173360381071510518",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "8577457132196416704522097501594655668901294847968468009638984545619869075664",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "15837762986711095683688321108217630996486515869136014565644733769391148626929",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "5463456693317499348284624752872504925503525383210990384056028646777277619954",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "9190145565845346982691327089526944734396545614557001961139879106722103346323",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "7486477418469008286702757695230354964444537903702012861827071438548266660077",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "917800133005026703468449020278050385975747106058446516077878757278715212168",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "1917128575780063918209206255409583579618130383567374149160563786086883780185",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "2121119934642131493483869798612784297422698633136413187994206022359813773461",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "18016069867850838558703924217822455916709013603540038371253273721924911261656",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "1424424185593151645204621819057928965036423078607177655512255459800356972917",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "1831306696671402600817456453694124365283979216995918875488452603669526432409",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "3693562553116748360088860850661413915401397397298827909873341745679625905867",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "800297211025426659604716579767107866943275979858803293698801555448499013824",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "17978746430397374851944544427562651956244019483861167557418345634552104515091",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "3406830455176761861814407934777750260068161120720654935034397051575496379238",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "419970611138908395522614298155676942609428392217844865223010747020855874134",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "5211712066402673092255545684603442361616399531750041373945807803331384522363",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "6837012356080146002746014424619870775470066656566741481405049578669611089756",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12295858707931391599734501377113685837035391535868112446408169068489540220669",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "11160055771881233514446900755186174302074927873688525466802620955612447238145",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "15768224874579208633192632786445323389913032363807491440492499057059238384511",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "1061927211566205141826931688409616702199271953739714470308726705839185368535",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "5359686050619800379032884146688597882147625135755023466786272061402797168505",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "18240060270692402026785329820851789086063436062143878566836632109127966245443",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "5590321730142895777340136705383187111072931178969765799877904091816203903199",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "16466667501722532256887481497932506315373132176386141303395874206348584058294",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "4603758445592255345768649147821800220988775446831967679682371712767106700246",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "11166456125293653043122338464794022565982101524335900008270114636048660259881",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "15835438991330671294822339406426004393877890806383320230647533283040715808882",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12447052562788492971932934631494166967042444703222309020713249865811019472685",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "10950241921439811692299634399322027776100438444341516700515746736554046185405",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "1071803673141722242305310133613811317525106055232588342778035671392782106964",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "7664232636790321998323759716285330422331823722798468180052736333294409929447",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "7848045799646001087737141328603373406140408974574956863266637023156539182192",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "6906329401246723118137338560717114267983762001715221720090855506043200568480",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "1034162905235236979814520654641004804139643410275152137230622745894457081103",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "13327058904225472605866508609103109730560830150873265603497115104909988390175",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "5119661740884062369964603642864269603000420937109288140991624602055051580564",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "13271695313692484830395883897315291637195762772040229070994171196270670166416",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "17281855091739762404432671549915612036685083601715757560904720454092210555549",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "5380643372162205212294686861200913986123925314684568431317488605015248906772",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "3033957968350816372565142864085084343919705300933226656916573411305838421314",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "17790408791295492391277341752558988469139487043638752048795166205132787116024",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "3892381523724871493098356636551712826964264024202605868254673401310911756133",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "1280706661890380590029206651110396657316476084753177034454383822891162564372",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "17058718274868810488254449422440856286336378882980211148350668962502264778123",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "17230895078054818813075453702868960244584619699928664567689680270091755282742",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "8823130817888380661871477787670946929216393867838546946830702698423255230205",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "7802611179337480658521133107221280002063783947023489188419586519548633740469",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "20208559792280515983734740818127036858533030492553475581739564466864641252285",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "21785295387636462184853793579825879807725434476993964779037854146143541756142",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "2711967669371206238234197622450831582789477765964271008869018601236000492559",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "4272991383215256825713778626952705999466539732201225310583342347262113944683",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "14614842398747443564883179442035765105622791604122173170024044641939795285334",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "19635712736654222015033436021314414831288323178706800359002109336757819665682",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "6018552565454746677702703581163751864343158527143900861873354553213493111593",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "6641726248360714864841563399204702705170478307833271461730780989540506886407",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "20843263946708711195946898073559232138608757085377926839816563279702068134225",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "17175287112276728161846078465920438994902988227910201506846235541813238242845",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "8021578195935195107945717581583014767890598696293127062646393746578404056471",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "17706324736689518335365298430536680022060802110186398458852163442068538474215",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "1735756646188502709578283770248047773820286651217968193666420076891807804047",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "781480232833551327458980240921528091804917061934986402029571405270428556798",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "11460294661084934507057327006264107308861305405889028202893010401068828039435",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "187310933230879768017581272502752634425448006321285171793987820206886254921",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "17507602383802352441191245033284463434066391498217097153834908522707531820466",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "8060626394815761102706464630844470647387303327555221038337996150818173026188",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "19412807112262541254094498107795525944231623626595153620581467874570704307725",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "422233587565630681819955604026916805679078693225465868127873706810167314607",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "5890514074616191300331531553206734678952728926082098578986848755394118543174",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "19130169242735228153327289865934314763294406405632583710583588348376661296345",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "6781668687772040579618845648193493829985592656240113158863343230256848125478",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "4226002257035223169108002590540563519241966273189825232800195535387048895755",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "3284152014973258245026955545661099827377986747490263894860835274636520194579",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12924916779883228904748819053900942374270292624164245189500579789915121699548",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "2701811164388303212408987175924463859375141755355758501648238929893249886613",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "11058509838843458824004545378047904354085781015593571871167534780048614268205",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "641831382173467710957403003737696726269959592423056552989484825497490807326",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "806918712537350644488345391395195558698195557186142449230154351820279168009",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "16153061952447578791029232356681239347523127394910874740371822061398129589189",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "15082205040461576209881813694243825876367252295768479591639386269387535246537",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "696252852818607126394309121254784268381418349832720307170090991431174525377",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "19958554194274592703594607894477900589335641368918751476053117627592027239198",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "20703040770357389261685780004396657477923523971142995421128286820237913570913",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "423119088647668246648844414922742558790966695410629144879462099564114110813",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "3785636888318264901073742578485292157978965266112775515196757947880518255249",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "16560739439327499561188336210014341348992738736988059634144619779012124821061",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "19926773201175221006791522368921349620208554670195808566046520730801654178491",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "21611568946009402962766747134470529369756616143889244720748868393910820593200",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "5919016358120576070462163613879326531696358604546628130121358763633729130278",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "6438560800920003281097309567878322405589824312036044374098630944940036353350",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "11303942425420376285940103067664728518940810046881311705201892920251952352190",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "6910790297611117098407883098044767282119209372544645960534207528646753647012",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "17255244651923489501274052665578282330991628537588531497678403679400624669540",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "8430507528574382942273222211442213242189770163847173619053664349782996050679",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "6870892043388730792696844351491795292083927376042644643073493013024477437225",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "19181296156278223615819142633833459772311730653135785679119349328500072809551",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "12816311865094708748046685289047668431956100230693834983583247306832982437837",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "8571541477157375194820018107140945726719511178082841130086031567862124023691",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "1375743986901142912196799776385156122012322040994078242374760603664136986995",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "3929515940552949462516338491160669019615980736982310716799686326473590956681",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "9988030373267046506866504421732463694440868732983584601955618547022180352508",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "9373908576263924042998000486494864404857287057210590720576211616292981302427",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "13742339115854624856147779806304277977065131877878319157116289863692760245234",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "14446622863378194307474855517894308855870430083882009643746591737870621485082",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "18810106539490160109317395236863397005778978564058807519288575854142531353062",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "3590236650761631432658136079606292079187725954055171448007684625466342473876",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "18745219233223234399758146142025928735985132527585298799369129897996032995139",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "543769951692683164722070928347022599577539410301038663050841357918073563807",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "18034076939804674090166555954724317915541861402859614740272752160545810218176",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "1775651254501666834012175250128119056519453612046170155263780939761984765555",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "10850352203076362473978347644948188000126114301419758902667300519308231467864",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "8273307746759256513616855563879591385799049132879972420387734879322371040098",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "20533722610945253693924317098442557618935426234125560107858483298348121900609",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "15646889593164984056622587382284185671188446241883652908404296154902679661303",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "5676194523185193456759967244732627006981680306954364313155176104301000568512",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "13326368958783347837362721651959159580718668521080152693523145509849114689427",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "4432017906059425334539448081883256364276177559516369580848708862628956873383",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "12152695399482231415970677575720165025611363511554162277101446119846395396225",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "11198172096210028369995953562494048301469260097115400864110051301449310150286",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "5268829323912225553840222558419579212513534548101256989040934230737373419707",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "13462021164800352317737342443054187384605559852809707781071122922062156221602",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12743195870079445691878451918321741340402914001102103214825775516406560574996",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "14676791865351694927646362576675069604941602670778837237437371941381494577398",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "933704736531217588124616371032896783541163373163839917929072671504004287145",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "2306600659242930306809454256202720155311001109239820613812271279944215757068",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "21193778548447541140163738185634908686855106299381128554040495967284421820442",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "10944527908499092117938203580525463102769374836018547912484791489279878651309",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "14919958033605745974091432044606312946179331353010843418477557559719866747304",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "10297225891340927092587610783727804731993993319405188247270106162674268045422",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "13816903090405557006535664424205769089806368707363495998936492053993767416964",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "7258944929059165653377949587189644628012806268603300676069689045236865833610",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "17116372595900386598393641705641637128078798139472952984113060946321262922394",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "11587629229984977046777912344819168977438112929141029963722663724908320526221",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "20747508622972167215266425368806260781929526214991159753587492397454633461429",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "19980344863266335073111401734093177614558711158905141346195089441831563300393",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "9457056010550480907642124111540096636581744105637806321094633086333508850556",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "14577805547767862752251897471225567838568462261494003929768033102893240797837",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "19104621163150438169021750098188727411316040142237890903073191052735911219886",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "19868755743575565941423439145629326632003206353830890440824826354395346531702",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12176945590321575241935215940580792040858019554058630320671620984804228123984",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "9434792968923547003932401287197242001483392826526453468547857584740475677229",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "3006740085102837820126707845794661858001064845274064733469237309238876177030",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "11728797430214373108314083390875931946318822401482206838741090272133040286711",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "720287490521273152776820135532744400549011933375829447938340827305981538007",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "16029202065014579925264539355565386370339426221448890780049079386325550541046",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "2073843786635886539948922645528624673716819881361214142051859570414509577648",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "16229926200956412779726407693433434058131057371165848878611887268311790017375",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "9955601886537097688283414486131532980408822663751344579978521139273492788621",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "17047457346769150016255992212487293631685398685135331147472626665908965380025",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "10185980148540599388976621815818258711951152075382753361833409616482440404864",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "14922190223335926072761272003639486111294024689863716079293032549638569927931",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "6274949823247874334062693101233219431091640638581539453609059644702889292218",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "15995171343935505375179246511605941906125595377873004153289224421854231625981",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "5433541850956620944635107916368073742688469903797633214092900712336294290233",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "7701994054625247499286423851907587983264601775611524625942340639076260438897",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "4417305587020062552665320097874902270387554680733831385598101560408499901554",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "11733757501559629417728976420595341981363653943924422027801061116730578171950",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "16838098781726844245818116081856613663026245818192450803745605499780762437933",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "12598075727411400616596760843692635388329063689049008574242356050026237332062",
+                )
+                .unwrap(),
+            ],
+            vec![
+                Fr::from_str(
+                    "18156726945817492917627522127841217785269046295838921696651748639612332532392",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "9059208178773617011988466267518003492531616474206075985535989480320151704097",
+                )
+                .unwrap(),
+                Fr::from_str(
+                    "21843838020466809152567493575694455234276212847478551845534651808271356591929",
+                )
+                .unwrap(),
+            ],
+        ],
+    }
+}
+
+pub fn static_params() -> &'static ArithmeticSpongeParams<Fr> {
+    static PARAMS: Lazy<ArithmeticSpongeParams<Fr>> = Lazy::new(params);
+    &PARAMS
+}
@@ -6,6 +6,28 @@ use ark_ff::PrimeField;
 
 use crate::witness::Env;
 
+/// Configuration of the prover.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProverConfig {
+    /// Process the rows in parallel when computing the cross-terms, see
+    /// [crate::cross_terms::CrossTermsComputer].
+    pub parallel: bool,
+
+    /// Keep the constraints converted into multivariate polynomials between
+    /// two iterations. As the circuit is the same for all the iterations, the
+    /// conversion only has to be done once.
+    pub cache_constraints: bool,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self {
+            parallel: true,
+            cache_constraints: true,
+        }
+    }
+}
+
 /// Generate a proof for the IVC circuit.
 /// All the information to make a proof is available in the environment given in
 /// parameter.
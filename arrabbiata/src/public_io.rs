@@ -0,0 +1,86 @@
+//! This module contains the host-side helpers to compute the hash of the
+//! public IO of a step of the IVC.
+//!
+//! Instead of exposing all the accumulators as public inputs, each step of
+//! the IVC only exposes a single field element, the hash of the running
+//! accumulators. The hash is computed in the circuit by the gadget
+//! [crate::columns::Gadget::Poseidon], the values being absorbed two by two,
+//! and the digest is exposed as a public input by the gadget
+//! [crate::columns::Gadget::PublicIOHash].
+//!
+//! The values are absorbed in the following order:
+//! - the accumulators `acc_1, ..., acc_N`, coordinate by coordinate,
+//! - the hash of the previous step, encoded in 2 chunks of 128 bits as it is
+//! an element of the other field of the cycle,
+//! - the iteration index `i`,
+//! - the initial state `z_0`,
+//! - the current state `z_i`,
+//! - the output state `z_(i + 1)`, written by the application,
+//! - the accumulated challenges.
+//!
+//! The functions in this module are used by the verifier, and by the tests, to
+//! recompute the value outside of the circuit. The function [absorb] can also
+//! be used to recompute the state of the stream hash, see
+//! [run_stream_hash](crate::interpreter::run_stream_hash).
+
+use ark_ff::PrimeField;
+use mina_poseidon::{
+    constants::SpongeConstants, permutation::poseidon_block_cipher,
+    poseidon::ArithmeticSpongeParams,
+};
+
+use crate::{POSEIDON_ALPHA, POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE};
+
+/// The Poseidon instance used in the circuit, described in the top-level
+/// documentation of the [interpreter](crate::interpreter) module.
+#[derive(Clone)]
+pub struct PoseidonSpongeConstants {}
+
+impl SpongeConstants for PoseidonSpongeConstants {
+    const SPONGE_CAPACITY: usize = 1;
+    const SPONGE_WIDTH: usize = POSEIDON_STATE_SIZE;
+    const SPONGE_RATE: usize = POSEIDON_STATE_SIZE - 1;
+    const PERM_ROUNDS_FULL: usize = POSEIDON_ROUNDS_FULL;
+    const PERM_ROUNDS_PARTIAL: usize = 0;
+    const PERM_HALF_ROUNDS_FULL: usize = 0;
+    const PERM_SBOX: u32 = POSEIDON_ALPHA as u32;
+    const PERM_FULL_MDS: bool = true;
+    const PERM_INITIAL_ARK: bool = false;
+}
+
+/// Return the number of full Poseidon hashes the circuit must compute to
+/// absorb the given number of values.
+pub fn number_of_poseidon_calls(number_of_values: usize) -> usize {
+    (number_of_values + POSEIDON_STATE_SIZE - 2) / (POSEIDON_STATE_SIZE - 1)
+}
+
+/// Absorb the given values into the sponge state. The values are added to the
+/// first [POSEIDON_STATE_SIZE] - 1 elements of the state before each
+/// permutation, the last chunk being padded with zeroes, like it is done by the
+/// gadget [crate::columns::Gadget::Poseidon].
+pub fn absorb<F: PrimeField>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut [F; POSEIDON_STATE_SIZE],
+    values: &[F],
+) {
+    let mut s = state.to_vec();
+    values.chunks(POSEIDON_STATE_SIZE - 1).for_each(|chunk| {
+        chunk.iter().zip(s.iter_mut()).for_each(|(v, s)| *s += v);
+        poseidon_block_cipher::<F, PoseidonSpongeConstants>(params, &mut s);
+    });
+    state.copy_from_slice(&s);
+}
+
+/// Compute the hash of the public IO, starting from the given sponge state,
+/// using [absorb].
+///
+/// The digest is the first element of the state after the last permutation.
+pub fn hash_public_io<F: PrimeField>(
+    params: &ArithmeticSpongeParams<F>,
+    initial_state: [F; POSEIDON_STATE_SIZE],
+    values: &[F],
+) -> F {
+    let mut state = initial_state;
+    absorb(params, &mut state, values);
+    state[0]
+}
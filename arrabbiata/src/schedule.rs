@@ -0,0 +1,110 @@
+//! This module defines the schedule of a multi-phase computation, i.e. the
+//! sequence of instructions executed by the iterations, like "Poseidon for 10
+//! steps, then EC scaling".
+//!
+//! The position in the schedule, called the program counter, is carried in two
+//! consecutive elements of the IVC state: the index `p` of the current phase,
+//! and the number of steps `c` already executed in the current phase. At each
+//! step, the gadget [Gadget::ProgramCounter](crate::columns::Gadget::ProgramCounter)
+//! constrains the update of the program counter, i.e.
+//!
+//! ```text
+//! (p, c) -> (p + 1, 0)      if c + 1 = L_p
+//! (p, c) -> (p, c + 1)      otherwise
+//! ```
+//!
+//! where `L_p` is the number of steps of the phase `p`. The lengths of the
+//! phases are passed in fixed columns. See the section [Program
+//! counter](crate::interpreter#program-counter) of the interpreter for the
+//! layout of the gadget.
+//!
+//! When all the phases have been executed, the program counter stays in the
+//! final phase, whose length is zero.
+
+use crate::{interpreter::Instruction, MAX_NUMBER_OF_PHASES};
+
+/// The maximum number of steps of a phase. The number of steps already
+/// executed in the current phase is range-checked on 16 bits by the gadget
+/// [Gadget::ProgramCounter](crate::columns::Gadget::ProgramCounter).
+pub const MAX_NUMBER_OF_STEPS_PER_PHASE: u64 = 1 << 16;
+
+/// A phase of a [Schedule]: the instruction is executed during
+/// `number_of_steps` consecutive iterations.
+#[derive(Clone, Copy, Debug)]
+pub struct Phase {
+    pub instruction: Instruction,
+    pub number_of_steps: u64,
+}
+
+/// A sequence of phases executed by the iterations.
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    pub phases: Vec<Phase>,
+}
+
+impl Schedule {
+    /// Create a new schedule from a list of instructions and their number of
+    /// steps.
+    ///
+    /// There must be less than [MAX_NUMBER_OF_PHASES] phases, one phase being
+    /// reserved for the end of the schedule, and each phase must contain at
+    /// least one step and at most [MAX_NUMBER_OF_STEPS_PER_PHASE] steps.
+    pub fn new(phases: Vec<(Instruction, u64)>) -> Self {
+        assert!(
+            phases.len() < MAX_NUMBER_OF_PHASES,
+            "A schedule contains at most {} phases, got {}",
+            MAX_NUMBER_OF_PHASES - 1,
+            phases.len()
+        );
+        assert!(
+            phases.iter().all(|(_, n)| *n > 0),
+            "Each phase must contain at least one step"
+        );
+        assert!(
+            phases
+                .iter()
+                .all(|(_, n)| *n <= MAX_NUMBER_OF_STEPS_PER_PHASE),
+            "Each phase must contain at most {MAX_NUMBER_OF_STEPS_PER_PHASE} steps"
+        );
+        let phases = phases
+            .into_iter()
+            .map(|(instruction, number_of_steps)| Phase {
+                instruction,
+                number_of_steps,
+            })
+            .collect();
+        Self { phases }
+    }
+
+    /// Return the number of steps of the given phase. It is zero for the
+    /// phases after the last one.
+    pub fn number_of_steps(&self, phase: usize) -> u64 {
+        self.phases
+            .get(phase)
+            .map(|p| p.number_of_steps)
+            .unwrap_or(0)
+    }
+
+    /// Return the instruction of the given phase, or `None` if all the phases
+    /// have been executed.
+    pub fn instruction(&self, phase: usize) -> Option<Instruction> {
+        self.phases.get(phase).map(|p| p.instruction)
+    }
+
+    /// Return the total number of steps of the schedule.
+    pub fn total_number_of_steps(&self) -> u64 {
+        self.phases.iter().map(|p| p.number_of_steps).sum()
+    }
+
+    /// Compute, outside of the circuit, the value of the program counter
+    /// `(p, c)` after executing one step. It is the value the gadget
+    /// [Gadget::ProgramCounter](crate::columns::Gadget::ProgramCounter)
+    /// writes in the IVC state.
+    pub fn next(&self, phase: usize, step: u64) -> (usize, u64) {
+        if step + 1 == self.number_of_steps(phase) {
+            (phase + 1, 0)
+        } else {
+            (phase, step + 1)
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! This module provides a way to export the execution trace of an iteration,
+//! for debugging purposes.
+//!
+//! The trace contains the witness matrix, labelled using the names of the
+//! columns given by [FormattedOutput], the values of the selectors, and the
+//! ranges of rows each gadget has been activated on. It can be written in CSV
+//! or JSON, to inspect for instance why the combined constraint of a fold
+//! doesn't vanish.
+//!
+//! The trace can be built from a witness environment using
+//! [Env::trace](crate::witness::Env::trace).
+
+use kimchi::circuits::expr::FormattedOutput;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, io::Write};
+use strum::IntoEnumIterator;
+
+use crate::columns::{Column, Gadget};
+
+/// A range of consecutive rows on which a gadget is activated.
+/// The row `end` is excluded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GadgetRange {
+    pub gadget: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A row of the execution trace.
+/// The values are given in base 10.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceRow {
+    pub row: usize,
+    pub values: Vec<String>,
+    pub selectors: Vec<bool>,
+}
+
+/// The execution trace of an iteration.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trace {
+    /// The index of the iteration the trace has been built for.
+    pub iteration: u64,
+
+    /// The labels of the witness columns, e.g. `x[0]`.
+    pub column_labels: Vec<String>,
+
+    /// The labels of the selectors, e.g. `q_app`.
+    pub selector_labels: Vec<String>,
+
+    pub rows: Vec<TraceRow>,
+
+    /// The ranges of rows each gadget has been activated on, ordered by the
+    /// first row.
+    pub gadget_ranges: Vec<GadgetRange>,
+}
+
+impl Trace {
+    /// Build the trace from a witness and the selectors, given column by
+    /// column. Only the first `nb_rows` rows are kept.
+    pub fn new<T: ToString>(
+        iteration: u64,
+        witness: &[Vec<T>],
+        selectors: &[Vec<bool>],
+        nb_rows: usize,
+    ) -> Self {
+        let mut cache = HashMap::new();
+        let column_labels: Vec<String> = (0..witness.len())
+            .map(|i| Column::X(i).text(&mut cache))
+            .collect();
+        let selector_labels: Vec<String> = Gadget::iter()
+            .map(|gadget| Column::Selector(gadget).text(&mut cache))
+            .collect();
+        let rows: Vec<TraceRow> = (0..nb_rows)
+            .map(|row| TraceRow {
+                row,
+                values: witness.iter().map(|col| col[row].to_string()).collect(),
+                selectors: selectors.iter().map(|col| col[row]).collect(),
+            })
+            .collect();
+
+        let mut gadget_ranges: Vec<GadgetRange> = vec![];
+        Gadget::iter()
+            .zip(selectors.iter())
+            .for_each(|(gadget, col)| {
+                let mut start: Option<usize> = None;
+                (0..=nb_rows).for_each(|row| {
+                    let is_active = row < nb_rows && col[row];
+                    match (start, is_active) {
+                        (None, true) => start = Some(row),
+                        (Some(s), false) => {
+                            gadget_ranges.push(GadgetRange {
+                                gadget: format!("{gadget:?}"),
+                                start: s,
+                                end: row,
+                            });
+                            start = None
+                        }
+                        _ => (),
+                    }
+                })
+            });
+        gadget_ranges.sort_by_key(|range| range.start);
+
+        Self {
+            iteration,
+            column_labels,
+            selector_labels,
+            rows,
+            gadget_ranges,
+        }
+    }
+
+    /// Write the rows of the trace in CSV. The first line contains the labels
+    /// of the columns and of the selectors.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let header: Vec<&str> = std::iter::once("row")
+            .chain(self.column_labels.iter().map(|l| l.as_str()))
+            .chain(self.selector_labels.iter().map(|l| l.as_str()))
+            .collect();
+        writeln!(writer, "{}", header.join(","))?;
+        self.rows.iter().try_for_each(|row| {
+            let line: Vec<String> = std::iter::once(row.row.to_string())
+                .chain(row.values.iter().cloned())
+                .chain(row.selectors.iter().map(|s| u8::from(*s).to_string()))
+                .collect();
+            writeln!(writer, "{}", line.join(","))
+        })
+    }
+
+    /// Write the ranges of rows of each gadget in CSV.
+    pub fn write_gadget_ranges_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "gadget,start,end")?;
+        self.gadget_ranges.iter().try_for_each(|range| {
+            writeln!(writer, "{},{},{}", range.gadget, range.start, range.end)
+        })
+    }
+
+    /// Write the whole trace in JSON.
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(std::io::Error::from)
+    }
+}
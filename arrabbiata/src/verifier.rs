@@ -1 +1,322 @@
 //! A verifier for the folding/accumulation scheme
+//!
+//! The relation a folded instance must satisfy is derived from the same
+//! constraints the prover uses to compute the cross-terms (see
+//! [crate::cross_terms]), to avoid maintaining by hand, in the verifier
+//! circuit, a copy of the constraints that could drift from the ones used to
+//! prove.
+//!
+//! For a relaxed instance `(w, u)` with error term `E`, the relation is, for
+//! each row:
+//!
+//! ```text
+//! Σ_i α^i C_i(w, u) - E = 0
+//! ```
+//!
+//! where `C_i(w, u)` is the constraint `i` homogenized to the degree
+//! [crate::cross_terms::HOMOGENEOUS_DEGREE] using `u`, i.e.
+//! `C_i(w, u) = u^D C_i(w / u)` for the degree `D`. In addition, the column of
+//! each lookup table must be `u t`, where `t` is the fixed column of the table,
+//! see the [logup](crate::logup) module.
+//!
+//! The combination is built once as an expression, see
+//! [FoldedRelation::combined_expression], where `u` is the challenge
+//! [ChallengeTerm::HomogenousChallenge]. The same expression is evaluated by
+//! the verifier on the host, see [FoldedRelation::check], and in a circuit, see
+//! [FoldedRelation::evaluate_in_circuit].
+
+use ark_ff::PrimeField;
+use kimchi::circuits::{
+    expr::{ConstantExpr, ConstantExprInner, ConstantTerm, ExprInner, Operations, Variable},
+    gate::CurrOrNext,
+};
+use num_bigint::BigInt;
+use o1_utils::field_helpers::FieldHelpers;
+use std::ops::{Add, Mul, Sub};
+use strum::IntoEnumIterator;
+
+use crate::{
+    columns::{ChallengeTerm, Column, E},
+    cross_terms::{
+        constraints_to_polynomials, evaluations_at_row, ConstraintPolynomial, HOMOGENEOUS_DEGREE,
+        MAX_NUMBER_OF_VARIABLES_PER_ROW, NUMBER_OF_VARIABLES,
+    },
+    interpreter::InterpreterEnv,
+    logup::LookupTable,
+    NUMBER_OF_COLUMNS,
+};
+
+/// The relation checked by the verifier for a folded instance, derived from
+/// the constraints of the circuit.
+pub struct FoldedRelation<F: PrimeField> {
+    /// The constraints, as used by the prover.
+    pub constraints: Vec<E<F>>,
+
+    /// The constraints converted into multivariate polynomials, as used by
+    /// the prover to compute the cross-terms.
+    pub polynomials: Vec<ConstraintPolynomial<F>>,
+
+    /// The number of columns of the circuit, giving the layout of the
+    /// variables, see [crate::columns::Column::index].
+    pub number_of_columns: usize,
+
+    /// The combination of the homogenized constraints, see
+    /// [FoldedRelation::combined_expression].
+    combined_expression: E<F>,
+}
+
+impl<F: PrimeField> FoldedRelation<F> {
+    pub fn new(constraints: Vec<E<F>>) -> Self {
+        Self::new_with_number_of_columns(constraints, NUMBER_OF_COLUMNS)
+    }
+
+    /// Build the relation of a circuit using the given number of columns, see
+    /// [crate::columns::Column::index] for the layout of the variables.
+    pub fn new_with_number_of_columns(constraints: Vec<E<F>>, number_of_columns: usize) -> Self {
+        crate::check_number_of_columns(number_of_columns);
+        let polynomials = constraints_to_polynomials(&constraints, number_of_columns);
+        let combined_expression = combine_homogenized_constraints(&constraints);
+        Self {
+            constraints,
+            polynomials,
+            number_of_columns,
+            combined_expression,
+        }
+    }
+
+    /// Return the expression `Σ_i α^i C_i(w, u)` combining the homogenized
+    /// constraints, the challenge `α` being [ChallengeTerm::Alpha] and `u`
+    /// being [ChallengeTerm::HomogenousChallenge]. It is the expression the
+    /// verifier evaluates, on the host and in the circuit.
+    pub fn combined_expression(&self) -> &E<F> {
+        &self.combined_expression
+    }
+
+    /// Evaluate the combined homogenized constraint `Σ_i α^i C_i(w, u)` for
+    /// the given evaluations of the variables of a row, using the layout of
+    /// [crate::cross_terms].
+    pub fn evaluate_row(&self, eval: &[F; NUMBER_OF_VARIABLES], u: F, alpha: F) -> F {
+        evaluate_expression(
+            &self.combined_expression,
+            &|var| eval[self.variable_index(var)],
+            &|chal| match chal {
+                ChallengeTerm::Alpha => alpha,
+                ChallengeTerm::HomogenousChallenge => u,
+                chal => panic!("The challenge {chal} is not used by the folded relation"),
+            },
+            &|x| x,
+        )
+    }
+
+    /// Evaluate the combined homogenized constraint in the circuit described
+    /// by `env`. The cells of the witness are given by `cell`, and the
+    /// challenges `α` and `u` by `challenge`.
+    pub fn evaluate_in_circuit<Env: InterpreterEnv>(
+        &self,
+        env: &Env,
+        cell: impl Fn(&Variable<Column>) -> Env::Variable,
+        challenge: impl Fn(ChallengeTerm) -> Env::Variable,
+    ) -> Env::Variable {
+        evaluate_expression(&self.combined_expression, &cell, &challenge, &|x: F| {
+            env.constant(BigInt::from(x.to_biguint()))
+        })
+    }
+
+    /// The index of the variable in the layout of [crate::cross_terms].
+    fn variable_index(&self, var: &Variable<Column>) -> usize {
+        let idx = var.col.index(self.number_of_columns);
+        match var.row {
+            CurrOrNext::Curr => idx,
+            CurrOrNext::Next => MAX_NUMBER_OF_VARIABLES_PER_ROW + idx,
+        }
+    }
+
+    /// Evaluate the combined homogenized constraint on each row of the given
+    /// witness, given column by column. The next row of the last row is the
+    /// first one.
+    pub fn evaluate(&self, witness: &[Vec<F>], u: F, alpha: F) -> Vec<F> {
+        let domain_size = witness[0].len();
+        (0..domain_size)
+            .map(|row| self.evaluate_row(&evaluations_at_row(witness, row), u, alpha))
+            .collect()
+    }
+
+    /// Check that the relaxed instance `(witness, u)` satisfies the relation
+    /// with the given error term, and that the column of each lookup table is
+    /// `u t`, where `t` is the fixed column of the table. In case of failure,
+    /// the first row that does not satisfy the relation, or whose entries of
+    /// the tables are not the fixed ones, is returned.
+    pub fn check(&self, witness: &[Vec<F>], u: F, alpha: F, error: &[F]) -> Result<(), usize> {
+        let domain_size = witness[0].len();
+        let tables: Vec<(usize, Vec<F>)> = LookupTable::iter()
+            .map(|table| {
+                let idx = Column::LookupTable(table).index(self.number_of_columns);
+                (idx, table.fixed_column(domain_size))
+            })
+            .collect();
+        match self
+            .evaluate(witness, u, alpha)
+            .iter()
+            .zip(error.iter())
+            .enumerate()
+            .position(|(row, (eval, e))| {
+                eval != e
+                    || tables
+                        .iter()
+                        .any(|(idx, t)| witness[*idx][row] != u * t[row])
+            }) {
+            Some(row) => Err(row),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Return `u^d` as an expression, where `u` is
+/// [ChallengeTerm::HomogenousChallenge].
+fn homogenizing_power<F: PrimeField>(d: u64) -> Option<E<F>> {
+    let u = E::<F>::from(ChallengeTerm::HomogenousChallenge);
+    match d {
+        0 => None,
+        1 => Some(u),
+        d => Some(Operations::Pow(Box::new(u), d)),
+    }
+}
+
+/// Multiply `expr`, homogeneous of degree `degree`, by the power of `u` making
+/// it homogeneous of degree `target`.
+fn pad<F: PrimeField>(expr: E<F>, degree: u64, target: u64) -> E<F> {
+    match homogenizing_power(target - degree) {
+        None => expr,
+        Some(u) => u * expr,
+    }
+}
+
+/// Homogenize the expression `expr` using `u`, returning the homogenized
+/// expression and its degree. The challenges and the constants are of degree
+/// `0`, and the cells of degree `1`.
+///
+/// # Panics
+///
+/// The Lagrange basis, the vanishing polynomial and the feature flags are not
+/// supported, as they are not polynomials in the cells.
+fn homogenize<F: PrimeField>(expr: &E<F>) -> (E<F>, u64) {
+    match expr {
+        Operations::Atom(ExprInner::Constant(_)) => (expr.clone(), 0),
+        Operations::Atom(ExprInner::Cell(_)) => (expr.clone(), 1),
+        Operations::Atom(_) => panic!("Only the cells and the constants can be homogenized"),
+        Operations::Pow(e, k) => {
+            let (e, d) = homogenize(e);
+            (Operations::Pow(Box::new(e), *k), d * k)
+        }
+        Operations::Add(x, y) | Operations::Sub(x, y) => {
+            let ((x, dx), (y, dy)) = (homogenize(x), homogenize(y));
+            let d = std::cmp::max(dx, dy);
+            let (x, y) = (pad(x, dx, d), pad(y, dy, d));
+            match expr {
+                Operations::Add(_, _) => (x + y, d),
+                _ => (x - y, d),
+            }
+        }
+        Operations::Mul(x, y) => {
+            let ((x, dx), (y, dy)) = (homogenize(x), homogenize(y));
+            (x * y, dx + dy)
+        }
+        Operations::Double(e) => {
+            let (e, d) = homogenize(e);
+            (Operations::Double(Box::new(e)), d)
+        }
+        Operations::Square(e) => {
+            let (e, d) = homogenize(e);
+            (Operations::Square(Box::new(e)), 2 * d)
+        }
+        // The cached expression changes when homogenized, the cache is dropped
+        Operations::Cache(_, e) => homogenize(e),
+        Operations::IfFeature(_, _, _) => panic!("Feature flags are not supported"),
+    }
+}
+
+/// Return `Σ_i α^i C_i(w, u)`, each constraint `C_i` being homogenized to the
+/// degree [HOMOGENEOUS_DEGREE].
+fn combine_homogenized_constraints<F: PrimeField>(constraints: &[E<F>]) -> E<F> {
+    let alpha = E::<F>::from(ChallengeTerm::Alpha);
+    constraints
+        .iter()
+        .rev()
+        .map(|c| {
+            let (c, d) = homogenize(c);
+            assert!(
+                d <= HOMOGENEOUS_DEGREE as u64,
+                "The constraint is of degree {d}, higher than {HOMOGENEOUS_DEGREE}"
+            );
+            pad(c, d, HOMOGENEOUS_DEGREE as u64)
+        })
+        .reduce(|acc, c| acc * alpha.clone() + c)
+        .expect("The relation must contain at least one constraint")
+}
+
+/// Evaluate the expression `expr`, the cells being given by `cell`, the
+/// challenges by `challenge` and the literals by `literal`.
+///
+/// # Panics
+///
+/// Only the cells, the challenges and the literals are supported, like in
+/// [homogenize].
+pub fn evaluate_expression<F, V>(
+    expr: &E<F>,
+    cell: &impl Fn(&Variable<Column>) -> V,
+    challenge: &impl Fn(ChallengeTerm) -> V,
+    literal: &impl Fn(F) -> V,
+) -> V
+where
+    F: PrimeField,
+    V: Clone + Add<Output = V> + Sub<Output = V> + Mul<Output = V>,
+{
+    evaluate_operations(expr, &|inner| match inner {
+        ExprInner::Constant(c) => evaluate_constant(c, challenge, literal),
+        ExprInner::Cell(var) => cell(var),
+        _ => panic!("Only the cells and the constants can be evaluated"),
+    })
+}
+
+fn evaluate_constant<F, V>(
+    expr: &ConstantExpr<F, ChallengeTerm>,
+    challenge: &impl Fn(ChallengeTerm) -> V,
+    literal: &impl Fn(F) -> V,
+) -> V
+where
+    F: PrimeField,
+    V: Clone + Add<Output = V> + Sub<Output = V> + Mul<Output = V>,
+{
+    evaluate_operations(expr, &|inner| match inner {
+        ConstantExprInner::Challenge(chal) => challenge(*chal),
+        ConstantExprInner::Constant(ConstantTerm::Literal(x)) => literal(*x),
+        ConstantExprInner::Constant(_) => panic!("Only the literal constants can be evaluated"),
+    })
+}
+
+fn evaluate_operations<T, V>(expr: &Operations<T>, atom: &impl Fn(&T) -> V) -> V
+where
+    V: Clone + Add<Output = V> + Sub<Output = V> + Mul<Output = V>,
+{
+    match expr {
+        Operations::Atom(x) => atom(x),
+        Operations::Pow(e, k) => {
+            assert!(*k > 0, "The exponent must be positive");
+            let e = evaluate_operations(e, atom);
+            (1..*k).fold(e.clone(), |acc, _| acc * e.clone())
+        }
+        Operations::Add(x, y) => evaluate_operations(x, atom) + evaluate_operations(y, atom),
+        Operations::Sub(x, y) => evaluate_operations(x, atom) - evaluate_operations(y, atom),
+        Operations::Mul(x, y) => evaluate_operations(x, atom) * evaluate_operations(y, atom),
+        Operations::Double(e) => {
+            let e = evaluate_operations(e, atom);
+            e.clone() + e
+        }
+        Operations::Square(e) => {
+            let e = evaluate_operations(e, atom);
+            e.clone() * e
+        }
+        Operations::Cache(_, e) => evaluate_operations(e, atom),
+        Operations::IfFeature(_, _, _) => panic!("Feature flags are not supported"),
+    }
+}
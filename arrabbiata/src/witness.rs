@@ -3,19 +3,26 @@ use ark_ff::PrimeField;
 use ark_poly::Evaluations;
 use kimchi::circuits::{domains::EvaluationDomains, gate::CurrOrNext};
 use log::{debug, info};
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
 use num_bigint::{BigInt, BigUint};
 use num_integer::Integer;
 use o1_utils::field_helpers::FieldHelpers;
-use poly_commitment::{commitment::CommitmentCurve, ipa::SRS, PolyComm, SRS as _};
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::time::Instant;
+use poly_commitment::{ipa::SRS, PolyComm, SRS as _};
+use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+use std::{sync::Arc, time::Instant};
+use strum::{EnumCount as _, IntoEnumIterator};
 
 use crate::{
     columns::{Column, Gadget},
-    interpreter::{Instruction, InterpreterEnv, Side},
-    poseidon_3_60_0_5_5_fp, poseidon_3_60_0_5_5_fq, MAXIMUM_FIELD_SIZE_IN_BITS, NUMBER_OF_COLUMNS,
-    NUMBER_OF_PUBLIC_INPUTS, NUMBER_OF_SELECTORS, NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO,
-    POSEIDON_ALPHA, POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE,
+    curve::ArrabbiataCurve,
+    interpreter::{Hint, Instruction, InterpreterEnv, Side},
+    logup::LookupTable,
+    public_io,
+    schedule::Schedule,
+    trace::Trace,
+    MAXIMUM_FIELD_SIZE_IN_BITS, MAX_NUMBER_OF_LOOKUPS_PER_ROW, NUMBER_OF_COLUMNS,
+    NUMBER_OF_LOOKUP_COLUMNS, NUMBER_OF_PUBLIC_INPUTS, NUMBER_OF_SELECTORS, POSEIDON_ALPHA,
+    POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE,
 };
 
 pub const IVC_STARTING_INSTRUCTION: Instruction = Instruction::Poseidon(0);
@@ -36,17 +43,32 @@ pub struct Env<
 > {
     // ----------------
     // Setup related (domains + SRS)
-    /// Domain for Fp
-    pub domain_fp: EvaluationDomains<Fp>,
-
-    /// Domain for Fq
-    pub domain_fq: EvaluationDomains<Fq>,
-
-    /// SRS for the first curve
-    pub srs_e1: SRS<E1>,
-
-    /// SRS for the second curve
-    pub srs_e2: SRS<E2>,
+    /// Domain for Fp.
+    /// It is `None` if the field does not contain a multiplicative subgroup of
+    /// the size of the SRS, like the base field of BN254 whose 2-adicity is 1.
+    /// In this case, the witness can be built, but the commitments to the
+    /// witness can not be computed.
+    // FIXME: a different commitment strategy is required for the second curve
+    // of the cycle BN254/Grumpkin.
+    pub domain_fp: Option<EvaluationDomains<Fp>>,
+
+    /// Domain for Fq. See [Env::domain_fp].
+    pub domain_fq: Option<EvaluationDomains<Fq>>,
+
+    /// SRS for the first curve.
+    /// The SRS is shared with the environments created by [Env::fork].
+    pub srs_e1: Arc<SRS<E1>>,
+
+    /// SRS for the second curve.
+    /// The SRS is shared with the environments created by [Env::fork].
+    pub srs_e2: Arc<SRS<E2>>,
+
+    /// The number of columns the circuit uses
+    pub number_of_columns: usize,
+
+    /// The number of fixed columns the circuit uses per row. It depends on
+    /// the number of columns, see [crate::number_of_fixed_columns].
+    pub number_of_fixed_columns: usize,
     // ----------------
 
     // ----------------
@@ -61,6 +83,13 @@ pub struct Env<
     /// Commitments to the previous instances
     pub previous_commitments_e1: Vec<PolyComm<E1>>,
     pub previous_commitments_e2: Vec<PolyComm<E2>>,
+
+    /// Commitments to the columns of the lookup argument of the previous
+    /// instances, except the tables which are fixed: the multiplicities first,
+    /// then the other columns of [Env::lookup_columns], see
+    /// [Env::compute_lookup_commitments].
+    pub previous_lookup_commitments_e1: Vec<PolyComm<E1>>,
+    pub previous_lookup_commitments_e2: Vec<PolyComm<E2>>,
     // ----------------
 
     // ----------------
@@ -77,20 +106,33 @@ pub struct Env<
     /// the position.
     pub idx_var_pi: usize,
 
+    /// The index of the latest allocated fixed column in the circuit.
+    pub idx_var_fixed: usize,
+
     /// Current processing row. Used to build the witness.
     pub current_row: usize,
 
-    /// State of the current row in the execution trace
-    pub state: [BigInt; NUMBER_OF_COLUMNS],
+    /// The row of the parent environment corresponding to the first row of
+    /// this environment. It is always `0`, except for the environments
+    /// created by [Env::fork].
+    pub row_offset: usize,
+
+    /// State of the current row in the execution trace.
+    /// The size of the vector is the number of columns.
+    pub state: Vec<BigInt>,
 
     /// Next row in the execution trace. It is useful when we deal with
     /// polynomials accessing "the next row", i.e. witness columns where we do
     /// evaluate at ζ and ζω.
-    pub next_state: [BigInt; NUMBER_OF_COLUMNS],
+    pub next_state: Vec<BigInt>,
 
     /// Contain the public state
     // FIXME: I don't like this design. Feel free to suggest a better solution
-    pub public_state: [BigInt; NUMBER_OF_PUBLIC_INPUTS],
+    pub public_state: Vec<BigInt>,
+
+    /// The values of the fixed columns for the current row. The fixed columns
+    /// are defined at setup time, see [Column::Fixed].
+    pub fixed_state: Vec<BigInt>,
 
     /// Selectors to activate the gadgets.
     /// The size of the outer vector must be equal to the number of gadgets in
@@ -121,12 +163,23 @@ pub struct Env<
     pub sponge_e1: [BigInt; POSEIDON_STATE_SIZE],
     pub sponge_e2: [BigInt; POSEIDON_STATE_SIZE],
 
+    /// The sponge state of the stream hash, loaded from the IVC state by the
+    /// gadget [crate::columns::Gadget::StreamHashState]. See
+    /// [run_stream_hash](crate::interpreter::run_stream_hash).
+    pub stream_hash_state: [BigInt; POSEIDON_STATE_SIZE],
+
     /// The current iteration of the IVC
     pub current_iteration: u64,
 
     /// A previous hash, encoded in 2 chunks of 128 bits.
+    /// It is the hash of the public IO of the previous iteration, which is an
+    /// element of the other field of the cycle, see [Env::compute_output].
     pub previous_hash: [u128; 2],
 
+    /// The hash of the public IO of the current iteration, written by the
+    /// gadget [crate::columns::Gadget::PublicIOHash].
+    pub public_io_hash: BigInt,
+
     /// The coin folding combiner will be used to generate the combinaison of
     /// folding instances
     pub r: BigInt,
@@ -147,8 +200,51 @@ pub struct Env<
     /// (x, y).
     pub temporary_accumulators: ((BigInt, BigInt), (BigInt, BigInt)),
 
+    /// The result of the scaling of the commitments to the previous witness
+    /// columns by the folding combiner `r`, in affine coordinates, for the
+    /// current iteration. The i-th element is set by the gadget
+    /// [crate::columns::Gadget::EllipticCurveScaling] when processing the last
+    /// bit of the scaling of the commitment `i`.
+    ///
+    /// As the accumulator of the double-and-add algorithm is initialized with
+    /// the blinder `H` of the SRS, the points are `r * C_i + H`.
+    pub scaled_commitments: Vec<(BigInt, BigInt)>,
+
     /// Index of the values to absorb in the sponge
     pub idx_values_to_absorb: usize,
+
+    /// The values of the stream to absorb in the sponge of the stream hash
+    /// during the current iteration. They are set by the application using
+    /// [Env::set_stream_values].
+    pub stream_values: Vec<BigInt>,
+
+    /// Index of the next value of the stream to absorb
+    pub idx_stream_values: usize,
+
+    /// The schedule followed by the iterations, if any. The lengths of the
+    /// phases are given to the gadget [crate::columns::Gadget::ProgramCounter].
+    pub schedule: Option<Schedule>,
+
+    /// The index of the latest lookup performed in the current row.
+    pub idx_lookup: usize,
+
+    /// The values looked up, for each row, or `None` if the row performs less
+    /// lookups.
+    /// The size of the outer vector is [MAX_NUMBER_OF_LOOKUPS_PER_ROW], and
+    /// the size of the inner vector is the number of rows in the circuit. The
+    /// same column/row layout than the witness is used.
+    pub lookup_values: Vec<Vec<Option<BigInt>>>,
+
+    /// The multiplicities of the entries of each table, indexed by
+    /// [LookupTable]. Multiplicities are added when folding.
+    pub lookup_multiplicities: Vec<Vec<u64>>,
+
+    /// The columns of the lookup argument, computed by
+    /// [Env::compute_lookup_columns] once all the rows have been built.
+    /// The size of the outer vector is [NUMBER_OF_LOOKUP_COLUMNS], using the
+    /// order given by the conversion of a [Column] into a `usize`, and the size
+    /// of the inner vector is the number of rows in the circuit.
+    pub lookup_columns: Vec<Vec<BigInt>>,
     // ----------------
     /// The witness of the current instance of the circuit.
     /// The size of the outer vector must be equal to the number of columns in the
@@ -162,11 +258,19 @@ pub struct Env<
 
     // --------------
     // Inputs
-    /// Initial input
-    pub z0: BigInt,
-
-    /// Current input
-    pub zi: BigInt,
+    /// Initial state of the IVC, `z_0`.
+    /// The size of the vector is the size of the state carried between
+    /// iterations.
+    pub z0: Vec<BigInt>,
+
+    /// State given as input to the current iteration, `z_i`.
+    pub zi: Vec<BigInt>,
+
+    /// State computed by the current iteration, `z_(i + 1)`. It is written by
+    /// the application using [InterpreterEnv::write_ivc_state], and becomes
+    /// the input of the next iteration after calling
+    /// [Env::compute_output].
+    pub next_zi: Vec<BigInt>,
     // ---------------
 
     // ---------------
@@ -184,8 +288,8 @@ pub struct Env<
 impl<
         Fp: PrimeField,
         Fq: PrimeField,
-        E1: CommitmentCurve<ScalarField = Fp, BaseField = Fq>,
-        E2: CommitmentCurve<ScalarField = Fq, BaseField = Fp>,
+        E1: ArrabbiataCurve<ScalarField = Fp, BaseField = Fq>,
+        E2: ArrabbiataCurve<ScalarField = Fq, BaseField = Fp>,
     > InterpreterEnv for Env<Fp, Fq, E1, E2>
 where
     <E1::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
@@ -201,15 +305,27 @@ where
     // for allocations.
     type Variable = BigInt;
 
+    fn number_of_columns(&self) -> usize {
+        self.number_of_columns
+    }
+
     fn allocate(&mut self) -> Self::Position {
-        assert!(self.idx_var < NUMBER_OF_COLUMNS, "Maximum number of columns reached ({NUMBER_OF_COLUMNS}), increase the number of columns");
+        assert!(
+            self.idx_var < self.number_of_columns,
+            "Maximum number of columns reached ({}), increase the number of columns",
+            self.number_of_columns
+        );
         let pos = Column::X(self.idx_var);
         self.idx_var += 1;
         (pos, CurrOrNext::Curr)
     }
 
     fn allocate_next_row(&mut self) -> Self::Position {
-        assert!(self.idx_var_next_row < NUMBER_OF_COLUMNS, "Maximum number of columns reached ({NUMBER_OF_COLUMNS}), increase the number of columns");
+        assert!(
+            self.idx_var_next_row < self.number_of_columns,
+            "Maximum number of columns reached ({}), increase the number of columns",
+            self.number_of_columns
+        );
         let pos = Column::X(self.idx_var_next_row);
         self.idx_var_next_row += 1;
         (pos, CurrOrNext::Next)
@@ -227,12 +343,26 @@ where
     }
 
     fn allocate_public_input(&mut self) -> Self::Position {
-        assert!(self.idx_var_pi < NUMBER_OF_PUBLIC_INPUTS, "Maximum number of public inputs reached ({NUMBER_OF_PUBLIC_INPUTS}), increase the number of public inputs");
+        assert!(
+            self.idx_var_pi < NUMBER_OF_PUBLIC_INPUTS,
+            "Maximum number of public inputs reached ({NUMBER_OF_PUBLIC_INPUTS})"
+        );
         let pos = Column::PublicInput(self.idx_var_pi);
         self.idx_var_pi += 1;
         (pos, CurrOrNext::Curr)
     }
 
+    fn allocate_fixed(&mut self) -> Self::Position {
+        assert!(
+            self.idx_var_fixed < self.number_of_fixed_columns,
+            "Maximum number of fixed columns reached ({}), increase the number of columns",
+            self.number_of_fixed_columns
+        );
+        let pos = Column::Fixed(self.idx_var_fixed);
+        self.idx_var_fixed += 1;
+        (pos, CurrOrNext::Curr)
+    }
+
     fn write_column(&mut self, pos: Self::Position, v: Self::Variable) -> Self::Variable {
         let (col, row) = pos;
         let Column::X(idx) = col else {
@@ -270,6 +400,21 @@ where
         v
     }
 
+    fn write_fixed(&mut self, pos: Self::Position, v: BigInt) -> Self::Variable {
+        let (col, _row) = pos;
+        let Column::Fixed(idx) = col else {
+            unimplemented!("Only works for fixed columns")
+        };
+        let modulus: BigInt = if self.current_iteration % 2 == 0 {
+            Fp::modulus_biguint().into()
+        } else {
+            Fq::modulus_biguint().into()
+        };
+        let v = v.mod_floor(&modulus);
+        self.fixed_state[idx] = v.clone();
+        v
+    }
+
     /// Activate the gadget for the current row
     fn activate_gadget(&mut self, gadget: Gadget) {
         // IMPROVEME: it should be called only once per row
@@ -286,6 +431,55 @@ where
         assert!(x == BigInt::from(0_usize) || x == BigInt::from(1_usize));
     }
 
+    fn range_check16(&mut self, pos: Self::Position) -> Self::Variable {
+        assert!(self.idx_lookup < MAX_NUMBER_OF_LOOKUPS_PER_ROW, "Maximum number of lookups reached ({MAX_NUMBER_OF_LOOKUPS_PER_ROW}), increase the number of lookups per row");
+        let table = LookupTable::RangeCheck16;
+        let v = self.read_position(pos);
+        let idx = table
+            .index_of(&v)
+            .unwrap_or_else(|| panic!("The value {v} is not in the table {table:?}"));
+        self.lookup_multiplicities[table as usize][idx] += 1;
+        self.lookup_values[self.idx_lookup][self.current_row] = Some(v.clone());
+        self.idx_lookup += 1;
+        v
+    }
+
+    fn read_ivc_state(&mut self, pos: Self::Position, i: usize) -> Self::Variable {
+        assert!(
+            i < self.zi.len(),
+            "The IVC state contains only {} elements",
+            self.zi.len()
+        );
+        self.write_column(pos, self.zi[i].clone())
+    }
+
+    fn write_ivc_state(
+        &mut self,
+        pos: Self::Position,
+        i: usize,
+        v: Self::Variable,
+    ) -> Self::Variable {
+        assert!(
+            i < self.next_zi.len(),
+            "The IVC state contains only {} elements",
+            self.next_zi.len()
+        );
+        // The value is reduced modulo the field of the current iteration
+        let v = self.write_column(pos, v);
+        // The output is absorbed in the public IO hash, see
+        // [Env::values_to_absorb_public_io], and becomes the input of the next
+        // iteration after calling [Env::compute_output].
+        self.next_zi[i] = v.clone();
+        v
+    }
+
+    fn write_public_io_hash(&mut self, v: Self::Variable) -> Self::Variable {
+        let pos = self.allocate_public_input();
+        let v = self.write_public_input(pos, v);
+        self.public_io_hash = v.clone();
+        v
+    }
+
     fn constant(&self, v: BigInt) -> Self::Variable {
         v
     }
@@ -295,11 +489,16 @@ where
     }
 
     fn assert_zero(&mut self, var: Self::Variable) {
-        assert_eq!(var, BigInt::from(0_usize));
+        let modulus: BigInt = if self.current_iteration % 2 == 0 {
+            Fp::modulus_biguint().into()
+        } else {
+            Fq::modulus_biguint().into()
+        };
+        assert_eq!(var.mod_floor(&modulus), BigInt::from(0_usize));
     }
 
     fn assert_equal(&mut self, x: Self::Variable, y: Self::Variable) {
-        assert_eq!(x, y);
+        self.assert_zero(x - y);
     }
 
     fn square(&mut self, pos: Self::Position, x: Self::Variable) -> Self::Variable {
@@ -316,26 +515,71 @@ where
         lowest_bit: u32,
         pos: Self::Position,
     ) -> Self::Variable {
-        let diff: u32 = highest_bit - lowest_bit;
-        if diff == 0 {
-            self.write_column(pos, BigInt::from(0_usize))
-        } else {
-            assert!(
-                diff > 0,
-                "The difference between the highest and lowest bit should be greater than 0"
-            );
-            let rht = (BigInt::from(1_usize) << diff) - BigInt::from(1_usize);
-            let lft = x >> lowest_bit;
-            let res: BigInt = lft & rht;
-            self.write_column(pos, res)
+        let hint = Hint::Bitmask {
+            value: x.clone(),
+            highest_bit,
+            lowest_bit,
+        };
+        self.compute_hint(pos, hint)
+    }
+
+    unsafe fn compute_hint(
+        &mut self,
+        pos: Self::Position,
+        hint: Hint<Self::Variable>,
+    ) -> Self::Variable {
+        // Compute the hint in the field of the current iteration
+        fn compute<F: PrimeField>(hint: Hint<BigInt>) -> BigInt {
+            let to_field = |x: BigInt| -> F {
+                let modulus: BigInt = F::modulus_biguint().into();
+                F::from_biguint(&x.mod_floor(&modulus).to_biguint().unwrap()).unwrap()
+            };
+            match hint {
+                Hint::Inverse(x) => to_field(x)
+                    .inverse()
+                    .expect("Zero has no inverse")
+                    .to_biguint()
+                    .into(),
+                Hint::InverseOrZero(x) => to_field(x)
+                    .inverse()
+                    .unwrap_or(F::zero())
+                    .to_biguint()
+                    .into(),
+                Hint::IsZero(x) => BigInt::from(u64::from(to_field(x).is_zero())),
+                Hint::SquareRoot(x) => to_field(x)
+                    .sqrt()
+                    .expect("The value is not a quadratic residue")
+                    .to_biguint()
+                    .into(),
+                Hint::Bitmask {
+                    value,
+                    highest_bit,
+                    lowest_bit,
+                } => {
+                    assert!(
+                        highest_bit >= lowest_bit,
+                        "The highest bit should be greater than or equal to the lowest bit"
+                    );
+                    let diff: u32 = highest_bit - lowest_bit;
+                    let rht = (BigInt::from(1_usize) << diff) - BigInt::from(1_usize);
+                    let lft = value >> lowest_bit;
+                    lft & rht
+                }
+            }
         }
+        let res = if self.current_iteration % 2 == 0 {
+            compute::<Fp>(hint)
+        } else {
+            compute::<Fq>(hint)
+        };
+        self.write_column(pos, res)
     }
 
     // FIXME: for now, we use the row number and compute the square.
     // This is only for testing purposes, and having something to build the
     // witness.
     fn fetch_input(&mut self, pos: Self::Position) -> Self::Variable {
-        let x = BigInt::from(self.current_row as u64);
+        let x = BigInt::from((self.row_offset + self.current_row) as u64);
         self.write_column(pos, x.clone());
         x
     }
@@ -353,10 +597,12 @@ where
         self.idx_var = 0;
         self.idx_var_next_row = 0;
         self.idx_var_pi = 0;
+        self.idx_var_fixed = 0;
+        self.idx_lookup = 0;
         // We keep track of the values we already set.
         self.state = self.next_state.clone();
         // And we reset the next state
-        self.next_state = std::array::from_fn(|_| BigInt::from(0_usize));
+        self.next_state = vec![BigInt::from(0_usize); self.number_of_columns];
     }
 
     /// FIXME: check if we need to pick the left or right sponge
@@ -391,26 +637,22 @@ where
         i: usize,
     ) -> Self::Variable {
         let rc = if self.current_iteration % 2 == 0 {
-            poseidon_3_60_0_5_5_fp::static_params().round_constants[round][i]
+            E1::sponge_params().round_constants[round][i]
                 .to_biguint()
                 .into()
         } else {
-            poseidon_3_60_0_5_5_fq::static_params().round_constants[round][i]
+            E2::sponge_params().round_constants[round][i]
                 .to_biguint()
                 .into()
         };
-        self.write_public_input(pos, rc)
+        self.write_fixed(pos, rc)
     }
 
     fn get_poseidon_mds_matrix(&mut self, i: usize, j: usize) -> Self::Variable {
         if self.current_iteration % 2 == 0 {
-            poseidon_3_60_0_5_5_fp::static_params().mds[i][j]
-                .to_biguint()
-                .into()
+            E1::sponge_params().mds[i][j].to_biguint().into()
         } else {
-            poseidon_3_60_0_5_5_fq::static_params().mds[i][j]
-                .to_biguint()
-                .into()
+            E2::sponge_params().mds[i][j].to_biguint().into()
         }
     }
 
@@ -424,65 +666,80 @@ where
         }
     }
 
-    // The following values are expected to be absorbed in order:
-    // - z0
-    // - z1
-    // - acc[0]
-    // - acc[1]
-    // - ...
-    // - acc[N_COL - 1]
-    // FIXME: for now, we will only absorb the accumulators as z0 and z1 are not
-    // updated yet.
+    // The values are absorbed in the order given by
+    // [Env::values_to_absorb_public_io]. When all the values have been
+    // absorbed, zero is absorbed.
     unsafe fn fetch_value_to_absorb(
         &mut self,
         pos: Self::Position,
         curr_round: usize,
     ) -> Self::Variable {
         let (col, _) = pos;
-        let Column::PublicInput(_idx) = col else {
-            panic!("Only works for public inputs")
+        let Column::X(_idx) = col else {
+            panic!("Only works for private inputs")
         };
         // If we are not the round 0, we must absorb nothing.
         if curr_round != 0 {
-            self.write_public_input(pos, self.zero())
+            self.write_column(pos, self.zero())
         } else {
-            // FIXME: we must absorb z0, z1 and i!
-            // We multiply by 2 as we have two coordinates
             let idx = self.idx_values_to_absorb;
-            let res = if idx < 2 * NUMBER_OF_COLUMNS {
-                let idx_col = idx / 2;
-                debug!("Absorbing the accumulator for the column index {idx_col}. After this, there will still be {} elements to absorb", NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO - idx - 1);
-                if self.current_iteration % 2 == 0 {
-                    let (pt_x, pt_y) = self.ivc_accumulator_e2[idx_col]
-                        .get_first_chunk()
-                        .to_coordinates()
-                        .unwrap();
-                    if idx % 2 == 0 {
-                        self.write_public_input(pos, pt_x.to_biguint().into())
-                    } else {
-                        self.write_public_input(pos, pt_y.to_biguint().into())
-                    }
-                } else {
-                    let (pt_x, pt_y) = self.ivc_accumulator_e1[idx_col]
-                        .get_first_chunk()
-                        .to_coordinates()
-                        .unwrap();
-                    if idx % 2 == 0 {
-                        self.write_public_input(pos, pt_x.to_biguint().into())
-                    } else {
-                        self.write_public_input(pos, pt_y.to_biguint().into())
-                    }
-                }
+            let values = self.values_to_absorb_public_io();
+            let res = if idx < values.len() {
+                debug!("Absorbing the value {idx} of the public IO. After this, there will still be {} elements to absorb", values.len() - idx - 1);
+                self.write_column(pos, values[idx].clone())
             } else {
-                unimplemented!(
-                    "We only absorb the accumulators for now. Of course, this is not sound."
-                )
+                self.write_column(pos, self.zero())
             };
             self.idx_values_to_absorb += 1;
             res
         }
     }
 
+    fn get_phase_length(&mut self, pos: Self::Position, phase: usize) -> Self::Variable {
+        let length = self
+            .schedule
+            .as_ref()
+            .map(|schedule| schedule.number_of_steps(phase))
+            .unwrap_or(0);
+        self.write_fixed(pos, BigInt::from(length))
+    }
+
+    fn load_stream_hash_state(&mut self, pos: Self::Position, i: usize) -> Self::Variable {
+        let state = self.stream_hash_state[i].clone();
+        self.write_column(pos, state)
+    }
+
+    unsafe fn save_stream_hash_state(&mut self, v: Self::Variable, i: usize) {
+        let modulus: BigInt = if self.current_iteration % 2 == 0 {
+            Fp::modulus_biguint().into()
+        } else {
+            Fq::modulus_biguint().into()
+        };
+        self.stream_hash_state[i] = v.mod_floor(&modulus)
+    }
+
+    // The values are absorbed in the order given by [Env::set_stream_values].
+    // When all the values have been absorbed, zero is absorbed.
+    unsafe fn fetch_stream_value_to_absorb(
+        &mut self,
+        pos: Self::Position,
+        curr_round: usize,
+    ) -> Self::Variable {
+        if curr_round != 0 {
+            self.write_column(pos, self.zero())
+        } else {
+            let idx = self.idx_stream_values;
+            let res = if idx < self.stream_values.len() {
+                debug!("Absorbing the value {idx} of the stream");
+                self.write_column(pos, self.stream_values[idx].clone())
+            } else {
+                self.write_column(pos, self.zero())
+            };
+            self.idx_stream_values += 1;
+            res
+        }
+    }
+
     unsafe fn load_temporary_accumulators(
         &mut self,
         pos_x: Self::Position,
@@ -544,7 +801,7 @@ where
                                 let pt = self.srs_e1.h;
                                 let (pt_x, pt_y) = pt.to_coordinates().unwrap();
                                 let pt_x = self.write_column(pos_x, pt_x.to_biguint().into());
-                                let pt_y = self.write_column(pos_x, pt_y.to_biguint().into());
+                                let pt_y = self.write_column(pos_y, pt_y.to_biguint().into());
                                 (pt_x, pt_y)
                             }
                         }
@@ -603,6 +860,15 @@ where
         }
     }
 
+    unsafe fn save_scaled_commitment(
+        &mut self,
+        i_comm: usize,
+        x: Self::Variable,
+        y: Self::Variable,
+    ) {
+        self.scaled_commitments[i_comm] = (x, y);
+    }
+
     // It is unsafe as no constraint is added
     unsafe fn is_same_ec_point(
         &mut self,
@@ -634,22 +900,7 @@ where
     ///
     /// Zero is not allowed as an input.
     unsafe fn inverse(&mut self, pos: Self::Position, x: Self::Variable) -> Self::Variable {
-        let res = if self.current_iteration % 2 == 0 {
-            Fp::from_biguint(&x.to_biguint().unwrap())
-                .unwrap()
-                .inverse()
-                .unwrap()
-                .to_biguint()
-                .into()
-        } else {
-            Fq::from_biguint(&x.to_biguint().unwrap())
-                .unwrap()
-                .inverse()
-                .unwrap()
-                .to_biguint()
-                .into()
-        };
-        self.write_column(pos, res)
+        self.compute_hint(pos, Hint::Inverse(x))
     }
 
     fn compute_lambda(
@@ -755,16 +1006,31 @@ where
 impl<
         Fp: PrimeField,
         Fq: PrimeField,
-        E1: CommitmentCurve<ScalarField = Fp, BaseField = Fq>,
-        E2: CommitmentCurve<ScalarField = Fq, BaseField = Fp>,
+        E1: ArrabbiataCurve<ScalarField = Fp, BaseField = Fq>,
+        E2: ArrabbiataCurve<ScalarField = Fq, BaseField = Fp>,
     > Env<Fp, Fq, E1, E2>
 {
     pub fn new(
         srs_log2_size: usize,
-        z0: BigInt,
+        z0: Vec<BigInt>,
+        sponge_e1: [BigInt; 3],
+        sponge_e2: [BigInt; 3],
+    ) -> Self {
+        Self::new_with_number_of_columns(srs_log2_size, z0, sponge_e1, sponge_e2, NUMBER_OF_COLUMNS)
+    }
+
+    /// Create a new environment building the witness of a circuit using
+    /// `number_of_columns` columns. The layout of the gadgets adapts to the
+    /// number of columns, see [crate::check_number_of_columns] for the
+    /// requirements.
+    pub fn new_with_number_of_columns(
+        srs_log2_size: usize,
+        z0: Vec<BigInt>,
         sponge_e1: [BigInt; 3],
         sponge_e2: [BigInt; 3],
+        number_of_columns: usize,
     ) -> Self {
+        crate::check_number_of_columns(number_of_columns);
         {
             assert!(Fp::MODULUS_BIT_SIZE <= MAXIMUM_FIELD_SIZE_IN_BITS.try_into().unwrap(), "The size of the field Fp is too large, it should be less than {MAXIMUM_FIELD_SIZE_IN_BITS}");
             assert!(Fq::MODULUS_BIT_SIZE <= MAXIMUM_FIELD_SIZE_IN_BITS.try_into().unwrap(), "The size of the field Fq is too large, it should be less than {MAXIMUM_FIELD_SIZE_IN_BITS}");
@@ -782,17 +1048,19 @@ impl<
             );
         }
         let srs_size = 1 << srs_log2_size;
-        let domain_fp = EvaluationDomains::<Fp>::create(srs_size).unwrap();
-        let domain_fq = EvaluationDomains::<Fq>::create(srs_size).unwrap();
+        let domain_fp = EvaluationDomains::<Fp>::create(srs_size).ok();
+        let domain_fq = EvaluationDomains::<Fq>::create(srs_size).ok();
 
         info!("Create an SRS of size {srs_log2_size} for the first curve");
         let srs_e1: SRS<E1> = {
             let start = Instant::now();
             let srs = SRS::create(srs_size);
             debug!("SRS for E1 created in {:?}", start.elapsed());
-            let start = Instant::now();
-            srs.get_lagrange_basis(domain_fp.d1);
-            debug!("Lagrange basis for E1 added in {:?}", start.elapsed());
+            if let Some(domain) = domain_fp {
+                let start = Instant::now();
+                srs.get_lagrange_basis(domain.d1);
+                debug!("Lagrange basis for E1 added in {:?}", start.elapsed());
+            }
             srs
         };
         info!("Create an SRS of size {srs_log2_size} for the second curve");
@@ -800,17 +1068,19 @@ impl<
             let start = Instant::now();
             let srs = SRS::create(srs_size);
             debug!("SRS for E2 created in {:?}", start.elapsed());
-            let start = Instant::now();
-            srs.get_lagrange_basis(domain_fq.d1);
-            debug!("Lagrange basis for E2 added in {:?}", start.elapsed());
+            if let Some(domain) = domain_fq {
+                let start = Instant::now();
+                srs.get_lagrange_basis(domain.d1);
+                debug!("Lagrange basis for E2 added in {:?}", start.elapsed());
+            }
             srs
         };
 
-        let mut witness: Vec<Vec<BigInt>> = Vec::with_capacity(NUMBER_OF_COLUMNS);
+        let mut witness: Vec<Vec<BigInt>> = Vec::with_capacity(number_of_columns);
         {
             let mut vec: Vec<BigInt> = Vec::with_capacity(srs_size);
             (0..srs_size).for_each(|_| vec.push(BigInt::from(0_usize)));
-            (0..NUMBER_OF_COLUMNS).for_each(|_| witness.push(vec.clone()));
+            (0..number_of_columns).for_each(|_| witness.push(vec.clone()));
         };
 
         let mut selectors: Vec<Vec<bool>> = Vec::with_capacity(NUMBER_OF_SELECTORS);
@@ -821,30 +1091,49 @@ impl<
         };
 
         // Default set to the blinders. Using double to make the EC scaling happy.
-        let previous_commitments_e1: Vec<PolyComm<E1>> = (0..NUMBER_OF_COLUMNS)
+        let previous_commitments_e1: Vec<PolyComm<E1>> = (0..number_of_columns)
             .map(|_| PolyComm::new(vec![(srs_e1.h + srs_e1.h).into()]))
             .collect();
-        let previous_commitments_e2: Vec<PolyComm<E2>> = (0..NUMBER_OF_COLUMNS)
+        let previous_commitments_e2: Vec<PolyComm<E2>> = (0..number_of_columns)
             .map(|_| PolyComm::new(vec![(srs_e2.h + srs_e2.h).into()]))
             .collect();
         // FIXME: zero will not work.
-        let ivc_accumulator_e1: Vec<PolyComm<E1>> = (0..NUMBER_OF_COLUMNS)
+        let ivc_accumulator_e1: Vec<PolyComm<E1>> = (0..number_of_columns)
             .map(|_| PolyComm::new(vec![srs_e1.h]))
             .collect();
-        let ivc_accumulator_e2: Vec<PolyComm<E2>> = (0..NUMBER_OF_COLUMNS)
+        let ivc_accumulator_e2: Vec<PolyComm<E2>> = (0..number_of_columns)
             .map(|_| PolyComm::new(vec![srs_e2.h]))
             .collect();
 
         // FIXME: challenges
         let challenges: Vec<BigInt> = vec![];
 
+        let lookup_values: Vec<Vec<Option<BigInt>>> = (0..MAX_NUMBER_OF_LOOKUPS_PER_ROW)
+            .map(|_| vec![None; srs_size])
+            .collect();
+        let lookup_multiplicities: Vec<Vec<u64>> = LookupTable::iter()
+            .map(|table| vec![0; table.length()])
+            .collect();
+        let lookup_columns: Vec<Vec<BigInt>> = (0..NUMBER_OF_LOOKUP_COLUMNS)
+            .map(|_| vec![BigInt::from(0_usize); srs_size])
+            .collect();
+        let number_of_lookup_commitments = NUMBER_OF_LOOKUP_COLUMNS - LookupTable::COUNT;
+        let previous_lookup_commitments_e1: Vec<PolyComm<E1>> = (0..number_of_lookup_commitments)
+            .map(|_| PolyComm::new(vec![(srs_e1.h + srs_e1.h).into()]))
+            .collect();
+        let previous_lookup_commitments_e2: Vec<PolyComm<E2>> = (0..number_of_lookup_commitments)
+            .map(|_| PolyComm::new(vec![(srs_e2.h + srs_e2.h).into()]))
+            .collect();
+
         Self {
             // -------
             // Setup
             domain_fp,
             domain_fq,
-            srs_e1,
-            srs_e2,
+            srs_e1: Arc::new(srs_e1),
+            srs_e2: Arc::new(srs_e2),
+            number_of_columns,
+            number_of_fixed_columns: crate::number_of_fixed_columns(number_of_columns),
             // -------
             // -------
             // IVC only
@@ -852,29 +1141,47 @@ impl<
             ivc_accumulator_e2,
             previous_commitments_e1,
             previous_commitments_e2,
+            previous_lookup_commitments_e1,
+            previous_lookup_commitments_e2,
             // ------
             // ------
             idx_var: 0,
             idx_var_next_row: 0,
             idx_var_pi: 0,
+            idx_var_fixed: 0,
             current_row: 0,
-            state: std::array::from_fn(|_| BigInt::from(0_usize)),
-            next_state: std::array::from_fn(|_| BigInt::from(0_usize)),
-            public_state: std::array::from_fn(|_| BigInt::from(0_usize)),
+            row_offset: 0,
+            state: vec![BigInt::from(0_usize); number_of_columns],
+            next_state: vec![BigInt::from(0_usize); number_of_columns],
+            public_state: vec![BigInt::from(0_usize); NUMBER_OF_PUBLIC_INPUTS],
+            fixed_state: vec![
+                BigInt::from(0_usize);
+                crate::number_of_fixed_columns(number_of_columns)
+            ],
             selectors,
             challenges,
             current_instruction: IVC_STARTING_INSTRUCTION,
             sponge_e1,
             sponge_e2,
+            stream_hash_state: std::array::from_fn(|_| BigInt::from(0_usize)),
             current_iteration: 0,
             previous_hash: [0; 2],
+            public_io_hash: BigInt::from(0_usize),
             r: BigInt::from(0_usize),
             // Initialize the temporary accumulators with 0
             temporary_accumulators: (
                 (BigInt::from(0_u64), BigInt::from(0_u64)),
                 (BigInt::from(0_u64), BigInt::from(0_u64)),
             ),
+            scaled_commitments: vec![(BigInt::from(0_u64), BigInt::from(0_u64)); number_of_columns],
             idx_values_to_absorb: 0,
+            stream_values: vec![],
+            idx_stream_values: 0,
+            schedule: None,
+            idx_lookup: 0,
+            lookup_values,
+            lookup_multiplicities,
+            lookup_columns,
             // ------
             // ------
             // Used by the interpreter
@@ -884,20 +1191,174 @@ impl<
             // ------
             // Inputs
             z0: z0.clone(),
-            zi: z0,
+            zi: z0.clone(),
+            next_zi: z0,
             // ------
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// Create a new environment to build `nb_rows` rows of the witness,
+    /// starting at the current row, independently of this environment.
+    ///
+    /// The setup (SRS and domains), the accumulators and the state of the IVC
+    /// are shared with the parent environment. The witness, the selectors and
+    /// the lookups are local to the new environment and must be merged back
+    /// into the parent environment using [Env::merge].
+    ///
+    /// The changes made to the sponges and to the temporary accumulators in
+    /// the forked environment are not propagated to the parent environment.
+    /// Therefore, only blocks of rows that do not depend on each other, like
+    /// the rows of the application, should be built in a forked environment.
+    pub fn fork(&self, nb_rows: usize) -> Self {
+        Self {
+            domain_fp: self.domain_fp,
+            domain_fq: self.domain_fq,
+            srs_e1: self.srs_e1.clone(),
+            srs_e2: self.srs_e2.clone(),
+            number_of_columns: self.number_of_columns,
+            number_of_fixed_columns: self.number_of_fixed_columns,
+            ivc_accumulator_e1: self.ivc_accumulator_e1.clone(),
+            ivc_accumulator_e2: self.ivc_accumulator_e2.clone(),
+            previous_commitments_e1: self.previous_commitments_e1.clone(),
+            previous_commitments_e2: self.previous_commitments_e2.clone(),
+            previous_lookup_commitments_e1: self.previous_lookup_commitments_e1.clone(),
+            previous_lookup_commitments_e2: self.previous_lookup_commitments_e2.clone(),
+            idx_var: 0,
+            idx_var_next_row: 0,
+            idx_var_pi: 0,
+            idx_var_fixed: 0,
+            current_row: 0,
+            row_offset: self.row_offset + self.current_row,
+            state: vec![BigInt::from(0_usize); self.number_of_columns],
+            next_state: vec![BigInt::from(0_usize); self.number_of_columns],
+            public_state: vec![BigInt::from(0_usize); NUMBER_OF_PUBLIC_INPUTS],
+            fixed_state: vec![BigInt::from(0_usize); self.number_of_fixed_columns],
+            selectors: (0..NUMBER_OF_SELECTORS)
+                .map(|_| vec![false; nb_rows])
+                .collect(),
+            challenges: self.challenges.clone(),
+            current_instruction: self.current_instruction,
+            sponge_e1: self.sponge_e1.clone(),
+            sponge_e2: self.sponge_e2.clone(),
+            stream_hash_state: self.stream_hash_state.clone(),
+            current_iteration: self.current_iteration,
+            previous_hash: self.previous_hash,
+            public_io_hash: self.public_io_hash.clone(),
+            r: self.r.clone(),
+            temporary_accumulators: self.temporary_accumulators.clone(),
+            scaled_commitments: self.scaled_commitments.clone(),
+            idx_values_to_absorb: self.idx_values_to_absorb,
+            stream_values: self.stream_values.clone(),
+            idx_stream_values: self.idx_stream_values,
+            schedule: self.schedule.clone(),
+            idx_lookup: 0,
+            lookup_values: (0..MAX_NUMBER_OF_LOOKUPS_PER_ROW)
+                .map(|_| vec![None; nb_rows])
+                .collect(),
+            lookup_multiplicities: LookupTable::iter()
+                .map(|table| vec![0; table.length()])
+                .collect(),
+            // The columns of the lookup argument are computed by the parent
+            // environment, once all the rows have been merged.
+            lookup_columns: vec![],
+            witness: (0..self.number_of_columns)
+                .map(|_| vec![BigInt::from(0_usize); nb_rows])
+                .collect(),
+            z0: self.z0.clone(),
+            zi: self.zi.clone(),
+            next_zi: self.next_zi.clone(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Merge the witness, the selectors and the lookups built by an
+    /// environment created with [Env::fork] into this environment.
+    /// The rows are copied at the position the forked environment has been
+    /// created at.
+    pub fn merge(&mut self, other: Self) {
+        let start = other.row_offset - self.row_offset;
+        let nb_rows = other.current_row;
+        self.witness
+            .iter_mut()
+            .zip(other.witness)
+            .for_each(|(col, other_col)| {
+                col[start..start + nb_rows].clone_from_slice(&other_col[0..nb_rows])
+            });
+        self.selectors
+            .iter_mut()
+            .zip(other.selectors)
+            .for_each(|(col, other_col)| {
+                col[start..start + nb_rows].copy_from_slice(&other_col[0..nb_rows])
+            });
+        self.lookup_values
+            .iter_mut()
+            .zip(other.lookup_values)
+            .for_each(|(col, other_col)| {
+                col[start..start + nb_rows].clone_from_slice(&other_col[0..nb_rows])
+            });
+        self.lookup_multiplicities
+            .iter_mut()
+            .zip(other.lookup_multiplicities)
+            .for_each(|(m, other_m)| m.iter_mut().zip(other_m).for_each(|(x, y)| *x += y));
+    }
+
+    /// Build the given blocks of rows in parallel, starting at the current
+    /// row. The i-th block contains `block_sizes[i]` rows, and is built by
+    /// calling `run_block` on an environment created with [Env::fork], with the
+    /// number of rows of the block as second parameter. The function
+    /// `run_block` must build exactly the number of rows of the block, i.e.
+    /// call [InterpreterEnv::reset] once per row.
+    ///
+    /// The blocks must be independent, see [Env::fork]. At the end, the current
+    /// row is moved after the last block.
+    pub fn build_blocks_in_parallel<F>(&mut self, block_sizes: &[usize], run_block: F)
+    where
+        F: Fn(&mut Self, usize) + Sync,
+    {
+        let mut forks: Vec<Self> = Vec::with_capacity(block_sizes.len());
+        let mut nb_rows = 0;
+        block_sizes.iter().for_each(|block_size| {
+            let mut fork = self.fork(*block_size);
+            fork.row_offset += nb_rows;
+            forks.push(fork);
+            nb_rows += block_size;
+        });
+        forks.par_iter_mut().for_each(|fork| {
+            let block_size = fork.witness[0].len();
+            run_block(fork, block_size);
+        });
+        forks.into_iter().for_each(|fork| {
+            assert_eq!(
+                fork.current_row,
+                fork.witness[0].len(),
+                "The block must build exactly the number of rows requested"
+            );
+            self.merge(fork)
+        });
+        self.current_row += nb_rows;
+    }
+
     /// Reset the environment to build the next iteration
     pub fn reset_for_next_iteration(&mut self) {
         // Rest the state for the next row
         self.current_row = 0;
-        self.state = std::array::from_fn(|_| BigInt::from(0_usize));
+        self.state = vec![BigInt::from(0_usize); self.number_of_columns];
         self.idx_var = 0;
         self.current_instruction = IVC_STARTING_INSTRUCTION;
         self.idx_values_to_absorb = 0;
+        self.stream_values = vec![];
+        self.idx_stream_values = 0;
+        self.idx_lookup = 0;
+        self.scaled_commitments
+            .iter_mut()
+            .for_each(|pt| *pt = (BigInt::from(0_u64), BigInt::from(0_u64)));
+        self.lookup_values
+            .iter_mut()
+            .for_each(|col| col.iter_mut().for_each(|v| *v = None));
+        self.lookup_multiplicities
+            .iter_mut()
+            .for_each(|m| m.iter_mut().for_each(|v| *v = 0));
     }
 
     /// The blinder used to commit, to avoid committing to the zero polynomial
@@ -908,48 +1369,344 @@ impl<
         // TODO
     }
 
-    /// Compute the commitments to the current witness, and update the previous
-    /// instances.
+    /// Compute the commitments to the current witness and to the
+    /// multiplicities of the lookup tables, and update the previous instances.
+    ///
+    /// The commitments to the multiplicities are saved as the first
+    /// commitments of [Env::previous_lookup_commitments_e1] (resp.
+    /// [Env::previous_lookup_commitments_e2]). The commitments to the other
+    /// columns of the lookup argument are computed by
+    /// [Env::compute_lookup_commitments], once the challenge β has been coined.
     // Might be worth renaming this function
     pub fn compute_and_update_previous_commitments(&mut self) {
+        let multiplicities = self.lookup_multiplicity_columns();
+        let columns: Vec<&Vec<BigInt>> = self.witness.iter().chain(multiplicities.iter()).collect();
         if self.current_iteration % 2 == 0 {
-            let comms: Vec<PolyComm<E1>> = self
-                .witness
-                .par_iter()
-                .map(|evals| {
-                    let evals: Vec<Fp> = evals
-                        .par_iter()
-                        .map(|x| Fp::from_biguint(&x.to_biguint().unwrap()).unwrap())
-                        .collect();
-                    let evals = Evaluations::from_vec_and_domain(evals.to_vec(), self.domain_fp.d1);
-                    self.srs_e1
-                        .commit_evaluations_non_hiding(self.domain_fp.d1, &evals)
+            let domain = self
+                .domain_fp
+                .expect("The commitments can not be computed without a domain for Fp");
+            let mut comms = commit_columns(&self.srs_e1, domain, &columns);
+            self.previous_lookup_commitments_e1 = comms.split_off(self.number_of_columns);
+            self.previous_commitments_e1 = comms
+        } else {
+            let domain = self
+                .domain_fq
+                .expect("The commitments can not be computed without a domain for Fq");
+            let mut comms = commit_columns(&self.srs_e2, domain, &columns);
+            self.previous_lookup_commitments_e2 = comms.split_off(self.number_of_columns);
+            self.previous_commitments_e2 = comms
+        }
+    }
+
+    /// Coin the challenge β of the lookup argument, after absorbing the
+    /// commitments to the witness and to the multiplicities computed by
+    /// [Env::compute_and_update_previous_commitments].
+    ///
+    /// The coordinates of the commitments are absorbed, using
+    /// [public_io::absorb], into a copy of the sponge state over the base field
+    /// of the curve used in the current iteration, i.e. the sponge of the other
+    /// curve. The challenge is the 128 low bits of the first element of the
+    /// resulting state, to be an element of both fields of the cycle.
+    pub fn coin_lookup_challenge(&self) -> BigInt {
+        let number_of_multiplicities = LookupTable::COUNT;
+        if self.current_iteration % 2 == 0 {
+            let commitments = self
+                .previous_commitments_e1
+                .iter()
+                .chain(&self.previous_lookup_commitments_e1[..number_of_multiplicities]);
+            squeeze_lookup_challenge(
+                E1::other_curve_sponge_params(),
+                &self.sponge_e2,
+                commitments,
+            )
+        } else {
+            let commitments = self
+                .previous_commitments_e2
+                .iter()
+                .chain(&self.previous_lookup_commitments_e2[..number_of_multiplicities]);
+            squeeze_lookup_challenge(
+                E2::other_curve_sponge_params(),
+                &self.sponge_e1,
+                commitments,
+            )
+        }
+    }
+
+    /// Compute the commitments to the columns of the lookup argument computed
+    /// by [Env::compute_lookup_columns], except the multiplicities, already
+    /// committed by [Env::compute_and_update_previous_commitments], and the
+    /// tables, which are fixed. The commitments are appended to
+    /// [Env::previous_lookup_commitments_e1] (resp.
+    /// [Env::previous_lookup_commitments_e2]), in the order of the columns.
+    pub fn compute_lookup_commitments(&mut self) {
+        let first_column = usize::from(Column::LookupInverse(0));
+        let committed: Vec<usize> = LookupTable::iter()
+            .flat_map(|table| {
+                [
+                    Column::LookupMultiplicity(table),
+                    Column::LookupTable(table),
+                ]
+            })
+            .map(|col| usize::from(col) - first_column)
+            .collect();
+        let columns: Vec<&Vec<BigInt>> = self
+            .lookup_columns
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !committed.contains(i))
+            .map(|(_, col)| col)
+            .collect();
+        let number_of_multiplicities = LookupTable::COUNT;
+        if self.current_iteration % 2 == 0 {
+            let domain = self
+                .domain_fp
+                .expect("The commitments can not be computed without a domain for Fp");
+            let comms = commit_columns(&self.srs_e1, domain, &columns);
+            self.previous_lookup_commitments_e1
+                .truncate(number_of_multiplicities);
+            self.previous_lookup_commitments_e1.extend(comms)
+        } else {
+            let domain = self
+                .domain_fq
+                .expect("The commitments can not be computed without a domain for Fq");
+            let comms = commit_columns(&self.srs_e2, domain, &columns);
+            self.previous_lookup_commitments_e2
+                .truncate(number_of_multiplicities);
+            self.previous_lookup_commitments_e2.extend(comms)
+        }
+    }
+
+    /// Return the multiplicities of the entries of each table, over the whole
+    /// domain. The rows after the last entry of a table have a multiplicity of
+    /// zero.
+    fn lookup_multiplicity_columns(&self) -> Vec<Vec<BigInt>> {
+        let domain_size = self.witness[0].len();
+        LookupTable::iter()
+            .map(|table| {
+                let multiplicities = &self.lookup_multiplicities[table as usize];
+                (0..domain_size)
+                    .map(|i| BigInt::from(multiplicities.get(i).copied().unwrap_or(0)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Compute the columns of the lookup argument for the rows built during
+    /// the current iteration, using the challenge `beta`, and save them in
+    /// [Env::lookup_columns]. See the [logup](crate::logup) module for a
+    /// description of the columns.
+    ///
+    /// The tables are written over the whole domain, which must therefore
+    /// contain at least as many rows as the largest table.
+    pub fn compute_lookup_columns(&mut self, beta: BigInt) {
+        let columns: Vec<Vec<BigInt>> = if self.current_iteration % 2 == 0 {
+            let beta = Fp::from_biguint(&beta.to_biguint().unwrap()).unwrap();
+            self.lookup_columns_in_field(beta)
+                .into_iter()
+                .map(|col| col.into_iter().map(|x| x.to_biguint().into()).collect())
+                .collect()
+        } else {
+            let beta = Fq::from_biguint(&beta.to_biguint().unwrap()).unwrap();
+            self.lookup_columns_in_field(beta)
+                .into_iter()
+                .map(|col| col.into_iter().map(|x| x.to_biguint().into()).collect())
+                .collect()
+        };
+        self.lookup_columns = columns;
+    }
+
+    fn lookup_columns_in_field<F: PrimeField>(&self, beta: F) -> Vec<Vec<F>> {
+        let domain_size = self.witness[0].len();
+        let first_column = usize::from(Column::LookupInverse(0));
+        let idx = |col: Column| usize::from(col) - first_column;
+        let mut columns: Vec<Vec<F>> = (0..NUMBER_OF_LOOKUP_COLUMNS)
+            .map(|_| vec![F::zero(); domain_size])
+            .collect();
+
+        // φ_i = 1 / (β + f_i), or zero if the row performs less lookups
+        self.lookup_values
+            .iter()
+            .enumerate()
+            .for_each(|(i, values)| {
+                let phi = &mut columns[idx(Column::LookupInverse(i))];
+                values.iter().zip(phi.iter_mut()).for_each(|(v, phi)| {
+                    if let Some(v) = v {
+                        *phi = beta + F::from_biguint(&v.to_biguint().unwrap()).unwrap();
+                    }
+                });
+                ark_ff::batch_inversion(phi);
+            });
+
+        // t, m and ψ = m / (β + t), for each table
+        LookupTable::iter().for_each(|table| {
+            assert!(
+                table.length() <= domain_size,
+                "The table {table:?} contains {} entries, but the domain only contains {domain_size} rows",
+                table.length()
+            );
+            let t: Vec<F> = table.fixed_column(domain_size);
+            let m: Vec<F> = (0..domain_size)
+                .map(|i| {
+                    if i < table.length() {
+                        F::from(self.lookup_multiplicities[table as usize][i])
+                    } else {
+                        F::zero()
+                    }
                 })
                 .collect();
-            self.previous_commitments_e1 = comms
+            let mut psi: Vec<F> = t.iter().map(|t| beta + t).collect();
+            ark_ff::batch_inversion(&mut psi);
+            psi.iter_mut().zip(m.iter()).for_each(|(psi, m)| *psi *= m);
+            columns[idx(Column::LookupTable(table))] = t;
+            columns[idx(Column::LookupMultiplicity(table))] = m;
+            columns[idx(Column::LookupTableInverse(table))] = psi;
+        });
+
+        // acc(ωX) = acc(X) + Σ_i φ_i(X) - Σ_t ψ_t(X), starting at zero
+        let mut acc = vec![F::zero(); domain_size];
+        (0..domain_size - 1).for_each(|row| {
+            let sum_phi: F = (0..MAX_NUMBER_OF_LOOKUPS_PER_ROW)
+                .map(|i| columns[idx(Column::LookupInverse(i))][row])
+                .sum();
+            let sum_psi: F = LookupTable::iter()
+                .map(|table| columns[idx(Column::LookupTableInverse(table))][row])
+                .sum();
+            acc[row + 1] = acc[row] + sum_phi - sum_psi;
+        });
+        columns[idx(Column::LookupAccumulator)] = acc;
+        columns[idx(Column::LookupChallenge)] = vec![beta; domain_size];
+
+        columns
+    }
+
+    /// Set the output of the current iteration, `z_(i + 1)`, as the input of
+    /// the next iteration.
+    /// The hash of the public IO of the current iteration is also saved as the
+    /// previous hash, encoded in 2 chunks of 128 bits, to be absorbed by the
+    /// next iteration.
+    pub fn compute_output(&mut self) {
+        self.zi = self.next_zi.clone();
+        let mask: BigInt = (BigInt::from(1_u64) << 128) - BigInt::from(1_u64);
+        let low: BigInt = &self.public_io_hash & &mask;
+        let high: BigInt = (&self.public_io_hash >> 128) & &mask;
+        self.previous_hash = [
+            u128::try_from(&low).unwrap(),
+            u128::try_from(&high).unwrap(),
+        ];
+    }
+
+    /// Return the values of the public IO to absorb, in the order described in
+    /// the [public_io](crate::public_io) module: the accumulators of the
+    /// current iteration, the previous hash, the iteration index, the initial
+    /// state `z_0`, the current state `z_i`, the output `z_(i + 1)` and the
+    /// challenges.
+    ///
+    /// As the hash of the iteration `i` is absorbed by the iteration `i + 1`
+    /// with its own input state, the output `z_(i + 1)` of the iteration `i`
+    /// and the input of the iteration `i + 1` are both bound to the chain of
+    /// public IO hashes. A verifier recomputing the chain with
+    /// [public_io::hash_public_io] rejects any iteration whose input is not
+    /// the output of the previous one.
+    ///
+    /// The accumulators are the ones whose coordinates are in the field of the
+    /// current iteration, i.e. the accumulators on E2 for even iterations and
+    /// on E1 for odd iterations.
+    pub fn values_to_absorb_public_io(&self) -> Vec<BigInt> {
+        let accumulators: Vec<(BigInt, BigInt)> = if self.current_iteration % 2 == 0 {
+            self.ivc_accumulator_e2
+                .iter()
+                .map(|acc| {
+                    let (pt_x, pt_y) = acc.get_first_chunk().to_coordinates().unwrap();
+                    (pt_x.to_biguint().into(), pt_y.to_biguint().into())
+                })
+                .collect()
         } else {
-            let comms: Vec<PolyComm<E2>> = self
-                .witness
+            self.ivc_accumulator_e1
                 .iter()
-                .map(|evals| {
-                    let evals: Vec<Fq> = evals
-                        .par_iter()
-                        .map(|x| Fq::from_biguint(&x.to_biguint().unwrap()).unwrap())
-                        .collect();
-                    let evals = Evaluations::from_vec_and_domain(evals.to_vec(), self.domain_fq.d1);
-                    self.srs_e2
-                        .commit_evaluations_non_hiding(self.domain_fq.d1, &evals)
+                .map(|acc| {
+                    let (pt_x, pt_y) = acc.get_first_chunk().to_coordinates().unwrap();
+                    (pt_x.to_biguint().into(), pt_y.to_biguint().into())
                 })
+                .collect()
+        };
+        let mut values: Vec<BigInt> = accumulators.into_iter().flat_map(|(x, y)| [x, y]).collect();
+        values.extend(self.previous_hash.iter().map(|h| BigInt::from(*h)));
+        values.push(BigInt::from(self.current_iteration));
+        values.extend(self.z0.iter().cloned());
+        values.extend(self.zi.iter().cloned());
+        values.extend(self.next_zi.iter().cloned());
+        values.extend(self.challenges.iter().cloned());
+        values
+    }
+
+    /// Compute, outside of the circuit, the hash of the public IO of the
+    /// current iteration, starting from the given sponge state. The result is
+    /// the value the gadget [crate::columns::Gadget::PublicIOHash] exposes
+    /// after the values have been absorbed from the same initial state.
+    pub fn compute_public_io_hash(&self, initial_state: &[BigInt; POSEIDON_STATE_SIZE]) -> BigInt {
+        let values = self.values_to_absorb_public_io();
+        if self.current_iteration % 2 == 0 {
+            let initial_state: [Fp; POSEIDON_STATE_SIZE] =
+                std::array::from_fn(|i| Fp::from(initial_state[i].to_biguint().unwrap()));
+            let values: Vec<Fp> = values
+                .iter()
+                .map(|v| Fp::from(v.to_biguint().unwrap()))
                 .collect();
-            self.previous_commitments_e2 = comms
+            public_io::hash_public_io(E1::sponge_params(), initial_state, &values)
+                .to_biguint()
+                .into()
+        } else {
+            let initial_state: [Fq; POSEIDON_STATE_SIZE] =
+                std::array::from_fn(|i| Fq::from(initial_state[i].to_biguint().unwrap()));
+            let values: Vec<Fq> = values
+                .iter()
+                .map(|v| Fq::from(v.to_biguint().unwrap()))
+                .collect();
+            public_io::hash_public_io(E2::sponge_params(), initial_state, &values)
+                .to_biguint()
+                .into()
         }
     }
 
-    /// Compute the output of the application on the previous output
-    // TODO: we should compute the hash of the previous commitments, only on
-    // CPU?
-    pub fn compute_output(&mut self) {
-        self.zi = BigInt::from(42_usize)
+    /// Set the values of the stream the application absorbs in the current
+    /// iteration using [run_stream_hash](crate::interpreter::run_stream_hash).
+    /// The values are cleared when moving to the next iteration.
+    pub fn set_stream_values(&mut self, values: Vec<BigInt>) {
+        self.stream_values = values;
+        self.idx_stream_values = 0;
+    }
+
+    /// Set the schedule followed by the iterations. See the
+    /// [schedule](crate::schedule) module.
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.schedule = Some(schedule);
+    }
+
+    /// Return the instruction the current iteration must execute according
+    /// to the schedule, the program counter being carried in the IVC state at
+    /// the index `idx_state` and the next one. `None` is returned if there is
+    /// no schedule, or if all the phases have been executed.
+    pub fn fetch_scheduled_instruction(&self, idx_state: usize) -> Option<Instruction> {
+        let phase = usize::try_from(&self.zi[idx_state]).unwrap();
+        self.schedule
+            .as_ref()
+            .and_then(|schedule| schedule.instruction(phase))
+    }
+
+    /// Return the execution trace of the current iteration, containing the
+    /// rows built so far. See the [trace](crate::trace) module.
+    pub fn trace(&self) -> Trace {
+        Trace::new(
+            self.current_iteration,
+            &self.witness,
+            &self.selectors,
+            self.current_row,
+        )
+    }
+
+    /// Return the state given as input to the current iteration, `z_i`.
+    pub fn ivc_state(&self) -> &[BigInt] {
+        &self.zi
     }
 
     pub fn fetch_instruction(&self) -> Instruction {
@@ -976,7 +1733,7 @@ impl<
     /// corresponds to the public input:
     ///
     /// ```text
-    /// hash = H(i, acc_1, ..., acc_17, z_0, z_i)
+    /// hash = H(i, acc_1, ..., acc_17, z_0, z_(i - 1), z_i)
     /// ```
     ///
     /// - We also have to check that the previous challenges (α, β, γ) have been
@@ -1010,13 +1767,14 @@ impl<
     /// - We compute the next hash we give to the next instance
     ///
     /// ```text
-    /// hash' = H(i + 1, acc'_1, ..., acc'_17, z_0, z_(i + 1))
+    /// hash' = H(i + 1, acc'_1, ..., acc'_17, z_0, z_i, z_(i + 1))
     /// ```
     pub fn fetch_next_instruction(&mut self) -> Instruction {
         match self.current_instruction {
             Instruction::Poseidon(i) => {
-                if i < POSEIDON_ROUNDS_FULL - 5 {
-                    Instruction::Poseidon(i + 5)
+                let rounds_per_row = crate::poseidon_rounds_per_row(self.number_of_columns);
+                if i < POSEIDON_ROUNDS_FULL - rounds_per_row {
+                    Instruction::Poseidon(i + rounds_per_row)
                 } else {
                     // FIXME: we continue absorbing
                     Instruction::Poseidon(0)
@@ -1025,14 +1783,19 @@ impl<
             Instruction::EllipticCurveScaling(i_comm, bit) => {
                 // TODO: we still need to substract (or not?) the blinder.
                 // Maybe we can avoid this by aggregating them.
-                // TODO: we also need to aggregate the cross-terms.
+                // TODO: we also need to aggregate the cross-terms and the
+                // commitments to the columns of the lookup argument.
                 // Therefore i_comm must also take into the account the number
-                // of cross-terms.
-                assert!(i_comm < NUMBER_OF_COLUMNS, "Maximum number of columns reached ({NUMBER_OF_COLUMNS}), increase the number of columns");
+                // of cross-terms and NUMBER_OF_LOOKUP_COLUMNS.
+                assert!(
+                    i_comm < self.number_of_columns,
+                    "Maximum number of columns reached ({}), increase the number of columns",
+                    self.number_of_columns
+                );
                 assert!(bit < MAXIMUM_FIELD_SIZE_IN_BITS, "Maximum number of bits reached ({MAXIMUM_FIELD_SIZE_IN_BITS}), increase the number of bits");
                 if bit < MAXIMUM_FIELD_SIZE_IN_BITS - 1 {
                     Instruction::EllipticCurveScaling(i_comm, bit + 1)
-                } else if i_comm < NUMBER_OF_COLUMNS - 1 {
+                } else if i_comm < self.number_of_columns - 1 {
                     Instruction::EllipticCurveScaling(i_comm + 1, 0)
                 } else {
                     // We have computed all the bits for all the columns
@@ -1040,13 +1803,76 @@ impl<
                 }
             }
             Instruction::EllipticCurveAddition(i_comm) => {
-                if i_comm < NUMBER_OF_COLUMNS - 1 {
+                if i_comm < self.number_of_columns - 1 {
                     Instruction::EllipticCurveAddition(i_comm + 1)
                 } else {
                     Instruction::NoOp
                 }
             }
+            Instruction::StreamHashAbsorb(i) => {
+                let rounds_per_row = crate::poseidon_rounds_per_row(self.number_of_columns);
+                if i < POSEIDON_ROUNDS_FULL - rounds_per_row {
+                    Instruction::StreamHashAbsorb(i + rounds_per_row)
+                } else {
+                    Instruction::StreamHashAbsorb(0)
+                }
+            }
+            Instruction::PublicIOHash
+            | Instruction::StreamHashLoadState
+            | Instruction::StreamHashStoreState
+            | Instruction::ProgramCounter(_) => Instruction::NoOp,
             Instruction::NoOp => Instruction::NoOp,
         }
     }
 }
+
+/// Commit to the given columns, whose values are reduced in the scalar field of
+/// the curve.
+fn commit_columns<C: ArrabbiataCurve>(
+    srs: &SRS<C>,
+    domain: EvaluationDomains<C::ScalarField>,
+    columns: &[&Vec<BigInt>],
+) -> Vec<PolyComm<C>>
+where
+    C::BaseField: PrimeField,
+{
+    columns
+        .par_iter()
+        .map(|evals| {
+            let evals: Vec<C::ScalarField> = evals
+                .par_iter()
+                .map(|x| C::ScalarField::from_biguint(&x.to_biguint().unwrap()).unwrap())
+                .collect();
+            let evals = Evaluations::from_vec_and_domain(evals, domain.d1);
+            srs.commit_evaluations_non_hiding(domain.d1, &evals)
+        })
+        .collect()
+}
+
+/// Absorb the coordinates of the given commitments into a copy of the sponge
+/// state `state`, over the base field of the curve, and return the 128 low bits
+/// of the first element of the resulting state. See
+/// [Env::coin_lookup_challenge].
+fn squeeze_lookup_challenge<'a, C: ArrabbiataCurve>(
+    params: &ArithmeticSpongeParams<C::BaseField>,
+    state: &[BigInt; POSEIDON_STATE_SIZE],
+    commitments: impl Iterator<Item = &'a PolyComm<C>>,
+) -> BigInt
+where
+    C::BaseField: PrimeField,
+{
+    let mut state: [C::BaseField; POSEIDON_STATE_SIZE] = std::array::from_fn(|i| {
+        C::BaseField::from_biguint(&state[i].to_biguint().unwrap()).unwrap()
+    });
+    let values: Vec<C::BaseField> = commitments
+        .flat_map(|comm| comm.chunks.iter())
+        .flat_map(|pt| {
+            let (x, y) = pt.to_coordinates().unwrap_or_default();
+            [x, y]
+        })
+        .collect();
+    public_io::absorb(params, &mut state, &values);
+    let mask = (BigUint::from(1_u64) << 128) - BigUint::from(1_u64);
+    let challenge: BigUint = state[0].to_biguint() & mask;
+    challenge.into()
+}
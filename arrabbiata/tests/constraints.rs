@@ -1,10 +1,13 @@
 use num_bigint::BigInt;
 use std::collections::HashMap;
+use strum::EnumCount;
 
 use arrabbiata::{
     columns::Gadget,
     constraints,
-    interpreter::{self, Instruction},
+    cross_terms::selected_constraints,
+    interpreter::{self, Instruction, InterpreterEnv},
+    logup::LookupTable,
     poseidon_3_60_0_5_5_fp, poseidon_3_60_0_5_5_fq,
 };
 use mina_curves::pasta::fields::{Fp, Fq};
@@ -57,6 +60,7 @@ fn helper_check_expected_degree_constraints(instr: Instruction, exp_degrees: Has
 fn helper_gadget_number_of_columns_used(
     instr: Instruction,
     exp_nb_columns: usize,
+    exp_nb_fixed_columns: usize,
     exp_nb_public_input: usize,
 ) {
     let mut constraints_fp = {
@@ -68,6 +72,9 @@ fn helper_gadget_number_of_columns_used(
     let nb_columns = constraints_fp.idx_var;
     assert_eq!(nb_columns, exp_nb_columns);
 
+    let nb_fixed_columns = constraints_fp.idx_var_fixed;
+    assert_eq!(nb_fixed_columns, exp_nb_fixed_columns);
+
     let nb_public_input = constraints_fp.idx_var_pi;
     assert_eq!(nb_public_input, exp_nb_public_input);
 }
@@ -85,17 +92,19 @@ fn helper_check_gadget_activated(instr: Instruction, gadget: Gadget) {
 #[test]
 fn test_gadget_poseidon_next_row() {
     let instr = Instruction::Poseidon(0);
-    helper_compute_constraints_gadget(instr, 15);
+    helper_compute_constraints_gadget(instr, 12);
 
     let mut exp_degrees = HashMap::new();
-    exp_degrees.insert(5, 15);
+    exp_degrees.insert(5, 12);
     helper_check_expected_degree_constraints(instr, exp_degrees);
 
-    helper_gadget_number_of_columns_used(instr, 15, 17);
+    // 4 rounds per row, 2 private values to absorb and the round constants in
+    // fixed columns
+    helper_gadget_number_of_columns_used(instr, 14, 12, 0);
 
-    // We always have 2 additional public inputs, even if set to 0
+    // We always have 2 values to absorb, even if set to 0
     let instr = Instruction::Poseidon(1);
-    helper_gadget_number_of_columns_used(instr, 15, 17);
+    helper_gadget_number_of_columns_used(instr, 14, 12, 0);
 
     helper_check_gadget_activated(instr, Gadget::Poseidon);
 }
@@ -110,11 +119,90 @@ fn test_gadget_elliptic_curve_addition() {
     exp_degrees.insert(2, 2);
     helper_check_expected_degree_constraints(instr, exp_degrees);
 
-    helper_gadget_number_of_columns_used(instr, 8, 0);
+    helper_gadget_number_of_columns_used(instr, 8, 0, 0);
 
     helper_check_gadget_activated(instr, Gadget::EllipticCurveAddition);
 }
 
+#[test]
+fn test_gadget_public_io_hash() {
+    let instr = Instruction::PublicIOHash;
+    helper_compute_constraints_gadget(instr, 1);
+
+    let mut exp_degrees = HashMap::new();
+    exp_degrees.insert(1, 1);
+    helper_check_expected_degree_constraints(instr, exp_degrees);
+
+    // The hash is the only public input of the gadget
+    helper_gadget_number_of_columns_used(instr, 1, 0, 1);
+
+    helper_check_gadget_activated(instr, Gadget::PublicIOHash);
+}
+
+#[test]
+fn test_gadget_stream_hash_state() {
+    // Loading and saving the state use the same constraints, one per element
+    // of the state.
+    [
+        Instruction::StreamHashLoadState,
+        Instruction::StreamHashStoreState,
+    ]
+    .into_iter()
+    .for_each(|instr| {
+        helper_compute_constraints_gadget(instr, 3);
+
+        let mut exp_degrees = HashMap::new();
+        exp_degrees.insert(1, 3);
+        helper_check_expected_degree_constraints(instr, exp_degrees);
+
+        // One column for the element of the IVC state and one for the
+        // element of the sponge state
+        helper_gadget_number_of_columns_used(instr, 6, 0, 0);
+
+        helper_check_gadget_activated(instr, Gadget::StreamHashState);
+    });
+
+    // The values are absorbed using the Poseidon gadget
+    let instr = Instruction::StreamHashAbsorb(0);
+    helper_compute_constraints_gadget(instr, 12);
+    helper_gadget_number_of_columns_used(instr, 14, 12, 0);
+    helper_check_gadget_activated(instr, Gadget::Poseidon);
+}
+
+#[test]
+fn test_gadget_program_counter() {
+    let instr = Instruction::ProgramCounter(0);
+    helper_compute_constraints_gadget(instr, 10);
+
+    let mut exp_degrees = HashMap::new();
+    exp_degrees.insert(1, 3);
+    exp_degrees.insert(2, 5);
+    exp_degrees.insert(3, 2);
+    helper_check_expected_degree_constraints(instr, exp_degrees);
+
+    // The program counter, the inverse, the end-of-phase flag, one flag per
+    // phase and the updated program counter. The program counter is read from
+    // and written to the IVC state, and the lengths of the phases are fixed
+    // columns.
+    helper_gadget_number_of_columns_used(
+        instr,
+        6 + arrabbiata::MAX_NUMBER_OF_PHASES,
+        arrabbiata::MAX_NUMBER_OF_PHASES,
+        0,
+    );
+
+    helper_check_gadget_activated(instr, Gadget::ProgramCounter);
+
+    // The counter of the current phase is range-checked
+    let mut constraints_fp = {
+        let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+        constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize))
+    };
+    interpreter::run_ivc(&mut constraints_fp, instr);
+    assert_eq!(constraints_fp.lookups.len(), 1);
+    assert_eq!(constraints_fp.lookups[0].table, LookupTable::RangeCheck16);
+}
+
 #[test]
 fn test_ivc_total_number_of_constraints_ivc() {
     let constraints_fp = {
@@ -123,7 +211,7 @@ fn test_ivc_total_number_of_constraints_ivc() {
     };
 
     let constraints = constraints_fp.get_all_constraints_for_ivc();
-    assert_eq!(constraints.len(), 28);
+    assert_eq!(constraints.len(), 26);
 }
 
 #[test]
@@ -142,11 +230,11 @@ fn test_degree_of_constraints_ivc() {
         *count += 1;
     });
 
-    assert_eq!(degree_per_constraints.get(&1), Some(&1));
+    assert_eq!(degree_per_constraints.get(&1), Some(&2));
     assert_eq!(degree_per_constraints.get(&2), Some(&11));
     assert_eq!(degree_per_constraints.get(&3), Some(&1));
     assert_eq!(degree_per_constraints.get(&4), None);
-    assert_eq!(degree_per_constraints.get(&5), Some(&15));
+    assert_eq!(degree_per_constraints.get(&5), Some(&12));
 }
 
 #[test]
@@ -160,7 +248,190 @@ fn test_gadget_elliptic_curve_scaling() {
     exp_degrees.insert(2, 9);
     helper_check_expected_degree_constraints(instr, exp_degrees);
 
-    helper_gadget_number_of_columns_used(instr, 10, 0);
+    helper_gadget_number_of_columns_used(instr, 10, 0, 0);
 
     helper_check_gadget_activated(instr, Gadget::EllipticCurveScaling);
 }
+
+#[test]
+fn test_range_check16_lookup_constraints() {
+    let mut constraints_fp = {
+        let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+        constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize))
+    };
+
+    // No lookup, no constraint
+    assert!(constraints_fp.get_lookup_constraints().is_empty());
+
+    let pos = constraints_fp.allocate();
+    constraints_fp.range_check16(pos);
+    let pos = constraints_fp.allocate();
+    constraints_fp.range_check16(pos);
+    assert_eq!(constraints_fp.lookups.len(), 2);
+    assert!(constraints_fp
+        .lookups
+        .iter()
+        .all(|l| l.table == LookupTable::RangeCheck16));
+
+    // One constraint per lookup
+    let lookup_constraints = constraints_fp.get_lookup_constraints();
+    assert_eq!(lookup_constraints.len(), 2);
+
+    let degrees: Vec<u64> = lookup_constraints.iter().map(|c| c.degree(1, 0)).collect();
+    assert_eq!(degrees, vec![2, 2]);
+
+    // The lookups are cleaned when resetting the environment
+    constraints_fp.reset();
+    assert!(constraints_fp.lookups.is_empty());
+    assert!(constraints_fp.get_lookup_constraints().is_empty());
+}
+
+#[test]
+fn test_global_lookup_constraints() {
+    let constraints_fp = {
+        let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+        constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize))
+    };
+
+    // One constraint for the challenge, one per table and one for the
+    // accumulator. The accumulator is multiplied by the selectors of the
+    // gadgets performing lookups.
+    let constraints = constraints_fp.get_global_lookup_constraints();
+    assert_eq!(constraints.len(), LookupTable::COUNT + 2);
+    let degrees: Vec<u64> = constraints.iter().map(|c| c.degree(1, 0)).collect();
+    assert_eq!(degrees, vec![1, 2, 2]);
+
+    // The global constraints are included in the constraints of the circuit
+    let all_constraints = constraints_fp.get_all_constraints();
+    assert!(constraints.iter().all(|c| all_constraints.contains(c)));
+}
+
+#[test]
+fn test_ivc_state_uses_private_columns() {
+    let mut constraints_fp = {
+        let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+        constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize))
+    };
+
+    let pos = constraints_fp.allocate();
+    let x = constraints_fp.read_ivc_state(pos, 0);
+    // Reading does not add any constraint
+    assert!(constraints_fp.constraints.is_empty());
+    let pos = constraints_fp.allocate();
+    constraints_fp.write_ivc_state(pos, 0, x.clone() * x);
+
+    // The state is bound to the public IO by its hash, which is the only
+    // public input
+    assert_eq!(constraints_fp.idx_var_pi, 0);
+    assert_eq!(constraints_fp.idx_var, 2);
+    assert_eq!(constraints_fp.constraints.len(), 1);
+    assert_eq!(constraints_fp.constraints[0].degree(1, 0), 2);
+}
+
+#[test]
+fn test_gadget_poseidon_next_row_with_twelve_columns() {
+    let instr = Instruction::Poseidon(0);
+    let mut constraints_fp = {
+        let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+        constraints::Env::<Fp>::new_with_number_of_columns(
+            poseidon_mds.to_vec(),
+            BigInt::from(0_usize),
+            12,
+        )
+    };
+    assert_eq!(constraints_fp.poseidon_rounds_per_row(), 3);
+
+    interpreter::run_ivc(&mut constraints_fp, instr);
+    // 3 rounds per row, each round adds one constraint per state element
+    assert_eq!(constraints_fp.constraints.len(), 9);
+    // The input, 2 values to absorb and the intermediary states
+    assert_eq!(constraints_fp.idx_var, 11);
+    // 3 * 3 round constants
+    assert_eq!(constraints_fp.idx_var_fixed, 9);
+    assert_eq!(constraints_fp.idx_var_pi, 0);
+}
+
+#[test]
+#[should_panic]
+fn test_number_of_columns_too_small() {
+    let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+    constraints::Env::<Fp>::new_with_number_of_columns(
+        poseidon_mds.to_vec(),
+        BigInt::from(0_usize),
+        arrabbiata::MIN_NUMBER_OF_COLUMNS - 1,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_number_of_columns_not_dividing_poseidon_rounds() {
+    let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+    // 23 columns gives 7 rounds per row, which does not divide 60
+    constraints::Env::<Fp>::new_with_number_of_columns(
+        poseidon_mds.to_vec(),
+        BigInt::from(0_usize),
+        23,
+    );
+}
+
+#[test]
+fn test_hints_are_constrained() {
+    let mut constraints_fp = {
+        let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+        constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize))
+    };
+
+    let x = {
+        let pos = constraints_fp.allocate();
+        constraints_fp.read_position(pos)
+    };
+    let pos = constraints_fp.allocate();
+    constraints_fp.hint_inverse(pos, x.clone());
+    let pos = constraints_fp.allocate();
+    constraints_fp.hint_square_root(pos, x.clone());
+    assert_eq!(constraints_fp.constraints.len(), 2);
+
+    // One boolean constraint per bit, and one for the recomposition
+    let positions: Vec<_> = (0..4).map(|_| constraints_fp.allocate()).collect();
+    let bits = constraints_fp.hint_bit_decomposition(x, &positions);
+    assert_eq!(bits.len(), 4);
+    assert_eq!(constraints_fp.constraints.len(), 7);
+
+    let degrees: Vec<u64> = constraints_fp
+        .constraints
+        .iter()
+        .map(|c| c.degree(1, 0))
+        .collect();
+    assert_eq!(degrees, vec![2, 2, 2, 2, 2, 2, 1]);
+}
+
+#[test]
+fn test_constraints_per_gadget_are_selected() {
+    let constraints_fp = {
+        let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+        constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize))
+    };
+    let per_gadget = constraints_fp.get_all_constraints_per_gadget();
+    let gadgets: Vec<Gadget> = per_gadget.iter().map(|(g, _)| *g).collect();
+    assert_eq!(
+        gadgets,
+        vec![
+            Gadget::Poseidon,
+            Gadget::EllipticCurveScaling,
+            Gadget::EllipticCurveAddition,
+            Gadget::PublicIOHash,
+            Gadget::StreamHashState,
+            Gadget::ProgramCounter,
+        ]
+    );
+
+    // Multiplying by the selector increases the degree of each constraint by
+    // one.
+    let constraints: Vec<_> = per_gadget.iter().flat_map(|(_, c)| c.clone()).collect();
+    let selected = selected_constraints(&per_gadget);
+    assert_eq!(selected.len(), constraints.len());
+    selected
+        .iter()
+        .zip(constraints.iter())
+        .for_each(|(s, c)| assert_eq!(s.degree(1, 0), c.degree(1, 0) + 1));
+}
@@ -0,0 +1,166 @@
+use ark_ff::{Field, One, UniformRand, Zero};
+use arrabbiata::{
+    constraints,
+    cross_terms::{
+        constraints_to_polynomials, number_of_variables_per_row, CrossTermsComputer,
+        HOMOGENEOUS_DEGREE, MAX_NUMBER_OF_VARIABLES_PER_ROW, NUMBER_OF_VARIABLES,
+        NUMBER_OF_VARIABLES_PER_ROW,
+    },
+    poseidon_3_60_0_5_5_fp,
+    prover::ProverConfig,
+    NUMBER_OF_COLUMNS,
+};
+use mina_curves::pasta::Fp;
+use mvpoly::MVPoly;
+use num_bigint::BigInt;
+use rand::{CryptoRng, RngCore};
+
+fn random_witness<RNG: RngCore + CryptoRng>(rng: &mut RNG, domain_size: usize) -> Vec<Vec<Fp>> {
+    random_witness_with_number_of_columns(rng, domain_size, NUMBER_OF_COLUMNS)
+}
+
+fn random_witness_with_number_of_columns<RNG: RngCore + CryptoRng>(
+    rng: &mut RNG,
+    domain_size: usize,
+    number_of_columns: usize,
+) -> Vec<Vec<Fp>> {
+    (0..number_of_variables_per_row(number_of_columns))
+        .map(|_| (0..domain_size).map(|_| Fp::rand(rng)).collect())
+        .collect()
+}
+
+fn ivc_constraints() -> Vec<arrabbiata::columns::E<Fp>> {
+    ivc_constraints_with_number_of_columns(NUMBER_OF_COLUMNS)
+}
+
+fn ivc_constraints_with_number_of_columns(
+    number_of_columns: usize,
+) -> Vec<arrabbiata::columns::E<Fp>> {
+    let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+    let env = constraints::Env::<Fp>::new_with_number_of_columns(
+        poseidon_mds.to_vec(),
+        BigInt::from(0_usize),
+        number_of_columns,
+    );
+    env.get_all_constraints_for_ivc()
+}
+
+fn helper_cross_terms_fold_combined_constraint(number_of_columns: usize) {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let domain_size = 4;
+    let constraints = ivc_constraints_with_number_of_columns(number_of_columns);
+    let polynomials = constraints_to_polynomials(&constraints, number_of_columns);
+
+    let witness1 = random_witness_with_number_of_columns(&mut rng, domain_size, number_of_columns);
+    let witness2 = random_witness_with_number_of_columns(&mut rng, domain_size, number_of_columns);
+    let u1 = Fp::rand(&mut rng);
+    let u2 = Fp::rand(&mut rng);
+    let alpha = Fp::rand(&mut rng);
+    let r = Fp::rand(&mut rng);
+
+    let mut computer =
+        CrossTermsComputer::new_with_number_of_columns(ProverConfig::default(), number_of_columns);
+    let cross_terms =
+        computer.compute_cross_terms(&constraints, &witness1, &witness2, u1, u2, alpha);
+    assert_eq!(cross_terms.len(), HOMOGENEOUS_DEGREE - 1);
+
+    // The variables of the next row start at the same offset whatever the
+    // number of columns, the unused variables being set to zero.
+    let eval_row = |witness: &[Vec<Fp>], row: usize| -> [Fp; NUMBER_OF_VARIABLES] {
+        std::array::from_fn(|i| {
+            let (col, row) = if i < MAX_NUMBER_OF_VARIABLES_PER_ROW {
+                (i, row)
+            } else {
+                (i - MAX_NUMBER_OF_VARIABLES_PER_ROW, (row + 1) % domain_size)
+            };
+            if col < witness.len() {
+                witness[col][row]
+            } else {
+                Fp::zero()
+            }
+        })
+    };
+    let combined_eval = |eval: &[Fp; NUMBER_OF_VARIABLES], u: Fp| {
+        polynomials.iter().rev().fold(Fp::zero(), |acc, p| {
+            acc * alpha + p.homogeneous_eval(eval, u)
+        })
+    };
+
+    (0..domain_size).for_each(|row| {
+        let eval1 = eval_row(&witness1, row);
+        let eval2 = eval_row(&witness2, row);
+        let folded_eval: [Fp; NUMBER_OF_VARIABLES] =
+            std::array::from_fn(|i| eval1[i] + r * eval2[i]);
+        let folded_u = u1 + r * u2;
+        // C(w1 + r w2) = C(w1) + Σ r^k T_k + r^D C(w2)
+        let exp = combined_eval(&folded_eval, folded_u);
+        let cross_terms_eval = (1..HOMOGENEOUS_DEGREE).fold(Fp::zero(), |acc, power_r| {
+            acc + r.pow([power_r as u64]) * cross_terms[&power_r][row]
+        });
+        let res = combined_eval(&eval1, u1)
+            + cross_terms_eval
+            + r.pow([HOMOGENEOUS_DEGREE as u64]) * combined_eval(&eval2, u2);
+        assert_eq!(res, exp, "Invalid cross-terms for the row {row}");
+    });
+}
+
+#[test]
+fn test_cross_terms_fold_combined_constraint() {
+    helper_cross_terms_fold_combined_constraint(NUMBER_OF_COLUMNS);
+}
+
+#[test]
+fn test_cross_terms_fold_combined_constraint_with_more_columns() {
+    // 6 Poseidon rounds per row, and more variables per row than with the
+    // default number of columns.
+    let number_of_columns = 20;
+    assert!(number_of_variables_per_row(number_of_columns) > NUMBER_OF_VARIABLES_PER_ROW);
+    helper_cross_terms_fold_combined_constraint(number_of_columns);
+}
+
+#[test]
+#[should_panic]
+fn test_cross_terms_too_many_columns() {
+    CrossTermsComputer::<Fp>::new_with_number_of_columns(
+        ProverConfig::default(),
+        arrabbiata::MAX_NUMBER_OF_COLUMNS + 3,
+    );
+}
+
+#[test]
+fn test_cross_terms_prover_config_does_not_change_the_result() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let domain_size = 8;
+    let constraints = ivc_constraints();
+
+    let witness1 = random_witness(&mut rng, domain_size);
+    let witness2 = random_witness(&mut rng, domain_size);
+    let u2 = Fp::rand(&mut rng);
+    let alpha = Fp::rand(&mut rng);
+
+    let mut sequential_computer = CrossTermsComputer::new(ProverConfig {
+        parallel: false,
+        cache_constraints: false,
+    });
+    let exp = sequential_computer.compute_cross_terms(
+        &constraints,
+        &witness1,
+        &witness2,
+        Fp::one(),
+        u2,
+        alpha,
+    );
+    assert!(sequential_computer.cached_polynomials.is_none());
+
+    let mut computer = CrossTermsComputer::new(ProverConfig::default());
+    // Computing twice to use the cached polynomials
+    (0..2).for_each(|_| {
+        let res =
+            computer.compute_cross_terms(&constraints, &witness1, &witness2, Fp::one(), u2, alpha);
+        assert_eq!(res, exp);
+        assert_eq!(
+            computer.cached_polynomials.as_ref().map(|p| p.len()),
+            Some(constraints.len())
+        );
+    });
+}
@@ -0,0 +1,98 @@
+use ark_ec::CurveGroup;
+use ark_ff::{Field, One, PrimeField};
+use arrabbiata::{
+    curve::ArrabbiataCurve,
+    interpreter::{self, Instruction, InterpreterEnv},
+    public_io::PoseidonSpongeConstants,
+    witness::Env,
+    POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE,
+};
+use mina_curves::{
+    grumpkin::Grumpkin,
+    pasta::{Pallas, Vesta},
+};
+use mina_poseidon::permutation::poseidon_block_cipher;
+use num_bigint::BigInt;
+use o1_utils::FieldHelpers;
+
+fn helper_check_sponge_params<E: ArrabbiataCurve>()
+where
+    E::BaseField: PrimeField,
+{
+    let params = E::sponge_params();
+    assert_eq!(params.mds.len(), POSEIDON_STATE_SIZE);
+    assert_eq!(params.round_constants.len(), POSEIDON_ROUNDS_FULL);
+    let params = E::other_curve_sponge_params();
+    assert_eq!(params.mds.len(), POSEIDON_STATE_SIZE);
+    assert_eq!(params.round_constants.len(), POSEIDON_ROUNDS_FULL);
+}
+
+fn helper_check_endos<E: ArrabbiataCurve>()
+where
+    E::BaseField: PrimeField,
+{
+    let (endo_q, endo_r) = E::endos();
+    // The coefficients are cube roots of unity
+    assert_eq!(endo_q.pow([3]), E::BaseField::one());
+    assert_eq!(endo_r.pow([3]), E::ScalarField::one());
+    // φ(x, y) = (endo_q x, y) = [endo_r] (x, y)
+    let g = E::generator();
+    let (x, y) = g.to_coordinates().unwrap();
+    let phi_g = E::of_coordinates(x * endo_q, y);
+    assert_eq!(g.mul(*endo_r).into_affine(), phi_g);
+}
+
+#[test]
+fn test_sponge_params() {
+    helper_check_sponge_params::<Pallas>();
+    helper_check_sponge_params::<Vesta>();
+    helper_check_sponge_params::<ark_bn254::G1Affine>();
+    helper_check_sponge_params::<Grumpkin>();
+}
+
+#[test]
+fn test_endos() {
+    helper_check_endos::<Pallas>();
+    helper_check_endos::<Vesta>();
+    helper_check_endos::<ark_bn254::G1Affine>();
+    helper_check_endos::<Grumpkin>();
+}
+
+#[test]
+fn test_unit_witness_poseidon_gadget_one_full_hash_bn254() {
+    let srs_log2_size = 6;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<ark_bn254::Fr, ark_bn254::Fq, ark_bn254::G1Affine, Grumpkin>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge.clone(),
+        sponge.clone(),
+    );
+
+    let rounds_per_row = env.poseidon_rounds_per_row();
+    (0..(POSEIDON_ROUNDS_FULL / rounds_per_row)).for_each(|i| {
+        interpreter::run_ivc(&mut env, Instruction::Poseidon(rounds_per_row * i));
+        env.reset();
+    });
+
+    let exp_output = {
+        let mut state: Vec<ark_bn254::Fr> = sponge
+            .iter()
+            .map(|x| ark_bn254::Fr::from_biguint(&x.to_biguint().unwrap()).unwrap())
+            .collect();
+        // The first values absorbed are the coordinates of the first
+        // accumulator, initialized with the blinder of the SRS
+        state[0] += env.srs_e2.h.x;
+        state[1] += env.srs_e2.h.y;
+        poseidon_block_cipher::<ark_bn254::Fr, PoseidonSpongeConstants>(
+            ark_bn254::G1Affine::sponge_params(),
+            &mut state,
+        );
+        state
+            .iter()
+            .map(|x| x.to_biguint().into())
+            .collect::<Vec<BigInt>>()
+    };
+    assert_eq!(env.sponge_e1.to_vec(), exp_output);
+    assert_eq!(env.sponge_e2, sponge);
+}
@@ -0,0 +1,162 @@
+use ark_ff::{One, UniformRand, Zero};
+use arrabbiata::{
+    columns::{Column, Gadget},
+    constraints,
+    cross_terms::{selected_constraints, CrossTermsComputer, NUMBER_OF_VARIABLES_PER_ROW},
+    folding::{fold_instances, RelaxedInstance},
+    logup::LookupTable,
+    poseidon_3_60_0_5_5_fp,
+    prover::ProverConfig,
+    verifier::FoldedRelation,
+};
+use mina_curves::pasta::Fp;
+use num_bigint::BigInt;
+use rand::{CryptoRng, RngCore};
+
+/// Build a random witness where each row activates the gadget given for the
+/// row, if any. The columns of the lookup argument are set to zero.
+fn random_witness<RNG: RngCore + CryptoRng>(
+    rng: &mut RNG,
+    gadgets: &[Option<Gadget>],
+) -> Vec<Vec<Fp>> {
+    let mut witness: Vec<Vec<Fp>> = (0..NUMBER_OF_VARIABLES_PER_ROW)
+        .map(|_| (0..gadgets.len()).map(|_| Fp::rand(rng)).collect())
+        .collect();
+    let first_selector = usize::from(Column::Selector(Gadget::App));
+    witness[first_selector..]
+        .iter_mut()
+        .for_each(|col| col.iter_mut().for_each(|x| *x = Fp::zero()));
+    gadgets.iter().enumerate().for_each(|(row, gadget)| {
+        if let Some(gadget) = gadget {
+            witness[usize::from(Column::Selector(*gadget))][row] = Fp::one();
+        }
+    });
+    witness
+}
+
+#[test]
+fn test_fold_instances_with_different_selectors() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+    let env = constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize));
+    let mut constraints = selected_constraints(&env.get_all_constraints_per_gadget());
+    constraints.extend(env.get_global_lookup_constraints());
+    let relation = FoldedRelation::new(constraints.clone());
+    let alpha = Fp::rand(&mut rng);
+
+    // The accumulator comes from a step circuit performing a hash, the
+    // incoming instance from a step circuit performing an elliptic curve
+    // addition.
+    let gadgets_acc = [
+        Some(Gadget::Poseidon),
+        Some(Gadget::Poseidon),
+        Some(Gadget::PublicIOHash),
+        None,
+    ];
+    let gadgets_inc = [
+        Some(Gadget::EllipticCurveAddition),
+        Some(Gadget::EllipticCurveAddition),
+        Some(Gadget::EllipticCurveAddition),
+        None,
+    ];
+    let witness_acc = random_witness(&mut rng, &gadgets_acc);
+    let witness_inc = random_witness(&mut rng, &gadgets_inc);
+
+    // The random values do not satisfy the constraints. The errors are set to
+    // get satisfying relaxed instances.
+    let mut accumulator = RelaxedInstance::new(witness_acc);
+    accumulator.error = relation.evaluate(&accumulator.witness, accumulator.u, alpha);
+    let mut incoming = RelaxedInstance::new(witness_inc);
+    incoming.error = relation.evaluate(&incoming.witness, incoming.u, alpha);
+    // The rows without any gadget activated are satisfied
+    assert_eq!(accumulator.error[3], Fp::zero());
+
+    let mut computer = CrossTermsComputer::new(ProverConfig::default());
+    let r = Fp::rand(&mut rng);
+    let (folded, _cross_terms) = fold_instances(
+        &mut computer,
+        &constraints,
+        &accumulator,
+        &incoming,
+        alpha,
+        r,
+    );
+    assert_eq!(
+        relation.check(&folded.witness, folded.u, alpha, &folded.error),
+        Ok(())
+    );
+
+    // The selectors of the folded instance are the combination of the
+    // selectors of both instances.
+    [
+        Gadget::Poseidon,
+        Gadget::PublicIOHash,
+        Gadget::EllipticCurveAddition,
+    ]
+    .into_iter()
+    .for_each(|gadget| {
+        let expected: Vec<Fp> = accumulator
+            .selector(gadget)
+            .iter()
+            .zip(incoming.selector(gadget).iter())
+            .map(|(s1, s2)| *s1 + r * s2)
+            .collect();
+        assert_eq!(folded.selector(gadget), expected.as_slice());
+    });
+    assert_eq!(folded.selector(Gadget::EllipticCurveAddition)[0], r);
+    assert_eq!(folded.selector(Gadget::Poseidon)[0], Fp::one());
+}
+
+#[test]
+fn test_fold_instances_keeps_the_lookup_tables_fixed() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+    let env = constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize));
+    let mut constraints = selected_constraints(&env.get_all_constraints_per_gadget());
+    constraints.extend(env.get_global_lookup_constraints());
+    let relation = FoldedRelation::new(constraints.clone());
+    let alpha = Fp::rand(&mut rng);
+    let table = LookupTable::RangeCheck16;
+    let idx = usize::from(Column::LookupTable(table));
+
+    // The prover can not pick the entries of the table of a fresh instance
+    let gadgets = [Some(Gadget::Poseidon), None, None, None];
+    let mut witness = random_witness(&mut rng, &gadgets);
+    witness[idx] = vec![Fp::from(42_u64); gadgets.len()];
+    let mut accumulator = RelaxedInstance::new(witness);
+    assert_eq!(accumulator.witness[idx], table.fixed_column::<Fp>(4));
+    accumulator.error = relation.evaluate(&accumulator.witness, accumulator.u, alpha);
+    let mut incoming = RelaxedInstance::new(random_witness(&mut rng, &gadgets));
+    incoming.error = relation.evaluate(&incoming.witness, incoming.u, alpha);
+
+    // The table of the folded instance is the fixed one, scaled by u
+    let mut computer = CrossTermsComputer::new(ProverConfig::default());
+    let r = Fp::rand(&mut rng);
+    let (folded, _cross_terms) = fold_instances(
+        &mut computer,
+        &constraints,
+        &accumulator,
+        &incoming,
+        alpha,
+        r,
+    );
+    let expected: Vec<Fp> = table
+        .fixed_column::<Fp>(4)
+        .into_iter()
+        .map(|t| folded.u * t)
+        .collect();
+    assert_eq!(folded.witness[idx], expected);
+    assert_eq!(
+        relation.check(&folded.witness, folded.u, alpha, &folded.error),
+        Ok(())
+    );
+
+    // A folded instance with another table is rejected
+    let mut invalid = folded.clone();
+    invalid.witness[idx][3] = Fp::from(42_u64) * invalid.u;
+    let invalid_error = relation.evaluate(&invalid.witness, invalid.u, alpha);
+    assert_eq!(
+        relation.check(&invalid.witness, invalid.u, alpha, &invalid_error),
+        Err(3)
+    );
+}
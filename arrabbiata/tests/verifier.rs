@@ -0,0 +1,164 @@
+use ark_ff::{Field, UniformRand, Zero};
+use arrabbiata::{
+    columns::{ChallengeTerm, Column, E},
+    constraints,
+    cross_terms::{
+        CrossTermsComputer, HOMOGENEOUS_DEGREE, MAX_NUMBER_OF_VARIABLES_PER_ROW,
+        NUMBER_OF_VARIABLES, NUMBER_OF_VARIABLES_PER_ROW,
+    },
+    logup::LookupTable,
+    poseidon_3_60_0_5_5_fp,
+    prover::ProverConfig,
+    verifier::{evaluate_expression, FoldedRelation},
+    NUMBER_OF_COLUMNS,
+};
+use kimchi::circuits::{expr::Variable, gate::CurrOrNext};
+use mina_curves::pasta::Fp;
+use mvpoly::MVPoly;
+use num_bigint::BigInt;
+use rand::{CryptoRng, RngCore};
+use strum::IntoEnumIterator;
+
+fn random_witness<RNG: RngCore + CryptoRng>(rng: &mut RNG, domain_size: usize) -> Vec<Vec<Fp>> {
+    (0..NUMBER_OF_VARIABLES_PER_ROW)
+        .map(|_| (0..domain_size).map(|_| Fp::rand(rng)).collect())
+        .collect()
+}
+
+/// Set the column of each lookup table to `u t`, as expected by the verifier
+fn set_lookup_tables(witness: &mut [Vec<Fp>], u: Fp) {
+    let domain_size = witness[0].len();
+    LookupTable::iter().for_each(|table| {
+        witness[usize::from(Column::LookupTable(table))] = table
+            .fixed_column::<Fp>(domain_size)
+            .into_iter()
+            .map(|t| u * t)
+            .collect();
+    });
+}
+
+/// The index of a variable in the layout of [arrabbiata::cross_terms]
+fn var_index(var: &Variable<Column>) -> usize {
+    let idx = var.col.index(NUMBER_OF_COLUMNS);
+    match var.row {
+        CurrOrNext::Curr => idx,
+        CurrOrNext::Next => MAX_NUMBER_OF_VARIABLES_PER_ROW + idx,
+    }
+}
+
+fn ivc_constraints() -> Vec<E<Fp>> {
+    let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+    let env = constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize));
+    env.get_all_constraints_for_ivc()
+}
+
+#[test]
+fn test_folded_relation_accepts_folded_instance() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let domain_size = 4;
+    let constraints = ivc_constraints();
+    let relation = FoldedRelation::new(constraints.clone());
+
+    // Two random relaxed instances, with the error terms making them satisfy
+    // the relation.
+    let mut witness1 = random_witness(&mut rng, domain_size);
+    let mut witness2 = random_witness(&mut rng, domain_size);
+    let u1 = Fp::rand(&mut rng);
+    let u2 = Fp::rand(&mut rng);
+    set_lookup_tables(&mut witness1, u1);
+    set_lookup_tables(&mut witness2, u2);
+    let alpha = Fp::rand(&mut rng);
+    let error1 = relation.evaluate(&witness1, u1, alpha);
+    let error2 = relation.evaluate(&witness2, u2, alpha);
+    assert_eq!(relation.check(&witness1, u1, alpha, &error1), Ok(()));
+
+    // Fold the instances using the cross-terms computed by the prover
+    let mut computer = CrossTermsComputer::new(ProverConfig::default());
+    let cross_terms =
+        computer.compute_cross_terms(&constraints, &witness1, &witness2, u1, u2, alpha);
+    let r = Fp::rand(&mut rng);
+    let witness: Vec<Vec<Fp>> = witness1
+        .iter()
+        .zip(witness2.iter())
+        .map(|(c1, c2)| c1.iter().zip(c2.iter()).map(|(x, y)| *x + r * y).collect())
+        .collect();
+    let u = u1 + r * u2;
+    let error: Vec<Fp> = (0..domain_size)
+        .map(|row| {
+            let cross_terms_eval = (1..HOMOGENEOUS_DEGREE).fold(Fp::zero(), |acc, power_r| {
+                acc + r.pow([power_r as u64]) * cross_terms[&power_r][row]
+            });
+            error1[row] + cross_terms_eval + r.pow([HOMOGENEOUS_DEGREE as u64]) * error2[row]
+        })
+        .collect();
+    assert_eq!(relation.check(&witness, u, alpha, &error), Ok(()));
+
+    // Tampering with the error term is detected
+    let mut invalid_error = error.clone();
+    invalid_error[2] += Fp::from(1u64);
+    assert_eq!(relation.check(&witness, u, alpha, &invalid_error), Err(2));
+
+    // The tables are fixed: changing an entry is detected, even if the
+    // error term is updated accordingly
+    let mut invalid_witness = witness.clone();
+    invalid_witness[usize::from(Column::LookupTable(LookupTable::RangeCheck16))][1] +=
+        Fp::from(1u64);
+    let invalid_error = relation.evaluate(&invalid_witness, u, alpha);
+    assert_eq!(
+        relation.check(&invalid_witness, u, alpha, &invalid_error),
+        Err(1)
+    );
+}
+
+#[test]
+fn test_folded_relation_combined_expression_is_homogeneous() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let relation = FoldedRelation::new(ivc_constraints());
+    let eval: [Fp; NUMBER_OF_VARIABLES] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let u = Fp::rand(&mut rng);
+    let alpha = Fp::rand(&mut rng);
+    let res = relation.evaluate_row(&eval, u, alpha);
+
+    // Same evaluation as the polynomials used by the prover
+    let expected = relation
+        .polynomials
+        .iter()
+        .rev()
+        .fold(Fp::zero(), |acc, p| {
+            acc * alpha + p.homogeneous_eval(&eval, u)
+        });
+    assert_eq!(res, expected);
+
+    // Scaling the witness and `u` scales the relation by λ^D
+    let lambda = Fp::rand(&mut rng);
+    let scaled_eval = eval.map(|x| lambda * x);
+    assert_eq!(
+        relation.evaluate_row(&scaled_eval, lambda * u, alpha),
+        lambda.pow([HOMOGENEOUS_DEGREE as u64]) * res
+    );
+}
+
+#[test]
+fn test_folded_relation_evaluate_in_circuit() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let relation = FoldedRelation::new(ivc_constraints());
+    let poseidon_mds = poseidon_3_60_0_5_5_fp::static_params().mds.clone();
+    let env = constraints::Env::<Fp>::new(poseidon_mds.to_vec(), BigInt::from(0_usize));
+    let circuit_expr =
+        relation.evaluate_in_circuit(&env, |var| E::<Fp>::cell(var.col, var.row), E::<Fp>::from);
+
+    let eval: [Fp; NUMBER_OF_VARIABLES] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let u = Fp::rand(&mut rng);
+    let alpha = Fp::rand(&mut rng);
+    let circuit_eval = evaluate_expression(
+        &circuit_expr,
+        &|var| eval[var_index(var)],
+        &|chal| match chal {
+            ChallengeTerm::Alpha => alpha,
+            ChallengeTerm::HomogenousChallenge => u,
+            _ => panic!("Unexpected challenge"),
+        },
+        &|x| x,
+    );
+    assert_eq!(circuit_eval, relation.evaluate_row(&eval, u, alpha));
+}
@@ -1,17 +1,22 @@
 use ark_ec::{AffineRepr, Group};
-use ark_ff::{PrimeField, UniformRand};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
 use arrabbiata::{
+    columns::Column,
     interpreter::{self, Instruction, InterpreterEnv},
-    poseidon_3_60_0_5_5_fp,
+    logup::LookupTable,
+    poseidon_3_60_0_5_5_fp, public_io,
+    schedule::Schedule,
     witness::Env,
-    MAXIMUM_FIELD_SIZE_IN_BITS, POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE,
+    MAXIMUM_FIELD_SIZE_IN_BITS, MAX_NUMBER_OF_LOOKUPS_PER_ROW, MAX_NUMBER_OF_PHASES,
+    MIN_SRS_LOG2_SIZE, NUMBER_OF_LOOKUP_COLUMNS, POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE,
 };
-use mina_curves::pasta::{Fp, Fq, Pallas, ProjectivePallas, Vesta};
+use mina_curves::pasta::{Fp, Fq, Pallas, ProjectivePallas, ProjectiveVesta, Vesta};
 use mina_poseidon::{constants::SpongeConstants, permutation::poseidon_block_cipher};
 use num_bigint::{BigInt, ToBigInt};
 use o1_utils::FieldHelpers;
 use poly_commitment::{commitment::CommitmentCurve, PolyComm};
 use rand::{CryptoRng, RngCore};
+use strum::EnumCount as _;
 
 // Used by the mina_poseidon library. Only for testing.
 #[derive(Clone)]
@@ -35,15 +40,16 @@ fn test_unit_witness_poseidon_next_row_gadget_one_full_hash() {
     let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
     let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
         srs_log2_size,
-        BigInt::from(1u64),
+        vec![BigInt::from(1u64)],
         sponge.clone(),
         sponge.clone(),
     );
 
     env.current_instruction = Instruction::Poseidon(0);
 
-    (0..(POSEIDON_ROUNDS_FULL / 5)).for_each(|i| {
-        interpreter::run_ivc(&mut env, Instruction::Poseidon(5 * i));
+    let rounds_per_row = env.poseidon_rounds_per_row();
+    (0..(POSEIDON_ROUNDS_FULL / rounds_per_row)).for_each(|i| {
+        interpreter::run_ivc(&mut env, Instruction::Poseidon(rounds_per_row * i));
         env.reset();
     });
     let exp_output = {
@@ -70,7 +76,51 @@ fn test_unit_witness_poseidon_next_row_gadget_one_full_hash() {
     assert_eq!(env.sponge_e2, sponge.clone());
 
     // Number of rows used by one full hash
-    assert_eq!(env.current_row, 13);
+    assert_eq!(env.current_row, 16);
+}
+
+#[test]
+fn test_unit_witness_poseidon_next_row_gadget_one_full_hash_with_twelve_columns() {
+    let srs_log2_size = 6;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new_with_number_of_columns(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge.clone(),
+        sponge.clone(),
+        12,
+    );
+
+    env.current_instruction = Instruction::Poseidon(0);
+
+    // With 12 columns, 3 rounds are computed per row
+    while env.current_row < POSEIDON_ROUNDS_FULL / 3 {
+        let instr = env.fetch_instruction();
+        interpreter::run_ivc(&mut env, instr);
+        env.current_instruction = env.fetch_next_instruction();
+        env.reset();
+    }
+    let exp_output = {
+        let mut state = sponge
+            .clone()
+            .to_vec()
+            .iter()
+            .map(|x| Fp::from_biguint(&x.to_biguint().unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        state[0] += env.srs_e2.h.x;
+        state[1] += env.srs_e2.h.y;
+        poseidon_block_cipher::<Fp, PlonkSpongeConstants>(
+            poseidon_3_60_0_5_5_fp::static_params(),
+            &mut state,
+        );
+        state
+            .iter()
+            .map(|x| x.to_biguint().into())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(env.sponge_e1.to_vec(), exp_output);
+    // The last row of the hash resets the environment itself
+    assert_eq!(env.current_row, POSEIDON_ROUNDS_FULL / 3 + 1);
 }
 
 #[test]
@@ -79,7 +129,7 @@ fn test_unit_witness_elliptic_curve_addition() {
     let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
     let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
         srs_log2_size,
-        BigInt::from(1u64),
+        vec![BigInt::from(1u64)],
         sponge_e1.clone(),
         sponge_e1.clone(),
     );
@@ -153,7 +203,7 @@ fn test_witness_double_elliptic_curve_point() {
     let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
     let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
         srs_log2_size,
-        BigInt::from(1u64),
+        vec![BigInt::from(1u64)],
         sponge_e1.clone(),
         sponge_e1.clone(),
     );
@@ -189,7 +239,7 @@ where
     let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| r.clone());
     let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
         srs_log2_size,
-        BigInt::from(1u64),
+        vec![BigInt::from(1u64)],
         sponge_e1.clone(),
         sponge_e1.clone(),
     );
@@ -242,3 +292,614 @@ fn test_witness_elliptic_curve_scalar_multiplication() {
     let r: BigInt = Fp::rand(&mut rng).to_biguint().to_bigint().unwrap();
     helper_elliptic_curve_scalar_multiplication(r, &mut rng);
 }
+
+#[test]
+fn test_witness_range_check16_multiplicities() {
+    let srs_log2_size = 6;
+    let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge_e1.clone(),
+        sponge_e1.clone(),
+    );
+
+    let table = LookupTable::RangeCheck16;
+    [3_u64, 3, (1 << 16) - 1].iter().for_each(|v| {
+        let pos = env.allocate();
+        env.write_column(pos, BigInt::from(*v));
+        env.range_check16(pos);
+    });
+    assert_eq!(env.idx_lookup, 3);
+    assert_eq!(env.lookup_values[1][0], Some(BigInt::from(3_u64)));
+    assert_eq!(env.lookup_values[3][0], None);
+    assert_eq!(env.lookup_multiplicities[table as usize][3], 2);
+    assert_eq!(env.lookup_multiplicities[table as usize][(1 << 16) - 1], 1);
+
+    // The index is reset when moving to the next row, but the multiplicities
+    // are kept for the whole iteration.
+    env.reset();
+    assert_eq!(env.idx_lookup, 0);
+    assert_eq!(env.lookup_multiplicities[table as usize][3], 2);
+
+    env.reset_for_next_iteration();
+    assert!(env.lookup_multiplicities[table as usize]
+        .iter()
+        .all(|m| *m == 0));
+    assert!(env
+        .lookup_values
+        .iter()
+        .all(|col| col.iter().all(|v| v.is_none())));
+}
+
+#[test]
+fn test_witness_lookup_columns() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let srs_log2_size = MIN_SRS_LOG2_SIZE;
+    let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge_e1.clone(),
+        sponge_e1.clone(),
+    );
+
+    // Two lookups on the first row, none on the second one and one on the
+    // third one.
+    [vec![3_u64, 42], vec![], vec![3]]
+        .iter()
+        .for_each(|values| {
+            values.iter().for_each(|v| {
+                let pos = env.allocate();
+                env.write_column(pos, BigInt::from(*v));
+                env.range_check16(pos);
+            });
+            env.reset();
+        });
+
+    let beta = Fp::rand(&mut rng);
+    env.compute_lookup_columns(beta.to_biguint().into());
+
+    let column = |col: Column| -> Vec<Fp> {
+        let idx = usize::from(col) - usize::from(Column::LookupInverse(0));
+        env.lookup_columns[idx]
+            .iter()
+            .map(|x| Fp::from_biguint(&x.to_biguint().unwrap()).unwrap())
+            .collect()
+    };
+    let domain_size = 1 << srs_log2_size;
+
+    // φ_i (β + f_i) = 1 for the values looked up, and zero otherwise
+    assert_eq!(
+        column(Column::LookupInverse(0))[0] * (beta + Fp::from(3_u64)),
+        Fp::one()
+    );
+    assert_eq!(
+        column(Column::LookupInverse(1))[0] * (beta + Fp::from(42_u64)),
+        Fp::one()
+    );
+    assert_eq!(column(Column::LookupInverse(1))[2], Fp::zero());
+    assert!((0..MAX_NUMBER_OF_LOOKUPS_PER_ROW)
+        .all(|i| column(Column::LookupInverse(i))[1] == Fp::zero()));
+
+    // The table is written over the whole domain, with its multiplicities
+    let table = LookupTable::RangeCheck16;
+    let t = column(Column::LookupTable(table));
+    let m = column(Column::LookupMultiplicity(table));
+    let psi = column(Column::LookupTableInverse(table));
+    assert_eq!(t.len(), domain_size);
+    assert_eq!(t[42], Fp::from(42_u64));
+    assert_eq!(m[3], Fp::from(2_u64));
+    assert_eq!(psi[3] * (beta + t[3]), m[3]);
+
+    // The challenge is the same on every row
+    assert!(column(Column::LookupChallenge).iter().all(|b| *b == beta));
+
+    // The accumulator wraps around the domain
+    let acc = column(Column::LookupAccumulator);
+    (0..domain_size).for_each(|row| {
+        let sum_phi: Fp = (0..MAX_NUMBER_OF_LOOKUPS_PER_ROW)
+            .map(|i| column(Column::LookupInverse(i))[row])
+            .sum();
+        assert_eq!(acc[(row + 1) % domain_size], acc[row] + sum_phi - psi[row]);
+    });
+}
+
+#[test]
+fn test_witness_lookup_challenge_binds_the_commitments() {
+    let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let lookup_challenge = |value: u64| {
+        let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+            MIN_SRS_LOG2_SIZE,
+            vec![BigInt::from(1u64)],
+            sponge_e1.clone(),
+            sponge_e1.clone(),
+        );
+        let pos = env.allocate();
+        env.write_column(pos, BigInt::from(value));
+        env.range_check16(pos);
+        env.reset();
+        env.compute_and_update_previous_commitments();
+        let beta = env.coin_lookup_challenge();
+
+        // The multiplicities are committed before coining β, the other
+        // columns, except the fixed tables, afterwards
+        assert_eq!(env.previous_lookup_commitments_e1.len(), LookupTable::COUNT);
+        env.compute_lookup_columns(beta.clone());
+        env.compute_lookup_commitments();
+        assert_eq!(
+            env.previous_lookup_commitments_e1.len(),
+            NUMBER_OF_LOOKUP_COLUMNS - LookupTable::COUNT
+        );
+        beta
+    };
+
+    let beta = lookup_challenge(3);
+    assert!(beta < BigInt::from(1_u64) << 128);
+    // Deterministic
+    assert_eq!(beta, lookup_challenge(3));
+    // Another looked-up value changes the witness and the multiplicities,
+    // therefore the challenge
+    assert_ne!(beta, lookup_challenge(4));
+}
+
+#[test]
+#[should_panic]
+fn test_witness_lookup_columns_domain_smaller_than_table() {
+    let srs_log2_size = 6;
+    let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge_e1.clone(),
+        sponge_e1.clone(),
+    );
+    env.compute_lookup_columns(BigInt::from(1_u64));
+}
+
+#[test]
+#[should_panic]
+fn test_witness_range_check16_out_of_range() {
+    let srs_log2_size = 6;
+    let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge_e1.clone(),
+        sponge_e1.clone(),
+    );
+
+    let pos = env.allocate();
+    env.write_column(pos, BigInt::from(1_u64 << 16));
+    env.range_check16(pos);
+}
+
+#[test]
+fn test_witness_ivc_state_chaining() {
+    let srs_log2_size = 6;
+    let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let z0 = vec![BigInt::from(2u64), BigInt::from(3u64)];
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        z0.clone(),
+        sponge_e1.clone(),
+        sponge_e1.clone(),
+    );
+    assert_eq!(env.ivc_state(), z0.as_slice());
+
+    // z_(i + 1) = (z_i[0] * z_i[1], z_i[1])
+    let run_step = |env: &mut Env<Fp, Fq, Vesta, Pallas>| {
+        let positions: Vec<_> = (0..4).map(|_| env.allocate()).collect();
+        let x = env.read_ivc_state(positions[0], 0);
+        let y = env.read_ivc_state(positions[1], 1);
+        env.write_ivc_state(positions[2], 0, x * y.clone());
+        env.write_ivc_state(positions[3], 1, y);
+        env.reset();
+    };
+
+    run_step(&mut env);
+    // The row contains the input and the output of the step
+    assert_eq!(env.witness[0][0], BigInt::from(2u64));
+    assert_eq!(env.witness[1][0], BigInt::from(3u64));
+    assert_eq!(env.witness[2][0], BigInt::from(6u64));
+    assert_eq!(env.witness[3][0], BigInt::from(3u64));
+    // The state is not a public input
+    assert!(env.public_state.iter().all(|v| *v == BigInt::from(0u64)));
+    // The input is not modified before calling compute_output
+    assert_eq!(env.ivc_state(), z0.as_slice());
+
+    env.compute_output();
+    env.reset_for_next_iteration();
+    env.current_iteration += 1;
+    assert_eq!(env.ivc_state(), &[BigInt::from(6u64), BigInt::from(3u64)]);
+
+    run_step(&mut env);
+    env.compute_output();
+    assert_eq!(env.ivc_state(), &[BigInt::from(18u64), BigInt::from(3u64)]);
+    // The initial state is kept
+    assert_eq!(env.z0, z0);
+}
+
+#[test]
+fn test_witness_build_blocks_in_parallel_is_sequential_witness() {
+    let srs_log2_size = 6;
+    let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut seq_env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge_e1.clone(),
+        sponge_e1.clone(),
+    );
+    let mut par_env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge_e1.clone(),
+        sponge_e1.clone(),
+    );
+
+    // Start from a non-zero row to check the offsets are correctly handled
+    let start_row = 3;
+    let nb_rows = 40;
+    (0..start_row).for_each(|_| {
+        seq_env.reset();
+        par_env.reset();
+    });
+
+    (0..nb_rows).for_each(|_| {
+        interpreter::run_app(&mut seq_env);
+        seq_env.reset();
+    });
+
+    par_env.build_blocks_in_parallel(&[7, 13, 1, 19], |env, nb_rows| {
+        (0..nb_rows).for_each(|_| {
+            interpreter::run_app(env);
+            env.reset();
+        })
+    });
+
+    assert_eq!(par_env.current_row, start_row + nb_rows);
+    assert_eq!(par_env.witness, seq_env.witness);
+    assert_eq!(par_env.selectors, seq_env.selectors);
+}
+
+#[test]
+fn test_witness_elliptic_curve_scaling_full_width_multiple_commitments() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let srs_log2_size = 10;
+    let nb_commitments = 3;
+    // A random scalar, fitting both scalar fields
+    let r: BigInt = Fp::rand(&mut rng).to_biguint().to_bigint().unwrap();
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| r.clone());
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge.clone(),
+        sponge.clone(),
+    );
+
+    // Iteration 0: the commitments are points of Pallas
+    let comms_e2: Vec<Pallas> = (0..nb_commitments)
+        .map(|_| {
+            let x = Fq::rand(&mut rng);
+            Pallas::generator().mul_bigint(x.into_bigint()).into()
+        })
+        .collect();
+    comms_e2.iter().enumerate().for_each(|(i, c)| {
+        env.previous_commitments_e2[i] = PolyComm::new(vec![*c]);
+    });
+    env.current_instruction = Instruction::EllipticCurveScaling(0, 0);
+    (0..nb_commitments * MAXIMUM_FIELD_SIZE_IN_BITS as usize).for_each(|_| {
+        let instr = env.fetch_instruction();
+        interpreter::run_ivc(&mut env, instr);
+        env.current_instruction = env.fetch_next_instruction();
+        env.reset();
+    });
+    assert!(matches!(
+        env.current_instruction,
+        Instruction::EllipticCurveScaling(i_comm, 0) if i_comm == nb_commitments
+    ));
+    comms_e2.iter().enumerate().for_each(|(i, c)| {
+        let c: ProjectivePallas = (*c).into();
+        let exp_res: Pallas = (c.mul_bigint(r.to_u64_digits().1) + env.srs_e2.h).into();
+        let exp_x: BigInt = exp_res.x.to_biguint().into();
+        let exp_y: BigInt = exp_res.y.to_biguint().into();
+        assert_eq!(env.scaled_commitments[i], (exp_x, exp_y));
+    });
+
+    // Iteration 1: the commitments are points of Vesta
+    env.reset_for_next_iteration();
+    env.current_iteration += 1;
+    let comms_e1: Vec<Vesta> = (0..nb_commitments)
+        .map(|_| {
+            let x = Fp::rand(&mut rng);
+            Vesta::generator().mul_bigint(x.into_bigint()).into()
+        })
+        .collect();
+    comms_e1.iter().enumerate().for_each(|(i, c)| {
+        env.previous_commitments_e1[i] = PolyComm::new(vec![*c]);
+    });
+    env.current_instruction = Instruction::EllipticCurveScaling(0, 0);
+    (0..nb_commitments * MAXIMUM_FIELD_SIZE_IN_BITS as usize).for_each(|_| {
+        let instr = env.fetch_instruction();
+        interpreter::run_ivc(&mut env, instr);
+        env.current_instruction = env.fetch_next_instruction();
+        env.reset();
+    });
+    comms_e1.iter().enumerate().for_each(|(i, c)| {
+        let c: ProjectiveVesta = (*c).into();
+        let exp_res: Vesta = (c.mul_bigint(r.to_u64_digits().1) + env.srs_e1.h).into();
+        let exp_x: BigInt = exp_res.x.to_biguint().into();
+        let exp_y: BigInt = exp_res.y.to_biguint().into();
+        assert_eq!(env.scaled_commitments[i], (exp_x, exp_y));
+    });
+}
+
+#[test]
+fn test_witness_public_io_hash_is_host_side_hash() {
+    // The hash of the public IO does not fit in 2^8 rows
+    let srs_log2_size = 9;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let z0 = vec![BigInt::from(2u64), BigInt::from(3u64)];
+    let mut env =
+        Env::<Fp, Fq, Vesta, Pallas>::new(srs_log2_size, z0, sponge.clone(), sponge.clone());
+
+    let nb_values = env.values_to_absorb_public_io().len();
+    assert_eq!(
+        nb_values,
+        arrabbiata::number_of_values_to_absorb_public_io(env.number_of_columns, 2, 0)
+    );
+
+    let rounds_per_row = env.poseidon_rounds_per_row();
+    (0..public_io::number_of_poseidon_calls(nb_values)).for_each(|_| {
+        (0..(POSEIDON_ROUNDS_FULL / rounds_per_row)).for_each(|i| {
+            interpreter::run_ivc(&mut env, Instruction::Poseidon(rounds_per_row * i));
+            env.reset();
+        });
+    });
+    interpreter::run_ivc(&mut env, Instruction::PublicIOHash);
+
+    let exp_hash = env.compute_public_io_hash(&sponge);
+    // The hash is the only public input of the gadget
+    assert_eq!(env.public_state[0], exp_hash);
+    assert_eq!(env.public_io_hash, exp_hash);
+
+    // The hash is given to the next iteration, in 2 chunks of 128 bits
+    env.compute_output();
+    let previous_hash =
+        BigInt::from(env.previous_hash[0]) + (BigInt::from(env.previous_hash[1]) << 128);
+    assert_eq!(previous_hash, exp_hash);
+}
+
+#[test]
+fn test_witness_public_io_hash_binds_the_output_state() {
+    let srs_log2_size = 8;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let z0 = vec![BigInt::from(2u64), BigInt::from(3u64)];
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        z0.clone(),
+        sponge.clone(),
+        sponge.clone(),
+    );
+
+    let hash_before = env.compute_public_io_hash(&sponge);
+    // The output is reduced modulo the field of the current iteration
+    let modulus: BigInt = Fp::modulus_biguint().into();
+    let pos = env.allocate();
+    let v = env.write_ivc_state(pos, 1, modulus.clone() + BigInt::from(5u64));
+    assert_eq!(v, BigInt::from(5u64));
+    assert_eq!(env.next_zi, vec![BigInt::from(2u64), BigInt::from(5u64)]);
+    // The hash of the public IO depends on the output state
+    assert_ne!(env.compute_public_io_hash(&sponge), hash_before);
+
+    // The output becomes the input of the next iteration, which absorbs it
+    // as its current state, right after the hash of the previous iteration.
+    interpreter::run_ivc(&mut env, Instruction::PublicIOHash);
+    env.compute_output();
+    env.reset_for_next_iteration();
+    env.current_iteration += 1;
+    let values = env.values_to_absorb_public_io();
+    let idx_zi = env.number_of_columns * 2 + 2 + 1 + z0.len();
+    assert_eq!(values[idx_zi..idx_zi + z0.len()], env.next_zi[..]);
+    assert_eq!(values[idx_zi + 1], BigInt::from(5u64));
+}
+
+#[test]
+fn test_witness_trace_export() {
+    let srs_log2_size = 8;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge.clone(),
+        sponge.clone(),
+    );
+
+    (0..3).for_each(|_| {
+        interpreter::run_app(&mut env);
+        env.reset();
+    });
+    env.current_instruction = Instruction::Poseidon(0);
+    let rounds_per_row = env.poseidon_rounds_per_row();
+    (0..(POSEIDON_ROUNDS_FULL / rounds_per_row)).for_each(|i| {
+        interpreter::run_ivc(&mut env, Instruction::Poseidon(rounds_per_row * i));
+        env.reset();
+    });
+
+    let trace = env.trace();
+    assert_eq!(trace.rows.len(), env.current_row);
+    assert_eq!(trace.column_labels[0], "x[0]");
+    assert!(trace.selector_labels.contains(&"q_app".to_string()));
+
+    let ranges: Vec<(String, usize, usize)> = trace
+        .gadget_ranges
+        .iter()
+        .map(|r| (r.gadget.clone(), r.start, r.end))
+        .collect();
+    // The application does not activate any selector
+    assert_eq!(
+        ranges,
+        vec![(
+            "Poseidon".to_string(),
+            3,
+            3 + POSEIDON_ROUNDS_FULL / rounds_per_row
+        )]
+    );
+
+    let mut csv = vec![];
+    trace.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().starts_with("row,x[0],x[1]"));
+    assert_eq!(lines.count(), trace.rows.len());
+
+    let mut json = vec![];
+    trace.write_json(&mut json).unwrap();
+    let decoded: arrabbiata::trace::Trace = serde_json::from_slice(&json).unwrap();
+    assert_eq!(decoded, trace);
+}
+
+#[test]
+fn test_witness_stream_hash_across_iterations() {
+    let srs_log2_size = 8;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    // The sponge state of the stream hash is carried in the IVC state
+    let z0: Vec<BigInt> = vec![BigInt::from(1u64), BigInt::from(2u64), BigInt::from(3u64)];
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        z0.clone(),
+        sponge.clone(),
+        sponge.clone(),
+    );
+
+    let first_chunk: Vec<BigInt> = (10..13).map(BigInt::from).collect();
+    env.set_stream_values(first_chunk.clone());
+    interpreter::run_stream_hash(&mut env, first_chunk.len());
+    env.compute_output();
+
+    // A different number of values is absorbed in the second run. The
+    // iteration is kept to stay in the same field.
+    let second_chunk: Vec<BigInt> = vec![BigInt::from(13u64)];
+    env.set_stream_values(second_chunk.clone());
+    interpreter::run_stream_hash(&mut env, second_chunk.len());
+    env.compute_output();
+
+    let to_fp = |x: &BigInt| Fp::from(x.to_biguint().unwrap());
+    let mut exp_state: [Fp; POSEIDON_STATE_SIZE] = std::array::from_fn(|i| to_fp(&z0[i]));
+    let params = poseidon_3_60_0_5_5_fp::static_params();
+    let first_chunk: Vec<Fp> = first_chunk.iter().map(to_fp).collect();
+    public_io::absorb(params, &mut exp_state, &first_chunk);
+    let second_chunk: Vec<Fp> = second_chunk.iter().map(to_fp).collect();
+    public_io::absorb(params, &mut exp_state, &second_chunk);
+
+    let state: Vec<Fp> = env.zi.iter().map(to_fp).collect();
+    assert_eq!(state, exp_state.to_vec());
+}
+
+#[test]
+fn test_witness_hints() {
+    let srs_log2_size = 6;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge.clone(),
+        sponge.clone(),
+    );
+
+    let x = BigInt::from(13u64);
+    let pos = env.allocate();
+    let inv = env.hint_inverse(pos, x.clone());
+    assert_eq!(
+        Fp::from(13u64).inverse().unwrap(),
+        Fp::from(inv.to_biguint().unwrap())
+    );
+
+    let pos = env.allocate();
+    let root = env.hint_square_root(pos, BigInt::from(16u64));
+    let root = Fp::from(root.to_biguint().unwrap());
+    assert_eq!(root * root, Fp::from(16u64));
+
+    let positions: Vec<_> = (0..4).map(|_| env.allocate()).collect();
+    let bits = env.hint_bit_decomposition(x, &positions);
+    assert_eq!(
+        bits,
+        [1u64, 0, 1, 1]
+            .into_iter()
+            .map(BigInt::from)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_witness_hint_bit_decomposition_too_many_bits() {
+    let srs_log2_size = 6;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        srs_log2_size,
+        vec![BigInt::from(1u64)],
+        sponge.clone(),
+        sponge.clone(),
+    );
+
+    // 17 does not fit in 4 bits, the recomposition must fail
+    let positions: Vec<_> = (0..4).map(|_| env.allocate()).collect();
+    env.hint_bit_decomposition(BigInt::from(17u64), &positions);
+}
+
+#[test]
+fn test_witness_program_counter_follows_schedule() {
+    let srs_log2_size = 6;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    // The program counter is carried in the IVC state at the indices 1 and 2
+    let z0: Vec<BigInt> = vec![BigInt::from(7u64), BigInt::from(0u64), BigInt::from(0u64)];
+    let mut env =
+        Env::<Fp, Fq, Vesta, Pallas>::new(srs_log2_size, z0, sponge.clone(), sponge.clone());
+    let schedule = Schedule::new(vec![
+        (Instruction::Poseidon(0), 2),
+        (Instruction::EllipticCurveScaling(0, 0), 1),
+    ]);
+    env.set_schedule(schedule.clone());
+
+    let mut exp_pc = (0_usize, 0_u64);
+    let mut instructions = vec![];
+    (0..5).for_each(|_| {
+        instructions.push(env.fetch_scheduled_instruction(1).map(|i| format!("{i:?}")));
+        interpreter::run_ivc(&mut env, Instruction::ProgramCounter(1));
+        env.reset();
+        // The iteration is kept to stay in the same field
+        env.compute_output();
+        exp_pc = schedule.next(exp_pc.0, exp_pc.1);
+        assert_eq!(env.zi[1], BigInt::from(exp_pc.0));
+        assert_eq!(env.zi[2], BigInt::from(exp_pc.1));
+        // The rest of the state is untouched
+        assert_eq!(env.zi[0], BigInt::from(7u64));
+    });
+    assert_eq!(
+        instructions,
+        vec![
+            Some("Poseidon(0)".to_string()),
+            Some("Poseidon(0)".to_string()),
+            Some("EllipticCurveScaling(0, 0)".to_string()),
+            None,
+            None
+        ]
+    );
+    // All the phases have been executed, the program counter stays in the
+    // final phase.
+    assert_eq!(env.zi[1], BigInt::from(2u64));
+}
+
+#[test]
+#[should_panic]
+fn test_witness_program_counter_invalid_phase() {
+    let srs_log2_size = 6;
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    // The phase is out of the range supported by the gadget
+    let z0: Vec<BigInt> = vec![BigInt::from(MAX_NUMBER_OF_PHASES), BigInt::from(0u64)];
+    let mut env =
+        Env::<Fp, Fq, Vesta, Pallas>::new(srs_log2_size, z0, sponge.clone(), sponge.clone());
+    env.set_schedule(Schedule::new(vec![(Instruction::Poseidon(0), 2)]));
+    interpreter::run_ivc(&mut env, Instruction::ProgramCounter(0));
+}
@@ -13,7 +13,7 @@ use o1_utils::FieldHelpers;
 fn test_constrain_boolean_witness_negative_value() {
     let srs_log2_size = 2;
     let mut env = {
-        let z0 = BigInt::from(1u64);
+        let z0 = vec![BigInt::from(1u64)];
         let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(0u64));
         Env::<Fp, Fq, Vesta, Pallas>::new(srs_log2_size, z0, sponge_e1.clone(), sponge_e1.clone())
     };
@@ -25,7 +25,7 @@ fn test_constrain_boolean_witness_negative_value() {
 fn test_constrain_boolean_witness_positive_and_negative_modulus() {
     let srs_log2_size = 2;
     let mut env = {
-        let z0 = BigInt::from(1u64);
+        let z0 = vec![BigInt::from(1u64)];
         let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(0u64));
         Env::<Fp, Fq, Vesta, Pallas>::new(srs_log2_size, z0, sponge_e1.clone(), sponge_e1.clone())
     };
@@ -43,7 +43,7 @@ fn test_write_column_return_the_result_reduced_in_field() {
     let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
     let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
         srs_log2_size,
-        BigInt::from(1u64),
+        vec![BigInt::from(1u64)],
         sponge_e1.clone(),
         sponge_e1.clone(),
     );
@@ -60,7 +60,7 @@ fn test_write_public_return_the_result_reduced_in_field() {
     let sponge_e1: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
     let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
         srs_log2_size,
-        BigInt::from(1u64),
+        vec![BigInt::from(1u64)],
         sponge_e1.clone(),
         sponge_e1.clone(),
     );
@@ -0,0 +1,51 @@
+//! The Grumpkin curve, `y^2 = x^3 - 17`, forming a cycle with BN254: it is
+//! defined over the scalar field of BN254, and its scalar field is the base
+//! field of BN254. It is not provided by arkworks 0.4.
+//!
+//! The scalar field of Grumpkin, i.e. the base field of BN254, has a 2-adicity
+//! of 1. Therefore, it does not contain any multiplicative subgroup of size
+//! larger than 2 to be used as an evaluation domain.
+
+use ark_bn254::{Fq, Fr};
+use ark_ec::{
+    models::short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveConfig,
+};
+use ark_ff::MontFp;
+
+/// G_GENERATOR_X =
+/// 1
+pub const G_GENERATOR_X: Fr = MontFp!("1");
+
+/// G_GENERATOR_Y =
+/// sqrt(-16) =
+/// 17631683881184975370165255887551781615748388533673675138860
+pub const G_GENERATOR_Y: Fr =
+    MontFp!("17631683881184975370165255887551781615748388533673675138860");
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GrumpkinParameters;
+
+impl CurveConfig for GrumpkinParameters {
+    type BaseField = Fr;
+
+    type ScalarField = Fq;
+
+    /// COFACTOR = 1
+    const COFACTOR: &'static [u64] = &[0x1];
+
+    /// COFACTOR_INV = 1
+    const COFACTOR_INV: Fq = MontFp!("1");
+}
+
+pub type Grumpkin = Affine<GrumpkinParameters>;
+
+pub type ProjectiveGrumpkin = Projective<GrumpkinParameters>;
+
+impl SWCurveConfig for GrumpkinParameters {
+    const COEFF_A: Self::BaseField = MontFp!("0");
+
+    const COEFF_B: Self::BaseField = MontFp!("-17");
+
+    const GENERATOR: Affine<Self> = Affine::new_unchecked(G_GENERATOR_X, G_GENERATOR_Y);
+}
@@ -1 +1,2 @@
+pub mod grumpkin;
 pub mod pasta;
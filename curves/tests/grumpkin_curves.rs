@@ -0,0 +1,19 @@
+use ark_algebra_test_templates::*;
+use ark_ec::{AffineRepr, Group};
+use ark_ff::PrimeField;
+use mina_curves::grumpkin::{Grumpkin, ProjectiveGrumpkin};
+
+test_group!(g1; ProjectiveGrumpkin; sw);
+
+#[test]
+fn test_grumpkin_generator() {
+    let g = Grumpkin::generator();
+    assert!(g.is_on_curve());
+    assert!(g.is_in_correct_subgroup_assuming_on_curve());
+    // The order of the curve is the modulus of the base field of BN254
+    let order = <ark_bn254::Fq as PrimeField>::MODULUS;
+    assert_eq!(
+        ProjectiveGrumpkin::from(g).mul_bigint(order),
+        ProjectiveGrumpkin::default()
+    );
+}
@@ -0,0 +1,219 @@
+//! Benchmarks the computation of the cross terms, which dominates the cost of
+//! folding a pair of instances.
+//! The constraints are degree 2 relations sharing the same monomials, like the
+//! constraints of a circuit reusing the same products in several gates.
+//! Run with
+//! ```text
+//! cargo bench -p folding --bench cross_terms
+//! ```
+
+use ark_ff::{One, UniformRand};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, SamplingMode};
+use folding::{
+    checker::Column, expressions::FoldingCompatibleExprInner, instance_witness::Foldable, Alphas,
+    FoldingCompatibleExpr, FoldingConfig, FoldingEnv, FoldingScheme, Instance, Side, Witness,
+};
+use kimchi::{
+    circuits::{expr::Variable, gate::CurrOrNext},
+    curve::KimchiCurve,
+};
+use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge, FqSponge};
+use poly_commitment::{ipa::SRS, SRS as _};
+use rand::thread_rng;
+
+type Fp = ark_bn254::Fr;
+type Curve = ark_bn254::G1Affine;
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>;
+
+/// Number of witness columns
+const N_COL: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BenchInstance {
+    commitments: [Curve; N_COL],
+    alphas: Alphas<Fp>,
+}
+
+impl Foldable<Fp> for BenchInstance {
+    fn combine(a: Self, b: Self, challenge: Fp) -> Self {
+        BenchInstance {
+            commitments: std::array::from_fn(|i| {
+                (a.commitments[i] + b.commitments[i] * challenge).into()
+            }),
+            alphas: Alphas::combine(a.alphas, b.alphas, challenge),
+        }
+    }
+}
+
+impl Instance<Curve> for BenchInstance {
+    fn to_absorb(&self) -> (Vec<Fp>, Vec<Curve>) {
+        (self.alphas.clone().powers(), self.commitments.to_vec())
+    }
+
+    fn get_alphas(&self) -> &Alphas<Fp> {
+        &self.alphas
+    }
+
+    fn get_blinder(&self) -> Fp {
+        Fp::one()
+    }
+}
+
+#[derive(Clone)]
+struct BenchWitness([Evaluations<Fp, Radix2EvaluationDomain<Fp>>; N_COL]);
+
+impl Foldable<Fp> for BenchWitness {
+    fn combine(mut a: Self, b: Self, challenge: Fp) -> Self {
+        for (a, b) in a.0.iter_mut().zip(b.0) {
+            for (a, b) in a.evals.iter_mut().zip(b.evals) {
+                *a += challenge * b;
+            }
+        }
+        a
+    }
+}
+
+impl Witness<Curve> for BenchWitness {}
+
+struct BenchEnv {
+    witnesses: [BenchWitness; 2],
+    next_witnesses: [BenchWitness; 2],
+}
+
+impl FoldingEnv<Fp, BenchInstance, BenchWitness, Column, (), ()> for BenchEnv {
+    type Structure = ();
+
+    fn new(
+        _structure: &(),
+        _instances: [&BenchInstance; 2],
+        witnesses: [&BenchWitness; 2],
+    ) -> Self {
+        let witnesses = [witnesses[0].clone(), witnesses[1].clone()];
+        let mut next_witnesses = witnesses.clone();
+        for side in next_witnesses.iter_mut() {
+            for col in side.0.iter_mut() {
+                col.evals.rotate_left(1);
+            }
+        }
+        BenchEnv {
+            witnesses,
+            next_witnesses,
+        }
+    }
+
+    fn col(&self, col: Column, curr_or_next: CurrOrNext, side: Side) -> &[Fp] {
+        let witness = match curr_or_next {
+            CurrOrNext::Curr => &self.witnesses[side as usize],
+            CurrOrNext::Next => &self.next_witnesses[side as usize],
+        };
+        match col {
+            Column::X(i) => &witness.0[i].evals,
+            Column::Selector(_) => unreachable!(),
+        }
+    }
+
+    fn challenge(&self, _challenge: (), _side: Side) -> Fp {
+        unreachable!()
+    }
+
+    fn selector(&self, _s: &(), _side: Side) -> &[Fp] {
+        unreachable!()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct BenchFoldingConfig;
+
+impl FoldingConfig for BenchFoldingConfig {
+    type Structure = ();
+    type Column = Column;
+    type Selector = ();
+    type Challenge = ();
+    type Curve = Curve;
+    type Srs = SRS<Curve>;
+    type Instance = BenchInstance;
+    type Witness = BenchWitness;
+    type Env = BenchEnv;
+}
+
+/// The constraints `x_i x_{i + 1} - x_{j}` and `x_i x_{i + 1} + x_{i} - x_{j}`
+/// for all the pairs of columns: each product appears in two constraints.
+fn constraints() -> Vec<FoldingCompatibleExpr<BenchFoldingConfig>> {
+    let x = |i| {
+        Box::new(FoldingCompatibleExpr::Atom(
+            FoldingCompatibleExprInner::Cell(Variable {
+                col: Column::X(i),
+                row: CurrOrNext::Curr,
+            }),
+        ))
+    };
+    (0..N_COL / 2)
+        .flat_map(|i| {
+            let product = FoldingCompatibleExpr::Mul(x(i), x(i + 1));
+            let mul = FoldingCompatibleExpr::Sub(product.clone().into(), x(N_COL / 2 + i));
+            let mul_add = FoldingCompatibleExpr::Add(product.into(), x(i));
+            let mul_add = FoldingCompatibleExpr::Sub(mul_add.into(), x(N_COL / 2 + i));
+            [mul, mul_add]
+        })
+        .collect()
+}
+
+fn random_pair(
+    srs: &SRS<Curve>,
+    domain: Radix2EvaluationDomain<Fp>,
+) -> (BenchInstance, BenchWitness) {
+    let mut rng = thread_rng();
+    let witness = BenchWitness(std::array::from_fn(|_| {
+        Evaluations::from_vec_and_domain(
+            (0..domain.size()).map(|_| Fp::rand(&mut rng)).collect(),
+            domain,
+        )
+    }));
+    let commitments = std::array::from_fn(|i| {
+        srs.commit_evaluations_non_hiding(domain, &witness.0[i])
+            .get_first_chunk()
+    });
+    let instance = BenchInstance {
+        commitments,
+        alphas: Alphas::new(Fp::rand(&mut rng)),
+    };
+    (instance, witness)
+}
+
+pub fn bench_cross_terms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Cross terms");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat);
+
+    for log_size in [10, 12] {
+        let domain = Radix2EvaluationDomain::<Fp>::new(1 << log_size).unwrap();
+        let srs = SRS::<Curve>::create(domain.size());
+        srs.get_lagrange_basis(domain);
+        let (scheme, _) =
+            FoldingScheme::<BenchFoldingConfig>::new(constraints(), &srs, domain, &());
+        let left = random_pair(&srs, domain);
+        let right = random_pair(&srs, domain);
+
+        group.bench_function(
+            format!("fold instance witness pair (domain size 2^{log_size}, {N_COL} columns)"),
+            |b| {
+                b.iter_batched(
+                    || (left.clone(), right.clone()),
+                    |(left, right)| {
+                        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+                        black_box(
+                            scheme
+                                .fold_instance_witness_pair(left, right, &mut fq_sponge)
+                                .unwrap(),
+                        )
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+}
+
+criterion_group!(benches, bench_cross_terms);
+criterion_main!(benches);
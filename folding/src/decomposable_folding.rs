@@ -5,15 +5,19 @@
 //! selector, as opposed to a single list of constraints.
 
 use crate::{
+    check_alphas, check_chunks,
     columns::ExtendedFoldingColumn,
+    commit_cross_term,
     error_term::{compute_error, ExtendedEnv},
     expressions::{ExpExtension, FoldingCompatibleExpr, FoldingCompatibleExprInner, FoldingExp},
     instance_witness::{RelaxableInstance, RelaxablePair, RelaxedInstance, RelaxedWitness},
-    BaseField, FoldingConfig, FoldingOutput, FoldingScheme, ScalarField,
+    squeeze_and_combine, BaseField, FoldingConfig, FoldingError, FoldingOutput, FoldingScheme,
+    ScalarField,
 };
+use ark_ff::One;
 use ark_poly::{Evaluations, Radix2EvaluationDomain};
 use mina_poseidon::FqSponge;
-use poly_commitment::{PolyComm, SRS};
+use poly_commitment::{commitment::EndoCurve, PolyComm};
 use std::collections::BTreeMap;
 
 pub struct DecomposableFoldingScheme<'a, CF: FoldingConfig> {
@@ -59,6 +63,14 @@ impl<'a, CF: FoldingConfig> DecomposableFoldingScheme<'a, CF> {
         (DecomposableFoldingScheme { inner }, exp)
     }
 
+    /// Derives the folding challenges from endoscalars, see
+    /// [FoldingScheme::with_endo_challenges]
+    pub fn with_endo_challenges(self, endo_q: BaseField<CF>, endo_r: ScalarField<CF>) -> Self {
+        DecomposableFoldingScheme {
+            inner: self.inner.with_endo_challenges(endo_q, endo_r),
+        }
+    }
+
     /// Return the number of additional columns added by quadraticization
     pub fn get_number_of_additional_columns(&self) -> usize {
         self.inner.get_number_of_additional_columns()
@@ -68,14 +80,15 @@ impl<'a, CF: FoldingConfig> DecomposableFoldingScheme<'a, CF> {
     /// folding with a selector will assume that only the selector in question
     /// is enabled (i.e. set to 1) in all rows, and any other selector is 0 over
     /// all rows.
-    /// If that is not the case, providing `None` will fold without assumptions
+    /// If that is not the case, providing `None` will fold without assumptions.
+    /// The errors are the ones of [FoldingScheme::fold_instance_witness_pair].
     pub fn fold_instance_witness_pair<A, B, Sponge>(
         &self,
         a: A,
         b: B,
         selector: Option<CF::Selector>,
         fq_sponge: &mut Sponge,
-    ) -> FoldingOutput<CF>
+    ) -> Result<FoldingOutput<CF>, FoldingError>
     where
         A: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
         B: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
@@ -84,6 +97,9 @@ impl<'a, CF: FoldingConfig> DecomposableFoldingScheme<'a, CF> {
         let scheme = &self.inner;
         let a = a.relax(&scheme.zero_vec);
         let b = b.relax(&scheme.zero_vec);
+        let n_alphas = scheme.expression.number_of_alphas();
+        check_alphas(&a.0.extended_instance, n_alphas)?;
+        check_alphas(&b.0.extended_instance, n_alphas)?;
 
         let u = (a.0.u, b.0.u);
 
@@ -96,24 +112,19 @@ impl<'a, CF: FoldingConfig> DecomposableFoldingScheme<'a, CF> {
             scheme.domain,
             selector,
         );
+        env.check_columns(&scheme.expression)?;
 
-        let env = env.compute_extension(&scheme.extended_witness_generator, scheme.srs);
+        let env = env.compute_extension(&scheme.extended_witness_generator, scheme.srs)?;
         let error = compute_error(&scheme.expression, &env, u);
         let error_evals = error.map(|e| Evaluations::from_vec_and_domain(e, scheme.domain));
 
-        let error_commitments = error_evals
-            .iter()
-            .map(|e| scheme.srs.commit_evaluations_non_hiding(scheme.domain, e))
-            .collect::<Vec<_>>();
-        let error_commitments: [PolyComm<CF::Curve>; 2] = error_commitments.try_into().unwrap();
-
-        let error = error_evals.into_iter().map(|e| e.evals).collect::<Vec<_>>();
-        let error: [Vec<_>; 2] = error.try_into().unwrap();
+        let t_blinders = [ScalarField::<CF>::one(); 2];
+        let error_commitments: [PolyComm<CF::Curve>; 2] = [
+            commit_cross_term(scheme.srs, scheme.domain, &error_evals[0], t_blinders[0])?,
+            commit_cross_term(scheme.srs, scheme.domain, &error_evals[1], t_blinders[1])?,
+        ];
 
-        // sanity check to verify that we only have one commitment in polycomm
-        // (i.e. domain = poly size)
-        assert_eq!(error_commitments[0].len(), 1);
-        assert_eq!(error_commitments[1].len(), 1);
+        let error: [Vec<_>; 2] = error_evals.map(|e| e.evals);
 
         let t0 = &error_commitments[0].get_first_chunk();
         let t1 = &error_commitments[1].get_first_chunk();
@@ -122,7 +133,7 @@ impl<'a, CF: FoldingConfig> DecomposableFoldingScheme<'a, CF> {
         fq_sponge.absorb_fr(&to_absorb.0);
         fq_sponge.absorb_g(&to_absorb.1);
 
-        let challenge = fq_sponge.challenge();
+        let challenge = scheme.squeeze_challenge(fq_sponge);
 
         let (
             [relaxed_extended_left_instance, relaxed_extended_right_instance],
@@ -135,23 +146,25 @@ impl<'a, CF: FoldingConfig> DecomposableFoldingScheme<'a, CF> {
             relaxed_extended_right_instance.clone(),
             challenge,
             &error_commitments,
+            &t_blinders,
         );
 
         let folded_witness = RelaxedWitness::combine_and_sub_cross_terms(
             relaxed_extended_left_witness,
             relaxed_extended_right_witness,
             challenge,
-            error,
+            &error,
         );
-        FoldingOutput {
+        Ok(FoldingOutput {
             folded_instance,
             folded_witness,
             t_0: error_commitments[0].clone(),
             t_1: error_commitments[1].clone(),
+            t_blinders,
             relaxed_extended_left_instance,
             relaxed_extended_right_instance,
             to_absorb,
-        }
+        })
     }
 
     /// Fold two relaxable instances into a relaxed instance.
@@ -164,19 +177,17 @@ impl<'a, CF: FoldingConfig> DecomposableFoldingScheme<'a, CF> {
         b: B,
         error_commitments: [PolyComm<CF::Curve>; 2],
         fq_sponge: &mut Sponge,
-    ) -> RelaxedInstance<CF::Curve, CF::Instance>
+    ) -> Result<RelaxedInstance<CF::Curve, CF::Instance>, FoldingError>
     where
         A: RelaxableInstance<CF::Curve, CF::Instance>,
         B: RelaxableInstance<CF::Curve, CF::Instance>,
         Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+        CF::Curve: EndoCurve,
     {
         let a: RelaxedInstance<CF::Curve, CF::Instance> = a.relax();
         let b: RelaxedInstance<CF::Curve, CF::Instance> = b.relax();
 
-        // sanity check to verify that we only have one commitment in polycomm
-        // (i.e. domain = poly size)
-        assert_eq!(error_commitments[0].len(), 1);
-        assert_eq!(error_commitments[1].len(), 1);
+        check_chunks(&error_commitments)?;
 
         let to_absorb = {
             let mut left = a.to_absorb();
@@ -193,9 +204,14 @@ impl<'a, CF: FoldingConfig> DecomposableFoldingScheme<'a, CF> {
         fq_sponge.absorb_fr(&to_absorb.0);
         fq_sponge.absorb_g(&to_absorb.1);
 
-        let challenge = fq_sponge.challenge();
-
-        RelaxedInstance::combine_and_sub_cross_terms(a, b, challenge, &error_commitments)
+        Ok(squeeze_and_combine(
+            fq_sponge,
+            self.inner.endo,
+            a,
+            b,
+            &error_commitments,
+            &[ScalarField::<CF>::one(); 2],
+        ))
     }
 }
 
@@ -0,0 +1,34 @@
+//! This module defines the errors that can arise when folding instances, or
+//! when verifying a folding.
+
+use poly_commitment::error::CommitmentError;
+use thiserror::Error;
+
+/// Errors that can arise when folding instances and their witnesses, or when
+/// verifying a folding
+#[derive(Error, Debug, Clone)]
+pub enum FoldingError {
+    #[error("the evaluations have size {0}, while the domain has size {1}")]
+    WitnessSizeMismatch(usize, usize),
+
+    #[error("the instance does not provide the {0} alphas required by the constraints")]
+    MissingAlphas(usize),
+
+    #[error("the commitment has {0} chunks, while only one is supported (the domain must not be larger than the SRS)")]
+    CommitmentChunks(usize),
+
+    #[error("{0} cross terms were given, while {1} are required")]
+    CrossTermsCount(usize, usize),
+
+    #[error("the accumulator has {0} points β, while {1} are required")]
+    BetasCount(usize, usize),
+
+    #[error("the proof has {0} coefficients, while {1} are required")]
+    CoefficientsCount(usize, usize),
+
+    #[error("at least one instance must be folded")]
+    NoInstance,
+
+    #[error("the commitment could not be computed: {0}")]
+    Commitment(#[from] CommitmentError),
+}
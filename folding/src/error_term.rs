@@ -3,24 +3,27 @@
 //! module](crate::expressions).
 
 use crate::{
+    check_chunks,
     columns::ExtendedFoldingColumn,
     decomposable_folding::check_selector,
     eval_leaf::EvalLeaf,
     expressions::{Degree, FoldingExp, IntegratedFoldingExpr, Sign},
     quadraticization::ExtendedWitnessGenerator,
-    FoldingConfig, FoldingEnv, Instance, RelaxedInstance, RelaxedWitness, ScalarField,
+    FoldingConfig, FoldingEnv, FoldingError, Instance, RelaxedInstance, RelaxedWitness,
+    ScalarField,
 };
 use ark_ff::{Field, One, Zero};
-use ark_poly::{Evaluations, Radix2EvaluationDomain};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
 use kimchi::circuits::expr::Variable;
 use poly_commitment::{PolyComm, SRS};
+use std::{cell::RefCell, collections::HashMap};
 
 // FIXME: for optimisation, as values are not necessarily Fp elements and are
 // relatively small, we could get rid of the scalar field objects, and only use
 // bigint where we only apply the modulus when needed.
 
 /// This type refers to the two instances to be folded
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Side {
     Left = 0,
     Right = 1,
@@ -35,6 +38,15 @@ impl Side {
     }
 }
 
+/// The two ways the monomials are evaluated when computing the error terms
+#[derive(Clone, Copy)]
+enum Evaluation {
+    /// See [eval_sided]
+    Sided = 0,
+    /// See [eval_exp_error]
+    Error = 1,
+}
+
 /// Evaluates the expression in the provided side
 pub(crate) fn eval_sided<'a, C: FoldingConfig>(
     exp: &FoldingExp<C>,
@@ -43,6 +55,21 @@ pub(crate) fn eval_sided<'a, C: FoldingConfig>(
 ) -> EvalLeaf<'a, ScalarField<C>> {
     use FoldingExp::*;
 
+    match exp {
+        Mul(_, _) | Pow(_, _) | Square(_) => env.cached(exp, side, Evaluation::Sided, || {
+            eval_sided_uncached(exp, env, side)
+        }),
+        _ => eval_sided_uncached(exp, env, side),
+    }
+}
+
+fn eval_sided_uncached<'a, C: FoldingConfig>(
+    exp: &FoldingExp<C>,
+    env: &'a ExtendedEnv<C>,
+    side: Side,
+) -> EvalLeaf<'a, ScalarField<C>> {
+    use FoldingExp::*;
+
     match exp {
         Atom(col) => env.col(col, side),
         Double(e) => {
@@ -107,6 +134,21 @@ pub(crate) fn eval_exp_error<'a, C: FoldingConfig>(
 ) -> EvalLeaf<'a, ScalarField<C>> {
     use FoldingExp::*;
 
+    match exp {
+        Mul(_, _) | Pow(_, _) | Square(_) => env.cached(exp, side, Evaluation::Error, || {
+            eval_exp_error_uncached(exp, env, side)
+        }),
+        _ => eval_exp_error_uncached(exp, env, side),
+    }
+}
+
+fn eval_exp_error_uncached<'a, C: FoldingConfig>(
+    exp: &FoldingExp<C>,
+    env: &'a ExtendedEnv<C>,
+    side: Side,
+) -> EvalLeaf<'a, ScalarField<C>> {
+    use FoldingExp::*;
+
     match exp {
         Atom(col) => env.col(col, side),
         Double(e) => {
@@ -199,6 +241,7 @@ pub(crate) fn compute_error<C: FoldingConfig>(
     // 9th for a multiplication on the scalar field
     // Indirections are also costly, so we should avoid them as much as
     // possible, and inline code.
+    env.cache_monomials(exp.shared_monomials());
     let (ul, ur) = (u.0, u.1);
     let u_cross = ul * ur;
     let zero_vec = vec![ScalarField::<C>::zero(); env.domain.size as usize];
@@ -293,8 +336,14 @@ pub(crate) struct ExtendedEnv<CF: FoldingConfig> {
     witnesses: [RelaxedWitness<CF::Curve, CF::Witness>; 2],
     domain: Radix2EvaluationDomain<ScalarField<CF>>,
     selector: Option<CF::Selector>,
+    /// The evaluations of the monomials shared by several terms of the
+    /// expression, indexed by [Evaluation] and [Side]. Only the registered
+    /// monomials are cached, see [ExtendedEnv::cache_monomials].
+    monomials: RefCell<HashMap<FoldingExp<CF>, MonomialEvals<ScalarField<CF>>>>,
 }
 
+type MonomialEvals<F> = [[Option<Vec<F>>; 2]; 2];
+
 impl<CF: FoldingConfig> ExtendedEnv<CF> {
     pub fn new(
         structure: &CF::Structure,
@@ -319,6 +368,51 @@ impl<CF: FoldingConfig> ExtendedEnv<CF> {
             witnesses,
             domain,
             selector,
+            monomials: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Registers monomials whose evaluations are kept during the fold, as
+    /// they are used several times
+    pub fn cache_monomials<'e>(&self, monomials: impl IntoIterator<Item = &'e FoldingExp<CF>>)
+    where
+        CF: 'e,
+    {
+        let mut cache = self.monomials.borrow_mut();
+        for monomial in monomials {
+            cache.entry(monomial.clone()).or_default();
+        }
+    }
+
+    /// Returns the evaluations of the monomial if they have been cached,
+    /// otherwise computes them with `eval`, and caches them if the monomial
+    /// has been registered
+    fn cached<'a>(
+        &'a self,
+        exp: &FoldingExp<CF>,
+        side: Side,
+        evaluation: Evaluation,
+        eval: impl FnOnce() -> EvalLeaf<'a, ScalarField<CF>>,
+    ) -> EvalLeaf<'a, ScalarField<CF>> {
+        let (i, j) = (evaluation as usize, side as usize);
+        let cached = self
+            .monomials
+            .borrow()
+            .get(exp)
+            .map(|evals| evals[i][j].clone());
+        match cached {
+            // Not shared with other terms
+            None => eval(),
+            Some(Some(evals)) => EvalLeaf::Result(evals),
+            Some(None) => {
+                let res = eval();
+                if let EvalLeaf::Result(evals) = &res {
+                    if let Some(cached) = self.monomials.borrow_mut().get_mut(exp) {
+                        cached[i][j] = Some(evals.clone());
+                    }
+                }
+                res
+            }
         }
     }
 
@@ -400,17 +494,40 @@ impl<CF: FoldingConfig> ExtendedEnv<CF> {
         }
     }
 
+    /// Checks that the columns of the expression have the size of the domain,
+    /// on both sides, as the evaluations would be truncated otherwise.
+    pub(crate) fn check_columns(
+        &self,
+        exp: &IntegratedFoldingExpr<CF>,
+    ) -> Result<(), FoldingError> {
+        use ExtendedFoldingColumn::*;
+        let size = self.domain.size();
+        for col in exp.atoms() {
+            for side in [Side::Left, Side::Right] {
+                let len = match col {
+                    Inner(Variable { col, row }) => self.inner.col(*col, *row, side).len(),
+                    Selector(s) => self.inner.selector(s, side).len(),
+                    WitnessExtended(_) | Error | Constant(_) | Challenge(_) | Alpha(_) => size,
+                };
+                if len != size {
+                    return Err(FoldingError::WitnessSizeMismatch(len, size));
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Computes the extended witness column and the corresponding commitments,
     /// updating the innner instance/witness pairs
     pub fn compute_extension(
         self,
         witness_generator: &ExtendedWitnessGenerator<CF>,
         srs: &CF::Srs,
-    ) -> Self {
+    ) -> Result<Self, FoldingError> {
         let env = self;
         let env = witness_generator.compute_extended_witness(env, Side::Left);
         let env = witness_generator.compute_extended_witness(env, Side::Right);
-        let env = env.compute_extended_commitments(srs, Side::Left);
+        let env = env.compute_extended_commitments(srs, Side::Left)?;
         env.compute_extended_commitments(srs, Side::Right)
     }
 
@@ -422,31 +539,35 @@ impl<CF: FoldingConfig> ExtendedEnv<CF> {
     /// Note that this function is only going to be called on the left instance
     /// once. When we fold the second time, the left instance will already be
     /// relaxed and will have the extended columns.
-    /// Therefore, the blinder is always the one provided by the user, and it is
-    /// saved in the field `blinder` in the case of a relaxed instance that has
-    /// been built from a non-relaxed one.
-    fn compute_extended_commitments(mut self, srs: &CF::Srs, side: Side) -> Self {
+    /// Therefore, the blinder is always the one provided by the user, i.e. the
+    /// one of the original instance, and the commitments to the additional
+    /// columns are folded with it.
+    fn compute_extended_commitments(
+        mut self,
+        srs: &CF::Srs,
+        side: Side,
+    ) -> Result<Self, FoldingError> {
         let (relaxed_instance, relaxed_witness) = match side {
             Side::Left => (&mut self.instances[0], &self.witnesses[0]),
             Side::Right => (&mut self.instances[1], &self.witnesses[1]),
         };
 
         // FIXME: use parallelisation
-        let blinder = PolyComm::new(vec![relaxed_instance.blinder]);
+        let blinder = PolyComm::new(vec![relaxed_instance.extended_instance.get_blinder()]);
         for (expected_i, (i, wit)) in relaxed_witness.extended_witness.extended.iter().enumerate() {
             // in case any where to be missing for some reason
             assert_eq!(*i, expected_i);
             // Blinding the commitments to support the case the witness is zero.
             // The IVC circuit expects to have non-zero commitments.
             let commit = srs
-                .commit_evaluations_custom(self.domain, wit, &blinder)
-                .unwrap()
+                .commit_evaluations_custom(self.domain, wit, &blinder)?
                 .commitment;
+            check_chunks(std::slice::from_ref(&commit))?;
             relaxed_instance.extended_instance.extended.push(commit)
         }
         // FIXME: maybe returning a value is not necessary as it does inplace operations.
         // It implies copying on the stack and possibly copy multiple times.
-        self
+        Ok(self)
     }
 
     /// Return the list of scalars and commitments to be absorbed, by
@@ -1,3 +1,8 @@
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+    IntoParallelRefMutIterator, ParallelIterator,
+};
+
 #[derive(Clone, Debug)]
 /// Result of a folding expression evaluation.
 pub enum EvalLeaf<'a, F> {
@@ -25,7 +30,7 @@ impl<'a, F: std::fmt::Display> std::fmt::Display for EvalLeaf<'a, F> {
     }
 }
 
-impl<'a, F: std::ops::Add<Output = F> + Clone> std::ops::Add for EvalLeaf<'a, F> {
+impl<'a, F: std::ops::Add<Output = F> + Clone + Send + Sync> std::ops::Add for EvalLeaf<'a, F> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self {
@@ -33,7 +38,7 @@ impl<'a, F: std::ops::Add<Output = F> + Clone> std::ops::Add for EvalLeaf<'a, F>
     }
 }
 
-impl<'a, F: std::ops::Sub<Output = F> + Clone> std::ops::Sub for EvalLeaf<'a, F> {
+impl<'a, F: std::ops::Sub<Output = F> + Clone + Send + Sync> std::ops::Sub for EvalLeaf<'a, F> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self {
@@ -41,7 +46,7 @@ impl<'a, F: std::ops::Sub<Output = F> + Clone> std::ops::Sub for EvalLeaf<'a, F>
     }
 }
 
-impl<'a, F: std::ops::Mul<Output = F> + Clone> std::ops::Mul for EvalLeaf<'a, F> {
+impl<'a, F: std::ops::Mul<Output = F> + Clone + Send + Sync> std::ops::Mul for EvalLeaf<'a, F> {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self {
@@ -49,7 +54,7 @@ impl<'a, F: std::ops::Mul<Output = F> + Clone> std::ops::Mul for EvalLeaf<'a, F>
     }
 }
 
-impl<'a, F: std::ops::Mul<Output = F> + Clone> std::ops::Mul<F> for EvalLeaf<'a, F> {
+impl<'a, F: std::ops::Mul<Output = F> + Clone + Send + Sync> std::ops::Mul<F> for EvalLeaf<'a, F> {
     type Output = Self;
 
     fn mul(self, rhs: F) -> Self {
@@ -57,71 +62,70 @@ impl<'a, F: std::ops::Mul<Output = F> + Clone> std::ops::Mul<F> for EvalLeaf<'a,
     }
 }
 
-impl<'a, F: Clone> EvalLeaf<'a, F> {
-    pub fn map<M: Fn(&F) -> F, I: Fn(&mut F)>(self, map: M, in_place: I) -> Self {
+// The operations on the evaluations are done in parallel over the rows.
+impl<'a, F: Clone + Send + Sync> EvalLeaf<'a, F> {
+    pub fn map<M: Fn(&F) -> F + Send + Sync, I: Fn(&mut F) + Send + Sync>(
+        self,
+        map: M,
+        in_place: I,
+    ) -> Self {
         use EvalLeaf::*;
         match self {
             Const(c) => Const(map(&c)),
             Col(col) => {
-                let res = col.iter().map(map).collect();
+                let res = col.par_iter().map(map).collect();
                 Result(res)
             }
             Result(mut col) => {
-                for cell in col.iter_mut() {
-                    in_place(cell);
-                }
+                col.par_iter_mut().for_each(in_place);
                 Result(col)
             }
         }
     }
 
-    fn bin_op<M: Fn(F, F) -> F>(f: M, a: Self, b: Self) -> Self {
+    fn bin_op<M: Fn(F, F) -> F + Send + Sync>(f: M, a: Self, b: Self) -> Self {
         use EvalLeaf::*;
         match (a, b) {
             (Const(a), Const(b)) => Const(f(a, b)),
             (Const(a), Col(b)) => {
-                let res = b.iter().map(|b| f(a.clone(), b.clone())).collect();
+                let res = b.par_iter().map(|b| f(a.clone(), b.clone())).collect();
                 Result(res)
             }
             (Col(a), Const(b)) => {
-                let res = a.iter().map(|a| f(a.clone(), b.clone())).collect();
+                let res = a.par_iter().map(|a| f(a.clone(), b.clone())).collect();
                 Result(res)
             }
             (Col(a), Col(b)) => {
-                let res = (a.iter())
-                    .zip(b.iter())
+                let res = (a.par_iter())
+                    .zip(b.par_iter())
                     .map(|(a, b)| f(a.clone(), b.clone()))
                     .collect();
                 Result(res)
             }
             (Result(mut a), Const(b)) => {
-                for a in a.iter_mut() {
-                    *a = f(a.clone(), b.clone())
-                }
+                a.par_iter_mut().for_each(|a| *a = f(a.clone(), b.clone()));
                 Result(a)
             }
             (Const(a), Result(mut b)) => {
-                for b in b.iter_mut() {
-                    *b = f(a.clone(), b.clone())
-                }
+                b.par_iter_mut().for_each(|b| *b = f(a.clone(), b.clone()));
                 Result(b)
             }
             (Result(mut a), Col(b)) => {
-                for (a, b) in a.iter_mut().zip(b.iter()) {
-                    *a = f(a.clone(), b.clone())
-                }
+                a.par_iter_mut()
+                    .zip(b.par_iter())
+                    .for_each(|(a, b)| *a = f(a.clone(), b.clone()));
                 Result(a)
             }
             (Col(a), Result(mut b)) => {
-                for (a, b) in a.iter().zip(b.iter_mut()) {
-                    *b = f(a.clone(), b.clone())
-                }
+                a.par_iter()
+                    .zip(b.par_iter_mut())
+                    .for_each(|(a, b)| *b = f(a.clone(), b.clone()));
                 Result(b)
             }
             (Result(mut a), Result(b)) => {
-                for (a, b) in a.iter_mut().zip(b.into_iter()) {
-                    *a = f(a.clone(), b)
-                }
+                a.par_iter_mut()
+                    .zip(b.into_par_iter())
+                    .for_each(|(a, b)| *a = f(a.clone(), b));
                 Result(a)
             }
         }
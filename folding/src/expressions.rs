@@ -7,6 +7,8 @@
 //!
 //! Before folding, we do suppose that each expression has been reduced to
 //! degree `2` using [crate::quadraticization].
+//! Expressions of higher degree can also be folded without being reduced,
+//! using [crate::high_degree], which computes all the cross terms.
 //!
 //! The library introduces different types of expressions:
 //! - [FoldingCompatibleExpr]: an expression that can be used with folding. It
@@ -257,6 +259,10 @@
 //! u'' = u + r u'
 //! ```
 //!
+//! The constraints of the complete argument, with vector lookups, several
+//! lookups per row and the accumulator of the fractions, are provided by
+//! [crate::logup].
+//!
 //! ## Supporting polynomial commitment blinders
 //!
 //! The library also supports polynomial commitment blinders. The blinding
@@ -270,6 +276,14 @@
 //!
 //! When doing the final proof, the blinder factor that will need to be used is
 //! the one from the final relaxed instance.
+//!
+//! By default, the cross terms are committed with the blinder `1`. For the
+//! final proof to be zero-knowledge with respect to the folded witnesses, the
+//! instances must be committed with random blinders and the cross terms must
+//! be blinded as well, using
+//! [FoldingScheme::fold_instance_witness_pair_zk](crate::FoldingScheme::fold_instance_witness_pair_zk).
+//! The blinders of the cross terms are then accumulated in the blinder of the
+//! error commitment of the folded instance.
 
 use crate::{
     columns::ExtendedFoldingColumn,
@@ -286,6 +300,7 @@ use kimchi::circuits::{
     gate::CurrOrNext,
 };
 use num_traits::Zero;
+use std::collections::{HashMap, HashSet};
 
 /// Describe the degree of a constraint.
 /// As described in the [top level documentation](super::expressions), we only
@@ -677,6 +692,27 @@ impl<C: FoldingConfig> FoldingCompatibleExpr<C> {
             FoldingCompatibleExpr::Pow(e, p) => Pow(Box::new(e.flatten_quad_columns(mapper)), p),
         }
     }
+
+    /// Compute the degree of the expression in the variables randomised by
+    /// folding, i.e. the witness columns, the challenges and the extensions.
+    /// Contrary to [Degree], the degree is not bounded by `2`, see
+    /// [crate::high_degree].
+    pub fn degree(&self) -> usize {
+        use FoldingCompatibleExpr::*;
+        match self {
+            Atom(FoldingCompatibleExprInner::Constant(_)) => 0,
+            Atom(FoldingCompatibleExprInner::Challenge(_)) => 1,
+            Atom(FoldingCompatibleExprInner::Cell(Variable { col, .. })) => {
+                col.is_witness() as usize
+            }
+            Atom(FoldingCompatibleExprInner::Extensions(_)) => 1,
+            Double(e) => e.degree(),
+            Square(e) => 2 * e.degree(),
+            Add(e1, e2) | Sub(e1, e2) => std::cmp::max(e1.degree(), e2.degree()),
+            Mul(e1, e2) => e1.degree() + e2.degree(),
+            Pow(e, p) => *p as usize * e.degree(),
+        }
+    }
 }
 
 impl<C: FoldingConfig> FoldingExp<C> {
@@ -846,6 +882,89 @@ pub struct IntegratedFoldingExpr<C: FoldingConfig> {
 }
 
 impl<C: FoldingConfig> IntegratedFoldingExpr<C> {
+    /// Returns the atoms occurring in the expression, without duplicates
+    pub(crate) fn atoms(&self) -> HashSet<&ExtendedFoldingColumn<C>> {
+        let mut atoms = HashSet::new();
+        let mut stack: Vec<&FoldingExp<C>> = self
+            .degree_0
+            .iter()
+            .chain(&self.degree_1)
+            .chain(&self.degree_2)
+            .map(|(exp, _, _)| exp)
+            .collect();
+        while let Some(exp) = stack.pop() {
+            use FoldingExp::*;
+            match exp {
+                Atom(col) => {
+                    atoms.insert(col);
+                }
+                Pow(e, _) | Double(e) | Square(e) => stack.push(e),
+                Add(e1, e2) | Mul(e1, e2) | Sub(e1, e2) => {
+                    stack.push(e1);
+                    stack.push(e2);
+                }
+            }
+        }
+        atoms
+    }
+
+    /// Returns the number of alphas combining the constraints
+    pub(crate) fn number_of_alphas(&self) -> usize {
+        self.degree_0
+            .iter()
+            .chain(&self.degree_1)
+            .chain(&self.degree_2)
+            .map(|(_, _, alpha)| alpha + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the monomials, and the products inside them, that occur more
+    /// than once in the expression, e.g. in several constraints. Their
+    /// evaluations are computed once per fold when computing the error terms.
+    /// The factors of a repeated monomial are not considered, as the monomial
+    /// is itself evaluated only once.
+    pub(crate) fn shared_monomials(&self) -> HashSet<&FoldingExp<C>> {
+        fn count<'e, C: FoldingConfig>(
+            exp: &'e FoldingExp<C>,
+            counts: &mut HashMap<&'e FoldingExp<C>, usize>,
+        ) {
+            use FoldingExp::*;
+            match exp {
+                Atom(_) => {}
+                Add(e1, e2) | Sub(e1, e2) => {
+                    count(e1, counts);
+                    count(e2, counts);
+                }
+                Double(e) => count(e, counts),
+                Mul(_, _) | Pow(_, _) | Square(_) => {
+                    let occurrences = counts.entry(exp).or_default();
+                    *occurrences += 1;
+                    if *occurrences == 1 {
+                        match exp {
+                            Mul(e1, e2) => {
+                                count(e1, counts);
+                                count(e2, counts);
+                            }
+                            Pow(e, _) | Square(e) => count(e, counts),
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+            }
+        }
+        let mut counts = HashMap::new();
+        [&self.degree_0, &self.degree_1, &self.degree_2]
+            .into_iter()
+            .flatten()
+            .for_each(|(exp, _, _)| count(exp, &mut counts));
+        counts
+            .into_iter()
+            .filter(|(_, occurrences)| *occurrences > 1)
+            .map(|(exp, _)| exp)
+            .collect()
+    }
+
     /// Combines constraints into single expression
     pub fn final_expression(self) -> FoldingCompatibleExpr<C> {
         use FoldingCompatibleExpr::*;
@@ -0,0 +1,614 @@
+//! This module implements a folding scheme for expressions of any degree,
+//! without reducing them to degree `2` with [crate::quadraticization].
+//!
+//! The constraints `C_{l}` are combined with the alphas into a single
+//! polynomial `P = ∑_{l} α_{l} C_{l}` of degree `d`, where `d` is the maximum
+//! degree of the constraints plus one for the alphas. The polynomial `P` is
+//! relaxed into a homogeneous polynomial of degree `d` by multiplying each
+//! monomial of degree `k` by `u^{d - k}`, as described in the [top-level
+//! documentation of the expressions module](crate::expressions) for degree
+//! `3`.
+//!
+//! Folding two instances with a challenge `r` gives:
+//! ```text
+//! P_relaxed(X + r X', u + r u') = P_relaxed(X, u)
+//!                               + r T_{1} + ... + r^{d - 1} T_{d - 1}
+//!                               + r^{d} P_relaxed(X', u')
+//! ```
+//! where the `d - 1` cross terms `T_{k}` are computed by the prover and
+//! committed to. The error term is then folded as
+//! `E <- E - r T_{1} - ... - r^{d - 1} T_{d - 1} + r^{d} E'`.
+//!
+//! To compute the cross terms, the expressions are evaluated as polynomials
+//! in `r`, whose coefficients are the evaluations of the monomials on all the
+//! rows. A witness column is the polynomial `X + r X'`, and the variable `u` is
+//! the polynomial `u + r u'`. The sums are homogenized on the fly by
+//! multiplying the term of lower degree by powers of `u + r u'`.
+//!
+//! Compared to [crate::FoldingScheme], no additional column is needed, at the
+//! cost of committing to `d - 1` cross terms instead of `2`. For instance,
+//! kimchi gates of degree `7` give `7` cross terms.
+//!
+//! ## Folding many instances in sequence
+//!
+//! [HighDegreeFoldingScheme::fold_sequentially] folds `k` incoming instances
+//! `X_1, ..., X_k` into an accumulator `X_0`, one after the other, each step
+//! with its own challenge and its own `d - 1` cross terms, for a total of
+//! `k (d - 1)` cross terms. This is a convenience over `k` calls to
+//! [HighDegreeFoldingScheme::fold_instance_witness_pair], not a multi-folding
+//! scheme: the verifier still coins `k` challenges.
+//! Combining all the instances with the powers of a single challenge is not
+//! sound: the coefficient of `r^{d i}` would contain the relaxed polynomial
+//! evaluated on `X_i`, mixed with cross terms, and a prover could hide an
+//! unsatisfied instance in the corresponding commitment.
+
+use crate::{
+    check_alphas, check_chunks, commit_cross_term,
+    eval_leaf::EvalLeaf,
+    expressions::{
+        ExpExtension, FoldingColumnTrait, FoldingCompatibleExpr, FoldingCompatibleExprInner,
+    },
+    instance_witness::{RelaxablePair, RelaxedInstance, RelaxedWitness},
+    squeeze_and_combine, squeeze_challenge, Alphas, BaseField, Evals, FoldingConfig, FoldingEnv,
+    FoldingError, Instance, ScalarField, Side,
+};
+use ark_ff::{Field, One, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
+use kimchi::circuits::expr::Variable;
+use mina_poseidon::FqSponge;
+use poly_commitment::{commitment::EndoCurve, PolyComm};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// A polynomial in the folding challenge `r`, whose coefficients are
+/// evaluated on all the rows.
+/// The degree of the polynomial is its number of coefficients minus one,
+/// even if the leading coefficients are zero.
+type Polynomial<'a, F> = Vec<EvalLeaf<'a, F>>;
+
+pub struct HighDegreeFoldingScheme<'a, CF: FoldingConfig> {
+    pub constraints: Vec<FoldingCompatibleExpr<CF>>,
+    pub srs: &'a CF::Srs,
+    pub domain: Radix2EvaluationDomain<ScalarField<CF>>,
+    pub zero_vec: Evals<ScalarField<CF>>,
+    pub structure: CF::Structure,
+    /// Degree of the relaxed polynomial, alphas included
+    degree: usize,
+    /// The coefficients `(endo_q, endo_r)` of the endomorphism of the curve,
+    /// if the folding challenges are endoscalars
+    endo: Option<(BaseField<CF>, ScalarField<CF>)>,
+}
+
+impl<'a, CF: FoldingConfig> HighDegreeFoldingScheme<'a, CF> {
+    pub fn new(
+        constraints: Vec<FoldingCompatibleExpr<CF>>,
+        srs: &'a CF::Srs,
+        domain: Radix2EvaluationDomain<ScalarField<CF>>,
+        structure: &CF::Structure,
+    ) -> Self {
+        // The alphas increase the degree by one
+        let degree = constraints
+            .iter()
+            .map(|c| c.degree() + 1)
+            .max()
+            .expect("At least one constraint must be given");
+        let zero = <ScalarField<CF>>::zero();
+        let evals = std::iter::repeat(zero).take(domain.size()).collect();
+        let zero_vec = Evaluations::from_vec_and_domain(evals, domain);
+        Self {
+            constraints,
+            srs,
+            domain,
+            zero_vec,
+            structure: structure.clone(),
+            degree,
+            endo: None,
+        }
+    }
+
+    /// Derives the folding challenges from endoscalars, see
+    /// [crate::FoldingScheme::with_endo_challenges]
+    pub fn with_endo_challenges(mut self, endo_q: BaseField<CF>, endo_r: ScalarField<CF>) -> Self {
+        self.endo = Some((endo_q, endo_r));
+        self
+    }
+
+    /// Return the degree `d` of the relaxed polynomial
+    pub fn get_degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Return the number of cross terms, i.e. `d - 1`
+    pub fn get_number_of_cross_terms(&self) -> usize {
+        self.degree - 1
+    }
+
+    /// Return the number of cross terms to fold `k` instances into an
+    /// accumulator one after the other, i.e. `k (d - 1)`
+    pub fn get_number_of_cross_terms_sequential(&self, k: usize) -> usize {
+        k * self.get_number_of_cross_terms()
+    }
+
+    /// This is the main entry point to fold two instances and their witnesses.
+    /// The process is as follows:
+    /// - Both pairs are relaxed.
+    /// - The `d - 1` cross terms are computed and committed.
+    /// - The sponge absorbs the instances and the commitments to the cross
+    /// terms, and the challenge `r` is coined.
+    ///
+    /// The errors are the ones of [crate::FoldingScheme::fold_instance_witness_pair].
+    pub fn fold_instance_witness_pair<A, B, Sponge>(
+        &self,
+        a: A,
+        b: B,
+        fq_sponge: &mut Sponge,
+    ) -> Result<HighDegreeFoldingOutput<CF>, FoldingError>
+    where
+        A: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
+        B: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+    {
+        let SequentialFoldingOutput {
+            folded_instance,
+            folded_witness,
+            cross_terms,
+            mut relaxed_instances,
+            to_absorb,
+        } = self.fold_sequentially(a, vec![b], fq_sponge)?;
+        let relaxed_right_instance = relaxed_instances.pop().unwrap();
+        let relaxed_left_instance = relaxed_instances.pop().unwrap();
+        Ok(HighDegreeFoldingOutput {
+            folded_instance,
+            folded_witness,
+            cross_terms,
+            relaxed_left_instance,
+            relaxed_right_instance,
+            to_absorb,
+        })
+    }
+
+    /// Folds the `k` pairs `incoming` into the pair `accumulator`, see the
+    /// [top-level documentation](self). The pairs are folded one after the
+    /// other into the accumulator, each time as follows:
+    /// - The `d - 1` cross terms are computed and committed.
+    /// - The sponge absorbs the accumulator, the incoming instance and the
+    /// commitments to the cross terms, and a challenge `r` is coined.
+    ///
+    /// The errors are the ones of [crate::FoldingScheme::fold_instance_witness_pair],
+    /// and [FoldingError::NoInstance] if no pair is given in `incoming`.
+    pub fn fold_sequentially<A, B, Sponge>(
+        &self,
+        accumulator: A,
+        incoming: Vec<B>,
+        fq_sponge: &mut Sponge,
+    ) -> Result<SequentialFoldingOutput<CF>, FoldingError>
+    where
+        A: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
+        B: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+    {
+        if incoming.is_empty() {
+            return Err(FoldingError::NoInstance);
+        }
+        let (instances, witnesses): (Vec<_>, Vec<_>) =
+            std::iter::once(accumulator.relax(&self.zero_vec))
+                .chain(incoming.into_iter().map(|pair| pair.relax(&self.zero_vec)))
+                .unzip();
+        for instance in instances.iter() {
+            check_alphas(&instance.extended_instance, self.constraints.len())?;
+        }
+
+        let mut pairs = instances.clone().into_iter().zip(witnesses);
+        let (mut folded_instance, mut folded_witness) = pairs.next().unwrap();
+        let mut cross_terms = vec![];
+        let mut to_absorb: (Vec<_>, Vec<_>) = (vec![], vec![]);
+        for (instance, witness) in pairs {
+            let coefficients = self.evaluate_relaxed_polynomial(
+                &[&folded_instance, &instance],
+                &[&folded_witness, &witness],
+            );
+            // The first and last coefficients are the relaxed polynomial
+            // evaluated on each pair, and are not needed.
+            let step_cross_terms = &coefficients[1..self.degree];
+
+            // Committing to the cross terms
+            let step_commitments: Vec<PolyComm<CF::Curve>> = step_cross_terms
+                .iter()
+                .map(|t| {
+                    let evals = Evaluations::from_vec_and_domain(t.clone(), self.domain);
+                    commit_cross_term(self.srs, self.domain, &evals, ScalarField::<CF>::one())
+                })
+                .collect::<Result<_, _>>()?;
+
+            let step_to_absorb =
+                absorb::<CF, _>(fq_sponge, &[&folded_instance, &instance], &step_commitments);
+            let challenge = squeeze_challenge(fq_sponge, self.endo.map(|(_, endo_r)| endo_r));
+
+            folded_instance = RelaxedInstance::combine_and_sub_cross_terms(
+                folded_instance,
+                instance,
+                challenge,
+                &step_commitments,
+                &vec![ScalarField::<CF>::one(); step_commitments.len()],
+            );
+            folded_witness = RelaxedWitness::combine_and_sub_cross_terms(
+                folded_witness,
+                witness,
+                challenge,
+                step_cross_terms,
+            );
+            cross_terms.extend(step_commitments);
+            to_absorb.0.extend(step_to_absorb.0);
+            to_absorb.1.extend(step_to_absorb.1);
+        }
+        Ok(SequentialFoldingOutput {
+            folded_instance,
+            folded_witness,
+            cross_terms,
+            relaxed_instances: instances,
+            to_absorb,
+        })
+    }
+
+    /// Verifier of the folding scheme; returns a new folded instance,
+    /// which can be then compared with the one claimed to be the real
+    /// one.
+    pub fn verify_fold<Sponge>(
+        &self,
+        left_instance: RelaxedInstance<CF::Curve, CF::Instance>,
+        right_instance: RelaxedInstance<CF::Curve, CF::Instance>,
+        cross_terms: &[PolyComm<CF::Curve>],
+        fq_sponge: &mut Sponge,
+    ) -> Result<RelaxedInstance<CF::Curve, CF::Instance>, FoldingError>
+    where
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+        CF::Curve: EndoCurve,
+    {
+        self.verify_fold_sequentially(vec![left_instance, right_instance], cross_terms, fq_sponge)
+    }
+
+    /// Verifier of [Self::fold_sequentially]: folds the relaxed `instances`, the
+    /// accumulator first, one after the other, with the commitments to the
+    /// `d - 1` cross terms of each step.
+    pub fn verify_fold_sequentially<Sponge>(
+        &self,
+        instances: Vec<RelaxedInstance<CF::Curve, CF::Instance>>,
+        cross_terms: &[PolyComm<CF::Curve>],
+        fq_sponge: &mut Sponge,
+    ) -> Result<RelaxedInstance<CF::Curve, CF::Instance>, FoldingError>
+    where
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+        CF::Curve: EndoCurve,
+    {
+        if instances.len() < 2 {
+            return Err(FoldingError::NoInstance);
+        }
+        let n_cross_terms = self.get_number_of_cross_terms_sequential(instances.len() - 1);
+        if cross_terms.len() != n_cross_terms {
+            return Err(FoldingError::CrossTermsCount(
+                cross_terms.len(),
+                n_cross_terms,
+            ));
+        }
+        check_chunks(cross_terms)?;
+
+        let mut instances = instances.into_iter();
+        let accumulator = instances.next().unwrap();
+        Ok(instances
+            .zip(cross_terms.chunks(self.get_number_of_cross_terms()))
+            .fold(accumulator, |accumulator, (instance, step_cross_terms)| {
+                absorb::<CF, _>(fq_sponge, &[&accumulator, &instance], step_cross_terms);
+                squeeze_and_combine(
+                    fq_sponge,
+                    self.endo,
+                    accumulator,
+                    instance,
+                    step_cross_terms,
+                    &vec![ScalarField::<CF>::one(); step_cross_terms.len()],
+                )
+            }))
+    }
+
+    /// Checks that the relaxed polynomial and the error term cancel on each
+    /// row, i.e. that the pair satisfies the relaxed relation.
+    pub fn check(
+        &self,
+        instance: &RelaxedInstance<CF::Curve, CF::Instance>,
+        witness: &RelaxedWitness<CF::Curve, CF::Witness>,
+    ) -> bool {
+        // With the same pair on both sides, the constant coefficient is the
+        // relaxed polynomial evaluated on the pair
+        let coefficients =
+            self.evaluate_relaxed_polynomial(&[instance, instance], &[witness, witness]);
+        coefficients[0]
+            .iter()
+            .zip(witness.error_vec.evals.iter())
+            .all(|(p, e)| (*p + e).is_zero())
+    }
+
+    /// Computes the coefficients of the relaxed polynomial evaluated on the
+    /// combination of the `n` pairs with the powers of the folding challenge,
+    /// as a polynomial in the folding challenge of degree `d (n - 1)`.
+    /// At least two pairs must be given.
+    fn evaluate_relaxed_polynomial(
+        &self,
+        instances: &[&RelaxedInstance<CF::Curve, CF::Instance>],
+        witnesses: &[&RelaxedWitness<CF::Curve, CF::Witness>],
+    ) -> Vec<Vec<ScalarField<CF>>> {
+        // An environment gives access to two pairs, the last one being
+        // duplicated if the number of pairs is odd
+        let envs: Vec<CF::Env> = (0..instances.len())
+            .step_by(2)
+            .map(|i| {
+                let j = std::cmp::min(i + 1, instances.len() - 1);
+                <CF::Env>::new(
+                    &self.structure,
+                    [i, j].map(|i| &instances[i].extended_instance.instance),
+                    [i, j].map(|i| &witnesses[i].extended_witness.witness),
+                )
+            })
+            .collect();
+        let alphas: Vec<_> = instances
+            .iter()
+            .map(|i| i.extended_instance.instance.get_alphas())
+            .collect();
+        let u: Polynomial<_> = instances.iter().map(|i| EvalLeaf::Const(i.u)).collect();
+        // Each variable is a polynomial of degree n - 1 in the challenge
+        let variable_degree = instances.len() - 1;
+        let relaxed = self
+            .constraints
+            .iter()
+            .enumerate()
+            .map(|(i, constraint)| {
+                let constraint = eval_polynomial(constraint, &envs, &alphas, &u);
+                let constraint = homogenize(constraint, (self.degree - 1) * variable_degree, &u);
+                let alpha = alphas
+                    .iter()
+                    .map(|a| EvalLeaf::Const(a.get(i).expect("alpha not present")))
+                    .collect();
+                mul(alpha, constraint)
+            })
+            .reduce(|acc, p| add(acc, p, &u))
+            .unwrap();
+        relaxed
+            .into_iter()
+            .map(|coefficient| match coefficient {
+                EvalLeaf::Const(c) => vec![c; self.domain.size()],
+                coefficient => coefficient.unwrap(),
+            })
+            .collect()
+    }
+}
+
+/// Return the list of scalars and commitments to be absorbed, by
+/// concatenating the ones of the instances, in order, followed by the
+/// commitments to the cross terms
+fn to_absorb<CF: FoldingConfig>(
+    instances: &[&RelaxedInstance<CF::Curve, CF::Instance>],
+    cross_terms: &[PolyComm<CF::Curve>],
+) -> (Vec<ScalarField<CF>>, Vec<CF::Curve>) {
+    let mut res: (Vec<_>, Vec<_>) = (vec![], vec![]);
+    for instance in instances {
+        let (scalars, points) = instance.to_absorb();
+        res.0.extend(scalars);
+        res.1.extend(points);
+    }
+    res.1
+        .extend(cross_terms.iter().map(|t| t.get_first_chunk()));
+    res
+}
+
+/// Absorbs the elements returned by [to_absorb] in the sponge, and returns
+/// them
+fn absorb<CF: FoldingConfig, Sponge>(
+    fq_sponge: &mut Sponge,
+    instances: &[&RelaxedInstance<CF::Curve, CF::Instance>],
+    cross_terms: &[PolyComm<CF::Curve>],
+) -> (Vec<ScalarField<CF>>, Vec<CF::Curve>)
+where
+    Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+{
+    let to_absorb = to_absorb::<CF>(instances, cross_terms);
+    fq_sponge.absorb_fr(&to_absorb.0);
+    fq_sponge.absorb_g(&to_absorb.1);
+    to_absorb
+}
+
+/// Output of the folding prover
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Instance: Serialize, C::Witness: Serialize",
+    deserialize = "C::Instance: Deserialize<'de>, C::Witness: Deserialize<'de>"
+))]
+pub struct HighDegreeFoldingOutput<C: FoldingConfig> {
+    /// The folded instance, containing, in particular, the result `C_l + r C_r`
+    pub folded_instance: RelaxedInstance<C::Curve, C::Instance>,
+    /// Folded witness, containing, in particular, the result of the evaluations
+    /// `W_l + r W_r`
+    pub folded_witness: RelaxedWitness<C::Curve, C::Witness>,
+    /// The commitments to the `d - 1` cross terms `T_{1}, ..., T_{d - 1}`
+    pub cross_terms: Vec<PolyComm<C::Curve>>,
+    /// The left relaxed instance
+    pub relaxed_left_instance: RelaxedInstance<C::Curve, C::Instance>,
+    /// The right relaxed instance
+    pub relaxed_right_instance: RelaxedInstance<C::Curve, C::Instance>,
+    /// Elements to absorbed in IVC, in the same order as done in folding
+    #[serde_as(
+        as = "(Vec<o1_utils::serialization::SerdeAs>, Vec<o1_utils::serialization::SerdeAs>)"
+    )]
+    pub to_absorb: (Vec<ScalarField<C>>, Vec<C::Curve>),
+}
+
+impl<C: FoldingConfig> HighDegreeFoldingOutput<C> {
+    #[allow(clippy::type_complexity)]
+    pub fn pair(
+        self,
+    ) -> (
+        RelaxedInstance<C::Curve, C::Instance>,
+        RelaxedWitness<C::Curve, C::Witness>,
+    ) {
+        (self.folded_instance, self.folded_witness)
+    }
+}
+
+/// Output of the folding prover for many instances folded in sequence, see
+/// [HighDegreeFoldingScheme::fold_sequentially]
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Instance: Serialize, C::Witness: Serialize",
+    deserialize = "C::Instance: Deserialize<'de>, C::Witness: Deserialize<'de>"
+))]
+pub struct SequentialFoldingOutput<C: FoldingConfig> {
+    /// The folded instance, containing, in particular, the result of folding
+    /// `C_1, ..., C_k` one after the other into `C_0`
+    pub folded_instance: RelaxedInstance<C::Curve, C::Instance>,
+    /// Folded witness, containing, in particular, the result of folding the
+    /// evaluations `W_1, ..., W_k` one after the other into `W_0`
+    pub folded_witness: RelaxedWitness<C::Curve, C::Witness>,
+    /// The commitments to the `d - 1` cross terms of each of the `k` steps,
+    /// in order
+    pub cross_terms: Vec<PolyComm<C::Curve>>,
+    /// The relaxed instances, the accumulator first
+    pub relaxed_instances: Vec<RelaxedInstance<C::Curve, C::Instance>>,
+    /// Elements to absorbed in IVC, in the same order as done in folding
+    #[serde_as(
+        as = "(Vec<o1_utils::serialization::SerdeAs>, Vec<o1_utils::serialization::SerdeAs>)"
+    )]
+    pub to_absorb: (Vec<ScalarField<C>>, Vec<C::Curve>),
+}
+
+impl<C: FoldingConfig> SequentialFoldingOutput<C> {
+    #[allow(clippy::type_complexity)]
+    pub fn pair(
+        self,
+    ) -> (
+        RelaxedInstance<C::Curve, C::Instance>,
+        RelaxedWitness<C::Curve, C::Witness>,
+    ) {
+        (self.folded_instance, self.folded_witness)
+    }
+}
+
+/// Evaluates an expression as a polynomial in the folding challenge, the
+/// variables randomised by folding being `X_0 + r X_1 + ... + r^{n - 1} X_{n - 1}`.
+/// The pairs `2 i` and `2 i + 1` are given by the sides of the environment
+/// `envs[i]`.
+fn eval_polynomial<'a, C: FoldingConfig>(
+    exp: &FoldingCompatibleExpr<C>,
+    envs: &'a [C::Env],
+    alphas: &[&Alphas<ScalarField<C>>],
+    u: &Polynomial<'a, ScalarField<C>>,
+) -> Polynomial<'a, ScalarField<C>> {
+    use FoldingCompatibleExpr::*;
+    let sided = |f: &dyn Fn(&'a C::Env, Side, usize) -> EvalLeaf<'a, ScalarField<C>>| {
+        (0..alphas.len())
+            .map(|i| {
+                let side = if i % 2 == 0 { Side::Left } else { Side::Right };
+                f(&envs[i / 2], side, i)
+            })
+            .collect()
+    };
+    match exp {
+        Atom(FoldingCompatibleExprInner::Constant(c)) => vec![EvalLeaf::Const(*c)],
+        Atom(FoldingCompatibleExprInner::Challenge(chal)) => {
+            sided(&|env, side, _| EvalLeaf::Const(env.challenge(*chal, side)))
+        }
+        Atom(FoldingCompatibleExprInner::Cell(Variable { col, row })) => {
+            if col.is_witness() {
+                sided(&|env, side, _| EvalLeaf::Col(env.col(*col, *row, side)))
+            } else {
+                // The column is the same for all the pairs
+                vec![EvalLeaf::Col(envs[0].col(*col, *row, Side::Left))]
+            }
+        }
+        Atom(FoldingCompatibleExprInner::Extensions(ext)) => match ext {
+            ExpExtension::U => u.clone(),
+            ExpExtension::Alpha(i) => {
+                sided(&|_, _, j| EvalLeaf::Const(alphas[j].get(*i).expect("alpha not present")))
+            }
+            ExpExtension::Selector(s) => {
+                sided(&|env, side, _| EvalLeaf::Col(env.selector(s, side)))
+            }
+            ExpExtension::Error | ExpExtension::ExtendedWitness(_) => {
+                panic!("this should only be created by folding itself")
+            }
+        },
+        Double(e) => eval_polynomial(e, envs, alphas, u)
+            .into_iter()
+            .map(|c| {
+                c.map(Field::double, |f| {
+                    Field::double_in_place(f);
+                })
+            })
+            .collect(),
+        Square(e) => {
+            let e = eval_polynomial(e, envs, alphas, u);
+            mul(e.clone(), e)
+        }
+        Add(e1, e2) => add(
+            eval_polynomial(e1, envs, alphas, u),
+            eval_polynomial(e2, envs, alphas, u),
+            u,
+        ),
+        Sub(e1, e2) => {
+            let e2 = eval_polynomial(e2, envs, alphas, u)
+                .into_iter()
+                .map(|c| c * -ScalarField::<C>::one())
+                .collect();
+            add(eval_polynomial(e1, envs, alphas, u), e2, u)
+        }
+        Mul(e1, e2) => mul(
+            eval_polynomial(e1, envs, alphas, u),
+            eval_polynomial(e2, envs, alphas, u),
+        ),
+        Pow(e, p) => {
+            let e = eval_polynomial(e, envs, alphas, u);
+            (0..*p).fold(vec![EvalLeaf::Const(ScalarField::<C>::one())], |acc, _| {
+                mul(acc, e.clone())
+            })
+        }
+    }
+}
+
+/// Multiplies two polynomials, the degree of the result being the sum of the
+/// degrees
+fn mul<'a, F: Field>(a: Polynomial<'a, F>, b: Polynomial<'a, F>) -> Polynomial<'a, F> {
+    let mut res: Vec<Option<EvalLeaf<'a, F>>> = vec![None; a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            let product = a.clone() * b.clone();
+            res[i + j] = Some(match res[i + j].take() {
+                Some(acc) => acc + product,
+                None => product,
+            });
+        }
+    }
+    res.into_iter().map(Option::unwrap).collect()
+}
+
+/// Adds two polynomials, after homogenizing the one of lower degree
+fn add<'a, F: Field>(
+    a: Polynomial<'a, F>,
+    b: Polynomial<'a, F>,
+    u: &Polynomial<'a, F>,
+) -> Polynomial<'a, F> {
+    let degree = std::cmp::max(a.len(), b.len()) - 1;
+    let a = homogenize(a, degree, u);
+    let b = homogenize(b, degree, u);
+    a.into_iter().zip(b).map(|(a, b)| a + b).collect()
+}
+
+/// Multiplies the polynomial by `u_0 + r u_1 + ...` until it reaches the given
+/// degree
+fn homogenize<'a, F: Field>(
+    mut a: Polynomial<'a, F>,
+    degree: usize,
+    u: &Polynomial<'a, F>,
+) -> Polynomial<'a, F> {
+    while a.len() <= degree {
+        a = mul(a, u.clone());
+    }
+    a
+}
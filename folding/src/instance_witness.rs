@@ -27,8 +27,11 @@
 
 use crate::{Alphas, Evals};
 use ark_ff::Field;
-use num_traits::One;
-use poly_commitment::commitment::{CommitmentCurve, PolyComm};
+use mina_poseidon::sponge::ScalarChallenge;
+use num_traits::{One, Zero};
+use poly_commitment::commitment::{CommitmentCurve, EndoCurve, PolyComm};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use std::collections::BTreeMap;
 
 pub trait Foldable<F: Field> {
@@ -63,6 +66,11 @@ pub trait Instance<G: CommitmentCurve>: Sized + Foldable<G::ScalarField> {
     /// ```text
     /// let challenge_r = sponge.challenge();
     /// ```
+    /// or, if the folding challenges are endoscalars (see
+    /// [crate::FoldingScheme::with_endo_challenges]):
+    /// ```text
+    /// let challenge_r = ScalarChallenge(sponge.challenge()).to_field(&endo_r);
+    /// ```
     fn to_absorb(&self) -> (Vec<G::ScalarField>, Vec<G>);
 
     /// Returns the alphas values for the instance
@@ -70,6 +78,74 @@ pub trait Instance<G: CommitmentCurve>: Sized + Foldable<G::ScalarField> {
 
     /// Return the blinder that can be used while committing to polynomials.
     fn get_blinder(&self) -> G::ScalarField;
+
+    /// Same as [Foldable::combine], for the endoscalar `challenge` used when
+    /// the folding challenges are endoscalars (see
+    /// [crate::FoldingScheme::with_endo_challenges]).
+    /// By default, the challenge is converted to a field element and the
+    /// instances are combined with [Foldable::combine]. Implementations are
+    /// expected to override it to fold the commitments with
+    /// [combine_commitments_endo], which only needs 128-bit scalar
+    /// multiplications.
+    fn combine_endo(
+        a: Self,
+        b: Self,
+        challenge: ScalarChallenge<G::ScalarField>,
+        _endo_q: G::BaseField,
+        endo_r: G::ScalarField,
+    ) -> Self
+    where
+        G: EndoCurve,
+    {
+        Self::combine(a, b, challenge.to_field(&endo_r))
+    }
+}
+
+/// Computes `a_i + c b_i` for each pair of points, where `c` is the field
+/// element represented by the endoscalar `challenge`, using
+/// [EndoCurve::combine_one_endo].
+/// The batched additions of [EndoCurve::combine_one_endo] do not support the
+/// point at infinity as `b_i`, which is common for error commitments, so
+/// these pairs are skipped, `a_i + c b_i` being `a_i` in this case.
+pub fn combine_commitments_endo<G: EndoCurve>(
+    a: &[G],
+    b: &[G],
+    challenge: &ScalarChallenge<G::ScalarField>,
+    endo_q: G::BaseField,
+    endo_r: G::ScalarField,
+) -> Vec<G> {
+    assert_eq!(a.len(), b.len());
+    let (g1, g2): (Vec<G>, Vec<G>) = a
+        .iter()
+        .zip(b)
+        .filter(|(_, b)| !b.is_zero())
+        .map(|(a, b)| (*a, *b))
+        .unzip();
+    let mut combined = G::combine_one_endo(endo_r, endo_q, &g1, &g2, challenge.clone()).into_iter();
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| {
+            if b.is_zero() {
+                *a
+            } else {
+                combined.next().unwrap()
+            }
+        })
+        .collect()
+}
+
+/// Same as [combine_commitments_endo], for the chunks of two polynomial
+/// commitments.
+fn combine_poly_comms_endo<G: EndoCurve>(
+    a: &PolyComm<G>,
+    b: &PolyComm<G>,
+    challenge: &ScalarChallenge<G::ScalarField>,
+    endo_q: G::BaseField,
+    endo_r: G::ScalarField,
+) -> PolyComm<G> {
+    PolyComm::new(combine_commitments_endo(
+        &a.chunks, &b.chunks, challenge, endo_q, endo_r,
+    ))
 }
 
 pub trait Witness<G: CommitmentCurve>: Sized + Foldable<G::ScalarField> {}
@@ -101,12 +177,15 @@ impl<G: CommitmentCurve, I: Instance<G>> ExtendedInstance<G, I> {
 // -- Extended witness
 /// This structure represents a witness extended with extra columns that are
 /// added by quadraticization
-#[derive(Clone, Debug)]
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "W: Serialize", deserialize = "W: Deserialize<'de>"))]
 pub struct ExtendedWitness<G: CommitmentCurve, W: Witness<G>> {
     /// This is the original witness, without quadraticization
     pub witness: W,
     /// Extra columns added by quadraticization to lower the degree of
     /// expressions to 2
+    #[serde_as(as = "BTreeMap<_, o1_utils::serialization::SerdeAs>")]
     pub extended: BTreeMap<usize, Evals<G::ScalarField>>,
 }
 
@@ -167,7 +246,8 @@ impl<G: CommitmentCurve, W: Witness<G>> ExtendedWitness<G, W> {
 /// described by a degree 3 polynomial, an additional column will be added, and
 /// `extended` will contain `1` commitment.
 // FIXME: We should forbid cloning, for memory footprint.
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "I: Serialize", deserialize = "I: Deserialize<'de>"))]
 pub struct ExtendedInstance<G: CommitmentCurve, I: Instance<G>> {
     /// The original instance.
     pub instance: I,
@@ -224,6 +304,28 @@ impl<G: CommitmentCurve, I: Instance<G>> Instance<G> for ExtendedInstance<G, I>
     fn get_blinder(&self) -> G::ScalarField {
         self.instance.get_blinder()
     }
+
+    /// Combines the original instances with [Instance::combine_endo], and the
+    /// commitments to the extra columns with [combine_commitments_endo].
+    fn combine_endo(
+        a: Self,
+        b: Self,
+        challenge: ScalarChallenge<G::ScalarField>,
+        endo_q: G::BaseField,
+        endo_r: G::ScalarField,
+    ) -> Self
+    where
+        G: EndoCurve,
+    {
+        let extended = a
+            .extended
+            .iter()
+            .zip(b.extended.iter())
+            .map(|(a, b)| combine_poly_comms_endo(a, b, &challenge, endo_q, endo_r))
+            .collect();
+        let instance = I::combine_endo(a.instance, b.instance, challenge, endo_q, endo_r);
+        Self { instance, extended }
+    }
 }
 
 // -- "Relaxed"/"Homogenized" structures
@@ -234,17 +336,24 @@ impl<G: CommitmentCurve, I: Instance<G>> Instance<G> for ExtendedInstance<G, I>
 /// slack/error term.
 /// See page 15 of [Nova](https://eprint.iacr.org/2021/370.pdf).
 // FIXME: We should forbid cloning, for memory footprint.
-#[derive(PartialEq, Eq, Clone)]
+#[serde_as]
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "I: Serialize", deserialize = "I: Deserialize<'de>"))]
 pub struct RelaxedInstance<G: CommitmentCurve, I: Instance<G>> {
     /// The original instance, extended with the columns added by
     /// quadriticization
     pub extended_instance: ExtendedInstance<G, I>,
     /// The scalar `u` that is used to homogenize the polynomials
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub u: G::ScalarField,
     /// The commitment to the error term, introduced when homogenizing the
     /// polynomials
     pub error_commitment: PolyComm<G>,
-    /// Blinder used for the commitments to the cross terms
+    /// The blinder of the commitment to the error term. It is folded like the
+    /// error commitment, with the blinders of the cross terms, which are `1`
+    /// unless the cross terms are blinded, see
+    /// [crate::FoldingScheme::fold_instance_witness_pair_zk].
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub blinder: G::ScalarField,
 }
 
@@ -272,36 +381,116 @@ impl<G: CommitmentCurve, I: Instance<G>> RelaxedInstance<G, I> {
     /// This corresponds to the computation `E <- E1 - c T1 - c^2 T2 + c^3 E2`.
     /// As we do support folding of degree 3, we have two cross terms `T1` and
     /// `T2`.
+    /// More generally, for homogeneous polynomials of degree `d`, the `d - 1`
+    /// cross terms are given, and the computation is
+    /// `E <- E1 - c T1 - ... - c^{d - 1} T{d - 1} + c^d E2`.
+    /// The blinders of the cross terms are subtracted the same way from the
+    /// blinder of the error commitment.
     /// For more information, see the [top-level
     /// documentation](crate::expressions).
     pub(super) fn combine_and_sub_cross_terms(
         a: Self,
         b: Self,
         challenge: <G>::ScalarField,
-        cross_terms: &[PolyComm<G>; 2],
+        cross_terms: &[PolyComm<G>],
+        cross_term_blinders: &[G::ScalarField],
     ) -> Self {
-        // Compute E1 + c^3 E2 and all other folding of commitments. The
+        // Compute E1 + c^d E2 and all other folding of commitments. The
         // resulting error commitment is stored in res.commitment.
-        let mut res = Self::combine(a, b, challenge);
-        let [t0, t1] = cross_terms;
+        let res = Self::combine_with_degree(a, b, challenge, cross_terms.len() + 1);
         // Eq 4, page 15 of the Nova paper
-        // Computing (E1 + c^3 E2) - c T1 - c^2 T2
-        res.error_commitment =
-            &res.error_commitment - (&(&t0.scale(challenge) + &t1.scale(challenge.square())));
-        res
+        // Computing (E1 + c^d E2) - c T1 - ... - c^{d - 1} T{d - 1}
+        res.sub_cross_terms(challenge, cross_terms, cross_term_blinders)
     }
-}
 
-/// A relaxed instance can be folded.
-impl<G: CommitmentCurve, I: Instance<G>> Foldable<G::ScalarField> for RelaxedInstance<G, I> {
-    /// Combine two relaxed instances into a new relaxed instance.
-    fn combine(a: Self, b: Self, challenge: <G>::ScalarField) -> Self {
-        // We do support degree 3 folding, therefore, we must compute:
-        // E <- E1 - (c T1 + c^2 T2) + c^3 E2
+    /// Same as [Self::combine_and_sub_cross_terms], for the endoscalar
+    /// `challenge` used when the folding challenges are endoscalars. The
+    /// commitments are folded with [combine_commitments_endo]; the error
+    /// commitment is computed with Horner's method, as
+    /// `E <- E1 + c (- T1 + c (- T2 + ... + c (- T{d - 1} + c E2)))`.
+    pub(super) fn combine_and_sub_cross_terms_endo(
+        a: Self,
+        b: Self,
+        challenge: ScalarChallenge<G::ScalarField>,
+        endo_q: G::BaseField,
+        endo_r: G::ScalarField,
+        cross_terms: &[PolyComm<G>],
+        cross_term_blinders: &[G::ScalarField],
+    ) -> Self
+    where
+        G: EndoCurve,
+    {
+        assert_eq!(cross_terms.len(), cross_term_blinders.len());
+        let c = challenge.to_field(&endo_r);
+        let RelaxedInstance {
+            extended_instance: extended_instance_1,
+            u: u1,
+            error_commitment: e1,
+            blinder: blinder1,
+        } = a;
+        let RelaxedInstance {
+            extended_instance: extended_instance_2,
+            u: u2,
+            error_commitment: e2,
+            blinder: blinder2,
+        } = b;
+        // r_E <- r_E1 - c r_T1 - ... - c^{d - 1} r_T{d - 1} + c^d r_E2
+        let mut blinder = blinder1;
+        let mut power = c;
+        for t_blinder in cross_term_blinders {
+            blinder -= power * t_blinder;
+            power *= c;
+        }
+        blinder += power * blinder2;
+        let error_commitment = cross_terms.iter().rev().fold(e2, |acc, t| {
+            let minus_t = t.map(|p| G::from(-p.into_group()));
+            combine_poly_comms_endo(&minus_t, &acc, &challenge, endo_q, endo_r)
+        });
+        let error_commitment =
+            combine_poly_comms_endo(&e1, &error_commitment, &challenge, endo_q, endo_r);
+        let extended_instance = <ExtendedInstance<G, I>>::combine_endo(
+            extended_instance_1,
+            extended_instance_2,
+            challenge,
+            endo_q,
+            endo_r,
+        );
+        RelaxedInstance {
+            extended_instance,
+            u: u1 + u2 * c,
+            error_commitment,
+            blinder,
+        }
+    }
+
+    /// Subtracts `c T1 + ... + c^{m} T{m}` from the error commitment, and the
+    /// blinders of the cross terms the same way from the blinder of the error
+    /// commitment.
+    fn sub_cross_terms(
+        mut self,
+        challenge: <G>::ScalarField,
+        cross_terms: &[PolyComm<G>],
+        cross_term_blinders: &[G::ScalarField],
+    ) -> Self {
+        assert_eq!(cross_terms.len(), cross_term_blinders.len());
+        // r_E <- r_E - c r_T1 - ... - c^{m} r_T{m}
+        let mut power = challenge;
+        for (t, blinder) in cross_terms.iter().zip(cross_term_blinders) {
+            self.error_commitment = &self.error_commitment - &t.scale(power);
+            self.blinder -= power * blinder;
+            power *= challenge;
+        }
+        self
+    }
+
+    /// Combine two relaxed instances of homogeneous polynomials of degree
+    /// `degree` into a new relaxed instance, without the cross terms.
+    fn combine_with_degree(a: Self, b: Self, challenge: <G>::ScalarField, degree: usize) -> Self {
+        // We must compute, with d the degree:
+        // E <- E1 - (c T1 + ... + c^{d - 1} T{d - 1}) + c^d E2
         // (page 15, eq 3 of the Nova paper)
-        // The term T1 and T2 are the cross terms
-        let challenge_square = challenge * challenge;
-        let challenge_cube = challenge_square * challenge;
+        // The terms T1, ..., T{d - 1} are the cross terms
+        let challenge_degree = challenge.pow([degree as u64]);
         let RelaxedInstance {
             extended_instance: extended_instance_1,
             u: u1,
@@ -314,38 +503,52 @@ impl<G: CommitmentCurve, I: Instance<G>> Foldable<G::ScalarField> for RelaxedIns
             error_commitment: e2,
             blinder: blinder2,
         } = b;
-        // We simply fold the blinders
-        //                 = 1        = 1
-        // r_E <- r_E1 + c r_T1 + c^2 r_T2 + c^3 r_E2
-        let blinder = blinder1 + challenge + challenge_square + challenge_cube * blinder2;
+        // We fold the blinders like the error commitments, the blinders of the
+        // cross terms being subtracted afterwards
+        // r_E1 + c^d r_E2
+        let blinder = blinder1 + challenge_degree * blinder2;
         let extended_instance =
             <ExtendedInstance<G, I>>::combine(extended_instance_1, extended_instance_2, challenge);
         // Combining the challenges
         // eq 3, page 15 of the Nova paper
         let u = u1 + u2 * challenge;
-        // We do have 2 cross terms as we have degree 3 folding
-        // e1 + c^3 e^2
-        let error_commitment = &e1 + &e2.scale(challenge_cube);
+        // e1 + c^d e^2
+        let error_commitment = &e1 + &e2.scale(challenge_degree);
         RelaxedInstance {
             // I <- I1 + c I2
             extended_instance,
             // u <- u1 + c u2
             u,
-            // E <- E1 - (c T1 + c^2 T2) + c^3 E2
+            // E <- E1 - (c T1 + ... + c^{d - 1} T{d - 1}) + c^d E2
             error_commitment,
             blinder,
         }
     }
 }
 
+/// A relaxed instance can be folded.
+impl<G: CommitmentCurve, I: Instance<G>> Foldable<G::ScalarField> for RelaxedInstance<G, I> {
+    /// Combine two relaxed instances into a new relaxed instance.
+    fn combine(a: Self, b: Self, challenge: <G>::ScalarField) -> Self {
+        // We do support degree 3 folding by default, therefore, we must
+        // compute:
+        // E <- E1 - (c T1 + c^2 T2) + c^3 E2
+        // The term T1 and T2 are the cross terms
+        Self::combine_with_degree(a, b, challenge, 3)
+    }
+}
+
 // -- Relaxed witnesses
-#[derive(Clone, Debug)]
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "W: Serialize", deserialize = "W: Deserialize<'de>"))]
 pub struct RelaxedWitness<G: CommitmentCurve, W: Witness<G>> {
     /// The original witness, extended with the columns added by
     /// quadriticization.
     pub extended_witness: ExtendedWitness<G, W>,
     /// The error vector, introduced when homogenizing the polynomials.
     /// For degree 3 folding, it is `E1 - c T1 - c^2 T2 + c^3 E2`
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub error_vec: Evals<G::ScalarField>,
 }
 
@@ -358,6 +561,9 @@ impl<G: CommitmentCurve, W: Witness<G>> RelaxedWitness<G, W> {
     /// We do have two cross terms as we work with homogeneous polynomials of
     /// degree 3. The value is saved into the field `error_vec` of the relaxed
     /// witness.
+    /// More generally, with `d - 1` cross terms for homogeneous polynomials of
+    /// degree `d`, the result is
+    /// `E1 + c^d E2 - (c T1 + ... + c^{d - 1} T{d - 1})`.
     /// This corresponds to the step 4, page 15 of the Nova paper, but with two
     /// cross terms (T1 and T2), see [top-level
     /// documentation](crate::expressions).
@@ -365,37 +571,41 @@ impl<G: CommitmentCurve, W: Witness<G>> RelaxedWitness<G, W> {
         a: Self,
         b: Self,
         challenge: <G>::ScalarField,
-        cross_terms: [Vec<G::ScalarField>; 2],
+        cross_terms: &[Vec<G::ScalarField>],
+    ) -> Self {
+        // Computing E1 + c^d E2
+        let res = Self::combine_with_degree(a, b, challenge, cross_terms.len() + 1);
+        res.sub_cross_terms(challenge, cross_terms)
+    }
+
+    /// Subtracts `c T1 + ... + c^{m} T{m}` from the error vector.
+    fn sub_cross_terms(
+        mut self,
+        challenge: <G>::ScalarField,
+        cross_terms: &[Vec<G::ScalarField>],
     ) -> Self {
-        // Computing E1 + c^3 E2
-        let mut res = Self::combine(a, b, challenge);
-
-        // Now substracting the cross terms
-        let [e0, e1] = cross_terms;
-
-        for (res, (e0, e1)) in res
-            .error_vec
-            .evals
-            .iter_mut()
-            .zip(e0.into_iter().zip(e1.into_iter()))
-        {
+        // Substracting the cross terms, using Horner's method:
+        // c T1 + ... + c^{m} T{m} = c (T1 + c (T2 + ...))
+        for (i, res) in self.error_vec.evals.iter_mut().enumerate() {
             // FIXME: for optimisation, use inplace operators. Allocating can be
             // costly
-            // should be the same as e0 * c + e1 * c^2
-            *res -= ((e1 * challenge) + e0) * challenge;
+            let cross = cross_terms
+                .iter()
+                .rev()
+                .fold(G::ScalarField::zero(), |acc, t| (acc + t[i]) * challenge);
+            *res -= cross;
         }
-        res
+        self
     }
 
     /// Provides access to the extra columns added by quadraticization
     pub fn get_extended_column(&self, i: &usize) -> Option<&Evals<G::ScalarField>> {
         self.extended_witness.extended.get(i)
     }
-}
 
-/// A relaxed/homogenized witness can be folded.
-impl<G: CommitmentCurve, W: Witness<G>> Foldable<G::ScalarField> for RelaxedWitness<G, W> {
-    fn combine(a: Self, b: Self, challenge: <G>::ScalarField) -> Self {
+    /// Combine two relaxed witnesses of homogeneous polynomials of degree
+    /// `degree` into a new relaxed witness, without the cross terms.
+    fn combine_with_degree(a: Self, b: Self, challenge: <G>::ScalarField, degree: usize) -> Self {
         let RelaxedWitness {
             extended_witness: a,
             error_vec: mut e1,
@@ -404,12 +614,11 @@ impl<G: CommitmentCurve, W: Witness<G>> Foldable<G::ScalarField> for RelaxedWitn
             extended_witness: b,
             error_vec: e2,
         } = b;
-        // We combine E1 and E2 into E1 + c^3 E2 as we do have two cross-terms
-        // with degree 3 folding
-        let challenge_cube = (challenge * challenge) * challenge;
+        // We combine E1 and E2 into E1 + c^d E2
+        let challenge_degree = challenge.pow([degree as u64]);
         let extended_witness = <ExtendedWitness<G, W>>::combine(a, b, challenge);
         for (a, b) in e1.evals.iter_mut().zip(e2.evals.into_iter()) {
-            *a += b * challenge_cube;
+            *a += b * challenge_degree;
         }
         let error_vec = e1;
         RelaxedWitness {
@@ -419,6 +628,15 @@ impl<G: CommitmentCurve, W: Witness<G>> Foldable<G::ScalarField> for RelaxedWitn
     }
 }
 
+/// A relaxed/homogenized witness can be folded.
+impl<G: CommitmentCurve, W: Witness<G>> Foldable<G::ScalarField> for RelaxedWitness<G, W> {
+    fn combine(a: Self, b: Self, challenge: <G>::ScalarField) -> Self {
+        // We combine E1 and E2 into E1 + c^3 E2 as we do have two cross-terms
+        // with degree 3 folding
+        Self::combine_with_degree(a, b, challenge, 3)
+    }
+}
+
 // -- Relaxable instance
 pub trait RelaxableInstance<G: CommitmentCurve, I: Instance<G>> {
     fn relax(self) -> RelaxedInstance<G, I>;
@@ -428,10 +646,10 @@ impl<G: CommitmentCurve, I: Instance<G>> RelaxableInstance<G, I> for I {
     /// This method takes an Instance and a commitment to zero and extends the
     /// instance, returning a relaxed instance which is composed by the extended
     /// instance, the scalar one, and the error commitment which is set to the
-    /// commitment to zero.
+    /// commitment to zero, with a zero blinder.
     fn relax(self) -> RelaxedInstance<G, Self> {
         let extended_instance = ExtendedInstance::extend(self);
-        let blinder = extended_instance.instance.get_blinder();
+        let blinder = G::ScalarField::zero();
         let u = G::ScalarField::one();
         let error_commitment = PolyComm::new(vec![G::zero()]);
         RelaxedInstance {
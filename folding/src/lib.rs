@@ -6,6 +6,14 @@
 //! to degree `2`.
 //! - [decomposable_folding]: a submodule to "parallelize" folded
 //! computations.
+//! - [high_degree]: a folding scheme for expressions of any degree, computing
+//! all the cross terms instead of reducing the degree to `2`, and folding
+//! many instances into an accumulator one after the other.
+//! - [logup]: the constraints and the witness of the logup lookup argument,
+//! to fold relations including lookups.
+//! - [protogalaxy]: a folding scheme based on
+//! [ProtoGalaxy](https://eprint.iacr.org/2023/1106), folding many instances at
+//! once without reducing the degree of the expressions.
 //!
 //! Examples can be found in the directory `examples`.
 //!
@@ -18,15 +26,21 @@
 // TODO: the documentation above might need more descriptions.
 
 use ark_ec::AffineRepr;
-use ark_ff::{Field, One, Zero};
+use ark_ff::{Field, One, UniformRand, Zero};
 use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
 use error_term::{compute_error, ExtendedEnv};
 use expressions::{folding_expression, FoldingColumnTrait, IntegratedFoldingExpr};
 use instance_witness::{Foldable, RelaxableInstance, RelaxablePair};
 use kimchi::circuits::gate::CurrOrNext;
-use mina_poseidon::FqSponge;
-use poly_commitment::{commitment::CommitmentCurve, PolyComm, SRS};
+use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
+use poly_commitment::{
+    commitment::{CommitmentCurve, EndoCurve},
+    PolyComm, SRS,
+};
 use quadraticization::ExtendedWitnessGenerator;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::serde_as;
 use std::{
     fmt::Debug,
     hash::Hash,
@@ -36,6 +50,7 @@ use std::{
 };
 
 // Make available outside the crate to avoid code duplication
+pub use error::FoldingError;
 pub use error_term::Side;
 pub use expressions::{ExpExtension, FoldingCompatibleExpr};
 pub use instance_witness::{Instance, RelaxedInstance, RelaxedWitness, Witness};
@@ -43,11 +58,15 @@ pub use instance_witness::{Instance, RelaxedInstance, RelaxedWitness, Witness};
 pub mod columns;
 pub mod decomposable_folding;
 
+mod error;
 mod error_term;
 
 pub mod eval_leaf;
 pub mod expressions;
+pub mod high_degree;
 pub mod instance_witness;
+pub mod logup;
+pub mod protogalaxy;
 pub mod quadraticization;
 pub mod standard_config;
 
@@ -139,6 +158,10 @@ pub struct FoldingScheme<'a, CF: FoldingConfig> {
     pub structure: CF::Structure,
     pub extended_witness_generator: ExtendedWitnessGenerator<CF>,
     quadraticization_columns: usize,
+    /// The coefficients `(endo_q, endo_r)` of the endomorphism of the curve,
+    /// if the folding challenges are endoscalars, see
+    /// [Self::with_endo_challenges]
+    endo: Option<(BaseField<CF>, ScalarField<CF>)>,
 }
 
 impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
@@ -162,15 +185,39 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
             structure: structure.clone(),
             extended_witness_generator,
             quadraticization_columns,
+            endo: None,
         };
         (scheme, final_expression)
     }
 
+    /// Derives the folding challenges from 128-bit endoscalars, like the
+    /// [ScalarChallenge] of kimchi, instead of using the squeezed values
+    /// directly. The verifier methods, e.g. [Self::verify_fold], then fold
+    /// the commitments with [EndoCurve::combine_one_endo], i.e. with 128-bit
+    /// scalar multiplications using the endomorphism of the curve, instead of
+    /// full scalar multiplications.
+    /// `endo_q` and `endo_r` are the coefficients of the endomorphism of the
+    /// base field and of the scalar field, e.g. as given by
+    /// `KimchiCurve::endos`.
+    pub fn with_endo_challenges(mut self, endo_q: BaseField<CF>, endo_r: ScalarField<CF>) -> Self {
+        self.endo = Some((endo_q, endo_r));
+        self
+    }
+
     /// Return the number of additional columns added by quadraticization
     pub fn get_number_of_additional_columns(&self) -> usize {
         self.quadraticization_columns
     }
 
+    /// Squeezes the folding challenge, once the instances and the commitments
+    /// to the cross terms have been absorbed
+    pub fn squeeze_challenge<Sponge>(&self, fq_sponge: &mut Sponge) -> ScalarField<CF>
+    where
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+    {
+        squeeze_challenge(fq_sponge, self.endo.map(|(_, endo_r)| endo_r))
+    }
+
     /// This is the main entry point to fold two instances and their witnesses.
     /// The process is as follows:
     /// - Both pairs are relaxed.
@@ -181,13 +228,66 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
     /// commitments are added into a list to absorb them into the sponge later.
     /// - The error terms are computed and committed.
     /// - The sponge absorbs the commitments and challenges.
+    ///
+    /// An error is returned if the pairs are not compatible with the scheme,
+    /// e.g. if the witnesses do not have the size of the domain, or if the
+    /// instances do not provide enough alphas.
+    ///
+    /// The cross terms are committed with the blinder `1`. See
+    /// [Self::fold_instance_witness_pair_zk] to blind them.
     #[allow(clippy::type_complexity)]
     pub fn fold_instance_witness_pair<A, B, Sponge>(
         &self,
         a: A,
         b: B,
         fq_sponge: &mut Sponge,
-    ) -> FoldingOutput<CF>
+    ) -> Result<FoldingOutput<CF>, FoldingError>
+    where
+        A: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
+        B: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+    {
+        let blinders = [ScalarField::<CF>::one(); 2];
+        self.fold_with_cross_term_blinders(a, b, blinders, fq_sponge)
+    }
+
+    /// Same as [Self::fold_instance_witness_pair], but the cross terms are
+    /// committed with random blinders, so that the commitment to the error
+    /// term of the folded instance does not leak information on the witnesses.
+    /// The blinders are returned in [FoldingOutput::t_blinders] and are
+    /// accumulated in the blinder of the folded instance, which must then be
+    /// used by the final proof.
+    ///
+    /// The commitments to the witness columns are blinded with the blinder of
+    /// the instances, see [Instance::get_blinder], which must also be random
+    /// for the final proof to be zero-knowledge, e.g. by building the
+    /// instances with [standard_config::StandardInstance::from_witness_zk].
+    #[allow(clippy::type_complexity)]
+    pub fn fold_instance_witness_pair_zk<A, B, Sponge, RNG>(
+        &self,
+        a: A,
+        b: B,
+        fq_sponge: &mut Sponge,
+        rng: &mut RNG,
+    ) -> Result<FoldingOutput<CF>, FoldingError>
+    where
+        A: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
+        B: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+        RNG: RngCore + CryptoRng,
+    {
+        let blinders = [(); 2].map(|_| ScalarField::<CF>::rand(rng));
+        self.fold_with_cross_term_blinders(a, b, blinders, fq_sponge)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn fold_with_cross_term_blinders<A, B, Sponge>(
+        &self,
+        a: A,
+        b: B,
+        t_blinders: [ScalarField<CF>; 2],
+        fq_sponge: &mut Sponge,
+    ) -> Result<FoldingOutput<CF>, FoldingError>
     where
         A: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
         B: RelaxablePair<CF::Curve, CF::Instance, CF::Witness>,
@@ -195,6 +295,9 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
     {
         let a = a.relax(&self.zero_vec);
         let b = b.relax(&self.zero_vec);
+        let n_alphas = self.expression.number_of_alphas();
+        check_alphas(&a.0.extended_instance, n_alphas)?;
+        check_alphas(&b.0.extended_instance, n_alphas)?;
 
         let u = (a.0.u, b.0.u);
 
@@ -207,37 +310,25 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
             self.domain,
             None,
         );
+        env.check_columns(&self.expression)?;
         // Computing the additional columns, resulting of the quadritization
         // process.
         // Side-effect: commitments are added in both relaxed (extended) instance.
         let env: ExtendedEnv<CF> =
-            env.compute_extension(&self.extended_witness_generator, self.srs);
+            env.compute_extension(&self.extended_witness_generator, self.srs)?;
 
         // Computing the error terms
         let error: [Vec<ScalarField<CF>>; 2] = compute_error(&self.expression, &env, u);
         let error_evals = error.map(|e| Evaluations::from_vec_and_domain(e, self.domain));
 
         // Committing to the cross terms
-        // Default blinder for commiting to the cross terms
-        let blinders = PolyComm::new(vec![ScalarField::<CF>::one()]);
-        let error_commitments = error_evals
-            .iter()
-            .map(|e| {
-                self.srs
-                    .commit_evaluations_custom(self.domain, e, &blinders)
-                    .unwrap()
-                    .commitment
-            })
-            .collect::<Vec<_>>();
-        let error_commitments: [PolyComm<CF::Curve>; 2] = error_commitments.try_into().unwrap();
+        let error_commitments: [PolyComm<CF::Curve>; 2] = [
+            commit_cross_term(self.srs, self.domain, &error_evals[0], t_blinders[0])?,
+            commit_cross_term(self.srs, self.domain, &error_evals[1], t_blinders[1])?,
+        ];
 
         let error: [Vec<_>; 2] = error_evals.map(|e| e.evals);
 
-        // sanity check to verify that we only have one commitment in polycomm
-        // (i.e. domain = poly size)
-        assert_eq!(error_commitments[0].len(), 1);
-        assert_eq!(error_commitments[1].len(), 1);
-
         let t_0 = &error_commitments[0].get_first_chunk();
         let t_1 = &error_commitments[1].get_first_chunk();
 
@@ -247,7 +338,7 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
         fq_sponge.absorb_fr(&to_absorb.0);
         fq_sponge.absorb_g(&to_absorb.1);
 
-        let challenge = fq_sponge.challenge();
+        let challenge = self.squeeze_challenge(fq_sponge);
 
         let (
             [relaxed_extended_left_instance, relaxed_extended_right_instance],
@@ -260,48 +351,72 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
             relaxed_extended_right_instance.clone(),
             challenge,
             &error_commitments,
+            &t_blinders,
         );
 
         let folded_witness = RelaxedWitness::combine_and_sub_cross_terms(
             relaxed_extended_left_witness,
             relaxed_extended_right_witness,
             challenge,
-            error,
+            &error,
         );
-        FoldingOutput {
+        Ok(FoldingOutput {
             folded_instance,
             folded_witness,
             t_0: error_commitments[0].clone(),
             t_1: error_commitments[1].clone(),
+            t_blinders,
             relaxed_extended_left_instance,
             relaxed_extended_right_instance,
             to_absorb,
-        }
+        })
     }
 
     /// Fold two relaxable instances into a relaxed instance.
     /// It is parametrized by two different types `A` and `B` that represent
     /// "relaxable" instances to be able to fold a normal and "already relaxed"
     /// instance.
+    /// The cross terms are expected to be committed with the blinder `1`, see
+    /// [Self::fold_instance_pair_with_blinders] otherwise.
     pub fn fold_instance_pair<A, B, Sponge>(
         &self,
         a: A,
         b: B,
         error_commitments: [PolyComm<CF::Curve>; 2],
         fq_sponge: &mut Sponge,
-    ) -> RelaxedInstance<CF::Curve, CF::Instance>
+    ) -> Result<RelaxedInstance<CF::Curve, CF::Instance>, FoldingError>
     where
         A: RelaxableInstance<CF::Curve, CF::Instance>,
         B: RelaxableInstance<CF::Curve, CF::Instance>,
         Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+        CF::Curve: EndoCurve,
+    {
+        let blinders = [ScalarField::<CF>::one(); 2];
+        self.fold_instance_pair_with_blinders(a, b, error_commitments, blinders, fq_sponge)
+    }
+
+    /// Same as [Self::fold_instance_pair], for cross terms committed with the
+    /// blinders `t_blinders`, e.g. the ones returned by
+    /// [Self::fold_instance_witness_pair_zk]. The blinders are only used to
+    /// accumulate the blinder of the error commitment, and are not absorbed.
+    pub fn fold_instance_pair_with_blinders<A, B, Sponge>(
+        &self,
+        a: A,
+        b: B,
+        error_commitments: [PolyComm<CF::Curve>; 2],
+        t_blinders: [ScalarField<CF>; 2],
+        fq_sponge: &mut Sponge,
+    ) -> Result<RelaxedInstance<CF::Curve, CF::Instance>, FoldingError>
+    where
+        A: RelaxableInstance<CF::Curve, CF::Instance>,
+        B: RelaxableInstance<CF::Curve, CF::Instance>,
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+        CF::Curve: EndoCurve,
     {
         let a: RelaxedInstance<CF::Curve, CF::Instance> = a.relax();
         let b: RelaxedInstance<CF::Curve, CF::Instance> = b.relax();
 
-        // sanity check to verify that we only have one commitment in polycomm
-        // (i.e. domain = poly size)
-        assert_eq!(error_commitments[0].len(), 1);
-        assert_eq!(error_commitments[1].len(), 1);
+        check_chunks(&error_commitments)?;
 
         let to_absorb = {
             let mut left = a.to_absorb();
@@ -318,15 +433,20 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
         fq_sponge.absorb_fr(&to_absorb.0);
         fq_sponge.absorb_g(&to_absorb.1);
 
-        let challenge = fq_sponge.challenge();
-
-        RelaxedInstance::combine_and_sub_cross_terms(a, b, challenge, &error_commitments)
+        Ok(squeeze_and_combine(
+            fq_sponge,
+            self.endo,
+            a,
+            b,
+            &error_commitments,
+            &t_blinders,
+        ))
     }
 
     #[allow(clippy::type_complexity)]
     /// Verifier of the folding scheme; returns a new folded instance,
     /// which can be then compared with the one claimed to be the real
-    /// one.
+    /// one. The cross terms are expected to be committed with the blinder `1`.
     pub fn verify_fold<Sponge>(
         &self,
         left_instance: RelaxedInstance<CF::Curve, CF::Instance>,
@@ -334,10 +454,12 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
         t_0: PolyComm<CF::Curve>,
         t_1: PolyComm<CF::Curve>,
         fq_sponge: &mut Sponge,
-    ) -> RelaxedInstance<CF::Curve, CF::Instance>
+    ) -> Result<RelaxedInstance<CF::Curve, CF::Instance>, FoldingError>
     where
         Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+        CF::Curve: EndoCurve,
     {
+        check_chunks(&[t_0.clone(), t_1.clone()])?;
         let to_absorb = {
             let mut left = left_instance.to_absorb();
             let right = right_instance.to_absorb();
@@ -351,19 +473,127 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
         fq_sponge.absorb_fr(&to_absorb.0);
         fq_sponge.absorb_g(&to_absorb.1);
 
-        let challenge = fq_sponge.challenge();
-
-        RelaxedInstance::combine_and_sub_cross_terms(
+        Ok(squeeze_and_combine(
+            fq_sponge,
+            self.endo,
             // FIXME: remove clone
             left_instance.clone(),
             right_instance.clone(),
-            challenge,
             &[t_0, t_1],
-        )
+            &[ScalarField::<CF>::one(); 2],
+        ))
+    }
+}
+
+/// Squeezes the folding challenge and folds the relaxed instances `a` and
+/// `b` with the commitments to the cross terms. With the coefficients
+/// `(endo_q, endo_r)` of the endomorphism, the challenge is an endoscalar and
+/// the commitments are folded with [EndoCurve::combine_one_endo].
+pub(crate) fn squeeze_and_combine<G, I, Sponge>(
+    fq_sponge: &mut Sponge,
+    endo: Option<(G::BaseField, G::ScalarField)>,
+    a: RelaxedInstance<G, I>,
+    b: RelaxedInstance<G, I>,
+    cross_terms: &[PolyComm<G>],
+    cross_term_blinders: &[G::ScalarField],
+) -> RelaxedInstance<G, I>
+where
+    G: EndoCurve,
+    I: Instance<G>,
+    Sponge: FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    match endo {
+        Some((endo_q, endo_r)) => RelaxedInstance::combine_and_sub_cross_terms_endo(
+            a,
+            b,
+            ScalarChallenge(fq_sponge.challenge()),
+            endo_q,
+            endo_r,
+            cross_terms,
+            cross_term_blinders,
+        ),
+        None => RelaxedInstance::combine_and_sub_cross_terms(
+            a,
+            b,
+            fq_sponge.challenge(),
+            cross_terms,
+            cross_term_blinders,
+        ),
+    }
+}
+
+/// Squeezes a folding challenge. With the coefficient `endo_r` of the
+/// endomorphism, the squeezed value is interpreted as a [ScalarChallenge].
+pub(crate) fn squeeze_challenge<G, Sponge>(
+    fq_sponge: &mut Sponge,
+    endo_r: Option<G::ScalarField>,
+) -> G::ScalarField
+where
+    G: CommitmentCurve,
+    Sponge: FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    let challenge = fq_sponge.challenge();
+    match endo_r {
+        Some(endo_r) => ScalarChallenge(challenge).to_field(&endo_r),
+        None => challenge,
+    }
+}
+
+/// Checks that the instance provides the `n_alphas` alphas combining the
+/// constraints.
+pub(crate) fn check_alphas<G: CommitmentCurve, I: Instance<G>>(
+    instance: &I,
+    n_alphas: usize,
+) -> Result<(), FoldingError> {
+    match n_alphas.checked_sub(1) {
+        Some(last) if instance.get_alphas().get(last).is_none() => {
+            Err(FoldingError::MissingAlphas(n_alphas))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the commitments have a single chunk, i.e. that the domain is
+/// not larger than the SRS.
+pub(crate) fn check_chunks<G: CommitmentCurve>(
+    commitments: &[PolyComm<G>],
+) -> Result<(), FoldingError> {
+    match commitments.iter().find(|c| c.len() != 1) {
+        Some(c) => Err(FoldingError::CommitmentChunks(c.len())),
+        None => Ok(()),
     }
 }
 
+/// Commits to a cross term with the given blinder, which is `1` unless the
+/// cross terms are blinded.
+pub(crate) fn commit_cross_term<G: CommitmentCurve, Srs: SRS<G>>(
+    srs: &Srs,
+    domain: Radix2EvaluationDomain<G::ScalarField>,
+    evals: &Evals<G::ScalarField>,
+    blinder: G::ScalarField,
+) -> Result<PolyComm<G>, FoldingError> {
+    // The evaluations are shorter than the domain if the witness columns are
+    if evals.evals.len() != domain.size() {
+        return Err(FoldingError::WitnessSizeMismatch(
+            evals.evals.len(),
+            domain.size(),
+        ));
+    }
+    let blinders = PolyComm::new(vec![blinder]);
+    let commitment = srs
+        .commit_evaluations_custom(domain, evals, &blinders)?
+        .commitment;
+    check_chunks(std::slice::from_ref(&commitment))?;
+    Ok(commitment)
+}
+
 /// Output of the folding prover
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Instance: Serialize, C::Witness: Serialize",
+    deserialize = "C::Instance: Deserialize<'de>, C::Witness: Deserialize<'de>"
+))]
 pub struct FoldingOutput<C: FoldingConfig> {
     /// The folded instance, containing, in particular, the result `C_l + r C_r`
     pub folded_instance: RelaxedInstance<C::Curve, C::Instance>,
@@ -376,6 +606,10 @@ pub struct FoldingOutput<C: FoldingConfig> {
     /// The error terms of degree 2, see the top-level documentation of
     /// [crate::expressions]
     pub t_1: PolyComm<C::Curve>,
+    /// The blinders of the commitments `t_0` and `t_1`, which are `1` unless
+    /// the cross terms are blinded
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; 2]")]
+    pub t_blinders: [ScalarField<C>; 2],
     /// The left relaxed instance, including the potential additional columns
     /// added by quadritization
     pub relaxed_extended_left_instance: RelaxedInstance<C::Curve, C::Instance>,
@@ -383,6 +617,9 @@ pub struct FoldingOutput<C: FoldingConfig> {
     /// added by quadritization
     pub relaxed_extended_right_instance: RelaxedInstance<C::Curve, C::Instance>,
     /// Elements to absorbed in IVC, in the same order as done in folding
+    #[serde_as(
+        as = "(Vec<o1_utils::serialization::SerdeAs>, Vec<o1_utils::serialization::SerdeAs>)"
+    )]
     pub to_absorb: (Vec<ScalarField<C>>, Vec<C::Curve>),
 }
 
@@ -421,6 +658,40 @@ impl<F: Field> PartialEq for Alphas<F> {
 
 impl<F: Field> Eq for Alphas<F> {}
 
+/// Serialized form of [Alphas]. The counter of the powers is shared between
+/// the clones of an instance, and is not shared anymore after a roundtrip.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "F: Field")]
+enum SerializedAlphas<F: Field> {
+    Powers(
+        #[serde_as(as = "o1_utils::serialization::SerdeAs")] F,
+        usize,
+    ),
+    Combinations(#[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")] Vec<F>),
+}
+
+impl<F: Field> Serialize for Alphas<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let alphas = match self {
+            Alphas::Powers(alpha, count) => {
+                SerializedAlphas::Powers(*alpha, count.load(Ordering::Relaxed))
+            }
+            Alphas::Combinations(alphas) => SerializedAlphas::Combinations(alphas.clone()),
+        };
+        alphas.serialize(serializer)
+    }
+}
+
+impl<'de, F: Field> Deserialize<'de> for Alphas<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match SerializedAlphas::deserialize(deserializer)? {
+            SerializedAlphas::Powers(alpha, count) => Alphas::new_sized(alpha, count),
+            SerializedAlphas::Combinations(alphas) => Alphas::Combinations(alphas),
+        })
+    }
+}
+
 impl<F: Field> Foldable<F> for Alphas<F> {
     fn combine(a: Self, b: Self, challenge: F) -> Self {
         let a = a.powers();
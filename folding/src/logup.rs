@@ -0,0 +1,197 @@
+//! This module provides the constraints and the witness of the additive
+//! lookup argument, "logup", in a form that can be folded.
+//!
+//! The argument is the one described in the [documentation of the MSM
+//! crate](https://o1-labs.github.io/proof-systems/rustdoc/kimchi_msm/logup/index.html):
+//! each row contributes to the sum of fractions
+//! ```text
+//!         n_{i}
+//! ∑  -------------------   with   v_{i} = ∑_{k} γ^k v_{i, k}
+//! i  β + v_{i}
+//! ```
+//! where the looked-up values have numerators `1`, and the tables have
+//! numerators `-m`, `m` being the multiplicities. The tables identifiers, if
+//! any, are simply a coordinate of the vectors `v_{i}`.
+//!
+//! The lookups of a row are split in chunks, and the sum of each chunk is
+//! given by an "inner sum" column `h_{c}`, constrained by
+//! ```text
+//! h_{c} ∏_{i} (β + v_{i}) = ∑_{i} n_{i} ∏_{j ≠ i} (β + v_{j})
+//! ```
+//! The accumulator column `φ` sums the inner sums over the rows, and is
+//! constrained by `φ(ω X) = φ(X) + ∑_{c} h_{c}(X)` on all the rows, including
+//! the last one. Therefore, the total sum must be zero.
+//!
+//! All the constraints are polynomials in the columns and in the challenges
+//! `β` and `γ`, and are folded like any other constraint: the challenges are
+//! folded as the other variables of the instances, and the inner sums and the
+//! accumulator are witness columns, committed to in the instances and folded
+//! with them.
+//! The tables can be fixed columns of the structure, which are the same for
+//! all the instances and are not folded, or witness columns for runtime
+//! tables, folded like the other columns.
+//! As the constraints have a degree increasing with the number of lookups per
+//! chunk, they are meant to be used with [crate::high_degree] or with
+//! [crate::FoldingScheme] after quadraticization.
+
+use crate::{
+    expressions::{FoldingCompatibleExpr, FoldingCompatibleExprInner},
+    FoldingConfig, ScalarField,
+};
+use ark_ff::{Field, One, Zero};
+use kimchi::circuits::{expr::Variable, gate::CurrOrNext};
+use std::collections::HashMap;
+
+/// A (vector) lookup, i.e. the fraction `numerator / (β + ∑_{k} γ^k value_k)`
+/// of the sum. `T` is a folding expression for the constraints, and a field
+/// element for the witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Logup<T> {
+    pub numerator: T,
+    pub value: Vec<T>,
+}
+
+impl<T> Logup<T> {
+    pub fn new(numerator: T, value: Vec<T>) -> Self {
+        Self { numerator, value }
+    }
+}
+
+/// A chunk of the lookups of a row, whose sum is given by the witness column
+/// `inner_sum`
+pub struct LogupChunk<C: FoldingConfig> {
+    pub inner_sum: C::Column,
+    pub lookups: Vec<Logup<FoldingCompatibleExpr<C>>>,
+}
+
+/// The lookups of a row, with the columns of the argument
+pub struct LogupArgument<C: FoldingConfig> {
+    /// The challenge `β`, the evaluation point of the log-derivatives
+    pub beta: C::Challenge,
+    /// The challenge `γ` combining the coordinates of the vector lookups
+    pub joint_combiner: C::Challenge,
+    /// The chunks of lookups, each with the witness column of its inner sum
+    pub chunks: Vec<LogupChunk<C>>,
+    /// The witness column of the accumulator `φ`
+    pub accumulator: C::Column,
+}
+
+impl<C: FoldingConfig> LogupArgument<C> {
+    /// Returns the constraints of the argument, one for each chunk, and one
+    /// for the accumulator
+    pub fn constraints(&self) -> Vec<FoldingCompatibleExpr<C>> {
+        let mut constraints: Vec<_> = self
+            .chunks
+            .iter()
+            .map(|LogupChunk { inner_sum, lookups }| {
+                let denominators: Vec<_> = lookups
+                    .iter()
+                    .map(|lookup| self.denominator(&lookup.value))
+                    .collect();
+                let product = |skip: Option<usize>| {
+                    denominators
+                        .iter()
+                        .enumerate()
+                        .filter(|(j, _)| Some(*j) != skip)
+                        .map(|(_, d)| d.clone())
+                        .reduce(|acc, d| acc * d)
+                        .unwrap_or_else(|| constant(ScalarField::<C>::one()))
+                };
+                let numerator = lookups
+                    .iter()
+                    .enumerate()
+                    .map(|(i, lookup)| lookup.numerator.clone() * product(Some(i)))
+                    .reduce(|acc, n| acc + n)
+                    .unwrap_or_else(|| constant(ScalarField::<C>::zero()));
+                cell(*inner_sum, CurrOrNext::Curr) * product(None) - numerator
+            })
+            .collect();
+        let accumulator = self.chunks.iter().fold(
+            cell(self.accumulator, CurrOrNext::Next) - cell(self.accumulator, CurrOrNext::Curr),
+            |acc, chunk| acc - cell(chunk.inner_sum, CurrOrNext::Curr),
+        );
+        constraints.push(accumulator);
+        constraints
+    }
+
+    /// `β + ∑_{k} γ^k value_k`
+    fn denominator(&self, value: &[FoldingCompatibleExpr<C>]) -> FoldingCompatibleExpr<C> {
+        let beta = challenge(self.beta);
+        let joint_combiner = challenge(self.joint_combiner);
+        // Horner's method, from the last coordinate
+        let combined = value
+            .iter()
+            .rev()
+            .cloned()
+            .reduce(|acc, v| v + joint_combiner.clone() * acc)
+            .unwrap_or_else(|| constant(ScalarField::<C>::zero()));
+        beta + combined
+    }
+}
+
+fn cell<C: FoldingConfig>(col: C::Column, row: CurrOrNext) -> FoldingCompatibleExpr<C> {
+    FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Cell(Variable { col, row }))
+}
+
+fn challenge<C: FoldingConfig>(chal: C::Challenge) -> FoldingCompatibleExpr<C> {
+    FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Challenge(chal))
+}
+
+fn constant<C: FoldingConfig>(c: ScalarField<C>) -> FoldingCompatibleExpr<C> {
+    FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Constant(c))
+}
+
+/// Computes the evaluations of the inner sum of a chunk, given the lookups of
+/// the chunk on each row
+pub fn inner_sum<F: Field>(beta: F, joint_combiner: F, rows: &[Vec<Logup<F>>]) -> Vec<F> {
+    let mut denominators: Vec<F> = rows
+        .iter()
+        .flat_map(|lookups| {
+            lookups.iter().map(|lookup| {
+                beta + lookup
+                    .value
+                    .iter()
+                    .rev()
+                    .fold(F::zero(), |acc, v| *v + joint_combiner * acc)
+            })
+        })
+        .collect();
+    ark_ff::batch_inversion(&mut denominators);
+    let mut inverses = denominators.into_iter();
+    rows.iter()
+        .map(|lookups| {
+            lookups
+                .iter()
+                .map(|lookup| lookup.numerator * inverses.next().unwrap())
+                .sum()
+        })
+        .collect()
+}
+
+/// Computes the evaluations of the accumulator `φ` from the inner sums of all
+/// the chunks, starting from zero on the first row.
+pub fn accumulator<F: Field>(inner_sums: &[Vec<F>]) -> Vec<F> {
+    let size = inner_sums.first().map_or(0, |h| h.len());
+    (0..size)
+        .scan(F::zero(), |phi, row| {
+            let current = *phi;
+            *phi += inner_sums.iter().map(|h| h[row]).sum::<F>();
+            Some(current)
+        })
+        .collect()
+}
+
+/// Computes the multiplicities of the entries of a table, given the looked-up
+/// values. Returns `None` if a value is not in the table.
+pub fn multiplicities<F: Field>(table: &[Vec<F>], values: &[Vec<F>]) -> Option<Vec<F>> {
+    let positions: HashMap<&[F], usize> = table
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| (entry.as_slice(), i))
+        .collect();
+    let mut counts = vec![F::zero(); table.len()];
+    for value in values {
+        counts[*positions.get(value.as_slice())?] += F::one();
+    }
+    Some(counts)
+}
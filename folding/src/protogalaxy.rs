@@ -0,0 +1,560 @@
+//! This module implements a folding scheme based on
+//! [ProtoGalaxy](https://eprint.iacr.org/2023/1106), folding any number `k`
+//! of instances into an accumulator at once.
+//!
+//! Contrary to the Nova-based scheme of [crate::FoldingScheme], the
+//! expressions do not need to be reduced to degree `2` nor homogenized: the
+//! constraints `C_{l}` provided by the user are combined with the alphas of
+//! the instance into a single polynomial `f = ∑_{l} α_{l} C_{l}`, of degree
+//! `d`, evaluated on each row `i` of the witness, giving `f_{i}`.
+//!
+//! An accumulator is an instance `φ`, a vector `β` of `t = log2(n)` points,
+//! where `n` is the size of the domain, and a scalar error `e`, such that:
+//! ```text
+//! ∑_{i} pow_{i}(β) f_{i}(φ, w) = e
+//! where pow_{i}(β) = ∏_{j} β_{j}^{i_{j}}, i_{j} being the bit j of i
+//! ```
+//! A fresh instance satisfying the constraints is an accumulator with any `β`
+//! and `e = 0`, see [ProtoGalaxyScheme::accumulate].
+//!
+//! To fold the accumulator `(φ_{0}, β, e)` with the instances `φ_{1}, ...,
+//! φ_{k}`:
+//! 1. The instances are absorbed and a challenge `δ` is coined, giving the
+//!    points `δ_{j} = δ^{2^j}`.
+//! 2. The prover sends the coefficients of degree `1` to `t` of
+//!    `F(X) = ∑_{i} pow_{i}(β + X δ) f_{i}(φ_{0}, w_{0})`, whose constant
+//!    coefficient is `e`, and a challenge `α` is coined. We set
+//!    `β* = β + α δ`.
+//! 3. With `L_{j}` the Lagrange polynomials of the points `0, ..., k`, and
+//!    `Z` their vanishing polynomial, the prover sends the coefficients of the
+//!    polynomial `K` such that
+//!    `G(X) = ∑_{i} pow_{i}(β*) f_{i}(∑_{j} L_{j}(X) φ_{j}, ∑_{j} L_{j}(X) w_{j})
+//!          = F(α) L_{0}(X) + Z(X) K(X)`,
+//!    and a challenge `γ` is coined.
+//! 4. The folded accumulator is `(∑_{j} L_{j}(γ) φ_{j}, β*, F(α) L_{0}(γ) +
+//!    Z(γ) K(γ))`, with the witness `∑_{j} L_{j}(γ) w_{j}`.
+//!
+//! The verifier only handles `t + d k - k` scalars, and no commitment to an
+//! error term is needed. The linear combinations of the instances and
+//! witnesses are computed with [Foldable::combine], so that any
+//! [FoldingConfig] can be used with this scheme.
+
+use crate::{
+    check_alphas,
+    eval_leaf::EvalLeaf,
+    expressions::{ExpExtension, FoldingCompatibleExpr, FoldingCompatibleExprInner},
+    instance_witness::Foldable,
+    BaseField, FoldingConfig, FoldingEnv, FoldingError, Instance, ScalarField, Side,
+};
+use ark_ff::{Field, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use kimchi::circuits::expr::Variable;
+use mina_poseidon::FqSponge;
+use poly_commitment::commitment::CommitmentCurve;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// An instance accumulated by the ProtoGalaxy folding scheme, see the
+/// [module documentation](self).
+#[serde_as]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "I: Serialize", deserialize = "I: Deserialize<'de>"))]
+pub struct AccumulatedInstance<G: CommitmentCurve, I: Instance<G>> {
+    /// The combination of the folded instances
+    pub instance: I,
+    /// The points `β_{j}`, one for each bit of the index of a row
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub betas: Vec<G::ScalarField>,
+    /// The combination `e` of the constraints over all the rows
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub error: G::ScalarField,
+}
+
+impl<G: CommitmentCurve, I: Instance<G>> AccumulatedInstance<G, I> {
+    /// Returns the elements to be absorbed by the sponge
+    ///
+    /// The scalar elements of the instance are appended with the points `β`
+    /// and the error `e`.
+    pub fn to_absorb(&self) -> (Vec<G::ScalarField>, Vec<G>) {
+        let mut elements = self.instance.to_absorb();
+        elements.0.extend(self.betas.iter());
+        elements.0.push(self.error);
+        elements
+    }
+}
+
+/// The messages of the prover, sent to the verifier to fold the instances
+#[serde_as]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "F: Field")]
+pub struct ProtoGalaxyProof<F> {
+    /// The coefficients of degree `1` to `t` of the polynomial `F`
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub f_coefficients: Vec<F>,
+    /// The coefficients of the polynomial `K`
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub k_coefficients: Vec<F>,
+}
+
+/// Output of the ProtoGalaxy prover
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Instance: Serialize, C::Witness: Serialize",
+    deserialize = "C::Instance: Deserialize<'de>, C::Witness: Deserialize<'de>"
+))]
+pub struct ProtoGalaxyOutput<C: FoldingConfig> {
+    pub folded_instance: AccumulatedInstance<C::Curve, C::Instance>,
+    pub folded_witness: C::Witness,
+    pub proof: ProtoGalaxyProof<ScalarField<C>>,
+}
+
+impl<C: FoldingConfig> ProtoGalaxyOutput<C> {
+    pub fn pair(self) -> (AccumulatedInstance<C::Curve, C::Instance>, C::Witness) {
+        (self.folded_instance, self.folded_witness)
+    }
+}
+
+pub struct ProtoGalaxyScheme<CF: FoldingConfig> {
+    pub constraints: Vec<FoldingCompatibleExpr<CF>>,
+    pub domain: Radix2EvaluationDomain<ScalarField<CF>>,
+    pub structure: CF::Structure,
+    /// Degree of the combination of the constraints, alphas included
+    degree: usize,
+}
+
+impl<CF: FoldingConfig> ProtoGalaxyScheme<CF> {
+    pub fn new(
+        constraints: Vec<FoldingCompatibleExpr<CF>>,
+        domain: Radix2EvaluationDomain<ScalarField<CF>>,
+        structure: &CF::Structure,
+    ) -> Self {
+        // The alphas increase the degree by one
+        let degree = constraints
+            .iter()
+            .map(|c| c.degree() + 1)
+            .max()
+            .unwrap_or(0);
+        Self {
+            constraints,
+            domain,
+            structure: structure.clone(),
+            degree,
+        }
+    }
+
+    /// Return the degree of the combination of the constraints, including
+    /// the alphas
+    pub fn get_degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Number of points `β`, i.e. the logarithm of the size of the domain
+    pub fn get_number_of_betas(&self) -> usize {
+        self.domain.log_size_of_group as usize
+    }
+
+    /// Makes an accumulator from an instance and its witness satisfying the
+    /// constraints, with the points `β` set to zero.
+    pub fn accumulate(
+        &self,
+        instance: CF::Instance,
+        witness: CF::Witness,
+    ) -> (AccumulatedInstance<CF::Curve, CF::Instance>, CF::Witness) {
+        let accumulated = AccumulatedInstance {
+            instance,
+            betas: vec![ScalarField::<CF>::zero(); self.get_number_of_betas()],
+            error: ScalarField::<CF>::zero(),
+        };
+        (accumulated, witness)
+    }
+
+    /// Evaluates `f = ∑_{l} α_{l} C_{l}` on each row of the witness.
+    pub fn evaluate_rows(
+        &self,
+        instance: &CF::Instance,
+        witness: &CF::Witness,
+    ) -> Vec<ScalarField<CF>> {
+        let env = <CF::Env>::new(&self.structure, [instance, instance], [witness, witness]);
+        let alphas = instance.get_alphas();
+        let zero = EvalLeaf::Result(vec![ScalarField::<CF>::zero(); self.domain.size()]);
+        let f = self
+            .constraints
+            .iter()
+            .enumerate()
+            .fold(zero, |acc, (i, constraint)| {
+                let alpha = alphas.get(i).expect("alpha not present");
+                acc + eval_rows(constraint, &env, instance) * alpha
+            });
+        f.unwrap()
+    }
+
+    /// The decider of the accumulators: checks that
+    /// `∑_{i} pow_{i}(β) f_{i}(φ, w) = e`.
+    pub fn check(
+        &self,
+        accumulated: &AccumulatedInstance<CF::Curve, CF::Instance>,
+        witness: &CF::Witness,
+    ) -> bool {
+        let rows = self.evaluate_rows(&accumulated.instance, witness);
+        inner_product(&pows(&accumulated.betas), &rows) == accumulated.error
+    }
+
+    /// This is the main entry point to fold instances and their witnesses
+    /// into an accumulator, see the [module documentation](self).
+    /// An error is returned if no instance is given, if the accumulator does
+    /// not have the points `β` of the domain, if the instances do not provide
+    /// enough alphas, or if the witnesses do not have the size of the domain.
+    pub fn fold<Sponge>(
+        &self,
+        accumulator: (AccumulatedInstance<CF::Curve, CF::Instance>, CF::Witness),
+        instances: Vec<(CF::Instance, CF::Witness)>,
+        fq_sponge: &mut Sponge,
+    ) -> Result<ProtoGalaxyOutput<CF>, FoldingError>
+    where
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+    {
+        if instances.is_empty() {
+            return Err(FoldingError::NoInstance);
+        }
+        let (accumulated, accumulated_witness) = accumulator;
+        self.check_betas(&accumulated)?;
+        let (instances, witnesses): (Vec<_>, Vec<_>) = instances.into_iter().unzip();
+        self.set_alphas(&accumulated.instance, &instances)?;
+
+        let deltas = self.absorb_instances(&accumulated, &instances, fq_sponge);
+
+        // Computing F, of degree t, from its evaluations at 0, ..., t
+        let rows = self.evaluate_rows(&accumulated.instance, &accumulated_witness);
+        if rows.len() != self.domain.size() {
+            return Err(FoldingError::WitnessSizeMismatch(
+                rows.len(),
+                self.domain.size(),
+            ));
+        }
+        let t = self.get_number_of_betas();
+        let points: Vec<ScalarField<CF>> = (0..=t as u64).map(ScalarField::<CF>::from).collect();
+        let f_evaluations: Vec<_> = points
+            .iter()
+            .map(|x| {
+                inner_product(
+                    &pows(&shifted_betas(&accumulated.betas, &deltas, *x)),
+                    &rows,
+                )
+            })
+            .collect();
+        let f = interpolate(&points, &f_evaluations);
+        debug_assert_eq!(f[0], accumulated.error);
+        let f_coefficients = f[1..].to_vec();
+        fq_sponge.absorb_fr(&f_coefficients);
+        let alpha = fq_sponge.challenge();
+        let f_alpha = evaluate(&f, alpha);
+        let betas = shifted_betas(&accumulated.betas, &deltas, alpha);
+        let pows_betas = pows(&betas);
+
+        // Computing K, of degree d k - k - 1, from its evaluations outside of
+        // the points of the instances
+        let n_instances = instances.len() + 1;
+        let all_instances: Vec<_> = std::iter::once(accumulated.instance)
+            .chain(instances)
+            .collect();
+        let all_witnesses: Vec<_> = std::iter::once(accumulated_witness)
+            .chain(witnesses)
+            .collect();
+        let k_points: Vec<ScalarField<CF>> = (n_instances..=self.g_degree(n_instances))
+            .map(|x| ScalarField::<CF>::from(x as u64))
+            .collect();
+        let k_evaluations: Vec<_> = k_points
+            .iter()
+            .map(|x| {
+                let lagrange = lagrange_basis(n_instances, *x);
+                let instance = linear_combination(&all_instances, &lagrange);
+                let witness = linear_combination(&all_witnesses, &lagrange);
+                let g = inner_product(&pows_betas, &self.evaluate_rows(&instance, &witness));
+                let z = vanishing(n_instances, *x);
+                (g - f_alpha * lagrange[0]) * z.inverse().unwrap()
+            })
+            .collect();
+        let k_coefficients = interpolate(&k_points, &k_evaluations);
+        fq_sponge.absorb_fr(&k_coefficients);
+        let gamma = fq_sponge.challenge();
+
+        let lagrange = lagrange_basis(n_instances, gamma);
+        let error = f_alpha * lagrange[0]
+            + vanishing(n_instances, gamma) * evaluate(&k_coefficients, gamma);
+        let folded_instance = AccumulatedInstance {
+            instance: linear_combination(&all_instances, &lagrange),
+            betas,
+            error,
+        };
+        let folded_witness = linear_combination(&all_witnesses, &lagrange);
+        Ok(ProtoGalaxyOutput {
+            folded_instance,
+            folded_witness,
+            proof: ProtoGalaxyProof {
+                f_coefficients,
+                k_coefficients,
+            },
+        })
+    }
+
+    /// Verifier of the folding scheme; returns a new accumulated instance,
+    /// which can be then compared with the one claimed to be the real one.
+    pub fn verify_fold<Sponge>(
+        &self,
+        accumulated: AccumulatedInstance<CF::Curve, CF::Instance>,
+        instances: Vec<CF::Instance>,
+        proof: &ProtoGalaxyProof<ScalarField<CF>>,
+        fq_sponge: &mut Sponge,
+    ) -> Result<AccumulatedInstance<CF::Curve, CF::Instance>, FoldingError>
+    where
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+    {
+        if instances.is_empty() {
+            return Err(FoldingError::NoInstance);
+        }
+        self.check_betas(&accumulated)?;
+        let n_instances = instances.len() + 1;
+        // F has degree t, and K has degree d k - k - 1
+        let expected = (
+            self.get_number_of_betas(),
+            self.g_degree(n_instances) + 1 - n_instances,
+        );
+        if proof.f_coefficients.len() != expected.0 {
+            return Err(FoldingError::CoefficientsCount(
+                proof.f_coefficients.len(),
+                expected.0,
+            ));
+        }
+        if proof.k_coefficients.len() != expected.1 {
+            return Err(FoldingError::CoefficientsCount(
+                proof.k_coefficients.len(),
+                expected.1,
+            ));
+        }
+        self.set_alphas(&accumulated.instance, &instances)?;
+
+        let deltas = self.absorb_instances(&accumulated, &instances, fq_sponge);
+
+        fq_sponge.absorb_fr(&proof.f_coefficients);
+        let alpha = fq_sponge.challenge();
+        let f = std::iter::once(accumulated.error)
+            .chain(proof.f_coefficients.iter().cloned())
+            .collect::<Vec<_>>();
+        let f_alpha = evaluate(&f, alpha);
+        let betas = shifted_betas(&accumulated.betas, &deltas, alpha);
+
+        fq_sponge.absorb_fr(&proof.k_coefficients);
+        let gamma = fq_sponge.challenge();
+
+        let lagrange = lagrange_basis(n_instances, gamma);
+        let error = f_alpha * lagrange[0]
+            + vanishing(n_instances, gamma) * evaluate(&proof.k_coefficients, gamma);
+        let all_instances: Vec<_> = std::iter::once(accumulated.instance)
+            .chain(instances)
+            .collect();
+        Ok(AccumulatedInstance {
+            instance: linear_combination(&all_instances, &lagrange),
+            betas,
+            error,
+        })
+    }
+
+    /// Degree of the polynomial `G` when folding `n_instances` instances,
+    /// the accumulator included
+    fn g_degree(&self, n_instances: usize) -> usize {
+        self.degree * (n_instances - 1)
+    }
+
+    /// The alphas are only combined with the ones of the other instances, so
+    /// their number must be known before combining the instances.
+    fn set_alphas(
+        &self,
+        accumulated: &CF::Instance,
+        instances: &[CF::Instance],
+    ) -> Result<(), FoldingError> {
+        std::iter::once(accumulated)
+            .chain(instances.iter())
+            .try_for_each(|instance| check_alphas(instance, self.constraints.len()))
+    }
+
+    fn check_betas(
+        &self,
+        accumulated: &AccumulatedInstance<CF::Curve, CF::Instance>,
+    ) -> Result<(), FoldingError> {
+        if accumulated.betas.len() != self.get_number_of_betas() {
+            return Err(FoldingError::BetasCount(
+                accumulated.betas.len(),
+                self.get_number_of_betas(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Absorbs the accumulator and the instances, and returns the points
+    /// `δ_{j} = δ^{2^j}`.
+    fn absorb_instances<Sponge>(
+        &self,
+        accumulated: &AccumulatedInstance<CF::Curve, CF::Instance>,
+        instances: &[CF::Instance],
+        fq_sponge: &mut Sponge,
+    ) -> Vec<ScalarField<CF>>
+    where
+        Sponge: FqSponge<BaseField<CF>, CF::Curve, ScalarField<CF>>,
+    {
+        let mut to_absorb = accumulated.to_absorb();
+        for instance in instances {
+            let (scalars, points) = instance.to_absorb();
+            to_absorb.0.extend(scalars);
+            to_absorb.1.extend(points);
+        }
+        fq_sponge.absorb_fr(&to_absorb.0);
+        fq_sponge.absorb_g(&to_absorb.1);
+        let delta = fq_sponge.challenge();
+        std::iter::successors(Some(delta), |d| Some(d.square()))
+            .take(self.get_number_of_betas())
+            .collect()
+    }
+}
+
+/// Evaluates an expression on each row of the witness of the left side of
+/// the environment
+fn eval_rows<'a, C: FoldingConfig>(
+    exp: &FoldingCompatibleExpr<C>,
+    env: &'a C::Env,
+    instance: &C::Instance,
+) -> EvalLeaf<'a, ScalarField<C>> {
+    use FoldingCompatibleExpr::*;
+    match exp {
+        Atom(FoldingCompatibleExprInner::Constant(c)) => EvalLeaf::Const(*c),
+        Atom(FoldingCompatibleExprInner::Challenge(chal)) => {
+            EvalLeaf::Const(env.challenge(*chal, Side::Left))
+        }
+        Atom(FoldingCompatibleExprInner::Cell(Variable { col, row })) => {
+            EvalLeaf::Col(env.col(*col, *row, Side::Left))
+        }
+        Atom(FoldingCompatibleExprInner::Extensions(ext)) => match ext {
+            ExpExtension::Alpha(i) => {
+                EvalLeaf::Const(instance.get_alphas().get(*i).expect("alpha not present"))
+            }
+            ExpExtension::Selector(s) => EvalLeaf::Col(env.selector(s, Side::Left)),
+            _ => panic!("this should only be created by folding itself"),
+        },
+        Double(e) => eval_rows(e, env, instance).map(Field::double, |f| {
+            Field::double_in_place(f);
+        }),
+        Square(e) => eval_rows(e, env, instance).map(Field::square, |f| {
+            Field::square_in_place(f);
+        }),
+        Add(e1, e2) => eval_rows(e1, env, instance) + eval_rows(e2, env, instance),
+        Sub(e1, e2) => eval_rows(e1, env, instance) - eval_rows(e2, env, instance),
+        Mul(e1, e2) => eval_rows(e1, env, instance) * eval_rows(e2, env, instance),
+        Pow(e, p) => {
+            let p = [*p];
+            eval_rows(e, env, instance).map(|x| x.pow(p), |x| *x = x.pow(p))
+        }
+    }
+}
+
+/// The values `pow_{i}(β)` for all the rows `i`
+fn pows<F: Field>(betas: &[F]) -> Vec<F> {
+    let mut pows = Vec::with_capacity(1 << betas.len());
+    pows.push(F::one());
+    for beta in betas {
+        let half: Vec<F> = pows.iter().map(|x| *x * beta).collect();
+        pows.extend(half);
+    }
+    pows
+}
+
+/// The points `β + x δ`
+fn shifted_betas<F: Field>(betas: &[F], deltas: &[F], x: F) -> Vec<F> {
+    betas
+        .iter()
+        .zip(deltas.iter())
+        .map(|(beta, delta)| *beta + x * delta)
+        .collect()
+}
+
+fn inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b.iter()).map(|(a, b)| *a * b).sum()
+}
+
+/// The values `L_{j}(x)` of the Lagrange polynomials of the points
+/// `0, ..., n - 1`
+fn lagrange_basis<F: Field>(n: usize, x: F) -> Vec<F> {
+    (0..n)
+        .map(|j| {
+            let xj = F::from(j as u64);
+            let (num, den) =
+                (0..n)
+                    .filter(|l| *l != j)
+                    .fold((F::one(), F::one()), |(num, den), l| {
+                        let xl = F::from(l as u64);
+                        (num * (x - xl), den * (xj - xl))
+                    });
+            num * den.inverse().unwrap()
+        })
+        .collect()
+}
+
+/// The value `Z(x)` of the vanishing polynomial of the points `0, ..., n - 1`
+fn vanishing<F: Field>(n: usize, x: F) -> F {
+    (0..n).map(|l| x - F::from(l as u64)).product()
+}
+
+/// Computes `∑_{j} c_{j} v_{j}` with [Foldable::combine], given that the
+/// coefficients sum to one, as `v_{0} + ∑_{j > 0} c_{j} (v_{j} - v_{0})`.
+fn linear_combination<F: Field, T: Foldable<F> + Clone>(values: &[T], coefficients: &[F]) -> T {
+    assert_eq!(values.len(), coefficients.len());
+    debug_assert_eq!(coefficients.iter().copied().sum::<F>(), F::one());
+    values[1..].iter().zip(coefficients[1..].iter()).fold(
+        values[0].clone(),
+        |acc, (value, coefficient)| {
+            let difference = T::combine(value.clone(), values[0].clone(), -F::one());
+            T::combine(acc, difference, *coefficient)
+        },
+    )
+}
+
+/// The coefficients of the polynomial of degree `n - 1` taking the values
+/// `ys` at the `n` points `xs`
+fn interpolate<F: Field>(xs: &[F], ys: &[F]) -> Vec<F> {
+    // The coefficients of Z(X) = ∏ (X - x_{l})
+    let z = xs.iter().fold(vec![F::one()], |z, x| {
+        let mut res = vec![F::zero(); z.len() + 1];
+        for (i, c) in z.iter().enumerate() {
+            res[i + 1] += c;
+            res[i] -= *c * x;
+        }
+        res
+    });
+    let mut coefficients = vec![F::zero(); xs.len()];
+    for (j, (xj, yj)) in xs.iter().zip(ys.iter()).enumerate() {
+        // Z(X) / (X - x_{j}), by synthetic division
+        let mut quotient = vec![F::zero(); xs.len()];
+        let mut carry = F::zero();
+        for i in (0..xs.len()).rev() {
+            carry = z[i + 1] + carry * xj;
+            quotient[i] = carry;
+        }
+        let den: F = xs
+            .iter()
+            .enumerate()
+            .filter(|(l, _)| *l != j)
+            .map(|(_, xl)| *xj - xl)
+            .product();
+        let scale = *yj * den.inverse().unwrap();
+        for (c, q) in coefficients.iter_mut().zip(quotient) {
+            *c += scale * q;
+        }
+    }
+    coefficients
+}
+
+/// Evaluates the polynomial of coefficients `coefficients` at `x`
+fn evaluate<F: Field>(coefficients: &[F], x: F) -> F {
+    coefficients
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, c| acc * x + c)
+}
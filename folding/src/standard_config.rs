@@ -1,21 +1,66 @@
 //! This module offers a standard implementation of [FoldingConfig] supporting
-//! many use cases
+//! many use cases.
+//! It also provides the instances [StandardInstance] and the witnesses
+//! [StandardWitness] of relations over a fixed number of witness columns,
+//! which can be used with [StandardConfig] for the columns and the challenges
+//! implementing [StandardIndex], e.g. with the macro [crate::standard_index].
+//! A new relation then only requires its column and challenge types and its
+//! constraints:
+//! ```ignore
+//! #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! enum MyCol {
+//!     A,
+//!     B,
+//!     C,
+//! }
+//!
+//! impl FoldingColumnTrait for MyCol {
+//!     fn is_witness(&self) -> bool {
+//!         true
+//!     }
+//! }
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! enum MyChallenge {
+//!     Beta,
+//! }
+//!
+//! folding::standard_index!(MyCol, MyChallenge);
+//!
+//! type MyConfig = StandardConfig<
+//!     Curve,
+//!     MyCol,
+//!     MyChallenge,
+//!     StandardInstance<Curve, 3, 1>,
+//!     StandardWitness<Fp, 3>,
+//!     SRS<Curve>,
+//! >;
+//! ```
 use crate::{
-    expressions::FoldingColumnTrait, instance_witness::Witness, FoldingConfig, FoldingEnv,
-    Instance, Side,
+    expressions::FoldingColumnTrait,
+    instance_witness::{combine_commitments_endo, Foldable, Witness},
+    Alphas, FoldingConfig, FoldingEnv, Instance, Side,
 };
+use ark_ff::{FftField, One, UniformRand};
+use ark_poly::{Evaluations, Radix2EvaluationDomain};
 use derivative::Derivative;
-use kimchi::{circuits::gate::CurrOrNext, curve::KimchiCurve};
+use kimchi::circuits::gate::CurrOrNext;
 use memoization::ColumnMemoizer;
-use poly_commitment::{self, commitment::CommitmentCurve};
-use std::{fmt::Debug, hash::Hash, marker::PhantomData, ops::Index};
+use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
+use poly_commitment::{
+    self,
+    commitment::{CommitmentCurve, EndoCurve},
+    PolyComm, SRS,
+};
+use rand::{CryptoRng, RngCore};
+use std::{array, fmt::Debug, hash::Hash, marker::PhantomData, ops::Index};
 
 #[derive(Clone, Default)]
 /// Default type for when you don't need structure
-pub struct EmptyStructure<G: KimchiCurve>(PhantomData<G::ScalarField>);
+pub struct EmptyStructure<G: CommitmentCurve>(PhantomData<G::ScalarField>);
 
-impl<G: KimchiCurve, Col> Index<Col> for EmptyStructure<G> {
-    type Output = Vec<G::ScalarField>;
+impl<G: CommitmentCurve, Col> Index<Col> for EmptyStructure<G> {
+    type Output = [G::ScalarField];
 
     fn index(&self, _index: Col) -> &Self::Output {
         panic!("shouldn't reach this point, as this type only works with witness-only constraint systems");
@@ -195,6 +240,211 @@ where
     }
 }
 
+/// Columns, challenges and dynamic selectors stored at a fixed position in
+/// [StandardInstance] and [StandardWitness]. For enums without fields, it can
+/// be implemented with the macro [crate::standard_index].
+pub trait StandardIndex: Copy {
+    /// The position of the challenge in the instance, or of the column (or
+    /// selector) in the witness.
+    fn standard_index(self) -> usize;
+}
+
+/// Implements [StandardIndex] for enums without fields, using the declaration
+/// order of the variants.
+/// ```ignore
+/// folding::standard_index!(MyCol, MyChallenge);
+/// ```
+#[macro_export]
+macro_rules! standard_index {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl $crate::standard_config::StandardIndex for $t {
+                fn standard_index(self) -> usize {
+                    self as usize
+                }
+            }
+        )+
+    };
+}
+
+/// An instance of a relation over `N_COL` witness columns and `N_CHALS`
+/// challenges: the commitments to the columns, the challenges, the powers of
+/// the combiner `α` of the constraints, and the blinder of the commitments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StandardInstance<G: CommitmentCurve, const N_COL: usize, const N_CHALS: usize> {
+    pub commitments: [G; N_COL],
+    pub challenges: [G::ScalarField; N_CHALS],
+    pub alphas: Alphas<G::ScalarField>,
+    pub blinder: G::ScalarField,
+}
+
+impl<G: CommitmentCurve, const N_COL: usize, const N_CHALS: usize>
+    StandardInstance<G, N_COL, N_CHALS>
+{
+    /// Commits to the columns of the witness with the blinder `1`, absorbs the
+    /// commitments, and squeezes the challenges and then `α`.
+    pub fn from_witness<Srs, Sponge>(
+        witness: &StandardWitness<G::ScalarField, N_COL>,
+        srs: &Srs,
+        domain: Radix2EvaluationDomain<G::ScalarField>,
+        fq_sponge: &mut Sponge,
+    ) -> Self
+    where
+        Srs: SRS<G>,
+        Sponge: FqSponge<G::BaseField, G, G::ScalarField>,
+    {
+        Self::from_witness_with_blinder(witness, srs, domain, fq_sponge, G::ScalarField::one())
+    }
+
+    /// Same as [Self::from_witness], but the columns are committed with a
+    /// random blinder, to be used with
+    /// [crate::FoldingScheme::fold_instance_witness_pair_zk].
+    pub fn from_witness_zk<Srs, Sponge, RNG>(
+        witness: &StandardWitness<G::ScalarField, N_COL>,
+        srs: &Srs,
+        domain: Radix2EvaluationDomain<G::ScalarField>,
+        fq_sponge: &mut Sponge,
+        rng: &mut RNG,
+    ) -> Self
+    where
+        Srs: SRS<G>,
+        Sponge: FqSponge<G::BaseField, G, G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    {
+        let blinder = G::ScalarField::rand(rng);
+        Self::from_witness_with_blinder(witness, srs, domain, fq_sponge, blinder)
+    }
+
+    fn from_witness_with_blinder<Srs, Sponge>(
+        witness: &StandardWitness<G::ScalarField, N_COL>,
+        srs: &Srs,
+        domain: Radix2EvaluationDomain<G::ScalarField>,
+        fq_sponge: &mut Sponge,
+        blinder: G::ScalarField,
+    ) -> Self
+    where
+        Srs: SRS<G>,
+        Sponge: FqSponge<G::BaseField, G, G::ScalarField>,
+    {
+        let blinders = PolyComm::new(vec![blinder]);
+        let commitments = array::from_fn(|i| {
+            srs.commit_evaluations_custom(domain, &witness.columns[i], &blinders)
+                .unwrap()
+                .commitment
+                .get_first_chunk()
+        });
+        fq_sponge.absorb_g(&commitments);
+        let challenges = array::from_fn(|_| fq_sponge.challenge());
+        let alphas = Alphas::new(fq_sponge.challenge());
+        Self {
+            commitments,
+            challenges,
+            alphas,
+            blinder,
+        }
+    }
+}
+
+impl<G: CommitmentCurve, const N_COL: usize, const N_CHALS: usize> Foldable<G::ScalarField>
+    for StandardInstance<G, N_COL, N_CHALS>
+{
+    fn combine(a: Self, b: Self, challenge: G::ScalarField) -> Self {
+        Self {
+            commitments: array::from_fn(|i| {
+                (a.commitments[i] + b.commitments[i].mul(challenge)).into()
+            }),
+            challenges: array::from_fn(|i| a.challenges[i] + challenge * b.challenges[i]),
+            alphas: Alphas::combine(a.alphas, b.alphas, challenge),
+            blinder: a.blinder + challenge * b.blinder,
+        }
+    }
+}
+
+impl<G: CommitmentCurve, const N_COL: usize, const N_CHALS: usize> Instance<G>
+    for StandardInstance<G, N_COL, N_CHALS>
+{
+    fn to_absorb(&self) -> (Vec<G::ScalarField>, Vec<G>) {
+        let mut fields = self.challenges.to_vec();
+        fields.extend(self.alphas.clone().powers());
+        (fields, self.commitments.to_vec())
+    }
+
+    fn get_alphas(&self) -> &Alphas<G::ScalarField> {
+        &self.alphas
+    }
+
+    fn get_blinder(&self) -> G::ScalarField {
+        self.blinder
+    }
+
+    fn combine_endo(
+        a: Self,
+        b: Self,
+        challenge: ScalarChallenge<G::ScalarField>,
+        endo_q: G::BaseField,
+        endo_r: G::ScalarField,
+    ) -> Self
+    where
+        G: EndoCurve,
+    {
+        let commitments =
+            combine_commitments_endo(&a.commitments, &b.commitments, &challenge, endo_q, endo_r);
+        let challenge = challenge.to_field(&endo_r);
+        Self {
+            commitments: array::from_fn(|i| commitments[i]),
+            challenges: array::from_fn(|i| a.challenges[i] + challenge * b.challenges[i]),
+            alphas: Alphas::combine(a.alphas, b.alphas, challenge),
+            blinder: a.blinder + challenge * b.blinder,
+        }
+    }
+}
+
+impl<G: CommitmentCurve, Chall: StandardIndex, const N_COL: usize, const N_CHALS: usize>
+    Index<Chall> for StandardInstance<G, N_COL, N_CHALS>
+{
+    type Output = G::ScalarField;
+
+    fn index(&self, index: Chall) -> &Self::Output {
+        &self.challenges[index.standard_index()]
+    }
+}
+
+/// A witness of `N_COL` columns, given by their evaluations over the domain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StandardWitness<F: FftField, const N_COL: usize> {
+    pub columns: [Evaluations<F, Radix2EvaluationDomain<F>>; N_COL],
+}
+
+impl<F: FftField, const N_COL: usize> Foldable<F> for StandardWitness<F, N_COL> {
+    fn combine(mut a: Self, b: Self, challenge: F) -> Self {
+        for (a, b) in a.columns.iter_mut().zip(b.columns) {
+            for (a, b) in a.evals.iter_mut().zip(b.evals) {
+                *a += challenge * b;
+            }
+        }
+        a
+    }
+}
+
+impl<G: CommitmentCurve, const N_COL: usize> Witness<G> for StandardWitness<G::ScalarField, N_COL> {}
+
+impl<F: FftField, Col: StandardIndex, const N_COL: usize> Index<Col> for StandardWitness<F, N_COL> {
+    type Output = [F];
+
+    fn index(&self, index: Col) -> &Self::Output {
+        &self.columns[index.standard_index()].evals
+    }
+}
+
+// for the default selector of StandardConfig, when there is no dynamic selector
+impl<F: FftField, const N_COL: usize> Index<()> for StandardWitness<F, N_COL> {
+    type Output = [F];
+
+    fn index(&self, _index: ()) -> &Self::Output {
+        unreachable!("there is no dynamic selector")
+    }
+}
+
 /// contains a data structure useful to support the [CurrOrNext::Next] case
 /// in [FoldingEnv::col]
 mod memoization {
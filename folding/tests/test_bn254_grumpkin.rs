@@ -0,0 +1,201 @@
+/// This file folds a toy relation on both sides of the BN254/Grumpkin cycle,
+/// using the BN254 sponge parameters and constants of [mina_poseidon]. The
+/// Fq-sponge of each curve absorbs the commitments over the base field of the
+/// curve, which is the scalar field of the other curve of the cycle.
+///
+/// The folding scheme works over radix-2 evaluation domains. The scalar field
+/// of Grumpkin has a 2-adicity of 1, therefore a relation can only be folded
+/// over Grumpkin with an evaluation domain of size 2, while the BN254 side is
+/// checked on domains of different sizes.
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
+use folding::{
+    checker::{Checker, Provide},
+    expressions::{FoldingColumnTrait, FoldingCompatibleExprInner},
+    standard_config::{EmptyStructure, StandardConfig, StandardInstance, StandardWitness},
+    ExpExtension, FoldingCompatibleExpr, FoldingOutput, FoldingScheme, RelaxedInstance,
+    RelaxedWitness,
+};
+use kimchi::circuits::{expr::Variable, gate::CurrOrNext};
+use mina_curves::grumpkin::{Grumpkin, GrumpkinParameters};
+use mina_poseidon::{
+    bn254::{fq, fr, Bn254FqSponge},
+    constants::PlonkSpongeConstantsBn254,
+    sponge::DefaultFqSponge,
+    FqSponge,
+};
+use poly_commitment::{
+    commitment::{CommitmentCurve, EndoCurve},
+    ipa::SRS,
+    SRS as _,
+};
+use rand::thread_rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ToyColumn {
+    X,
+    Y,
+    Z,
+}
+
+impl FoldingColumnTrait for ToyColumn {
+    fn is_witness(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ToyChallenge {
+    Beta,
+}
+
+folding::standard_index!(ToyColumn, ToyChallenge);
+
+type ToyInstance<G> = StandardInstance<G, 3, 1>;
+type ToyWitness<G> = StandardWitness<<G as AffineRepr>::ScalarField, 3>;
+type ToyFoldingConfig<G> =
+    StandardConfig<G, ToyColumn, ToyChallenge, ToyInstance<G>, ToyWitness<G>, SRS<G>>;
+
+/// `x * x - y` and `β (x + y - z)`
+fn constraints<G: CommitmentCurve>() -> Vec<FoldingCompatibleExpr<ToyFoldingConfig<G>>>
+where
+    G::BaseField: PrimeField,
+{
+    let cell = |col| {
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Cell(Variable {
+            col,
+            row: CurrOrNext::Curr,
+        }))
+    };
+    let beta =
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Challenge(ToyChallenge::Beta));
+    let [x, y, z] = [ToyColumn::X, ToyColumn::Y, ToyColumn::Z].map(cell);
+    vec![x.clone() * x.clone() - y.clone(), beta * (x + y - z)]
+}
+
+fn random_witness<F: PrimeField>(domain: Radix2EvaluationDomain<F>) -> StandardWitness<F, 3> {
+    let mut rng = thread_rng();
+    let x: Vec<F> = (0..domain.size()).map(|_| F::rand(&mut rng)).collect();
+    let y: Vec<F> = x.iter().map(|x| x.square()).collect();
+    let z = x.iter().zip(y.iter()).map(|(x, y)| *x + y).collect();
+    StandardWitness {
+        columns: [x, y, z].map(|evals| Evaluations::from_vec_and_domain(evals, domain)),
+    }
+}
+
+/// Resolves the atoms of the relaxed expression for a folded pair
+struct FoldedProvider<G: CommitmentCurve>
+where
+    G::BaseField: PrimeField,
+{
+    instance: RelaxedInstance<G, ToyInstance<G>>,
+    witness: RelaxedWitness<G, ToyWitness<G>>,
+}
+
+impl<G: CommitmentCurve> Provide<ToyFoldingConfig<G>> for FoldedProvider<G>
+where
+    G::BaseField: PrimeField,
+{
+    fn resolve(
+        &self,
+        inner: FoldingCompatibleExprInner<ToyFoldingConfig<G>>,
+        domain: Radix2EvaluationDomain<G::ScalarField>,
+    ) -> Vec<G::ScalarField> {
+        let domain_size = domain.size as usize;
+        let instance = &self.instance.extended_instance.instance;
+        match inner {
+            FoldingCompatibleExprInner::Constant(c) => vec![c; domain_size],
+            FoldingCompatibleExprInner::Challenge(chal) => vec![instance[chal]; domain_size],
+            FoldingCompatibleExprInner::Cell(Variable { col, row }) => {
+                let mut col = self.witness.extended_witness.witness[col].to_vec();
+                if let CurrOrNext::Next = row {
+                    col.rotate_left(1);
+                }
+                col
+            }
+            FoldingCompatibleExprInner::Extensions(ext) => match ext {
+                ExpExtension::U => vec![self.instance.u; domain_size],
+                ExpExtension::Error => self.witness.error_vec.evals.clone(),
+                ExpExtension::ExtendedWitness(i) => {
+                    self.witness.extended_witness.extended[&i].evals.clone()
+                }
+                ExpExtension::Alpha(i) => vec![instance.alphas.get(i).unwrap(); domain_size],
+                ExpExtension::Selector(_) => panic!("no dynamic selector"),
+            },
+        }
+    }
+}
+
+impl<G: CommitmentCurve> Checker<ToyFoldingConfig<G>> for FoldedProvider<G> where
+    G::BaseField: PrimeField
+{
+}
+
+/// Folds two instances of the toy relation over the curve `G`, using the
+/// Fq-sponges built by `new_sponge`, and checks the folded pair.
+fn fold_toy_relation<G, Sponge>(domain_size: usize, new_sponge: impl Fn() -> Sponge)
+where
+    G: EndoCurve,
+    G::BaseField: PrimeField,
+    Sponge: FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    let domain = Radix2EvaluationDomain::<G::ScalarField>::new(domain_size).unwrap();
+    let srs = SRS::<G>::create(domain.size());
+    srs.get_lagrange_basis(domain);
+    let structure = EmptyStructure::default();
+    let (scheme, final_constraint) =
+        FoldingScheme::<ToyFoldingConfig<G>>::new(constraints(), &srs, domain, &structure);
+
+    let [left, right] = [(); 2].map(|_| {
+        let witness = random_witness(domain);
+        let instance = ToyInstance::from_witness(&witness, &srs, domain, &mut new_sponge());
+        (instance, witness)
+    });
+
+    let FoldingOutput {
+        folded_instance,
+        folded_witness,
+        t_0,
+        t_1,
+        t_blinders: _,
+        relaxed_extended_left_instance,
+        relaxed_extended_right_instance,
+        to_absorb: _,
+    } = scheme
+        .fold_instance_witness_pair(left, right, &mut new_sponge())
+        .unwrap();
+
+    let folded_instance_explicit = scheme
+        .fold_instance_pair(
+            relaxed_extended_left_instance,
+            relaxed_extended_right_instance,
+            [t_0, t_1],
+            &mut new_sponge(),
+        )
+        .unwrap();
+    assert!(folded_instance == folded_instance_explicit);
+
+    let checker = FoldedProvider {
+        instance: folded_instance,
+        witness: folded_witness,
+    };
+    checker.check(&final_constraint, domain);
+}
+
+#[test]
+fn test_fold_over_bn254() {
+    for domain_size in [2, 8, 32] {
+        fold_toy_relation::<ark_bn254::G1Affine, _>(domain_size, || {
+            Bn254FqSponge::new(fq::static_params())
+        });
+    }
+}
+
+#[test]
+fn test_fold_over_grumpkin() {
+    assert!(Radix2EvaluationDomain::<ark_bn254::Fq>::new(4).is_none());
+    fold_toy_relation::<Grumpkin, _>(2, || {
+        DefaultFqSponge::<GrumpkinParameters, PlonkSpongeConstantsBn254>::new(fr::static_params())
+    });
+}
@@ -384,17 +384,20 @@ fn test_decomposable_folding() {
         let right = (instance2, witness2);
         // here we provide normal instance-witness pairs, which will be
         // automatically relaxed
-        let folded = scheme.fold_instance_witness_pair(
-            left,
-            right,
-            Some(DynamicSelector::SelecAdd),
-            &mut fq_sponge,
-        );
+        let folded = scheme
+            .fold_instance_witness_pair(
+                left,
+                right,
+                Some(DynamicSelector::SelecAdd),
+                &mut fq_sponge,
+            )
+            .unwrap();
         let FoldingOutput {
             folded_instance,
             folded_witness,
             t_0: _,
             t_1: _,
+            t_blinders: _,
             relaxed_extended_left_instance: _,
             relaxed_extended_right_instance: _,
             to_absorb: _,
@@ -420,17 +423,20 @@ fn test_decomposable_folding() {
 
         let left = (instance1, witness1);
         let right = (instance2, witness2);
-        let folded = scheme.fold_instance_witness_pair(
-            left,
-            right,
-            Some(DynamicSelector::SelecSub),
-            &mut fq_sponge,
-        );
+        let folded = scheme
+            .fold_instance_witness_pair(
+                left,
+                right,
+                Some(DynamicSelector::SelecSub),
+                &mut fq_sponge,
+            )
+            .unwrap();
         let FoldingOutput {
             folded_instance,
             folded_witness,
             t_0: _,
             t_1: _,
+            t_blinders: _,
             relaxed_extended_left_instance: _,
             relaxed_extended_right_instance: _,
             to_absorb: _,
@@ -449,12 +455,15 @@ fn test_decomposable_folding() {
     debug!("fold mixed");
     {
         // here we use already relaxed pairs, which have a trival x -> x implementation
-        let folded = scheme.fold_instance_witness_pair(left, right, None, &mut fq_sponge);
+        let folded = scheme
+            .fold_instance_witness_pair(left, right, None, &mut fq_sponge)
+            .unwrap();
         let FoldingOutput {
             folded_instance,
             folded_witness,
             t_0,
             t_1,
+            t_blinders: _,
             relaxed_extended_left_instance: _,
             relaxed_extended_right_instance: _,
             to_absorb: _,
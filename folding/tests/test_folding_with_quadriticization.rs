@@ -385,12 +385,14 @@ fn test_quadriticization() {
         let right = (instance2, witness2);
         // here we provide normal instance-witness pairs, which will be
         // automatically relaxed
-        let folded = scheme.fold_instance_witness_pair(
-            left,
-            right,
-            Some(DynamicSelector::SelecAdd),
-            &mut fq_sponge,
-        );
+        let folded = scheme
+            .fold_instance_witness_pair(
+                left,
+                right,
+                Some(DynamicSelector::SelecAdd),
+                &mut fq_sponge,
+            )
+            .unwrap();
         let FoldingOutput {
             folded_instance,
             folded_witness,
@@ -400,12 +402,14 @@ fn test_quadriticization() {
         {
             let folded_instance_explicit = {
                 let mut fq_sponge_inst = BaseSponge::new(Curve::other_curve_sponge_params());
-                scheme.fold_instance_pair(
-                    folded.relaxed_extended_left_instance,
-                    folded.relaxed_extended_right_instance,
-                    [folded.t_0.clone(), folded.t_1.clone()],
-                    &mut fq_sponge_inst,
-                )
+                scheme
+                    .fold_instance_pair(
+                        folded.relaxed_extended_left_instance,
+                        folded.relaxed_extended_right_instance,
+                        [folded.t_0.clone(), folded.t_1.clone()],
+                        &mut fq_sponge_inst,
+                    )
+                    .unwrap()
             };
 
             assert!(folded_instance == folded_instance_explicit);
@@ -434,12 +438,14 @@ fn test_quadriticization() {
 
         let left = (instance1, witness1);
         let right = (instance2, witness2);
-        let folded = scheme.fold_instance_witness_pair(
-            left,
-            right,
-            Some(DynamicSelector::SelecMul),
-            &mut fq_sponge,
-        );
+        let folded = scheme
+            .fold_instance_witness_pair(
+                left,
+                right,
+                Some(DynamicSelector::SelecMul),
+                &mut fq_sponge,
+            )
+            .unwrap();
         let FoldingOutput {
             folded_instance,
             folded_witness,
@@ -448,12 +454,14 @@ fn test_quadriticization() {
 
         {
             let folded_instance_explicit = {
-                scheme.fold_instance_pair(
-                    folded.relaxed_extended_left_instance,
-                    folded.relaxed_extended_right_instance,
-                    [folded.t_0.clone(), folded.t_1.clone()],
-                    &mut fq_sponge_before_fold,
-                )
+                scheme
+                    .fold_instance_pair(
+                        folded.relaxed_extended_left_instance,
+                        folded.relaxed_extended_right_instance,
+                        [folded.t_0.clone(), folded.t_1.clone()],
+                        &mut fq_sponge_before_fold,
+                    )
+                    .unwrap()
             };
 
             assert!(folded_instance == folded_instance_explicit);
@@ -474,12 +482,15 @@ fn test_quadriticization() {
         let mut fq_sponge_before_fold = fq_sponge.clone();
 
         // here we use already relaxed pairs, which have a trival x -> x implementation
-        let folded = scheme.fold_instance_witness_pair(left, right, None, &mut fq_sponge);
+        let folded = scheme
+            .fold_instance_witness_pair(left, right, None, &mut fq_sponge)
+            .unwrap();
         let FoldingOutput {
             folded_instance,
             folded_witness,
             t_0,
             t_1,
+            t_blinders: _,
             relaxed_extended_left_instance,
             relaxed_extended_right_instance,
             to_absorb: _,
@@ -487,12 +498,14 @@ fn test_quadriticization() {
 
         {
             let folded_instance_explicit = {
-                scheme.fold_instance_pair(
-                    relaxed_extended_left_instance,
-                    relaxed_extended_right_instance,
-                    [t_0.clone(), t_1.clone()],
-                    &mut fq_sponge_before_fold,
-                )
+                scheme
+                    .fold_instance_pair(
+                        relaxed_extended_left_instance,
+                        relaxed_extended_right_instance,
+                        [t_0.clone(), t_1.clone()],
+                        &mut fq_sponge_before_fold,
+                    )
+                    .unwrap()
             };
 
             assert!(folded_instance == folded_instance_explicit);
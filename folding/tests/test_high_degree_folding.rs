@@ -0,0 +1,445 @@
+/// This file shows how to fold expressions of degree higher than 2 without
+/// quadraticization, using the same configuration of 3 columns and two
+/// selectors as `test_vanilla_folding.rs`. See [test_high_degree_folding] at
+/// the end for a test.
+use ark_ff::{Field, One, UniformRand};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
+use folding::{
+    checker::Column,
+    expressions::FoldingCompatibleExprInner,
+    high_degree::{HighDegreeFoldingOutput, HighDegreeFoldingScheme, SequentialFoldingOutput},
+    instance_witness::{Foldable, RelaxablePair},
+    Alphas, FoldingCompatibleExpr, FoldingConfig, FoldingEnv, FoldingError, FoldingScheme,
+    Instance, Side, Witness,
+};
+use itertools::Itertools;
+use kimchi::{
+    circuits::{expr::Variable, gate::CurrOrNext},
+    curve::KimchiCurve,
+};
+use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge, FqSponge};
+use poly_commitment::{ipa::SRS, SRS as _};
+use rand::{thread_rng, Rng};
+
+type Fp = ark_bn254::Fr;
+type Curve = ark_bn254::G1Affine;
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>;
+
+/// The instance is the commitments to the polynomials and the challenges
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct TestInstance {
+    commitments: [Curve; 3],
+    challenges: [Fp; 3],
+    alphas: Alphas<Fp>,
+    blinder: Fp,
+}
+
+impl Foldable<Fp> for TestInstance {
+    fn combine(a: Self, b: Self, challenge: Fp) -> Self {
+        TestInstance {
+            commitments: std::array::from_fn(|i| {
+                (a.commitments[i] + b.commitments[i] * challenge).into()
+            }),
+            challenges: std::array::from_fn(|i| a.challenges[i] + challenge * b.challenges[i]),
+            alphas: Alphas::combine(a.alphas, b.alphas, challenge),
+            blinder: a.blinder + challenge * b.blinder,
+        }
+    }
+}
+
+impl Instance<Curve> for TestInstance {
+    fn to_absorb(&self) -> (Vec<Fp>, Vec<Curve>) {
+        let mut fields = Vec::with_capacity(3 + 2);
+        fields.extend(self.challenges);
+        fields.extend(self.alphas.clone().powers());
+        let points = self.commitments.to_vec();
+        (fields, points)
+    }
+
+    fn get_alphas(&self) -> &Alphas<Fp> {
+        &self.alphas
+    }
+
+    fn get_blinder(&self) -> Fp {
+        self.blinder
+    }
+}
+
+/// The evaluations of each column over the domain
+#[derive(Clone)]
+struct TestWitness([Evaluations<Fp, Radix2EvaluationDomain<Fp>>; 3]);
+
+impl Foldable<Fp> for TestWitness {
+    fn combine(mut a: Self, b: Self, challenge: Fp) -> Self {
+        for (a, b) in a.0.iter_mut().zip(b.0) {
+            for (a, b) in a.evals.iter_mut().zip(b.evals) {
+                *a += challenge * b;
+            }
+        }
+        a
+    }
+}
+
+impl Witness<Curve> for TestWitness {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TestStructure {
+    s_add: Vec<Fp>,
+    s_mul: Vec<Fp>,
+}
+
+struct TestFoldingEnv {
+    structure: TestStructure,
+    instances: [TestInstance; 2],
+    curr_witnesses: [TestWitness; 2],
+    next_witnesses: [TestWitness; 2],
+}
+
+impl FoldingEnv<Fp, TestInstance, TestWitness, Column, TestChallenge, ()> for TestFoldingEnv {
+    type Structure = TestStructure;
+
+    fn new(
+        structure: &Self::Structure,
+        instances: [&TestInstance; 2],
+        witnesses: [&TestWitness; 2],
+    ) -> Self {
+        let curr_witnesses = [witnesses[0].clone(), witnesses[1].clone()];
+        let mut next_witnesses = curr_witnesses.clone();
+        for side in next_witnesses.iter_mut() {
+            for col in side.0.iter_mut() {
+                col.evals.rotate_left(1);
+            }
+        }
+        TestFoldingEnv {
+            structure: structure.clone(),
+            instances: [instances[0].clone(), instances[1].clone()],
+            curr_witnesses,
+            next_witnesses,
+        }
+    }
+
+    fn col(&self, col: Column, curr_or_next: CurrOrNext, side: Side) -> &[Fp] {
+        let wit = match curr_or_next {
+            CurrOrNext::Curr => &self.curr_witnesses[side as usize],
+            CurrOrNext::Next => &self.next_witnesses[side as usize],
+        };
+        match col {
+            Column::X(i @ 0..=2) => &wit.0[i].evals,
+            Column::Selector(0) => &self.structure.s_add,
+            Column::Selector(1) => &self.structure.s_mul,
+            // Only 3 columns and 2 selectors
+            Column::X(_) | Column::Selector(_) => unreachable!(),
+        }
+    }
+
+    fn challenge(&self, challenge: TestChallenge, side: Side) -> Fp {
+        match challenge {
+            TestChallenge::Beta => self.instances[side as usize].challenges[0],
+        }
+    }
+
+    fn selector(&self, _s: &(), _side: Side) -> &[Fp] {
+        unreachable!()
+    }
+}
+
+/// An addition gate scaled by a challenge, and a multiplication gate
+/// `a^{degree - 1} b = c`
+fn constraints(degree: u64) -> Vec<FoldingCompatibleExpr<TestFoldingConfig>> {
+    let get_col = |col| {
+        Box::new(FoldingCompatibleExpr::Atom(
+            FoldingCompatibleExprInner::Cell(Variable {
+                col,
+                row: CurrOrNext::Curr,
+            }),
+        ))
+    };
+    let (a, b, c) = (
+        get_col(Column::X(0)),
+        get_col(Column::X(1)),
+        get_col(Column::X(2)),
+    );
+
+    let add = FoldingCompatibleExpr::Add(a.clone(), b.clone());
+    let add = FoldingCompatibleExpr::Sub(add.into(), c.clone());
+    let add = FoldingCompatibleExpr::Mul(add.into(), get_col(Column::Selector(0)));
+    let beta =
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Challenge(TestChallenge::Beta));
+    let add = FoldingCompatibleExpr::Mul(add.into(), beta.into());
+
+    let pow = (2..degree).fold(a.clone(), |acc, _| {
+        Box::new(FoldingCompatibleExpr::Mul(acc, a.clone()))
+    });
+    let mul = FoldingCompatibleExpr::Mul(pow, b);
+    let mul = FoldingCompatibleExpr::Sub(mul.into(), c);
+    let mul = FoldingCompatibleExpr::Mul(mul.into(), get_col(Column::Selector(1)));
+
+    vec![add, mul]
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TestFoldingConfig;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum TestChallenge {
+    Beta,
+}
+
+impl FoldingConfig for TestFoldingConfig {
+    type Structure = TestStructure;
+    type Column = Column;
+    type Selector = ();
+    type Challenge = TestChallenge;
+    type Curve = Curve;
+    type Srs = SRS<Curve>;
+    type Instance = TestInstance;
+    type Witness = TestWitness;
+    type Env = TestFoldingEnv;
+}
+
+/// Additions on the even rows, multiplications on the odd rows
+fn circuit(domain: Radix2EvaluationDomain<Fp>) -> TestStructure {
+    let size = domain.size();
+    TestStructure {
+        s_add: (0..size).map(|i| Fp::from((i % 2 == 0) as u64)).collect(),
+        s_mul: (0..size).map(|i| Fp::from((i % 2 == 1) as u64)).collect(),
+    }
+}
+
+fn random_pair(
+    srs: &SRS<Curve>,
+    domain: Radix2EvaluationDomain<Fp>,
+    degree: u64,
+    rng: &mut impl Rng,
+) -> (TestInstance, TestWitness) {
+    let (mut a, mut b, mut c) = (vec![], vec![], vec![]);
+    for i in 0..domain.size() {
+        let (x, y) = (Fp::rand(rng), Fp::rand(rng));
+        a.push(x);
+        b.push(y);
+        c.push(if i % 2 == 0 {
+            x + y
+        } else {
+            x.pow([degree - 1]) * y
+        });
+    }
+    let witness =
+        TestWitness([a, b, c].map(|evals| Evaluations::from_vec_and_domain(evals, domain)));
+    let commitments = witness
+        .0
+        .iter()
+        .map(|w| {
+            srs.commit_evaluations_non_hiding(domain, w)
+                .get_first_chunk()
+        })
+        .collect_vec();
+    let instance = TestInstance {
+        commitments: commitments.try_into().unwrap(),
+        challenges: [(); 3].map(|_| Fp::rand(rng)),
+        alphas: Alphas::new(Fp::rand(rng)),
+        blinder: Fp::one(),
+    };
+    (instance, witness)
+}
+
+#[test]
+fn test_high_degree_folding() {
+    let mut rng = thread_rng();
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(8);
+    srs.get_lagrange_basis(domain);
+    let structure = circuit(domain);
+
+    // A gate of degree 5, i.e. 5 cross terms with the alphas
+    let scheme =
+        HighDegreeFoldingScheme::<TestFoldingConfig>::new(constraints(5), &srs, domain, &structure);
+    assert_eq!(scheme.get_degree(), 6);
+    assert_eq!(scheme.get_number_of_cross_terms(), 5);
+
+    let left = random_pair(&srs, domain, 5, &mut rng);
+    let right = random_pair(&srs, domain, 5, &mut rng);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let HighDegreeFoldingOutput {
+        folded_instance,
+        folded_witness,
+        cross_terms,
+        relaxed_left_instance,
+        relaxed_right_instance,
+        to_absorb,
+    } = scheme
+        .fold_instance_witness_pair(left, right, &mut fq_sponge)
+        .unwrap();
+    assert_eq!(cross_terms.len(), 5);
+    // 3 + 2 from each instance + 1 from u, times 2 instances
+    assert_eq!(to_absorb.0.len(), (3 + 2 + 1) * 2);
+    // 3 from each instance + 1 from E, times 2 instances + the cross terms
+    assert_eq!(to_absorb.1.len(), (3 + 1) * 2 + 5);
+    assert!(scheme.check(&folded_instance, &folded_witness));
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let verified = scheme
+        .verify_fold(
+            relaxed_left_instance,
+            relaxed_right_instance,
+            &cross_terms,
+            &mut fq_sponge,
+        )
+        .unwrap();
+    assert!(verified == folded_instance);
+
+    // Folding a fresh instance in the folded one
+    let fresh = random_pair(&srs, domain, 5, &mut rng);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let (folded_instance, folded_witness) = scheme
+        .fold_instance_witness_pair((folded_instance, folded_witness), fresh, &mut fq_sponge)
+        .unwrap()
+        .pair();
+    assert!(scheme.check(&folded_instance, &folded_witness));
+
+    // A different witness does not satisfy the relaxed relation
+    let mut tampered = folded_witness;
+    tampered.extended_witness.witness.0[2].evals[1] += Fp::one();
+    assert!(!scheme.check(&folded_instance, &tampered));
+}
+
+// For expressions of degree 2, the cross terms are the ones of the folding
+// scheme using quadraticization
+#[test]
+fn test_high_degree_folding_of_degree_two() {
+    let mut rng = thread_rng();
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(8);
+    srs.get_lagrange_basis(domain);
+    let structure = circuit(domain);
+
+    let scheme =
+        HighDegreeFoldingScheme::<TestFoldingConfig>::new(constraints(2), &srs, domain, &structure);
+    let (quadratic_scheme, _) =
+        FoldingScheme::<TestFoldingConfig>::new(constraints(2), &srs, domain, &structure);
+    assert_eq!(quadratic_scheme.get_number_of_additional_columns(), 0);
+    assert_eq!(scheme.get_number_of_cross_terms(), 2);
+
+    let left = random_pair(&srs, domain, 2, &mut rng);
+    let right = random_pair(&srs, domain, 2, &mut rng);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let output = scheme
+        .fold_instance_witness_pair(left.clone(), right.clone(), &mut fq_sponge)
+        .unwrap();
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let quadratic_output = quadratic_scheme
+        .fold_instance_witness_pair(left, right, &mut fq_sponge)
+        .unwrap();
+
+    assert_eq!(
+        output.cross_terms,
+        [quadratic_output.t_0, quadratic_output.t_1]
+    );
+    assert!(output.folded_instance == quadratic_output.folded_instance);
+    assert_eq!(
+        output.folded_witness.error_vec,
+        quadratic_output.folded_witness.error_vec
+    );
+    assert!(scheme.check(&output.folded_instance, &output.folded_witness));
+}
+
+// Folding many instances into an accumulator one after the other
+#[test]
+fn test_high_degree_folding_sequentially() {
+    let mut rng = thread_rng();
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(8);
+    srs.get_lagrange_basis(domain);
+    let structure = circuit(domain);
+
+    let scheme =
+        HighDegreeFoldingScheme::<TestFoldingConfig>::new(constraints(3), &srs, domain, &structure);
+    assert_eq!(scheme.get_degree(), 4);
+    assert_eq!(scheme.get_number_of_cross_terms_sequential(1), 3);
+    assert_eq!(scheme.get_number_of_cross_terms_sequential(3), 9);
+
+    // The accumulator and one of the incoming pairs are already relaxed, with
+    // a non-zero error term
+    let mut relaxed_pair = || {
+        let left = random_pair(&srs, domain, 3, &mut rng);
+        let right = random_pair(&srs, domain, 3, &mut rng);
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        scheme
+            .fold_instance_witness_pair(left, right, &mut fq_sponge)
+            .unwrap()
+            .pair()
+    };
+    let accumulator = relaxed_pair();
+    let relaxed = relaxed_pair();
+    let incoming = vec![
+        random_pair(&srs, domain, 3, &mut rng).relax(&scheme.zero_vec),
+        relaxed,
+        random_pair(&srs, domain, 3, &mut rng).relax(&scheme.zero_vec),
+    ];
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let SequentialFoldingOutput {
+        folded_instance,
+        folded_witness,
+        cross_terms,
+        relaxed_instances,
+        to_absorb,
+    } = scheme
+        .fold_sequentially(accumulator, incoming, &mut fq_sponge)
+        .unwrap();
+    assert_eq!(cross_terms.len(), 9);
+    // For each of the 3 steps, 3 from each instance + 1 from E, times 2
+    // instances + the cross terms
+    assert_eq!(to_absorb.1.len(), 3 * ((3 + 1) * 2 + 3));
+    assert!(scheme.check(&folded_instance, &folded_witness));
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let verified = scheme
+        .verify_fold_sequentially(relaxed_instances.clone(), &cross_terms, &mut fq_sponge)
+        .unwrap();
+    assert!(verified == folded_instance);
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let result =
+        scheme.verify_fold_sequentially(relaxed_instances, &cross_terms[1..], &mut fq_sponge);
+    assert!(matches!(result, Err(FoldingError::CrossTermsCount(8, 9))));
+
+    // At least one instance must be folded into the accumulator
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let result = scheme.fold_sequentially(
+        (folded_instance, folded_witness),
+        Vec::<(TestInstance, TestWitness)>::new(),
+        &mut fq_sponge,
+    );
+    assert!(matches!(result, Err(FoldingError::NoInstance)));
+}
+
+// An unsatisfied instance folded between other instances gives an
+// accumulator which does not satisfy the relaxed relation
+#[test]
+fn test_high_degree_folding_sequentially_unsatisfied() {
+    let mut rng = thread_rng();
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(8);
+    srs.get_lagrange_basis(domain);
+    let structure = circuit(domain);
+
+    let scheme =
+        HighDegreeFoldingScheme::<TestFoldingConfig>::new(constraints(3), &srs, domain, &structure);
+
+    let accumulator = random_pair(&srs, domain, 3, &mut rng);
+    let (instance, mut witness) = random_pair(&srs, domain, 3, &mut rng);
+    witness.0[2].evals[1] += Fp::one();
+    let incoming = vec![
+        random_pair(&srs, domain, 3, &mut rng),
+        (instance, witness),
+        random_pair(&srs, domain, 3, &mut rng),
+    ];
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let (folded_instance, folded_witness) = scheme
+        .fold_sequentially(accumulator, incoming, &mut fq_sponge)
+        .unwrap()
+        .pair();
+    assert!(!scheme.check(&folded_instance, &folded_witness));
+}
@@ -0,0 +1,321 @@
+/// This file shows how to fold a relation containing a lookup argument, using
+/// the constraints given by [folding::logup] and the folding scheme for
+/// expressions of any degree. See [test_logup_folding] at the end for a test.
+use ark_ff::{One, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
+use folding::{
+    checker::Column,
+    expressions::FoldingCompatibleExprInner,
+    high_degree::HighDegreeFoldingScheme,
+    instance_witness::{Foldable, RelaxablePair},
+    logup::{accumulator, inner_sum, multiplicities, Logup, LogupArgument, LogupChunk},
+    Alphas, FoldingCompatibleExpr, FoldingConfig, FoldingEnv, Instance, Side, Witness,
+};
+use itertools::Itertools;
+use kimchi::{
+    circuits::{expr::Variable, gate::CurrOrNext},
+    curve::KimchiCurve,
+};
+use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge, FqSponge};
+use poly_commitment::{ipa::SRS, SRS as _};
+use rand::{thread_rng, Rng};
+
+type Fp = ark_bn254::Fr;
+type Curve = ark_bn254::G1Affine;
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>;
+
+/// The looked-up values
+const VALUE: Column = Column::X(0);
+/// The multiplicities of the entries of the table
+const MULTIPLICITY: Column = Column::X(1);
+/// The inner sum of the lookups of each row
+const INNER_SUM: Column = Column::X(2);
+/// The accumulator of the inner sums
+const ACCUMULATOR: Column = Column::X(3);
+/// The fixed table
+const TABLE: Column = Column::Selector(0);
+/// The identifier of the table, as the first coordinate of the vector lookups
+const TABLE_ID: u64 = 1;
+
+/// The instance is the commitments to the polynomials and the challenges
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct TestInstance {
+    commitments: [Curve; 4],
+    challenges: [Fp; 2],
+    alphas: Alphas<Fp>,
+    blinder: Fp,
+}
+
+impl Foldable<Fp> for TestInstance {
+    fn combine(a: Self, b: Self, challenge: Fp) -> Self {
+        TestInstance {
+            commitments: std::array::from_fn(|i| {
+                (a.commitments[i] + b.commitments[i] * challenge).into()
+            }),
+            challenges: std::array::from_fn(|i| a.challenges[i] + challenge * b.challenges[i]),
+            alphas: Alphas::combine(a.alphas, b.alphas, challenge),
+            blinder: a.blinder + challenge * b.blinder,
+        }
+    }
+}
+
+impl Instance<Curve> for TestInstance {
+    fn to_absorb(&self) -> (Vec<Fp>, Vec<Curve>) {
+        let mut fields = Vec::with_capacity(2 + 2);
+        fields.extend(self.challenges);
+        fields.extend(self.alphas.clone().powers());
+        let points = self.commitments.to_vec();
+        (fields, points)
+    }
+
+    fn get_alphas(&self) -> &Alphas<Fp> {
+        &self.alphas
+    }
+
+    fn get_blinder(&self) -> Fp {
+        self.blinder
+    }
+}
+
+/// The evaluations of each column over the domain
+#[derive(Clone)]
+struct TestWitness([Evaluations<Fp, Radix2EvaluationDomain<Fp>>; 4]);
+
+impl Foldable<Fp> for TestWitness {
+    fn combine(mut a: Self, b: Self, challenge: Fp) -> Self {
+        for (a, b) in a.0.iter_mut().zip(b.0) {
+            for (a, b) in a.evals.iter_mut().zip(b.evals) {
+                *a += challenge * b;
+            }
+        }
+        a
+    }
+}
+
+impl Witness<Curve> for TestWitness {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TestStructure {
+    table: Vec<Fp>,
+}
+
+struct TestFoldingEnv {
+    structure: TestStructure,
+    instances: [TestInstance; 2],
+    curr_witnesses: [TestWitness; 2],
+    next_witnesses: [TestWitness; 2],
+}
+
+impl FoldingEnv<Fp, TestInstance, TestWitness, Column, TestChallenge, ()> for TestFoldingEnv {
+    type Structure = TestStructure;
+
+    fn new(
+        structure: &Self::Structure,
+        instances: [&TestInstance; 2],
+        witnesses: [&TestWitness; 2],
+    ) -> Self {
+        let curr_witnesses = [witnesses[0].clone(), witnesses[1].clone()];
+        let mut next_witnesses = curr_witnesses.clone();
+        for side in next_witnesses.iter_mut() {
+            for col in side.0.iter_mut() {
+                col.evals.rotate_left(1);
+            }
+        }
+        TestFoldingEnv {
+            structure: structure.clone(),
+            instances: [instances[0].clone(), instances[1].clone()],
+            curr_witnesses,
+            next_witnesses,
+        }
+    }
+
+    fn col(&self, col: Column, curr_or_next: CurrOrNext, side: Side) -> &[Fp] {
+        let wit = match curr_or_next {
+            CurrOrNext::Curr => &self.curr_witnesses[side as usize],
+            CurrOrNext::Next => &self.next_witnesses[side as usize],
+        };
+        match col {
+            Column::X(i @ 0..=3) => &wit.0[i].evals,
+            TABLE => &self.structure.table,
+            // Only 4 columns and the table
+            Column::X(_) | Column::Selector(_) => unreachable!(),
+        }
+    }
+
+    fn challenge(&self, challenge: TestChallenge, side: Side) -> Fp {
+        match challenge {
+            TestChallenge::Beta => self.instances[side as usize].challenges[0],
+            TestChallenge::JointCombiner => self.instances[side as usize].challenges[1],
+        }
+    }
+
+    fn selector(&self, _s: &(), _side: Side) -> &[Fp] {
+        unreachable!()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TestFoldingConfig;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum TestChallenge {
+    Beta,
+    JointCombiner,
+}
+
+impl FoldingConfig for TestFoldingConfig {
+    type Structure = TestStructure;
+    type Column = Column;
+    type Selector = ();
+    type Challenge = TestChallenge;
+    type Curve = Curve;
+    type Srs = SRS<Curve>;
+    type Instance = TestInstance;
+    type Witness = TestWitness;
+    type Env = TestFoldingEnv;
+}
+
+/// Each row looks up its value in the table, and adds the multiplicity of its
+/// entry of the table
+fn argument() -> LogupArgument<TestFoldingConfig> {
+    let cell = |col| {
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Cell(Variable {
+            col,
+            row: CurrOrNext::Curr,
+        }))
+    };
+    let constant = |c| FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Constant(c));
+    let table_id = constant(Fp::from(TABLE_ID));
+    LogupArgument {
+        beta: TestChallenge::Beta,
+        joint_combiner: TestChallenge::JointCombiner,
+        chunks: vec![LogupChunk {
+            inner_sum: INNER_SUM,
+            lookups: vec![
+                Logup::new(constant(Fp::one()), vec![table_id.clone(), cell(VALUE)]),
+                Logup::new(
+                    constant(-Fp::one()) * cell(MULTIPLICITY),
+                    vec![table_id, cell(TABLE)],
+                ),
+            ],
+        }],
+        accumulator: ACCUMULATOR,
+    }
+}
+
+/// Builds a pair looking up `values`, with the multiplicities `counts`
+fn pair(
+    srs: &SRS<Curve>,
+    domain: Radix2EvaluationDomain<Fp>,
+    structure: &TestStructure,
+    values: Vec<Fp>,
+    counts: Vec<Fp>,
+    rng: &mut impl Rng,
+) -> (TestInstance, TestWitness) {
+    let (beta, joint_combiner) = (Fp::rand(rng), Fp::rand(rng));
+    let table_id = Fp::from(TABLE_ID);
+    let rows = (0..domain.size())
+        .map(|i| {
+            vec![
+                Logup::new(Fp::one(), vec![table_id, values[i]]),
+                Logup::new(-counts[i], vec![table_id, structure.table[i]]),
+            ]
+        })
+        .collect_vec();
+    let inner_sum = inner_sum(beta, joint_combiner, &rows);
+    let accumulator = accumulator(&[inner_sum.clone()]);
+    let witness = TestWitness(
+        [values, counts, inner_sum, accumulator]
+            .map(|evals| Evaluations::from_vec_and_domain(evals, domain)),
+    );
+    let commitments = witness
+        .0
+        .iter()
+        .map(|w| {
+            srs.commit_evaluations_non_hiding(domain, w)
+                .get_first_chunk()
+        })
+        .collect_vec();
+    let instance = TestInstance {
+        commitments: commitments.try_into().unwrap(),
+        challenges: [beta, joint_combiner],
+        alphas: Alphas::new(Fp::rand(rng)),
+        blinder: Fp::one(),
+    };
+    (instance, witness)
+}
+
+/// A pair looking up random values of the table
+fn random_pair(
+    srs: &SRS<Curve>,
+    domain: Radix2EvaluationDomain<Fp>,
+    structure: &TestStructure,
+    rng: &mut impl Rng,
+) -> (TestInstance, TestWitness) {
+    let values = (0..domain.size())
+        .map(|_| structure.table[rng.gen_range(0..domain.size())])
+        .collect_vec();
+    let counts = multiplicities(
+        &structure.table.iter().map(|t| vec![*t]).collect_vec(),
+        &values.iter().map(|v| vec![*v]).collect_vec(),
+    )
+    .unwrap();
+    pair(srs, domain, structure, values, counts, rng)
+}
+
+#[test]
+fn test_logup_folding() {
+    let mut rng = thread_rng();
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(8);
+    srs.get_lagrange_basis(domain);
+    let structure = TestStructure {
+        table: (0..domain.size() as u64).map(|i| Fp::from(3 * i)).collect(),
+    };
+
+    let constraints = argument().constraints();
+    // The inner sum, and the accumulator
+    assert_eq!(constraints.len(), 2);
+    let scheme =
+        HighDegreeFoldingScheme::<TestFoldingConfig>::new(constraints, &srs, domain, &structure);
+    // h (β + 1 + γ f) (β + 1 + γ t), the table being fixed, and the alphas
+    assert_eq!(scheme.get_degree(), 5);
+
+    let left = random_pair(&srs, domain, &structure, &mut rng);
+    assert!(left.1 .0[2].evals.iter().sum::<Fp>().is_zero());
+    let right = random_pair(&srs, domain, &structure, &mut rng);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let output = scheme
+        .fold_instance_witness_pair(left, right, &mut fq_sponge)
+        .unwrap();
+    assert!(scheme.check(&output.folded_instance, &output.folded_witness));
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let verified = scheme
+        .verify_fold(
+            output.relaxed_left_instance.clone(),
+            output.relaxed_right_instance.clone(),
+            &output.cross_terms,
+            &mut fq_sponge,
+        )
+        .unwrap();
+    assert!(verified == output.folded_instance);
+
+    let fresh = random_pair(&srs, domain, &structure, &mut rng);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let (folded_instance, folded_witness) = scheme
+        .fold_instance_witness_pair(output.pair(), fresh, &mut fq_sponge)
+        .unwrap()
+        .pair();
+    assert!(scheme.check(&folded_instance, &folded_witness));
+
+    // A value out of the table can not be counted in the multiplicities
+    let mut values = vec![structure.table[0]; domain.size()];
+    values[1] = Fp::from(1u64);
+    let mut counts = vec![Fp::zero(); domain.size()];
+    counts[0] = Fp::from(domain.size() as u64);
+    let (instance, witness) =
+        pair(&srs, domain, &structure, values, counts, &mut rng).relax(&scheme.zero_vec);
+    assert!(!scheme.check(&instance, &witness));
+}
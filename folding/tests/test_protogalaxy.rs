@@ -0,0 +1,294 @@
+/// This file shows how to use the ProtoGalaxy folding scheme with a simple
+/// configuration of 3 columns, and two selectors. The configuration is the
+/// same as the one of `test_vanilla_folding.rs`. See [test_protogalaxy] at the
+/// end for a test.
+use ark_ff::{One, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
+use folding::{
+    checker::Column,
+    expressions::FoldingCompatibleExprInner,
+    instance_witness::Foldable,
+    protogalaxy::{ProtoGalaxyOutput, ProtoGalaxyScheme},
+    Alphas, FoldingCompatibleExpr, FoldingConfig, FoldingEnv, Instance, Side, Witness,
+};
+use itertools::Itertools;
+use kimchi::{
+    circuits::{expr::Variable, gate::CurrOrNext},
+    curve::KimchiCurve,
+};
+use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge, FqSponge};
+use poly_commitment::{ipa::SRS, SRS as _};
+use rand::{thread_rng, Rng};
+
+type Fp = ark_bn254::Fr;
+type Curve = ark_bn254::G1Affine;
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>;
+
+/// The instance is the commitments to the polynomials and the challenges
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct TestInstance {
+    commitments: [Curve; 3],
+    challenges: [Fp; 3],
+    alphas: Alphas<Fp>,
+    blinder: Fp,
+}
+
+impl Foldable<Fp> for TestInstance {
+    fn combine(a: Self, b: Self, challenge: Fp) -> Self {
+        TestInstance {
+            commitments: std::array::from_fn(|i| {
+                (a.commitments[i] + b.commitments[i] * challenge).into()
+            }),
+            challenges: std::array::from_fn(|i| a.challenges[i] + challenge * b.challenges[i]),
+            alphas: Alphas::combine(a.alphas, b.alphas, challenge),
+            blinder: a.blinder + challenge * b.blinder,
+        }
+    }
+}
+
+impl Instance<Curve> for TestInstance {
+    fn to_absorb(&self) -> (Vec<Fp>, Vec<Curve>) {
+        let mut fields = Vec::with_capacity(3 + 2);
+        fields.extend(self.challenges);
+        fields.extend(self.alphas.clone().powers());
+        let points = self.commitments.to_vec();
+        (fields, points)
+    }
+
+    fn get_alphas(&self) -> &Alphas<Fp> {
+        &self.alphas
+    }
+
+    fn get_blinder(&self) -> Fp {
+        self.blinder
+    }
+}
+
+/// The evaluations of each column over the domain
+#[derive(Clone)]
+struct TestWitness([Evaluations<Fp, Radix2EvaluationDomain<Fp>>; 3]);
+
+impl Foldable<Fp> for TestWitness {
+    fn combine(mut a: Self, b: Self, challenge: Fp) -> Self {
+        for (a, b) in a.0.iter_mut().zip(b.0) {
+            for (a, b) in a.evals.iter_mut().zip(b.evals) {
+                *a += challenge * b;
+            }
+        }
+        a
+    }
+}
+
+impl Witness<Curve> for TestWitness {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TestStructure {
+    s_add: Vec<Fp>,
+    s_mul: Vec<Fp>,
+}
+
+struct TestFoldingEnv {
+    structure: TestStructure,
+    instances: [TestInstance; 2],
+    curr_witnesses: [TestWitness; 2],
+    next_witnesses: [TestWitness; 2],
+}
+
+impl FoldingEnv<Fp, TestInstance, TestWitness, Column, TestChallenge, ()> for TestFoldingEnv {
+    type Structure = TestStructure;
+
+    fn new(
+        structure: &Self::Structure,
+        instances: [&TestInstance; 2],
+        witnesses: [&TestWitness; 2],
+    ) -> Self {
+        let curr_witnesses = [witnesses[0].clone(), witnesses[1].clone()];
+        let mut next_witnesses = curr_witnesses.clone();
+        for side in next_witnesses.iter_mut() {
+            for col in side.0.iter_mut() {
+                col.evals.rotate_left(1);
+            }
+        }
+        TestFoldingEnv {
+            structure: structure.clone(),
+            instances: [instances[0].clone(), instances[1].clone()],
+            curr_witnesses,
+            next_witnesses,
+        }
+    }
+
+    fn col(&self, col: Column, curr_or_next: CurrOrNext, side: Side) -> &[Fp] {
+        let wit = match curr_or_next {
+            CurrOrNext::Curr => &self.curr_witnesses[side as usize],
+            CurrOrNext::Next => &self.next_witnesses[side as usize],
+        };
+        match col {
+            Column::X(i @ 0..=2) => &wit.0[i].evals,
+            Column::Selector(0) => &self.structure.s_add,
+            Column::Selector(1) => &self.structure.s_mul,
+            // Only 3 columns and 2 selectors
+            Column::X(_) | Column::Selector(_) => unreachable!(),
+        }
+    }
+
+    fn challenge(&self, challenge: TestChallenge, side: Side) -> Fp {
+        match challenge {
+            TestChallenge::Beta => self.instances[side as usize].challenges[0],
+            TestChallenge::Gamma => self.instances[side as usize].challenges[1],
+        }
+    }
+
+    fn selector(&self, _s: &(), _side: Side) -> &[Fp] {
+        unreachable!()
+    }
+}
+
+/// An addition gate, and a multiplication gate scaled by the challenges,
+/// i.e. of degree 4.
+fn constraints() -> Vec<FoldingCompatibleExpr<TestFoldingConfig>> {
+    let get_col = |col| {
+        Box::new(FoldingCompatibleExpr::Atom(
+            FoldingCompatibleExprInner::Cell(Variable {
+                col,
+                row: CurrOrNext::Curr,
+            }),
+        ))
+    };
+    let get_chal = |chal| {
+        Box::new(FoldingCompatibleExpr::Atom(
+            FoldingCompatibleExprInner::Challenge(chal),
+        ))
+    };
+    let (a, b, c) = (
+        get_col(Column::X(0)),
+        get_col(Column::X(1)),
+        get_col(Column::X(2)),
+    );
+
+    let add = FoldingCompatibleExpr::Add(a.clone(), b.clone());
+    let add = FoldingCompatibleExpr::Sub(add.into(), c.clone());
+    let add = FoldingCompatibleExpr::Mul(add.into(), get_col(Column::Selector(0)));
+
+    let mul = FoldingCompatibleExpr::Mul(a, b);
+    let mul = FoldingCompatibleExpr::Sub(mul.into(), c);
+    let mul = FoldingCompatibleExpr::Mul(mul.into(), get_chal(TestChallenge::Beta));
+    let mul = FoldingCompatibleExpr::Mul(mul.into(), get_chal(TestChallenge::Gamma));
+    let mul = FoldingCompatibleExpr::Mul(mul.into(), get_col(Column::Selector(1)));
+
+    vec![add, mul]
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TestFoldingConfig;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum TestChallenge {
+    Beta,
+    Gamma,
+}
+
+impl FoldingConfig for TestFoldingConfig {
+    type Structure = TestStructure;
+    type Column = Column;
+    type Selector = ();
+    type Challenge = TestChallenge;
+    type Curve = Curve;
+    type Srs = SRS<Curve>;
+    type Instance = TestInstance;
+    type Witness = TestWitness;
+    type Env = TestFoldingEnv;
+}
+
+/// Additions on the even rows, multiplications on the odd rows
+fn circuit(domain: Radix2EvaluationDomain<Fp>) -> TestStructure {
+    let size = domain.size();
+    TestStructure {
+        s_add: (0..size).map(|i| Fp::from((i % 2 == 0) as u64)).collect(),
+        s_mul: (0..size).map(|i| Fp::from((i % 2 == 1) as u64)).collect(),
+    }
+}
+
+fn random_pair(
+    srs: &SRS<Curve>,
+    domain: Radix2EvaluationDomain<Fp>,
+    rng: &mut impl Rng,
+) -> (TestInstance, TestWitness) {
+    let (mut a, mut b, mut c) = (vec![], vec![], vec![]);
+    for i in 0..domain.size() {
+        let (x, y) = (Fp::rand(rng), Fp::rand(rng));
+        a.push(x);
+        b.push(y);
+        c.push(if i % 2 == 0 { x + y } else { x * y });
+    }
+    let witness =
+        TestWitness([a, b, c].map(|evals| Evaluations::from_vec_and_domain(evals, domain)));
+    let commitments = witness
+        .0
+        .iter()
+        .map(|w| {
+            srs.commit_evaluations_non_hiding(domain, w)
+                .get_first_chunk()
+        })
+        .collect_vec();
+    let instance = TestInstance {
+        commitments: commitments.try_into().unwrap(),
+        challenges: [(); 3].map(|_| Fp::rand(rng)),
+        alphas: Alphas::new(Fp::rand(rng)),
+        blinder: Fp::one(),
+    };
+    (instance, witness)
+}
+
+#[test]
+fn test_protogalaxy() {
+    let mut rng = thread_rng();
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(8);
+    srs.get_lagrange_basis(domain);
+    let structure = circuit(domain);
+
+    let scheme = ProtoGalaxyScheme::<TestFoldingConfig>::new(constraints(), domain, &structure);
+    // The multiplication gate, and the alphas
+    assert_eq!(scheme.get_degree(), 5);
+
+    let (instance, witness) = random_pair(&srs, domain, &mut rng);
+    let mut accumulator = scheme.accumulate(instance, witness);
+    assert!(scheme.check(&accumulator.0, &accumulator.1));
+
+    // Folding two instances, then a single one, in the accumulator
+    for n_instances in [2, 1] {
+        let instances = (0..n_instances)
+            .map(|_| random_pair(&srs, domain, &mut rng))
+            .collect_vec();
+        let to_verify = instances.iter().map(|(i, _)| i.clone()).collect_vec();
+        let acc_instance = accumulator.0.clone();
+
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        let ProtoGalaxyOutput {
+            folded_instance,
+            folded_witness,
+            proof,
+        } = scheme.fold(accumulator, instances, &mut fq_sponge).unwrap();
+        assert_eq!(proof.f_coefficients.len(), scheme.get_number_of_betas());
+        assert_eq!(
+            proof.k_coefficients.len(),
+            (scheme.get_degree() - 1) * n_instances
+        );
+
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        let verified = scheme
+            .verify_fold(acc_instance, to_verify, &proof, &mut fq_sponge)
+            .unwrap();
+        assert_eq!(verified, folded_instance);
+        assert!(!folded_instance.error.is_zero());
+        assert!(scheme.check(&folded_instance, &folded_witness));
+
+        // A different witness does not satisfy the accumulated relation
+        let mut tampered = folded_witness.clone();
+        tampered.0[2].evals[1] += Fp::one();
+        assert!(!scheme.check(&folded_instance, &tampered));
+
+        accumulator = (folded_instance, folded_witness);
+    }
+}
@@ -0,0 +1,367 @@
+/// This file shows how to fold a relation with the standard configuration,
+/// instances and witnesses of [folding::standard_config], which only requires
+/// the types of the columns and of the challenges of the relation. See
+/// [test_standard_config] at the end for a test.
+use ark_ec::AffineRepr;
+use ark_ff::{One, UniformRand};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
+use folding::{
+    checker::{Checker, Provide},
+    expressions::{FoldingColumnTrait, FoldingCompatibleExprInner},
+    standard_config::{EmptyStructure, StandardConfig, StandardInstance, StandardWitness},
+    ExpExtension, FoldingCompatibleExpr, FoldingOutput, FoldingScheme, RelaxedInstance,
+    RelaxedWitness,
+};
+use kimchi::{
+    circuits::{expr::Variable, gate::CurrOrNext},
+    curve::KimchiCurve,
+};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, ScalarChallenge},
+    FqSponge,
+};
+use poly_commitment::{commitment::EndoCurve, ipa::SRS, SRS as _};
+use rand::thread_rng;
+
+type Fp = ark_bn254::Fr;
+type Curve = ark_bn254::G1Affine;
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum TestColumn {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl FoldingColumnTrait for TestColumn {
+    fn is_witness(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum TestChallenge {
+    Beta,
+}
+
+folding::standard_index!(TestColumn, TestChallenge);
+
+type TestInstance = StandardInstance<Curve, 4, 1>;
+type TestWitness = StandardWitness<Fp, 4>;
+type TestFoldingConfig =
+    StandardConfig<Curve, TestColumn, TestChallenge, TestInstance, TestWitness, SRS<Curve>>;
+
+/// `a * b - c` and `β (a + b - d)`
+fn constraints() -> Vec<FoldingCompatibleExpr<TestFoldingConfig>> {
+    let cell = |col| {
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Cell(Variable {
+            col,
+            row: CurrOrNext::Curr,
+        }))
+    };
+    let beta =
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Challenge(TestChallenge::Beta));
+    let [a, b, c, d] = [TestColumn::A, TestColumn::B, TestColumn::C, TestColumn::D].map(cell);
+    vec![a.clone() * b.clone() - c, beta * (a + b - d)]
+}
+
+fn random_witness(domain: Radix2EvaluationDomain<Fp>) -> TestWitness {
+    let mut rng = thread_rng();
+    let a: Vec<Fp> = (0..domain.size()).map(|_| Fp::rand(&mut rng)).collect();
+    let b: Vec<Fp> = (0..domain.size()).map(|_| Fp::rand(&mut rng)).collect();
+    let c = a.iter().zip(b.iter()).map(|(a, b)| *a * b).collect();
+    let d = a.iter().zip(b.iter()).map(|(a, b)| *a + b).collect();
+    StandardWitness {
+        columns: [a, b, c, d].map(|evals| Evaluations::from_vec_and_domain(evals, domain)),
+    }
+}
+
+/// Resolves the atoms of the relaxed expression for a folded pair
+struct FoldedProvider {
+    instance: RelaxedInstance<Curve, TestInstance>,
+    witness: RelaxedWitness<Curve, TestWitness>,
+}
+
+impl Provide<TestFoldingConfig> for FoldedProvider {
+    fn resolve(
+        &self,
+        inner: FoldingCompatibleExprInner<TestFoldingConfig>,
+        domain: Radix2EvaluationDomain<Fp>,
+    ) -> Vec<Fp> {
+        let domain_size = domain.size as usize;
+        let instance = &self.instance.extended_instance.instance;
+        match inner {
+            FoldingCompatibleExprInner::Constant(c) => vec![c; domain_size],
+            FoldingCompatibleExprInner::Challenge(chal) => vec![instance[chal]; domain_size],
+            FoldingCompatibleExprInner::Cell(Variable { col, row }) => {
+                let mut col = self.witness.extended_witness.witness[col].to_vec();
+                if let CurrOrNext::Next = row {
+                    col.rotate_left(1);
+                }
+                col
+            }
+            FoldingCompatibleExprInner::Extensions(ext) => match ext {
+                ExpExtension::U => vec![self.instance.u; domain_size],
+                ExpExtension::Error => self.witness.error_vec.evals.clone(),
+                ExpExtension::ExtendedWitness(i) => {
+                    self.witness.extended_witness.extended[&i].evals.clone()
+                }
+                ExpExtension::Alpha(i) => vec![instance.alphas.get(i).unwrap(); domain_size],
+                ExpExtension::Selector(_) => panic!("no dynamic selector"),
+            },
+        }
+    }
+}
+
+impl Checker<TestFoldingConfig> for FoldedProvider {}
+
+/// Checks that the commitments of the folded instance are the commitments to
+/// the columns and to the error term of the folded witness, with the blinders
+/// accumulated in the folded instance.
+fn check_folded_commitments(
+    srs: &SRS<Curve>,
+    domain: Radix2EvaluationDomain<Fp>,
+    instance: &RelaxedInstance<Curve, TestInstance>,
+    witness: &RelaxedWitness<Curve, TestWitness>,
+) {
+    let columns_blinder = instance.extended_instance.instance.blinder;
+    for (commitment, column) in instance
+        .extended_instance
+        .instance
+        .commitments
+        .iter()
+        .zip(witness.extended_witness.witness.columns.iter())
+    {
+        let expected = srs
+            .commit_evaluations_non_hiding(domain, column)
+            .get_first_chunk()
+            + srs.h * columns_blinder;
+        assert_eq!(*commitment, expected);
+    }
+    let expected = srs
+        .commit_evaluations_non_hiding(domain, &witness.error_vec)
+        .get_first_chunk()
+        + srs.h * instance.blinder;
+    assert_eq!(instance.error_commitment.get_first_chunk(), expected);
+}
+
+#[test]
+fn test_standard_config() {
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(domain.size());
+    srs.get_lagrange_basis(domain);
+    let structure = EmptyStructure::default();
+    let (scheme, final_constraint) =
+        FoldingScheme::<TestFoldingConfig>::new(constraints(), &srs, domain, &structure);
+
+    let [left, right] = [(); 2].map(|_| {
+        let witness = random_witness(domain);
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        let instance = TestInstance::from_witness(&witness, &srs, domain, &mut fq_sponge);
+        (instance, witness)
+    });
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let FoldingOutput {
+        folded_instance,
+        folded_witness,
+        t_0,
+        t_1,
+        t_blinders,
+        relaxed_extended_left_instance,
+        relaxed_extended_right_instance,
+        to_absorb: _,
+    } = scheme
+        .fold_instance_witness_pair(left, right, &mut fq_sponge)
+        .unwrap();
+    assert!(!t_0.get_first_chunk().is_zero());
+    assert!(!t_1.get_first_chunk().is_zero());
+    assert_eq!(t_blinders, [Fp::one(); 2]);
+
+    check_folded_commitments(&srs, domain, &folded_instance, &folded_witness);
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let folded_instance_explicit = scheme
+        .fold_instance_pair(
+            relaxed_extended_left_instance,
+            relaxed_extended_right_instance,
+            [t_0, t_1],
+            &mut fq_sponge,
+        )
+        .unwrap();
+    assert!(folded_instance == folded_instance_explicit);
+
+    let checker = FoldedProvider {
+        instance: folded_instance,
+        witness: folded_witness,
+    };
+    checker.check(&final_constraint, domain);
+}
+
+#[test]
+fn test_standard_config_zk() {
+    let mut rng = thread_rng();
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(domain.size());
+    srs.get_lagrange_basis(domain);
+    let structure = EmptyStructure::default();
+    let (scheme, final_constraint) =
+        FoldingScheme::<TestFoldingConfig>::new(constraints(), &srs, domain, &structure);
+
+    let [left, right] = [(); 2].map(|_| {
+        let witness = random_witness(domain);
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        let instance =
+            TestInstance::from_witness_zk(&witness, &srs, domain, &mut fq_sponge, &mut rng);
+        (instance, witness)
+    });
+    assert_ne!(left.0.blinder, Fp::one());
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let FoldingOutput {
+        folded_instance,
+        folded_witness,
+        t_0,
+        t_1,
+        t_blinders,
+        relaxed_extended_left_instance,
+        relaxed_extended_right_instance,
+        to_absorb: _,
+    } = scheme
+        .fold_instance_witness_pair_zk(left, right, &mut fq_sponge, &mut rng)
+        .unwrap();
+    assert!(t_blinders.iter().all(|blinder| !blinder.is_one()));
+
+    check_folded_commitments(&srs, domain, &folded_instance, &folded_witness);
+
+    // The blinders of the cross terms are required to fold the instances
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let folded_instance_explicit = scheme
+        .fold_instance_pair_with_blinders(
+            relaxed_extended_left_instance,
+            relaxed_extended_right_instance,
+            [t_0, t_1],
+            t_blinders,
+            &mut fq_sponge,
+        )
+        .unwrap();
+    assert!(folded_instance == folded_instance_explicit);
+
+    let checker = FoldedProvider {
+        instance: folded_instance,
+        witness: folded_witness,
+    };
+    checker.check(&final_constraint, domain);
+}
+
+#[test]
+fn test_standard_config_endo_challenges() {
+    let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+    let srs = SRS::<Curve>::create(domain.size());
+    srs.get_lagrange_basis(domain);
+    let structure = EmptyStructure::default();
+    let (endo_q, endo_r) = Curve::endos();
+    let (scheme, final_constraint) =
+        FoldingScheme::<TestFoldingConfig>::new(constraints(), &srs, domain, &structure);
+    let scheme = scheme.with_endo_challenges(*endo_q, *endo_r);
+
+    let [left, right] = [(); 2].map(|_| {
+        let witness = random_witness(domain);
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        let instance = TestInstance::from_witness(&witness, &srs, domain, &mut fq_sponge);
+        (instance, witness)
+    });
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let mut verifier_sponge = fq_sponge.clone();
+    let FoldingOutput {
+        folded_instance,
+        folded_witness,
+        t_0,
+        t_1,
+        t_blinders: _,
+        relaxed_extended_left_instance,
+        relaxed_extended_right_instance,
+        to_absorb,
+    } = scheme
+        .fold_instance_witness_pair(left, right, &mut fq_sponge)
+        .unwrap();
+
+    // The verifier can fold the commitments with the endomorphism, using the
+    // 128 bits squeezed from the sponge
+    verifier_sponge.absorb_fr(&to_absorb.0);
+    verifier_sponge.absorb_g(&to_absorb.1);
+    let challenge = ScalarChallenge(verifier_sponge.challenge());
+    let folded_commitments = Curve::combine_one_endo(
+        *endo_r,
+        *endo_q,
+        &relaxed_extended_left_instance
+            .extended_instance
+            .instance
+            .commitments,
+        &relaxed_extended_right_instance
+            .extended_instance
+            .instance
+            .commitments,
+        challenge,
+    );
+    assert_eq!(
+        folded_commitments,
+        folded_instance.extended_instance.instance.commitments
+    );
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let folded_instance_explicit = scheme
+        .fold_instance_pair(
+            relaxed_extended_left_instance,
+            relaxed_extended_right_instance,
+            [t_0, t_1],
+            &mut fq_sponge,
+        )
+        .unwrap();
+    assert!(folded_instance == folded_instance_explicit);
+
+    // Folding the accumulator again, whose error commitment is not the point
+    // at infinity anymore
+    let witness = random_witness(domain);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let instance = TestInstance::from_witness(&witness, &srs, domain, &mut fq_sponge);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let FoldingOutput {
+        folded_instance,
+        folded_witness,
+        t_0,
+        t_1,
+        t_blinders: _,
+        relaxed_extended_left_instance,
+        relaxed_extended_right_instance,
+        to_absorb: _,
+    } = scheme
+        .fold_instance_witness_pair(
+            (folded_instance, folded_witness),
+            (instance, witness),
+            &mut fq_sponge,
+        )
+        .unwrap();
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let verified = scheme
+        .verify_fold(
+            relaxed_extended_left_instance,
+            relaxed_extended_right_instance,
+            t_0,
+            t_1,
+            &mut fq_sponge,
+        )
+        .unwrap();
+    assert!(folded_instance == verified);
+
+    let checker = FoldedProvider {
+        instance: folded_instance,
+        witness: folded_witness,
+    };
+    checker.check(&final_constraint, domain);
+}
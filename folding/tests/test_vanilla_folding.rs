@@ -14,8 +14,8 @@ use folding::{
     checker::{Checker, Column, Provide},
     expressions::FoldingCompatibleExprInner,
     instance_witness::Foldable,
-    Alphas, ExpExtension, FoldingCompatibleExpr, FoldingConfig, FoldingEnv, FoldingOutput,
-    FoldingScheme, Instance, RelaxedInstance, RelaxedWitness, Side, Witness,
+    Alphas, ExpExtension, FoldingCompatibleExpr, FoldingConfig, FoldingEnv, FoldingError,
+    FoldingOutput, FoldingScheme, Instance, RelaxedInstance, RelaxedWitness, Side, Witness,
 };
 use itertools::Itertools;
 use kimchi::{
@@ -23,8 +23,10 @@ use kimchi::{
     curve::KimchiCurve,
 };
 use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge, FqSponge};
-use poly_commitment::{ipa::SRS, SRS as _};
+use poly_commitment::{ipa::SRS, PolyComm, SRS as _};
 use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use std::println as debug;
 
 type Fp = ark_bn254::Fr;
@@ -35,11 +37,15 @@ type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>;
 /// The instance is the commitments to the polynomials and the challenges
 /// There are 3 commitments and challanges because there are 3 columns, A, B and
 /// C.
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[serde_as]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 struct TestInstance {
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; 3]")]
     commitments: [Curve; 3],
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; 3]")]
     challenges: [Fp; 3],
     alphas: Alphas<Fp>,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     blinder: Fp,
 }
 
@@ -77,8 +83,12 @@ impl Instance<Curve> for TestInstance {
 
 /// Our witness is going to be the polynomials that we will commit too.
 /// Vec<Fp> will be the evaluations of each x_1, x_2 and x_3 over the domain.
-#[derive(Clone)]
-struct TestWitness([Evaluations<Fp, Radix2EvaluationDomain<Fp>>; 3]);
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+struct TestWitness(
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; 3]")]
+    [Evaluations<Fp, Radix2EvaluationDomain<Fp>>; 3],
+);
 
 impl Foldable<Fp> for TestWitness {
     fn combine(mut a: Self, b: Self, challenge: Fp) -> Self {
@@ -463,12 +473,15 @@ fn test_folding_instance() {
     let left = (left_instance.clone(), left_witness);
     let right = (right_instance.clone(), right_witness);
 
-    let folded = scheme.fold_instance_witness_pair(left, right, &mut fq_sponge);
+    let folded = scheme
+        .fold_instance_witness_pair(left, right, &mut fq_sponge)
+        .unwrap();
     let FoldingOutput {
         folded_instance,
         folded_witness,
         t_0,
         t_1,
+        t_blinders: _,
         relaxed_extended_left_instance,
         relaxed_extended_right_instance,
         to_absorb,
@@ -477,12 +490,14 @@ fn test_folding_instance() {
     {
         let folded_instance_explicit = {
             let mut fq_sponge_inst = BaseSponge::new(Curve::other_curve_sponge_params());
-            scheme.fold_instance_pair(
-                relaxed_extended_left_instance,
-                relaxed_extended_right_instance,
-                [t_0.clone(), t_1.clone()],
-                &mut fq_sponge_inst,
-            )
+            scheme
+                .fold_instance_pair(
+                    relaxed_extended_left_instance,
+                    relaxed_extended_right_instance,
+                    [t_0.clone(), t_1.clone()],
+                    &mut fq_sponge_inst,
+                )
+                .unwrap()
         };
 
         assert!(folded_instance == folded_instance_explicit);
@@ -508,3 +523,151 @@ fn test_folding_instance() {
         checker.check(&final_constraint, domain);
     }
 }
+
+// The folding state can be persisted and restored, e.g. to be sent to another
+// folding worker, and the restored state is still checked by the verifier.
+#[test]
+fn test_folding_serialization() {
+    let constraints = constraints();
+    let domain = Radix2EvaluationDomain::<Fp>::new(2).unwrap();
+    let srs = poly_commitment::ipa::SRS::<Curve>::create(2);
+    srs.get_lagrange_basis(domain);
+
+    let [s_add, s_mul] = circuit();
+    let structure = TestStructure {
+        s_add,
+        s_mul,
+        constants: vec![],
+    };
+    let (scheme, final_constraint) =
+        FoldingScheme::<TestFoldingConfig>::new(constraints, &srs, domain, &structure);
+
+    let witness =
+        |columns: [[u32; 2]; 3]| {
+            TestWitness(columns.map(|column| {
+                Evaluations::from_vec_and_domain(column.map(Fp::from).to_vec(), domain)
+            }))
+        };
+    let left_witness = witness([[1, 2], [2, 3], [3, 6]]);
+    let right_witness = witness([[4, 3], [5, 6], [9, 18]]);
+    let left = (
+        instance_from_witness(&left_witness, &srs, domain),
+        left_witness,
+    );
+    let right = (
+        instance_from_witness(&right_witness, &srs, domain),
+        right_witness,
+    );
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let folded = scheme
+        .fold_instance_witness_pair(left, right, &mut fq_sponge)
+        .unwrap();
+
+    let bytes = rmp_serde::to_vec(&folded).unwrap();
+    let restored: FoldingOutput<TestFoldingConfig> = rmp_serde::from_slice(&bytes).unwrap();
+    let json = serde_json::to_string(&folded).unwrap();
+    let restored_json: FoldingOutput<TestFoldingConfig> = serde_json::from_str(&json).unwrap();
+
+    for restored in [restored, restored_json] {
+        assert!(restored.folded_instance == folded.folded_instance);
+        assert_eq!(restored.t_0, folded.t_0);
+        assert_eq!(restored.t_1, folded.t_1);
+        assert_eq!(restored.to_absorb, folded.to_absorb);
+        assert_eq!(
+            restored.folded_witness.error_vec,
+            folded.folded_witness.error_vec
+        );
+        for (restored, expected) in restored
+            .folded_witness
+            .extended_witness
+            .witness
+            .0
+            .iter()
+            .zip(folded.folded_witness.extended_witness.witness.0.iter())
+        {
+            assert_eq!(restored.evals, expected.evals);
+        }
+
+        // The verifier folds the restored instances to the same instance
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        let verified = scheme
+            .fold_instance_pair(
+                restored.relaxed_extended_left_instance,
+                restored.relaxed_extended_right_instance,
+                [restored.t_0, restored.t_1],
+                &mut fq_sponge,
+            )
+            .unwrap();
+        assert!(verified == folded.folded_instance);
+
+        let checker = ExtendedProvider::new(
+            structure.clone(),
+            restored.folded_instance,
+            restored.folded_witness,
+        );
+        checker.check(&final_constraint, domain);
+    }
+}
+
+// Folding pairs that do not match the scheme returns an error instead of
+// panicking.
+#[test]
+fn test_folding_errors() {
+    let domain = Radix2EvaluationDomain::<Fp>::new(2).unwrap();
+    let srs = poly_commitment::ipa::SRS::<Curve>::create(2);
+    srs.get_lagrange_basis(domain);
+
+    let [s_add, s_mul] = circuit();
+    let structure = TestStructure {
+        s_add,
+        s_mul,
+        constants: vec![],
+    };
+    let (scheme, _) =
+        FoldingScheme::<TestFoldingConfig>::new(constraints(), &srs, domain, &structure);
+
+    let witness =
+        |columns: [[u32; 2]; 3]| {
+            TestWitness(columns.map(|column| {
+                Evaluations::from_vec_and_domain(column.map(Fp::from).to_vec(), domain)
+            }))
+        };
+    let pair = |witness: TestWitness| (instance_from_witness(&witness, &srs, domain), witness);
+    let left = pair(witness([[1, 2], [2, 3], [3, 6]]));
+    let right = pair(witness([[4, 3], [5, 6], [9, 18]]));
+
+    // The witness is missing a row
+    let mut short = right.clone();
+    for column in short.1 .0.iter_mut() {
+        column.evals.pop();
+    }
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let result = scheme.fold_instance_witness_pair(left.clone(), short, &mut fq_sponge);
+    assert!(matches!(
+        result,
+        Err(FoldingError::WitnessSizeMismatch(1, 2))
+    ));
+
+    // Only one alpha is given, while there are two constraints
+    let mut missing_alpha = right.clone();
+    missing_alpha.0.alphas = Alphas::Combinations(vec![Fp::one()]);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let result = scheme.fold_instance_witness_pair(left.clone(), missing_alpha, &mut fq_sponge);
+    assert!(matches!(result, Err(FoldingError::MissingAlphas(2))));
+
+    // The verifier rejects cross terms of several chunks
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let output = scheme
+        .fold_instance_witness_pair(left, right, &mut fq_sponge)
+        .unwrap();
+    let chunked = PolyComm::new(vec![output.t_0.get_first_chunk(); 2]);
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    let result = scheme.fold_instance_pair(
+        output.relaxed_extended_left_instance,
+        output.relaxed_extended_right_instance,
+        [chunked, output.t_1],
+        &mut fq_sponge,
+    );
+    assert!(matches!(result, Err(FoldingError::CommitmentChunks(2))));
+}
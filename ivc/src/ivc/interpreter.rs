@@ -542,6 +542,9 @@ pub fn process_ecadds<F, Ff, Env, const N_COL_TOTAL: usize, const N_CHALS: usize
     };
 
     // TODO FIXME STUBBED. multiply by r. For now these are just C_{R,i}, they must be {r * C_{R,i}}
+    // The scaling should use the endomorphism like the folding verifier with
+    // `FoldingScheme::with_endo_challenges`, i.e. `r` given as a 128-bit
+    // endoscalar, see `folding::instance_witness::combine_commitments_endo`.
     //let r_hat_large: Box<[[F; 2 * N_LIMBS_LARGE]; N_COL_TOTAL]> = Box::new(comms_large[1]);
     let r_hat_large: Box<[[F; 2 * N_LIMBS_LARGE]; N_COL_TOTAL]> = {
         let mut rng = rand::thread_rng();
@@ -474,11 +474,13 @@ pub fn heavy_test_simple_add() {
     // });
     // ```
     println!("fold_instance_witness_pair");
-    let folding_output_one = folding_scheme.fold_instance_witness_pair(
-        (folding_instance_one, folding_witness_one),
-        (folding_instance_two, folding_witness_two),
-        &mut fq_sponge,
-    );
+    let folding_output_one = folding_scheme
+        .fold_instance_witness_pair(
+            (folding_instance_one, folding_witness_one),
+            (folding_instance_two, folding_witness_two),
+            &mut fq_sponge,
+        )
+        .unwrap();
     println!("Folding 1 succeeded");
 
     let FoldingOutput {
@@ -747,14 +749,16 @@ pub fn heavy_test_simple_add() {
 
     let mut fq_sponge_before_last_fold = fq_sponge.clone();
 
-    let folding_output_two = folding_scheme.fold_instance_witness_pair(
-        (folded_instance_one.clone(), folded_witness_one.clone()),
-        (
-            folding_instance_three.clone(),
-            folding_witness_three.clone(),
-        ),
-        &mut fq_sponge,
-    );
+    let folding_output_two = folding_scheme
+        .fold_instance_witness_pair(
+            (folded_instance_one.clone(), folded_witness_one.clone()),
+            (
+                folding_instance_three.clone(),
+                folding_witness_three.clone(),
+            ),
+            &mut fq_sponge,
+        )
+        .unwrap();
 
     let folded_instance_two = folding_output_two.folded_instance;
     let folded_witness_two = folding_output_two.folded_witness;
@@ -1017,12 +1021,14 @@ pub fn heavy_test_simple_add() {
     {
         assert!(
             folded_instance_two
-                == folding_scheme.fold_instance_pair(
-                    folding_output_two.relaxed_extended_left_instance,
-                    folding_output_two.relaxed_extended_right_instance,
-                    [folding_output_two.t_0, folding_output_two.t_1],
-                    &mut fq_sponge_before_last_fold,
-                ),
+                == folding_scheme
+                    .fold_instance_pair(
+                        folding_output_two.relaxed_extended_left_instance,
+                        folding_output_two.relaxed_extended_right_instance,
+                        [folding_output_two.t_0, folding_output_two.t_1],
+                        &mut fq_sponge_before_last_fold,
+                    )
+                    .unwrap(),
             "Last fold must (natively) verify"
         );
     }
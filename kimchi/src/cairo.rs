@@ -0,0 +1,241 @@
+//! This module proves the execution of Cairo programs with the Cairo gates.
+//!
+//! The program is executed by the segmented runner of turshi, whose memory
+//! and trace are relocated, and replayed by the flat runner to obtain the
+//! witness of the Cairo gates. The circuit starts with one public input row
+//! per claimed register, followed by the Cairo gadget:
+//!
+//! | row       | gate                | content                                 |
+//! |-----------|---------------------|-----------------------------------------|
+//! | 0..4      | public generic gate | `pc_ini`, `ap_ini`, `pc_fin`, `ap_fin`  |
+//! | 4         | `CairoClaim`        | claimed and last registers              |
+//! | 4i+5      | `CairoInstruction`  | registers and operands of step i        |
+//! | 4i+6      | `CairoFlags`        | flags of step i                         |
+//! | 4i+7      | `CairoTransition`   | registers of steps i and i+1            |
+//! | 4i+8      | `Zero`              | registers of step i+1                   |
+//!
+//! The cells holding the same register or operand in consecutive rows are
+//! wired together, and the claim row is wired to the public input.
+//!
+//! Note that the Cairo gates do not include a memory argument: the proof
+//! attests the transitions of the registers, but not that the values read
+//! by the instructions are consistent with a single memory. The public
+//! memory of the execution, e.g. the program and its arguments, is part of
+//! the [CairoStatement] and checked by [verify_cairo] against the program,
+//! but it is not bound to the witness.
+
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, Connect},
+        polynomials::{generic::GenericGateSpec, turshi::witness::cairo_witness},
+        wires::{Wire, COLUMNS},
+    },
+    curve::KimchiCurve,
+    error::CairoError,
+    plonk_sponge::FrSponge,
+    proof::ProverProof,
+    prover_index::ProverIndex,
+    verifier::verify,
+    verifier_index::VerifierIndex,
+};
+use ark_ff::{PrimeField, Zero};
+use mina_poseidon::FqSponge;
+use poly_commitment::{OpenProof, SRS as _};
+use rand::{CryptoRng, RngCore};
+use std::{array, sync::Arc};
+use turshi::{CairoProgram, CairoRunner, MaybeRelocatable, Pointers};
+
+/// Number of public inputs of a Cairo circuit: the initial and final `pc`
+/// and `ap`
+pub const CAIRO_PUBLIC_INPUTS: usize = 4;
+
+/// Number of columns shared by the instruction and transition rows of a step
+const SHARED_COLUMNS: usize = 7;
+
+/// Number of registers
+const REGISTERS: usize = 3;
+
+/// The public statement of a Cairo execution
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CairoStatement<F> {
+    /// initial program counter
+    pub pc_ini: F,
+    /// initial allocation pointer
+    pub ap_ini: F,
+    /// program counter of the last step
+    pub pc_fin: F,
+    /// allocation pointer of the last step
+    pub ap_fin: F,
+    /// number of steps of the execution
+    pub steps: usize,
+    /// addresses and values of the public memory, sorted by address
+    pub public_memory: Vec<(u64, F)>,
+}
+
+impl<F: PrimeField> CairoStatement<F> {
+    /// Returns the public input of the circuit
+    pub fn public_input(&self) -> Vec<F> {
+        vec![self.pc_ini, self.ap_ini, self.pc_fin, self.ap_fin]
+    }
+}
+
+/// Creates the circuit of a Cairo execution of `steps` steps, see the module
+/// documentation.
+/// Panics if `steps` is zero
+pub fn cairo_circuit<F: PrimeField>(steps: usize) -> Vec<CircuitGate<F>> {
+    assert!(steps > 0, "a Cairo execution has at least one step");
+    let mut gates: Vec<_> = (0..CAIRO_PUBLIC_INPUTS)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+        })
+        .collect();
+    let claim = gates.len();
+    let (cairo, _) = CircuitGate::create_cairo_gadget(claim, steps);
+    gates.extend(cairo);
+
+    let instruction = |i: usize| claim + 4 * i + 1;
+    // the claimed registers are the public input
+    for col in 0..CAIRO_PUBLIC_INPUTS {
+        gates.connect_cell_pair((col, 0), (claim, col));
+    }
+    // the last registers are the ones of the last instruction
+    for col in 0..2 {
+        gates.connect_cell_pair((claim, 4 + col), (instruction(steps - 1), col));
+    }
+    for i in 0..steps - 1 {
+        // the transition uses the registers and operands of the instruction
+        for col in 0..SHARED_COLUMNS {
+            gates.connect_cell_pair((instruction(i), col), (instruction(i) + 2, col));
+        }
+        // the auxiliary row contains the registers of the next instruction
+        for col in 0..REGISTERS {
+            gates.connect_cell_pair((instruction(i) + 3, col), (instruction(i + 1), col));
+        }
+    }
+    gates
+}
+
+/// Creates the prover index of the circuit of a Cairo execution of `steps`
+/// steps
+pub fn cairo_index<G: KimchiCurve, OpeningProof: OpenProof<G>>(
+    srs: Arc<OpeningProof::SRS>,
+    steps: usize,
+) -> Result<ProverIndex<G, OpeningProof>, CairoError>
+where
+    G::BaseField: PrimeField,
+{
+    let cs = ConstraintSystem::create(cairo_circuit(steps))
+        .public(CAIRO_PUBLIC_INPUTS)
+        .build()?;
+    srs.get_lagrange_basis(cs.domain.d1);
+    Ok(ProverIndex::create(cs, *G::other_curve_endo(), srs))
+}
+
+/// Runs the function at offset `entry` of `program` with the arguments
+/// `inputs`, for at most `max_steps` steps, and proves its execution.
+/// Returns the statement of the execution with its proof.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn prove_cairo<
+    G: KimchiCurve,
+    OpeningProof: OpenProof<G>,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    RNG: RngCore + CryptoRng,
+>(
+    group_map: &G::Map,
+    srs: Arc<OpeningProof::SRS>,
+    program: &[G::ScalarField],
+    entry: u64,
+    inputs: &[MaybeRelocatable<G::ScalarField>],
+    max_steps: u64,
+    rng: &mut RNG,
+) -> Result<(CairoStatement<G::ScalarField>, ProverProof<G, OpeningProof>), CairoError>
+where
+    G::BaseField: PrimeField,
+    VerifierIndex<G, OpeningProof>: Clone,
+{
+    let mut runner = CairoRunner::new(program, vec![]);
+    runner.run(entry, inputs, max_steps)?;
+    let (mut memory, trace) = runner.relocate()?;
+    let public_memory = memory.public_memory();
+
+    // replay the relocated execution to obtain the operands of each step
+    let prog = CairoProgram::new_from_state(&mut memory.memory, trace[0]);
+    if let Some(step) = (0..trace.len().max(prog.trace().len())).find(|&i| {
+        match (trace.get(i), prog.trace().get(i)) {
+            (Some(regs), Some(instr)) => {
+                regs.pc() != instr.pc() || regs.ap() != instr.ap() || regs.fp() != instr.fp()
+            }
+            _ => true,
+        }
+    }) {
+        return Err(CairoError::InconsistentTrace(step));
+    }
+
+    let statement = CairoStatement {
+        pc_ini: prog.ini().pc(),
+        ap_ini: prog.ini().ap(),
+        pc_fin: prog.fin().pc(),
+        ap_fin: prog.fin().ap(),
+        steps: trace.len(),
+        public_memory,
+    };
+
+    let cairo = cairo_witness(&prog);
+    let witness: [Vec<G::ScalarField>; COLUMNS] = array::from_fn(|col| {
+        let mut column = vec![G::ScalarField::zero(); CAIRO_PUBLIC_INPUTS];
+        if col == 0 {
+            column.copy_from_slice(&statement.public_input());
+        }
+        column.extend_from_slice(&cairo[col]);
+        column
+    });
+
+    let index = cairo_index::<G, OpeningProof>(srs, statement.steps)?;
+    let proof =
+        ProverProof::create::<EFqSponge, EFrSponge, RNG>(group_map, witness, &[], &index, rng)?;
+    Ok((statement, proof))
+}
+
+/// Verifies the proof of an execution of `program` with the statement
+/// `statement`, as produced by [prove_cairo]
+pub fn verify_cairo<
+    G: KimchiCurve,
+    OpeningProof: OpenProof<G>,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+>(
+    group_map: &G::Map,
+    srs: Arc<OpeningProof::SRS>,
+    program: &[G::ScalarField],
+    statement: &CairoStatement<G::ScalarField>,
+    proof: &ProverProof<G, OpeningProof>,
+) -> Result<(), CairoError>
+where
+    G::BaseField: PrimeField,
+{
+    if statement.steps == 0 {
+        return Err(CairoError::EmptyExecution);
+    }
+    // the program is the first segment of the public memory, from address 1
+    let loaded = statement
+        .public_memory
+        .iter()
+        .take(program.len())
+        .map(|(addr, value)| (*addr, *value));
+    if statement.public_memory.len() < program.len()
+        || !loaded.eq(program.iter().enumerate().map(|(i, x)| (i as u64 + 1, *x)))
+    {
+        return Err(CairoError::WrongProgram);
+    }
+
+    let verifier_index = cairo_index::<G, OpeningProof>(srs, statement.steps)?.verifier_index();
+    verify::<G, EFqSponge, EFrSponge, OpeningProof>(
+        group_map,
+        &verifier_index,
+        proof,
+        &statement.public_input(),
+    )?;
+    Ok(())
+}
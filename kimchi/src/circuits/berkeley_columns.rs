@@ -205,6 +205,18 @@ impl<F: Copy> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>> {
             Index(GateType::Rot64) => self
                 .rot_selector
                 .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::CairoClaim) => self
+                .cairo_claim_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::CairoInstruction) => self
+                .cairo_instruction_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::CairoFlags) => self
+                .cairo_flags_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::CairoTransition) => self
+                .cairo_transition_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
             Permutation(i) => Ok(self.s[i]),
             Coefficient(i) => Ok(self.coefficients[i]),
             LookupKindIndex(LookupPattern::Xor) => self
@@ -163,6 +163,22 @@ pub struct ColumnEvaluations<F: PrimeField> {
     /// Rot gate selector over domain d8
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub rot_selector8: Option<E<F, D<F>>>,
+
+    /// CairoClaim gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_claim_selector8: Option<E<F, D<F>>>,
+
+    /// CairoInstruction gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_instruction_selector8: Option<E<F, D<F>>>,
+
+    /// CairoFlags gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_flags_selector8: Option<E<F, D<F>>>,
+
+    /// CairoTransition gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_transition_selector8: Option<E<F, D<F>>>,
 }
 
 #[serde_as]
@@ -205,6 +221,12 @@ pub struct ConstraintSystem<F: PrimeField> {
 
     /// Disable gates checks (for testing; only enables with development builds)
     pub disable_gates_checks: bool,
+
+    /// Whether the circuit uses the Cairo gates. It is kept out of the
+    /// [FeatureFlags], whose layout is shared with the OCaml bindings, and is
+    /// serialized last so that the existing constraint systems still decode.
+    #[serde(default)]
+    pub cairo: bool,
 }
 
 /// Represents an error found when verifying a witness with a gate
@@ -391,7 +413,7 @@ impl<F: PrimeField> ConstraintSystem<F> {
         WitnessOverDomains {
             d4: WitnessShifts {
                 next: WitnessEvals {
-                    w: array::from_fn(|i| w4[i].shift(4)),
+                    w: array::from_fn(|i| w4[i].next_row(self.domain.d1.size())),
                     // TODO(mimoo): change z to an Option? Or maybe not, we might actually need this dummy evaluation in the aggregated evaluation proof
                     z: z4.clone(), // dummy evaluation
                 },
@@ -402,8 +424,8 @@ impl<F: PrimeField> ConstraintSystem<F> {
             },
             d8: WitnessShifts {
                 next: WitnessEvals {
-                    w: array::from_fn(|i| w8[i].shift(8)),
-                    z: z8.shift(8),
+                    w: array::from_fn(|i| w8[i].next_row(self.domain.d1.size())),
+                    z: z8.next_row(self.domain.d1.size()),
                 },
                 this: WitnessEvals { w: w8, z: z8 },
             },
@@ -628,6 +650,25 @@ impl<F: PrimeField> ConstraintSystem<F> {
             }
         };
 
+        // Cairo constraint selector polynomials
+        let cairo_selector8 = |typ| {
+            if !self.cairo {
+                None
+            } else {
+                Some(selector_polynomial(
+                    typ,
+                    &self.gates,
+                    &self.domain,
+                    &self.domain.d8,
+                    self.disable_gates_checks,
+                ))
+            }
+        };
+        let cairo_claim_selector8 = cairo_selector8(GateType::CairoClaim);
+        let cairo_instruction_selector8 = cairo_selector8(GateType::CairoInstruction);
+        let cairo_flags_selector8 = cairo_selector8(GateType::CairoFlags);
+        let cairo_transition_selector8 = cairo_selector8(GateType::CairoTransition);
+
         // TODO: This doesn't need to be degree 8 but that would require some changes in expr
         let coefficients8 = array::from_fn(|i| {
             evaluated_column_coefficients.coefficients[i]
@@ -649,10 +690,27 @@ impl<F: PrimeField> ConstraintSystem<F> {
             foreign_field_mul_selector8,
             xor_selector8,
             rot_selector8,
+            cairo_claim_selector8,
+            cairo_instruction_selector8,
+            cairo_flags_selector8,
+            cairo_transition_selector8,
         }
     }
 }
 
+impl<F: PrimeField> ColumnEvaluations<F> {
+    /// Returns the selectors of the Cairo gates, in the order claim,
+    /// instruction, flags and transition, if the Cairo gates are used
+    pub fn cairo_selectors8(&self) -> Option<[&E<F, D<F>>; 4]> {
+        Some([
+            self.cairo_claim_selector8.as_ref()?,
+            self.cairo_instruction_selector8.as_ref()?,
+            self.cairo_flags_selector8.as_ref()?,
+            self.cairo_transition_selector8.as_ref()?,
+        ])
+    }
+}
+
 /// The default number of chunks in a circuit is one (< 2^16 rows)
 pub const NUM_CHUNKS_BY_DEFAULT: usize = 1;
 
@@ -934,6 +992,16 @@ impl<F: PrimeField> Builder<F> {
 
         let domain_constant_evaluation = OnceCell::new();
 
+        let cairo = gates.iter().any(|gate| {
+            matches!(
+                gate.typ,
+                GateType::CairoClaim
+                    | GateType::CairoInstruction
+                    | GateType::CairoFlags
+                    | GateType::CairoTransition
+            )
+        });
+
         let constraints = ConstraintSystem {
             domain,
             public: self.public,
@@ -948,6 +1016,7 @@ impl<F: PrimeField> Builder<F> {
             feature_flags,
             precomputations: domain_constant_evaluation,
             disable_gates_checks: self.disable_gates_checks,
+            cairo,
         };
 
         match self.precomputations {
@@ -12,7 +12,7 @@ use crate::{
 };
 use ark_ff::{FftField, One, PrimeField, Zero};
 use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
-use o1_utils::adjacent_pairs::AdjacentPairs;
+use o1_utils::{adjacent_pairs::AdjacentPairs, batch_inverse::batch_inverse};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -267,7 +267,7 @@ where
             })
             .fold(F::one(), |acc, x| acc * x)
     }));
-    ark_ff::fields::batch_inversion::<F>(&mut lookup_aggreg[1..]);
+    batch_inverse(&mut lookup_aggreg[1..]);
 
     let max_lookups_per_row = lookup_info.max_per_row;
 
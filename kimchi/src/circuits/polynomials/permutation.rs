@@ -55,7 +55,7 @@ use ark_poly::{
     DenseUVPolynomial, EvaluationDomain, Evaluations, Polynomial, Radix2EvaluationDomain as D,
 };
 use blake2::{Blake2b512, Digest};
-use o1_utils::{ExtendedDensePolynomial, ExtendedEvaluations};
+use o1_utils::{batch_inverse::batch_inverse, ExtendedDensePolynomial, ExtendedEvaluations};
 use poly_commitment::OpenProof;
 use rand::{CryptoRng, RngCore};
 use rayon::prelude::*;
@@ -476,7 +476,7 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
                 .fold(F::one(), |x, y| x * y);
         }
 
-        ark_ff::fields::batch_inversion::<F>(&mut z[1..n]);
+        batch_inverse(&mut z[1..n]);
 
         //~ We randomize the evaluations at `n - zk_rows + 1` and `n - zk_rows + 2` in order to add
         //~ zero-knowledge to the protocol.
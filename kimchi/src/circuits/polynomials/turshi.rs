@@ -756,6 +756,7 @@ pub fn circuit_gate_combined_constraints<F: PrimeField>(
     }
 }
 
+#[derive(Default)]
 pub struct Claim<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Claim<F>
@@ -796,6 +797,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Instruction<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Instruction<F>
@@ -945,6 +947,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Flags<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Flags<F>
@@ -1015,6 +1018,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Transition<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Transition<F>
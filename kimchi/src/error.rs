@@ -112,3 +112,29 @@ pub enum VerifierIndexError {
     #[error("srs has already been set")]
     SRSHasBeenSet,
 }
+
+/// Errors that can arise when proving or verifying the execution of a Cairo
+/// program
+#[derive(Error, Debug, Clone)]
+pub enum CairoError {
+    #[error("the execution of the program failed: {0}")]
+    Execution(#[from] turshi::VmError),
+
+    #[error("the relocated execution diverges from the run at step {0}")]
+    InconsistentTrace(usize),
+
+    #[error("the circuit could not be created: {0}")]
+    Setup(#[from] SetupError),
+
+    #[error("the proof could not be created: {0}")]
+    Prover(#[from] ProverError),
+
+    #[error("the proof is invalid: {0}")]
+    Verify(#[from] VerifyError),
+
+    #[error("the public memory does not contain the program")]
+    WrongProgram,
+
+    #[error("the execution has no steps")]
+    EmptyExecution,
+}
@@ -19,7 +19,7 @@ use crate::{
             generic, permutation,
             poseidon::Poseidon,
             range_check::circuitgates::{RangeCheck0, RangeCheck1},
-            rot,
+            rot, turshi,
             varbasemul::VarbaseMul,
             xor,
         },
@@ -46,15 +46,45 @@ pub fn constraints_expr<F: PrimeField>(
 ) -> (
     Expr<ConstantExpr<F, BerkeleyChallengeTerm>, Column>,
     Alphas<F>,
+) {
+    constraints_expr_with_cairo(feature_flags, generic, false)
+}
+
+/// Get the expression of constraints, including the ones of the Cairo gates
+/// if `cairo` is set. The Cairo gates are not part of the [FeatureFlags], see
+/// [crate::circuits::constraints::ConstraintSystem::cairo].
+///
+/// # Panics
+///
+/// Will panic if `generic_gate` is not associate with `alpha^0`, or if `cairo`
+/// is set without explicit `feature_flags`.
+pub fn constraints_expr_with_cairo<F: PrimeField>(
+    feature_flags: Option<&FeatureFlags>,
+    generic: bool,
+    cairo: bool,
+) -> (
+    Expr<ConstantExpr<F, BerkeleyChallengeTerm>, Column>,
+    Alphas<F>,
 ) {
     // register powers of alpha so that we don't reuse them across mutually inclusive constraints
     let mut powers_of_alpha = Alphas::<F>::default();
 
     // Set up powers of alpha. Only the max number of constraints matters.
     // The gate type argument can just be the zero gate.
+    // The Cairo instruction gate has more constraints than the other gates.
+    // As it shifts the powers of alpha of the other arguments, the Cairo gates
+    // are only supported with explicit feature flags.
+    assert!(!cairo || feature_flags.is_some());
     powers_of_alpha.register(
         ArgumentType::Gate(GateType::Zero),
-        VarbaseMul::<F>::CONSTRAINTS,
+        if cairo {
+            std::cmp::max(
+                VarbaseMul::<F>::CONSTRAINTS,
+                turshi::Instruction::<F>::CONSTRAINTS,
+            )
+        } else {
+            VarbaseMul::<F>::CONSTRAINTS
+        },
     );
 
     let mut cache = expr::Cache::default();
@@ -161,6 +191,13 @@ pub fn constraints_expr<F: PrimeField>(
         }
     }
 
+    if cairo {
+        expr += turshi::Claim::combined_constraints(&powers_of_alpha, &mut cache);
+        expr += turshi::Instruction::combined_constraints(&powers_of_alpha, &mut cache);
+        expr += turshi::Flags::combined_constraints(&powers_of_alpha, &mut cache);
+        expr += turshi::Transition::combined_constraints(&powers_of_alpha, &mut cache);
+    }
+
     if generic {
         expr += generic::Generic::combined_constraints(&powers_of_alpha, &mut cache);
     }
@@ -228,7 +265,8 @@ pub fn constraints_expr<F: PrimeField>(
 
     // Check that the feature flags correctly turn on or off the constraints generated by the given
     // flags.
-    if cfg!(feature = "check_feature_flags") {
+    // The Cairo gates are not supported without feature flags, see above.
+    if cfg!(feature = "check_feature_flags") && !cairo {
         if let Some(feature_flags) = feature_flags {
             let (feature_flagged_expr, _) = constraints_expr(None, generic);
             let feature_flagged_expr = feature_flagged_expr.apply_feature_flags(feature_flags);
@@ -352,9 +390,35 @@ pub fn expr_linearization<F: PrimeField>(
     Linearization<Vec<PolishToken<F, Column, BerkeleyChallengeTerm>>, Column>,
     Alphas<F>,
 ) {
-    let evaluated_cols = linearization_columns::<F>(feature_flags);
+    expr_linearization_with_cairo(feature_flags, generic, false)
+}
+
+/// Linearize the `expr`, including the constraints of the Cairo gates if
+/// `cairo` is set, see [constraints_expr_with_cairo].
+///
+/// # Panics
+///
+/// Will panic if the `linearization` process fails.
+#[allow(clippy::type_complexity)]
+pub fn expr_linearization_with_cairo<F: PrimeField>(
+    feature_flags: Option<&FeatureFlags>,
+    generic: bool,
+    cairo: bool,
+) -> (
+    Linearization<Vec<PolishToken<F, Column, BerkeleyChallengeTerm>>, Column>,
+    Alphas<F>,
+) {
+    let mut evaluated_cols = linearization_columns::<F>(feature_flags);
+    if cairo {
+        evaluated_cols.extend([
+            Column::Index(GateType::CairoClaim),
+            Column::Index(GateType::CairoInstruction),
+            Column::Index(GateType::CairoFlags),
+            Column::Index(GateType::CairoTransition),
+        ]);
+    }
 
-    let (expr, powers_of_alpha) = constraints_expr(feature_flags, generic);
+    let (expr, powers_of_alpha) = constraints_expr_with_cairo(feature_flags, generic, cairo);
 
     let linearization = expr
         .linearize(evaluated_cols)
@@ -81,6 +81,10 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
             foreign_field_mul_selector,
             xor_selector,
             rot_selector,
+            cairo_claim_selector,
+            cairo_instruction_selector,
+            cairo_flags_selector,
+            cairo_transition_selector,
             lookup_aggregation,
             lookup_table,
             lookup_sorted,
@@ -125,6 +129,16 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
         if let Some(rot_selector) = rot_selector.as_ref() {
             points.push(rot_selector)
         }
+        for cairo_selector in [
+            cairo_claim_selector,
+            cairo_instruction_selector,
+            cairo_flags_selector,
+            cairo_transition_selector,
+        ] {
+            if let Some(cairo_selector) = cairo_selector.as_ref() {
+                points.push(cairo_selector)
+            }
+        }
         if let Some(lookup_aggregation) = lookup_aggregation.as_ref() {
             points.push(lookup_aggregation)
         }
@@ -108,6 +108,21 @@ pub struct ProofEvaluations<Evals> {
     /// evaluation of the ForeignFieldMul range check pattern selector
     /// polynomial
     pub foreign_field_mul_lookup_selector: Option<Evals>,
+
+    // the Cairo selectors are serialized last and only when present, so that
+    // the encoding of the proofs without Cairo gates is unchanged
+    /// evaluation of the CairoClaim selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_claim_selector: Option<Evals>,
+    /// evaluation of the CairoInstruction selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_instruction_selector: Option<Evals>,
+    /// evaluation of the CairoFlags selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_flags_selector: Option<Evals>,
+    /// evaluation of the CairoTransition selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_transition_selector: Option<Evals>,
 }
 
 /// Commitments linked to the lookup feature
@@ -221,6 +236,10 @@ impl<Eval> ProofEvaluations<Eval> {
             foreign_field_mul_selector,
             xor_selector,
             rot_selector,
+            cairo_claim_selector,
+            cairo_instruction_selector,
+            cairo_flags_selector,
+            cairo_transition_selector,
             lookup_aggregation,
             lookup_table,
             lookup_sorted,
@@ -249,6 +268,10 @@ impl<Eval> ProofEvaluations<Eval> {
             foreign_field_mul_selector: foreign_field_mul_selector.map(f),
             xor_selector: xor_selector.map(f),
             rot_selector: rot_selector.map(f),
+            cairo_claim_selector: cairo_claim_selector.map(f),
+            cairo_instruction_selector: cairo_instruction_selector.map(f),
+            cairo_flags_selector: cairo_flags_selector.map(f),
+            cairo_transition_selector: cairo_transition_selector.map(f),
             lookup_aggregation: lookup_aggregation.map(f),
             lookup_table: lookup_table.map(f),
             lookup_sorted: lookup_sorted.map(|x| x.map(f)),
@@ -280,6 +303,10 @@ impl<Eval> ProofEvaluations<Eval> {
             foreign_field_mul_selector,
             xor_selector,
             rot_selector,
+            cairo_claim_selector,
+            cairo_instruction_selector,
+            cairo_flags_selector,
+            cairo_transition_selector,
             lookup_aggregation,
             lookup_table,
             lookup_sorted,
@@ -340,6 +367,10 @@ impl<Eval> ProofEvaluations<Eval> {
             foreign_field_mul_selector: foreign_field_mul_selector.as_ref().map(f),
             xor_selector: xor_selector.as_ref().map(f),
             rot_selector: rot_selector.as_ref().map(f),
+            cairo_claim_selector: cairo_claim_selector.as_ref().map(f),
+            cairo_instruction_selector: cairo_instruction_selector.as_ref().map(f),
+            cairo_flags_selector: cairo_flags_selector.as_ref().map(f),
+            cairo_transition_selector: cairo_transition_selector.as_ref().map(f),
             lookup_aggregation: lookup_aggregation.as_ref().map(f),
             lookup_table: lookup_table.as_ref().map(f),
             lookup_sorted: array::from_fn(|i| lookup_sorted[i].as_ref().map(f)),
@@ -427,6 +458,10 @@ impl<F: Zero + Copy> ProofEvaluations<PointEvaluations<F>> {
             foreign_field_mul_selector: None,
             xor_selector: None,
             rot_selector: None,
+            cairo_claim_selector: None,
+            cairo_instruction_selector: None,
+            cairo_flags_selector: None,
+            cairo_transition_selector: None,
             lookup_aggregation: None,
             lookup_table: None,
             lookup_sorted: array::from_fn(|_| None),
@@ -481,6 +516,10 @@ impl<F> ProofEvaluations<F> {
             Column::Index(GateType::ForeignFieldMul) => self.foreign_field_mul_selector.as_ref(),
             Column::Index(GateType::Xor16) => self.xor_selector.as_ref(),
             Column::Index(GateType::Rot64) => self.rot_selector.as_ref(),
+            Column::Index(GateType::CairoClaim) => self.cairo_claim_selector.as_ref(),
+            Column::Index(GateType::CairoInstruction) => self.cairo_instruction_selector.as_ref(),
+            Column::Index(GateType::CairoFlags) => self.cairo_flags_selector.as_ref(),
+            Column::Index(GateType::CairoTransition) => self.cairo_transition_selector.as_ref(),
             Column::Index(_) => None,
             Column::Coefficient(i) => Some(&self.coefficients[i]),
             Column::Permutation(i) => Some(&self.s[i]),
@@ -961,6 +1000,11 @@ pub mod caml {
                 rot_selector: cpe
                     .rot_selector
                     .map(|x| x.map(&|x| x.into_iter().map(Into::into).collect())),
+                // the Cairo gates are not supported by the OCaml bindings
+                cairo_claim_selector: None,
+                cairo_instruction_selector: None,
+                cairo_flags_selector: None,
+                cairo_transition_selector: None,
                 lookup_aggregation: cpe
                     .lookup_aggregation
                     .map(|x| x.map(&|x| x.into_iter().map(Into::into).collect())),
@@ -18,6 +18,7 @@ use crate::{
             poseidon::Poseidon,
             range_check::circuitgates::{RangeCheck0, RangeCheck1},
             rot::Rot64,
+            turshi,
             varbasemul::VarbaseMul,
             xor::Xor16,
         },
@@ -41,7 +42,7 @@ use ark_poly::{
 };
 use itertools::Itertools;
 use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
-use o1_utils::ExtendedDensePolynomial as _;
+use o1_utils::{instrumentation::Phase, ExtendedDensePolynomial as _};
 use poly_commitment::{
     commitment::{
         absorb_commitment, b_poly_coefficients, BlindedCommitment, CommitmentCurve, PolyComm,
@@ -235,6 +236,7 @@ where
         //~ 1. Pad the witness columns with Zero gates to make them the same length as the domain.
         //~    Then, randomize the last `zk_rows` of each columns.
         internal_tracing::checkpoint!(internal_traces; pad_witness);
+        let phase = Phase::start("pad_witness");
         for w in &mut witness {
             if w.len() != length_witness {
                 return Err(ProverError::WitnessCsInconsistent);
@@ -297,6 +299,7 @@ where
         //~    Note: since the witness is in evaluation form,
         //~    we can use the `commit_evaluation` optimization.
         internal_tracing::checkpoint!(internal_traces; commit_to_witness_columns);
+        let mut phase = phase.next("commit_to_witness_columns");
         let mut w_comm = vec![];
         for col in 0..COLUMNS {
             // witness coeff -> witness eval
@@ -347,7 +350,9 @@ where
             )
             .interpolate()
         });
+        phase.add_bytes(poly_bytes(&witness_poly));
 
+        let phase = phase.next("lookup");
         let mut lookup_context = LookupContext::default();
 
         //~ 1. If using lookup:
@@ -617,6 +622,7 @@ where
 
         //~ 1. Compute the permutation aggregation polynomial $z$.
         internal_tracing::checkpoint!(internal_traces; z_permutation_aggregation_polynomial);
+        let phase = phase.next("permutation");
         let z_poly = index.perm_aggreg(&witness, &beta, &gamma, rng)?;
 
         //~ 1. Commit (hidding) to the permutation aggregation polynomial $z$.
@@ -659,6 +665,7 @@ where
         };
 
         internal_tracing::checkpoint!(internal_traces; eval_witness_polynomials_over_domains);
+        let mut phase = phase.next("quotient");
         let lagrange = index.cs.evaluate(&witness_poly, &z_poly);
         internal_tracing::checkpoint!(internal_traces; compute_index_evals);
         let env = {
@@ -714,6 +721,19 @@ where
                 index_evals.insert(GateType::Rot64, selector);
             }
 
+            if let Some(selectors) = index.column_evaluations.cairo_selectors8() {
+                index_evals.extend(
+                    [
+                        GateType::CairoClaim,
+                        GateType::CairoInstruction,
+                        GateType::CairoFlags,
+                        GateType::CairoTransition,
+                    ]
+                    .into_iter()
+                    .zip(selectors),
+                );
+            }
+
             let mds = &G::sponge_params().mds;
             Environment {
                 constants: Constants {
@@ -791,6 +811,7 @@ where
                     .is_some();
                 let xor_enabled = index.column_evaluations.xor_selector8.is_some();
                 let rot_enabled = index.column_evaluations.rot_selector8.is_some();
+                let cairo_enabled = index.column_evaluations.cairo_selectors8().is_some();
 
                 for gate in [
                     (
@@ -815,6 +836,11 @@ where
                     (&Xor16::default(), xor_enabled),
                     // Rot gate
                     (&Rot64::default(), rot_enabled),
+                    // Cairo gates
+                    (&turshi::Claim::default(), cairo_enabled),
+                    (&turshi::Instruction::default(), cairo_enabled),
+                    (&turshi::Flags::default(), cairo_enabled),
+                    (&turshi::Transition::default(), cairo_enabled),
                 ]
                 .into_iter()
                 .filter_map(|(gate, is_enabled)| if is_enabled { Some(gate) } else { None })
@@ -884,6 +910,8 @@ where
 
         //~ 1. commit (hiding) to the quotient polynomial $t$
         let t_comm = { index.srs.commit(&quotient_poly, 7 * num_chunks, rng) };
+        phase.add_bytes(poly_bytes(std::slice::from_ref(&quotient_poly)));
+        let phase = phase.next("evaluations");
 
         //~ 1. Absorb the commitment of the quotient polynomial with the Fq-Sponge.
         absorb_commitment(&mut fq_sponge, &t_comm.commitment);
@@ -1070,6 +1098,26 @@ where
                 .rot_selector8
                 .as_ref()
                 .map(chunked_evals_for_selector),
+            cairo_claim_selector: index
+                .column_evaluations
+                .cairo_claim_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
+            cairo_instruction_selector: index
+                .column_evaluations
+                .cairo_instruction_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
+            cairo_flags_selector: index
+                .column_evaluations
+                .cairo_flags_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
+            cairo_transition_selector: index
+                .column_evaluations
+                .cairo_transition_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
 
             runtime_lookup_table_selector: index.cs.lookup_constraint_system.as_ref().and_then(
                 |lcs| {
@@ -1199,6 +1247,7 @@ where
 
         //~ 1. Compute evaluations for the previous recursion challenges.
         internal_tracing::checkpoint!(internal_traces; build_polynomials);
+        let phase = phase.next("opening");
         let polys = prev_challenges
             .iter()
             .map(|RecursionChallenge { chals, comm }| {
@@ -1354,6 +1403,11 @@ where
         if let Some(rot_selector8) = index.column_evaluations.rot_selector8.as_ref() {
             polynomials.push((evaluations_form(rot_selector8), non_hiding(num_chunks)));
         }
+        if let Some(cairo_selectors8) = index.column_evaluations.cairo_selectors8() {
+            for cairo_selector8 in cairo_selectors8 {
+                polynomials.push((evaluations_form(cairo_selector8), non_hiding(num_chunks)));
+            }
+        }
 
         //~~ * optionally, the runtime table
         //~ 1. if using lookup:
@@ -1487,10 +1541,20 @@ where
         };
 
         internal_tracing::checkpoint!(internal_traces; create_recursive_done);
+        phase.finish();
         Ok(proof)
     }
 }
 
+/// Returns the size in bytes of the coefficients of `polys`, reported by the
+/// instrumentation of the prover phases
+fn poly_bytes<F: Field>(polys: &[DensePolynomial<F>]) -> u64 {
+    polys
+        .iter()
+        .map(|p| (p.coeffs.len() * std::mem::size_of::<F>()) as u64)
+        .sum()
+}
+
 internal_tracing::decl_traces!(internal_traces;
     pasta_fp_plonk_proof_create,
     pasta_fq_plonk_proof_create,
@@ -8,7 +8,7 @@ use crate::{
         expr::{Linearization, PolishToken},
     },
     curve::KimchiCurve,
-    linearization::expr_linearization,
+    linearization::expr_linearization_with_cairo,
     verifier_index::VerifierIndex,
 };
 use ark_ff::PrimeField;
@@ -71,7 +71,8 @@ where
         cs.endo = endo_q;
 
         // pre-compute the linearization
-        let (linearization, powers_of_alpha) = expr_linearization(Some(&cs.feature_flags), true);
+        let (linearization, powers_of_alpha) =
+            expr_linearization_with_cairo(Some(&cs.feature_flags), true, cs.cairo);
 
         let evaluated_column_coefficients = cs.evaluated_column_coefficients();
 
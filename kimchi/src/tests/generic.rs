@@ -121,3 +121,44 @@ fn test_generic_gate_kzg() {
     .prove_and_verify::<BaseSponge, ScalarSponge>()
     .unwrap();
 }
+
+#[test]
+fn test_generic_gate_instrumentation() {
+    use o1_utils::instrumentation::{clear_sink, set_sink, MemorySink};
+    use std::sync::Arc;
+
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    let framework = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup();
+
+    let sink = Arc::new(MemorySink::default());
+    set_sink(sink.clone());
+    let res = framework.prove_and_verify::<BaseSponge, ScalarSponge>();
+    clear_sink();
+    res.unwrap();
+
+    // the sink is global, so it may also contain the phases of other tests
+    let reports = sink.take();
+    for name in [
+        "pad_witness",
+        "commit_to_witness_columns",
+        "lookup",
+        "permutation",
+        "quotient",
+        "evaluations",
+        "opening",
+    ] {
+        assert!(
+            reports.iter().any(|r| r.name == name),
+            "missing phase {name}"
+        );
+    }
+    assert!(reports
+        .iter()
+        .any(|r| r.name == "commit_to_witness_columns" && r.bytes > 0));
+    assert!(reports.iter().any(|r| r.name == "quotient" && r.bytes > 0));
+}
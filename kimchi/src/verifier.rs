@@ -35,6 +35,15 @@ use rand::thread_rng;
 /// The result of a proof verification.
 pub type Result<T> = std::result::Result<T, VerifyError>;
 
+/// The selectors of the Cairo gates, in the order of
+/// [VerifierIndex::cairo_comm]
+const CAIRO_SELECTORS: [Column; 4] = [
+    Column::Index(GateType::CairoClaim),
+    Column::Index(GateType::CairoInstruction),
+    Column::Index(GateType::CairoFlags),
+    Column::Index(GateType::CairoTransition),
+];
+
 #[derive(Debug)]
 pub struct Context<'a, G: KimchiCurve, OpeningProof: OpenProof<G>> {
     /// The [VerifierIndex] associated to the proof
@@ -80,7 +89,10 @@ impl<'a, G: KimchiCurve, OpeningProof: OpenProof<G>> Context<'a, G, OpeningProof
                     EndoMul => Some(&self.verifier_index.emul_comm),
                     EndoMulScalar => Some(&self.verifier_index.endomul_scalar_comm),
                     Poseidon => Some(&self.verifier_index.psm_comm),
-                    CairoClaim | CairoInstruction | CairoFlags | CairoTransition => None,
+                    CairoClaim => Some(&self.verifier_index.cairo_comm.as_ref()?[0]),
+                    CairoInstruction => Some(&self.verifier_index.cairo_comm.as_ref()?[1]),
+                    CairoFlags => Some(&self.verifier_index.cairo_comm.as_ref()?[2]),
+                    CairoTransition => Some(&self.verifier_index.cairo_comm.as_ref()?[3]),
                     RangeCheck0 => Some(self.verifier_index.range_check0_comm.as_ref()?),
                     RangeCheck1 => Some(self.verifier_index.range_check1_comm.as_ref()?),
                     ForeignFieldAdd => Some(self.verifier_index.foreign_field_add_comm.as_ref()?),
@@ -531,6 +543,7 @@ where
                         .as_ref()
                         .map(|_| Column::Index(GateType::Rot64)),
                 )
+                .chain(index.cairo_comm.iter().flat_map(|_| CAIRO_SELECTORS))
                 .chain(
                     index
                         .lookup_index
@@ -646,6 +659,10 @@ where
         foreign_field_mul_selector,
         xor_selector,
         rot_selector,
+        cairo_claim_selector,
+        cairo_instruction_selector,
+        cairo_flags_selector,
+        cairo_transition_selector,
         lookup_aggregation,
         lookup_table,
         lookup_sorted,
@@ -732,6 +749,16 @@ where
     if let Some(rot_selector) = rot_selector {
         check_eval_len(rot_selector, "rot selector")?
     }
+    for (cairo_selector, name) in [
+        (cairo_claim_selector, "cairo claim selector"),
+        (cairo_instruction_selector, "cairo instruction selector"),
+        (cairo_flags_selector, "cairo flags selector"),
+        (cairo_transition_selector, "cairo transition selector"),
+    ] {
+        if let Some(cairo_selector) = cairo_selector {
+            check_eval_len(cairo_selector, name)?
+        }
+    }
 
     // Lookup selectors
 
@@ -1011,6 +1038,12 @@ where
             .as_ref()
             .map(|_| Column::Index(GateType::Rot64)),
     )
+    .chain(
+        verifier_index
+            .cairo_comm
+            .iter()
+            .flat_map(|_| CAIRO_SELECTORS),
+    )
     //~~ * lookup commitments
     //~
     .chain(
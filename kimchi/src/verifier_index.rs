@@ -150,6 +150,16 @@ pub struct VerifierIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
     /// The mapping between powers of alpha and constraints
     #[serde(skip)]
     pub powers_of_alpha: Alphas<G::ScalarField>,
+
+    /// Cairo gates commitments, in the order claim, instruction, flags and
+    /// transition. Serialized last and only when present, so that the
+    /// encoding of the indexes without Cairo gates is unchanged.
+    #[serde(
+        bound = "Option<[PolyComm<G>; 4]>: Serialize + DeserializeOwned",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cairo_comm: Option<[PolyComm<G>; 4]>,
 }
 //~spec:endcode
 
@@ -291,6 +301,9 @@ where
                 .rot_selector8
                 .as_ref()
                 .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            cairo_comm: self.column_evaluations.cairo_selectors8().map(|selectors| {
+                selectors.map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8))
+            }),
 
             shift: self.cs.shift,
             permutation_vanishing_polynomial_m: {
@@ -425,6 +438,7 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             foreign_field_mul_comm,
             xor_comm,
             rot_comm,
+            cairo_comm,
 
             // Lookup index; optional
             lookup_index,
@@ -479,6 +493,12 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             absorb_commitment(&mut fq_sponge, rot_comm);
         }
 
+        if let Some(cairo_comm) = cairo_comm {
+            for comm in cairo_comm {
+                absorb_commitment(&mut fq_sponge, comm);
+            }
+        }
+
         // Lookup index; optional
 
         if let Some(LookupVerifierIndex {
@@ -1,9 +1,27 @@
-use kimchi::circuits::{
-    gate::CircuitGate,
-    polynomials::turshi::{testing::*, witness::*},
+use groupmap::GroupMap;
+use kimchi::{
+    cairo::{prove_cairo, verify_cairo},
+    circuits::{
+        gate::CircuitGate,
+        polynomials::turshi::{testing::*, witness::*},
+    },
+    error::CairoError,
 };
-use mina_curves::pasta::Fp as F;
-use turshi::{CairoMemory, CairoProgram};
+use mina_curves::pasta::{Fp as F, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::{
+    commitment::CommitmentCurve,
+    ipa::{OpeningProof, SRS},
+    SRS as _,
+};
+use std::sync::Arc;
+use turshi::{CairoMemory, CairoProgram, MaybeRelocatable};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<F, PlonkSpongeConstantsKimchi>;
 
 #[test]
 fn test_cairo_should_fail() {
@@ -80,3 +98,77 @@ fn test_cairo_gate() {
         assert_eq!(Ok(()), res_ensure);
     }
 }
+
+#[test]
+fn test_prove_cairo() {
+    // func square(x) -> (y: felt) {
+    //     return (y=x * x);
+    // }
+    // func main(x) -> (y: felt) {
+    //     return square(x);
+    // }
+    let program: Vec<F> = [
+        0x480a7ffd7fff8000, // [ap] = [fp-3]; ap++
+        0x1104800180018000, // call rel 3
+        3,
+        0x208b7fff7fff7ffe, // ret
+        0x48527ffd7ffd8000, // [ap] = [fp-3] * [fp-3]; ap++
+        0x208b7fff7fff7ffe, // ret
+    ]
+    .iter()
+    .map(|&i: &i64| F::from(i))
+    .collect();
+    let inputs = [MaybeRelocatable::Int(F::from(7u32))];
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let srs = Arc::new(SRS::<Vesta>::create(64));
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let (statement, proof) =
+        prove_cairo::<Vesta, OpeningProof<Vesta>, BaseSponge, ScalarSponge, _>(
+            &group_map,
+            srs.clone(),
+            &program,
+            0,
+            &inputs,
+            100,
+            rng,
+        )
+        .unwrap();
+    assert_eq!(statement.steps, 5);
+    // the program and the arguments are public
+    assert_eq!(statement.public_memory[0], (1, program[0]));
+    assert_eq!(statement.public_memory[6], (7, F::from(7u32)));
+
+    verify_cairo::<Vesta, OpeningProof<Vesta>, BaseSponge, ScalarSponge>(
+        &group_map,
+        srs.clone(),
+        &program,
+        &statement,
+        &proof,
+    )
+    .unwrap();
+
+    // the proof does not hold for other final registers
+    let mut wrong = statement.clone();
+    wrong.ap_fin += F::from(1u32);
+    assert!(matches!(
+        verify_cairo::<Vesta, OpeningProof<Vesta>, BaseSponge, ScalarSponge>(
+            &group_map,
+            srs.clone(),
+            &program,
+            &wrong,
+            &proof,
+        ),
+        Err(CairoError::Verify(_))
+    ));
+
+    // nor for another program
+    let mut other = program.clone();
+    other[4] = F::from(0x48507ffd7ffd8000u64);
+    assert!(matches!(
+        verify_cairo::<Vesta, OpeningProof<Vesta>, BaseSponge, ScalarSponge>(
+            &group_map, srs, &other, &statement, &proof,
+        ),
+        Err(CairoError::WrongProgram)
+    ));
+}
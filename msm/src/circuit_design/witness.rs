@@ -9,6 +9,10 @@ use crate::{
 };
 use ark_ff::PrimeField;
 use log::debug;
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::ParallelSlice,
+};
 use std::{collections::BTreeMap, iter, marker::PhantomData};
 
 /// Witness builder environment. Operates on multiple rows at the same
@@ -474,6 +478,117 @@ impl<
         self.fixed_selectors = selectors
     }
 
+    /// Appends the rows of `other` after the rows of `self`, merging the
+    /// lookups of both environments. The two environments must do the same
+    /// number of lookups per row. The fixed selectors of `other` are
+    /// ignored.
+    pub fn append(&mut self, other: Self) {
+        self.append_rows(
+            other.witness,
+            other.lookup_multiplicities,
+            other.lookup_reads,
+            other.runtime_lookup_writes,
+        )
+    }
+
+    fn append_rows(
+        &mut self,
+        witness: Vec<Witness<N_WIT, F>>,
+        lookup_multiplicities: BTreeMap<LT, Vec<u64>>,
+        lookup_reads: BTreeMap<LT, Vec<Vec<Vec<F>>>>,
+        runtime_lookup_writes: BTreeMap<LT, Vec<Vec<Vec<F>>>>,
+    ) {
+        self.witness.extend(witness);
+        for (table_id, multiplicities) in lookup_multiplicities {
+            let self_multiplicities = self.lookup_multiplicities.get_mut(&table_id).unwrap();
+            if self_multiplicities.len() < multiplicities.len() {
+                self_multiplicities.resize(multiplicities.len(), 0u64);
+            }
+            self_multiplicities
+                .iter_mut()
+                .zip(multiplicities)
+                .for_each(|(m, other_m)| *m += other_m);
+        }
+        let append_columns = |columns: &mut BTreeMap<LT, Vec<Vec<Vec<F>>>>,
+                              other: BTreeMap<LT, Vec<Vec<Vec<F>>>>| {
+            for (table_id, other_columns) in other {
+                let columns = columns.get_mut(&table_id).unwrap();
+                if columns.len() < other_columns.len() {
+                    assert!(
+                        columns.iter().all(|column| column.is_empty()),
+                        "The number of lookups per row must be the same in both environments"
+                    );
+                    columns.resize(other_columns.len(), vec![]);
+                }
+                columns
+                    .iter_mut()
+                    .zip(other_columns)
+                    .for_each(|(column, other_column)| column.extend(other_column));
+            }
+        };
+        append_columns(&mut self.lookup_reads, lookup_reads);
+        append_columns(&mut self.runtime_lookup_writes, runtime_lookup_writes);
+    }
+
+    /// Builds the witness of independent rows in parallel, one row per
+    /// operation of `ops`. `build_row` is called on an environment whose
+    /// current row is the row of the operation, and must not read the other
+    /// rows. The fixed selectors are the ones of the whole circuit.
+    ///
+    /// The rows are split in chunks built by separate environments, merged
+    /// in order using [Self::append]. Returns the environment and the values
+    /// returned by `build_row`, in the order of the operations.
+    pub fn from_rows_par<Op: Sync, R: Send>(
+        ops: &[Op],
+        fixed_selectors: Vec<Vec<F>>,
+        build_row: impl Fn(&mut Self, &Op) -> R + Sync,
+    ) -> (Self, Vec<R>) {
+        let chunk_size = std::cmp::max(1, ops.len() / rayon::current_num_threads());
+        let chunks: Vec<_> = ops
+            .par_chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_i, chunk)| {
+                let mut env = Self::create();
+                env.set_fixed_selectors(
+                    fixed_selectors
+                        .iter()
+                        .map(|sel| sel[chunk_i * chunk_size..].to_vec())
+                        .collect(),
+                );
+                let results: Vec<R> = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(row_i, op)| {
+                        if row_i > 0 {
+                            env.next_row();
+                        }
+                        build_row(&mut env, op)
+                    })
+                    .collect();
+                (
+                    env.witness,
+                    env.lookup_multiplicities,
+                    env.lookup_reads,
+                    env.runtime_lookup_writes,
+                    results,
+                )
+            })
+            .collect();
+
+        let mut env = Self::create();
+        env.witness.clear();
+        env.set_fixed_selectors(fixed_selectors);
+        let mut results = Vec::with_capacity(ops.len());
+        for (witness, multiplicities, reads, writes, chunk_results) in chunks {
+            env.append_rows(witness, multiplicities, reads, writes);
+            results.extend(chunk_results);
+        }
+        if env.witness.is_empty() {
+            env.next_row();
+        }
+        (env, results)
+    }
+
     pub fn get_relation_witness(&self, domain_size: usize) -> Witness<N_WIT, Vec<F>> {
         // Boxing to avoid stack overflow
         let mut witness: Box<Witness<N_WIT, Vec<F>>> = Box::new(Witness {
@@ -645,7 +760,10 @@ impl<
             .iter()
             .filter_map(|(table_id, table)| {
                 // Only add a table if it's used. Otherwise lookups fail.
-                if !table.is_empty() && !table[0].is_empty() {
+                // A fixed table always contains its fixed column, so it
+                // is only used if it is read from.
+                let is_read = !table_id.is_fixed() || !self.lookup_reads[table_id].is_empty();
+                if is_read && !table.is_empty() && !table[0].is_empty() {
                     Some((
                         *table_id,
                         LogupWitness {
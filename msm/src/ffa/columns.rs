@@ -1,36 +1,67 @@
-use crate::columns::{Column, ColumnIndexer};
+use crate::{
+    circuit_design::composition::MPrism,
+    columns::{Column, ColumnIndexer},
+};
 
-use crate::N_LIMBS;
+/// Number of limbs representing one element of the foreign field in the FFA
+/// circuits. It is larger than [crate::N_LIMBS] to support the base fields of
+/// secp256k1, P-256 and BLS12-381.
+///
+/// It is the default number of limbs of the column indexers. The circuits can
+/// use another limb layout, given by the const parameters `N_LIMBS` and
+/// `LIMB_BITSIZE` of the column indexers, e.g. `FFAColumn<52, 8>`. The limbs
+/// must be at most 15 bits, as they are range-checked with the 15-bit lookup
+/// table, see [crate::ffa::interpreter::check_ffa_layout].
+pub const FFA_N_LIMBS: usize = 26;
+
+/// The FFA circuits are sound for any foreign modulus smaller than
+/// `2^FFA_MAX_MODULUS_BITSIZE`.
+pub const FFA_MAX_MODULUS_BITSIZE: usize = FFA_N_LIMBS * crate::LIMB_BITSIZE;
 
 /// Number of columns in the FFA circuits.
-pub const FFA_N_COLUMNS: usize = 5 * N_LIMBS;
-pub const FFA_NPUB_COLUMNS: usize = N_LIMBS;
+pub const FFA_N_COLUMNS: usize = ffa_n_columns(FFA_N_LIMBS);
+
+/// Number of columns in the FF addition and subtraction circuits with
+/// `n_limbs` limbs.
+pub const fn ffa_n_columns(n_limbs: usize) -> usize {
+    5 * n_limbs
+}
+
+/// Number of public columns in the FFA circuits, i.e. the limbs of the foreign
+/// modulus. The modulus is given per instance, and is the same on every row.
+/// The `ModulusF` columns are therefore the first columns of each circuit.
+pub const FFA_NPUB_COLUMNS: usize = FFA_N_LIMBS;
 
 /// Column indexer for MSM columns.
 ///
 /// They represent the equation
 ///   `InputA(i) + InputB(i) = ModulusF(i) * Quotient + Carry(i) * 2^LIMB_SIZE - Carry(i-1)`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub enum FFAColumn {
+pub enum FFAColumn<
+    const N_LIMBS: usize = FFA_N_LIMBS,
+    const LIMB_BITSIZE: usize = { crate::LIMB_BITSIZE },
+> {
+    ModulusF(usize),
     InputA(usize),
     InputB(usize),
-    ModulusF(usize),
     Remainder(usize),
     Carry(usize),
     Quotient,
 }
 
-impl ColumnIndexer for FFAColumn {
-    const N_COL: usize = FFA_N_COLUMNS;
+impl<const N_LIMBS: usize, const LIMB_BITSIZE: usize> ColumnIndexer
+    for FFAColumn<N_LIMBS, LIMB_BITSIZE>
+{
+    const N_COL: usize = ffa_n_columns(N_LIMBS);
     fn to_column(self) -> Column {
         let to_column_inner = |offset, i| {
             assert!(i < N_LIMBS);
             Column::Relation(N_LIMBS * offset + i)
         };
         match self {
-            FFAColumn::InputA(i) => to_column_inner(0, i),
-            FFAColumn::InputB(i) => to_column_inner(1, i),
-            FFAColumn::ModulusF(i) => to_column_inner(2, i),
+            FFAColumn::ModulusF(i) => to_column_inner(0, i),
+            FFAColumn::InputA(i) => to_column_inner(1, i),
+            FFAColumn::InputB(i) => to_column_inner(2, i),
             FFAColumn::Remainder(i) => to_column_inner(3, i),
             FFAColumn::Carry(i) => {
                 assert!(i < N_LIMBS - 1);
@@ -40,3 +71,254 @@ impl ColumnIndexer for FFAColumn {
         }
     }
 }
+
+/// Column indexer for the FF subtraction and negation circuits.
+///
+/// They represent the equation
+///   `InputA(i) - InputB(i) + ModulusF(i) * Quotient = Remainder(i) - Borrow(i) * 2^LIMB_SIZE + Borrow(i-1)`
+///
+/// For the negation, `InputA` is zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FFASubColumn<
+    const N_LIMBS: usize = FFA_N_LIMBS,
+    const LIMB_BITSIZE: usize = { crate::LIMB_BITSIZE },
+> {
+    ModulusF(usize),
+    InputA(usize),
+    InputB(usize),
+    Remainder(usize),
+    Borrow(usize),
+    Quotient,
+}
+
+impl<const N_LIMBS: usize, const LIMB_BITSIZE: usize> ColumnIndexer
+    for FFASubColumn<N_LIMBS, LIMB_BITSIZE>
+{
+    const N_COL: usize = ffa_n_columns(N_LIMBS);
+    fn to_column(self) -> Column {
+        let to_column_inner = |offset, i| {
+            assert!(i < N_LIMBS);
+            Column::Relation(N_LIMBS * offset + i)
+        };
+        match self {
+            FFASubColumn::ModulusF(i) => to_column_inner(0, i),
+            FFASubColumn::InputA(i) => to_column_inner(1, i),
+            FFASubColumn::InputB(i) => to_column_inner(2, i),
+            FFASubColumn::Remainder(i) => to_column_inner(3, i),
+            FFASubColumn::Borrow(i) => {
+                assert!(i < N_LIMBS - 1);
+                to_column_inner(4, i)
+            }
+            FFASubColumn::Quotient => to_column_inner(4, N_LIMBS - 1),
+        }
+    }
+}
+
+/// Number of carries of the FF multiplication, one per limb of the product
+/// except the highest one.
+pub const FFA_MUL_N_CARRIES: usize = ffa_mul_n_carries(FFA_N_LIMBS);
+
+/// Number of columns in the FF multiplication circuit.
+pub const FFA_MUL_N_COLUMNS: usize = ffa_mul_n_columns(FFA_N_LIMBS);
+
+/// Number of carries of the FF multiplication with `n_limbs` limbs.
+pub const fn ffa_mul_n_carries(n_limbs: usize) -> usize {
+    2 * n_limbs - 2
+}
+
+/// Number of columns in the FF multiplication circuit with `n_limbs` limbs.
+pub const fn ffa_mul_n_columns(n_limbs: usize) -> usize {
+    5 * n_limbs + 2 * ffa_mul_n_carries(n_limbs)
+}
+
+/// Column indexer for the FF multiplication circuit.
+///
+/// They represent the equation `InputA * InputB = Quotient * ModulusF + Remainder`,
+/// checked limb by limb on the product limbs:
+///   `Σ_{j+k=i} InputA(j) * InputB(k) - Σ_{j+k=i} Quotient(j) * ModulusF(k) - Remainder(i)
+///     = Carry(i) * 2^LIMB_SIZE - Carry(i-1)`
+///
+/// The carries are signed and larger than a limb. Each carry `c` is stored
+/// shifted by [crate::ffa::interpreter::FFA_MUL_CARRY_OFFSET] to be positive,
+/// and split into two limbs `CarryLow(i) + CarryHigh(i) * 2^LIMB_SIZE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FFAMulColumn<
+    const N_LIMBS: usize = FFA_N_LIMBS,
+    const LIMB_BITSIZE: usize = { crate::LIMB_BITSIZE },
+> {
+    ModulusF(usize),
+    InputA(usize),
+    InputB(usize),
+    Quotient(usize),
+    Remainder(usize),
+    CarryLow(usize),
+    CarryHigh(usize),
+}
+
+impl<const N_LIMBS: usize, const LIMB_BITSIZE: usize> ColumnIndexer
+    for FFAMulColumn<N_LIMBS, LIMB_BITSIZE>
+{
+    const N_COL: usize = ffa_mul_n_columns(N_LIMBS);
+    fn to_column(self) -> Column {
+        let to_column_inner = |offset, i| {
+            assert!(i < N_LIMBS);
+            Column::Relation(N_LIMBS * offset + i)
+        };
+        let to_column_carry = |offset, i| {
+            let n_carries = ffa_mul_n_carries(N_LIMBS);
+            assert!(i < n_carries);
+            Column::Relation(5 * N_LIMBS + n_carries * offset + i)
+        };
+        match self {
+            FFAMulColumn::ModulusF(i) => to_column_inner(0, i),
+            FFAMulColumn::InputA(i) => to_column_inner(1, i),
+            FFAMulColumn::InputB(i) => to_column_inner(2, i),
+            FFAMulColumn::Quotient(i) => to_column_inner(3, i),
+            FFAMulColumn::Remainder(i) => to_column_inner(4, i),
+            FFAMulColumn::CarryLow(i) => to_column_carry(0, i),
+            FFAMulColumn::CarryHigh(i) => to_column_carry(1, i),
+        }
+    }
+}
+
+/// Number of columns in the batched FFA circuit. Each row contains one FF
+/// addition, one FF subtraction and one FF multiplication, sharing the
+/// columns of the foreign modulus.
+pub const FFA_BATCH_N_COLUMNS: usize = ffa_batch_n_columns(FFA_N_LIMBS);
+
+/// Number of columns in the batched FFA circuit with `n_limbs` limbs.
+pub const fn ffa_batch_n_columns(n_limbs: usize) -> usize {
+    n_limbs + 2 * (ffa_n_columns(n_limbs) - n_limbs) + (ffa_mul_n_columns(n_limbs) - n_limbs)
+}
+
+/// Column indexer for the batched FFA circuit.
+///
+/// The modulus columns come first, as they are the public inputs, and are
+/// shared by the three operations of the row. The other columns of the
+/// operations follow, in the order addition, subtraction, multiplication.
+/// The `ModulusF` columns of the operations must be accessed using
+/// [FFABatchColumn::ModulusF], which the lenses [FFABatchAddLens],
+/// [FFABatchSubLens] and [FFABatchMulLens] take care of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FFABatchColumn<
+    const N_LIMBS: usize = FFA_N_LIMBS,
+    const LIMB_BITSIZE: usize = { crate::LIMB_BITSIZE },
+> {
+    ModulusF(usize),
+    Add(FFAColumn<N_LIMBS, LIMB_BITSIZE>),
+    Sub(FFASubColumn<N_LIMBS, LIMB_BITSIZE>),
+    Mul(FFAMulColumn<N_LIMBS, LIMB_BITSIZE>),
+}
+
+impl<const N_LIMBS: usize, const LIMB_BITSIZE: usize> ColumnIndexer
+    for FFABatchColumn<N_LIMBS, LIMB_BITSIZE>
+{
+    const N_COL: usize = ffa_batch_n_columns(N_LIMBS);
+    fn to_column(self) -> Column {
+        let shift = |column: Column, offset: usize| {
+            let Column::Relation(i) = column else {
+                panic!("The FFA circuits only use relation columns")
+            };
+            assert!(
+                i >= N_LIMBS,
+                "The modulus columns are shared, use FFABatchColumn::ModulusF"
+            );
+            Column::Relation(i + offset)
+        };
+        match self {
+            FFABatchColumn::ModulusF(i) => {
+                assert!(i < N_LIMBS);
+                Column::Relation(i)
+            }
+            FFABatchColumn::Add(col) => shift(col.to_column(), 0),
+            FFABatchColumn::Sub(col) => shift(col.to_column(), ffa_n_columns(N_LIMBS) - N_LIMBS),
+            FFABatchColumn::Mul(col) => {
+                shift(col.to_column(), 2 * (ffa_n_columns(N_LIMBS) - N_LIMBS))
+            }
+        }
+    }
+}
+
+/// Maps the columns of the FF addition of the batched FFA circuit.
+pub struct FFABatchAddLens<
+    const N_LIMBS: usize = FFA_N_LIMBS,
+    const LIMB_BITSIZE: usize = { crate::LIMB_BITSIZE },
+> {}
+
+impl<const N_LIMBS: usize, const LIMB_BITSIZE: usize> MPrism
+    for FFABatchAddLens<N_LIMBS, LIMB_BITSIZE>
+{
+    type Source = FFABatchColumn<N_LIMBS, LIMB_BITSIZE>;
+    type Target = FFAColumn<N_LIMBS, LIMB_BITSIZE>;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        match source {
+            FFABatchColumn::ModulusF(i) => Some(FFAColumn::ModulusF(i)),
+            FFABatchColumn::Add(col) => Some(col),
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        match target {
+            FFAColumn::ModulusF(i) => FFABatchColumn::ModulusF(i),
+            col => FFABatchColumn::Add(col),
+        }
+    }
+}
+
+/// Maps the columns of the FF subtraction of the batched FFA circuit.
+pub struct FFABatchSubLens<
+    const N_LIMBS: usize = FFA_N_LIMBS,
+    const LIMB_BITSIZE: usize = { crate::LIMB_BITSIZE },
+> {}
+
+impl<const N_LIMBS: usize, const LIMB_BITSIZE: usize> MPrism
+    for FFABatchSubLens<N_LIMBS, LIMB_BITSIZE>
+{
+    type Source = FFABatchColumn<N_LIMBS, LIMB_BITSIZE>;
+    type Target = FFASubColumn<N_LIMBS, LIMB_BITSIZE>;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        match source {
+            FFABatchColumn::ModulusF(i) => Some(FFASubColumn::ModulusF(i)),
+            FFABatchColumn::Sub(col) => Some(col),
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        match target {
+            FFASubColumn::ModulusF(i) => FFABatchColumn::ModulusF(i),
+            col => FFABatchColumn::Sub(col),
+        }
+    }
+}
+
+/// Maps the columns of the FF multiplication of the batched FFA circuit.
+pub struct FFABatchMulLens<
+    const N_LIMBS: usize = FFA_N_LIMBS,
+    const LIMB_BITSIZE: usize = { crate::LIMB_BITSIZE },
+> {}
+
+impl<const N_LIMBS: usize, const LIMB_BITSIZE: usize> MPrism
+    for FFABatchMulLens<N_LIMBS, LIMB_BITSIZE>
+{
+    type Source = FFABatchColumn<N_LIMBS, LIMB_BITSIZE>;
+    type Target = FFAMulColumn<N_LIMBS, LIMB_BITSIZE>;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        match source {
+            FFABatchColumn::ModulusF(i) => Some(FFAMulColumn::ModulusF(i)),
+            FFABatchColumn::Mul(col) => Some(col),
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        match target {
+            FFAMulColumn::ModulusF(i) => FFABatchColumn::ModulusF(i),
+            col => FFABatchColumn::Mul(col),
+        }
+    }
+}
@@ -1,26 +1,111 @@
 use crate::{
-    circuit_design::{ColAccessCap, ColWriteCap, LookupCap},
-    ffa::{columns::FFAColumn, lookups::LookupTable},
-    serialization::interpreter::{limb_decompose_biguint, limb_decompose_ff},
-    LIMB_BITSIZE, N_LIMBS,
+    circuit_design::{
+        composition::SubEnvColumn, ColAccessCap, ColWriteCap, LookupCap, MultiRowReadCap,
+        WitnessBuilderEnv,
+    },
+    columns::ColumnIndexer,
+    ffa::{
+        columns::{
+            ffa_mul_n_carries, FFABatchAddLens, FFABatchColumn, FFABatchMulLens, FFABatchSubLens,
+            FFAColumn, FFAMulColumn, FFASubColumn,
+        },
+        lookups::LookupTable,
+    },
+    logup::LookupTableID,
+    serialization::interpreter::{fold_choice2, limb_decompose_biguint},
+    witness::Witness,
 };
 use ark_ff::PrimeField;
-use num_bigint::BigUint;
+use num_bigint::{BigInt, BigUint, ToBigInt};
 use num_integer::Integer;
-use o1_utils::field_helpers::FieldHelpers;
+
+/// Decomposes a big integer into the limbs used by the FFA circuits.
+pub(crate) fn ffa_limbs<F: PrimeField, const N_LIMBS: usize, const LIMB_BITSIZE: usize>(
+    x: &BigUint,
+) -> [F; N_LIMBS] {
+    limb_decompose_biguint::<F, LIMB_BITSIZE, N_LIMBS>(x.clone())
+}
+
+/// Checks that the limb layout is supported by the FFA circuits. The limbs
+/// are range-checked using the 15-bit lookup table, and therefore must be at
+/// most 15 bits. The number of limbs must also be small enough for the carries
+/// of the FF multiplication to fit in two limbs, see
+/// [ffa_mul_carry_offset].
+pub fn check_ffa_layout<const N_LIMBS: usize, const LIMB_BITSIZE: usize>() {
+    assert!(
+        (1..=15).contains(&LIMB_BITSIZE),
+        "The limbs of the FFA circuits must be between 1 and 15 bits"
+    );
+    assert!(
+        N_LIMBS >= 2 && N_LIMBS < 1 << (LIMB_BITSIZE - 1),
+        "The FFA circuits do not support {N_LIMBS} limbs of {LIMB_BITSIZE} bits"
+    );
+}
+
+/// Checks that the foreign modulus is supported by the FFA circuits, and
+/// that the inputs are reduced modulo the foreign modulus.
+pub(crate) fn check_ffa_inputs<const N_LIMBS: usize, const LIMB_BITSIZE: usize>(
+    f: &BigUint,
+    inputs: &[&BigUint],
+) {
+    check_ffa_layout::<N_LIMBS, LIMB_BITSIZE>();
+    let max_modulus_bitsize = N_LIMBS * LIMB_BITSIZE;
+    assert!(
+        f.bits() as usize <= max_modulus_bitsize,
+        "The foreign modulus must be smaller than 2^{max_modulus_bitsize}"
+    );
+    assert!(
+        inputs.iter().all(|x| *x < f),
+        "The inputs must be smaller than the foreign modulus"
+    );
+}
+
+/// Range-checks a limb of `LIMB_BITSIZE` bits. Limbs smaller than the 15
+/// bits of the lookup table are also range-checked after being shifted to the
+/// top of the 15 bits.
+fn range_check_limb<
+    F: PrimeField,
+    const LIMB_BITSIZE: usize,
+    CIx: ColumnIndexer,
+    Env: LookupCap<F, CIx, LookupTable>,
+>(
+    env: &mut Env,
+    x: &Env::Variable,
+) {
+    env.lookup(LookupTable::RangeCheck15, vec![x.clone()]);
+    if LIMB_BITSIZE < 15 {
+        let shift = Env::constant(F::from(1u64 << (15 - LIMB_BITSIZE)));
+        env.lookup(LookupTable::RangeCheck15, vec![x.clone() * shift]);
+    }
+}
+
+/// Builds the public inputs of the FFA circuits for the foreign modulus `f`,
+/// i.e. the limbs of `f` on each row.
+pub fn ffa_public_inputs<F: PrimeField, const N_LIMBS: usize, const LIMB_BITSIZE: usize>(
+    f: &BigUint,
+    domain_size: usize,
+) -> Witness<N_LIMBS, Vec<F>> {
+    let f_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(f);
+    Witness {
+        cols: Box::new(std::array::from_fn(|i| vec![f_limbs[i]; domain_size])),
+    }
+}
 
 // For now this function does not /compute/ anything, although it could.
 /// Constraint for one row of FF addition:
 ///
 /// - First:        a_0 + b_0 - q * f_0 - r_0 - c_0 * 2^{15} = 0
 /// - Intermediate: a_i + b_i - q * f_i - r_i - c_i * 2^{15} + c_{i-1} = 0
-/// - Last (n=16):  a_n + b_n - q * f_n - r_n                + c_{n-1} = 0
+/// - Last (n=25):  a_n + b_n - q * f_n - r_n                + c_{n-1} = 0
 ///
 /// q, c_i ∈ {-1,0,1}
 /// a_i, b_i, f_i, r_i ∈ [0,2^15)
 pub fn constrain_ff_addition_row<
     F: PrimeField,
-    Env: ColAccessCap<F, FFAColumn> + LookupCap<F, FFAColumn, LookupTable>,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFAColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFAColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
 >(
     env: &mut Env,
     limb_num: usize,
@@ -30,10 +115,10 @@ pub fn constrain_ff_addition_row<
     let f: Env::Variable = Env::read_column(env, FFAColumn::ModulusF(limb_num));
     let r: Env::Variable = Env::read_column(env, FFAColumn::Remainder(limb_num));
     let q: Env::Variable = Env::read_column(env, FFAColumn::Quotient);
-    env.lookup(LookupTable::RangeCheck15, vec![a.clone()]);
-    env.lookup(LookupTable::RangeCheck15, vec![b.clone()]);
-    env.lookup(LookupTable::RangeCheck15, vec![f.clone()]);
-    env.lookup(LookupTable::RangeCheck15, vec![r.clone()]);
+    range_check_limb::<F, LIMB_BITSIZE, _, _>(env, &a);
+    range_check_limb::<F, LIMB_BITSIZE, _, _>(env, &b);
+    range_check_limb::<F, LIMB_BITSIZE, _, _>(env, &f);
+    range_check_limb::<F, LIMB_BITSIZE, _, _>(env, &r);
     env.lookup(LookupTable::RangeCheck1BitSigned, vec![q.clone()]);
     let constraint = if limb_num == 0 {
         let limb_size = Env::constant(From::from((1 << LIMB_BITSIZE) as u64));
@@ -57,7 +142,10 @@ pub fn constrain_ff_addition_row<
 
 pub fn constrain_ff_addition<
     F: PrimeField,
-    Env: ColAccessCap<F, FFAColumn> + LookupCap<F, FFAColumn, LookupTable>,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFAColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFAColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
 >(
     env: &mut Env,
 ) {
@@ -66,21 +154,26 @@ pub fn constrain_ff_addition<
     }
 }
 
+/// Builds the witness of the FF addition `a + b` modulo the foreign modulus
+/// `f`, and constrains it. Returns `a + b mod f`.
 pub fn ff_addition_circuit<
     F: PrimeField,
-    Ff: PrimeField,
-    Env: ColAccessCap<F, FFAColumn> + ColWriteCap<F, FFAColumn> + LookupCap<F, FFAColumn, LookupTable>,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFAColumn<N_LIMBS, LIMB_BITSIZE>>
+        + ColWriteCap<F, FFAColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFAColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
 >(
     env: &mut Env,
-    a: Ff,
-    b: Ff,
-) {
-    let f_bigint: BigUint = TryFrom::try_from(Ff::MODULUS).unwrap();
+    f: &BigUint,
+    a: &BigUint,
+    b: &BigUint,
+) -> BigUint {
+    check_ffa_inputs::<N_LIMBS, LIMB_BITSIZE>(f, &[a, b]);
 
-    let a_limbs: [F; N_LIMBS] = limb_decompose_ff::<F, Ff, LIMB_BITSIZE, N_LIMBS>(&a);
-    let b_limbs: [F; N_LIMBS] = limb_decompose_ff::<F, Ff, LIMB_BITSIZE, N_LIMBS>(&b);
-    let f_limbs: [F; N_LIMBS] =
-        limb_decompose_biguint::<F, LIMB_BITSIZE, N_LIMBS>(f_bigint.clone());
+    let a_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(a);
+    let b_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(b);
+    let f_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(f);
     a_limbs.iter().enumerate().for_each(|(i, var)| {
         env.write_column(FFAColumn::InputA(i), &Env::constant(*var));
     });
@@ -91,16 +184,11 @@ pub fn ff_addition_circuit<
         env.write_column(FFAColumn::ModulusF(i), &Env::constant(*var));
     });
 
-    let a_bigint = FieldHelpers::to_biguint(&a);
-    let b_bigint = FieldHelpers::to_biguint(&b);
-
-    // TODO FIXME this computation must be done over BigInts, not BigUInts
-    // q can be -1! But only in subtraction, so for now we don't care.
-    // for now with addition only q ∈ {0,1}
-    let (q_bigint, r_bigint) = (a_bigint + b_bigint).div_rem(&f_bigint);
-    let r_limbs: [F; N_LIMBS] = limb_decompose_biguint::<F, LIMB_BITSIZE, N_LIMBS>(r_bigint);
+    // With addition only q ∈ {0,1}
+    let (q_bigint, r_bigint) = (a + b).div_rem(f);
+    let r_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(&r_bigint);
     // We expect just one limb.
-    let q: F = limb_decompose_biguint::<F, LIMB_BITSIZE, N_LIMBS>(q_bigint)[0];
+    let q: F = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(&q_bigint)[0];
 
     env.write_column(FFAColumn::Quotient, &Env::constant(q));
     r_limbs.iter().enumerate().for_each(|(i, var)| {
@@ -133,4 +221,574 @@ pub fn ff_addition_circuit<
         }
         constrain_ff_addition_row(env, limb_i);
     }
+    r_bigint
+}
+
+/// Constraint for one row of FF subtraction:
+///
+/// - First:        a_0 - b_0 + q * f_0 - r_0 + w_0 * 2^{15} = 0
+/// - Intermediate: a_i - b_i + q * f_i - r_i + w_i * 2^{15} - w_{i-1} = 0
+/// - Last (n=25):  a_n - b_n + q * f_n - r_n                - w_{n-1} = 0
+///
+/// q ∈ {0,1}, w_i ∈ {-1,0,1}
+/// a_i, b_i, f_i, r_i ∈ [0,2^15)
+pub fn constrain_ff_subtraction_row<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+    limb_num: usize,
+) {
+    let a: Env::Variable = Env::read_column(env, FFASubColumn::InputA(limb_num));
+    let b: Env::Variable = Env::read_column(env, FFASubColumn::InputB(limb_num));
+    let f: Env::Variable = Env::read_column(env, FFASubColumn::ModulusF(limb_num));
+    let r: Env::Variable = Env::read_column(env, FFASubColumn::Remainder(limb_num));
+    let q: Env::Variable = Env::read_column(env, FFASubColumn::Quotient);
+    range_check_limb::<F, LIMB_BITSIZE, _, _>(env, &a);
+    range_check_limb::<F, LIMB_BITSIZE, _, _>(env, &b);
+    range_check_limb::<F, LIMB_BITSIZE, _, _>(env, &f);
+    range_check_limb::<F, LIMB_BITSIZE, _, _>(env, &r);
+    env.lookup(LookupTable::RangeCheck1BitSigned, vec![q.clone()]);
+    let constraint = if limb_num == 0 {
+        let limb_size = Env::constant(From::from((1 << LIMB_BITSIZE) as u64));
+        let w0: Env::Variable = Env::read_column(env, FFASubColumn::Borrow(limb_num));
+        env.lookup(LookupTable::RangeCheck1BitSigned, vec![w0.clone()]);
+        a - b + q * f - r + w0 * limb_size
+    } else if limb_num < N_LIMBS - 1 {
+        let limb_size = Env::constant(From::from((1 << LIMB_BITSIZE) as u64));
+        let w_prev: Env::Variable = Env::read_column(env, FFASubColumn::Borrow(limb_num - 1));
+        let w_cur: Env::Variable = Env::read_column(env, FFASubColumn::Borrow(limb_num));
+        env.lookup(LookupTable::RangeCheck1BitSigned, vec![w_prev.clone()]);
+        env.lookup(LookupTable::RangeCheck1BitSigned, vec![w_cur.clone()]);
+        a - b + q * f - r + w_cur * limb_size - w_prev
+    } else {
+        let w_prev: Env::Variable = Env::read_column(env, FFASubColumn::Borrow(limb_num - 1));
+        env.lookup(LookupTable::RangeCheck1BitSigned, vec![w_prev.clone()]);
+        a - b + q * f - r - w_prev
+    };
+    env.assert_zero(constraint);
+}
+
+pub fn constrain_ff_subtraction<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+) {
+    // The quotient is 1 when the modulus must be added, i.e. a < b.
+    let q: Env::Variable = Env::read_column(env, FFASubColumn::Quotient);
+    env.assert_zero(q.clone() * (q - Env::constant(F::one())));
+    for limb_i in 0..N_LIMBS {
+        constrain_ff_subtraction_row(env, limb_i);
+    }
+}
+
+/// Constraints for FF negation, i.e. the subtraction `0 - b`.
+pub fn constrain_ff_negation<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+) {
+    for limb_i in 0..N_LIMBS {
+        let a: Env::Variable = Env::read_column(env, FFASubColumn::InputA(limb_i));
+        env.assert_zero(a);
+    }
+    constrain_ff_subtraction(env);
+}
+
+/// Builds the witness of the FF subtraction `a - b`, without constraining it.
+fn ff_subtraction_witness<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>
+        + ColWriteCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>,
+>(
+    env: &mut Env,
+    f: &BigUint,
+    a: &BigUint,
+    b: &BigUint,
+) -> BigUint {
+    check_ffa_inputs::<N_LIMBS, LIMB_BITSIZE>(f, &[a, b]);
+
+    let a_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(a);
+    let b_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(b);
+    let f_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(f);
+    a_limbs.iter().enumerate().for_each(|(i, var)| {
+        env.write_column(FFASubColumn::InputA(i), &Env::constant(*var));
+    });
+    b_limbs.iter().enumerate().for_each(|(i, var)| {
+        env.write_column(FFASubColumn::InputB(i), &Env::constant(*var));
+    });
+    f_limbs.iter().enumerate().for_each(|(i, var)| {
+        env.write_column(FFASubColumn::ModulusF(i), &Env::constant(*var));
+    });
+
+    // The modulus is added when the difference is negative, q ∈ {0,1}
+    let (q, r_bigint) = if a >= b {
+        (F::zero(), a - b)
+    } else {
+        (F::one(), a + f - b)
+    };
+    let r_limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(&r_bigint);
+
+    env.write_column(FFASubColumn::Quotient, &Env::constant(q));
+    r_limbs.iter().enumerate().for_each(|(i, var)| {
+        env.write_column(FFASubColumn::Remainder(i), &Env::constant(*var));
+    });
+
+    let limb_size: F = From::from((1 << LIMB_BITSIZE) as u64);
+    let mut borrow: F = From::from(0u64);
+    for limb_i in 0..N_LIMBS {
+        let res =
+            a_limbs[limb_i] - b_limbs[limb_i] + q * f_limbs[limb_i] - r_limbs[limb_i] - borrow;
+        let newborrow: F = if res == -limb_size {
+            // Borrowing from the next limb
+            F::one()
+        } else if res == limb_size {
+            // Giving back to the next limb
+            F::zero() - F::one()
+        } else if res.is_zero() {
+            F::zero()
+        } else {
+            panic!("Computed borrow is not -1,0,1, impossible: limb number {limb_i:?}")
+        };
+        // Last borrow should be zero, otherwise we record it
+        if limb_i < N_LIMBS - 1 {
+            env.write_column(FFASubColumn::Borrow(limb_i), &Env::constant(newborrow));
+            borrow = newborrow;
+        } else {
+            assert!(newborrow.is_zero());
+        }
+    }
+    r_bigint
+}
+
+/// Builds the witness of the FF subtraction `a - b` modulo the foreign
+/// modulus `f`, and constrains it. Returns `a - b mod f`.
+pub fn ff_subtraction_circuit<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>
+        + ColWriteCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+    f: &BigUint,
+    a: &BigUint,
+    b: &BigUint,
+) -> BigUint {
+    let res = ff_subtraction_witness(env, f, a, b);
+    constrain_ff_subtraction(env);
+    res
+}
+
+/// Builds the witness of the FF negation `-b` modulo the foreign modulus `f`,
+/// and constrains it. Returns `-b mod f`.
+pub fn ff_negation_circuit<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>
+        + ColWriteCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFASubColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+    f: &BigUint,
+    b: &BigUint,
+) -> BigUint {
+    let res = ff_subtraction_witness(env, f, &BigUint::from(0u64), b);
+    constrain_ff_negation(env);
+    res
+}
+
+/// Offset added to the carries of the FF multiplication with limbs of
+/// `limb_bitsize` bits to make them positive. The carries are bounded in
+/// absolute value by `N_LIMBS * 2^{limb_bitsize}`, which is smaller than the
+/// offset for the layouts accepted by [check_ffa_layout], and the shifted
+/// carries fit in two limbs.
+pub const fn ffa_mul_carry_offset(limb_bitsize: usize) -> u64 {
+    1 << (2 * limb_bitsize - 1)
+}
+
+/// Offset added to the carries of the FF multiplication with the default limb
+/// layout. The carries are bounded in absolute value by `2^20`.
+pub const FFA_MUL_CARRY_OFFSET: u64 = ffa_mul_carry_offset(crate::LIMB_BITSIZE);
+
+/// Constraints for FF multiplication `a * b = q * f + r`, checked on the
+/// `2 * N_LIMBS - 1` limbs of the product:
+///
+/// - First:        a_0 * b_0 - q_0 * f_0 - r_0 - c_0 * 2^{L} = 0
+/// - Intermediate: Σ_{j+k=i} a_j * b_k - Σ_{j+k=i} q_j * f_k - r_i - c_i * 2^{L} + c_{i-1} = 0
+/// - Last (n=2N-2): a_{N-1} * b_{N-1} - q_{N-1} * f_{N-1} + c_{n-1} = 0
+///
+/// with `L = LIMB_BITSIZE`, `N = N_LIMBS`, `r_i = 0` for `i >= N_LIMBS`, and
+/// `c_i = cl_i + ch_i * 2^{L} - 2^{2L-1}`.
+///
+/// a_i, b_i, f_i, q_i, r_i, cl_i, ch_i ∈ [0,2^L)
+// FIXME: the remainder is not constrained to be smaller than the modulus.
+pub fn constrain_ff_multiplication<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFAMulColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFAMulColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+) {
+    let a: [_; N_LIMBS] = core::array::from_fn(|i| env.read_column(FFAMulColumn::InputA(i)));
+    let b: [_; N_LIMBS] = core::array::from_fn(|i| env.read_column(FFAMulColumn::InputB(i)));
+    let f: [_; N_LIMBS] = core::array::from_fn(|i| env.read_column(FFAMulColumn::ModulusF(i)));
+    let q: [_; N_LIMBS] = core::array::from_fn(|i| env.read_column(FFAMulColumn::Quotient(i)));
+    let r: [_; N_LIMBS] = core::array::from_fn(|i| env.read_column(FFAMulColumn::Remainder(i)));
+    let n_carries = ffa_mul_n_carries(N_LIMBS);
+    let carry_low: Vec<_> = (0..n_carries)
+        .map(|i| env.read_column(FFAMulColumn::CarryLow(i)))
+        .collect();
+    let carry_high: Vec<_> = (0..n_carries)
+        .map(|i| env.read_column(FFAMulColumn::CarryHigh(i)))
+        .collect();
+
+    a.iter()
+        .chain(b.iter())
+        .chain(f.iter())
+        .chain(q.iter())
+        .chain(r.iter())
+        .chain(carry_low.iter())
+        .chain(carry_high.iter())
+        .for_each(|x| range_check_limb::<F, LIMB_BITSIZE, _, _>(env, x));
+
+    let limb_size = Env::constant(From::from((1 << LIMB_BITSIZE) as u64));
+    let carry_offset = Env::constant(From::from(ffa_mul_carry_offset(LIMB_BITSIZE)));
+    let carries: Vec<_> = (0..n_carries)
+        .map(|i| {
+            carry_low[i].clone() + carry_high[i].clone() * limb_size.clone() - carry_offset.clone()
+        })
+        .collect();
+
+    for i in 0..2 * N_LIMBS - 1 {
+        let mut constraint = fold_choice2(N_LIMBS, i, |j, k| a[j].clone() * b[k].clone())
+            - fold_choice2(N_LIMBS, i, |j, k| q[j].clone() * f[k].clone());
+        if i < N_LIMBS {
+            constraint = constraint - r[i].clone();
+        }
+        if i > 0 {
+            constraint = constraint + carries[i - 1].clone();
+        }
+        if i < n_carries {
+            constraint = constraint - carries[i].clone() * limb_size.clone();
+        }
+        env.assert_zero(constraint);
+    }
+}
+
+/// Builds the witness of the FF multiplication `a * b` modulo the foreign
+/// modulus `f`, and constrains it. Returns `a * b mod f`.
+pub fn ff_multiplication_circuit<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFAMulColumn<N_LIMBS, LIMB_BITSIZE>>
+        + ColWriteCap<F, FFAMulColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFAMulColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+    f: &BigUint,
+    a: &BigUint,
+    b: &BigUint,
+) -> BigUint {
+    check_ffa_inputs::<N_LIMBS, LIMB_BITSIZE>(f, &[a, b]);
+    let (q_biguint, r_biguint) = (a * b).div_rem(f);
+
+    // The limbs as integers, to compute the carries
+    let to_limbs = |x: &BigUint| -> [BigInt; N_LIMBS] {
+        let mask = (BigUint::from(1u64) << LIMB_BITSIZE) - 1u64;
+        core::array::from_fn(|i| ((x >> (i * LIMB_BITSIZE)) & &mask).to_bigint().unwrap())
+    };
+    let a_limbs = to_limbs(a);
+    let b_limbs = to_limbs(b);
+    let f_limbs = to_limbs(f);
+    let q_limbs = to_limbs(&q_biguint);
+    let r_limbs = to_limbs(&r_biguint);
+
+    let write_limbs =
+        |env: &mut Env,
+         x: &BigUint,
+         column: &dyn Fn(usize) -> FFAMulColumn<N_LIMBS, LIMB_BITSIZE>| {
+            let limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(x);
+            limbs.iter().enumerate().for_each(|(i, var)| {
+                env.write_column(column(i), &Env::constant(*var));
+            });
+        };
+    write_limbs(env, f, &FFAMulColumn::ModulusF);
+    write_limbs(env, a, &FFAMulColumn::InputA);
+    write_limbs(env, b, &FFAMulColumn::InputB);
+    write_limbs(env, &q_biguint, &FFAMulColumn::Quotient);
+    write_limbs(env, &r_biguint, &FFAMulColumn::Remainder);
+
+    let limb_size: BigInt = BigInt::from(1u64) << LIMB_BITSIZE;
+    let mut carry: BigInt = BigInt::from(0u64);
+    #[allow(clippy::needless_range_loop)]
+    for i in 0..2 * N_LIMBS - 1 {
+        let mut res = fold_choice2(N_LIMBS, i, |j, k| &a_limbs[j] * &b_limbs[k])
+            - fold_choice2(N_LIMBS, i, |j, k| &q_limbs[j] * &f_limbs[k])
+            + &carry;
+        if i < N_LIMBS {
+            res -= &r_limbs[i];
+        }
+        let (newcarry, rem) = res.div_rem(&limb_size);
+        assert!(
+            rem == BigInt::from(0u64),
+            "Computed carry is not an integer: limb number {i:?}"
+        );
+        if i < ffa_mul_n_carries(N_LIMBS) {
+            let shifted_carry = (&newcarry + BigInt::from(ffa_mul_carry_offset(LIMB_BITSIZE)))
+                .to_biguint()
+                .expect("The carry is too small");
+            let [low, high]: [F; 2] = limb_decompose_biguint::<F, LIMB_BITSIZE, 2>(shifted_carry);
+            env.write_column(FFAMulColumn::CarryLow(i), &Env::constant(low));
+            env.write_column(FFAMulColumn::CarryHigh(i), &Env::constant(high));
+            carry = newcarry;
+        } else {
+            // Last carry should be zero
+            assert!(newcarry == BigInt::from(0u64));
+        }
+    }
+
+    constrain_ff_multiplication(env);
+
+    r_biguint
+}
+
+/// An operation of the batched FFA circuit, modulo the foreign modulus of the
+/// batch. The negation of `b` is the subtraction `0 - b`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FFAOperation {
+    Add(BigUint, BigUint),
+    Sub(BigUint, BigUint),
+    Mul(BigUint, BigUint),
+}
+
+/// Returns the number of rows needed by the batched FFA circuit for the given
+/// operations. Each row contains one operation of each kind.
+pub fn ffa_batch_n_rows(ops: &[FFAOperation]) -> usize {
+    let n_add = ops
+        .iter()
+        .filter(|op| matches!(op, FFAOperation::Add(..)))
+        .count();
+    let n_sub = ops
+        .iter()
+        .filter(|op| matches!(op, FFAOperation::Sub(..)))
+        .count();
+    let n_mul = ops.len() - n_add - n_sub;
+    n_add.max(n_sub).max(n_mul)
+}
+
+/// Returns the smallest domain size the given operations can be batched in.
+/// The domain must also be large enough to contain the 15-bit range-check
+/// table shared by all the operations.
+pub fn ffa_batch_domain_size(ops: &[FFAOperation]) -> usize {
+    ffa_batch_n_rows(ops)
+        .max(LookupTable::RangeCheck15.length())
+        .next_power_of_two()
+}
+
+/// Constraints of the batched FFA circuit: the constraints of the FF
+/// addition, subtraction and multiplication of the row.
+pub fn constrain_ff_batch<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+) {
+    constrain_ff_addition(&mut SubEnvColumn::new(
+        env,
+        FFABatchAddLens::<N_LIMBS, LIMB_BITSIZE> {},
+    ));
+    constrain_ff_subtraction(&mut SubEnvColumn::new(
+        env,
+        FFABatchSubLens::<N_LIMBS, LIMB_BITSIZE> {},
+    ));
+    constrain_ff_multiplication(&mut SubEnvColumn::new(
+        env,
+        FFABatchMulLens::<N_LIMBS, LIMB_BITSIZE> {},
+    ));
+}
+
+/// Builds the witness of the batched FFA circuit over `domain_size` rows,
+/// and constrains it. The operations are laid out in order, one of each kind
+/// per row, and the rows left are padded with operations on zero. All the
+/// operations share the lookup tables, and are proven together.
+///
+/// Returns the results of the operations, in the same order.
+pub fn ff_batch_circuit<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>>
+        + ColWriteCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>>
+        + MultiRowReadCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+    f: &BigUint,
+    ops: &[FFAOperation],
+    domain_size: usize,
+) -> Vec<BigUint> {
+    assert!(
+        ffa_batch_n_rows(ops) <= domain_size,
+        "Too many operations for a batch of {domain_size} rows"
+    );
+
+    let zero = BigUint::from(0u64);
+    let rows = ff_batch_rows(ops, &zero, domain_size);
+    let row_results = rows
+        .iter()
+        .enumerate()
+        .map(|(row_i, row)| {
+            if row_i > 0 {
+                env.next_row();
+            }
+            ff_batch_row(env, f, row)
+        })
+        .collect();
+    ff_batch_results(ops, row_results)
+}
+
+/// Builds the witness of the batched FFA circuit as
+/// [ff_batch_circuit], with the rows built in parallel. The rows of the
+/// batch are independent, so that the witness is the same as the one of
+/// [ff_batch_circuit].
+///
+/// Returns the witness environment and the results of the operations, in
+/// the same order.
+pub fn ff_batch_circuit_par<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    const N_WIT: usize,
+>(
+    f: &BigUint,
+    ops: impl IntoIterator<Item = FFAOperation>,
+    domain_size: usize,
+) -> (
+    WitnessBuilderEnv<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>, N_WIT, N_WIT, 0, 0, LookupTable>,
+    Vec<BigUint>,
+) {
+    let ops: Vec<FFAOperation> = ops.into_iter().collect();
+    assert!(
+        ffa_batch_n_rows(&ops) <= domain_size,
+        "Too many operations for a batch of {domain_size} rows"
+    );
+    let zero = BigUint::from(0u64);
+    let rows = ff_batch_rows(&ops, &zero, domain_size);
+    let (env, row_results) =
+        WitnessBuilderEnv::from_rows_par(&rows, vec![], |env, row| ff_batch_row(env, f, row));
+    (env, ff_batch_results(&ops, row_results))
+}
+
+/// The inputs of the rows of the batched FFA circuit: the addition, the
+/// subtraction and the multiplication of each row. The rows left are padded
+/// with operations on `zero`.
+fn ff_batch_rows<'a>(
+    ops: &'a [FFAOperation],
+    zero: &'a BigUint,
+    domain_size: usize,
+) -> Vec<[(&'a BigUint, &'a BigUint); 3]> {
+    let inputs = |kind: fn(&FFAOperation) -> Option<(&BigUint, &BigUint)>| {
+        let mut inputs: Vec<_> = ops.iter().filter_map(kind).collect();
+        inputs.resize(domain_size, (zero, zero));
+        inputs
+    };
+    let add_inputs = inputs(|op| match op {
+        FFAOperation::Add(a, b) => Some((a, b)),
+        _ => None,
+    });
+    let sub_inputs = inputs(|op| match op {
+        FFAOperation::Sub(a, b) => Some((a, b)),
+        _ => None,
+    });
+    let mul_inputs = inputs(|op| match op {
+        FFAOperation::Mul(a, b) => Some((a, b)),
+        _ => None,
+    });
+    (0..domain_size)
+        .map(|row_i| [add_inputs[row_i], sub_inputs[row_i], mul_inputs[row_i]])
+        .collect()
+}
+
+/// Builds the current row of the batched FFA circuit, and returns the
+/// results of its addition, subtraction and multiplication.
+fn ff_batch_row<
+    F: PrimeField,
+    const N_LIMBS: usize,
+    const LIMB_BITSIZE: usize,
+    Env: ColAccessCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>>
+        + ColWriteCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>>
+        + LookupCap<F, FFABatchColumn<N_LIMBS, LIMB_BITSIZE>, LookupTable>,
+>(
+    env: &mut Env,
+    f: &BigUint,
+    row: &[(&BigUint, &BigUint); 3],
+) -> [BigUint; 3] {
+    let [(add_a, add_b), (sub_a, sub_b), (mul_a, mul_b)] = *row;
+    [
+        ff_addition_circuit(
+            &mut SubEnvColumn::new(env, FFABatchAddLens::<N_LIMBS, LIMB_BITSIZE> {}),
+            f,
+            add_a,
+            add_b,
+        ),
+        ff_subtraction_circuit(
+            &mut SubEnvColumn::new(env, FFABatchSubLens::<N_LIMBS, LIMB_BITSIZE> {}),
+            f,
+            sub_a,
+            sub_b,
+        ),
+        ff_multiplication_circuit(
+            &mut SubEnvColumn::new(env, FFABatchMulLens::<N_LIMBS, LIMB_BITSIZE> {}),
+            f,
+            mul_a,
+            mul_b,
+        ),
+    ]
+}
+
+/// Returns the results of the operations from the results of the rows of
+/// the batched FFA circuit, in the order of the operations.
+fn ff_batch_results(ops: &[FFAOperation], row_results: Vec<[BigUint; 3]>) -> Vec<BigUint> {
+    let mut results: [Vec<BigUint>; 3] = Default::default();
+    for row in row_results {
+        for (kind_results, res) in results.iter_mut().zip(row) {
+            kind_results.push(res);
+        }
+    }
+    let [add_results, sub_results, mul_results] = results;
+    let (mut add_results, mut sub_results, mut mul_results) = (
+        add_results.into_iter(),
+        sub_results.into_iter(),
+        mul_results.into_iter(),
+    );
+    ops.iter()
+        .map(|op| match op {
+            FFAOperation::Add(..) => add_results.next(),
+            FFAOperation::Sub(..) => sub_results.next(),
+            FFAOperation::Mul(..) => mul_results.next(),
+        })
+        .collect::<Option<_>>()
+        .unwrap()
 }
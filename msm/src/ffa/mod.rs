@@ -9,17 +9,34 @@ mod tests {
         circuit_design::{ConstraintBuilderEnv, WitnessBuilderEnv},
         columns::ColumnIndexer,
         ffa::{
-            columns::FFAColumn,
-            interpreter::{self as ffa_interpreter},
+            columns::{
+                FFABatchColumn, FFAColumn, FFAMulColumn, FFASubColumn, FFA_NPUB_COLUMNS,
+                FFA_N_LIMBS as N_LIMBS,
+            },
+            interpreter::{self as ffa_interpreter, FFAOperation},
             lookups::LookupTable,
         },
+        folding::{
+            folding_constraints, folding_witness, prove_decider, verify_decider, FoldingInstance,
+            MSMFoldingConfig,
+        },
         logup::LookupTableID,
-        Ff1, Fp,
+        proof::ProofInputs,
+        BaseSponge, Ff1, Fp, ScalarSponge, LIMB_BITSIZE,
+    };
+    use ark_ff::PrimeField;
+    use folding::{
+        checker::{Checker, ExtendedProvider},
+        FoldingScheme,
     };
-    use ark_ff::UniformRand;
+    use kimchi::{circuits::domains::EvaluationDomains, curve::KimchiCurve};
+    use mina_poseidon::FqSponge;
+    use num_bigint::{BigUint, RandBigInt};
     use rand::{CryptoRng, RngCore};
     use std::collections::BTreeMap;
 
+    type Curve = crate::BN254G1Affine;
+
     type FFAWitnessBuilderEnv = WitnessBuilderEnv<
         Fp,
         FFAColumn,
@@ -30,23 +47,88 @@ mod tests {
         LookupTable,
     >;
 
+    type FFASubWitnessBuilderEnv = WitnessBuilderEnv<
+        Fp,
+        FFASubColumn,
+        { <FFASubColumn as ColumnIndexer>::N_COL },
+        { <FFASubColumn as ColumnIndexer>::N_COL },
+        0,
+        0,
+        LookupTable,
+    >;
+
+    type FFAMulWitnessBuilderEnv = WitnessBuilderEnv<
+        Fp,
+        FFAMulColumn,
+        { <FFAMulColumn as ColumnIndexer>::N_COL },
+        { <FFAMulColumn as ColumnIndexer>::N_COL },
+        0,
+        0,
+        LookupTable,
+    >;
+
+    type FFABatchWitnessBuilderEnv = WitnessBuilderEnv<
+        Fp,
+        FFABatchColumn,
+        { <FFABatchColumn as ColumnIndexer>::N_COL },
+        { <FFABatchColumn as ColumnIndexer>::N_COL },
+        0,
+        0,
+        LookupTable,
+    >;
+
+    /// The base field of secp256k1
+    fn secp256k1_modulus() -> BigUint {
+        BigUint::parse_bytes(
+            b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            16,
+        )
+        .unwrap()
+    }
+
+    /// The base field of P-256
+    fn p256_modulus() -> BigUint {
+        BigUint::parse_bytes(
+            b"ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+            16,
+        )
+        .unwrap()
+    }
+
+    /// The base field of BLS12-381
+    fn bls12_381_modulus() -> BigUint {
+        BigUint::parse_bytes(
+            b"1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab",
+            16,
+        )
+        .unwrap()
+    }
+
+    /// The moduli the FFA circuits are tested with.
+    fn test_moduli() -> Vec<BigUint> {
+        vec![
+            Ff1::MODULUS.into(),
+            secp256k1_modulus(),
+            p256_modulus(),
+            bls12_381_modulus(),
+        ]
+    }
+
     /// Builds the FF addition circuit with random values. The witness
     /// environment enforces the constraints internally, so it is
     /// enough to just build the circuit to ensure it is satisfied.
     fn build_ffa_circuit<RNG: RngCore + CryptoRng>(
         rng: &mut RNG,
+        f: &BigUint,
         domain_size: usize,
     ) -> FFAWitnessBuilderEnv {
         let mut witness_env = FFAWitnessBuilderEnv::create();
 
         for _row_i in 0..domain_size {
-            let a: Ff1 = <Ff1 as UniformRand>::rand(rng);
-            let b: Ff1 = <Ff1 as UniformRand>::rand(rng);
-
-            //use rand::Rng;
-            //let a: Ff1 = From::from(rng.gen_range(0..(1 << 50)));
-            //let b: Ff1 = From::from(rng.gen_range(0..(1 << 50)));
-            ffa_interpreter::ff_addition_circuit(&mut witness_env, a, b);
+            let a = rng.gen_biguint_below(f);
+            let b = rng.gen_biguint_below(f);
+            let res = ffa_interpreter::ff_addition_circuit(&mut witness_env, f, &a, &b);
+            assert_eq!(res, (a + b) % f);
             witness_env.next_row();
         }
 
@@ -57,20 +139,413 @@ mod tests {
     /// Tests if FFA circuit is valid.
     pub fn test_ffa_circuit() {
         let mut rng = o1_utils::tests::make_test_rng(None);
-        build_ffa_circuit(&mut rng, 1 << 4);
+        test_moduli().iter().for_each(|f| {
+            build_ffa_circuit(&mut rng, f, 1 << 4);
+        });
+    }
+
+    /// Builds the FF subtraction circuit with random values.
+    fn build_ffa_sub_circuit<RNG: RngCore + CryptoRng>(
+        rng: &mut RNG,
+        f: &BigUint,
+        domain_size: usize,
+    ) -> FFASubWitnessBuilderEnv {
+        let mut witness_env = FFASubWitnessBuilderEnv::create();
+
+        for _row_i in 0..domain_size {
+            let a = rng.gen_biguint_below(f);
+            let b = rng.gen_biguint_below(f);
+            let res = ffa_interpreter::ff_subtraction_circuit(&mut witness_env, f, &a, &b);
+            assert_eq!(res, (a + f - b) % f);
+            witness_env.next_row();
+        }
+
+        witness_env
     }
 
     #[test]
-    pub fn heavy_test_ffa_completeness() {
+    /// Tests if FF subtraction circuit is valid, including when the
+    /// modulus must be added.
+    pub fn test_ffa_sub_circuit() {
         let mut rng = o1_utils::tests::make_test_rng(None);
-        let domain_size = 1 << 15; // Otherwise we can't do 15-bit lookups.
+        test_moduli().iter().for_each(|f| {
+            build_ffa_sub_circuit(&mut rng, f, 1 << 4);
+
+            let mut witness_env = FFASubWitnessBuilderEnv::create();
+            let zero = BigUint::from(0u64);
+            let one = BigUint::from(1u64);
+            let minus_one = f - 1u64;
+            [
+                (zero.clone(), zero.clone()),
+                (zero.clone(), minus_one.clone()),
+                (minus_one.clone(), one.clone()),
+                (BigUint::from(1u64 << 15), one.clone()),
+            ]
+            .iter()
+            .for_each(|(a, b)| {
+                let res = ffa_interpreter::ff_subtraction_circuit(&mut witness_env, f, a, b);
+                assert_eq!(res, (a + f - b) % f);
+                witness_env.next_row();
+            });
+        });
+    }
+
+    #[test]
+    /// Tests if FF negation circuit is valid.
+    pub fn test_ffa_neg_circuit() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        test_moduli().iter().for_each(|f| {
+            let mut witness_env = FFASubWitnessBuilderEnv::create();
+            let b = rng.gen_biguint_below(f);
+            [BigUint::from(0u64), BigUint::from(1u64), b]
+                .iter()
+                .for_each(|b| {
+                    let res = ffa_interpreter::ff_negation_circuit(&mut witness_env, f, b);
+                    assert_eq!(res, (f - b) % f);
+                    witness_env.next_row();
+                });
+        });
+    }
+
+    /// Builds the FF multiplication circuit with random values, checking
+    /// the result against the multiplication modulo `f`.
+    fn build_ffa_mul_circuit<RNG: RngCore + CryptoRng>(
+        rng: &mut RNG,
+        f: &BigUint,
+        domain_size: usize,
+    ) -> FFAMulWitnessBuilderEnv {
+        let mut witness_env = FFAMulWitnessBuilderEnv::create();
+
+        for _row_i in 0..domain_size {
+            let a = rng.gen_biguint_below(f);
+            let b = rng.gen_biguint_below(f);
+            let res = ffa_interpreter::ff_multiplication_circuit(&mut witness_env, f, &a, &b);
+            assert_eq!(res, (a * b) % f);
+            witness_env.next_row();
+        }
+
+        witness_env
+    }
+
+    #[test]
+    /// Tests if FF multiplication circuit is valid.
+    pub fn test_ffa_mul_circuit() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        test_moduli().iter().for_each(|f| {
+            build_ffa_mul_circuit(&mut rng, f, 1 << 4);
+        });
+    }
+
+    #[test]
+    /// Tests the FF multiplication circuit with the inputs giving the
+    /// largest quotient and carries.
+    pub fn test_ffa_mul_circuit_edge_cases() {
+        test_moduli().iter().for_each(|f| {
+            let mut witness_env = FFAMulWitnessBuilderEnv::create();
+            let minus_one = f - 1u64;
+            [
+                (BigUint::from(0u64), minus_one.clone()),
+                (BigUint::from(1u64), minus_one.clone()),
+                (minus_one.clone(), minus_one.clone()),
+            ]
+            .iter()
+            .for_each(|(a, b)| {
+                let res = ffa_interpreter::ff_multiplication_circuit(&mut witness_env, f, a, b);
+                assert_eq!(res, (a * b) % f);
+                witness_env.next_row();
+            });
+        });
+    }
+
+    #[test]
+    /// Folds several traces of the FF multiplication circuit, and checks the
+    /// relation constraints once on the folded instance. The range checks are
+    /// not folded.
+    pub fn test_ffa_mul_folding() {
+        const N_COL: usize = <FFAMulColumn as ColumnIndexer>::N_COL;
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 4;
+        let domain = EvaluationDomains::<Fp>::create(domain_size).unwrap();
+        let srs = crate::precomputed_srs::get_bn254_srs(domain);
+        let f = secp256k1_modulus();
 
         let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
-        ffa_interpreter::constrain_ff_addition(&mut constraint_env);
-        let constraints = constraint_env.get_constraints();
+        ffa_interpreter::constrain_ff_multiplication::<_, N_LIMBS, LIMB_BITSIZE, _>(
+            &mut constraint_env,
+        );
+        let constraints = folding_constraints::<N_COL>(&constraint_env.get_relation_constraints());
+        let (scheme, final_constraint) =
+            FoldingScheme::<MSMFoldingConfig<N_COL>>::new(constraints, &srs, domain.d1, &());
+
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        let mut trace = || {
+            let witness_env = build_ffa_mul_circuit(&mut rng, &f, domain_size);
+            let witness = folding_witness(witness_env.get_relation_witness(domain_size), domain.d1);
+            let instance = FoldingInstance::from_witness(
+                &witness,
+                &mut BaseSponge::new(Curve::other_curve_sponge_params()),
+                domain.d1,
+                &srs,
+            );
+            (instance, witness)
+        };
+        let first = trace();
+        let second = trace();
+        let (mut instance, mut witness) = scheme
+            .fold_instance_witness_pair(first, second, &mut fq_sponge)
+            .unwrap()
+            .pair();
+        for _ in 0..2 {
+            (instance, witness) = scheme
+                .fold_instance_witness_pair((instance, witness), trace(), &mut fq_sponge)
+                .unwrap()
+                .pair();
+        }
+
+        ExtendedProvider::new(instance.clone(), witness.clone())
+            .check(&final_constraint, domain.d1);
+
+        // The constraints are quadratic, the decider only adds the error
+        // column to the trace.
+        assert_eq!(scheme.get_number_of_additional_columns(), 0);
+        const N_DEC: usize = N_COL + 1;
+        let proof = prove_decider::<BaseSponge, ScalarSponge, _, N_COL, N_DEC>(
+            domain,
+            &srs,
+            &final_constraint,
+            &instance,
+            witness,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(verify_decider::<BaseSponge, ScalarSponge, N_COL, N_DEC>(
+            domain,
+            &srs,
+            &final_constraint,
+            &instance,
+            &proof,
+        ));
+
+        // The proof is bound to the folded instance
+        let mut other_instance = instance;
+        other_instance.u += Fp::from(1u64);
+        assert!(!verify_decider::<BaseSponge, ScalarSponge, N_COL, N_DEC>(
+            domain,
+            &srs,
+            &final_constraint,
+            &other_instance,
+            &proof,
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    /// The modulus must be smaller than the bound the circuits are sound for.
+    pub fn test_ffa_modulus_too_large() {
+        let mut witness_env = FFAWitnessBuilderEnv::create();
+        let f = (BigUint::from(1u64) << 400) - 1u64;
+        ffa_interpreter::ff_addition_circuit(
+            &mut witness_env,
+            &f,
+            &BigUint::from(1u64),
+            &BigUint::from(1u64),
+        );
+    }
+
+    /// A limb layout different from the default one: 52 limbs of 8 bits.
+    type FFASmallLimbsWitnessBuilderEnv<C> = WitnessBuilderEnv<
+        Fp,
+        C,
+        { FFA_SMALL_LIMBS_N_COL },
+        { FFA_SMALL_LIMBS_N_COL },
+        0,
+        0,
+        LookupTable,
+    >;
+
+    /// The widest of the FFA column layouts with 52 limbs of 8 bits, the
+    /// witness environments of the three circuits share it.
+    const FFA_SMALL_LIMBS_N_COL: usize = <FFAMulColumn<52, 8> as ColumnIndexer>::N_COL;
+
+    #[test]
+    /// Tests the FFA circuits with limbs smaller than the default ones.
+    pub fn test_ffa_small_limbs_circuits() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        test_moduli().iter().for_each(|f| {
+            let mut add_env = FFASmallLimbsWitnessBuilderEnv::<FFAColumn<52, 8>>::create();
+            let mut sub_env = FFASmallLimbsWitnessBuilderEnv::<FFASubColumn<52, 8>>::create();
+            let mut mul_env = FFASmallLimbsWitnessBuilderEnv::<FFAMulColumn<52, 8>>::create();
+            let minus_one = f - 1u64;
+            let inputs: Vec<_> = (0..1 << 3)
+                .map(|_| (rng.gen_biguint_below(f), rng.gen_biguint_below(f)))
+                .chain([(minus_one.clone(), minus_one)])
+                .collect();
+            inputs.iter().for_each(|(a, b)| {
+                let res = ffa_interpreter::ff_addition_circuit(&mut add_env, f, a, b);
+                assert_eq!(res, (a + b) % f);
+                add_env.next_row();
+                let res = ffa_interpreter::ff_subtraction_circuit(&mut sub_env, f, a, b);
+                assert_eq!(res, (a + f - b) % f);
+                sub_env.next_row();
+                let res = ffa_interpreter::ff_multiplication_circuit(&mut mul_env, f, a, b);
+                assert_eq!(res, (a * b) % f);
+                mul_env.next_row();
+            });
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    /// The limbs are range-checked with the 15-bit table, and cannot be
+    /// larger.
+    pub fn test_ffa_limbs_too_large() {
+        let mut witness_env = FFASmallLimbsWitnessBuilderEnv::<FFAColumn<16, 16>>::create();
+        ffa_interpreter::ff_addition_circuit(
+            &mut witness_env,
+            &Ff1::MODULUS.into(),
+            &BigUint::from(1u64),
+            &BigUint::from(1u64),
+        );
+    }
+
+    /// Random operations for the batched FFA circuit, with more
+    /// multiplications than additions and subtractions, and a negation.
+    fn random_ffa_batch_ops<RNG: RngCore + CryptoRng>(
+        rng: &mut RNG,
+        f: &BigUint,
+        n_ops: usize,
+    ) -> Vec<FFAOperation> {
+        let mut ops: Vec<FFAOperation> = (0..n_ops)
+            .map(|i| {
+                let a = rng.gen_biguint_below(f);
+                let b = rng.gen_biguint_below(f);
+                match i % 4 {
+                    0 => FFAOperation::Add(a, b),
+                    1 => FFAOperation::Sub(a, b),
+                    _ => FFAOperation::Mul(a, b),
+                }
+            })
+            .collect();
+        ops.push(FFAOperation::Sub(
+            BigUint::from(0u64),
+            rng.gen_biguint_below(f),
+        ));
+        ops
+    }
+
+    #[test]
+    /// Tests if the batched FFA circuit is valid, and computes the results
+    /// of the operations in order.
+    pub fn test_ffa_batch_circuit() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 4;
+        test_moduli().iter().for_each(|f| {
+            let ops = random_ffa_batch_ops(&mut rng, f, 2 * domain_size);
+            assert_eq!(ffa_interpreter::ffa_batch_n_rows(&ops), domain_size);
+
+            let mut witness_env = FFABatchWitnessBuilderEnv::create();
+            let res = ffa_interpreter::ff_batch_circuit(&mut witness_env, f, &ops, domain_size);
+            assert_eq!(res.len(), ops.len());
+            ops.iter().zip(res.iter()).for_each(|(op, res)| {
+                let expected = match op {
+                    FFAOperation::Add(a, b) => (a + b) % f,
+                    FFAOperation::Sub(a, b) => (a + f - b) % f,
+                    FFAOperation::Mul(a, b) => (a * b) % f,
+                };
+                assert_eq!(*res, expected);
+            });
+        });
+    }
+
+    #[test]
+    /// The witness of the batched FFA circuit built row-parallel is the same
+    /// as the one built sequentially.
+    pub fn test_ffa_batch_circuit_par() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 6;
+        test_moduli().iter().for_each(|f| {
+            let ops = random_ffa_batch_ops(&mut rng, f, 2 * domain_size);
 
-        let witness_env = build_ffa_circuit(&mut rng, domain_size);
+            let mut witness_env = FFABatchWitnessBuilderEnv::create();
+            let res = ffa_interpreter::ff_batch_circuit(&mut witness_env, f, &ops, domain_size);
+            let (witness_env_par, res_par): (FFABatchWitnessBuilderEnv, _) =
+                ffa_interpreter::ff_batch_circuit_par(f, ops, domain_size);
 
+            assert_eq!(res_par, res);
+            assert_eq!(witness_env_par.witness, witness_env.witness);
+            assert_eq!(
+                witness_env_par.lookup_multiplicities,
+                witness_env.lookup_multiplicities
+            );
+            assert_eq!(witness_env_par.lookup_reads, witness_env.lookup_reads);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    /// The operations of a kind must fit in the rows of the batch.
+    pub fn test_ffa_batch_too_many_operations() {
+        let f = secp256k1_modulus();
+        let ops = vec![FFAOperation::Mul(BigUint::from(1u64), BigUint::from(1u64)); 5];
+        let mut witness_env = FFABatchWitnessBuilderEnv::create();
+        ffa_interpreter::ff_batch_circuit(&mut witness_env, &f, &ops, 4);
+    }
+
+    #[test]
+    /// The domain of the batch contains the range-check table, and a row for
+    /// each operation of each kind.
+    pub fn test_ffa_batch_domain_size() {
+        let one = BigUint::from(1u64);
+        assert_eq!(ffa_interpreter::ffa_batch_domain_size(&[]), 1 << 15);
+        let ops = vec![FFAOperation::Add(one.clone(), one.clone()); (1 << 15) + 1];
+        assert_eq!(ffa_interpreter::ffa_batch_domain_size(&ops), 1 << 16);
+        let ops: Vec<_> = [
+            FFAOperation::Add(one.clone(), one.clone()),
+            FFAOperation::Sub(one.clone(), one.clone()),
+            FFAOperation::Mul(one.clone(), one),
+        ]
+        .into_iter()
+        .cycle()
+        .take(3 << 15)
+        .collect();
+        assert_eq!(ffa_interpreter::ffa_batch_domain_size(&ops), 1 << 15);
+    }
+
+    #[test]
+    /// The batched circuit contains the relation constraints of the three
+    /// operations. The lookups of the operations share one logup argument.
+    pub fn test_ffa_batch_constraints() {
+        let n_constraints = |constrain: &dyn Fn(&mut ConstraintBuilderEnv<Fp, LookupTable>)| {
+            let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
+            constrain(&mut constraint_env);
+            constraint_env.get_relation_constraints().len()
+        };
+        assert_eq!(
+            n_constraints(
+                &|env| ffa_interpreter::constrain_ff_batch::<_, N_LIMBS, LIMB_BITSIZE, _>(env)
+            ),
+            n_constraints(&|env| ffa_interpreter::constrain_ff_addition::<
+                _,
+                N_LIMBS,
+                LIMB_BITSIZE,
+                _,
+            >(env))
+                + n_constraints(&|env| ffa_interpreter::constrain_ff_subtraction::<
+                    _,
+                    N_LIMBS,
+                    LIMB_BITSIZE,
+                    _,
+                >(env))
+                + n_constraints(&|env| ffa_interpreter::constrain_ff_multiplication::<
+                    _,
+                    N_LIMBS,
+                    LIMB_BITSIZE,
+                    _,
+                >(env))
+        );
+    }
+
+    fn lookup_tables_data(domain_size: usize) -> BTreeMap<LookupTable, Vec<Vec<Vec<Fp>>>> {
         // Fixed tables can be generated inside lookup_tables_data. Runtime should be generated here.
         let mut lookup_tables_data = BTreeMap::new();
         for table_id in LookupTable::all_variants().into_iter() {
@@ -83,21 +558,107 @@ mod tests {
                     .collect()],
             );
         }
-        let proof_inputs = witness_env.get_proof_inputs(domain_size, lookup_tables_data);
+        lookup_tables_data
+    }
 
-        crate::test::test_completeness_generic::<
-            { <FFAColumn as ColumnIndexer>::N_COL },
-            { <FFAColumn as ColumnIndexer>::N_COL },
+    /// Proves and verifies the given FFA circuit, with the modulus `f` as
+    /// public input.
+    fn check_ffa_completeness<const N_COL: usize, RNG: RngCore + CryptoRng>(
+        rng: &mut RNG,
+        f: &BigUint,
+        constraints: Vec<crate::expr::E<Fp>>,
+        proof_inputs: ProofInputs<N_COL, Fp, LookupTable>,
+        domain_size: usize,
+    ) {
+        crate::test::test_completeness_generic_with_public_inputs::<
+            N_COL,
+            N_COL,
             0,
             0,
+            FFA_NPUB_COLUMNS,
             LookupTable,
             _,
         >(
             constraints,
             Box::new([]),
             proof_inputs,
+            ffa_interpreter::ffa_public_inputs::<_, N_LIMBS, LIMB_BITSIZE>(f, domain_size),
             domain_size,
-            &mut rng,
+            rng,
         );
     }
+
+    #[test]
+    pub fn heavy_test_ffa_completeness() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 15; // Otherwise we can't do 15-bit lookups.
+        let f = bls12_381_modulus();
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
+        ffa_interpreter::constrain_ff_addition::<_, N_LIMBS, LIMB_BITSIZE, _>(&mut constraint_env);
+        let constraints = constraint_env.get_constraints();
+
+        let witness_env = build_ffa_circuit(&mut rng, &f, domain_size);
+        let proof_inputs =
+            witness_env.get_proof_inputs(domain_size, lookup_tables_data(domain_size));
+
+        check_ffa_completeness(&mut rng, &f, constraints, proof_inputs, domain_size);
+    }
+
+    #[test]
+    pub fn heavy_test_ffa_sub_completeness() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 15; // Otherwise we can't do 15-bit lookups.
+        let f = secp256k1_modulus();
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
+        ffa_interpreter::constrain_ff_subtraction::<_, N_LIMBS, LIMB_BITSIZE, _>(
+            &mut constraint_env,
+        );
+        let constraints = constraint_env.get_constraints();
+
+        let witness_env = build_ffa_sub_circuit(&mut rng, &f, domain_size);
+        let proof_inputs =
+            witness_env.get_proof_inputs(domain_size, lookup_tables_data(domain_size));
+
+        check_ffa_completeness(&mut rng, &f, constraints, proof_inputs, domain_size);
+    }
+
+    #[test]
+    pub fn heavy_test_ffa_mul_completeness() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 15; // Otherwise we can't do 15-bit lookups.
+        let f = p256_modulus();
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
+        ffa_interpreter::constrain_ff_multiplication::<_, N_LIMBS, LIMB_BITSIZE, _>(
+            &mut constraint_env,
+        );
+        let constraints = constraint_env.get_constraints();
+
+        let witness_env = build_ffa_mul_circuit(&mut rng, &f, domain_size);
+        let proof_inputs =
+            witness_env.get_proof_inputs(domain_size, lookup_tables_data(domain_size));
+
+        check_ffa_completeness(&mut rng, &f, constraints, proof_inputs, domain_size);
+    }
+
+    #[test]
+    pub fn heavy_test_ffa_batch_completeness() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let f = secp256k1_modulus();
+        let ops = random_ffa_batch_ops(&mut rng, &f, 1 << 10);
+        let domain_size = ffa_interpreter::ffa_batch_domain_size(&ops);
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
+        ffa_interpreter::constrain_ff_batch::<_, N_LIMBS, LIMB_BITSIZE, _>(&mut constraint_env);
+        let constraints = constraint_env.get_constraints();
+
+        let mut witness_env = FFABatchWitnessBuilderEnv::create();
+        ffa_interpreter::ff_batch_circuit(&mut witness_env, &f, &ops, domain_size);
+        let proof_inputs =
+            witness_env.get_proof_inputs(domain_size, lookup_tables_data(domain_size));
+
+        check_ffa_completeness(&mut rng, &f, constraints, proof_inputs, domain_size);
+    }
 }
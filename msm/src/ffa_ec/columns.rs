@@ -0,0 +1,532 @@
+use crate::{
+    circuit_design::composition::MPrism,
+    columns::{Column, ColumnIndexer},
+    ffa::columns::{FFAColumn, FFAMulColumn, FFASubColumn, FFA_MUL_N_CARRIES, FFA_N_LIMBS},
+};
+use strum::EnumCount;
+use strum_macros::{EnumCount as EnumCountMacro, EnumIter};
+
+/// Foreign field variables of the EC addition circuit. Each variable is an
+/// element of the foreign field, represented by [FFA_N_LIMBS] limbs.
+///
+/// The first three variables, describing the curve, are public.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumCountMacro, EnumIter)]
+pub enum FFAECVar {
+    /// The foreign modulus `f`
+    Modulus,
+    /// The coefficient `a` of the curve `y^2 = x^3 + a x + b`
+    CoeffA,
+    /// The coefficient `b` of the curve `y^2 = x^3 + a x + b`
+    CoeffB,
+    XP,
+    YP,
+    XQ,
+    YQ,
+    XR,
+    YR,
+    // Values given by the prover
+    /// The inverse of `Dx`, or zero
+    DxInv,
+    /// The inverse of `Sy`, or zero
+    SyInv,
+    /// The slope of the line through `P` and `Q`, or of the tangent at `P`
+    Lambda,
+    /// The denominator of the slope, `Dx` or `Sy`
+    Den,
+    // Results of the FF operations, see [FFAECOp]
+    Dx,
+    Dy,
+    Sy,
+    DxDxInv,
+    SySyInv,
+    XPSq,
+    UP,
+    VP,
+    WP,
+    YPSq,
+    XQSq,
+    UQ,
+    VQ,
+    WQ,
+    YQSq,
+    T1,
+    T,
+    Num,
+    LambdaSq,
+    X3a,
+    X3,
+    D3,
+    M3,
+    Y3,
+}
+
+/// Boolean flags of the EC addition circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumCountMacro, EnumIter)]
+pub enum FFAECFlag {
+    /// `P` is the point at infinity
+    InfP,
+    /// `Q` is the point at infinity
+    InfQ,
+    /// `x_P = x_Q`
+    SameX,
+    /// `y_P = -y_Q`
+    OppY,
+    /// `P = -Q`, i.e. `SameX * OppY`
+    Cancel,
+    /// None of the inputs is the point at infinity
+    Generic,
+    /// The result is computed using the slope, i.e. `Generic * (1 - Cancel)`
+    UseSlope,
+    /// `R` is the point at infinity
+    InfR,
+}
+
+/// FF additions of the EC addition circuit, named after their result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumCountMacro, EnumIter)]
+pub enum FFAECAdd {
+    /// `Sy = YQ + YP`
+    Sy,
+    /// `UP = XPSq + CoeffA`
+    UP,
+    /// `WP = VP + CoeffB`
+    WP,
+    /// `UQ = XQSq + CoeffA`
+    UQ,
+    /// `WQ = VQ + CoeffB`
+    WQ,
+    /// `T1 = XPSq + XPSq`
+    T1,
+    /// `T = T1 + UP`
+    T,
+}
+
+/// FF subtractions of the EC addition circuit, named after their result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumCountMacro, EnumIter)]
+pub enum FFAECSub {
+    /// `Dx = XQ - XP`
+    Dx,
+    /// `Dy = YQ - YP`
+    Dy,
+    /// `X3a = LambdaSq - XP`
+    X3a,
+    /// `X3 = X3a - XQ`
+    X3,
+    /// `D3 = XP - X3`
+    D3,
+    /// `Y3 = M3 - YP`
+    Y3,
+}
+
+/// FF multiplications of the EC addition circuit, named after their result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumCountMacro, EnumIter)]
+pub enum FFAECMul {
+    /// `DxDxInv = Dx * DxInv`
+    DxDxInv,
+    /// `SySyInv = Sy * SyInv`
+    SySyInv,
+    /// `XPSq = XP * XP`
+    XPSq,
+    /// `VP = XP * UP`
+    VP,
+    /// `YPSq = YP * YP`
+    YPSq,
+    /// `XQSq = XQ * XQ`
+    XQSq,
+    /// `VQ = XQ * UQ`
+    VQ,
+    /// `YQSq = YQ * YQ`
+    YQSq,
+    /// `Num = Lambda * Den`
+    Num,
+    /// `LambdaSq = Lambda * Lambda`
+    LambdaSq,
+    /// `M3 = Lambda * D3`
+    M3,
+}
+
+/// A FF operation of the EC addition circuit, whose inputs and result are
+/// variables of the circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FFAECOp {
+    Add(FFAECAdd),
+    Sub(FFAECSub),
+    Mul(FFAECMul),
+}
+
+impl FFAECOp {
+    /// Returns the two inputs of the operation.
+    pub fn inputs(self) -> (FFAECVar, FFAECVar) {
+        use FFAECVar::*;
+        match self {
+            FFAECOp::Add(op) => match op {
+                FFAECAdd::Sy => (YQ, YP),
+                FFAECAdd::UP => (XPSq, CoeffA),
+                FFAECAdd::WP => (VP, CoeffB),
+                FFAECAdd::UQ => (XQSq, CoeffA),
+                FFAECAdd::WQ => (VQ, CoeffB),
+                FFAECAdd::T1 => (XPSq, XPSq),
+                FFAECAdd::T => (T1, UP),
+            },
+            FFAECOp::Sub(op) => match op {
+                FFAECSub::Dx => (XQ, XP),
+                FFAECSub::Dy => (YQ, YP),
+                FFAECSub::X3a => (LambdaSq, XP),
+                FFAECSub::X3 => (X3a, XQ),
+                FFAECSub::D3 => (XP, X3),
+                FFAECSub::Y3 => (M3, YP),
+            },
+            FFAECOp::Mul(op) => match op {
+                FFAECMul::DxDxInv => (Dx, DxInv),
+                FFAECMul::SySyInv => (Sy, SyInv),
+                FFAECMul::XPSq => (XP, XP),
+                FFAECMul::VP => (XP, UP),
+                FFAECMul::YPSq => (YP, YP),
+                FFAECMul::XQSq => (XQ, XQ),
+                FFAECMul::VQ => (XQ, UQ),
+                FFAECMul::YQSq => (YQ, YQ),
+                FFAECMul::Num => (Lambda, Den),
+                FFAECMul::LambdaSq => (Lambda, Lambda),
+                FFAECMul::M3 => (Lambda, D3),
+            },
+        }
+    }
+
+    /// Returns the variable the result of the operation is written to.
+    pub fn output(self) -> FFAECVar {
+        use FFAECVar::*;
+        match self {
+            FFAECOp::Add(op) => match op {
+                FFAECAdd::Sy => Sy,
+                FFAECAdd::UP => UP,
+                FFAECAdd::WP => WP,
+                FFAECAdd::UQ => UQ,
+                FFAECAdd::WQ => WQ,
+                FFAECAdd::T1 => T1,
+                FFAECAdd::T => T,
+            },
+            FFAECOp::Sub(op) => match op {
+                FFAECSub::Dx => Dx,
+                FFAECSub::Dy => Dy,
+                FFAECSub::X3a => X3a,
+                FFAECSub::X3 => X3,
+                FFAECSub::D3 => D3,
+                FFAECSub::Y3 => Y3,
+            },
+            FFAECOp::Mul(op) => match op {
+                FFAECMul::DxDxInv => DxDxInv,
+                FFAECMul::SySyInv => SySyInv,
+                FFAECMul::XPSq => XPSq,
+                FFAECMul::VP => VP,
+                FFAECMul::YPSq => YPSq,
+                FFAECMul::XQSq => XQSq,
+                FFAECMul::VQ => VQ,
+                FFAECMul::YQSq => YQSq,
+                FFAECMul::Num => Num,
+                FFAECMul::LambdaSq => LambdaSq,
+                FFAECMul::M3 => M3,
+            },
+        }
+    }
+}
+
+/// Number of columns of the variables of the EC addition circuit.
+pub const FFA_EC_N_VAR_COLUMNS: usize = FFAECVar::COUNT * FFA_N_LIMBS;
+
+/// Number of columns private to a FF addition or subtraction, i.e. the
+/// carries (resp. borrows) and the quotient.
+pub const FFA_EC_N_ADD_COLUMNS: usize = FFA_N_LIMBS;
+
+/// Number of columns private to a FF multiplication, i.e. the quotient and
+/// the carries.
+pub const FFA_EC_N_MUL_COLUMNS: usize = FFA_N_LIMBS + 2 * FFA_MUL_N_CARRIES;
+
+/// Number of columns in the EC addition circuit.
+pub const FFA_EC_N_COLUMNS: usize = FFA_EC_N_VAR_COLUMNS
+    + FFAECFlag::COUNT
+    + (FFAECAdd::COUNT + FFAECSub::COUNT) * FFA_EC_N_ADD_COLUMNS
+    + FFAECMul::COUNT * FFA_EC_N_MUL_COLUMNS;
+
+/// Number of public columns in the EC addition circuit, i.e. the limbs of the
+/// foreign modulus and of the coefficients of the curve.
+pub const FFA_EC_NPUB_COLUMNS: usize = 3 * FFA_N_LIMBS;
+
+/// Column indexer for the EC addition circuit over a foreign field.
+///
+/// The circuit is a composition of FF additions, subtractions and
+/// multiplications (see [FFAECOp]), whose inputs and results are shared
+/// variables ([FFAECVar]). The modulus, inputs and remainder of each FF
+/// operation are mapped to the variables by the lenses [FFAECAddLens],
+/// [FFAECSubLens] and [FFAECMulLens], and the other columns of the operation
+/// are private. The exceptional cases are handled using the flags
+/// [FFAECFlag].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FFAECColumn {
+    Var(FFAECVar, usize),
+    Flag(FFAECFlag),
+    AddCarry(FFAECAdd, usize),
+    AddQuotient(FFAECAdd),
+    SubBorrow(FFAECSub, usize),
+    SubQuotient(FFAECSub),
+    MulQuotient(FFAECMul, usize),
+    MulCarryLow(FFAECMul, usize),
+    MulCarryHigh(FFAECMul, usize),
+}
+
+impl ColumnIndexer for FFAECColumn {
+    const N_COL: usize = FFA_EC_N_COLUMNS;
+    fn to_column(self) -> Column {
+        let flags_offset = FFA_EC_N_VAR_COLUMNS;
+        let add_offset = flags_offset + FFAECFlag::COUNT;
+        let sub_offset = add_offset + FFAECAdd::COUNT * FFA_EC_N_ADD_COLUMNS;
+        let mul_offset = sub_offset + FFAECSub::COUNT * FFA_EC_N_ADD_COLUMNS;
+        match self {
+            FFAECColumn::Var(var, i) => {
+                assert!(i < FFA_N_LIMBS);
+                Column::Relation(var as usize * FFA_N_LIMBS + i)
+            }
+            FFAECColumn::Flag(flag) => Column::Relation(flags_offset + flag as usize),
+            FFAECColumn::AddCarry(op, i) => {
+                assert!(i < FFA_N_LIMBS - 1);
+                Column::Relation(add_offset + op as usize * FFA_EC_N_ADD_COLUMNS + i)
+            }
+            FFAECColumn::AddQuotient(op) => {
+                Column::Relation(add_offset + op as usize * FFA_EC_N_ADD_COLUMNS + FFA_N_LIMBS - 1)
+            }
+            FFAECColumn::SubBorrow(op, i) => {
+                assert!(i < FFA_N_LIMBS - 1);
+                Column::Relation(sub_offset + op as usize * FFA_EC_N_ADD_COLUMNS + i)
+            }
+            FFAECColumn::SubQuotient(op) => {
+                Column::Relation(sub_offset + op as usize * FFA_EC_N_ADD_COLUMNS + FFA_N_LIMBS - 1)
+            }
+            FFAECColumn::MulQuotient(op, i) => {
+                assert!(i < FFA_N_LIMBS);
+                Column::Relation(mul_offset + op as usize * FFA_EC_N_MUL_COLUMNS + i)
+            }
+            FFAECColumn::MulCarryLow(op, i) => {
+                assert!(i < FFA_MUL_N_CARRIES);
+                Column::Relation(mul_offset + op as usize * FFA_EC_N_MUL_COLUMNS + FFA_N_LIMBS + i)
+            }
+            FFAECColumn::MulCarryHigh(op, i) => {
+                assert!(i < FFA_MUL_N_CARRIES);
+                Column::Relation(
+                    mul_offset
+                        + op as usize * FFA_EC_N_MUL_COLUMNS
+                        + FFA_N_LIMBS
+                        + FFA_MUL_N_CARRIES
+                        + i,
+                )
+            }
+        }
+    }
+}
+
+/// Maps the columns of a FF addition of the EC addition circuit.
+pub struct FFAECAddLens(pub FFAECAdd);
+
+impl MPrism for FFAECAddLens {
+    type Source = FFAECColumn;
+    type Target = FFAColumn;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        let (a, b) = FFAECOp::Add(self.0).inputs();
+        let r = FFAECOp::Add(self.0).output();
+        match source {
+            FFAECColumn::Var(FFAECVar::Modulus, i) => Some(FFAColumn::ModulusF(i)),
+            FFAECColumn::Var(var, i) if var == a => Some(FFAColumn::InputA(i)),
+            FFAECColumn::Var(var, i) if var == b => Some(FFAColumn::InputB(i)),
+            FFAECColumn::Var(var, i) if var == r => Some(FFAColumn::Remainder(i)),
+            FFAECColumn::AddCarry(op, i) if op == self.0 => Some(FFAColumn::Carry(i)),
+            FFAECColumn::AddQuotient(op) if op == self.0 => Some(FFAColumn::Quotient),
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        let (a, b) = FFAECOp::Add(self.0).inputs();
+        let r = FFAECOp::Add(self.0).output();
+        match target {
+            FFAColumn::ModulusF(i) => FFAECColumn::Var(FFAECVar::Modulus, i),
+            FFAColumn::InputA(i) => FFAECColumn::Var(a, i),
+            FFAColumn::InputB(i) => FFAECColumn::Var(b, i),
+            FFAColumn::Remainder(i) => FFAECColumn::Var(r, i),
+            FFAColumn::Carry(i) => FFAECColumn::AddCarry(self.0, i),
+            FFAColumn::Quotient => FFAECColumn::AddQuotient(self.0),
+        }
+    }
+}
+
+/// Maps the columns of a FF subtraction of the EC addition circuit.
+pub struct FFAECSubLens(pub FFAECSub);
+
+impl MPrism for FFAECSubLens {
+    type Source = FFAECColumn;
+    type Target = FFASubColumn;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        let (a, b) = FFAECOp::Sub(self.0).inputs();
+        let r = FFAECOp::Sub(self.0).output();
+        match source {
+            FFAECColumn::Var(FFAECVar::Modulus, i) => Some(FFASubColumn::ModulusF(i)),
+            FFAECColumn::Var(var, i) if var == a => Some(FFASubColumn::InputA(i)),
+            FFAECColumn::Var(var, i) if var == b => Some(FFASubColumn::InputB(i)),
+            FFAECColumn::Var(var, i) if var == r => Some(FFASubColumn::Remainder(i)),
+            FFAECColumn::SubBorrow(op, i) if op == self.0 => Some(FFASubColumn::Borrow(i)),
+            FFAECColumn::SubQuotient(op) if op == self.0 => Some(FFASubColumn::Quotient),
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        let (a, b) = FFAECOp::Sub(self.0).inputs();
+        let r = FFAECOp::Sub(self.0).output();
+        match target {
+            FFASubColumn::ModulusF(i) => FFAECColumn::Var(FFAECVar::Modulus, i),
+            FFASubColumn::InputA(i) => FFAECColumn::Var(a, i),
+            FFASubColumn::InputB(i) => FFAECColumn::Var(b, i),
+            FFASubColumn::Remainder(i) => FFAECColumn::Var(r, i),
+            FFASubColumn::Borrow(i) => FFAECColumn::SubBorrow(self.0, i),
+            FFASubColumn::Quotient => FFAECColumn::SubQuotient(self.0),
+        }
+    }
+}
+
+/// Maps the columns of a FF multiplication of the EC addition circuit.
+pub struct FFAECMulLens(pub FFAECMul);
+
+impl MPrism for FFAECMulLens {
+    type Source = FFAECColumn;
+    type Target = FFAMulColumn;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        let (a, b) = FFAECOp::Mul(self.0).inputs();
+        let r = FFAECOp::Mul(self.0).output();
+        match source {
+            FFAECColumn::Var(FFAECVar::Modulus, i) => Some(FFAMulColumn::ModulusF(i)),
+            FFAECColumn::Var(var, i) if var == a => Some(FFAMulColumn::InputA(i)),
+            FFAECColumn::Var(var, i) if var == b => Some(FFAMulColumn::InputB(i)),
+            FFAECColumn::Var(var, i) if var == r => Some(FFAMulColumn::Remainder(i)),
+            FFAECColumn::MulQuotient(op, i) if op == self.0 => Some(FFAMulColumn::Quotient(i)),
+            FFAECColumn::MulCarryLow(op, i) if op == self.0 => Some(FFAMulColumn::CarryLow(i)),
+            FFAECColumn::MulCarryHigh(op, i) if op == self.0 => Some(FFAMulColumn::CarryHigh(i)),
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        let (a, b) = FFAECOp::Mul(self.0).inputs();
+        let r = FFAECOp::Mul(self.0).output();
+        match target {
+            FFAMulColumn::ModulusF(i) => FFAECColumn::Var(FFAECVar::Modulus, i),
+            FFAMulColumn::InputA(i) => FFAECColumn::Var(a, i),
+            FFAMulColumn::InputB(i) => FFAECColumn::Var(b, i),
+            FFAMulColumn::Remainder(i) => FFAECColumn::Var(r, i),
+            FFAMulColumn::Quotient(i) => FFAECColumn::MulQuotient(self.0, i),
+            FFAMulColumn::CarryLow(i) => FFAECColumn::MulCarryLow(self.0, i),
+            FFAMulColumn::CarryHigh(i) => FFAECColumn::MulCarryHigh(self.0, i),
+        }
+    }
+}
+
+/// Number of public columns of the EC scalar multiplication circuit which
+/// are not columns of the EC addition, i.e. the result and the digits.
+pub const FFA_EC_MUL_N_EXTRA_PUB_COLUMNS: usize = 2 * FFA_N_LIMBS + 2;
+
+/// Number of public columns in the EC scalar multiplication circuit: the
+/// curve, the result `s * G` and the digits of the scalar `s`.
+pub const FFA_EC_MUL_NPUB_COLUMNS: usize = FFA_EC_NPUB_COLUMNS + FFA_EC_MUL_N_EXTRA_PUB_COLUMNS;
+
+/// Number of fixed selectors in the EC scalar multiplication circuit.
+pub const FFA_EC_MUL_N_FSEL: usize = 4;
+
+/// Number of witness columns in the EC scalar multiplication circuit.
+pub const FFA_EC_MUL_N_COLUMNS: usize = FFA_EC_N_COLUMNS + FFA_EC_MUL_N_EXTRA_PUB_COLUMNS;
+
+/// Column indexer for the EC scalar multiplication circuit over a foreign
+/// field.
+///
+/// Each row adds the point of the current window `d_i * 2^{w i} * G` to the
+/// accumulator, using the EC addition circuit `R = P + Q`. The accumulator
+/// `R` of a row is the input `P` of the next one.
+///
+/// The public columns of the EC addition, i.e. the curve, are followed by
+/// the public columns of the scalar multiplication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FFAECScalarMulColumn {
+    /// The columns of the EC addition.
+    Add(FFAECColumn),
+    /// The limbs of the x-coordinate of the result `s * G`
+    ResultX(usize),
+    /// The limbs of the y-coordinate of the result `s * G`
+    ResultY(usize),
+    /// `s * G` is the point at infinity
+    ResultInf,
+    /// The digit `d_i` of the current window.
+    Digit,
+    /// Fixed selector, the index `i` of the window, or zero on the unused
+    /// rows.
+    Step,
+    /// Fixed selector, set on the first row.
+    IsFirst,
+    /// Fixed selector, set on the rows whose accumulator is the input of
+    /// the next row, i.e. all the windows but the last one.
+    IsChained,
+    /// Fixed selector, set on the row of the last window.
+    IsLast,
+}
+
+impl ColumnIndexer for FFAECScalarMulColumn {
+    const N_COL: usize = FFA_EC_MUL_N_COLUMNS + FFA_EC_MUL_N_FSEL;
+    fn to_column(self) -> Column {
+        match self {
+            FFAECScalarMulColumn::Add(col) => {
+                let Column::Relation(i) = col.to_column() else {
+                    panic!("The EC addition only uses relation columns")
+                };
+                if i < FFA_EC_NPUB_COLUMNS {
+                    Column::Relation(i)
+                } else {
+                    Column::Relation(FFA_EC_MUL_N_EXTRA_PUB_COLUMNS + i)
+                }
+            }
+            FFAECScalarMulColumn::ResultX(i) => {
+                assert!(i < FFA_N_LIMBS);
+                Column::Relation(FFA_EC_NPUB_COLUMNS + i)
+            }
+            FFAECScalarMulColumn::ResultY(i) => {
+                assert!(i < FFA_N_LIMBS);
+                Column::Relation(FFA_EC_NPUB_COLUMNS + FFA_N_LIMBS + i)
+            }
+            FFAECScalarMulColumn::ResultInf => {
+                Column::Relation(FFA_EC_NPUB_COLUMNS + 2 * FFA_N_LIMBS)
+            }
+            FFAECScalarMulColumn::Digit => {
+                Column::Relation(FFA_EC_NPUB_COLUMNS + 2 * FFA_N_LIMBS + 1)
+            }
+            FFAECScalarMulColumn::Step => Column::FixedSelector(0),
+            FFAECScalarMulColumn::IsFirst => Column::FixedSelector(1),
+            FFAECScalarMulColumn::IsChained => Column::FixedSelector(2),
+            FFAECScalarMulColumn::IsLast => Column::FixedSelector(3),
+        }
+    }
+}
+
+/// Maps the columns of the EC addition of the EC scalar multiplication
+/// circuit.
+pub struct FFAECScalarMulLens {}
+
+impl MPrism for FFAECScalarMulLens {
+    type Source = FFAECScalarMulColumn;
+    type Target = FFAECColumn;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        match source {
+            FFAECScalarMulColumn::Add(col) => Some(col),
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        FFAECScalarMulColumn::Add(target)
+    }
+}
@@ -0,0 +1,842 @@
+use crate::{
+    circuit_design::{
+        composition::{SubEnvColumn, SubEnvLookup},
+        ColAccessCap, ColWriteCap, LookupCap, MultiRowReadCap,
+    },
+    columns::ColumnIndexer,
+    expr::{curr_cell, next_cell, E},
+    ffa::{
+        columns::FFA_N_LIMBS as N_LIMBS,
+        interpreter::{
+            check_ffa_inputs, constrain_ff_addition, constrain_ff_multiplication,
+            constrain_ff_subtraction, ff_addition_circuit, ff_multiplication_circuit,
+            ff_subtraction_circuit, ffa_limbs,
+        },
+        lookups::LookupTable,
+    },
+    ffa_ec::{
+        columns::{
+            FFAECAdd, FFAECAddLens, FFAECColumn, FFAECFlag, FFAECMul, FFAECMulLens, FFAECOp,
+            FFAECScalarMulColumn, FFAECScalarMulLens, FFAECSub, FFAECSubLens, FFAECVar,
+            FFA_EC_MUL_NPUB_COLUMNS, FFA_EC_MUL_N_FSEL, FFA_EC_NPUB_COLUMNS,
+        },
+        lookups::{FFAECLookupLens, LookupTable as FFAECLookupTable},
+    },
+    witness::Witness,
+    LIMB_BITSIZE,
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use std::collections::BTreeMap;
+
+/// The FF operations of the EC addition circuit, in the order they are
+/// computed and constrained. Each operation only depends on the variables
+/// given by the prover and on the results of the previous ones.
+pub const FFA_EC_OPS: [FFAECOp; 24] = [
+    FFAECOp::Sub(FFAECSub::Dx),
+    FFAECOp::Sub(FFAECSub::Dy),
+    FFAECOp::Add(FFAECAdd::Sy),
+    FFAECOp::Mul(FFAECMul::DxDxInv),
+    FFAECOp::Mul(FFAECMul::SySyInv),
+    // Curve equation for P
+    FFAECOp::Mul(FFAECMul::XPSq),
+    FFAECOp::Add(FFAECAdd::UP),
+    FFAECOp::Mul(FFAECMul::VP),
+    FFAECOp::Add(FFAECAdd::WP),
+    FFAECOp::Mul(FFAECMul::YPSq),
+    // Curve equation for Q
+    FFAECOp::Mul(FFAECMul::XQSq),
+    FFAECOp::Add(FFAECAdd::UQ),
+    FFAECOp::Mul(FFAECMul::VQ),
+    FFAECOp::Add(FFAECAdd::WQ),
+    FFAECOp::Mul(FFAECMul::YQSq),
+    // Numerator of the tangent slope, 3 x_P^2 + a
+    FFAECOp::Add(FFAECAdd::T1),
+    FFAECOp::Add(FFAECAdd::T),
+    // Slope and result
+    FFAECOp::Mul(FFAECMul::Num),
+    FFAECOp::Mul(FFAECMul::LambdaSq),
+    FFAECOp::Sub(FFAECSub::X3a),
+    FFAECOp::Sub(FFAECSub::X3),
+    FFAECOp::Sub(FFAECSub::D3),
+    FFAECOp::Mul(FFAECMul::M3),
+    FFAECOp::Sub(FFAECSub::Y3),
+];
+
+/// A short Weierstrass curve `y^2 = x^3 + a x + b` over the foreign field of
+/// prime modulus `f`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FFAECCurve {
+    pub modulus: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+}
+
+/// A point of a [FFAECCurve] in affine coordinates. The point at infinity
+/// has zero coordinates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FFAECPoint {
+    pub x: BigUint,
+    pub y: BigUint,
+    pub infinity: bool,
+}
+
+impl FFAECPoint {
+    pub fn new(x: BigUint, y: BigUint) -> Self {
+        FFAECPoint {
+            x,
+            y,
+            infinity: false,
+        }
+    }
+
+    pub fn infinity() -> Self {
+        FFAECPoint {
+            x: BigUint::from(0u64),
+            y: BigUint::from(0u64),
+            infinity: true,
+        }
+    }
+}
+
+impl FFAECCurve {
+    /// Returns the inverse of `x` modulo `f`, or zero if `x` is zero.
+    fn inverse(&self, x: &BigUint) -> BigUint {
+        x.modpow(&(&self.modulus - 2u64), &self.modulus)
+    }
+
+    /// Returns `x - y` modulo `f`.
+    fn sub(&self, x: &BigUint, y: &BigUint) -> BigUint {
+        (x + &self.modulus - y) % &self.modulus
+    }
+
+    /// Checks that the point is on the curve, or is the point at infinity
+    /// with zero coordinates.
+    pub fn is_on_curve(&self, p: &FFAECPoint) -> bool {
+        let f = &self.modulus;
+        if p.infinity {
+            p.x == BigUint::from(0u64) && p.y == BigUint::from(0u64)
+        } else {
+            p.x < *f
+                && p.y < *f
+                && (&p.y * &p.y) % f == (&p.x * &p.x * &p.x + &self.a * &p.x + &self.b) % f
+        }
+    }
+
+    /// Computes `P + Q` outside of the circuit.
+    pub fn add(&self, p: &FFAECPoint, q: &FFAECPoint) -> FFAECPoint {
+        let f = &self.modulus;
+        if p.infinity {
+            return q.clone();
+        }
+        if q.infinity {
+            return p.clone();
+        }
+        if p.x == q.x && (&p.y + &q.y) % f == BigUint::from(0u64) {
+            return FFAECPoint::infinity();
+        }
+        let lambda = if p.x == q.x {
+            let num = (BigUint::from(3u64) * &p.x * &p.x + &self.a) % f;
+            num * self.inverse(&((&p.y + &p.y) % f)) % f
+        } else {
+            self.sub(&q.y, &p.y) * self.inverse(&self.sub(&q.x, &p.x)) % f
+        };
+        let x = self.sub(&self.sub(&(&lambda * &lambda % f), &p.x), &q.x);
+        let y = self.sub(&(lambda * self.sub(&p.x, &x) % f), &p.y);
+        FFAECPoint::new(x, y)
+    }
+}
+
+/// Builds the public inputs of the EC addition circuit for the given curve,
+/// i.e. the limbs of the modulus and of the coefficients on each row.
+pub fn ffa_ec_public_inputs<F: PrimeField>(
+    curve: &FFAECCurve,
+    domain_size: usize,
+) -> Witness<FFA_EC_NPUB_COLUMNS, Vec<F>> {
+    let limbs: Vec<F> = [&curve.modulus, &curve.a, &curve.b]
+        .into_iter()
+        .flat_map(|x| ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(x))
+        .collect();
+    Witness {
+        cols: Box::new(std::array::from_fn(|i| vec![limbs[i]; domain_size])),
+    }
+}
+
+/// Constraints of the EC addition circuit which are not FF operations,
+/// written limb by limb on the variables:
+///
+/// - The flags `InfP`, `InfQ`, `SameX` and `OppY` are boolean, and
+///   `Cancel = SameX * OppY`,
+///   `Generic = (1 - InfP) * (1 - InfQ)`,
+///   `UseSlope = Generic * (1 - Cancel)`,
+///   `InfR = InfP * InfQ + Generic * Cancel`.
+/// - `SameX = 1` iff `Dx = 0`: `SameX * Dx = 0`, and `Dx * DxInv = 1 - SameX`.
+///   Similarly, `OppY = 1` iff `Sy = 0`.
+/// - A point at infinity has zero coordinates, and a point which is not at
+///   infinity is on the curve, i.e. `YPSq = WP` (resp. `YQSq = WQ`).
+/// - The slope satisfies `Lambda * Den = Num`, with
+///   `Den = Dx + SameX * Sy` and `Num = UseSlope * (Dy + SameX * (T - Dy))`,
+///   i.e. `(y_Q - y_P) / (x_Q - x_P)` in the generic case, and
+///   `(3 x_P^2 + a) / (2 y_P)` for the doubling. The selection is done limb
+///   by limb, as `SameX = 1` implies `Dx = 0`.
+/// - The result is `Q` if `P` is at infinity, `P` if `Q` is at infinity,
+///   the point at infinity if `P = -Q`, and `(X3, Y3)` otherwise.
+///
+/// FIXME: the remainders of the FF operations are not constrained to be
+/// smaller than the modulus, and the zero-tests and on-curve checks assume
+/// reduced values.
+pub fn constrain_ec_addition_native<F: PrimeField, Env: ColAccessCap<F, FFAECColumn>>(
+    env: &mut Env,
+) {
+    let one = Env::constant(F::one());
+    let flag = |env: &Env, flag| env.read_column(FFAECColumn::Flag(flag));
+    let inf_p = flag(env, FFAECFlag::InfP);
+    let inf_q = flag(env, FFAECFlag::InfQ);
+    let same_x = flag(env, FFAECFlag::SameX);
+    let opp_y = flag(env, FFAECFlag::OppY);
+    let cancel = flag(env, FFAECFlag::Cancel);
+    let generic = flag(env, FFAECFlag::Generic);
+    let use_slope = flag(env, FFAECFlag::UseSlope);
+    let inf_r = flag(env, FFAECFlag::InfR);
+
+    for x in [&inf_p, &inf_q, &same_x, &opp_y] {
+        env.assert_zero(x.clone() * (x.clone() - one.clone()));
+    }
+    env.assert_zero(cancel.clone() - same_x.clone() * opp_y.clone());
+    env.assert_zero(
+        generic.clone() - (one.clone() - inf_p.clone()) * (one.clone() - inf_q.clone()),
+    );
+    env.assert_zero(use_slope.clone() - generic.clone() * (one.clone() - cancel.clone()));
+    env.assert_zero(inf_r - (inf_p.clone() * inf_q.clone() + generic * cancel));
+
+    for i in 0..N_LIMBS {
+        let var = |env: &Env, var| env.read_column(FFAECColumn::Var(var, i));
+        // Zero-tests of Dx and Sy
+        let not_same_x = if i == 0 {
+            one.clone() - same_x.clone()
+        } else {
+            Env::constant(F::zero())
+        };
+        let not_opp_y = if i == 0 {
+            one.clone() - opp_y.clone()
+        } else {
+            Env::constant(F::zero())
+        };
+        let dx = var(env, FFAECVar::Dx);
+        let sy = var(env, FFAECVar::Sy);
+        env.assert_zero(var(env, FFAECVar::DxDxInv) - not_same_x);
+        env.assert_zero(same_x.clone() * dx.clone());
+        env.assert_zero(var(env, FFAECVar::SySyInv) - not_opp_y);
+        env.assert_zero(opp_y.clone() * sy.clone());
+
+        // Points at infinity and on-curve checks
+        let xp = var(env, FFAECVar::XP);
+        let yp = var(env, FFAECVar::YP);
+        let xq = var(env, FFAECVar::XQ);
+        let yq = var(env, FFAECVar::YQ);
+        env.assert_zero(inf_p.clone() * xp.clone());
+        env.assert_zero(inf_p.clone() * yp.clone());
+        env.assert_zero(inf_q.clone() * xq.clone());
+        env.assert_zero(inf_q.clone() * yq.clone());
+        env.assert_zero(
+            (one.clone() - inf_p.clone()) * (var(env, FFAECVar::YPSq) - var(env, FFAECVar::WP)),
+        );
+        env.assert_zero(
+            (one.clone() - inf_q.clone()) * (var(env, FFAECVar::YQSq) - var(env, FFAECVar::WQ)),
+        );
+
+        // Slope
+        let dy = var(env, FFAECVar::Dy);
+        let t = var(env, FFAECVar::T);
+        env.assert_zero(var(env, FFAECVar::Den) - (dx + same_x.clone() * sy));
+        env.assert_zero(
+            var(env, FFAECVar::Num) - use_slope.clone() * (dy.clone() + same_x.clone() * (t - dy)),
+        );
+
+        // Result
+        let not_inf_p_inf_q = (one.clone() - inf_p.clone()) * inf_q.clone();
+        env.assert_zero(
+            var(env, FFAECVar::XR)
+                - (inf_p.clone() * xq
+                    + not_inf_p_inf_q.clone() * xp
+                    + use_slope.clone() * var(env, FFAECVar::X3)),
+        );
+        env.assert_zero(
+            var(env, FFAECVar::YR)
+                - (inf_p.clone() * yq
+                    + not_inf_p_inf_q * yp
+                    + use_slope.clone() * var(env, FFAECVar::Y3)),
+        );
+    }
+}
+
+/// Constraints of the EC addition circuit: the FF operations of
+/// [FFA_EC_OPS], followed by [constrain_ec_addition_native].
+pub fn constrain_ec_addition<
+    F: PrimeField,
+    Env: ColAccessCap<F, FFAECColumn> + LookupCap<F, FFAECColumn, LookupTable>,
+>(
+    env: &mut Env,
+) {
+    for op in FFA_EC_OPS {
+        match op {
+            FFAECOp::Add(op) => {
+                constrain_ff_addition(&mut SubEnvColumn::new(env, FFAECAddLens(op)))
+            }
+            FFAECOp::Sub(op) => {
+                constrain_ff_subtraction(&mut SubEnvColumn::new(env, FFAECSubLens(op)))
+            }
+            FFAECOp::Mul(op) => {
+                constrain_ff_multiplication(&mut SubEnvColumn::new(env, FFAECMulLens(op)))
+            }
+        }
+    }
+    constrain_ec_addition_native(env);
+}
+
+/// Builds the witness of the EC addition `P + Q` on the given curve, and
+/// constrains it. Returns `P + Q`.
+///
+/// The modulus of the curve must be a prime supported by the FFA circuits,
+/// and the points must be on the curve.
+pub fn ec_addition_circuit<
+    F: PrimeField,
+    Env: ColAccessCap<F, FFAECColumn>
+        + ColWriteCap<F, FFAECColumn>
+        + LookupCap<F, FFAECColumn, LookupTable>,
+>(
+    env: &mut Env,
+    curve: &FFAECCurve,
+    p: &FFAECPoint,
+    q: &FFAECPoint,
+) -> FFAECPoint {
+    let f = &curve.modulus;
+    check_ffa_inputs::<N_LIMBS, LIMB_BITSIZE>(f, &[&curve.a, &curve.b]);
+    assert!(
+        curve.is_on_curve(p) && curve.is_on_curve(q),
+        "The points must be on the curve"
+    );
+    let zero = BigUint::from(0u64);
+
+    // Flags
+    let same_x = p.x == q.x;
+    let opp_y = (&p.y + &q.y) % f == zero;
+    let cancel = same_x && opp_y;
+    let generic = !p.infinity && !q.infinity;
+    let use_slope = generic && !cancel;
+    let inf_r = (p.infinity && q.infinity) || (generic && cancel);
+    [
+        (FFAECFlag::InfP, p.infinity),
+        (FFAECFlag::InfQ, q.infinity),
+        (FFAECFlag::SameX, same_x),
+        (FFAECFlag::OppY, opp_y),
+        (FFAECFlag::Cancel, cancel),
+        (FFAECFlag::Generic, generic),
+        (FFAECFlag::UseSlope, use_slope),
+        (FFAECFlag::InfR, inf_r),
+    ]
+    .into_iter()
+    .for_each(|(flag, value)| {
+        env.write_column(FFAECColumn::Flag(flag), &Env::constant(F::from(value)));
+    });
+
+    // Values given by the prover
+    let dx = curve.sub(&q.x, &p.x);
+    let sy = (&q.y + &p.y) % f;
+    let den = if same_x { sy.clone() } else { dx.clone() };
+    let lambda = if use_slope {
+        let num = if same_x {
+            (BigUint::from(3u64) * &p.x * &p.x + &curve.a) % f
+        } else {
+            curve.sub(&q.y, &p.y)
+        };
+        num * curve.inverse(&den) % f
+    } else {
+        zero.clone()
+    };
+    let mut values: BTreeMap<FFAECVar, BigUint> = BTreeMap::from([
+        (FFAECVar::Modulus, f.clone()),
+        (FFAECVar::CoeffA, curve.a.clone()),
+        (FFAECVar::CoeffB, curve.b.clone()),
+        (FFAECVar::XP, p.x.clone()),
+        (FFAECVar::YP, p.y.clone()),
+        (FFAECVar::XQ, q.x.clone()),
+        (FFAECVar::YQ, q.y.clone()),
+        (FFAECVar::DxInv, curve.inverse(&dx)),
+        (FFAECVar::SyInv, curve.inverse(&sy)),
+        (FFAECVar::Lambda, lambda),
+        (FFAECVar::Den, den),
+    ]);
+    values.iter().for_each(|(var, value)| {
+        let limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(value);
+        limbs.iter().enumerate().for_each(|(i, limb)| {
+            env.write_column(FFAECColumn::Var(*var, i), &Env::constant(*limb));
+        });
+    });
+
+    for op in FFA_EC_OPS {
+        let (a, b) = op.inputs();
+        let (a, b) = (&values[&a], &values[&b]);
+        let res = match op {
+            FFAECOp::Add(op) => {
+                ff_addition_circuit(&mut SubEnvColumn::new(env, FFAECAddLens(op)), f, a, b)
+            }
+            FFAECOp::Sub(op) => {
+                ff_subtraction_circuit(&mut SubEnvColumn::new(env, FFAECSubLens(op)), f, a, b)
+            }
+            FFAECOp::Mul(op) => {
+                ff_multiplication_circuit(&mut SubEnvColumn::new(env, FFAECMulLens(op)), f, a, b)
+            }
+        };
+        values.insert(op.output(), res);
+    }
+
+    let r = if p.infinity {
+        q.clone()
+    } else if q.infinity {
+        p.clone()
+    } else if cancel {
+        FFAECPoint::infinity()
+    } else {
+        FFAECPoint::new(values[&FFAECVar::X3].clone(), values[&FFAECVar::Y3].clone())
+    };
+    [(FFAECVar::XR, &r.x), (FFAECVar::YR, &r.y)]
+        .into_iter()
+        .for_each(|(var, value)| {
+            let limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(value);
+            limbs.iter().enumerate().for_each(|(i, limb)| {
+                env.write_column(FFAECColumn::Var(var, i), &Env::constant(*limb));
+            });
+        });
+
+    constrain_ec_addition_native(env);
+
+    r
+}
+
+/// Bitsize of the windows of the EC scalar multiplication, i.e. of the
+/// digits of the scalar.
+pub const FFA_EC_MUL_WINDOW_BITSIZE: usize = 4;
+
+/// Decomposes the scalar into `n_windows` digits of
+/// [FFA_EC_MUL_WINDOW_BITSIZE] bits, starting with the least significant
+/// one.
+pub fn ec_scalar_mul_digits(scalar: &BigUint, n_windows: usize) -> Vec<u64> {
+    assert!(
+        scalar.bits() as usize <= n_windows * FFA_EC_MUL_WINDOW_BITSIZE,
+        "The scalar must be smaller than 2^{}",
+        n_windows * FFA_EC_MUL_WINDOW_BITSIZE
+    );
+    let mask = BigUint::from((1u64 << FFA_EC_MUL_WINDOW_BITSIZE) - 1);
+    (0..n_windows)
+        .map(|i| {
+            let digit = (scalar >> (i * FFA_EC_MUL_WINDOW_BITSIZE)) & &mask;
+            digit.try_into().unwrap()
+        })
+        .collect()
+}
+
+/// Computes the points `d * 2^{w i} * G` for each window `i < n_windows` and
+/// each digit `d < 2^w`, where `w` is [FFA_EC_MUL_WINDOW_BITSIZE].
+fn ec_scalar_mul_window_points(
+    curve: &FFAECCurve,
+    base: &FFAECPoint,
+    n_windows: usize,
+) -> Vec<Vec<FFAECPoint>> {
+    let mut window_base = base.clone();
+    (0..n_windows)
+        .map(|_| {
+            let points: Vec<FFAECPoint> = (0..1 << FFA_EC_MUL_WINDOW_BITSIZE)
+                .scan(FFAECPoint::infinity(), |acc, _| {
+                    let point = acc.clone();
+                    *acc = curve.add(acc, &window_base);
+                    Some(point)
+                })
+                .collect();
+            window_base = curve.add(&points[(1 << FFA_EC_MUL_WINDOW_BITSIZE) - 1], &window_base);
+            points
+        })
+        .collect()
+}
+
+/// Builds the entries of the lookup table
+/// [WindowPoint](FFAECLookupTable::WindowPoint) for the base point `G`, i.e.
+/// `(i * 2^w + d, x, y, inf)` for each window `i` and digit `d`, where
+/// `(x, y)` are the limbs of `d * 2^{w i} * G`. The table is padded with its
+/// first entry, the point at infinity.
+pub fn ec_scalar_mul_window_table<F: PrimeField>(
+    curve: &FFAECCurve,
+    base: &FFAECPoint,
+    n_windows: usize,
+    domain_size: usize,
+) -> Vec<Vec<F>> {
+    ec_msm_window_table(curve, std::slice::from_ref(base), n_windows, domain_size)
+}
+
+/// Builds the entries of the lookup table
+/// [WindowPoint](FFAECLookupTable::WindowPoint) for the MSM with the base
+/// points `G_0, ..., G_{n-1}`. The windows of the base points are
+/// concatenated: the window `i` of `G_k` is the window `k * n_windows + i` of
+/// the table, see [ec_scalar_mul_window_table].
+pub fn ec_msm_window_table<F: PrimeField>(
+    curve: &FFAECCurve,
+    bases: &[FFAECPoint],
+    n_windows: usize,
+    domain_size: usize,
+) -> Vec<Vec<F>> {
+    assert!(
+        (bases.len() * n_windows) << FFA_EC_MUL_WINDOW_BITSIZE <= domain_size,
+        "The window table must fit in the domain"
+    );
+    let entry = |index: usize, point: &FFAECPoint| -> Vec<F> {
+        std::iter::once(F::from(index as u64))
+            .chain(ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(&point.x))
+            .chain(ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(&point.y))
+            .chain(std::iter::once(F::from(point.infinity)))
+            .collect()
+    };
+    let entries: Vec<Vec<F>> = bases
+        .iter()
+        .flat_map(|base| ec_scalar_mul_window_points(curve, base, n_windows))
+        .flatten()
+        .enumerate()
+        .map(|(index, point)| entry(index, &point))
+        .collect();
+    let padding = entries[0].clone();
+    entries
+        .into_iter()
+        .chain(std::iter::repeat(padding))
+        .take(domain_size)
+        .collect()
+}
+
+/// Builds the fixed selectors of the EC scalar multiplication with
+/// `n_windows` windows, see [FFAECScalarMulColumn]. The selectors of the MSM
+/// of `n` points are the ones of `n * n_windows` windows.
+pub fn ec_scalar_mul_fixed_selectors<F: PrimeField>(
+    n_windows: usize,
+    domain_size: usize,
+) -> Box<[Vec<F>; FFA_EC_MUL_N_FSEL]> {
+    assert!(n_windows > 0 && n_windows <= domain_size);
+    let step = (0..domain_size)
+        .map(|i| {
+            if i < n_windows {
+                F::from(i as u64)
+            } else {
+                F::zero()
+            }
+        })
+        .collect();
+    let is_first = (0..domain_size).map(|i| F::from(i == 0)).collect();
+    let is_chained = (0..domain_size)
+        .map(|i| F::from(i + 1 < n_windows))
+        .collect();
+    let is_last = (0..domain_size)
+        .map(|i| F::from(i + 1 == n_windows))
+        .collect();
+    Box::new([step, is_first, is_chained, is_last])
+}
+
+/// Builds the public inputs of the EC scalar multiplication `s * G = R`,
+/// i.e. the curve and `R` on each row, and the digits of `s` on the rows of
+/// the windows.
+pub fn ec_scalar_mul_public_inputs<F: PrimeField>(
+    curve: &FFAECCurve,
+    result: &FFAECPoint,
+    scalar: &BigUint,
+    n_windows: usize,
+    domain_size: usize,
+) -> Witness<FFA_EC_MUL_NPUB_COLUMNS, Vec<F>> {
+    ec_msm_public_inputs(
+        curve,
+        result,
+        std::slice::from_ref(scalar),
+        n_windows,
+        domain_size,
+    )
+}
+
+/// Builds the public inputs of the MSM `Σ s_k * G_k = R`, i.e. the curve
+/// and `R` on each row, and the digits of the scalars `s_k` on the rows of
+/// their windows, in order.
+pub fn ec_msm_public_inputs<F: PrimeField>(
+    curve: &FFAECCurve,
+    result: &FFAECPoint,
+    scalars: &[BigUint],
+    n_windows: usize,
+    domain_size: usize,
+) -> Witness<FFA_EC_MUL_NPUB_COLUMNS, Vec<F>> {
+    let curve_inputs = ffa_ec_public_inputs::<F>(curve, domain_size);
+    let result_limbs: Vec<F> = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(&result.x)
+        .into_iter()
+        .chain(ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(&result.y))
+        .chain(std::iter::once(F::from(result.infinity)))
+        .collect();
+    let digits: Vec<u64> = scalars
+        .iter()
+        .flat_map(|scalar| ec_scalar_mul_digits(scalar, n_windows))
+        .collect();
+    let digits_column = (0..domain_size)
+        .map(|i| F::from(digits.get(i).copied().unwrap_or(0)))
+        .collect();
+    let cols: Vec<Vec<F>> = curve_inputs
+        .cols
+        .into_iter()
+        .chain(result_limbs.into_iter().map(|x| vec![x; domain_size]))
+        .chain(std::iter::once(digits_column))
+        .collect();
+    Witness {
+        cols: Box::new(cols.try_into().unwrap()),
+    }
+}
+
+/// Constraints of the window of a row of the EC scalar multiplication:
+///
+/// - The digit `d` is smaller than `2^w`, checked by looking up `d * 2^{15 - w}`
+///   in the 15-bit range check table.
+/// - `Q = d * 2^{w i} * G`, where `i` is the step, checked by looking up
+///   `(i * 2^w + d, Q)` in the table of the window points.
+/// - The accumulator `P` is the point at infinity on the first row.
+/// - The accumulator `R` is the result on the last row.
+///
+/// The accumulator `R` of a row must also be the input `P` of the next
+/// row, see [ec_scalar_mul_chaining_constraints].
+pub fn constrain_ec_scalar_mul_window<
+    F: PrimeField,
+    Env: ColAccessCap<F, FFAECScalarMulColumn> + LookupCap<F, FFAECScalarMulColumn, FFAECLookupTable>,
+>(
+    env: &mut Env,
+) {
+    let one = Env::constant(F::one());
+    let var =
+        |env: &Env, var, i| env.read_column(FFAECScalarMulColumn::Add(FFAECColumn::Var(var, i)));
+    let flag =
+        |env: &Env, flag| env.read_column(FFAECScalarMulColumn::Add(FFAECColumn::Flag(flag)));
+
+    let digit = env.read_column(FFAECScalarMulColumn::Digit);
+    let step = env.read_column(FFAECScalarMulColumn::Step);
+    env.lookup(
+        FFAECLookupTable::FFALookupTable(LookupTable::RangeCheck15),
+        vec![
+            digit.clone()
+                * Env::constant(F::from(1u64 << (LIMB_BITSIZE - FFA_EC_MUL_WINDOW_BITSIZE))),
+        ],
+    );
+    let index = step * Env::constant(F::from(1u64 << FFA_EC_MUL_WINDOW_BITSIZE)) + digit;
+    let window_point: Vec<_> = std::iter::once(index)
+        .chain((0..N_LIMBS).map(|i| var(env, FFAECVar::XQ, i)))
+        .chain((0..N_LIMBS).map(|i| var(env, FFAECVar::YQ, i)))
+        .chain(std::iter::once(flag(env, FFAECFlag::InfQ)))
+        .collect();
+    env.lookup(FFAECLookupTable::WindowPoint, window_point);
+
+    let is_first = env.read_column(FFAECScalarMulColumn::IsFirst);
+    env.assert_zero(is_first * (one - flag(env, FFAECFlag::InfP)));
+
+    let is_last = env.read_column(FFAECScalarMulColumn::IsLast);
+    for i in 0..N_LIMBS {
+        let result_x = env.read_column(FFAECScalarMulColumn::ResultX(i));
+        let result_y = env.read_column(FFAECScalarMulColumn::ResultY(i));
+        env.assert_zero(is_last.clone() * (var(env, FFAECVar::XR, i) - result_x));
+        env.assert_zero(is_last.clone() * (var(env, FFAECVar::YR, i) - result_y));
+    }
+    let result_inf = env.read_column(FFAECScalarMulColumn::ResultInf);
+    env.assert_zero(is_last * (flag(env, FFAECFlag::InfR) - result_inf));
+}
+
+/// Constraints of a row of the EC scalar multiplication: the EC addition,
+/// followed by [constrain_ec_scalar_mul_window].
+pub fn constrain_ec_scalar_mul<
+    F: PrimeField,
+    Env: ColAccessCap<F, FFAECScalarMulColumn> + LookupCap<F, FFAECScalarMulColumn, FFAECLookupTable>,
+>(
+    env: &mut Env,
+) {
+    constrain_ec_addition(&mut SubEnvLookup::new(
+        &mut SubEnvColumn::new(env, FFAECScalarMulLens {}),
+        FFAECLookupLens {},
+    ));
+    constrain_ec_scalar_mul_window(env);
+}
+
+/// Constraints chaining the rows of the EC scalar multiplication: on the
+/// rows selected by `IsChained`, the accumulator `R` is the input `P` of the
+/// next row.
+///
+/// The constraints relate two consecutive rows, which can't be expressed
+/// using the capabilities of [crate::circuit_design]. They must be added to
+/// the constraints built by [constrain_ec_scalar_mul], and are not checked by
+/// the witness builder.
+pub fn ec_scalar_mul_chaining_constraints<F: PrimeField>() -> Vec<E<F>> {
+    let is_chained = curr_cell::<F>(FFAECScalarMulColumn::IsChained.to_column());
+    let column = |col| FFAECScalarMulColumn::Add(col).to_column();
+    (0..N_LIMBS)
+        .flat_map(|i| {
+            [(FFAECVar::XP, FFAECVar::XR), (FFAECVar::YP, FFAECVar::YR)].map(|(p, r)| {
+                (
+                    column(FFAECColumn::Var(p, i)),
+                    column(FFAECColumn::Var(r, i)),
+                )
+            })
+        })
+        .chain(std::iter::once((
+            column(FFAECColumn::Flag(FFAECFlag::InfP)),
+            column(FFAECColumn::Flag(FFAECFlag::InfR)),
+        )))
+        .map(|(p, r)| is_chained.clone() * (next_cell(p) - curr_cell(r)))
+        .collect()
+}
+
+/// Builds the witness of one row of the EC scalar multiplication, adding
+/// the point `Q` of the window with the given digit to the accumulator `P`,
+/// and constrains it. Returns the new accumulator.
+fn ec_scalar_mul_row<
+    F: PrimeField,
+    Env: ColAccessCap<F, FFAECScalarMulColumn>
+        + ColWriteCap<F, FFAECScalarMulColumn>
+        + LookupCap<F, FFAECScalarMulColumn, FFAECLookupTable>,
+>(
+    env: &mut Env,
+    curve: &FFAECCurve,
+    result: &FFAECPoint,
+    digit: u64,
+    p: &FFAECPoint,
+    q: &FFAECPoint,
+) -> FFAECPoint {
+    [
+        (FFAECScalarMulColumn::ResultX as fn(usize) -> _, &result.x),
+        (FFAECScalarMulColumn::ResultY, &result.y),
+    ]
+    .into_iter()
+    .for_each(|(column, value)| {
+        let limbs: [F; N_LIMBS] = ffa_limbs::<F, N_LIMBS, LIMB_BITSIZE>(value);
+        limbs.iter().enumerate().for_each(|(i, limb)| {
+            env.write_column(column(i), &Env::constant(*limb));
+        });
+    });
+    env.write_column(
+        FFAECScalarMulColumn::ResultInf,
+        &Env::constant(F::from(result.infinity)),
+    );
+    env.write_column(FFAECScalarMulColumn::Digit, &Env::constant(F::from(digit)));
+
+    let acc = ec_addition_circuit(
+        &mut SubEnvLookup::new(
+            &mut SubEnvColumn::new(env, FFAECScalarMulLens {}),
+            FFAECLookupLens {},
+        ),
+        curve,
+        p,
+        q,
+    );
+    constrain_ec_scalar_mul_window(env);
+    acc
+}
+
+/// Builds the witness of the EC scalar multiplication `s * G` on the given
+/// curve, using `n_windows` windows, and constrains it. Returns `s * G`.
+///
+/// The rows of the windows are written starting from the current row, which
+/// is the row of the last window at the end. The fixed selectors must be the
+/// ones given by [ec_scalar_mul_fixed_selectors], and the window table the
+/// one given by [ec_scalar_mul_window_table] for the same base point. The
+/// remaining rows of the domain can be filled with
+/// [ec_scalar_mul_padding_row].
+pub fn ec_scalar_mul_circuit<
+    F: PrimeField,
+    Env: ColAccessCap<F, FFAECScalarMulColumn>
+        + ColWriteCap<F, FFAECScalarMulColumn>
+        + MultiRowReadCap<F, FFAECScalarMulColumn>
+        + LookupCap<F, FFAECScalarMulColumn, FFAECLookupTable>,
+>(
+    env: &mut Env,
+    curve: &FFAECCurve,
+    base: &FFAECPoint,
+    scalar: &BigUint,
+    n_windows: usize,
+) -> FFAECPoint {
+    ec_msm_circuit(
+        env,
+        curve,
+        std::slice::from_ref(base),
+        std::slice::from_ref(scalar),
+        n_windows,
+    )
+}
+
+/// Builds the witness of the MSM `Σ s_k * G_k` on the given curve, using
+/// `n_windows` windows per scalar, and constrains it. Returns the MSM.
+///
+/// The MSM is the EC scalar multiplication whose windows are the windows of
+/// the scalars, in order, accumulated in one sum. The rows are written as in
+/// [ec_scalar_mul_circuit], using the fixed selectors of
+/// `bases.len() * n_windows` windows and the table given by
+/// [ec_msm_window_table].
+pub fn ec_msm_circuit<
+    F: PrimeField,
+    Env: ColAccessCap<F, FFAECScalarMulColumn>
+        + ColWriteCap<F, FFAECScalarMulColumn>
+        + MultiRowReadCap<F, FFAECScalarMulColumn>
+        + LookupCap<F, FFAECScalarMulColumn, FFAECLookupTable>,
+>(
+    env: &mut Env,
+    curve: &FFAECCurve,
+    bases: &[FFAECPoint],
+    scalars: &[BigUint],
+    n_windows: usize,
+) -> FFAECPoint {
+    assert_eq!(
+        bases.len(),
+        scalars.len(),
+        "There must be as many scalars as base points"
+    );
+    assert!(!bases.is_empty(), "The MSM must have at least one point");
+    let digits: Vec<u64> = scalars
+        .iter()
+        .flat_map(|scalar| ec_scalar_mul_digits(scalar, n_windows))
+        .collect();
+    let window_points: Vec<Vec<FFAECPoint>> = bases
+        .iter()
+        .flat_map(|base| ec_scalar_mul_window_points(curve, base, n_windows))
+        .collect();
+    let result = digits
+        .iter()
+        .zip(window_points.iter())
+        .fold(FFAECPoint::infinity(), |acc, (digit, points)| {
+            curve.add(&acc, &points[*digit as usize])
+        });
+
+    let mut acc = FFAECPoint::infinity();
+    for (i, (digit, points)) in digits.iter().zip(window_points.iter()).enumerate() {
+        if i > 0 {
+            env.next_row();
+        }
+        acc = ec_scalar_mul_row(env, curve, &result, *digit, &acc, &points[*digit as usize]);
+    }
+    assert_eq!(acc, result);
+
+    result
+}
+
+/// Builds the witness of a row after the last window of the EC scalar
+/// multiplication with the given result, adding the point at infinity to
+/// itself with the digit zero, and constrains it.
+pub fn ec_scalar_mul_padding_row<
+    F: PrimeField,
+    Env: ColAccessCap<F, FFAECScalarMulColumn>
+        + ColWriteCap<F, FFAECScalarMulColumn>
+        + LookupCap<F, FFAECScalarMulColumn, FFAECLookupTable>,
+>(
+    env: &mut Env,
+    curve: &FFAECCurve,
+    result: &FFAECPoint,
+) {
+    ec_scalar_mul_row(
+        env,
+        curve,
+        result,
+        0,
+        &FFAECPoint::infinity(),
+        &FFAECPoint::infinity(),
+    );
+}
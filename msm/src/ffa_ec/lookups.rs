@@ -0,0 +1,85 @@
+use crate::{circuit_design::composition::MPrism, ffa::lookups as ffalookup, logup::LookupTableID};
+use ark_ff::PrimeField;
+use o1_utils::FieldHelpers;
+
+/// Enumeration of concrete lookup tables used in the EC scalar
+/// multiplication circuit.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LookupTable {
+    /// The tables of the FF operations.
+    FFALookupTable(ffalookup::LookupTable),
+    /// The multiples of the base point for each window, i.e. the entries
+    /// `(i * 2^w + d, x, y, inf)` where `(x, y)` are the limbs of
+    /// `d * 2^{w i} * G` and `inf` is `1` if it is the point at infinity.
+    /// The entries are built by
+    /// [ec_scalar_mul_window_table](crate::ffa_ec::interpreter::ec_scalar_mul_window_table)
+    /// for a given base point.
+    WindowPoint,
+}
+
+impl LookupTableID for LookupTable {
+    fn to_u32(&self) -> u32 {
+        match self {
+            Self::FFALookupTable(lt) => lt.to_u32(),
+            Self::WindowPoint => 3,
+        }
+    }
+
+    fn from_u32(value: u32) -> Self {
+        match value {
+            1 | 2 => Self::FFALookupTable(ffalookup::LookupTable::from_u32(value)),
+            3 => Self::WindowPoint,
+            _ => panic!("Invalid lookup table id"),
+        }
+    }
+
+    /// All tables are fixed tables.
+    fn is_fixed(&self) -> bool {
+        true
+    }
+
+    fn runtime_create_column(&self) -> bool {
+        panic!("No runtime tables specified");
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            Self::FFALookupTable(lt) => lt.length(),
+            Self::WindowPoint => 1 << 15,
+        }
+    }
+
+    /// Converts a value to its index in the fixed table.
+    fn ix_by_value<F: PrimeField>(&self, value: &[F]) -> Option<usize> {
+        match self {
+            Self::FFALookupTable(lt) => lt.ix_by_value(value),
+            Self::WindowPoint => Some(TryFrom::try_from(value[0].to_biguint()).unwrap()),
+        }
+    }
+
+    fn all_variants() -> Vec<Self> {
+        ffalookup::LookupTable::all_variants()
+            .into_iter()
+            .map(LookupTable::FFALookupTable)
+            .chain([LookupTable::WindowPoint])
+            .collect()
+    }
+}
+
+pub struct FFAECLookupLens {}
+
+impl MPrism for FFAECLookupLens {
+    type Source = LookupTable;
+    type Target = ffalookup::LookupTable;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        match source {
+            LookupTable::FFALookupTable(lt) => Some(lt),
+            LookupTable::WindowPoint => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        LookupTable::FFALookupTable(target)
+    }
+}
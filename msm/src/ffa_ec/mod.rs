@@ -0,0 +1,477 @@
+pub mod columns;
+pub mod interpreter;
+pub mod lookups;
+pub mod msm;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        circuit_design::{ConstraintBuilderEnv, WitnessBuilderEnv},
+        columns::ColumnIndexer,
+        ffa::lookups::LookupTable,
+        ffa_ec::{
+            columns::{
+                FFAECColumn, FFAECFlag, FFAECScalarMulColumn, FFAECVar, FFA_EC_MUL_N_COLUMNS,
+                FFA_EC_MUL_N_FSEL,
+            },
+            interpreter::{
+                constrain_ec_addition, constrain_ec_scalar_mul, ec_addition_circuit,
+                ec_msm_circuit, ec_scalar_mul_chaining_constraints, ec_scalar_mul_circuit,
+                ec_scalar_mul_fixed_selectors, ec_scalar_mul_padding_row,
+                ec_scalar_mul_window_table, FFAECCurve, FFAECPoint, FFA_EC_MUL_WINDOW_BITSIZE,
+            },
+            lookups::LookupTable as FFAECLookupTable,
+            msm::{ec_msm_n_windows, prove_msm, verify_msm},
+        },
+        Ff1, Fp,
+    };
+    use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+    use ark_ff::{PrimeField, UniformRand};
+    use kimchi::circuits::domains::EvaluationDomains;
+    use mina_curves::pasta::{Pallas, PallasParameters};
+    use num_bigint::BigUint;
+    use std::{collections::HashMap, ops::Mul};
+
+    type FFAECWitnessBuilderEnv = WitnessBuilderEnv<
+        Fp,
+        FFAECColumn,
+        { <FFAECColumn as ColumnIndexer>::N_COL },
+        { <FFAECColumn as ColumnIndexer>::N_COL },
+        0,
+        0,
+        LookupTable,
+    >;
+
+    type FFAECScalarMulWitnessBuilderEnv = WitnessBuilderEnv<
+        Fp,
+        FFAECScalarMulColumn,
+        FFA_EC_MUL_N_COLUMNS,
+        FFA_EC_MUL_N_COLUMNS,
+        0,
+        FFA_EC_MUL_N_FSEL,
+        FFAECLookupTable,
+    >;
+
+    fn pallas_curve() -> FFAECCurve {
+        FFAECCurve {
+            modulus: Ff1::MODULUS.into(),
+            a: BigUint::from(0u64),
+            b: BigUint::from(5u64),
+        }
+    }
+
+    fn pallas_point(p: &Pallas) -> FFAECPoint {
+        if p.is_zero() {
+            FFAECPoint::infinity()
+        } else {
+            FFAECPoint::new(p.x.into(), p.y.into())
+        }
+    }
+
+    fn secp256k1_curve() -> FFAECCurve {
+        FFAECCurve {
+            modulus: BigUint::parse_bytes(
+                b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+                16,
+            )
+            .unwrap(),
+            a: BigUint::from(0u64),
+            b: BigUint::from(7u64),
+        }
+    }
+
+    fn secp256k1_generator() -> FFAECPoint {
+        FFAECPoint::new(
+            BigUint::parse_bytes(
+                b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                16,
+            )
+            .unwrap(),
+            BigUint::parse_bytes(
+                b"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+                16,
+            )
+            .unwrap(),
+        )
+    }
+
+    /// The curve P-256, with `a = -3`
+    fn p256_curve() -> FFAECCurve {
+        let modulus = BigUint::parse_bytes(
+            b"ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+            16,
+        )
+        .unwrap();
+        FFAECCurve {
+            a: &modulus - 3u64,
+            modulus,
+            b: BigUint::parse_bytes(
+                b"5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+                16,
+            )
+            .unwrap(),
+        }
+    }
+
+    fn p256_generator() -> FFAECPoint {
+        FFAECPoint::new(
+            BigUint::parse_bytes(
+                b"6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+                16,
+            )
+            .unwrap(),
+            BigUint::parse_bytes(
+                b"4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+                16,
+            )
+            .unwrap(),
+        )
+    }
+
+    /// Returns `-P`
+    fn negate(curve: &FFAECCurve, p: &FFAECPoint) -> FFAECPoint {
+        FFAECPoint::new(p.x.clone(), (&curve.modulus - &p.y) % &curve.modulus)
+    }
+
+    /// Builds the EC addition circuit for the given pairs of points, one
+    /// pair per row, and checks the result against `expected`. The witness
+    /// environment enforces the constraints internally, so it is enough to
+    /// just build the circuit to ensure it is satisfied.
+    fn build_ffa_ec_circuit(
+        curve: &FFAECCurve,
+        inputs: &[(FFAECPoint, FFAECPoint)],
+        expected: &dyn Fn(&FFAECPoint, &FFAECPoint) -> FFAECPoint,
+    ) -> FFAECWitnessBuilderEnv {
+        let mut witness_env = FFAECWitnessBuilderEnv::create();
+        for (p, q) in inputs.iter() {
+            let r = ec_addition_circuit(&mut witness_env, curve, p, q);
+            assert_eq!(r, expected(p, q));
+            assert!(curve.is_on_curve(&r));
+            witness_env.next_row();
+        }
+        witness_env
+    }
+
+    #[test]
+    /// Tests the EC addition circuit on Pallas against arkworks, including
+    /// the doubling and the exceptional cases.
+    pub fn test_ffa_ec_addition_circuit_pallas() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let curve = pallas_curve();
+        let gen = Pallas::generator();
+        let random_point = |rng: &mut _| -> Pallas {
+            let k: <Pallas as AffineRepr>::ScalarField = UniformRand::rand(rng);
+            gen.mul(k).into()
+        };
+        let p = random_point(&mut rng);
+        let q = random_point(&mut rng);
+        let zero = Pallas::zero();
+        let inputs: Vec<(Pallas, Pallas)> = vec![
+            (p, q),
+            (q, p),
+            (p, p),
+            (p, -p),
+            (zero, q),
+            (p, zero),
+            (zero, zero),
+        ];
+        let inputs: Vec<(FFAECPoint, FFAECPoint)> = inputs
+            .iter()
+            .map(|(p, q)| (pallas_point(p), pallas_point(q)))
+            .collect();
+        build_ffa_ec_circuit(&curve, &inputs, &|p, q| {
+            let to_pallas = |p: &FFAECPoint| {
+                if p.infinity {
+                    Pallas::zero()
+                } else {
+                    Pallas::new_unchecked(p.x.clone().into(), p.y.clone().into())
+                }
+            };
+            pallas_point(&(to_pallas(p) + to_pallas(q)).into_affine())
+        });
+    }
+
+    #[test]
+    /// Tests the EC addition circuit on secp256k1 against known multiples of
+    /// the generator.
+    pub fn test_ffa_ec_addition_circuit_secp256k1() {
+        let curve = secp256k1_curve();
+        let g = secp256k1_generator();
+        let g2 = FFAECPoint::new(
+            BigUint::parse_bytes(
+                b"c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+                16,
+            )
+            .unwrap(),
+            BigUint::parse_bytes(
+                b"1ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a",
+                16,
+            )
+            .unwrap(),
+        );
+        assert_eq!(curve.add(&g, &g), g2);
+        let inputs = vec![
+            (g.clone(), g.clone()),
+            (g.clone(), g2.clone()),
+            (g2.clone(), negate(&curve, &g2)),
+            (FFAECPoint::infinity(), g.clone()),
+        ];
+        build_ffa_ec_circuit(&curve, &inputs, &|p, q| curve.add(p, q));
+    }
+
+    #[test]
+    /// Tests the EC addition circuit on P-256, whose coefficient `a` is not
+    /// zero.
+    pub fn test_ffa_ec_addition_circuit_p256() {
+        let curve = p256_curve();
+        let g = p256_generator();
+        let g2 = curve.add(&g, &g);
+        let g3 = curve.add(&g2, &g);
+        let inputs = vec![
+            (g.clone(), g.clone()),
+            (g2.clone(), g.clone()),
+            (g3.clone(), g3.clone()),
+            (g3.clone(), negate(&curve, &g3)),
+        ];
+        build_ffa_ec_circuit(&curve, &inputs, &|p, q| curve.add(p, q));
+    }
+
+    #[test]
+    #[should_panic]
+    /// The inputs must be on the curve.
+    pub fn test_ffa_ec_addition_not_on_curve() {
+        let curve = secp256k1_curve();
+        let g = secp256k1_generator();
+        let p = FFAECPoint::new(g.x.clone(), &g.y + 1u64);
+        let mut witness_env = FFAECWitnessBuilderEnv::create();
+        ec_addition_circuit(&mut witness_env, &curve, &g, &p);
+    }
+
+    #[test]
+    pub fn test_regression_relation_constraints_ffa_ec() {
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
+        constrain_ec_addition::<Fp, _>(&mut constraint_env);
+        let constraints = constraint_env.get_relation_constraints();
+
+        let mut constraints_degrees = HashMap::new();
+
+        assert_eq!(constraints.len(), 1277);
+
+        constraints.iter().for_each(|c| {
+            let degree = c.degree(1, 0);
+            *constraints_degrees.entry(degree).or_insert(0) += 1;
+        });
+
+        assert_eq!(constraints_degrees.get(&1), Some(&52));
+        assert_eq!(constraints_degrees.get(&2), Some(&1147));
+        assert_eq!(constraints_degrees.get(&3), Some(&78));
+        assert!(constraints.iter().map(|c| c.degree(1, 0)).max() <= Some(3));
+    }
+
+    /// Builds the EC scalar multiplication circuit for `s * G` on
+    /// `domain_size` rows, padding the rows after the last window. The
+    /// witness environment enforces the constraints of each row, and the
+    /// chaining of the rows and the window points are checked on the
+    /// witness.
+    fn build_ffa_ec_scalar_mul_circuit(
+        curve: &FFAECCurve,
+        base: &FFAECPoint,
+        scalar: &BigUint,
+        n_windows: usize,
+        domain_size: usize,
+    ) -> FFAECPoint {
+        let mut witness_env = FFAECScalarMulWitnessBuilderEnv::create();
+        witness_env.set_fixed_selectors(
+            ec_scalar_mul_fixed_selectors::<Fp>(n_windows, domain_size).to_vec(),
+        );
+        let result = ec_scalar_mul_circuit(&mut witness_env, curve, base, scalar, n_windows);
+        for _ in n_windows..domain_size {
+            witness_env.next_row();
+            ec_scalar_mul_padding_row(&mut witness_env, curve, &result);
+        }
+
+        let read = |row: usize, col: FFAECScalarMulColumn| {
+            let crate::columns::Column::Relation(i) = col.to_column() else {
+                panic!("Not a relation column")
+            };
+            witness_env.witness[row].cols[i]
+        };
+        let var = |var, i| FFAECScalarMulColumn::Add(FFAECColumn::Var(var, i));
+        let flag = |flag| FFAECScalarMulColumn::Add(FFAECColumn::Flag(flag));
+
+        // The accumulator of a row is the input of the next one
+        for row in 0..n_windows - 1 {
+            for i in 0..crate::ffa::columns::FFA_N_LIMBS {
+                assert_eq!(
+                    read(row + 1, var(FFAECVar::XP, i)),
+                    read(row, var(FFAECVar::XR, i))
+                );
+                assert_eq!(
+                    read(row + 1, var(FFAECVar::YP, i)),
+                    read(row, var(FFAECVar::YR, i))
+                );
+            }
+            assert_eq!(
+                read(row + 1, flag(FFAECFlag::InfP)),
+                read(row, flag(FFAECFlag::InfR))
+            );
+        }
+
+        // The points added are the ones of the window table
+        let table = ec_scalar_mul_window_table::<Fp>(curve, base, n_windows, 1 << 15);
+        for row in 0..domain_size {
+            let step = if row < n_windows { row } else { 0 };
+            let digit: BigUint = read(row, FFAECScalarMulColumn::Digit).into();
+            let index = (step << FFA_EC_MUL_WINDOW_BITSIZE) + usize::try_from(digit).unwrap();
+            let point: Vec<Fp> = (0..crate::ffa::columns::FFA_N_LIMBS)
+                .map(|i| read(row, var(FFAECVar::XQ, i)))
+                .chain(
+                    (0..crate::ffa::columns::FFA_N_LIMBS).map(|i| read(row, var(FFAECVar::YQ, i))),
+                )
+                .chain(std::iter::once(read(row, flag(FFAECFlag::InfQ))))
+                .collect();
+            assert_eq!(table[index][1..], point);
+        }
+
+        result
+    }
+
+    #[test]
+    /// Tests the EC scalar multiplication circuit on Pallas against arkworks.
+    pub fn test_ffa_ec_scalar_mul_circuit_pallas() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let curve = pallas_curve();
+        let gen = Pallas::generator();
+        let scalar: <Pallas as AffineRepr>::ScalarField = UniformRand::rand(&mut rng);
+        let expected: Pallas = gen.mul(scalar).into_affine();
+        let n_windows = 256 / FFA_EC_MUL_WINDOW_BITSIZE;
+        let result = build_ffa_ec_scalar_mul_circuit(
+            &curve,
+            &pallas_point(&gen),
+            &scalar.into(),
+            n_windows,
+            n_windows + 2,
+        );
+        assert_eq!(result, pallas_point(&expected));
+    }
+
+    #[test]
+    /// Tests the EC scalar multiplication circuit on secp256k1 with small
+    /// scalars, including the ones giving the point at infinity.
+    pub fn test_ffa_ec_scalar_mul_circuit_secp256k1() {
+        let curve = secp256k1_curve();
+        let g = secp256k1_generator();
+        let g2 = curve.add(&g, &g);
+        [
+            (0u64, FFAECPoint::infinity()),
+            (1, g.clone()),
+            (2, g2.clone()),
+            (
+                1 << FFA_EC_MUL_WINDOW_BITSIZE,
+                (0..FFA_EC_MUL_WINDOW_BITSIZE).fold(g.clone(), |acc, _| curve.add(&acc, &acc)),
+            ),
+        ]
+        .iter()
+        .for_each(|(scalar, expected)| {
+            let result = build_ffa_ec_scalar_mul_circuit(&curve, &g, &BigUint::from(*scalar), 2, 4);
+            assert_eq!(result, *expected);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    /// The scalar must fit in the windows.
+    pub fn test_ffa_ec_scalar_mul_scalar_too_large() {
+        let curve = secp256k1_curve();
+        let g = secp256k1_generator();
+        build_ffa_ec_scalar_mul_circuit(
+            &curve,
+            &g,
+            &BigUint::from(1u64 << (2 * FFA_EC_MUL_WINDOW_BITSIZE)),
+            2,
+            4,
+        );
+    }
+
+    #[test]
+    /// Tests the MSM circuit on Pallas against arkworks, with the rows of the
+    /// windows of all the points followed by padding rows.
+    pub fn test_ffa_ec_msm_circuit_pallas() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let curve = pallas_curve();
+        let points: Vec<Pallas> = (0..3).map(|_| Pallas::rand(&mut rng)).collect();
+        let scalars: Vec<_> = (0..3)
+            .map(|_| <Pallas as AffineRepr>::ScalarField::rand(&mut rng))
+            .collect();
+        let expected = <Pallas as AffineRepr>::Group::msm(&points, &scalars)
+            .unwrap()
+            .into_affine();
+
+        let n_windows = ec_msm_n_windows::<PallasParameters>();
+        let n_rows = points.len() * n_windows;
+        let domain_size = n_rows + 2;
+        let mut witness_env = FFAECScalarMulWitnessBuilderEnv::create();
+        witness_env
+            .set_fixed_selectors(ec_scalar_mul_fixed_selectors::<Fp>(n_rows, domain_size).to_vec());
+        let result = ec_msm_circuit(
+            &mut witness_env,
+            &curve,
+            &points.iter().map(pallas_point).collect::<Vec<_>>(),
+            &scalars.iter().map(|s| (*s).into()).collect::<Vec<_>>(),
+            n_windows,
+        );
+        for _ in n_rows..domain_size {
+            witness_env.next_row();
+            ec_scalar_mul_padding_row(&mut witness_env, &curve, &result);
+        }
+        assert_eq!(result, pallas_point(&expected));
+    }
+
+    #[test]
+    pub fn heavy_test_prove_msm() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain = EvaluationDomains::<Fp>::create(crate::DOMAIN_SIZE).unwrap();
+        let srs = crate::precomputed_srs::get_bn254_srs(domain);
+        let points: Vec<Pallas> = (0..4).map(|_| Pallas::rand(&mut rng)).collect();
+        let scalars: Vec<_> = (0..4)
+            .map(|_| <Pallas as AffineRepr>::ScalarField::rand(&mut rng))
+            .collect();
+
+        let (proof, result) = prove_msm(&srs, &points, &scalars, &mut rng).unwrap();
+        let expected = <Pallas as AffineRepr>::Group::msm(&points, &scalars)
+            .unwrap()
+            .into_affine();
+        assert_eq!(result, expected);
+        assert!(verify_msm(&srs, &points, &scalars, &result, &proof));
+        assert!(!verify_msm(&srs, &points, &scalars, &points[0], &proof));
+        // The base points are bound to the proof by the window table
+        let mut swapped_points = points.clone();
+        swapped_points.swap(0, 1);
+        assert!(!verify_msm(
+            &srs,
+            &swapped_points,
+            &scalars,
+            &result,
+            &proof
+        ));
+        // Inputs of different lengths are rejected
+        assert!(!verify_msm(&srs, &points[..3], &scalars, &result, &proof));
+        assert!(prove_msm(&srs, &points[..3], &scalars, &mut rng).is_err());
+    }
+
+    #[test]
+    pub fn test_regression_relation_constraints_ffa_ec_scalar_mul() {
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, FFAECLookupTable>::create();
+        constrain_ec_scalar_mul::<Fp, _>(&mut constraint_env);
+        let constraints = constraint_env.get_relation_constraints();
+        let chaining_constraints = ec_scalar_mul_chaining_constraints::<Fp>();
+
+        assert_eq!(constraints.len(), 1277 + 54);
+        assert_eq!(chaining_constraints.len(), 53);
+        assert!(constraints
+            .iter()
+            .chain(chaining_constraints.iter())
+            .all(|c| c.degree(1, 0) <= 3));
+    }
+}
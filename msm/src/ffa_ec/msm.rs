@@ -0,0 +1,279 @@
+//! High-level prover and verifier of the MSM `Σ s_k * G_k` of points of a
+//! short Weierstrass curve over a foreign field, built on the EC scalar
+//! multiplication circuit. The trace layout, the witness and the lookup
+//! tables are handled here, the caller only gives the points and scalars.
+
+use crate::{
+    circuit_design::{ConstraintBuilderEnv, WitnessBuilderEnv},
+    expr::E,
+    ffa_ec::{
+        columns::{
+            FFAECScalarMulColumn, FFA_EC_MUL_NPUB_COLUMNS, FFA_EC_MUL_N_COLUMNS, FFA_EC_MUL_N_FSEL,
+        },
+        interpreter::{
+            constrain_ec_scalar_mul, ec_msm_circuit, ec_msm_public_inputs, ec_msm_window_table,
+            ec_scalar_mul_chaining_constraints, ec_scalar_mul_fixed_selectors,
+            ec_scalar_mul_padding_row, FFAECCurve, FFAECPoint, FFA_EC_MUL_WINDOW_BITSIZE,
+        },
+        lookups::LookupTable as FFAECLookupTable,
+    },
+    logup::LookupTableID,
+    proof::Proof,
+    prover::{prove, ProverError},
+    verifier::verify_with_fixed_tables,
+    BN254G1Affine, BaseSponge, Fp, OpeningProof, ScalarSponge, BN254, DOMAIN_SIZE,
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::PrimeField;
+use kimchi::circuits::domains::EvaluationDomains;
+use num_bigint::BigUint;
+use poly_commitment::kzg::PairingSRS;
+use rand::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
+
+/// A proof of the MSM, see [prove_msm].
+pub type FFAECMsmProof = Proof<
+    FFA_EC_MUL_N_COLUMNS,
+    FFA_EC_MUL_N_COLUMNS,
+    0,
+    FFA_EC_MUL_N_FSEL,
+    BN254G1Affine,
+    OpeningProof,
+    FFAECLookupTable,
+>;
+
+type FFAECMsmWitnessBuilderEnv = WitnessBuilderEnv<
+    Fp,
+    FFAECScalarMulColumn,
+    FFA_EC_MUL_N_COLUMNS,
+    FFA_EC_MUL_N_COLUMNS,
+    0,
+    FFA_EC_MUL_N_FSEL,
+    FFAECLookupTable,
+>;
+
+/// Returns the curve `P` as a [FFAECCurve].
+pub fn ffa_ec_curve<P: SWCurveConfig>() -> FFAECCurve
+where
+    P::BaseField: PrimeField,
+{
+    FFAECCurve {
+        modulus: P::BaseField::MODULUS.into(),
+        a: P::COEFF_A.into(),
+        b: P::COEFF_B.into(),
+    }
+}
+
+/// Returns the point `p` as a [FFAECPoint].
+pub fn ffa_ec_point<P: SWCurveConfig>(p: &Affine<P>) -> FFAECPoint
+where
+    P::BaseField: PrimeField,
+{
+    if p.is_zero() {
+        FFAECPoint::infinity()
+    } else {
+        FFAECPoint::new(p.x.into(), p.y.into())
+    }
+}
+
+/// Number of windows of the scalars of the curve `P`.
+pub fn ec_msm_n_windows<P: SWCurveConfig>() -> usize {
+    let bits = P::ScalarField::MODULUS_BIT_SIZE as usize;
+    (bits + FFA_EC_MUL_WINDOW_BITSIZE - 1) / FFA_EC_MUL_WINDOW_BITSIZE
+}
+
+/// Maximum number of points of an MSM over the curve `P` proven by
+/// [prove_msm]. The window points of all the base points must fit in the
+/// window table, which has [DOMAIN_SIZE] entries.
+pub fn ec_msm_max_points<P: SWCurveConfig>() -> usize {
+    DOMAIN_SIZE / (ec_msm_n_windows::<P>() << FFA_EC_MUL_WINDOW_BITSIZE)
+}
+
+/// The constraints of the MSM circuit, including the lookups and the
+/// chaining of the rows.
+fn ec_msm_constraints() -> Vec<E<Fp>> {
+    let mut constraint_env = ConstraintBuilderEnv::<Fp, FFAECLookupTable>::create();
+    constrain_ec_scalar_mul(&mut constraint_env);
+    let mut constraints = constraint_env.get_constraints();
+    constraints.extend(ec_scalar_mul_chaining_constraints());
+    constraints
+}
+
+/// The inputs of the circuit for the MSM of the given points and scalars.
+/// Returns an error if there are not as many scalars as points, or if the
+/// number of points is not between 1 and [ec_msm_max_points].
+fn ec_msm_inputs<P: SWCurveConfig>(
+    points: &[Affine<P>],
+    scalars: &[P::ScalarField],
+) -> Result<(FFAECCurve, Vec<FFAECPoint>, Vec<BigUint>), ProverError>
+where
+    P::BaseField: PrimeField,
+{
+    if points.len() != scalars.len() {
+        return Err(ProverError::Generic(
+            "there must be as many scalars as points",
+        ));
+    }
+    if points.is_empty() || points.len() > ec_msm_max_points::<P>() {
+        return Err(ProverError::Generic(
+            "the number of points of the MSM is not supported",
+        ));
+    }
+    Ok((
+        ffa_ec_curve::<P>(),
+        points.iter().map(ffa_ec_point).collect(),
+        scalars.iter().map(|s| s.into_bigint().into()).collect(),
+    ))
+}
+
+/// The lookup tables of the MSM with the base points `bases`. The table of
+/// the window points depends on the base points, and binds them to the
+/// proof.
+fn ec_msm_lookup_tables(
+    curve: &FFAECCurve,
+    bases: &[FFAECPoint],
+    n_windows: usize,
+) -> BTreeMap<FFAECLookupTable, Vec<Vec<Fp>>> {
+    FFAECLookupTable::all_variants()
+        .into_iter()
+        .map(|table_id| {
+            let entries = match table_id {
+                FFAECLookupTable::FFALookupTable(lt) => lt
+                    .entries(DOMAIN_SIZE as u64)
+                    .into_iter()
+                    .map(|x| vec![x])
+                    .collect(),
+                FFAECLookupTable::WindowPoint => {
+                    ec_msm_window_table(curve, bases, n_windows, DOMAIN_SIZE)
+                }
+            };
+            (table_id, entries)
+        })
+        .collect()
+}
+
+/// Proves the MSM `Σ s_k * G_k` of the given points and scalars, and returns
+/// the proof with the result.
+///
+/// The points, the scalars and the result are the public inputs of the
+/// proof, see [verify_msm]. The MSM can have at most [ec_msm_max_points]
+/// points, and an error is returned otherwise.
+pub fn prove_msm<P: SWCurveConfig, RNG: RngCore + CryptoRng>(
+    srs: &PairingSRS<BN254>,
+    points: &[Affine<P>],
+    scalars: &[P::ScalarField],
+    rng: &mut RNG,
+) -> Result<(FFAECMsmProof, Affine<P>), ProverError>
+where
+    P::BaseField: PrimeField,
+{
+    let (curve, bases, scalars_biguint) = ec_msm_inputs(points, scalars)?;
+    let n_windows = ec_msm_n_windows::<P>();
+    let n_rows = points.len() * n_windows;
+    let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+
+    let fixed_selectors = ec_scalar_mul_fixed_selectors::<Fp>(n_rows, DOMAIN_SIZE);
+    let mut witness_env = FFAECMsmWitnessBuilderEnv::create();
+    witness_env.set_fixed_selectors(fixed_selectors.to_vec());
+    let result = ec_msm_circuit(
+        &mut witness_env,
+        &curve,
+        &bases,
+        &scalars_biguint,
+        n_windows,
+    );
+    let expected = <Affine<P> as AffineRepr>::Group::msm(points, scalars)
+        .map_err(|_| ProverError::Generic("the MSM could not be computed"))?
+        .into_affine();
+    if ffa_ec_point(&expected) != result {
+        return Err(ProverError::Generic(
+            "the circuit does not compute the MSM of the inputs",
+        ));
+    }
+    for _ in n_rows..DOMAIN_SIZE {
+        witness_env.next_row();
+        ec_scalar_mul_padding_row(&mut witness_env, &curve, &result);
+    }
+
+    let lookup_tables_data: BTreeMap<FFAECLookupTable, Vec<Vec<Vec<Fp>>>> =
+        ec_msm_lookup_tables(&curve, &bases, n_windows)
+            .into_iter()
+            .map(|(table_id, entries)| (table_id, vec![entries]))
+            .collect();
+    let proof_inputs = witness_env.get_proof_inputs(DOMAIN_SIZE, lookup_tables_data);
+
+    let proof = prove::<
+        _,
+        OpeningProof,
+        BaseSponge,
+        ScalarSponge,
+        _,
+        FFA_EC_MUL_N_COLUMNS,
+        FFA_EC_MUL_N_COLUMNS,
+        0,
+        FFA_EC_MUL_N_FSEL,
+        FFAECLookupTable,
+    >(
+        domain,
+        srs,
+        &ec_msm_constraints(),
+        fixed_selectors,
+        proof_inputs,
+        rng,
+    )?;
+
+    Ok((proof, expected))
+}
+
+/// Verifies a proof given by [prove_msm] that `result` is the MSM of the
+/// given points and scalars.
+///
+/// The lookup tables, and in particular the table of the window points of
+/// the base points, are recomputed from the inputs and checked against the
+/// proof. Returns false for inputs [prove_msm] does not accept.
+pub fn verify_msm<P: SWCurveConfig>(
+    srs: &PairingSRS<BN254>,
+    points: &[Affine<P>],
+    scalars: &[P::ScalarField],
+    result: &Affine<P>,
+    proof: &FFAECMsmProof,
+) -> bool
+where
+    P::BaseField: PrimeField,
+{
+    let Ok((curve, bases, scalars_biguint)) = ec_msm_inputs(points, scalars) else {
+        return false;
+    };
+    let n_windows = ec_msm_n_windows::<P>();
+    let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+    let public_inputs = ec_msm_public_inputs(
+        &curve,
+        &ffa_ec_point(result),
+        &scalars_biguint,
+        n_windows,
+        DOMAIN_SIZE,
+    );
+    verify_with_fixed_tables::<
+        _,
+        OpeningProof,
+        BaseSponge,
+        ScalarSponge,
+        FFA_EC_MUL_N_COLUMNS,
+        FFA_EC_MUL_N_COLUMNS,
+        0,
+        FFA_EC_MUL_N_FSEL,
+        FFA_EC_MUL_NPUB_COLUMNS,
+        FFAECLookupTable,
+    >(
+        domain,
+        srs,
+        &ec_msm_constraints(),
+        ec_scalar_mul_fixed_selectors(points.len() * n_windows, DOMAIN_SIZE),
+        proof,
+        public_inputs,
+        &ec_msm_lookup_tables(&curve, &bases, n_windows),
+    )
+}
@@ -0,0 +1,542 @@
+//! Adapter between the msm circuits and the folding scheme of the `folding`
+//! crate.
+//!
+//! The traces of a circuit, e.g. many batches of FF operations, are folded
+//! into one relaxed instance, which is then decided once instead of proving
+//! each trace with [crate::prover::prove].
+//!
+//! Only the relation constraints of witness-only circuits are supported:
+//! the constraints must not use fixed selectors nor lookups, i.e. they are
+//! given by
+//! [get_relation_constraints](crate::circuit_design::ConstraintBuilderEnv::get_relation_constraints).
+//! The lookups of the circuits, e.g. the range checks of the FFA circuits,
+//! must be checked separately.
+//!
+//! The final folded instance is decided by [prove_decider], which produces a
+//! proof of the msm proof system for the relaxed relation
+//! ```text
+//! ∑_{i} α_{i} (u^2 C_{i, 0} + u C_{i, 1} + C_{i, 2}) + E = 0
+//! ```
+//! where `C_{i, k}` are the terms of degree `k` of the constraints after
+//! quadraticization. The challenges, the combiners `α_{i}` and `u` are the
+//! ones of the folded instance, and are constants of the proven constraint.
+//! The columns of the proof are the relation columns of the folded witness,
+//! followed by the columns added by quadraticization and by the error column
+//! `E`. [verify_decider] checks that the commitments of the proof are the
+//! ones of the folded instance, up to their blinders.
+//!
+//! The decider does not produce a kimchi proof. Kimchi only proves circuits
+//! built from its fixed set of gates, whereas the relaxed relation above is an
+//! arbitrary expression over the columns of the folded witness. Compiling the
+//! expression to generic gates would spread each row of the folded witness
+//! over several rows of the 15 kimchi wires, so the witness commitments of
+//! the kimchi proof would not be the commitments of the folded instance, and
+//! nothing would bind the proof to the instance the folding verifier
+//! accumulated. The msm proof system commits one polynomial per column of the
+//! folded witness, which is what [verify_decider] relies on.
+
+use crate::{
+    columns::Column,
+    expr::E,
+    lookups::LookupTableIDs,
+    proof::{Proof, ProofInputs},
+    prover::ProverError,
+    witness::Witness,
+    BN254G1Affine, Fp, BN254,
+};
+use ark_ec::AffineRepr;
+use ark_ff::{One, Zero};
+use ark_poly::{Evaluations, Radix2EvaluationDomain as R2D};
+use folding::{
+    checker::{Checker, ExtendedProvider, Provider},
+    expressions::{ExpExtension, FoldingCompatibleExpr, FoldingCompatibleExprInner},
+    instance_witness::Foldable,
+    Alphas, FoldingConfig, FoldingEnv, Instance, RelaxedInstance, RelaxedWitness, Side,
+};
+use kimchi::{
+    circuits::{
+        berkeley_columns::BerkeleyChallengeTerm,
+        domains::EvaluationDomains,
+        expr::{ExprInner, Operations, Variable},
+        gate::CurrOrNext,
+    },
+    plonk_sponge::FrSponge,
+};
+use mina_poseidon::FqSponge;
+use o1_utils::ExtendedEvaluations;
+use poly_commitment::{
+    commitment::{absorb_commitment, CommitmentCurve, PolyComm},
+    kzg::{KZGProof, PairingSRS},
+    SRS as _,
+};
+use rand::{CryptoRng, RngCore};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use std::{array, collections::BTreeMap, ops::Index};
+
+/// The challenges of the folded instances. The constraint combiner `alpha`
+/// is handled by the folding scheme.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Challenge {
+    Beta,
+    Gamma,
+    JointCombiner,
+}
+
+impl From<BerkeleyChallengeTerm> for Challenge {
+    fn from(chal: BerkeleyChallengeTerm) -> Self {
+        match chal {
+            BerkeleyChallengeTerm::Beta => Challenge::Beta,
+            BerkeleyChallengeTerm::Gamma => Challenge::Gamma,
+            BerkeleyChallengeTerm::JointCombiner => Challenge::JointCombiner,
+            BerkeleyChallengeTerm::Alpha => panic!("Alpha not allowed in folding expressions"),
+        }
+    }
+}
+
+/// Folding instance of a trace of `N_WIT` columns: the commitments to the
+/// columns, the challenges and the powers of `alpha`.
+#[derive(Debug, Clone)]
+pub struct FoldingInstance<const N_WIT: usize, G: CommitmentCurve> {
+    /// Commitments to the witness columns
+    pub commitments: [G; N_WIT],
+    /// The challenges `β`, `γ` and the joint combiner, in this order
+    pub challenges: [G::ScalarField; 3],
+    pub alphas: Alphas<G::ScalarField>,
+    /// Blinder used in the polynomial commitment scheme
+    pub blinder: G::ScalarField,
+}
+
+impl<const N_WIT: usize, G: CommitmentCurve> Foldable<G::ScalarField>
+    for FoldingInstance<N_WIT, G>
+{
+    fn combine(a: Self, b: Self, challenge: G::ScalarField) -> Self {
+        FoldingInstance {
+            commitments: array::from_fn(|i| {
+                (a.commitments[i] + b.commitments[i].mul(challenge)).into()
+            }),
+            challenges: array::from_fn(|i| a.challenges[i] + challenge * b.challenges[i]),
+            alphas: Alphas::combine(a.alphas, b.alphas, challenge),
+            blinder: a.blinder + challenge * b.blinder,
+        }
+    }
+}
+
+impl<const N_WIT: usize, G: CommitmentCurve> Instance<G> for FoldingInstance<N_WIT, G> {
+    fn to_absorb(&self) -> (Vec<G::ScalarField>, Vec<G>) {
+        let mut scalars = Vec::new();
+        scalars.extend(self.challenges);
+        scalars.extend(self.alphas.clone().powers());
+        (scalars, self.commitments.to_vec())
+    }
+
+    fn get_alphas(&self) -> &Alphas<G::ScalarField> {
+        &self.alphas
+    }
+
+    fn get_blinder(&self) -> G::ScalarField {
+        self.blinder
+    }
+}
+
+impl<const N_WIT: usize, G: CommitmentCurve> Index<Challenge> for FoldingInstance<N_WIT, G> {
+    type Output = G::ScalarField;
+
+    fn index(&self, index: Challenge) -> &Self::Output {
+        match index {
+            Challenge::Beta => &self.challenges[0],
+            Challenge::Gamma => &self.challenges[1],
+            Challenge::JointCombiner => &self.challenges[2],
+        }
+    }
+}
+
+/// The folding witness of a trace of `N_WIT` columns.
+pub type FoldingWitness<const N_WIT: usize> = Witness<N_WIT, Evaluations<Fp, R2D<Fp>>>;
+
+impl<const N_WIT: usize> Index<Column> for FoldingWitness<N_WIT> {
+    type Output = Evaluations<Fp, R2D<Fp>>;
+
+    fn index(&self, index: Column) -> &Self::Output {
+        match index {
+            Column::Relation(i) => &self.cols[i],
+            _ => panic!("Only relation columns can be folded, got {index:?}"),
+        }
+    }
+}
+
+impl<const N_WIT: usize> Index<()> for FoldingWitness<N_WIT> {
+    type Output = Evaluations<Fp, R2D<Fp>>;
+
+    fn index(&self, _index: ()) -> &Self::Output {
+        unreachable!("The folded circuits have no dynamic selectors")
+    }
+}
+
+/// Folding configuration of the witness-only msm circuits with `N_WIT`
+/// relation columns, over BN254.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MSMFoldingConfig<const N_WIT: usize>;
+
+impl<const N_WIT: usize> FoldingConfig for MSMFoldingConfig<N_WIT> {
+    type Column = Column;
+    type Selector = ();
+    type Challenge = Challenge;
+    type Curve = BN254G1Affine;
+    type Srs = PairingSRS<BN254>;
+    type Instance = FoldingInstance<N_WIT, BN254G1Affine>;
+    type Witness = FoldingWitness<N_WIT>;
+    type Structure = ();
+    type Env = MSMFoldingEnv<N_WIT>;
+}
+
+/// Folding environment of [MSMFoldingConfig].
+pub struct MSMFoldingEnv<const N_WIT: usize> {
+    instances: [FoldingInstance<N_WIT, BN254G1Affine>; 2],
+    /// The evaluations at `ω`, for both sides
+    curr_witnesses: [FoldingWitness<N_WIT>; 2],
+    /// The evaluations at `ζω`, for both sides, i.e. the current ones
+    /// shifted by one row.
+    next_witnesses: [FoldingWitness<N_WIT>; 2],
+}
+
+impl<const N_WIT: usize>
+    FoldingEnv<
+        Fp,
+        FoldingInstance<N_WIT, BN254G1Affine>,
+        FoldingWitness<N_WIT>,
+        Column,
+        Challenge,
+        (),
+    > for MSMFoldingEnv<N_WIT>
+{
+    type Structure = ();
+
+    fn new(
+        _structure: &(),
+        instances: [&FoldingInstance<N_WIT, BN254G1Affine>; 2],
+        witnesses: [&FoldingWitness<N_WIT>; 2],
+    ) -> Self {
+        let curr_witnesses = [witnesses[0].clone(), witnesses[1].clone()];
+        let mut next_witnesses = curr_witnesses.clone();
+        for side in next_witnesses.iter_mut() {
+            for col in side.cols.iter_mut() {
+                *col = col.rotate(1);
+            }
+        }
+        MSMFoldingEnv {
+            instances: [instances[0].clone(), instances[1].clone()],
+            curr_witnesses,
+            next_witnesses,
+        }
+    }
+
+    fn col(&self, col: Column, curr_or_next: CurrOrNext, side: Side) -> &[Fp] {
+        let witness = match curr_or_next {
+            CurrOrNext::Curr => &self.curr_witnesses[side as usize],
+            CurrOrNext::Next => &self.next_witnesses[side as usize],
+        };
+        &witness[col].evals
+    }
+
+    fn challenge(&self, challenge: Challenge, side: Side) -> Fp {
+        self.instances[side as usize][challenge]
+    }
+
+    fn selector(&self, _s: &(), _side: Side) -> &[Fp] {
+        unreachable!("The folded circuits have no dynamic selectors")
+    }
+}
+
+impl<const N_WIT: usize> Checker<MSMFoldingConfig<N_WIT>> for Provider<MSMFoldingConfig<N_WIT>> {}
+impl<const N_WIT: usize> Checker<MSMFoldingConfig<N_WIT>>
+    for ExtendedProvider<MSMFoldingConfig<N_WIT>>
+{
+}
+
+/// Converts the relation constraints of a circuit into folding expressions.
+pub fn folding_constraints<const N_WIT: usize>(
+    constraints: &[E<Fp>],
+) -> Vec<FoldingCompatibleExpr<MSMFoldingConfig<N_WIT>>> {
+    constraints
+        .iter()
+        .map(|c| FoldingCompatibleExpr::from(c.clone()))
+        .collect()
+}
+
+/// Converts the evaluations of a trace, e.g. given by
+/// [get_relation_witness](crate::circuit_design::WitnessBuilderEnv::get_relation_witness),
+/// into a folding witness.
+pub fn folding_witness<const N_WIT: usize>(
+    witness: Witness<N_WIT, Vec<Fp>>,
+    domain: R2D<Fp>,
+) -> FoldingWitness<N_WIT> {
+    witness
+        .into_par_iter()
+        .map(|evals| Evaluations::from_vec_and_domain(evals, domain))
+        .collect()
+}
+
+impl<const N_WIT: usize> FoldingInstance<N_WIT, BN254G1Affine> {
+    /// Commits to the witness, absorbs the commitments and squeezes the
+    /// challenges of the instance.
+    pub fn from_witness<
+        EFqSponge: FqSponge<<BN254G1Affine as AffineRepr>::BaseField, BN254G1Affine, Fp>,
+    >(
+        witness: &FoldingWitness<N_WIT>,
+        fq_sponge: &mut EFqSponge,
+        domain: R2D<Fp>,
+        srs: &PairingSRS<BN254>,
+    ) -> Self {
+        let blinder = Fp::one();
+        let blinders = PolyComm::new(vec![blinder]);
+        let commitments: Vec<_> = witness
+            .cols
+            .par_iter()
+            .map(|w| {
+                srs.commit_evaluations_custom(domain, w, &blinders)
+                    .unwrap()
+                    .commitment
+            })
+            .collect();
+        commitments
+            .iter()
+            .for_each(|c| absorb_commitment(fq_sponge, c));
+
+        let beta = fq_sponge.challenge();
+        let gamma = fq_sponge.challenge();
+        let joint_combiner = fq_sponge.challenge();
+        let alpha = fq_sponge.challenge();
+        FoldingInstance {
+            commitments: array::from_fn(|i| commitments[i].get_first_chunk()),
+            challenges: [beta, gamma, joint_combiner],
+            alphas: Alphas::new(alpha),
+            blinder,
+        }
+    }
+}
+
+/// Folded instance of a trace of `N_WIT` columns, to be decided.
+pub type FoldedInstance<const N_WIT: usize> =
+    RelaxedInstance<BN254G1Affine, FoldingInstance<N_WIT, BN254G1Affine>>;
+
+/// Folded witness of a trace of `N_WIT` columns, to be decided.
+pub type FoldedWitness<const N_WIT: usize> = RelaxedWitness<BN254G1Affine, FoldingWitness<N_WIT>>;
+
+/// Proof of the relaxed relation of a folded instance, with `N_DEC` columns:
+/// the `N_WIT` relation columns, the columns added by quadraticization and
+/// the error column.
+pub type DeciderProof<const N_DEC: usize> =
+    Proof<N_DEC, N_DEC, 0, 0, BN254G1Affine, KZGProof<BN254>, LookupTableIDs>;
+
+/// Converts the final expression of a folding scheme, as returned by
+/// [folding::FoldingScheme::new], into the constraint proven by the decider
+/// for the given folded instance.
+/// The columns added by quadraticization are mapped to the relation columns
+/// following the `N_WIT` columns of the trace, and the error term to the last
+/// column. The challenges, the combiners `α_{i}` and the scalar `u` are
+/// replaced by their values in the instance.
+pub fn decider_constraint<const N_WIT: usize>(
+    expr: &FoldingCompatibleExpr<MSMFoldingConfig<N_WIT>>,
+    instance: &FoldedInstance<N_WIT>,
+) -> E<Fp> {
+    let error_column = Column::Relation(N_WIT + instance.extended_instance.extended.len());
+    decider_expr(expr, instance, error_column)
+}
+
+fn decider_expr<const N_WIT: usize>(
+    expr: &FoldingCompatibleExpr<MSMFoldingConfig<N_WIT>>,
+    instance: &FoldedInstance<N_WIT>,
+    error_column: Column,
+) -> E<Fp> {
+    let cell = |col| {
+        E::Atom(ExprInner::Cell(Variable {
+            col,
+            row: CurrOrNext::Curr,
+        }))
+    };
+    let rec = |e: &FoldingCompatibleExpr<MSMFoldingConfig<N_WIT>>| {
+        Box::new(decider_expr(e, instance, error_column))
+    };
+    let folded = &instance.extended_instance.instance;
+    match expr {
+        FoldingCompatibleExpr::Atom(atom) => match atom {
+            FoldingCompatibleExprInner::Constant(c) => E::literal(*c),
+            FoldingCompatibleExprInner::Challenge(chal) => E::literal(folded[*chal]),
+            FoldingCompatibleExprInner::Cell(var) => E::Atom(ExprInner::Cell(*var)),
+            FoldingCompatibleExprInner::Extensions(ext) => match ext {
+                ExpExtension::U => E::literal(instance.u),
+                ExpExtension::Error => cell(error_column),
+                ExpExtension::ExtendedWitness(i) => cell(Column::Relation(N_WIT + i)),
+                ExpExtension::Alpha(i) => E::literal(
+                    folded
+                        .alphas
+                        .get(*i)
+                        .unwrap_or_else(|| panic!("Missing alpha {i} in the folded instance")),
+                ),
+                ExpExtension::Selector(()) => {
+                    unreachable!("The folded circuits have no dynamic selectors")
+                }
+            },
+        },
+        FoldingCompatibleExpr::Pow(e, p) => Operations::Pow(rec(e), *p),
+        FoldingCompatibleExpr::Add(_, _) | FoldingCompatibleExpr::Sub(_, _) => {
+            // The sums of the final expression are long chains of terms, they
+            // are balanced to keep the depth of the constraint logarithmic.
+            let mut positive = vec![];
+            let mut negative = vec![];
+            let mut stack = vec![(true, expr)];
+            while let Some((sign, e)) = stack.pop() {
+                match e {
+                    FoldingCompatibleExpr::Add(e1, e2) => {
+                        stack.push((sign, e2));
+                        stack.push((sign, e1));
+                    }
+                    FoldingCompatibleExpr::Sub(e1, e2) => {
+                        stack.push((!sign, e2));
+                        stack.push((sign, e1));
+                    }
+                    e if sign => positive.push(*rec(e)),
+                    e => negative.push(*rec(e)),
+                }
+            }
+            if negative.is_empty() {
+                balanced_sum(positive)
+            } else {
+                balanced_sum(positive) - balanced_sum(negative)
+            }
+        }
+        FoldingCompatibleExpr::Mul(e1, e2) => Operations::Mul(rec(e1), rec(e2)),
+        FoldingCompatibleExpr::Double(e) => Operations::Double(rec(e)),
+        FoldingCompatibleExpr::Square(e) => Operations::Square(rec(e)),
+    }
+}
+
+/// Sums the terms pairwise, building a tree of logarithmic depth
+fn balanced_sum(mut terms: Vec<E<Fp>>) -> E<Fp> {
+    if terms.is_empty() {
+        return E::literal(Fp::zero());
+    }
+    while terms.len() > 1 {
+        let mut iter = terms.into_iter();
+        let mut sums = vec![];
+        while let Some(a) = iter.next() {
+            sums.push(match iter.next() {
+                Some(b) => a + b,
+                None => a,
+            });
+        }
+        terms = sums;
+    }
+    terms.pop().unwrap()
+}
+
+/// The commitments the decider proof must have for the folded instance. The
+/// prover commits to all the columns with the blinder `1`, while the
+/// commitments of the instance have been folded with their own blinders.
+fn decider_commitments<const N_WIT: usize>(
+    instance: &FoldedInstance<N_WIT>,
+    srs: &PairingSRS<BN254>,
+) -> Vec<PolyComm<BN254G1Affine>> {
+    let h = PolyComm::new(vec![srs.blinding_commitment()]);
+    let columns_blinder = instance.extended_instance.instance.blinder;
+    let reblind =
+        |comm: &PolyComm<BN254G1Affine>, blinder: Fp| comm + &h.scale(Fp::one() - blinder);
+    let folded = &instance.extended_instance;
+    folded
+        .instance
+        .commitments
+        .iter()
+        .map(|comm| PolyComm::new(vec![*comm]))
+        .chain(folded.extended.iter().cloned())
+        .map(|comm| reblind(&comm, columns_blinder))
+        .chain(std::iter::once(reblind(
+            &instance.error_commitment,
+            instance.blinder,
+        )))
+        .collect()
+}
+
+/// Proves with the msm proof system that a folded instance and witness satisfy
+/// the relaxed relation given by the final expression `expr` of the folding
+/// scheme.
+/// `N_DEC` must be the number of columns of the trace, plus the number of
+/// columns added by quadraticization, plus one for the error column.
+pub fn prove_decider<
+    EFqSponge: Clone + FqSponge<<BN254G1Affine as AffineRepr>::BaseField, BN254G1Affine, Fp>,
+    EFrSponge: FrSponge<Fp>,
+    RNG: RngCore + CryptoRng,
+    const N_WIT: usize,
+    const N_DEC: usize,
+>(
+    domain: EvaluationDomains<Fp>,
+    srs: &PairingSRS<BN254>,
+    expr: &FoldingCompatibleExpr<MSMFoldingConfig<N_WIT>>,
+    instance: &FoldedInstance<N_WIT>,
+    witness: FoldedWitness<N_WIT>,
+    rng: &mut RNG,
+) -> Result<DeciderProof<N_DEC>, ProverError> {
+    let extended = witness.extended_witness.extended;
+    if N_DEC != N_WIT + extended.len() + 1 {
+        return Err(ProverError::Generic(
+            "the number of columns of the decider does not match the folded witness",
+        ));
+    }
+    let constraint = decider_constraint(expr, instance);
+    let evaluations: Witness<N_DEC, Vec<Fp>> = witness
+        .extended_witness
+        .witness
+        .cols
+        .into_iter()
+        .chain(extended.into_values())
+        .chain(std::iter::once(witness.error_vec))
+        .map(|evals| evals.evals)
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    let inputs = ProofInputs {
+        evaluations,
+        logups: BTreeMap::new(),
+    };
+    crate::prover::prove::<_, KZGProof<BN254>, EFqSponge, EFrSponge, _, N_DEC, N_DEC, 0, 0, _>(
+        domain,
+        srs,
+        &vec![constraint],
+        Box::new([]),
+        inputs,
+        rng,
+    )
+}
+
+/// Verifies a proof of [prove_decider] for the folded instance, including
+/// that the commitments of the proof are the ones of the instance.
+pub fn verify_decider<
+    EFqSponge: Clone + FqSponge<<BN254G1Affine as AffineRepr>::BaseField, BN254G1Affine, Fp>,
+    EFrSponge: FrSponge<Fp>,
+    const N_WIT: usize,
+    const N_DEC: usize,
+>(
+    domain: EvaluationDomains<Fp>,
+    srs: &PairingSRS<BN254>,
+    expr: &FoldingCompatibleExpr<MSMFoldingConfig<N_WIT>>,
+    instance: &FoldedInstance<N_WIT>,
+    proof: &DeciderProof<N_DEC>,
+) -> bool {
+    let expected_commitments = decider_commitments(instance, srs);
+    if expected_commitments.len() != N_DEC
+        || !expected_commitments
+            .iter()
+            .zip(proof.proof_comms.witness_comms.cols.iter())
+            .all(|(expected, comm)| expected == comm)
+    {
+        return false;
+    }
+    let constraint = decider_constraint(expr, instance);
+    crate::verifier::verify::<_, KZGProof<BN254>, EFqSponge, EFrSponge, N_DEC, N_DEC, 0, 0, 0, _>(
+        domain,
+        srs,
+        &vec![constraint],
+        Box::new([]),
+        proof,
+        Witness::zero_vec(domain.d1.size as usize),
+    )
+}
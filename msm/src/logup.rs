@@ -143,7 +143,9 @@ use kimchi::circuits::{
     berkeley_columns::BerkeleyChallengeTerm,
     expr::{ConstantExpr, ConstantTerm, Expr, ExprInner},
 };
-use std::{collections::BTreeMap, hash::Hash};
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{ser::SerializeAsWrap, serde_as, DeserializeAs, Same, SerializeAs};
+use std::{collections::BTreeMap, hash::Hash, marker::PhantomData};
 
 use crate::{
     columns::Column,
@@ -160,10 +162,15 @@ use crate::{
 /// The table ID is added to the random linear combination formed with the
 /// values. The combiner for the random linear combination is coined during the
 /// proving phase by the prover.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
 pub struct Logup<F, ID: LookupTableID> {
+    #[serde_as(as = "LookupTableIDAs")]
     pub(crate) table_id: ID,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub(crate) numerator: F,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
     pub(crate) value: Vec<F>,
 }
 
@@ -228,12 +235,33 @@ pub trait LookupTableID:
     fn all_variants() -> Vec<Self>;
 }
 
+/// Serializes a lookup table ID using its `u32` representation, see
+/// [LookupTableID::to_u32] and [LookupTableID::from_u32].
+pub struct LookupTableIDAs;
+
+impl<ID: LookupTableID> SerializeAs<ID> for LookupTableIDAs {
+    fn serialize_as<S: Serializer>(source: &ID, serializer: S) -> Result<S::Ok, S::Error> {
+        source.to_u32().serialize(serializer)
+    }
+}
+
+impl<'de, ID: LookupTableID> DeserializeAs<'de, ID> for LookupTableIDAs {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<ID, D::Error> {
+        let value = u32::deserialize(deserializer)?;
+        Ok(ID::from_u32(value))
+    }
+}
+
 /// A table of values that can be used for a lookup, along with the ID for the table.
-#[derive(Debug, Clone)]
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "F: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
 pub struct LookupTable<F, ID: LookupTableID> {
     /// Table ID corresponding to this table
+    #[serde_as(as = "LookupTableIDAs")]
     pub table_id: ID,
     /// Vector of values inside each entry of the table
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
     pub entries: Vec<Vec<F>>,
 }
 
@@ -241,7 +269,9 @@ pub struct LookupTable<F, ID: LookupTableID> {
 // IMPROVEME: Possible to index by a generic const?
 // The parameter N is the number of functions/looked-up values per row. It is
 // used by the PlonK polynomial IOP to compute the number of partial sums.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
 pub struct LogupWitness<F, ID: LookupTableID> {
     /// A list of functions/looked-up values.
     /// Invariant: for fixed lookup tables, the last value of the vector is the
@@ -263,6 +293,7 @@ pub struct LogupWitness<F, ID: LookupTableID> {
     /// The multiplicity polynomials; by convention, this is a vector
     /// of columns, corresponding to the `tail` of `f`. That is,
     /// `m[last] ~ f[last]`.
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
     pub m: Vec<Vec<F>>,
 }
 
@@ -284,6 +315,86 @@ pub struct LookupProof<T, ID> {
     pub(crate) fixed_tables: BTreeMap<ID, T>,
 }
 
+/// The lookup proof is serialized as a structure whose maps are indexed by the
+/// `u32` representation of the table IDs. The [SerializeAs] and
+/// [DeserializeAs] implementations allow to serialize the values with an
+/// adapter, e.g. for the evaluations, which do not implement [Serialize].
+impl<T, U: SerializeAs<T>, ID: LookupTableID> SerializeAs<LookupProof<T, ID>>
+    for LookupProof<U, ID>
+{
+    fn serialize_as<S: Serializer>(
+        source: &LookupProof<T, ID>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LookupProof", 4)?;
+        state.serialize_field(
+            "m",
+            &SerializeAsWrap::<_, BTreeMap<LookupTableIDAs, Vec<U>>>::new(&source.m),
+        )?;
+        state.serialize_field(
+            "h",
+            &SerializeAsWrap::<_, BTreeMap<LookupTableIDAs, Vec<U>>>::new(&source.h),
+        )?;
+        state.serialize_field("sum", &SerializeAsWrap::<_, U>::new(&source.sum))?;
+        state.serialize_field(
+            "fixed_tables",
+            &SerializeAsWrap::<_, BTreeMap<LookupTableIDAs, U>>::new(&source.fixed_tables),
+        )?;
+        state.end()
+    }
+}
+
+impl<'de, T, U: DeserializeAs<'de, T>, ID: LookupTableID> DeserializeAs<'de, LookupProof<T, ID>>
+    for LookupProof<U, ID>
+{
+    fn deserialize_as<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<LookupProof<T, ID>, D::Error> {
+        #[serde_as]
+        #[derive(Deserialize)]
+        #[serde(rename = "LookupProof")]
+        #[serde(bound = "U: DeserializeAs<'de, T>, ID: LookupTableID")]
+        struct LookupProofAs<T, U, ID> {
+            #[serde_as(as = "BTreeMap<LookupTableIDAs, Vec<U>>")]
+            m: BTreeMap<ID, Vec<T>>,
+            #[serde_as(as = "BTreeMap<LookupTableIDAs, Vec<U>>")]
+            h: BTreeMap<ID, Vec<T>>,
+            #[serde_as(as = "U")]
+            sum: T,
+            #[serde_as(as = "BTreeMap<LookupTableIDAs, U>")]
+            fixed_tables: BTreeMap<ID, T>,
+            #[serde(skip)]
+            _adapter: PhantomData<U>,
+        }
+
+        let LookupProofAs {
+            m,
+            h,
+            sum,
+            fixed_tables,
+            ..
+        } = LookupProofAs::<T, U, ID>::deserialize(deserializer)?;
+        Ok(LookupProof {
+            m,
+            h,
+            sum,
+            fixed_tables,
+        })
+    }
+}
+
+impl<T: Serialize, ID: LookupTableID> Serialize for LookupProof<T, ID> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        LookupProof::<Same, ID>::serialize_as(self, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, ID: LookupTableID> Deserialize<'de> for LookupProof<T, ID> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        LookupProof::<Same, ID>::deserialize_as(deserializer)
+    }
+}
+
 /// Iterator implementation to abstract the content of the structure.
 /// It can be used to iterate over the commitments (resp. the evaluations)
 /// without requiring to have a look at the inner fields.
@@ -886,11 +997,10 @@ pub mod prover {
             // - φ(ω^n) = 0
             let lookup_aggregation_evals_d1 = {
                 {
-                    for table_id in ID::all_variants().into_iter() {
+                    for (table_id, lookup_terms) in lookup_terms_evals_d1.iter() {
                         let mut acc = G::ScalarField::zero();
                         for i in 0..domain.d1.size as usize {
                             // φ(1) = 0
-                            let lookup_terms = lookup_terms_evals_d1.get(&table_id).unwrap();
                             acc = lookup_terms.iter().fold(acc, |acc, lte| acc + lte[i]);
                         }
                         // Sanity check to verify that the accumulator ends up being zero.
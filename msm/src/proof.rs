@@ -1,27 +1,71 @@
 use crate::{
-    logup::{LookupProof, LookupTableID},
+    columns::Column,
+    logup::{LookupProof, LookupTableID, LookupTableIDAs},
     lookups::{LookupTableIDs, LookupWitness},
     witness::Witness,
     LogupWitness, DOMAIN_SIZE,
 };
-use ark_ff::PrimeField;
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{Field, One, PrimeField, Zero};
 use kimchi::{
     circuits::{
         domains::EvaluationDomains,
         expr::{ColumnEvaluations, ExprError},
+        gate::{CircuitGate, Connect},
+        polynomials::generic::GenericGateSpec,
+        wires::{Wire, COLUMNS},
     },
     curve::KimchiCurve,
     proof::PointEvaluations,
 };
-use poly_commitment::{commitment::PolyComm, OpenProof};
+use o1_utils::serialization::SerdeAs;
+use poly_commitment::{
+    commitment::{CommitmentCurve, PolyComm},
+    OpenProof,
+};
 use rand::thread_rng;
+use serde::{ser::SerializeTuple, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{ser::SerializeAsWrap, serde_as, DeserializeAs, SerializeAs};
 use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Serializes the evaluations of a (non-chunked) polynomial at ζ and ζω. The
+/// serialization of [PointEvaluations] provided by kimchi is only available
+/// for chunked evaluations.
+pub struct PointEvaluationsAs;
+
+impl<F: ark_serialize::CanonicalSerialize> SerializeAs<PointEvaluations<F>> for PointEvaluationsAs {
+    fn serialize_as<S: Serializer>(
+        source: &PointEvaluations<F>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&SerializeAsWrap::<_, SerdeAs>::new(&source.zeta))?;
+        tuple.serialize_element(&SerializeAsWrap::<_, SerdeAs>::new(&source.zeta_omega))?;
+        tuple.end()
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl<'de, F: ark_serialize::CanonicalDeserialize> DeserializeAs<'de, PointEvaluations<F>>
+    for PointEvaluationsAs
+{
+    fn deserialize_as<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<PointEvaluations<F>, D::Error> {
+        let (zeta, zeta_omega) = <(SerdeAs, SerdeAs)>::deserialize_as(deserializer)?;
+        Ok(PointEvaluations { zeta, zeta_omega })
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct ProofInputs<const N_WIT: usize, F: PrimeField, ID: LookupTableID> {
     /// Actual values w_i of the witness columns. "Evaluations" as in
     /// evaluations of polynomial P_w that interpolates w_i.
+    #[serde_as(as = "Witness<N_WIT, Vec<SerdeAs>>")]
     pub evaluations: Witness<N_WIT, Vec<F>>,
+    #[serde_as(as = "BTreeMap<LookupTableIDAs, _>")]
     pub logups: BTreeMap<ID, LogupWitness<F, ID>>,
 }
 
@@ -63,7 +107,9 @@ impl<const N_WIT: usize, F: PrimeField, ID: LookupTableID> Default for ProofInpu
     }
 }
 
-#[derive(Debug, Clone)]
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "F: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
 // TODO Should public input and fixed selectors evaluations be here?
 pub struct ProofEvaluations<
     const N_WIT: usize,
@@ -74,12 +120,16 @@ pub struct ProofEvaluations<
     ID: LookupTableID,
 > {
     /// Witness evaluations, including public inputs
+    #[serde_as(as = "Witness<N_WIT, PointEvaluationsAs>")]
     pub(crate) witness_evals: Witness<N_WIT, PointEvaluations<F>>,
     /// Evaluations of fixed selectors.
+    #[serde_as(as = "Box<[PointEvaluationsAs; N_FSEL]>")]
     pub(crate) fixed_selectors_evals: Box<[PointEvaluations<F>; N_FSEL]>,
     /// Logup argument evaluations
+    #[serde_as(as = "Option<LookupProof<PointEvaluationsAs, ID>>")]
     pub(crate) logup_evals: Option<LookupProof<PointEvaluations<F>, ID>>,
     /// Evaluation of Z_H(ζ) (t_0(X) + ζ^n t_1(X) + ...) at ζω.
+    #[serde_as(as = "SerdeAs")]
     pub(crate) ft_eval1: F,
 }
 
@@ -146,7 +196,8 @@ impl<
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct ProofCommitments<const N_WIT: usize, G: KimchiCurve, ID: LookupTableID> {
     /// Commitments to the N columns of the circuits, also called the 'witnesses'.
     /// If some columns are considered as public inputs, it is counted in the witness.
@@ -159,7 +210,11 @@ pub struct ProofCommitments<const N_WIT: usize, G: KimchiCurve, ID: LookupTableI
     pub(crate) t_comm: PolyComm<G>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "OpeningProof: Serialize",
+    deserialize = "OpeningProof: Deserialize<'de>"
+))]
 pub struct Proof<
     const N_WIT: usize,
     const N_REL: usize,
@@ -173,3 +228,198 @@ pub struct Proof<
     pub(crate) proof_evals: ProofEvaluations<N_WIT, N_REL, N_DSEL, N_FSEL, G::ScalarField, ID>,
     pub(crate) opening_proof: OpeningProof,
 }
+
+impl<
+        const N_WIT: usize,
+        const N_REL: usize,
+        const N_DSEL: usize,
+        const N_FSEL: usize,
+        G: KimchiCurve,
+        OpeningProof: OpenProof<G>,
+        ID: LookupTableID,
+    > Proof<N_WIT, N_REL, N_DSEL, N_FSEL, G, OpeningProof, ID>
+{
+    /// Returns the commitments to the given witness columns, e.g. the
+    /// columns containing the inputs and outputs of the msm circuit. They are
+    /// the public IO another circuit uses to refer to the values proven by
+    /// the msm proof, see [commitments_to_public_input].
+    pub fn column_commitments(&self, columns: &[Column]) -> Result<Vec<PolyComm<G>>, ColumnError> {
+        columns
+            .iter()
+            .map(|col| match *col {
+                Column::Relation(i) if i < N_REL => Ok(self.proof_comms.witness_comms[i].clone()),
+                Column::DynamicSelector(i) if i < N_DSEL => {
+                    Ok(self.proof_comms.witness_comms[N_REL + i].clone())
+                }
+                Column::Relation(_) | Column::DynamicSelector(_) => {
+                    Err(ColumnError::OutOfBounds(*col))
+                }
+                col => Err(ColumnError::NotAWitnessColumn(col)),
+            })
+            .collect()
+    }
+}
+
+/// Errors that can arise when reading the commitments of a proof
+#[derive(Error, Debug, Clone)]
+pub enum ColumnError {
+    #[error("the column {0:?} is out of bounds")]
+    OutOfBounds(Column),
+
+    #[error("the column {0:?} is not a witness column")]
+    NotAWitnessColumn(Column),
+}
+
+/// Encodes the commitments as the public inputs of a circuit over the base
+/// field of `G`, like a kimchi circuit over the other curve of a cycle, which
+/// can then use the commitments of the msm proof it offloads the MSMs to.
+/// Each chunk is given by its affine coordinates, and the point at infinity
+/// by `(0, 0)`, which is not on the curve.
+pub fn commitments_to_public_input<G: CommitmentCurve>(comms: &[PolyComm<G>]) -> Vec<G::BaseField> {
+    comms
+        .iter()
+        .flat_map(|comm| comm.chunks.iter())
+        .flat_map(|chunk| {
+            let (x, y) = chunk
+                .to_coordinates()
+                .unwrap_or((G::BaseField::zero(), G::BaseField::zero()));
+            [x, y]
+        })
+        .collect()
+}
+
+/// The number of rows used by [extend_commitments_gadget] per point.
+pub const COMMITMENTS_GADGET_ROWS_PER_POINT: usize = 4;
+
+/// Extends the kimchi circuit `gates`, over the base field of the curve `P`,
+/// with the generic gates checking that the public inputs of the rows
+/// `public_start..public_start + 2 * number_of_points`, encoded by
+/// [commitments_to_public_input], are points of the curve or the encoding
+/// `(0, 0)` of the point at infinity. The public inputs can then be used by
+/// the rest of the circuit as the commitments of the msm proof.
+///
+/// The gates are added at the end of the circuit, using
+/// [COMMITMENTS_GADGET_ROWS_PER_POINT] rows per point, and the witness is
+/// given by [commitments_gadget_witness]. For a point `(x, y)`, the gadget
+/// uses a boolean `z`, set to `1` for the point at infinity, and checks
+/// ```text
+/// z (z - 1) = 0
+/// (1 - z) (y^2 - x^3 - b) = 0
+/// z x = 0
+/// z y = 0
+/// ```
+/// Only the curves with `a = 0`, like BN254 and the Pasta curves, are
+/// supported.
+pub fn extend_commitments_gadget<P: SWCurveConfig>(
+    gates: &mut Vec<CircuitGate<P::BaseField>>,
+    public_start: usize,
+    number_of_points: usize,
+) where
+    P::BaseField: PrimeField,
+{
+    assert!(
+        P::COEFF_A.is_zero(),
+        "Only the curves with a = 0 are supported"
+    );
+    let one = P::BaseField::one();
+    let zero = P::BaseField::zero();
+    for i in 0..number_of_points {
+        let r = gates.len();
+        // x * x = x^2 and y * y = y^2
+        let mul = GenericGateSpec::Mul {
+            output_coeff: None,
+            mul_coeff: None,
+        };
+        gates.push(CircuitGate::create_generic_gadget(
+            Wire::for_row(r),
+            mul.clone(),
+            Some(mul.clone()),
+        ));
+        // x^2 * x = x^3 and z * z = z
+        gates.push(CircuitGate::create_generic_gadget(
+            Wire::for_row(r + 1),
+            mul.clone(),
+            Some(mul),
+        ));
+        // y^2 - x^3 - b = e and z * x = 0
+        gates.push(CircuitGate::create_generic(
+            Wire::for_row(r + 2),
+            [
+                one,
+                -one,
+                -one,
+                zero,
+                -P::COEFF_B,
+                zero,
+                zero,
+                zero,
+                one,
+                zero,
+            ],
+        ));
+        // e - z * e = 0 and z * y = 0
+        gates.push(CircuitGate::create_generic(
+            Wire::for_row(r + 3),
+            [zero, one, zero, -one, zero, zero, zero, zero, one, zero],
+        ));
+
+        let x = public_start + 2 * i;
+        let y = x + 1;
+        let cycles: [&[(usize, usize)]; 7] = [
+            &[(x, 0), (r, 0), (r, 1), (r + 1, 1), (r + 2, 4)],
+            &[(y, 0), (r, 3), (r, 4), (r + 3, 4)],
+            &[(r, 2), (r + 1, 0)],
+            &[(r, 5), (r + 2, 0)],
+            &[(r + 1, 2), (r + 2, 1)],
+            &[
+                (r + 1, 3),
+                (r + 1, 4),
+                (r + 1, 5),
+                (r + 2, 3),
+                (r + 3, 0),
+                (r + 3, 3),
+            ],
+            &[(r + 2, 2), (r + 3, 1)],
+        ];
+        for cells in cycles {
+            for pair in cells.windows(2) {
+                gates.connect_cell_pair(pair[0], pair[1]);
+            }
+        }
+    }
+}
+
+/// Fills the witness of the gates added by [extend_commitments_gadget] from
+/// the row `start_row`, for the public inputs `public_input` given by
+/// [commitments_to_public_input].
+pub fn commitments_gadget_witness<P: SWCurveConfig>(
+    witness: &mut [Vec<P::BaseField>; COLUMNS],
+    start_row: usize,
+    public_input: &[P::BaseField],
+) where
+    P::BaseField: PrimeField,
+{
+    for (i, point) in public_input.chunks(2).enumerate() {
+        let (x, y) = (point[0], point[1]);
+        let z = if x.is_zero() && y.is_zero() {
+            P::BaseField::one()
+        } else {
+            P::BaseField::zero()
+        };
+        let (x2, y2) = (x.square(), y.square());
+        let x3 = x2 * x;
+        let e = y2 - x3 - P::COEFF_B;
+        let r = start_row + COMMITMENTS_GADGET_ROWS_PER_POINT * i;
+        let rows = [
+            [x, x, x2, y, y, y2],
+            [x2, x, x3, z, z, z],
+            [y2, x3, e, z, x, P::BaseField::zero()],
+            [z, e, P::BaseField::zero(), z, y, P::BaseField::zero()],
+        ];
+        for (j, row) in rows.iter().enumerate() {
+            for (col, value) in row.iter().enumerate() {
+                witness[col][r + j] = *value;
+            }
+        }
+    }
+}
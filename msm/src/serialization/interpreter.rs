@@ -7,7 +7,7 @@ use std::marker::PhantomData;
 use crate::{
     circuit_design::{
         capabilities::write_column_const, ColAccessCap, ColWriteCap, HybridCopyCap, LookupCap,
-        MultiRowReadCap,
+        MultiRowReadCap, WitnessBuilderEnv,
     },
     columns::ColumnIndexer,
     logup::LookupTableID,
@@ -752,6 +752,46 @@ pub fn serialization_circuit<
     }
 }
 
+/// Builds the witness of the serialization circuit as
+/// [serialization_circuit], one row per field element, with the rows built
+/// in parallel. The coefficient given as input to the multiplication of a
+/// row is computed beforehand, so that the rows are independent.
+pub fn serialization_circuit_par<
+    F: PrimeField,
+    Ff: PrimeField,
+    const N_WIT: usize,
+    const N_REL: usize,
+    const N_DSEL: usize,
+    const N_FSEL: usize,
+>(
+    input_chal: Ff,
+    field_elements: Vec<[F; 3]>,
+    fixed_selectors: Vec<Vec<F>>,
+) -> WitnessBuilderEnv<F, SerializationColumn, N_WIT, N_REL, N_DSEL, N_FSEL, LookupTable<Ff>> {
+    // The results of the multiplications, per row
+    let mut prev_rows: Vec<Ff> = Vec::with_capacity(field_elements.len());
+    let rows: Vec<([F; 3], Ff)> = field_elements
+        .into_iter()
+        .enumerate()
+        .map(|(i, limbs)| {
+            let coeff_input = if i == 0 {
+                Ff::zero()
+            } else {
+                prev_rows[i - (1 << (i.ilog2()))]
+            };
+            prev_rows.push(input_chal * coeff_input);
+            (limbs, coeff_input)
+        })
+        .collect();
+
+    let (env, _) =
+        WitnessBuilderEnv::from_rows_par(&rows, fixed_selectors, |env, (limbs, coeff_input)| {
+            deserialize_field_element(env, limbs.map(Into::into));
+            multiplication_circuit(env, input_chal, *coeff_input, false);
+        });
+    env
+}
+
 /// Builds fixed selectors for serialization circuit.
 ///
 ///
@@ -797,7 +837,7 @@ mod tests {
             column::SerializationColumn,
             interpreter::{
                 build_selectors, deserialize_field_element, limb_decompose_ff,
-                multiplication_circuit, serialization_circuit,
+                multiplication_circuit, serialization_circuit, serialization_circuit_par,
             },
             lookups::LookupTable,
             N_INTERMEDIATE_LIMBS,
@@ -1028,4 +1068,44 @@ mod tests {
             domain_size,
         );
     }
+
+    #[test]
+    /// The witness of the serialization circuit built row-parallel is the
+    /// same as the one built sequentially.
+    pub fn test_serialization_circuit_par() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 6;
+        let fixed_selectors = build_selectors(domain_size).to_vec();
+
+        let input_chal: Ff1 = <Ff1 as UniformRand>::rand(&mut rng);
+        let field_elements: Vec<[Fp; 3]> = (0..domain_size)
+            .map(|_| limb_decompose_ff::<Fp, Ff1, 88, 3>(&<Ff1 as UniformRand>::rand(&mut rng)))
+            .collect();
+
+        let mut witness_env: SerializationWitnessBuilderEnv = WitnessBuilderEnv::create();
+        witness_env.set_fixed_selectors(fixed_selectors.clone());
+        serialization_circuit(
+            &mut witness_env,
+            input_chal,
+            field_elements.clone(),
+            domain_size,
+        );
+        let witness_env_par: SerializationWitnessBuilderEnv =
+            serialization_circuit_par(input_chal, field_elements, fixed_selectors);
+
+        // The sequential circuit ends with an empty row.
+        assert_eq!(
+            witness_env_par.witness,
+            witness_env.witness[..domain_size].to_vec()
+        );
+        assert_eq!(
+            witness_env_par.lookup_multiplicities,
+            witness_env.lookup_multiplicities
+        );
+        assert_eq!(witness_env_par.lookup_reads, witness_env.lookup_reads);
+        assert_eq!(
+            witness_env_par.runtime_lookup_writes,
+            witness_env.runtime_lookup_writes
+        );
+    }
 }
@@ -1,7 +1,13 @@
 /// Generic test runners for prover/verifier.
 use crate::{
-    expr::E, logup::LookupTableID, lookups::LookupTableIDs, proof::ProofInputs, prover::prove,
-    verifier::verify, witness::Witness, BaseSponge, Fp, OpeningProof, ScalarSponge, BN254,
+    expr::E,
+    logup::LookupTableID,
+    lookups::LookupTableIDs,
+    proof::{Proof, ProofInputs},
+    prover::prove,
+    verifier::verify,
+    witness::Witness,
+    BaseSponge, Fp, OpeningProof, ScalarSponge, BN254,
 };
 use ark_ec::AffineRepr;
 use kimchi::circuits::domains::EvaluationDomains;
@@ -71,6 +77,36 @@ pub fn test_completeness_generic<
     rng: &mut RNG,
 ) where
     RNG: RngCore + CryptoRng,
+{
+    test_completeness_generic_with_public_inputs::<N_WIT, N_REL, N_DSEL, N_FSEL, 0, LT, _>(
+        constraints,
+        fixed_selectors,
+        proof_inputs,
+        Witness::zero_vec(domain_size),
+        domain_size,
+        rng,
+    )
+}
+
+/// Same as [test_completeness_generic], with the first `NPUB` columns of the
+/// witness being public inputs, given to the verifier.
+pub fn test_completeness_generic_with_public_inputs<
+    const N_WIT: usize,
+    const N_REL: usize,
+    const N_DSEL: usize,
+    const N_FSEL: usize,
+    const NPUB: usize,
+    LT: LookupTableID,
+    RNG,
+>(
+    constraints: Vec<E<Fp>>,
+    fixed_selectors: Box<[Vec<Fp>; N_FSEL]>,
+    proof_inputs: ProofInputs<N_WIT, Fp, LT>,
+    public_inputs: Witness<NPUB, Vec<Fp>>,
+    domain_size: usize,
+    rng: &mut RNG,
+) where
+    RNG: RngCore + CryptoRng,
 {
     let domain = EvaluationDomains::<Fp>::create(domain_size).unwrap();
 
@@ -130,15 +166,30 @@ pub fn test_completeness_generic<
         }
     }
 
-    let verifies =
-        verify::<_, OpeningProof, BaseSponge, ScalarSponge, N_WIT, N_REL, N_DSEL, N_FSEL, 0, LT>(
-            domain,
-            &srs,
-            &constraints,
-            fixed_selectors,
-            &proof,
-            Witness::zero_vec(domain_size),
-        );
+    // The proof is verified after a round-trip through its serialized form,
+    // like when it is sent to a separate verifier.
+    let proof: Proof<N_WIT, N_REL, N_DSEL, N_FSEL, _, OpeningProof, LT> =
+        rmp_serde::from_slice(&rmp_serde::to_vec(&proof).unwrap()).unwrap();
+
+    let verifies = verify::<
+        _,
+        OpeningProof,
+        BaseSponge,
+        ScalarSponge,
+        N_WIT,
+        N_REL,
+        N_DSEL,
+        N_FSEL,
+        NPUB,
+        LT,
+    >(
+        domain,
+        &srs,
+        &constraints,
+        fixed_selectors,
+        &proof,
+        public_inputs,
+    );
     assert!(verifies)
 }
 
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::{
+        logup::LookupTable,
         lookups::{Lookup, LookupTableIDs},
         proof::ProofInputs,
         prover::prove,
@@ -76,4 +77,30 @@ mod tests {
         // FIXME: At the moment, it does verify. It should not. We are missing constraints.
         assert!(!verifies);
     }
+
+    #[test]
+    fn test_serialization_proof_inputs_and_lookup_tables() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain = EvaluationDomains::<Fp>::create(1 << 8).unwrap();
+
+        // The inputs can be given to a prover running in another process
+        let inputs: ProofInputs<LOOKUP_TEST_N_COL, Fp, LookupTableIDs> =
+            ProofInputs::random(domain);
+        let bytes = rmp_serde::to_vec(&inputs).unwrap();
+        let deserialized_inputs: ProofInputs<LOOKUP_TEST_N_COL, Fp, LookupTableIDs> =
+            rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(inputs, deserialized_inputs);
+
+        let table = LookupTable {
+            table_id: LookupTableIDs::Custom(42),
+            entries: (0..16)
+                .map(|_| vec![Fp::rand(&mut rng), Fp::rand(&mut rng)])
+                .collect(),
+        };
+        let json = serde_json::to_string(&table).unwrap();
+        let deserialized_table: LookupTable<Fp, LookupTableIDs> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(table.table_id, deserialized_table.table_id);
+        assert_eq!(table.entries, deserialized_table.entries);
+    }
 }
@@ -399,4 +399,161 @@ mod tests {
         // TODO: Refactorize code in prover to handle a degug or add an adversarial prover.
         // test_soundness_generic(constraints, witness, domain_size, &mut rng);
     }
+
+    // The opening proofs of several msm proofs can be checked at once, after
+    // the rest of the verification, and their commitments given as public
+    // inputs to a circuit over the base field.
+    #[test]
+    fn test_deferred_verification() {
+        use crate::{
+            lookups::LookupTableIDs,
+            proof::{commitments_to_public_input, ColumnError, ProofInputs},
+            prover::prove,
+            verifier::to_batch,
+            BaseSponge, OpeningProof, ScalarSponge, BN254,
+        };
+        use kimchi::{circuits::domains::EvaluationDomains, groupmap::GroupMap};
+        use poly_commitment::{commitment::CommitmentCurve, kzg::PairingSRS, OpenProof};
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        const N: usize = 2;
+        let domain_size = 1 << 8;
+        let domain = EvaluationDomains::<Fp>::create(domain_size).unwrap();
+        let srs: PairingSRS<BN254> = crate::precomputed_srs::get_bn254_srs(domain);
+
+        // X_{1} = X_{0} * X_{0}
+        let constraints = {
+            let x0 = expr::curr_cell::<Fp>(Column::Relation(0));
+            let x1 = expr::curr_cell::<Fp>(Column::Relation(1));
+            vec![x0.clone() * x0 - x1]
+        };
+
+        let proofs: Vec<_> = (0..2)
+            .map(|_| {
+                let x0: Vec<Fp> = (0..domain_size).map(|_| Fp::rand(&mut rng)).collect();
+                let x1 = x0.iter().map(|x| x.square()).collect();
+                let proof_inputs = ProofInputs::<N, Fp, LookupTableIDs> {
+                    evaluations: Witness {
+                        cols: Box::new([x0, x1]),
+                    },
+                    logups: Default::default(),
+                };
+                prove::<_, OpeningProof, BaseSponge, ScalarSponge, _, N, N, 0, 0, _>(
+                    domain,
+                    &srs,
+                    &constraints,
+                    Box::new([]),
+                    proof_inputs,
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let mut batches: Vec<_> = proofs
+            .iter()
+            .map(|proof| {
+                to_batch::<_, OpeningProof, BaseSponge, ScalarSponge, N, N, 0, 0, 0, _>(
+                    domain,
+                    &srs,
+                    &constraints,
+                    Box::new([]),
+                    proof,
+                    Witness::zero_vec(domain_size),
+                )
+            })
+            .collect();
+        let group_map = <crate::BN254G1Affine as CommitmentCurve>::Map::setup();
+        assert!(<OpeningProof as OpenProof<_>>::verify(
+            &srs,
+            &group_map,
+            &mut batches,
+            &mut rng
+        ));
+
+        let comms = proofs[0]
+            .column_commitments(&[Column::Relation(1)])
+            .unwrap();
+        let public_input = commitments_to_public_input(&comms);
+        assert_eq!(public_input.len(), 2);
+        assert_eq!(
+            comms[0].chunks[0],
+            crate::BN254G1Affine::of_coordinates(public_input[0], public_input[1])
+        );
+
+        assert!(matches!(
+            proofs[0].column_commitments(&[Column::Relation(N)]),
+            Err(ColumnError::OutOfBounds(Column::Relation(N)))
+        ));
+        assert!(matches!(
+            proofs[0].column_commitments(&[Column::FixedSelector(0)]),
+            Err(ColumnError::NotAWitnessColumn(Column::FixedSelector(0)))
+        ));
+    }
+
+    // The commitments given as public inputs to a kimchi circuit are checked
+    // to be points of the curve by the gadget. The Pallas points are used, as
+    // their coordinates are in the scalar field of Vesta.
+    #[test]
+    fn test_commitments_gadget() {
+        use crate::proof::{
+            commitments_gadget_witness, commitments_to_public_input, extend_commitments_gadget,
+            COMMITMENTS_GADGET_ROWS_PER_POINT,
+        };
+        use ark_ff::Zero;
+        use kimchi::{
+            circuits::{
+                gate::CircuitGate,
+                polynomials::generic::GenericGateSpec,
+                wires::{Wire, COLUMNS},
+            },
+            prover_index::testing::new_index_for_test,
+        };
+        use mina_curves::pasta::{Fp as VestaScalar, Pallas, PallasParameters, Vesta};
+        use poly_commitment::PolyComm;
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let comms = [
+            PolyComm::new(vec![Pallas::rand(&mut rng), Pallas::rand(&mut rng)]),
+            PolyComm::new(vec![Pallas::identity()]),
+        ];
+        let public_input = commitments_to_public_input(&comms);
+        let public = public_input.len();
+        let number_of_points = public / 2;
+
+        let mut gates: Vec<_> = (0..public)
+            .map(|row| {
+                CircuitGate::<VestaScalar>::create_generic_gadget(
+                    Wire::for_row(row),
+                    GenericGateSpec::Pub,
+                    None,
+                )
+            })
+            .collect();
+        extend_commitments_gadget::<PallasParameters>(&mut gates, 0, number_of_points);
+        let rows = public + COMMITMENTS_GADGET_ROWS_PER_POINT * number_of_points;
+        assert_eq!(gates.len(), rows);
+
+        let mut witness: [Vec<VestaScalar>; COLUMNS] =
+            std::array::from_fn(|_| vec![VestaScalar::zero(); rows]);
+        witness[0][..public].copy_from_slice(&public_input);
+        commitments_gadget_witness::<PallasParameters>(&mut witness, public, &public_input);
+
+        let index = new_index_for_test::<Vesta>(gates, public);
+        index.verify(&witness, &public_input).unwrap();
+
+        // A point that is not on the curve is rejected
+        let mut invalid_public_input = public_input.clone();
+        invalid_public_input[1] += VestaScalar::one();
+        let mut invalid_witness = witness.clone();
+        invalid_witness[0][..public].copy_from_slice(&invalid_public_input);
+        commitments_gadget_witness::<PallasParameters>(
+            &mut invalid_witness,
+            public,
+            &invalid_public_input,
+        );
+        assert!(index
+            .verify(&invalid_witness, &invalid_public_input)
+            .is_err());
+    }
 }
@@ -2,13 +2,14 @@
 #![allow(clippy::boxed_local)]
 
 use crate::logup::LookupTableID;
-use ark_ff::{Field, Zero};
+use ark_ff::{Field, One, Zero};
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Evaluations, Polynomial,
     Radix2EvaluationDomain as R2D,
 };
 use rand::thread_rng;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::collections::BTreeMap;
 
 use kimchi::{
     circuits::{
@@ -31,6 +32,10 @@ use poly_commitment::{
 
 use crate::{expr::E, proof::Proof, witness::Witness};
 
+/// Verifies the msm proof, checking the opening proof immediately. See
+/// [to_batch] to defer the check of the opening proof.
+///
+/// The fixed lookup tables are not checked, see [verify_with_fixed_tables].
 pub fn verify<
     G: KimchiCurve,
     OpeningProof: OpenProof<G>,
@@ -50,6 +55,170 @@ pub fn verify<
     proof: &Proof<N_WIT, N_REL, N_DSEL, N_FSEL, G, OpeningProof, ID>,
     public_inputs: Witness<NPUB, Vec<G::ScalarField>>,
 ) -> bool
+where
+    OpeningProof::SRS: Sync,
+{
+    verify_with_fixed_tables::<
+        G,
+        OpeningProof,
+        EFqSponge,
+        EFrSponge,
+        N_WIT,
+        N_REL,
+        N_DSEL,
+        N_FSEL,
+        NPUB,
+        ID,
+    >(
+        domain,
+        srs,
+        constraints,
+        fixed_selectors,
+        proof,
+        public_inputs,
+        &BTreeMap::new(),
+    )
+}
+
+/// Verifies the msm proof as [verify], and checks that the fixed lookup
+/// tables `fixed_tables` are the ones committed in the proof. The entries of
+/// a table are given row by row, and must fill the domain.
+///
+/// A fixed table which depends on the inputs of the statement, e.g. a table
+/// of multiples of a base point, must be given here: the prover would
+/// otherwise be free to commit to any table.
+pub fn verify_with_fixed_tables<
+    G: KimchiCurve,
+    OpeningProof: OpenProof<G>,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    const N_WIT: usize,
+    const N_REL: usize,
+    const N_DSEL: usize,
+    const N_FSEL: usize,
+    const NPUB: usize,
+    ID: LookupTableID,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &OpeningProof::SRS,
+    constraints: &Vec<E<G::ScalarField>>,
+    fixed_selectors: Box<[Vec<G::ScalarField>; N_FSEL]>,
+    proof: &Proof<N_WIT, N_REL, N_DSEL, N_FSEL, G, OpeningProof, ID>,
+    public_inputs: Witness<NPUB, Vec<G::ScalarField>>,
+    fixed_tables: &BTreeMap<ID, Vec<Vec<G::ScalarField>>>,
+) -> bool
+where
+    OpeningProof::SRS: Sync,
+{
+    let Some(batch) = to_batch_with_fixed_tables::<
+        G,
+        OpeningProof,
+        EFqSponge,
+        EFrSponge,
+        N_WIT,
+        N_REL,
+        N_DSEL,
+        N_FSEL,
+        NPUB,
+        ID,
+    >(
+        domain,
+        srs,
+        constraints,
+        fixed_selectors,
+        proof,
+        public_inputs,
+        fixed_tables,
+    ) else {
+        return false;
+    };
+
+    let group_map = G::Map::setup();
+    OpeningProof::verify(srs, &group_map, &mut [batch], &mut thread_rng())
+}
+
+/// Checks the msm proof, except the opening proof, and returns the batch of
+/// evaluations the opening proof must be checked against.
+///
+/// The opening proof is the expensive part of the verification. Deferring it
+/// allows a host to accumulate the batches of several msm proofs, and of
+/// the kimchi proofs it offloads the MSMs from, and to check all of them at
+/// once using [OpenProof::verify].
+///
+/// Panics if the public inputs do not match the proof.
+pub fn to_batch<
+    'a,
+    G: KimchiCurve,
+    OpeningProof: OpenProof<G>,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    const N_WIT: usize,
+    const N_REL: usize,
+    const N_DSEL: usize,
+    const N_FSEL: usize,
+    const NPUB: usize,
+    ID: LookupTableID,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &OpeningProof::SRS,
+    constraints: &Vec<E<G::ScalarField>>,
+    fixed_selectors: Box<[Vec<G::ScalarField>; N_FSEL]>,
+    proof: &'a Proof<N_WIT, N_REL, N_DSEL, N_FSEL, G, OpeningProof, ID>,
+    public_inputs: Witness<NPUB, Vec<G::ScalarField>>,
+) -> BatchEvaluationProof<'a, G, EFqSponge, OpeningProof>
+where
+    OpeningProof::SRS: Sync,
+{
+    to_batch_with_fixed_tables::<
+        G,
+        OpeningProof,
+        EFqSponge,
+        EFrSponge,
+        N_WIT,
+        N_REL,
+        N_DSEL,
+        N_FSEL,
+        NPUB,
+        ID,
+    >(
+        domain,
+        srs,
+        constraints,
+        fixed_selectors,
+        proof,
+        public_inputs,
+        &BTreeMap::new(),
+    )
+    .expect("The public inputs must match the proof")
+}
+
+/// Checks the msm proof as [to_batch], and that the fixed lookup tables
+/// `fixed_tables` are the ones committed in the proof, see
+/// [verify_with_fixed_tables].
+///
+/// Returns `None` if the public inputs or the fixed tables do not match the
+/// proof.
+pub fn to_batch_with_fixed_tables<
+    'a,
+    G: KimchiCurve,
+    OpeningProof: OpenProof<G>,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    const N_WIT: usize,
+    const N_REL: usize,
+    const N_DSEL: usize,
+    const N_FSEL: usize,
+    const NPUB: usize,
+    ID: LookupTableID,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &OpeningProof::SRS,
+    constraints: &Vec<E<G::ScalarField>>,
+    fixed_selectors: Box<[Vec<G::ScalarField>; N_FSEL]>,
+    proof: &'a Proof<N_WIT, N_REL, N_DSEL, N_FSEL, G, OpeningProof, ID>,
+    public_inputs: Witness<NPUB, Vec<G::ScalarField>>,
+    fixed_tables: &BTreeMap<ID, Vec<Vec<G::ScalarField>>>,
+) -> Option<BatchEvaluationProof<'a, G, EFqSponge, OpeningProof>>
 where
     OpeningProof::SRS: Sync,
 {
@@ -113,7 +282,14 @@ where
     };
 
     let public_input_comms: Witness<NPUB, PolyComm<G>> = {
-        let comm = |poly: &DensePolynomial<G::ScalarField>| srs.commit_non_hiding(poly, 1);
+        // The prover masks the commitments to the columns with a blinder
+        // equal to one, which is reproduced here.
+        let blinders = PolyComm {
+            chunks: vec![G::ScalarField::one()],
+        };
+        let comm = |poly: &DensePolynomial<G::ScalarField>| {
+            srs.commit_custom(poly, 1, &blinders).unwrap().commitment
+        };
         (&public_input_polys)
             .into_par_iter()
             .map(comm)
@@ -125,7 +301,9 @@ where
         "Number of public inputs exceeds number of witness columns"
     );
     for i in 0..NPUB {
-        assert!(public_input_comms.cols[i] == proof_comms.witness_comms.cols[i]);
+        if public_input_comms.cols[i] != proof_comms.witness_comms.cols[i] {
+            return None;
+        }
     }
 
     ////////////////////////////////////////////////////////////////////////////
@@ -156,13 +334,36 @@ where
             // FIXME @volhovm it seems that the verifier does not
             // actually check that the fixed tables used in the proof
             // are the fixed tables defined in the code. In other
-            // words, all the currently used "fixed" tables are
-            // runtime and can be chosen freely by the prover.
+            // words, the "fixed" tables which are not given in
+            // `fixed_tables` are runtime and can be chosen freely by
+            // the prover.
 
             // To generate the challenges
             let joint_combiner = fq_sponge.challenge();
             let beta = fq_sponge.challenge();
 
+            // The fixed tables given by the verifier must be the ones
+            // committed by the prover, combined as vectors with the joint
+            // combiner.
+            for (table_id, entries) in fixed_tables.iter() {
+                let combined_entries: Vec<G::ScalarField> = entries
+                    .iter()
+                    .map(|entry| {
+                        entry
+                            .iter()
+                            .rev()
+                            .fold(G::ScalarField::zero(), |acc, y| acc * joint_combiner + y)
+                    })
+                    .collect();
+                let comm = srs.commit_evaluations_non_hiding(
+                    domain.d1,
+                    &Evaluations::from_vec_and_domain(combined_entries, domain.d1),
+                );
+                if logup_comms.fixed_tables.get(table_id) != Some(&comm) {
+                    return None;
+                }
+            }
+
             // And now, we absorb the commitments to the other polynomials
             logup_comms.h.values().for_each(|comms| {
                 comms
@@ -178,6 +379,8 @@ where
             // And at the end, the aggregation
             absorb_commitment(&mut fq_sponge, &logup_comms.sum);
             (Some(joint_combiner), beta)
+        } else if !fixed_tables.is_empty() {
+            return None;
         } else {
             (None, G::ScalarField::zero())
         }
@@ -320,7 +523,7 @@ where
         combined_inner_product(&v, &u, es.as_slice())
     };
 
-    let batch = BatchEvaluationProof {
+    Some(BatchEvaluationProof {
         sponge: fq_sponge_before_coms_and_evaluations,
         evaluations: coms_and_evaluations,
         evaluation_points: vec![zeta, zeta_omega],
@@ -328,8 +531,5 @@ where
         evalscale: u,
         opening: opening_proof,
         combined_inner_product,
-    };
-
-    let group_map = G::Map::setup();
-    OpeningProof::verify(srs, &group_map, &mut [batch], &mut thread_rng())
+    })
 }
@@ -3,6 +3,8 @@ use ark_poly::{Evaluations, Radix2EvaluationDomain};
 use folding::{instance_witness::Foldable, Witness as FoldingWitnessT};
 use poly_commitment::commitment::CommitmentCurve;
 use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{DeserializeAs, Same, SerializeAs};
 use std::ops::Index;
 
 /// The witness columns used by a gate of the MSM circuits.
@@ -38,6 +40,44 @@ impl<const N_WIT: usize, T> TryFrom<Vec<T>> for Witness<N_WIT, T> {
     }
 }
 
+/// The witness is serialized as a sequence of `N_WIT` columns. The
+/// [SerializeAs] and [DeserializeAs] implementations allow to serialize the
+/// columns with an adapter, e.g. `Witness<N_WIT, Vec<SerdeAs>>` for the
+/// evaluations, which do not implement [Serialize].
+impl<const N_WIT: usize, T, U: SerializeAs<T>> SerializeAs<Witness<N_WIT, T>>
+    for Witness<N_WIT, U>
+{
+    fn serialize_as<S: Serializer>(
+        source: &Witness<N_WIT, T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        <Box<[U; N_WIT]>>::serialize_as(&source.cols, serializer)
+    }
+}
+
+impl<'de, const N_WIT: usize, T, U: DeserializeAs<'de, T>> DeserializeAs<'de, Witness<N_WIT, T>>
+    for Witness<N_WIT, U>
+{
+    fn deserialize_as<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Witness<N_WIT, T>, D::Error> {
+        let cols = <Box<[U; N_WIT]>>::deserialize_as(deserializer)?;
+        Ok(Witness { cols })
+    }
+}
+
+impl<const N_WIT: usize, T: Serialize> Serialize for Witness<N_WIT, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Witness::<N_WIT, Same>::serialize_as(self, serializer)
+    }
+}
+
+impl<'de, const N_WIT: usize, T: Deserialize<'de>> Deserialize<'de> for Witness<N_WIT, T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Witness::<N_WIT, Same>::deserialize_as(deserializer)
+    }
+}
+
 impl<const N_WIT: usize, T> Index<usize> for Witness<N_WIT, T> {
     type Output = T;
 
@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mina_curves::pasta::Fp;
+use mvpoly::{kronecker, monomials::Sparse, MVPoly};
+
+// Compare the naive multiplication and the Kronecker substitution on dense
+// polynomials of increasing degree. The substitution wins once the number of
+// term products outgrows the size of the FFTs, which
+// `kronecker::mul_strategy` estimates.
+fn bench_mul<const N: usize, const D: usize>(c: &mut Criterion) {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1: Sparse<Fp, N, D> = unsafe { Sparse::random(&mut rng, None) };
+    let p2: Sparse<Fp, N, D> = unsafe { Sparse::random(&mut rng, None) };
+    let bounds = kronecker::bounds(&p1.monomials, &p2.monomials);
+    let strategy = kronecker::mul_strategy(
+        p1.monomials.len(),
+        p2.monomials.len(),
+        kronecker::substitution_size(&bounds),
+    );
+
+    let mut group = c.benchmark_group(format!("sparse_mul_{N}_variables"));
+    let parameter = format!("degree_{D}_{strategy:?}");
+    group.bench_with_input(BenchmarkId::new("naive", &parameter), &(), |b, _| {
+        b.iter(|| black_box(&p1).mul_naive(black_box(&p2)))
+    });
+    group.bench_with_input(BenchmarkId::new("kronecker", &parameter), &(), |b, _| {
+        b.iter(|| black_box(&p1).mul_kronecker(black_box(&p2)))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_mul::<2, 2>,
+    bench_mul::<2, 8>,
+    bench_mul::<2, 32>,
+    bench_mul::<3, 2>,
+    bench_mul::<3, 4>,
+    bench_mul::<3, 8>,
+    bench_mul::<3, 16>,
+    bench_mul::<5, 2>,
+    bench_mul::<5, 4>,
+    bench_mul::<5, 6>,
+);
+criterion_main!(benches);
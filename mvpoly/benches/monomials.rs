@@ -61,12 +61,26 @@ fn bench_sparse_eval(c: &mut Criterion) {
     });
 }
 
+fn bench_sparse_eval_many(c: &mut Criterion) {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1: Sparse<Fp, 10, 3> = unsafe { Sparse::random(&mut rng, None) };
+    let points: Vec<[Fp; 10]> = (0..1 << 10)
+        .map(|_| std::array::from_fn(|_| Fp::rand(&mut rng)))
+        .collect();
+    c.bench_function("sparse_eval_many", |b: &mut Bencher| {
+        b.iter(|| {
+            let _ = black_box(&p1).eval_many(black_box(&points));
+        })
+    });
+}
+
 criterion_group!(
     benches,
     bench_sparse_add,
     bench_sparse_mul,
     bench_sparse_neg,
     bench_sparse_sub,
-    bench_sparse_eval
+    bench_sparse_eval,
+    bench_sparse_eval_many
 );
 criterion_main!(benches);
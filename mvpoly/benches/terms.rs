@@ -0,0 +1,48 @@
+use ark_ff::UniformRand;
+use criterion::{black_box, criterion_group, criterion_main, Bencher, Criterion};
+use mina_curves::pasta::Fp;
+use mvpoly::{terms::TermList, MVPoly};
+
+// Using 300 variables, with max degree 3 for the operands, to reflect the
+// constraints of real circuits. The dense representations can not be used
+// with so many variables.
+fn bench_term_list_mul(c: &mut Criterion) {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1: TermList<Fp, 300, 6> = unsafe { TermList::random(&mut rng, Some(3)) };
+    let p2: TermList<Fp, 300, 6> = unsafe { TermList::random(&mut rng, Some(3)) };
+    c.bench_function("term_list_mul", |b: &mut Bencher| {
+        b.iter(|| {
+            let _ = black_box(&p1) * black_box(&p2);
+        })
+    });
+}
+
+fn bench_term_list_add(c: &mut Criterion) {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1: TermList<Fp, 300, 6> = unsafe { TermList::random(&mut rng, Some(3)) };
+    let p2: TermList<Fp, 300, 6> = unsafe { TermList::random(&mut rng, Some(3)) };
+    c.bench_function("term_list_add", |b: &mut Bencher| {
+        b.iter(|| {
+            let _ = black_box(&p1) + black_box(&p2);
+        })
+    });
+}
+
+fn bench_term_list_eval(c: &mut Criterion) {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1: TermList<Fp, 300, 6> = unsafe { TermList::random(&mut rng, Some(3)) };
+    let x: [Fp; 300] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    c.bench_function("term_list_eval", |b: &mut Bencher| {
+        b.iter(|| {
+            let _ = black_box(&p1).eval(black_box(&x));
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_term_list_mul,
+    bench_term_list_add,
+    bench_term_list_eval
+);
+criterion_main!(benches);
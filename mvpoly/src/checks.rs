@@ -0,0 +1,49 @@
+//! This module contains assertions on the const generics of the polynomials,
+//! evaluated at compile time.
+//!
+//! The sum `D1 + D2` cannot be used as a const generic on stable Rust, so the
+//! degree of a product cannot be computed by the type system. Instead, the
+//! operations changing the number of variables or the maximum degree take the
+//! ones of the result as const generics, and check that they are large enough.
+//! The checks are associated constants: referencing them in a function makes
+//! the compilation fail when the function is instantiated with invalid
+//! parameters, instead of panicking at runtime. Their value is always `true`.
+
+/// Assertions on the maximum degrees `D1` and `D2` of the operands and `D` of
+/// the result
+pub(crate) struct DegreeCheck<const D1: usize, const D2: usize, const D: usize>;
+
+impl<const D1: usize, const D2: usize, const D: usize> DegreeCheck<D1, D2, D> {
+    /// The polynomials of degree at most `D1` and `D2` have degree at most `D`
+    pub(crate) const MAX: bool = {
+        assert!(
+            D1 <= D && D2 <= D,
+            "The maximum degree of the result is lower than the maximum degree of the operands"
+        );
+        true
+    };
+
+    /// The product of polynomials of degree at most `D1` and `D2` has degree at
+    /// most `D`
+    pub(crate) const SUM: bool = {
+        assert!(
+            D1 + D2 <= D,
+            "The maximum degree of the result is lower than the sum of the maximum degrees of the operands"
+        );
+        true
+    };
+}
+
+/// Assertion on the number of variables `N` of a polynomial converted into a
+/// polynomial in `M` variables
+pub(crate) struct ArityCheck<const N: usize, const M: usize>;
+
+impl<const N: usize, const M: usize> ArityCheck<N, M> {
+    pub(crate) const EXTEND: bool = {
+        assert!(
+            N <= M,
+            "The number of variables of the result is lower than the number of variables of the operand"
+        );
+        true
+    };
+}
@@ -0,0 +1,175 @@
+//! This module contains the conversion of the expressions of [kimchi] into
+//! multivariate polynomials.
+//!
+//! An expression is flattened into any type implementing [MVPoly]. The cells of
+//! the expression are mapped to the variables of the polynomial by a function
+//! given by the user, and the cached sub-expressions are only converted once.
+
+use ark_ff::PrimeField;
+use kimchi::circuits::expr::{
+    CacheId, ConstantExpr, ConstantExprInner, ConstantTerm, Expr, ExprInner, Variable,
+};
+use std::collections::HashMap;
+
+use crate::MVPoly;
+
+/// Compute `p^e` with a square-and-multiply
+pub fn pow<F: PrimeField, const N: usize, const D: usize, T: MVPoly<F, N, D>>(p: T, e: u64) -> T {
+    let mut res = T::one();
+    for i in (0..u64::BITS - e.leading_zeros()).rev() {
+        res = res.clone() * res;
+        if (e >> i) & 1 == 1 {
+            res = res * p.clone();
+        }
+    }
+    res
+}
+
+/// Convert the constant expression `c` into a constant polynomial.
+///
+/// # Panics
+///
+/// The challenges and the constants of kimchi (the endomorphism coefficient
+/// and the MDS matrix) are not supported, as their value is not part of the
+/// expression.
+pub fn from_constant<
+    F: PrimeField,
+    const N: usize,
+    const D: usize,
+    T: MVPoly<F, N, D>,
+    ChallengeTerm,
+>(
+    c: &ConstantExpr<F, ChallengeTerm>,
+) -> T {
+    use kimchi::circuits::expr::Operations::*;
+    let convert = |c| from_constant::<F, N, D, T, ChallengeTerm>(c);
+    match c {
+        Atom(ConstantExprInner::Challenge(_)) => {
+            unimplemented!("Challenges are not supposed to be used in this context for now")
+        }
+        Atom(ConstantExprInner::Constant(ConstantTerm::EndoCoefficient)) => {
+            unimplemented!(
+                "The constant EndoCoefficient is not supposed to be used in this context"
+            )
+        }
+        Atom(ConstantExprInner::Constant(ConstantTerm::Mds { .. })) => {
+            unimplemented!("The constant Mds is not supposed to be used in this context")
+        }
+        Atom(ConstantExprInner::Constant(ConstantTerm::Literal(c))) => T::from(*c),
+        Add(c1, c2) => convert(c1) + convert(c2),
+        Sub(c1, c2) => convert(c1) - convert(c2),
+        Mul(c1, c2) => convert(c1) * convert(c2),
+        Square(c) => {
+            let p = convert(c);
+            p.clone() * p
+        }
+        Double(c) => convert(c).double(),
+        Pow(c, e) => pow(convert(c), *e),
+        Cache(_, c) => convert(c),
+        IfFeature(_c, _t, _f) => {
+            unimplemented!("The method is supposed to be used for generic multivariate expressions, not tied to a specific use case like Kimchi with this constructor")
+        }
+    }
+}
+
+/// Convert the expression `expr` into a multivariate polynomial. The cell
+/// `var` of the expression becomes the variable of index `column_map(var)`,
+/// which must be lower than `N`.
+///
+/// A sub-expression cached with [kimchi::circuits::expr::Operations::Cache] is converted the first time
+/// it is met, and its polynomial is reused for the next occurrences of its
+/// [CacheId].
+///
+/// # Panics
+///
+/// The constants that are not supported by [from_constant], the Lagrange
+/// basis and the vanishing polynomial, which are not polynomials in the
+/// cells, and the feature flags are not supported.
+pub fn from_expr<
+    F: PrimeField,
+    const N: usize,
+    const D: usize,
+    T: MVPoly<F, N, D>,
+    Column,
+    ChallengeTerm,
+>(
+    expr: &Expr<ConstantExpr<F, ChallengeTerm>, Column>,
+    column_map: impl Fn(&Variable<Column>) -> usize,
+) -> T {
+    // The variables are not built with `MVPoly::from_variable`, as its
+    // conversion of the columns depends on the representation
+    from_expr_with_variables(expr, |var| {
+        let idx = column_map(var);
+        assert!(idx < N, "The variable x_{idx} is out of bounds");
+        let mut p = T::zero();
+        p.add_monomial(std::array::from_fn(|i| usize::from(i == idx)), F::one());
+        p
+    })
+}
+
+/// Convert the expression `expr` into a multivariate polynomial, where the
+/// cells are converted into polynomials by `variable`. See [from_expr].
+pub fn from_expr_with_variables<
+    F: PrimeField,
+    const N: usize,
+    const D: usize,
+    T: MVPoly<F, N, D>,
+    Column,
+    ChallengeTerm,
+>(
+    expr: &Expr<ConstantExpr<F, ChallengeTerm>, Column>,
+    variable: impl Fn(&Variable<Column>) -> T,
+) -> T {
+    let mut cache = HashMap::new();
+    from_expr_with_cache(expr, &variable, &mut cache)
+}
+
+fn from_expr_with_cache<
+    F: PrimeField,
+    const N: usize,
+    const D: usize,
+    T: MVPoly<F, N, D>,
+    Column,
+    ChallengeTerm,
+>(
+    expr: &Expr<ConstantExpr<F, ChallengeTerm>, Column>,
+    variable: &impl Fn(&Variable<Column>) -> T,
+    cache: &mut HashMap<CacheId, T>,
+) -> T {
+    use kimchi::circuits::expr::Operations::*;
+    macro_rules! convert {
+        ($e:expr) => {
+            from_expr_with_cache($e, variable, cache)
+        };
+    }
+    match expr {
+        Atom(ExprInner::Constant(c)) => from_constant(c),
+        Atom(ExprInner::Cell(var)) => variable(var),
+        Atom(ExprInner::UnnormalizedLagrangeBasis(_)) => {
+            unimplemented!("Not used in this context")
+        }
+        Atom(ExprInner::VanishesOnZeroKnowledgeAndPreviousRows) => {
+            unimplemented!("Not used in this context")
+        }
+        Add(e1, e2) => convert!(e1) + convert!(e2),
+        Sub(e1, e2) => convert!(e1) - convert!(e2),
+        Mul(e1, e2) => convert!(e1) * convert!(e2),
+        Double(e) => convert!(e).double(),
+        Square(e) => {
+            let p = convert!(e);
+            p.clone() * p
+        }
+        Pow(e, k) => pow(convert!(e), *k),
+        Cache(id, e) => {
+            if let Some(p) = cache.get(id) {
+                return p.clone();
+            }
+            let p = convert!(e);
+            cache.insert(*id, p.clone());
+            p
+        }
+        IfFeature(_c, _t, _f) => {
+            unimplemented!("The method is supposed to be used for generic multivariate expressions, not tied to a specific use case like Kimchi with this constructor")
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! This module implements the multiplication of multivariate polynomials by
+//! Kronecker substitution.
+//!
+//! The monomial `X_{1}^{e_1} ... X_{N}^{e_N}` is mapped to the univariate
+//! monomial `Y^{e_1 s_1 + ... + e_N s_N}`, where the strides `s_i` are the
+//! products `B_1 ... B_{i - 1}` of the bounds `B_j`, the bound `B_j` being
+//! larger than the degree of `X_j` in the product. No carry can happen between
+//! the digits of the exponents of `Y`, therefore the product of the univariate
+//! polynomials, computed with FFTs, can be mapped back to the product of the
+//! multivariate polynomials.
+//!
+//! The univariate polynomials have `B_1 ... B_N` coefficients, which grows
+//! exponentially with `N`. The substitution is worth it for dense polynomials
+//! with few variables, and the term-by-term multiplication is faster for
+//! sparse polynomials. [mul_strategy] chooses between both.
+
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+
+/// The maximum number of coefficients of the univariate polynomials of the
+/// substitution
+pub const KRONECKER_MAX_SIZE: usize = 1 << 22;
+
+/// The cost of multiplying two terms in the naive multiplication, relatively to
+/// a butterfly of the FFTs. It includes the update of the map of the monomials
+/// of the result. It has been measured with the benchmark `kronecker`.
+pub const NAIVE_TERM_COST: usize = 3;
+
+/// The algorithms to multiply two multivariate polynomials
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MulStrategy {
+    /// Multiply each term of the first polynomial by each term of the second
+    Naive,
+    /// Multiply the univariate polynomials given by a Kronecker substitution
+    Kronecker,
+}
+
+fn max_degrees<'a, F: 'a, const N: usize>(
+    terms: impl IntoIterator<Item = (&'a [usize; N], &'a F)>,
+) -> [usize; N] {
+    let mut degrees = [0; N];
+    for (exponents, _) in terms {
+        for (d, e) in degrees.iter_mut().zip(exponents.iter()) {
+            *d = (*d).max(*e);
+        }
+    }
+    degrees
+}
+
+/// The bounds `B_i` of the substitution, i.e. one more than the sum of the
+/// maximum degrees of each variable in both polynomials
+pub fn bounds<'a, F: 'a, const N: usize>(
+    p: impl IntoIterator<Item = (&'a [usize; N], &'a F)>,
+    q: impl IntoIterator<Item = (&'a [usize; N], &'a F)>,
+) -> [usize; N] {
+    let (degrees_p, degrees_q) = (max_degrees(p), max_degrees(q));
+    std::array::from_fn(|i| degrees_p[i] + degrees_q[i] + 1)
+}
+
+/// The number of coefficients of the univariate polynomials for the given
+/// bounds, or `None` if it overflows
+pub fn substitution_size<const N: usize>(bounds: &[usize; N]) -> Option<usize> {
+    bounds.iter().try_fold(1usize, |acc, b| acc.checked_mul(*b))
+}
+
+/// Choose how to multiply two polynomials with `nb_terms1` and `nb_terms2`
+/// terms, whose Kronecker substitution has `size` coefficients (`None` if it
+/// overflows).
+///
+/// The naive multiplication costs `nb_terms1 * nb_terms2` term products, and
+/// the substitution three FFTs of the next power of two of `size`.
+pub fn mul_strategy(nb_terms1: usize, nb_terms2: usize, size: Option<usize>) -> MulStrategy {
+    let size = match size {
+        Some(size) if size <= KRONECKER_MAX_SIZE => size.next_power_of_two(),
+        _ => return MulStrategy::Naive,
+    };
+    let fft_cost = 3 * size * (size.trailing_zeros() as usize).max(1) / 2 + size;
+    let naive_cost = nb_terms1
+        .saturating_mul(nb_terms2)
+        .saturating_mul(NAIVE_TERM_COST);
+    if fft_cost < naive_cost {
+        MulStrategy::Kronecker
+    } else {
+        MulStrategy::Naive
+    }
+}
+
+/// Multiply the polynomials given by their terms `p` and `q` by Kronecker
+/// substitution. The exponents of the variables of the product must be
+/// lower than `bounds`, which can be computed with [self::bounds].
+///
+/// The terms of the result have non-zero coefficients.
+///
+/// # Panics
+///
+/// Panics if the substitution has more than [KRONECKER_MAX_SIZE]
+/// coefficients, or if the field does not have a multiplicative subgroup of
+/// the size of the substitution.
+pub fn mul<'a, F: PrimeField, const N: usize>(
+    p: impl IntoIterator<Item = (&'a [usize; N], &'a F)>,
+    q: impl IntoIterator<Item = (&'a [usize; N], &'a F)>,
+    bounds: &[usize; N],
+) -> Vec<([usize; N], F)> {
+    let size = substitution_size(bounds)
+        .filter(|size| *size <= KRONECKER_MAX_SIZE)
+        .expect("The Kronecker substitution is too large");
+    let domain = Radix2EvaluationDomain::<F>::new(size)
+        .expect("The field does not have a subgroup large enough for the substitution");
+
+    let mut strides = [1; N];
+    for i in 1..N {
+        strides[i] = strides[i - 1] * bounds[i - 1];
+    }
+    let encode = |exponents: &[usize; N]| -> usize {
+        exponents
+            .iter()
+            .zip(strides.iter())
+            .map(|(e, s)| e * s)
+            .sum()
+    };
+    let to_univariate = |terms: &mut dyn Iterator<Item = (&'a [usize; N], &'a F)>| {
+        let mut coeffs = vec![F::zero(); domain.size()];
+        for (exponents, coeff) in terms {
+            coeffs[encode(exponents)] += coeff;
+        }
+        coeffs
+    };
+    let mut a = to_univariate(&mut p.into_iter());
+    let mut b = to_univariate(&mut q.into_iter());
+
+    domain.fft_in_place(&mut a);
+    domain.fft_in_place(&mut b);
+    a.iter_mut().zip(b.iter()).for_each(|(a, b)| *a *= b);
+    domain.ifft_in_place(&mut a);
+
+    // The exponents of the product are smaller than the bounds, therefore the
+    // coefficients after the size of the substitution are zero
+    a.into_iter()
+        .take(size)
+        .enumerate()
+        .filter(|(_, coeff)| !coeff.is_zero())
+        .map(|(mut k, coeff)| {
+            let exponents = std::array::from_fn(|i| {
+                let e = k % bounds[i];
+                k /= bounds[i];
+                e
+            });
+            (exponents, coeff)
+        })
+        .collect()
+}
@@ -6,22 +6,41 @@
 //! - `prime`: a representation based on a mapping from variables to prime
 //! numbers. This representation is unmaintained for now. We leave it
 //! for interested users.
+//! - `terms`: a sorted list of terms, where each monomial only stores its
+//! variables. It is meant for sparse polynomials with many variables.
+//!
+//! The polynomials can be serialized with serde, and printed and parsed in a
+//! canonical textual form, see [serialization].
+//!
+//! The operations changing the maximum degree or the number of variables, like
+//! `widening_mul`, are checked at compile time, see the module `checks`.
 //!
 //! "Expressions", as defined in the [kimchi] crate, can be converted into a
-//! multi-variate polynomial using the `from_expr` method.
+//! multi-variate polynomial using the `from_expr` method, or with
+//! [expr::from_expr] to map the cells to the variables explicitly.
 
 use ark_ff::PrimeField;
-use kimchi::circuits::expr::{
-    ConstantExpr, ConstantExprInner, ConstantTerm, Expr, ExprInner, Operations, Variable,
-};
+use kimchi::circuits::expr::{ConstantExpr, ConstantExprInner, Expr, Operations, Variable};
 use rand::RngCore;
+use rayon::prelude::*;
 use std::collections::HashMap;
 
+pub(crate) mod checks;
+pub mod expr;
+pub mod kronecker;
 pub mod monomials;
 pub mod pbt;
 pub mod prime;
+pub mod serialization;
+pub mod terms;
 pub mod utils;
 
+pub use expr::from_expr;
+
+/// Number of terms from which [MVPoly::eval] sums the terms in parallel. Below
+/// this threshold, spawning the tasks costs more than evaluating the terms.
+pub const PAR_EVAL_MIN_TERMS: usize = 256;
+
 /// Generic trait to represent a multi-variate polynomial
 pub trait MVPoly<F: PrimeField, const N: usize, const D: usize>:
     // Addition
@@ -82,8 +101,21 @@ pub trait MVPoly<F: PrimeField, const N: usize, const D: usize>:
     ///
     /// This is a dummy implementation. A cache can be used for the monomials to
     /// speed up the computation.
+    /// The terms are evaluated in parallel if there are at least
+    /// [PAR_EVAL_MIN_TERMS] of them.
     fn eval(&self, x: &[F; N]) -> F;
 
+    /// Evaluate the polynomial at each point of `points`, in parallel.
+    ///
+    /// This is the method to use to evaluate a polynomial over the rows of a
+    /// witness.
+    fn eval_many(&self, points: &[[F; N]]) -> Vec<F>
+    where
+        Self: Sync,
+    {
+        points.par_iter().map(|x| self.eval(x)).collect()
+    }
+
     /// Build the univariate polynomial `x_i` from the variable `i`.
     /// The conversion into the type `usize` is unspecified by this trait. It
     /// is left to the trait implementation.
@@ -99,49 +131,10 @@ pub trait MVPoly<F: PrimeField, const N: usize, const D: usize>:
     /// N should be at least `8 = 2 * 4`).
     fn from_variable<Column: Into<usize>>(var: Variable<Column>, offset_next_row: Option<usize>) -> Self;
 
+    /// Build a constant polynomial from a constant expression. See
+    /// [crate::expr::from_constant].
     fn from_constant<ChallengeTerm: Clone>(op: Operations<ConstantExprInner<F, ChallengeTerm>>) -> Self {
-        use kimchi::circuits::expr::Operations::*;
-        match op {
-            Atom(op_const) => {
-                match op_const {
-                    ConstantExprInner::Challenge(_) => {
-                        unimplemented!("Challenges are not supposed to be used in this context for now")
-                    }
-                    ConstantExprInner::Constant(ConstantTerm::EndoCoefficient) => {
-                        unimplemented!(
-                            "The constant EndoCoefficient is not supposed to be used in this context"
-                        )
-                    }
-                    ConstantExprInner::Constant(ConstantTerm::Mds {
-                        row: _row,
-                        col: _col,
-                    }) => {
-                        unimplemented!("The constant Mds is not supposed to be used in this context")
-                    }
-                    ConstantExprInner::Constant(ConstantTerm::Literal(c)) => Self::from(c),
-                }
-            }
-            Add(c1, c2) => Self::from_constant(*c1) + Self::from_constant(*c2),
-            Sub(c1, c2) => Self::from_constant(*c1) - Self::from_constant(*c2),
-            Mul(c1, c2) => Self::from_constant(*c1) * Self::from_constant(*c2),
-            Square(c) => Self::from_constant(*c.clone()) * Self::from_constant(*c),
-            Double(c1) => Self::from_constant(*c1).double(),
-            Pow(c, e) => {
-                // FIXME: dummy implementation
-                let p = Self::from_constant(*c);
-                let mut result = p.clone();
-                for _ in 0..e {
-                    result = result.clone() * p.clone();
-                }
-                result
-            }
-            Cache(_c, _) => {
-                unimplemented!("The method is supposed to be used for generic multivariate expressions, not tied to a specific use case like Kimchi with this constructor")
-            }
-            IfFeature(_c, _t, _f) => {
-                unimplemented!("The method is supposed to be used for generic multivariate expressions, not tied to a specific use case like Kimchi with this constructor")
-            }
-        }
+        expr::from_constant(&op)
     }
 
     /// Build a value from an expression.
@@ -158,63 +151,14 @@ pub trait MVPoly<F: PrimeField, const N: usize, const D: usize>:
     /// support the case where the "next row" is used. In this case, the type
     /// parameter `N` must include this offset (i.e. if 4 variables are in ued,
     /// N should be at least `8 = 2 * 4`).
-    fn from_expr<Column: Into<usize>, ChallengeTerm: Clone>(expr: Expr<ConstantExpr<F, ChallengeTerm>, Column>, offset_next_row: Option<usize>) -> Self {
-        use kimchi::circuits::expr::Operations::*;
-
-        match expr {
-            Atom(op_const) => {
-                match op_const {
-                    ExprInner::UnnormalizedLagrangeBasis(_) => {
-                        unimplemented!("Not used in this context")
-                    }
-                    ExprInner::VanishesOnZeroKnowledgeAndPreviousRows => {
-                        unimplemented!("Not used in this context")
-                    }
-                    ExprInner::Constant(c) => Self::from_constant(c),
-                    ExprInner::Cell(var) => {
-                        Self::from_variable::<Column>(var, offset_next_row)
-                    }
-                }
-            }
-            Add(e1, e2) => {
-                let p1 = Self::from_expr::<Column, ChallengeTerm>(*e1, offset_next_row);
-                let p2 = Self::from_expr::<Column, ChallengeTerm>(*e2, offset_next_row);
-                p1 + p2
-            }
-            Sub(e1, e2) => {
-                let p1 = Self::from_expr::<Column, ChallengeTerm>(*e1, offset_next_row);
-                let p2 = Self::from_expr::<Column, ChallengeTerm>(*e2, offset_next_row);
-                p1 - p2
-            }
-            Mul(e1, e2) => {
-                let p1 = Self::from_expr::<Column, ChallengeTerm>(*e1, offset_next_row);
-                let p2 = Self::from_expr::<Column, ChallengeTerm>(*e2, offset_next_row);
-                p1 * p2
-            }
-            Double(p) => {
-                let p = Self::from_expr::<Column, ChallengeTerm>(*p, offset_next_row);
-                p.double()
-            }
-            Square(p) => {
-                let p = Self::from_expr::<Column, ChallengeTerm>(*p, offset_next_row);
-                p.clone() * p.clone()
-            }
-            Pow(c, e) => {
-                // FIXME: dummy implementation
-                let p = Self::from_expr::<Column, ChallengeTerm>(*c, offset_next_row);
-                let mut result = p.clone();
-                for _ in 0..e {
-                    result = result.clone() * p.clone();
-                }
-                result
-            }
-            Cache(_c, _) => {
-                unimplemented!("The method is supposed to be used for generic multivariate expressions, not tied to a specific use case like Kimchi with this constructor")
-            }
-            IfFeature(_c, _t, _f) => {
-                unimplemented!("The method is supposed to be used for generic multivariate expressions, not tied to a specific use case like Kimchi with this constructor")
-            }
-        }
+    ///
+    /// The cached sub-expressions are converted once. See
+    /// [crate::expr::from_expr] to give the mapping of the variables
+    /// explicitly.
+    fn from_expr<Column: Into<usize> + Clone, ChallengeTerm: Clone>(expr: Expr<ConstantExpr<F, ChallengeTerm>, Column>, offset_next_row: Option<usize>) -> Self {
+        expr::from_expr_with_variables(&expr, |var| {
+            Self::from_variable::<Column>(var.clone(), offset_next_row)
+        })
     }
 
     /// Returns true if the polynomial is homogeneous (of degree `D`).
@@ -2,6 +2,7 @@ use ark_ff::{One, PrimeField, Zero};
 use kimchi::circuits::{expr::Variable, gate::CurrOrNext};
 use num_integer::binomial;
 use rand::{Rng, RngCore};
+use rayon::prelude::*;
 use std::{
     collections::HashMap,
     fmt::Debug,
@@ -9,9 +10,11 @@ use std::{
 };
 
 use crate::{
+    checks::{ArityCheck, DegreeCheck},
+    kronecker::{self, MulStrategy},
     prime,
     utils::{compute_indices_nested_loop, naive_prime_factors, PrimeNumberGenerator},
-    MVPoly,
+    MVPoly, PAR_EVAL_MIN_TERMS,
 };
 
 /// Represents a multivariate polynomial in `N` variables with coefficients in
@@ -101,33 +104,17 @@ impl<const N: usize, const D: usize, F: PrimeField> Debug for Sparse<F, N, D> {
 impl<const N: usize, const D: usize, F: PrimeField> Mul for Sparse<F, N, D> {
     type Output = Self;
 
+    /// Multiply term by term, or by Kronecker substitution for dense
+    /// polynomials, see [kronecker::mul_strategy].
     fn mul(self, other: Self) -> Self {
-        let mut monomials = HashMap::new();
-        self.monomials.iter().for_each(|(exponents1, coeff1)| {
-            other
-                .monomials
-                .clone()
-                .iter()
-                .for_each(|(exponents2, coeff2)| {
-                    let mut exponents = [0; N];
-                    for i in 0..N {
-                        exponents[i] = exponents1[i] + exponents2[i];
-                    }
-                    monomials
-                        .entry(exponents)
-                        .and_modify(|c| *c += *coeff1 * *coeff2)
-                        .or_insert(*coeff1 * *coeff2);
-                })
-        });
-        // Remove monomials with zero coefficients
-        let monomials: HashMap<[usize; N], F> = monomials
-            .into_iter()
-            .filter(|(_, coeff)| !coeff.is_zero())
-            .collect();
-        if monomials.is_empty() {
-            Self::zero()
-        } else {
-            Self { monomials }
+        let bounds = kronecker::bounds(&self.monomials, &other.monomials);
+        match kronecker::mul_strategy(
+            self.monomials.len(),
+            other.monomials.len(),
+            kronecker::substitution_size(&bounds),
+        ) {
+            MulStrategy::Naive => self.mul_naive(&other),
+            MulStrategy::Kronecker => self.mul_kronecker(&other),
         }
     }
 }
@@ -215,6 +202,150 @@ impl<const N: usize, const D: usize, F: PrimeField> Zero for Sparse<F, N, D> {
     }
 }
 
+impl<const N: usize, const D: usize, F: PrimeField> Sparse<F, N, D> {
+    /// Multiply each term of `self` by each term of `other`
+    pub fn mul_naive(&self, other: &Self) -> Self {
+        let mut monomials = HashMap::new();
+        self.monomials.iter().for_each(|(exponents1, coeff1)| {
+            other.monomials.iter().for_each(|(exponents2, coeff2)| {
+                let mut exponents = [0; N];
+                for i in 0..N {
+                    exponents[i] = exponents1[i] + exponents2[i];
+                }
+                monomials
+                    .entry(exponents)
+                    .and_modify(|c| *c += *coeff1 * *coeff2)
+                    .or_insert(*coeff1 * *coeff2);
+            })
+        });
+        // Remove monomials with zero coefficients
+        let monomials: HashMap<[usize; N], F> = monomials
+            .into_iter()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .collect();
+        if monomials.is_empty() {
+            Self::zero()
+        } else {
+            Self { monomials }
+        }
+    }
+
+    /// Multiply `self` and `other` by Kronecker substitution, see
+    /// [crate::kronecker]
+    pub fn mul_kronecker(&self, other: &Self) -> Self {
+        let bounds = kronecker::bounds(&self.monomials, &other.monomials);
+        let monomials: HashMap<[usize; N], F> =
+            kronecker::mul(&self.monomials, &other.monomials, &bounds)
+                .into_iter()
+                .collect();
+        if monomials.is_empty() {
+            Self::zero()
+        } else {
+            Self { monomials }
+        }
+    }
+
+    /// Evaluate the variables given by `assignments`, a list of pairs `(i, v)`
+    /// setting `x_i` to `v`, and keep the other variables symbolic. The
+    /// remaining variables keep their order and are renumbered from `0`,
+    /// therefore the result has `M = N - assignments.len()` variables.
+    ///
+    /// For instance, assigning `x_1 = 2` in `x_0 * x_1 + x_2` with `N = 3`
+    /// gives `2 * y_0 + y_1`, where `y_0 = x_0` and `y_1 = x_2`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable is out of bounds or assigned twice, or if `M` is
+    /// not the number of remaining variables.
+    pub fn partial_eval<const M: usize>(&self, assignments: &[(usize, F)]) -> Sparse<F, M, D> {
+        let (values, new_indices) = partial_eval_variables::<F, N, M>(assignments);
+        let mut monomials: HashMap<[usize; M], F> = HashMap::new();
+        self.monomials.iter().for_each(|(exponents, coeff)| {
+            let mut new_exponents = [0; M];
+            let mut coeff = *coeff;
+            for (i, d) in exponents.iter().enumerate() {
+                match values[i] {
+                    Some(v) => coeff *= v.pow([*d as u64]),
+                    None => new_exponents[new_indices[i]] = *d,
+                }
+            }
+            *monomials.entry(new_exponents).or_insert(F::zero()) += coeff;
+        });
+        let mut result = Sparse { monomials };
+        result.remove_zero_coefficients();
+        result
+    }
+
+    /// Convert the polynomial into a polynomial of maximum degree `D_PRIME`.
+    /// The compilation fails if `D_PRIME` is lower than `D`.
+    pub fn increase_degree<const D_PRIME: usize>(&self) -> Sparse<F, N, D_PRIME> {
+        assert!(DegreeCheck::<D, D, D_PRIME>::MAX);
+        Sparse {
+            monomials: self.monomials.clone(),
+        }
+    }
+
+    /// Convert the polynomial into a polynomial in `M` variables, the
+    /// variables `x_N, ..., x_{M - 1}` not appearing in it. The compilation
+    /// fails if `M` is lower than `N`, see the conversion into
+    /// `Result<Sparse<F, M, D>, String>` to check it at runtime.
+    pub fn increase_arity<const M: usize>(&self) -> Sparse<F, M, D> {
+        assert!(ArityCheck::<N, M>::EXTEND);
+        let monomials = self
+            .monomials
+            .iter()
+            .map(|(exponents, coeff)| {
+                let new_exponents = std::array::from_fn(|i| if i < N { exponents[i] } else { 0 });
+                (new_exponents, *coeff)
+            })
+            .collect();
+        Sparse { monomials }
+    }
+
+    /// Add `self` and `other`, of maximum degrees `D` and `D2`, into a
+    /// polynomial of maximum degree `D3`. The compilation fails if `D3` is
+    /// lower than `D` or `D2`.
+    pub fn widening_add<const D2: usize, const D3: usize>(
+        &self,
+        other: &Sparse<F, N, D2>,
+    ) -> Sparse<F, N, D3> {
+        assert!(DegreeCheck::<D, D2, D3>::MAX);
+        self.increase_degree::<D3>() + other.increase_degree::<D3>()
+    }
+
+    /// Multiply `self` and `other`, of maximum degrees `D` and `D2`, into a
+    /// polynomial of maximum degree `D3`. The compilation fails if `D3` is
+    /// lower than `D + D2`, i.e. if the product could have terms of degree
+    /// larger than `D3`.
+    ///
+    /// ```compile_fail
+    /// use ark_ff::One;
+    /// use mina_curves::pasta::Fp;
+    /// use mvpoly::monomials::Sparse;
+    ///
+    /// let p = Sparse::<Fp, 3, 2>::one();
+    /// let q = Sparse::<Fp, 3, 1>::one();
+    /// let _: Sparse<Fp, 3, 2> = p.widening_mul(&q);
+    /// ```
+    pub fn widening_mul<const D2: usize, const D3: usize>(
+        &self,
+        other: &Sparse<F, N, D2>,
+    ) -> Sparse<F, N, D3> {
+        assert!(DegreeCheck::<D, D2, D3>::SUM);
+        self.increase_degree::<D3>() * other.increase_degree::<D3>()
+    }
+
+    /// Remove the monomials with a zero coefficient, as done by the
+    /// arithmetic operations, to keep a unique representation. The zero
+    /// polynomial keeps its constant monomial.
+    fn remove_zero_coefficients(&mut self) {
+        self.monomials.retain(|_, coeff| !coeff.is_zero());
+        if self.monomials.is_empty() {
+            *self = Self::zero();
+        }
+    }
+}
+
 impl<const N: usize, const D: usize, F: PrimeField> MVPoly<F, N, D> for Sparse<F, N, D> {
     /// Returns the degree of the polynomial.
     ///
@@ -239,16 +370,18 @@ impl<const N: usize, const D: usize, F: PrimeField> MVPoly<F, N, D> for Sparse<F
     /// This is a dummy implementation. A cache can be used for the monomials to
     /// speed up the computation.
     fn eval(&self, x: &[F; N]) -> F {
-        self.monomials
-            .iter()
-            .map(|(exponents, coeff)| {
-                let mut term = F::one();
-                for (exp, point) in exponents.iter().zip(x.iter()) {
-                    term *= point.pow([*exp as u64]);
-                }
-                term * coeff
-            })
-            .sum()
+        let eval_term = |(exponents, coeff): (&[usize; N], &F)| {
+            let mut term = F::one();
+            for (exp, point) in exponents.iter().zip(x.iter()) {
+                term *= point.pow([*exp as u64]);
+            }
+            term * coeff
+        };
+        if self.monomials.len() < PAR_EVAL_MIN_TERMS {
+            self.monomials.iter().map(eval_term).sum()
+        } else {
+            self.monomials.par_iter().map(eval_term).sum()
+        }
     }
 
     fn is_constant(&self) -> bool {
@@ -373,6 +506,7 @@ impl<const N: usize, const D: usize, F: PrimeField> MVPoly<F, N, D> for Sparse<F
             .entry(exponents)
             .and_modify(|c| *c += coeff)
             .or_insert(coeff);
+        self.remove_zero_coefficients();
     }
 
     fn compute_cross_terms(
@@ -393,81 +527,21 @@ impl<const N: usize, const D: usize, F: PrimeField> MVPoly<F, N, D> for Sparse<F
             // "Exponents" contains all powers, even the ones that are 0. We must
             // get rid of them and keep the index to fetch the correct
             // evaluation later
-            let non_zero_exponents_with_index: Vec<(usize, &usize)> = exponents
+            let non_zero_exponents_with_index: Vec<(usize, usize)> = exponents
                 .iter()
                 .enumerate()
                 .filter(|(_, &d)| d != 0)
+                .map(|(i, &d)| (i, d))
                 .collect();
-            // coeff = 0 should not happen as we suppose we have a sparse polynomial
-            // Therefore, skipping a check
-            let non_zero_exponents: Vec<usize> = non_zero_exponents_with_index
-                .iter()
-                .map(|(_, d)| *d)
-                .copied()
-                .collect::<Vec<usize>>();
-            let monomial_degree = non_zero_exponents.iter().sum::<usize>();
-            let u_degree: usize = D - monomial_degree;
-            // Will be used to compute the nested sums
-            // It returns all the indices i_1, ..., i_k for the sums:
-            // Σ_{i_1 = 0}^{n_1} Σ_{i_2 = 0}^{n_2} ... Σ_{i_k = 0}^{n_k}
-            let indices = compute_indices_nested_loop(
-                non_zero_exponents.iter().map(|d| *d + 1).collect(),
-                None,
+            add_monomial_cross_terms::<F, D>(
+                &mut cross_terms_by_powers_of_r,
+                &non_zero_exponents_with_index,
+                *coeff,
+                eval1,
+                eval2,
+                u1,
+                u2,
             );
-            for i in 0..=u_degree {
-                // Add the binomial from the homogeneisation
-                // i.e (u_degree choose i)
-                let u_binomial_term = binomial(u_degree, i);
-                // Now, we iterate over all the indices i_1, ..., i_k, i.e. we
-                // do over the whole sum, and we populate the map depending on
-                // the power of r
-                indices.iter().for_each(|indices| {
-                    let sum_indices = indices.iter().sum::<usize>() + i;
-                    // power of r is Σ (n_k - i_k)
-                    let power_r: usize = D - sum_indices;
-
-                    // If the sum of the indices is 0 or D, we skip the
-                    // computation as the contribution would go in the
-                    // evaluation of the polynomial at each evaluation
-                    // vectors eval1 and eval2
-                    if sum_indices == 0 || sum_indices == D {
-                        return;
-                    }
-                    // Compute
-                    // (n_1 choose i_1) * (n_2 choose i_2) * ... * (n_k choose i_k)
-                    let binomial_term = indices
-                        .iter()
-                        .zip(non_zero_exponents.iter())
-                        .fold(u_binomial_term, |acc, (i, &d)| acc * binomial(d, *i));
-                    let binomial_term = F::from(binomial_term as u64);
-                    // Compute the product x_k^i_k
-                    // We ignore the power as it comes into account for the
-                    // right evaluation.
-                    // NB: we could merge both loops, but we keep them separate
-                    // for readability
-                    let eval_left = indices
-                        .iter()
-                        .zip(non_zero_exponents_with_index.iter())
-                        .fold(F::one(), |acc, (i, (idx, _d))| {
-                            acc * eval1[*idx].pow([*i as u64])
-                        });
-                    // Compute the product x'_k^(n_k - i_k)
-                    let eval_right = indices
-                        .iter()
-                        .zip(non_zero_exponents_with_index.iter())
-                        .fold(F::one(), |acc, (i, (idx, d))| {
-                            acc * eval2[*idx].pow([(*d - *i) as u64])
-                        });
-                    // u1^i * u2^(u_degree - i)
-                    let u = u1.pow([i as u64]) * u2.pow([(u_degree - i) as u64]);
-                    let res = binomial_term * eval_left * eval_right * u;
-                    let res = *coeff * res;
-                    cross_terms_by_powers_of_r
-                        .entry(power_r)
-                        .and_modify(|e| *e += res)
-                        .or_insert(res);
-                })
-            }
         });
         cross_terms_by_powers_of_r
     }
@@ -477,6 +551,7 @@ impl<const N: usize, const D: usize, F: PrimeField> MVPoly<F, N, D> for Sparse<F
             .entry(exponents)
             .and_modify(|c| *c = coeff)
             .or_insert(coeff);
+        self.remove_zero_coefficients();
     }
 
     fn is_multilinear(&self) -> bool {
@@ -486,6 +561,117 @@ impl<const N: usize, const D: usize, F: PrimeField> MVPoly<F, N, D> for Sparse<F
     }
 }
 
+/// Split the `N` variables between the ones assigned by `assignments` (see
+/// [Sparse::partial_eval]), whose values are returned, and the `M` remaining
+/// ones, whose new indices are returned.
+pub(crate) fn partial_eval_variables<F: PrimeField, const N: usize, const M: usize>(
+    assignments: &[(usize, F)],
+) -> ([Option<F>; N], [usize; N]) {
+    let mut values = [None; N];
+    for (i, v) in assignments {
+        assert!(*i < N, "The variable x_{i} is out of bounds");
+        assert!(values[*i].is_none(), "The variable x_{i} is assigned twice");
+        values[*i] = Some(*v);
+    }
+    assert_eq!(
+        M + assignments.len(),
+        N,
+        "The partial evaluation must have {} variables",
+        N - assignments.len()
+    );
+    let mut new_indices = [0; N];
+    let mut next = 0;
+    for (new_index, value) in new_indices.iter_mut().zip(values.iter()) {
+        if value.is_none() {
+            *new_index = next;
+            next += 1;
+        }
+    }
+    (values, new_indices)
+}
+
+/// Add the cross-terms of the monomial `coeff * x_{i_1}^{d_1} * ... *
+/// x_{i_k}^{d_k}` to `cross_terms_by_powers_of_r`, where the pairs `(i_j, d_j)`
+/// of `non_zero_exponents_with_index` are the variables of the monomial with
+/// their (non-zero) exponents. See [MVPoly::compute_cross_terms].
+pub(crate) fn add_monomial_cross_terms<F: PrimeField, const D: usize>(
+    cross_terms_by_powers_of_r: &mut HashMap<usize, F>,
+    non_zero_exponents_with_index: &[(usize, usize)],
+    coeff: F,
+    eval1: &[F],
+    eval2: &[F],
+    u1: F,
+    u2: F,
+) {
+    // coeff = 0 should not happen as we suppose we have a sparse polynomial
+    // Therefore, skipping a check
+    let non_zero_exponents: Vec<usize> = non_zero_exponents_with_index
+        .iter()
+        .map(|(_, d)| *d)
+        .collect::<Vec<usize>>();
+    let monomial_degree = non_zero_exponents.iter().sum::<usize>();
+    let u_degree: usize = D - monomial_degree;
+    // Will be used to compute the nested sums
+    // It returns all the indices i_1, ..., i_k for the sums:
+    // Σ_{i_1 = 0}^{n_1} Σ_{i_2 = 0}^{n_2} ... Σ_{i_k = 0}^{n_k}
+    let indices =
+        compute_indices_nested_loop(non_zero_exponents.iter().map(|d| *d + 1).collect(), None);
+    for i in 0..=u_degree {
+        // Add the binomial from the homogeneisation
+        // i.e (u_degree choose i)
+        let u_binomial_term = binomial(u_degree, i);
+        // Now, we iterate over all the indices i_1, ..., i_k, i.e. we
+        // do over the whole sum, and we populate the map depending on
+        // the power of r
+        indices.iter().for_each(|indices| {
+            let sum_indices = indices.iter().sum::<usize>() + i;
+            // power of r is Σ (n_k - i_k)
+            let power_r: usize = D - sum_indices;
+
+            // If the sum of the indices is 0 or D, we skip the
+            // computation as the contribution would go in the
+            // evaluation of the polynomial at each evaluation
+            // vectors eval1 and eval2
+            if sum_indices == 0 || sum_indices == D {
+                return;
+            }
+            // Compute
+            // (n_1 choose i_1) * (n_2 choose i_2) * ... * (n_k choose i_k)
+            let binomial_term = indices
+                .iter()
+                .zip(non_zero_exponents.iter())
+                .fold(u_binomial_term, |acc, (i, &d)| acc * binomial(d, *i));
+            let binomial_term = F::from(binomial_term as u64);
+            // Compute the product x_k^i_k
+            // We ignore the power as it comes into account for the
+            // right evaluation.
+            // NB: we could merge both loops, but we keep them separate
+            // for readability
+            let eval_left = indices
+                .iter()
+                .zip(non_zero_exponents_with_index.iter())
+                .fold(F::one(), |acc, (i, (idx, _d))| {
+                    acc * eval1[*idx].pow([*i as u64])
+                });
+            // Compute the product x'_k^(n_k - i_k)
+            let eval_right = indices
+                .iter()
+                .zip(non_zero_exponents_with_index.iter())
+                .fold(F::one(), |acc, (i, (idx, d))| {
+                    acc * eval2[*idx].pow([(*d - *i) as u64])
+                });
+            // u1^i * u2^(u_degree - i)
+            let u = u1.pow([i as u64]) * u2.pow([(u_degree - i) as u64]);
+            let res = binomial_term * eval_left * eval_right * u;
+            let res = coeff * res;
+            cross_terms_by_powers_of_r
+                .entry(power_r)
+                .and_modify(|e| *e += res)
+                .or_insert(res);
+        })
+    }
+}
+
 impl<const N: usize, const D: usize, F: PrimeField> From<prime::Dense<F, N, D>>
     for Sparse<F, N, D>
 {
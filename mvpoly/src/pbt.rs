@@ -587,3 +587,17 @@ pub fn test_is_constant<F: PrimeField, const N: usize, const D: usize, T: MVPoly
     let p = unsafe { T::random(&mut rng, None) };
     assert!(!p.is_constant());
 }
+
+pub fn test_eval_many<F: PrimeField, const N: usize, const D: usize, T: MVPoly<F, N, D> + Sync>() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p = unsafe { T::random(&mut rng, None) };
+    let points: Vec<[F; N]> = (0..20)
+        .map(|_| std::array::from_fn(|_| F::rand(&mut rng)))
+        .collect();
+    let evals = p.eval_many(&points);
+    assert_eq!(evals.len(), points.len());
+    for (x, eval) in points.iter().zip(evals) {
+        assert_eq!(p.eval(x), eval);
+    }
+    assert!(p.eval_many(&[]).is_empty());
+}
@@ -152,11 +152,13 @@ use kimchi::circuits::{expr::Variable, gate::CurrOrNext};
 use num_integer::binomial;
 use o1_utils::FieldHelpers;
 use rand::{Rng, RngCore};
+use rayon::prelude::*;
 use std::ops::{Index, IndexMut};
 
 use crate::{
+    checks::DegreeCheck,
     utils::{compute_all_two_factors_decomposition, naive_prime_factors, PrimeNumberGenerator},
-    MVPoly,
+    MVPoly, PAR_EVAL_MIN_TERMS,
 };
 
 /// Represents a multivariate polynomial of degree less than `D` in `N` variables.
@@ -312,30 +314,40 @@ impl<F: PrimeField, const N: usize, const D: usize> MVPoly<F, N, D> for Dense<F,
     /// This is a dummy implementation. A cache can be used for the monomials to
     /// speed up the computation.
     fn eval(&self, x: &[F; N]) -> F {
-        let mut prime_gen = PrimeNumberGenerator::new();
-        let primes = prime_gen.get_first_nth_primes(N);
-        self.coeff
-            .iter()
-            .enumerate()
-            .fold(F::zero(), |acc, (i, c)| {
-                if i == 0 {
-                    acc + c
-                } else {
-                    let normalized_index = self.normalized_indices[i];
-                    // IMPROVEME: we should keep the prime decomposition somewhere.
-                    // It can be precomputed for a few multi-variate polynomials
-                    // vector space
-                    let prime_decomposition = naive_prime_factors(normalized_index, &mut prime_gen);
-                    let mut monomial = F::one();
-                    prime_decomposition.iter().for_each(|(p, d)| {
-                        // IMPROVEME: we should keep the inverse indices
-                        let inv_p = primes.iter().position(|&x| x == *p).unwrap();
-                        let x_p = x[inv_p].pow([*d as u64]);
-                        monomial *= x_p;
-                    });
-                    acc + *c * monomial
-                }
-            })
+        let primes = PrimeNumberGenerator::new().get_first_nth_primes(N);
+        let eval_term = |prime_gen: &mut PrimeNumberGenerator, (i, c): (usize, &F)| {
+            if i == 0 {
+                *c
+            } else {
+                let normalized_index = self.normalized_indices[i];
+                // IMPROVEME: we should keep the prime decomposition somewhere.
+                // It can be precomputed for a few multi-variate polynomials
+                // vector space
+                let prime_decomposition = naive_prime_factors(normalized_index, prime_gen);
+                let mut monomial = F::one();
+                prime_decomposition.iter().for_each(|(p, d)| {
+                    // IMPROVEME: we should keep the inverse indices
+                    let inv_p = primes.iter().position(|&x| x == *p).unwrap();
+                    let x_p = x[inv_p].pow([*d as u64]);
+                    monomial *= x_p;
+                });
+                *c * monomial
+            }
+        };
+        if self.coeff.len() < PAR_EVAL_MIN_TERMS {
+            let mut prime_gen = PrimeNumberGenerator::new();
+            self.coeff
+                .iter()
+                .enumerate()
+                .map(|term| eval_term(&mut prime_gen, term))
+                .sum()
+        } else {
+            self.coeff
+                .par_iter()
+                .enumerate()
+                .map_init(PrimeNumberGenerator::new, eval_term)
+                .sum()
+        }
     }
 
     fn from_variable<Column: Into<usize>>(
@@ -532,8 +544,10 @@ impl<F: PrimeField, const N: usize, const D: usize> Dense<F, N, D> {
         normalized_indices
     }
 
+    /// Convert the polynomial into a polynomial of maximum degree `D_PRIME`.
+    /// The compilation fails if `D_PRIME` is lower than `D`.
     pub fn increase_degree<const D_PRIME: usize>(&self) -> Dense<F, N, D_PRIME> {
-        assert!(D <= D_PRIME, "The degree of the target polynomial must be greater or equal to the degree of the source polynomial");
+        assert!(DegreeCheck::<D, D, D_PRIME>::MAX);
         let mut result: Dense<F, N, D_PRIME> = Dense::zero();
         let dst_normalized_indices = Dense::<F, N, D_PRIME>::compute_normalized_indices();
         let src_normalized_indices = Dense::<F, N, D>::compute_normalized_indices();
@@ -550,6 +564,29 @@ impl<F: PrimeField, const N: usize, const D: usize> Dense<F, N, D> {
             });
         result
     }
+
+    /// Add `self` and `other`, of maximum degrees `D` and `D2`, into a
+    /// polynomial of maximum degree `D3`. The compilation fails if `D3` is
+    /// lower than `D` or `D2`.
+    pub fn widening_add<const D2: usize, const D3: usize>(
+        &self,
+        other: &Dense<F, N, D2>,
+    ) -> Dense<F, N, D3> {
+        assert!(DegreeCheck::<D, D2, D3>::MAX);
+        self.increase_degree::<D3>() + other.increase_degree::<D3>()
+    }
+
+    /// Multiply `self` and `other`, of maximum degrees `D` and `D2`, into a
+    /// polynomial of maximum degree `D3`. The compilation fails if `D3` is
+    /// lower than `D + D2`, i.e. if the product could have terms of degree
+    /// larger than `D3`.
+    pub fn widening_mul<const D2: usize, const D3: usize>(
+        &self,
+        other: &Dense<F, N, D2>,
+    ) -> Dense<F, N, D3> {
+        assert!(DegreeCheck::<D, D2, D3>::SUM);
+        self.increase_degree::<D3>() * other.increase_degree::<D3>()
+    }
 }
 
 impl<F: PrimeField, const N: usize, const D: usize> Default for Dense<F, N, D> {
@@ -0,0 +1,269 @@
+//! This module contains the serialization of the multivariate polynomials, with
+//! [serde] and with a canonical textual form.
+//!
+//! The textual form is a sum of terms, e.g. `5 + 3*x_0^2*x_3 + x_1`. A term
+//! is the product of its coefficient, omitted when it is `1`, and of the
+//! variables `x_i^e` of its monomial, written `x_i` when `e = 1`. The
+//! coefficients are the decimal representatives in `[0, p)` of the field
+//! elements. The terms are sorted as the monomials of [TermList], and the
+//! zero polynomial is `0`. As a polynomial has a single canonical form, the
+//! output can be compared between versions, e.g. to check the cross-terms of a
+//! circuit.
+//!
+//! The parser is more permissive: it accepts the terms in any order, repeated
+//! monomials, subtractions, integer factors at any position of a term, and
+//! whitespace.
+//!
+//! With [serde], a polynomial is serialized as its number of variables, its
+//! maximum degree and its list of terms. The representations of this crate
+//! share the same serialized form, so a polynomial can be loaded into another
+//! representation.
+
+use ark_ff::PrimeField;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::serde_as;
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    monomials::Sparse,
+    prime::Dense,
+    terms::{monomial_to_exponents, Monomial, TermList},
+    MVPoly,
+};
+
+/// Write the term `coeff * monomial` in the canonical form
+fn write_term<F: PrimeField>(
+    f: &mut Formatter<'_>,
+    monomial: &Monomial,
+    coeff: &F,
+) -> std::fmt::Result {
+    let mut factors = vec![];
+    if monomial.is_empty() || !coeff.is_one() {
+        factors.push(coeff.into_bigint().to_string());
+    }
+    for (i, d) in monomial {
+        if *d == 1 {
+            factors.push(format!("x_{i}"));
+        } else {
+            factors.push(format!("x_{i}^{d}"));
+        }
+    }
+    write!(f, "{}", factors.join("*"))
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Display for TermList<F, N, D> {
+    /// Write the polynomial in the canonical form, see the module documentation
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "0");
+        }
+        for (k, (monomial, coeff)) in self.iter().enumerate() {
+            if k > 0 {
+                write!(f, " + ")?;
+            }
+            write_term(f, monomial, coeff)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Display for Sparse<F, N, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", TermList::from(self.clone()))
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Display for Dense<F, N, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Sparse::from(self.clone()))
+    }
+}
+
+fn parse_integer(s: &str) -> Result<usize, String> {
+    s.parse()
+        .map_err(|_| format!("Invalid integer '{s}' in the polynomial"))
+}
+
+fn parse_coefficient<F: PrimeField>(s: &str) -> Result<F, String> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Invalid coefficient '{s}' in the polynomial"));
+    }
+    // The parser of arkworks rejects the leading zeros
+    let digits = s.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(F::zero());
+    }
+    F::from_str(digits).map_err(|_| format!("Invalid coefficient '{s}' in the polynomial"))
+}
+
+/// Parse a term, i.e. a product of integers and of powers of variables
+fn parse_term<F: PrimeField, const N: usize>(s: &str) -> Result<([usize; N], F), String> {
+    let mut exponents = [0; N];
+    let mut coeff = F::one();
+    for factor in s.split('*').map(str::trim) {
+        match factor.strip_prefix("x_") {
+            Some(var) => {
+                let (i, d) = match var.split_once('^') {
+                    Some((i, d)) => (parse_integer(i.trim())?, parse_integer(d.trim())?),
+                    None => (parse_integer(var)?, 1),
+                };
+                if i >= N {
+                    return Err(format!("The variable x_{i} is out of bounds"));
+                }
+                exponents[i] += d;
+            }
+            None => coeff *= parse_coefficient::<F>(factor)?,
+        }
+    }
+    Ok((exponents, coeff))
+}
+
+/// Parse a polynomial from its textual form, see the module documentation.
+/// An error is returned if a variable is out of bounds or if the degree of a
+/// monomial is larger than `D`.
+pub fn parse<F: PrimeField, const N: usize, const D: usize, T: MVPoly<F, N, D>>(
+    s: &str,
+) -> Result<T, String> {
+    let mut result = T::zero();
+    // Split the terms on the signs, and keep the sign of each term
+    let mut terms = vec![];
+    let mut start = 0;
+    let mut negative = false;
+    for (k, c) in s.char_indices() {
+        if c == '+' || c == '-' {
+            terms.push((negative, &s[start..k]));
+            negative = c == '-';
+            start = k + 1;
+        }
+    }
+    terms.push((negative, &s[start..]));
+    // The first term can be empty only if the polynomial starts with a sign
+    if terms[0].1.trim().is_empty() && terms.len() > 1 {
+        terms.remove(0);
+    }
+    for (negative, term) in terms {
+        let term = term.trim();
+        if term.is_empty() {
+            return Err(format!("Empty term in the polynomial '{s}'"));
+        }
+        let (exponents, coeff) = parse_term::<F, N>(term)?;
+        let degree: usize = exponents.iter().sum();
+        if degree > D {
+            return Err(format!(
+                "The monomial {term} has degree {degree}, larger than the maximum degree {D}"
+            ));
+        }
+        result.add_monomial(exponents, if negative { -coeff } else { coeff });
+    }
+    Ok(result)
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> std::str::FromStr for TermList<F, N, D> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> std::str::FromStr for Sparse<F, N, D> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> std::str::FromStr for Dense<F, N, D> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+/// The serialized form of a polynomial, shared by all the representations
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+struct SerializedPoly<F: PrimeField> {
+    nb_variables: usize,
+    max_degree: usize,
+    #[serde_as(as = "Vec<(_, o1_utils::serialization::SerdeAs)>")]
+    terms: Vec<(Monomial, F)>,
+}
+
+impl<F: PrimeField> SerializedPoly<F> {
+    fn new<const N: usize, const D: usize>(poly: &TermList<F, N, D>) -> Self {
+        SerializedPoly {
+            nb_variables: N,
+            max_degree: D,
+            terms: poly.iter().map(|(m, c)| (m.clone(), *c)).collect(),
+        }
+    }
+
+    /// Check the parameters and the terms, and build the polynomial
+    fn into_poly<const N: usize, const D: usize, T: MVPoly<F, N, D>>(self) -> Result<T, String> {
+        if self.nb_variables != N || self.max_degree != D {
+            return Err(format!(
+                "Expected a polynomial in {N} variables of maximum degree {D}, got {} variables and maximum degree {}",
+                self.nb_variables, self.max_degree
+            ));
+        }
+        let mut result = T::zero();
+        for (monomial, coeff) in self.terms {
+            let is_sorted = monomial.windows(2).all(|w| w[0].0 < w[1].0);
+            if !is_sorted || monomial.iter().any(|(i, d)| *i >= N || *d == 0) {
+                return Err(format!("Invalid monomial {monomial:?}"));
+            }
+            if monomial.iter().map(|(_, d)| d).sum::<usize>() > D {
+                return Err(format!(
+                    "The monomial {monomial:?} has a degree larger than {D}"
+                ));
+            }
+            result.add_monomial(monomial_to_exponents(&monomial), coeff);
+        }
+        Ok(result)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Serialize for TermList<F, N, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedPoly::new(self).serialize(serializer)
+    }
+}
+
+impl<'de, F: PrimeField, const N: usize, const D: usize> Deserialize<'de> for TermList<F, N, D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        SerializedPoly::deserialize(deserializer)?
+            .into_poly()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Serialize for Sparse<F, N, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedPoly::new(&TermList::from(self.clone())).serialize(serializer)
+    }
+}
+
+impl<'de, F: PrimeField, const N: usize, const D: usize> Deserialize<'de> for Sparse<F, N, D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        SerializedPoly::deserialize(deserializer)?
+            .into_poly()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Serialize for Dense<F, N, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedPoly::new(&TermList::from(Sparse::from(self.clone()))).serialize(serializer)
+    }
+}
+
+impl<'de, F: PrimeField, const N: usize, const D: usize> Deserialize<'de> for Dense<F, N, D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        SerializedPoly::deserialize(deserializer)?
+            .into_poly()
+            .map_err(serde::de::Error::custom)
+    }
+}
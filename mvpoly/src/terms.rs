@@ -0,0 +1,550 @@
+//! This module contains a representation of multivariate polynomials as a
+//! sorted list of terms, where each monomial only stores the variables
+//! appearing in it.
+//!
+//! [crate::monomials::Sparse] stores an array of `N` exponents for each
+//! monomial, and [crate::prime::Dense] stores all the monomials of degree at
+//! most `D`. The polynomials coming from the constraints of real circuits have
+//! hundreds of variables, and each monomial only uses a handful of them. With
+//! [TermList], the memory used by a monomial and the cost of multiplying two
+//! monomials only depend on the number of variables of the monomials, and not
+//! on `N`.
+
+use ark_ff::{One, PrimeField, Zero};
+use kimchi::circuits::{expr::Variable, gate::CurrOrNext};
+use rand::{seq::index::sample, Rng, RngCore};
+use rayon::prelude::*;
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use crate::{
+    checks::{ArityCheck, DegreeCheck},
+    monomials::{add_monomial_cross_terms, partial_eval_variables, Sparse},
+    MVPoly, PAR_EVAL_MIN_TERMS,
+};
+
+/// A monomial, given by the list of its variables with their exponents. The
+/// list is sorted by variable, and the exponents are non-zero. The empty list
+/// is the constant monomial `1`.
+pub type Monomial = Vec<(usize, usize)>;
+
+/// Represents a multivariate polynomial in `N` variables with coefficients in
+/// `F`, as a list of terms sorted by monomial. The coefficients of the terms
+/// are non-zero, therefore the zero polynomial is the empty list.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TermList<F: PrimeField, const N: usize, const D: usize> {
+    terms: BTreeMap<Monomial, F>,
+}
+
+/// Convert the `N` exponents of a monomial into a [Monomial]
+pub fn monomial_from_exponents<const N: usize>(exponents: &[usize; N]) -> Monomial {
+    exponents
+        .iter()
+        .enumerate()
+        .filter(|(_, &d)| d != 0)
+        .map(|(i, &d)| (i, d))
+        .collect()
+}
+
+/// Convert a [Monomial] into its `N` exponents
+pub fn monomial_to_exponents<const N: usize>(monomial: &Monomial) -> [usize; N] {
+    let mut exponents = [0; N];
+    for (i, d) in monomial {
+        exponents[*i] = *d;
+    }
+    exponents
+}
+
+/// Multiply two monomials, by merging their sorted lists of variables
+fn mul_monomials(m1: &Monomial, m2: &Monomial) -> Monomial {
+    let mut res = Vec::with_capacity(m1.len() + m2.len());
+    let (mut it1, mut it2) = (m1.iter().peekable(), m2.iter().peekable());
+    loop {
+        match (it1.peek(), it2.peek()) {
+            (Some((i1, d1)), Some((i2, d2))) => match i1.cmp(i2) {
+                std::cmp::Ordering::Less => {
+                    res.push((*i1, *d1));
+                    it1.next();
+                }
+                std::cmp::Ordering::Greater => {
+                    res.push((*i2, *d2));
+                    it2.next();
+                }
+                std::cmp::Ordering::Equal => {
+                    res.push((*i1, d1 + d2));
+                    it1.next();
+                    it2.next();
+                }
+            },
+            (Some(_), None) => res.extend(it1.by_ref().copied()),
+            (None, Some(_)) => res.extend(it2.by_ref().copied()),
+            (None, None) => return res,
+        }
+    }
+}
+
+fn monomial_degree(monomial: &Monomial) -> usize {
+    monomial.iter().map(|(_, d)| d).sum()
+}
+
+fn eval_monomial<F: PrimeField>(monomial: &Monomial, x: &[F]) -> F {
+    monomial
+        .iter()
+        .fold(F::one(), |acc, (i, d)| acc * x[*i].pow([*d as u64]))
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> TermList<F, N, D> {
+    /// The number of terms with a non-zero coefficient
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Returns true if the polynomial has no term, i.e. if it is zero
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Iterate over the terms, sorted by monomial
+    pub fn iter(&self) -> impl Iterator<Item = (&Monomial, &F)> {
+        self.terms.iter()
+    }
+
+    /// Evaluate the variables given by `assignments` and keep the other ones
+    /// symbolic, see [Sparse::partial_eval].
+    pub fn partial_eval<const M: usize>(&self, assignments: &[(usize, F)]) -> TermList<F, M, D> {
+        let (values, new_indices) = partial_eval_variables::<F, N, M>(assignments);
+        let mut result = TermList::zero();
+        for (monomial, coeff) in self.terms.iter() {
+            let mut coeff = *coeff;
+            let mut new_monomial = Vec::with_capacity(monomial.len());
+            for (i, d) in monomial {
+                match values[*i] {
+                    Some(v) => coeff *= v.pow([*d as u64]),
+                    None => new_monomial.push((new_indices[*i], *d)),
+                }
+            }
+            result.add_term(new_monomial, coeff);
+        }
+        result
+    }
+
+    /// Convert the polynomial into a polynomial of maximum degree `D_PRIME`.
+    /// The compilation fails if `D_PRIME` is lower than `D`.
+    pub fn increase_degree<const D_PRIME: usize>(&self) -> TermList<F, N, D_PRIME> {
+        assert!(DegreeCheck::<D, D, D_PRIME>::MAX);
+        TermList {
+            terms: self.terms.clone(),
+        }
+    }
+
+    /// Convert the polynomial into a polynomial in `M` variables. The
+    /// compilation fails if `M` is lower than `N`.
+    pub fn increase_arity<const M: usize>(&self) -> TermList<F, M, D> {
+        assert!(ArityCheck::<N, M>::EXTEND);
+        TermList {
+            terms: self.terms.clone(),
+        }
+    }
+
+    /// Add `self` and `other` into a polynomial of maximum degree `D3`, see
+    /// [Sparse::widening_add].
+    pub fn widening_add<const D2: usize, const D3: usize>(
+        &self,
+        other: &TermList<F, N, D2>,
+    ) -> TermList<F, N, D3> {
+        assert!(DegreeCheck::<D, D2, D3>::MAX);
+        self.increase_degree::<D3>() + other.increase_degree::<D3>()
+    }
+
+    /// Multiply `self` and `other` into a polynomial of maximum degree `D3`,
+    /// see [Sparse::widening_mul].
+    pub fn widening_mul<const D2: usize, const D3: usize>(
+        &self,
+        other: &TermList<F, N, D2>,
+    ) -> TermList<F, N, D3> {
+        assert!(DegreeCheck::<D, D2, D3>::SUM);
+        self.increase_degree::<D3>() * other.increase_degree::<D3>()
+    }
+
+    /// Add `coeff * monomial` to the polynomial
+    pub fn add_term(&mut self, monomial: Monomial, coeff: F) {
+        debug_assert!(monomial.windows(2).all(|w| w[0].0 < w[1].0));
+        debug_assert!(monomial.iter().all(|(i, d)| *i < N && *d != 0));
+        match self.terms.entry(monomial) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() += coeff;
+                if entry.get().is_zero() {
+                    entry.remove();
+                }
+            }
+            Entry::Vacant(entry) => {
+                if !coeff.is_zero() {
+                    entry.insert(coeff);
+                }
+            }
+        }
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Add for TermList<F, N, D> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self + &other
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Add<&TermList<F, N, D>> for TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn add(mut self, other: &TermList<F, N, D>) -> Self::Output {
+        for (monomial, coeff) in &other.terms {
+            *self.terms.entry(monomial.clone()).or_insert(F::zero()) += coeff;
+        }
+        self.terms.retain(|_, c| !c.is_zero());
+        self
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Add<TermList<F, N, D>> for &TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn add(self, other: TermList<F, N, D>) -> Self::Output {
+        other + self
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Add<&TermList<F, N, D>> for &TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn add(self, other: &TermList<F, N, D>) -> Self::Output {
+        self.clone() + other
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Debug for TermList<F, N, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        let monomials: Vec<String> = self
+            .terms
+            .iter()
+            .map(|(monomial, coeff)| {
+                let mut term = format!("{}", coeff);
+                for (i, d) in monomial {
+                    if *d == 1 {
+                        term.push_str(&format!("x_{}", i));
+                    } else {
+                        term.push_str(&format!("x_{}^{}", i, d));
+                    }
+                }
+                term
+            })
+            .collect();
+        write!(f, "{}", monomials.join(" + "))
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Mul for TermList<F, N, D> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        &self * &other
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Mul<&TermList<F, N, D>> for &TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn mul(self, other: &TermList<F, N, D>) -> Self::Output {
+        let mut terms = BTreeMap::new();
+        for (m1, c1) in &self.terms {
+            for (m2, c2) in &other.terms {
+                *terms.entry(mul_monomials(m1, m2)).or_insert(F::zero()) += *c1 * c2;
+            }
+        }
+        terms.retain(|_, c: &mut F| !c.is_zero());
+        TermList { terms }
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Neg for TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn neg(mut self) -> Self::Output {
+        self.terms.values_mut().for_each(|c| *c = -*c);
+        self
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Neg for &TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Sub for TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn sub(self, other: TermList<F, N, D>) -> Self::Output {
+        self + (-other)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Sub<&TermList<F, N, D>> for TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn sub(self, other: &TermList<F, N, D>) -> Self::Output {
+        self + (-other)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Sub<TermList<F, N, D>> for &TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn sub(self, other: TermList<F, N, D>) -> Self::Output {
+        self + (-other)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Sub<&TermList<F, N, D>> for &TermList<F, N, D> {
+    type Output = TermList<F, N, D>;
+
+    fn sub(self, other: &TermList<F, N, D>) -> Self::Output {
+        self + (-other)
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> One for TermList<F, N, D> {
+    fn one() -> Self {
+        Self::from(F::one())
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> Zero for TermList<F, N, D> {
+    fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    fn zero() -> Self {
+        TermList {
+            terms: BTreeMap::new(),
+        }
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> From<F> for TermList<F, N, D> {
+    fn from(value: F) -> Self {
+        let mut result = Self::zero();
+        result.add_term(vec![], value);
+        result
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> MVPoly<F, N, D> for TermList<F, N, D> {
+    /// Generate a random polynomial of maximum degree `max_degree`, with at
+    /// most `N + D` terms.
+    ///
+    /// If `None` is provided as the maximum degree, the polynomial will be
+    /// generated with a maximum degree of `D`.
+    ///
+    /// Contrary to [crate::monomials::Sparse], the monomials are not
+    /// enumerated, so that sparse polynomials with many variables can be
+    /// generated. The first term is of degree `max_degree`, therefore the
+    /// polynomial is not constant if `max_degree` is not zero.
+    ///
+    /// # Safety
+    ///
+    /// Marked as unsafe to warn the user to use it with caution and to not
+    /// necessarily rely on it for security/randomness in cryptographic
+    /// protocols. The user is responsible for providing its own secure
+    /// polynomial random generator, if needed.
+    ///
+    /// For now, the function is only used for testing.
+    unsafe fn random<RNG: RngCore>(rng: &mut RNG, max_degree: Option<usize>) -> Self {
+        let max_degree = max_degree.unwrap_or(D);
+        let nb_terms = rng.gen_range(1..=N + D);
+        let mut result = Self::zero();
+        for k in 0..nb_terms {
+            let degree = if k == 0 {
+                max_degree
+            } else {
+                rng.gen_range(0..=max_degree)
+            };
+            // Spread the degree over at most `degree` distinct variables
+            let nb_vars = if degree == 0 {
+                0
+            } else {
+                rng.gen_range(1..=degree.min(N))
+            };
+            let mut vars = sample(rng, N, nb_vars).into_vec();
+            vars.sort();
+            let mut exponents = vec![1; nb_vars];
+            for _ in nb_vars..degree {
+                exponents[rng.gen_range(0..nb_vars)] += 1;
+            }
+            let coeff = loop {
+                let c = F::rand(rng);
+                if !c.is_zero() {
+                    break c;
+                }
+            };
+            result.add_term(vars.into_iter().zip(exponents).collect(), coeff);
+        }
+        result
+    }
+
+    fn double(&self) -> Self {
+        let mut result = self.clone();
+        result.terms.values_mut().for_each(|c| {
+            c.double_in_place();
+        });
+        result
+    }
+
+    fn is_constant(&self) -> bool {
+        self.terms.keys().all(|monomial| monomial.is_empty())
+    }
+
+    fn mul_by_scalar(&self, scalar: F) -> Self {
+        if scalar.is_zero() {
+            return Self::zero();
+        }
+        let mut result = self.clone();
+        result.terms.values_mut().for_each(|c| *c *= scalar);
+        result
+    }
+
+    /// Returns the degree of the polynomial.
+    ///
+    /// The degree of the polynomial is the maximum degree of the monomials
+    /// that have a non-zero coefficient.
+    ///
+    /// # Safety
+    ///
+    /// The zero polynomial as a degree equals to 0, as the degree of the
+    /// constant polynomials. We do use the `unsafe` keyword to warn the user
+    /// for this specific case.
+    unsafe fn degree(&self) -> usize {
+        self.terms.keys().map(monomial_degree).max().unwrap_or(0)
+    }
+
+    fn eval(&self, x: &[F; N]) -> F {
+        let eval_term = |(monomial, coeff): (&Monomial, &F)| eval_monomial(monomial, x) * coeff;
+        if self.terms.len() < PAR_EVAL_MIN_TERMS {
+            self.terms.iter().map(eval_term).sum()
+        } else {
+            self.terms.par_iter().map(eval_term).sum()
+        }
+    }
+
+    fn from_variable<Column: Into<usize>>(
+        var: Variable<Column>,
+        offset_next_row: Option<usize>,
+    ) -> Self {
+        let Variable { col, row } = var;
+        let offset = match row {
+            CurrOrNext::Curr => 0,
+            CurrOrNext::Next => {
+                offset_next_row.expect("The offset must be provided for the next row")
+            }
+        };
+        let idx = offset + col.into();
+        assert!(idx < N, "The variable x_{idx} is out of bounds");
+
+        let mut result = Self::zero();
+        result.add_term(vec![(idx, 1)], F::one());
+        result
+    }
+
+    fn is_homogeneous(&self) -> bool {
+        self.terms
+            .keys()
+            .all(|monomial| monomial_degree(monomial) == D)
+    }
+
+    fn homogeneous_eval(&self, x: &[F; N], u: F) -> F {
+        self.terms
+            .iter()
+            .map(|(monomial, coeff)| {
+                let u_degree = D - monomial_degree(monomial);
+                eval_monomial(monomial, x) * u.pow([u_degree as u64]) * coeff
+            })
+            .sum()
+    }
+
+    fn add_monomial(&mut self, exponents: [usize; N], coeff: F) {
+        self.add_term(monomial_from_exponents(&exponents), coeff)
+    }
+
+    fn compute_cross_terms(
+        &self,
+        eval1: &[F; N],
+        eval2: &[F; N],
+        u1: F,
+        u2: F,
+    ) -> HashMap<usize, F> {
+        assert!(
+            D >= 2,
+            "The degree of the polynomial must be greater than 2"
+        );
+        let mut cross_terms_by_powers_of_r: HashMap<usize, F> = HashMap::new();
+        for (monomial, coeff) in &self.terms {
+            add_monomial_cross_terms::<F, D>(
+                &mut cross_terms_by_powers_of_r,
+                monomial,
+                *coeff,
+                eval1,
+                eval2,
+                u1,
+                u2,
+            );
+        }
+        cross_terms_by_powers_of_r
+    }
+
+    fn modify_monomial(&mut self, exponents: [usize; N], coeff: F) {
+        let monomial = monomial_from_exponents(&exponents);
+        if coeff.is_zero() {
+            self.terms.remove(&monomial);
+        } else {
+            self.terms.insert(monomial, coeff);
+        }
+    }
+
+    fn is_multilinear(&self) -> bool {
+        self.terms
+            .keys()
+            .all(|monomial| monomial.iter().all(|(_, d)| *d <= 1))
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> From<Sparse<F, N, D>> for TermList<F, N, D> {
+    fn from(poly: Sparse<F, N, D>) -> Self {
+        let mut result = Self::zero();
+        for (exponents, coeff) in poly.monomials {
+            result.add_monomial(exponents, coeff);
+        }
+        result
+    }
+}
+
+impl<F: PrimeField, const N: usize, const D: usize> From<TermList<F, N, D>> for Sparse<F, N, D> {
+    fn from(poly: TermList<F, N, D>) -> Self {
+        if poly.is_zero() {
+            return Sparse::zero();
+        }
+        let monomials = poly
+            .terms
+            .into_iter()
+            .map(|(monomial, coeff)| (monomial_to_exponents(&monomial), coeff))
+            .collect();
+        Sparse { monomials }
+    }
+}
@@ -0,0 +1,102 @@
+use ark_ff::{Field, UniformRand};
+use kimchi::circuits::{
+    berkeley_columns::{constant, BerkeleyChallengeTerm, Column, E},
+    expr::{Cache, ConstantExpr, Expr, Variable},
+    gate::CurrOrNext,
+};
+use mina_curves::pasta::Fp;
+use mvpoly::{monomials::Sparse, prime::Dense, terms::TermList, MVPoly};
+
+/// Witness column `i` of the current row is the variable `i`, and of the next
+/// row the variable `3 + i`
+fn column_map(var: &Variable<Column>) -> usize {
+    let offset = match var.row {
+        CurrOrNext::Curr => 0,
+        CurrOrNext::Next => 3,
+    };
+    match var.col {
+        Column::Witness(i) => offset + i,
+        _ => panic!("Only witness columns are used"),
+    }
+}
+
+fn witness(i: usize, row: CurrOrNext) -> E<Fp> {
+    E::cell(Column::Witness(i), row)
+}
+
+/// `(x0 + 2 x1)^3 - x0' * (x0 + 2 x1) + x2^2`, where `x0 + 2 x1` is cached and
+/// `x0'` is the column 0 of the next row
+fn expression(cache: &mut Cache) -> E<Fp> {
+    let s = cache.cache(
+        witness(0, CurrOrNext::Curr) + constant(Fp::from(2u64)) * witness(1, CurrOrNext::Curr),
+    );
+    s.clone().pow(3) - witness(0, CurrOrNext::Next) * s + witness(2, CurrOrNext::Curr).square()
+}
+
+fn expected_eval(x: &[Fp; 6]) -> Fp {
+    let s = x[0] + x[1].double();
+    s.pow([3]) - x[3] * s + x[2].square()
+}
+
+#[test]
+fn test_from_expr_with_cache() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let expr = expression(&mut Cache::default());
+    let x: [Fp; 6] = std::array::from_fn(|_| Fp::rand(&mut rng));
+
+    let p1: Sparse<Fp, 6, 3> = mvpoly::from_expr(&expr, column_map);
+    assert_eq!(p1.eval(&x), expected_eval(&x));
+    assert_eq!(unsafe { p1.degree() }, 3);
+
+    let p2: TermList<Fp, 6, 3> = mvpoly::from_expr(&expr, column_map);
+    assert_eq!(TermList::from(p1.clone()), p2);
+
+    let p3: Dense<Fp, 6, 3> = mvpoly::from_expr(&expr, column_map);
+    assert_eq!(Sparse::from(p3), p1);
+}
+
+#[test]
+fn test_from_expr_is_the_trait_method() {
+    // The trait method maps the columns with `Into<usize>`, which is not
+    // implemented for the columns of kimchi
+    #[derive(Clone, Copy, PartialEq)]
+    struct Witness(usize);
+    impl From<Witness> for usize {
+        fn from(w: Witness) -> usize {
+            w.0
+        }
+    }
+    type W = Expr<ConstantExpr<Fp, BerkeleyChallengeTerm>, Witness>;
+
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let x: [Fp; 6] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let s = W::cell(Witness(0), CurrOrNext::Curr) + W::cell(Witness(1), CurrOrNext::Curr).double();
+    let expr = s.clone() * s.clone() * s.clone() - W::cell(Witness(0), CurrOrNext::Next) * s
+        + W::cell(Witness(2), CurrOrNext::Curr).square();
+
+    let p = Sparse::<Fp, 6, 3>::from_expr(expr.clone(), Some(3));
+    assert_eq!(p.eval(&x), expected_eval(&x));
+    let q: Sparse<Fp, 6, 3> = mvpoly::from_expr(&expr, |var| {
+        usize::from(var.col) + if var.row == CurrOrNext::Next { 3 } else { 0 }
+    });
+    assert_eq!(p, q);
+}
+
+#[test]
+fn test_from_expr_pow() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let x: [Fp; 6] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    for e in 0..6 {
+        let expr = witness(0, CurrOrNext::Curr).pow(e);
+        let p: Sparse<Fp, 6, 5> = mvpoly::from_expr(&expr, column_map);
+        assert_eq!(p.eval(&x), x[0].pow([e]));
+        assert_eq!(unsafe { p.degree() }, e as usize);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_from_expr_variable_out_of_bounds() {
+    let expr = witness(2, CurrOrNext::Next);
+    let _: Sparse<Fp, 4, 2> = mvpoly::from_expr(&expr, column_map);
+}
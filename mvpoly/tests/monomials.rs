@@ -712,3 +712,153 @@ fn test_from_expr_ec_addition() {
         assert_eq!(eval, exp_eval);
     }
 }
+
+#[test]
+fn test_eval_many() {
+    mvpoly::pbt::test_eval_many::<Fp, 4, 3, Sparse<Fp, 4, 3>>();
+    // Enough terms to evaluate them in parallel
+    mvpoly::pbt::test_eval_many::<Fp, 7, 4, Sparse<Fp, 7, 4>>();
+}
+
+#[test]
+fn test_eval_in_parallel() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p = unsafe { Sparse::<Fp, 7, 4>::random(&mut rng, None) };
+    assert!(p.monomials.len() >= mvpoly::PAR_EVAL_MIN_TERMS);
+    let x: [Fp; 7] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let expected: Fp = p
+        .monomials
+        .iter()
+        .map(|(exponents, coeff)| {
+            exponents
+                .iter()
+                .zip(x.iter())
+                .fold(*coeff, |acc, (d, x)| acc * x.pow([*d as u64]))
+        })
+        .sum();
+    assert_eq!(p.eval(&x), expected);
+}
+
+#[test]
+fn test_mul_kronecker_equals_mul_naive() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { Sparse::<Fp, 3, 8>::random(&mut rng, None) };
+    let p2 = unsafe { Sparse::<Fp, 3, 8>::random(&mut rng, None) };
+    assert_eq!(p1.mul_kronecker(&p2), p1.mul_naive(&p2));
+
+    // Sparse polynomials, with a bound of the substitution per variable
+    let max_degree = rng.gen_range(1..4);
+    let p1 = unsafe { Sparse::<Fp, 5, 6>::random(&mut rng, Some(max_degree)) };
+    let p2 = unsafe { Sparse::<Fp, 5, 6>::random(&mut rng, Some(max_degree)) };
+    assert_eq!(p1.mul_kronecker(&p2), p1.mul_naive(&p2));
+    assert_eq!(p1.clone() * p2.clone(), p1.mul_naive(&p2));
+}
+
+#[test]
+fn test_mul_kronecker_zero() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p = unsafe { Sparse::<Fp, 3, 4>::random(&mut rng, None) };
+    let zero = Sparse::<Fp, 3, 4>::zero();
+    assert_eq!(p.mul_kronecker(&zero), zero);
+    assert_eq!(zero.mul_kronecker(&p), zero);
+}
+
+#[test]
+fn test_mul_strategy() {
+    use mvpoly::kronecker::{mul_strategy, MulStrategy, KRONECKER_MAX_SIZE};
+
+    // Few terms in a large substitution
+    assert_eq!(mul_strategy(3, 3, Some(1 << 16)), MulStrategy::Naive);
+    // Dense polynomials
+    assert_eq!(
+        mul_strategy(969, 969, Some(33 * 33 * 33)),
+        MulStrategy::Kronecker
+    );
+    // The substitution is too large, or overflows
+    assert_eq!(
+        mul_strategy(1 << 20, 1 << 20, Some(KRONECKER_MAX_SIZE + 1)),
+        MulStrategy::Naive
+    );
+    assert_eq!(mul_strategy(1 << 20, 1 << 20, None), MulStrategy::Naive);
+}
+
+#[test]
+fn test_mul_dense_uses_kronecker() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { Sparse::<Fp, 2, 32>::random(&mut rng, None) };
+    let p2 = unsafe { Sparse::<Fp, 2, 32>::random(&mut rng, None) };
+    let bounds = mvpoly::kronecker::bounds(&p1.monomials, &p2.monomials);
+    assert_eq!(
+        mvpoly::kronecker::mul_strategy(
+            p1.monomials.len(),
+            p2.monomials.len(),
+            mvpoly::kronecker::substitution_size(&bounds)
+        ),
+        mvpoly::kronecker::MulStrategy::Kronecker
+    );
+    assert_eq!(p1.clone() * p2.clone(), p1.mul_naive(&p2));
+}
+
+#[test]
+fn test_partial_eval() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p = unsafe { Sparse::<Fp, 5, 4>::random(&mut rng, None) };
+    let x: [Fp; 5] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    // Specialize the "challenges" x_1 and x_3
+    let q: Sparse<Fp, 3, 4> = p.partial_eval(&[(3, x[3]), (1, x[1])]);
+    assert_eq!(q.eval(&[x[0], x[2], x[4]]), p.eval(&x));
+    // Assigning all the variables gives a constant
+    let c: Sparse<Fp, 0, 4> = p.partial_eval(&std::array::from_fn::<_, 5, _>(|i| (i, x[i])));
+    assert_eq!(c.eval(&[]), p.eval(&x));
+    // Assigning no variable does not change the polynomial
+    let r: Sparse<Fp, 5, 4> = p.partial_eval(&[]);
+    assert_eq!(r, p);
+}
+
+#[test]
+fn test_partial_eval_renumbers_variables() {
+    // x_0 * x_1 + x_2 with x_1 = 2 gives 2 * y_0 + y_1
+    let mut p = Sparse::<Fp, 3, 2>::zero();
+    p.add_monomial([1, 1, 0], Fp::one());
+    p.add_monomial([0, 0, 1], Fp::one());
+    let q: Sparse<Fp, 2, 2> = p.partial_eval(&[(1, Fp::from(2u64))]);
+    let mut expected = Sparse::<Fp, 2, 2>::zero();
+    expected.add_monomial([1, 0], Fp::from(2u64));
+    expected.add_monomial([0, 1], Fp::one());
+    assert_eq!(q, expected);
+
+    // Terms cancelling after the evaluation are removed
+    let mut p = Sparse::<Fp, 2, 2>::zero();
+    p.add_monomial([1, 1], Fp::one());
+    p.add_monomial([0, 1], -Fp::from(2u64));
+    let q: Sparse<Fp, 1, 2> = p.partial_eval(&[(0, Fp::from(2u64))]);
+    assert_eq!(q, Sparse::<Fp, 1, 2>::zero());
+}
+
+#[test]
+#[should_panic]
+fn test_partial_eval_wrong_arity() {
+    let p = Sparse::<Fp, 3, 2>::one();
+    let _: Sparse<Fp, 3, 2> = p.partial_eval(&[(0, Fp::one())]);
+}
+
+#[test]
+fn test_widening_mul() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { Sparse::<Fp, 4, 2>::random(&mut rng, None) };
+    let p2 = unsafe { Sparse::<Fp, 4, 3>::random(&mut rng, None) };
+    let x: [Fp; 4] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let p: Sparse<Fp, 4, 5> = p1.widening_mul(&p2);
+    assert_eq!(p.eval(&x), p1.eval(&x) * p2.eval(&x));
+    let p: Sparse<Fp, 4, 3> = p1.widening_add(&p2);
+    assert_eq!(p.eval(&x), p1.eval(&x) + p2.eval(&x));
+}
+
+#[test]
+fn test_increase_arity() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { Sparse::<Fp, 4, 2>::random(&mut rng, None) };
+    let p2: Sparse<Fp, 6, 2> = p1.increase_arity();
+    let p3: Result<Sparse<Fp, 6, 2>, String> = p1.clone().into();
+    assert_eq!(p2, p3.unwrap());
+}
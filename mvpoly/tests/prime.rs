@@ -707,3 +707,22 @@ fn test_build_from_variable_next_row_with_offset_given() {
         assert_eq!(p.eval(&eval), eval[idx]);
     }
 }
+
+#[test]
+fn test_eval_many() {
+    mvpoly::pbt::test_eval_many::<Fp, 4, 3, Dense<Fp, 4, 3>>();
+    // Enough terms to evaluate them in parallel
+    mvpoly::pbt::test_eval_many::<Fp, 7, 4, Dense<Fp, 7, 4>>();
+}
+
+#[test]
+fn test_prime_widening_mul() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { Dense::<Fp, 3, 2>::random(&mut rng, None) };
+    let p2 = unsafe { Dense::<Fp, 3, 1>::random(&mut rng, None) };
+    let x: [Fp; 3] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let p: Dense<Fp, 3, 3> = p1.widening_mul(&p2);
+    assert_eq!(p.eval(&x), p1.eval(&x) * p2.eval(&x));
+    let p: Dense<Fp, 3, 2> = p1.widening_add(&p2);
+    assert_eq!(p.eval(&x), p1.eval(&x) + p2.eval(&x));
+}
@@ -0,0 +1,81 @@
+use ark_ff::{One, UniformRand, Zero};
+use mina_curves::pasta::Fp;
+use mvpoly::{monomials::Sparse, prime::Dense, terms::TermList, MVPoly};
+
+#[test]
+fn test_display_canonical_form() {
+    let mut p = Sparse::<Fp, 4, 3>::zero();
+    p.add_monomial([2, 0, 0, 1], Fp::from(3u64));
+    p.add_monomial([0, 1, 0, 0], Fp::one());
+    p.add_monomial([0, 0, 0, 0], Fp::from(5u64));
+    assert_eq!(p.to_string(), "5 + 3*x_0^2*x_3 + x_1");
+    assert_eq!(TermList::from(p.clone()).to_string(), p.to_string());
+    assert_eq!(Sparse::<Fp, 4, 3>::zero().to_string(), "0");
+    assert_eq!(Sparse::<Fp, 4, 3>::one().to_string(), "1");
+
+    let minus_one: Sparse<Fp, 1, 1> = "-1".parse().unwrap();
+    assert_eq!(minus_one.to_string(), (-Fp::one()).to_string());
+}
+
+#[test]
+fn test_parse() {
+    let p: Sparse<Fp, 4, 3> = " x_1 + 3 * x_3*x_0^2 + 2 + 3 - x_1 + x_1".parse().unwrap();
+    assert_eq!(p.to_string(), "5 + 3*x_0^2*x_3 + x_1");
+    let q: Sparse<Fp, 4, 3> = "-x_2 + x_2".parse().unwrap();
+    assert_eq!(q, Sparse::zero());
+    let r: Sparse<Fp, 4, 3> = "0".parse().unwrap();
+    assert_eq!(r, Sparse::zero());
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!("x_4".parse::<Sparse<Fp, 4, 3>>().is_err());
+    assert!("x_0^2*x_1^2".parse::<Sparse<Fp, 4, 3>>().is_err());
+    assert!("x_0 +".parse::<Sparse<Fp, 4, 3>>().is_err());
+    assert!("x_0 + + x_1".parse::<Sparse<Fp, 4, 3>>().is_err());
+    assert!("y_0".parse::<Sparse<Fp, 4, 3>>().is_err());
+    assert!("".parse::<Sparse<Fp, 4, 3>>().is_err());
+}
+
+#[test]
+fn test_text_round_trip() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p = unsafe { Sparse::<Fp, 6, 3>::random(&mut rng, None) };
+    assert_eq!(p.to_string().parse::<Sparse<Fp, 6, 3>>().unwrap(), p);
+
+    let p = unsafe { TermList::<Fp, 6, 3>::random(&mut rng, None) };
+    assert_eq!(p.to_string().parse::<TermList<Fp, 6, 3>>().unwrap(), p);
+
+    let p = unsafe { Dense::<Fp, 3, 2>::random(&mut rng, None) };
+    let q: Dense<Fp, 3, 2> = p.to_string().parse().unwrap();
+    assert_eq!(q, p);
+    assert_eq!(p.to_string(), Sparse::from(p.clone()).to_string());
+}
+
+#[test]
+fn test_serde_round_trip() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p = unsafe { Sparse::<Fp, 6, 3>::random(&mut rng, None) };
+    let json = serde_json::to_string(&p).unwrap();
+    assert_eq!(serde_json::from_str::<Sparse<Fp, 6, 3>>(&json).unwrap(), p);
+    // The representations share the same serialized form
+    let q: TermList<Fp, 6, 3> = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&q).unwrap(), json);
+
+    let p = unsafe { Dense::<Fp, 3, 2>::random(&mut rng, None) };
+    let json = serde_json::to_string(&p).unwrap();
+    assert_eq!(serde_json::from_str::<Dense<Fp, 3, 2>>(&json).unwrap(), p);
+
+    let x: [Fp; 3] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let q: Sparse<Fp, 3, 2> = serde_json::from_str(&json).unwrap();
+    assert_eq!(q.eval(&x), p.eval(&x));
+}
+
+#[test]
+fn test_serde_checks_parameters() {
+    let p = Sparse::<Fp, 3, 2>::one();
+    let json = serde_json::to_string(&p).unwrap();
+    assert!(serde_json::from_str::<Sparse<Fp, 4, 2>>(&json).is_err());
+    assert!(serde_json::from_str::<Sparse<Fp, 3, 3>>(&json).is_err());
+    assert_eq!(serde_json::from_str::<Sparse<Fp, 3, 2>>(&json).unwrap(), p);
+}
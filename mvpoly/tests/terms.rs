@@ -0,0 +1,288 @@
+use ark_ff::{Field, One, UniformRand, Zero};
+use kimchi::circuits::{expr::Variable, gate::CurrOrNext};
+use mina_curves::pasta::Fp;
+use mvpoly::{monomials::Sparse, terms::TermList, MVPoly};
+
+#[test]
+fn test_mul_by_one() {
+    mvpoly::pbt::test_mul_by_one::<Fp, 7, 2, TermList<Fp, 7, 2>>();
+}
+
+#[test]
+fn test_mul_by_zero() {
+    mvpoly::pbt::test_mul_by_zero::<Fp, 5, 4, TermList<Fp, 5, 4>>();
+}
+
+#[test]
+fn test_add_zero() {
+    mvpoly::pbt::test_add_zero::<Fp, 3, 4, TermList<Fp, 3, 4>>();
+}
+
+#[test]
+fn test_double_is_add_twice() {
+    mvpoly::pbt::test_double_is_add_twice::<Fp, 3, 4, TermList<Fp, 3, 4>>();
+}
+
+#[test]
+fn test_sub_zero() {
+    mvpoly::pbt::test_sub_zero::<Fp, 3, 4, TermList<Fp, 3, 4>>();
+}
+
+#[test]
+fn test_neg() {
+    mvpoly::pbt::test_neg::<Fp, 3, 4, TermList<Fp, 3, 4>>();
+}
+
+#[test]
+fn test_eval_pbt_add() {
+    mvpoly::pbt::test_eval_pbt_add::<Fp, 6, 4, TermList<Fp, 6, 4>>();
+}
+
+#[test]
+fn test_eval_pbt_sub() {
+    mvpoly::pbt::test_eval_pbt_sub::<Fp, 6, 4, TermList<Fp, 6, 4>>();
+}
+
+#[test]
+fn test_eval_pbt_mul_by_scalar() {
+    mvpoly::pbt::test_eval_pbt_mul_by_scalar::<Fp, 6, 4, TermList<Fp, 6, 4>>();
+}
+
+#[test]
+fn test_eval_pbt_neg() {
+    mvpoly::pbt::test_eval_pbt_neg::<Fp, 6, 4, TermList<Fp, 6, 4>>();
+}
+
+#[test]
+fn test_neg_ref() {
+    mvpoly::pbt::test_neg_ref::<Fp, 3, 4, TermList<Fp, 3, 4>>();
+}
+
+#[test]
+fn test_mul_by_scalar() {
+    mvpoly::pbt::test_mul_by_scalar::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_mul_by_scalar_with_zero() {
+    mvpoly::pbt::test_mul_by_scalar_with_zero::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_mul_by_scalar_with_one() {
+    mvpoly::pbt::test_mul_by_scalar_with_one::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_evaluation_zero_polynomial() {
+    mvpoly::pbt::test_evaluation_zero_polynomial::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_evaluation_constant_polynomial() {
+    mvpoly::pbt::test_evaluation_constant_polynomial::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_degree_constant() {
+    mvpoly::pbt::test_degree_constant::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_degree_random_degree() {
+    mvpoly::pbt::test_degree_random_degree::<Fp, 3, 5, TermList<Fp, 3, 5>>();
+}
+
+#[test]
+fn test_is_constant() {
+    mvpoly::pbt::test_is_constant::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_mvpoly_add_degree_pbt() {
+    mvpoly::pbt::test_mvpoly_add_degree_pbt::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_mvpoly_sub_degree_pbt() {
+    mvpoly::pbt::test_mvpoly_sub_degree_pbt::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_mvpoly_neg_degree_pbt() {
+    mvpoly::pbt::test_mvpoly_neg_degree_pbt::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_mvpoly_mul_by_scalar_degree_pbt() {
+    mvpoly::pbt::test_mvpoly_mul_by_scalar_degree_pbt::<Fp, 4, 5, TermList<Fp, 4, 5>>();
+}
+
+#[test]
+fn test_mvpoly_mul_degree_pbt() {
+    mvpoly::pbt::test_mvpoly_mul_degree_pbt::<Fp, 4, 6, TermList<Fp, 4, 6>>();
+}
+
+#[test]
+fn test_mvpoly_mul_eval_pbt() {
+    mvpoly::pbt::test_mvpoly_mul_eval_pbt::<Fp, 4, 6, TermList<Fp, 4, 6>>();
+}
+
+#[test]
+fn test_mvpoly_mul_pbt() {
+    mvpoly::pbt::test_mvpoly_mul_pbt::<Fp, 4, 6, TermList<Fp, 4, 6>>();
+}
+
+#[test]
+fn test_can_be_printed_with_debug() {
+    mvpoly::pbt::test_can_be_printed_with_debug::<Fp, 2, 2, TermList<Fp, 2, 2>>();
+}
+
+#[test]
+fn test_is_zero() {
+    mvpoly::pbt::test_is_zero::<Fp, 4, 6, TermList<Fp, 4, 6>>();
+}
+
+#[test]
+fn test_homogeneous_eval() {
+    mvpoly::pbt::test_homogeneous_eval::<Fp, 4, 2, TermList<Fp, 4, 2>>();
+}
+
+#[test]
+fn test_add_monomial() {
+    mvpoly::pbt::test_add_monomial::<Fp, 4, 2, TermList<Fp, 4, 2>>();
+}
+
+#[test]
+fn test_is_multilinear() {
+    mvpoly::pbt::test_is_multilinear::<Fp, 6, 2, TermList<Fp, 6, 2>>();
+}
+
+#[test]
+fn test_mvpoly_many_variables() {
+    // The dense representations would have to enumerate the monomials of
+    // degree at most 3 in 300 variables
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { TermList::<Fp, 300, 6>::random(&mut rng, Some(3)) };
+    let p2 = unsafe { TermList::<Fp, 300, 6>::random(&mut rng, Some(3)) };
+    assert!(p1.len() <= 306);
+
+    let p3 = p1.clone() * p2.clone();
+    assert!(p3.len() <= p1.len() * p2.len());
+    assert!(unsafe { p3.degree() } <= 6);
+
+    let x: [Fp; 300] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    assert_eq!(p3.eval(&x), p1.eval(&x) * p2.eval(&x));
+    assert_eq!((&p1 + &p2).eval(&x), p1.eval(&x) + p2.eval(&x));
+}
+
+#[test]
+fn test_mvpoly_compute_cross_terms() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { TermList::<Fp, 10, 4>::random(&mut rng, None) };
+    let random_eval1: [Fp; 10] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let random_eval2: [Fp; 10] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let u1 = Fp::rand(&mut rng);
+    let u2 = Fp::rand(&mut rng);
+    let cross_terms = p1.compute_cross_terms(&random_eval1, &random_eval2, u1, u2);
+
+    let r = Fp::rand(&mut rng);
+    let random_lincomb: [Fp; 10] = std::array::from_fn(|i| random_eval1[i] + r * random_eval2[i]);
+
+    let lhs = p1.homogeneous_eval(&random_lincomb, u1 + r * u2);
+
+    let rhs = {
+        let eval1_hom = p1.homogeneous_eval(&random_eval1, u1);
+        let eval2_hom = p1.homogeneous_eval(&random_eval2, u2);
+        let cross_terms_eval = cross_terms.iter().fold(Fp::zero(), |acc, (power, term)| {
+            acc + r.pow([*power as u64]) * term
+        });
+        eval1_hom + r.pow([4]) * eval2_hom + cross_terms_eval
+    };
+    assert_eq!(lhs, rhs);
+
+    // The cross-terms are the same as with the other representation
+    let p2 = Sparse::<Fp, 10, 4>::from(p1);
+    assert_eq!(
+        p2.compute_cross_terms(&random_eval1, &random_eval2, u1, u2),
+        cross_terms
+    );
+}
+
+#[test]
+fn test_from_and_into_sparse() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { Sparse::<Fp, 4, 3>::random(&mut rng, None) };
+    let p2 = TermList::<Fp, 4, 3>::from(p1.clone());
+    let x: [Fp; 4] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    assert_eq!(p1.eval(&x), p2.eval(&x));
+    assert_eq!(Sparse::from(p2.clone()), p1);
+
+    // Products computed in both representations agree
+    let p3 = unsafe { Sparse::<Fp, 4, 3>::random(&mut rng, Some(1)) };
+    let p4 = p2 * TermList::from(p3.clone());
+    assert_eq!(Sparse::from(p4), p1 * p3);
+
+    assert_eq!(
+        TermList::<Fp, 4, 3>::from(Sparse::<Fp, 4, 3>::zero()),
+        TermList::zero()
+    );
+    assert_eq!(
+        Sparse::from(TermList::<Fp, 4, 3>::one()),
+        Sparse::<Fp, 4, 3>::one()
+    );
+}
+
+#[test]
+fn test_build_from_variable() {
+    let p = TermList::<Fp, 8, 2>::from_variable::<usize>(
+        Variable {
+            col: 1,
+            row: CurrOrNext::Next,
+        },
+        Some(4),
+    );
+    let mut expected = TermList::<Fp, 8, 2>::zero();
+    expected.add_monomial(std::array::from_fn(|i| usize::from(i == 5)), Fp::one());
+    assert_eq!(p, expected);
+    assert_eq!(p.iter().next(), Some((&vec![(5, 1)], &Fp::one())));
+}
+
+#[test]
+fn test_eval_many() {
+    mvpoly::pbt::test_eval_many::<Fp, 4, 3, TermList<Fp, 4, 3>>();
+    // Enough terms to evaluate them in parallel
+    mvpoly::pbt::test_eval_many::<Fp, 7, 4, TermList<Fp, 7, 4>>();
+}
+
+#[test]
+fn test_partial_eval() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p = unsafe { TermList::<Fp, 5, 4>::random(&mut rng, None) };
+    let x: [Fp; 5] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let q: TermList<Fp, 3, 4> = p.partial_eval(&[(1, x[1]), (3, x[3])]);
+    assert_eq!(q.eval(&[x[0], x[2], x[4]]), p.eval(&x));
+    // Same result as the sparse representation
+    let sparse = Sparse::from(p.clone());
+    assert_eq!(
+        Sparse::from(q),
+        sparse.partial_eval::<3>(&[(1, x[1]), (3, x[3])])
+    );
+}
+
+#[test]
+fn test_widening_mul() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let p1 = unsafe { TermList::<Fp, 4, 2>::random(&mut rng, None) };
+    let p2 = unsafe { TermList::<Fp, 4, 3>::random(&mut rng, None) };
+    let x: [Fp; 4] = std::array::from_fn(|_| Fp::rand(&mut rng));
+    let p: TermList<Fp, 4, 5> = p1.widening_mul(&p2);
+    assert_eq!(p.eval(&x), p1.eval(&x) * p2.eval(&x));
+    let p: TermList<Fp, 4, 3> = p1.widening_add(&p2);
+    assert_eq!(p.eval(&x), p1.eval(&x) + p2.eval(&x));
+    let p: TermList<Fp, 6, 2> = p1.increase_arity();
+    assert_eq!(
+        p.eval(&[x[0], x[1], x[2], x[3], Fp::zero(), Fp::zero()]),
+        p1.eval(&x)
+    );
+}
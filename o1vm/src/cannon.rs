@@ -2,16 +2,23 @@
 
 use base64::{engine::general_purpose, Engine as _};
 
-use libflate::zlib::{Decoder, Encoder};
+use libflate::{
+    gzip,
+    zlib::{Decoder, Encoder},
+};
 use regex::Regex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::io::{Read, Write};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
 
 pub const PAGE_ADDRESS_SIZE: u32 = 12;
 pub const PAGE_SIZE: u32 = 1 << PAGE_ADDRESS_SIZE;
 pub const PAGE_ADDRESS_MASK: u32 = PAGE_SIZE - 1;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Page {
     pub index: u32,
     #[serde(deserialize_with = "from_base64", serialize_with = "to_base64")]
@@ -44,7 +51,7 @@ where
 }
 
 // The renaming below keeps compatibility with OP Cannon's state format
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct State {
     pub memory: Vec<Page>,
     #[serde(
@@ -61,6 +68,15 @@ pub struct State {
     pub lo: u32,
     pub hi: u32,
     pub heap: u32,
+    /// The program break, i.e. the end of the heap grown by `brk`. It is not
+    /// part of the state of Cannon, and is [DEFAULT_PROGRAM_BREAK] for its
+    /// states.
+    #[serde(default = "default_program_break")]
+    pub brk: u32,
+    /// The address of the handler of the floating-point instructions, zero
+    /// when none is registered. It is not part of the state of Cannon.
+    #[serde(default)]
+    pub trap_handler: u32,
     pub exit: u8,
     pub exited: bool,
     pub step: u64,
@@ -69,6 +85,212 @@ pub struct State {
     pub preimage: Option<Vec<u8>>,
 }
 
+/// The program break of the states which do not define one, e.g. the states
+/// of Cannon. It is the value returned by `brk` in Cannon.
+/// The heap allocated by `mmap` can grow above it. The program break can not
+/// grow anymore once it does.
+pub const DEFAULT_PROGRAM_BREAK: u32 = 0x4000_0000;
+
+fn default_program_break() -> u32 {
+    DEFAULT_PROGRAM_BREAK
+}
+
+/// Version of the binary encoding of the single-threaded MIPS state. The
+/// version 0 is the encoding of Cannon, which the version 1 extends with the
+/// program break, and the version 2 with the floating-point trap handler.
+pub const STATE_BINARY_VERSION: u8 = 2;
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_be_bytes(buf))
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl State {
+    /// Writes the state in the binary format of Cannon, i.e. the version
+    /// [STATE_BINARY_VERSION] followed by the big-endian encoding of
+    /// - the number of pages, as a `u32`, and each page as its index and its
+    ///   [PAGE_SIZE] bytes,
+    /// - the preimage key and offset,
+    /// - `pc`, `nextPC`, `lo`, `hi`, the heap pointer, the program break and
+    ///   the floating-point trap handler,
+    /// - the exit code, whether the program exited, and the step as a `u64`,
+    /// - the registers,
+    /// - the length of the last hint, as a `u32`, and its bytes.
+    ///
+    /// The preimage being read is not part of the format, see
+    /// [crate::interpreters::mips::witness::Env::create].
+    pub fn serialize_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[STATE_BINARY_VERSION])?;
+        writer.write_all(&(self.memory.len() as u32).to_be_bytes())?;
+        for page in self.memory.iter() {
+            if page.data.len() != PAGE_SIZE as usize {
+                return Err(invalid_data(format!(
+                    "page {} has {} bytes instead of {PAGE_SIZE}",
+                    page.index,
+                    page.data.len()
+                )));
+            }
+            writer.write_all(&page.index.to_be_bytes())?;
+            writer.write_all(&page.data)?;
+        }
+        writer.write_all(&self.preimage_key)?;
+        for word in [
+            self.preimage_offset,
+            self.pc,
+            self.next_pc,
+            self.lo,
+            self.hi,
+            self.heap,
+            self.brk,
+            self.trap_handler,
+        ] {
+            writer.write_all(&word.to_be_bytes())?;
+        }
+        writer.write_all(&[self.exit, self.exited as u8])?;
+        writer.write_all(&self.step.to_be_bytes())?;
+        for register in self.registers {
+            writer.write_all(&register.to_be_bytes())?;
+        }
+        let last_hint = self.last_hint.as_deref().unwrap_or_default();
+        writer.write_all(&(last_hint.len() as u32).to_be_bytes())?;
+        writer.write_all(last_hint)
+    }
+
+    /// Reads a state written by [State::serialize_binary], or by Cannon.
+    pub fn deserialize_binary<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let version = read_u8(reader)?;
+        if version > STATE_BINARY_VERSION {
+            return Err(invalid_data(format!("unsupported state version {version}")));
+        }
+        let n_pages = read_u32(reader)?;
+        let mut memory = Vec::with_capacity(n_pages as usize);
+        for _ in 0..n_pages {
+            let index = read_u32(reader)?;
+            let mut data = vec![0u8; PAGE_SIZE as usize];
+            reader.read_exact(&mut data)?;
+            memory.push(Page { index, data });
+        }
+        let mut preimage_key = [0u8; 32];
+        reader.read_exact(&mut preimage_key)?;
+        let preimage_offset = read_u32(reader)?;
+        let pc = read_u32(reader)?;
+        let next_pc = read_u32(reader)?;
+        let lo = read_u32(reader)?;
+        let hi = read_u32(reader)?;
+        let heap = read_u32(reader)?;
+        let brk = if version == 0 {
+            DEFAULT_PROGRAM_BREAK
+        } else {
+            read_u32(reader)?
+        };
+        let trap_handler = if version < 2 { 0 } else { read_u32(reader)? };
+        let exit = read_u8(reader)?;
+        let exited = read_u8(reader)? != 0;
+        let step = read_u64(reader)?;
+        let mut registers = [0u32; 32];
+        for register in registers.iter_mut() {
+            *register = read_u32(reader)?;
+        }
+        let last_hint_len = read_u32(reader)?;
+        let mut last_hint = vec![0u8; last_hint_len as usize];
+        reader.read_exact(&mut last_hint)?;
+        Ok(State {
+            memory,
+            preimage_key,
+            preimage_offset,
+            pc,
+            next_pc,
+            lo,
+            hi,
+            heap,
+            brk,
+            trap_handler,
+            exit,
+            exited,
+            step,
+            registers,
+            last_hint: if last_hint.is_empty() {
+                None
+            } else {
+                Some(last_hint)
+            },
+            preimage: None,
+        })
+    }
+
+    /// Reads a state from a file, in the JSON format if its name ends with
+    /// `.json` and in the binary format otherwise. A `.gz` suffix denotes a
+    /// gzip-compressed file, e.g. `state.bin.gz`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        let mut reader: Box<dyn Read> = if has_extension(path, "gz") {
+            Box::new(gzip::Decoder::new(file)?)
+        } else {
+            Box::new(file)
+        };
+        if is_json_file(path) {
+            serde_json::from_reader(reader).map_err(io::Error::from)
+        } else {
+            State::deserialize_binary(&mut reader)
+        }
+    }
+
+    /// Writes the state to a file, in the format given by its name as for
+    /// [State::from_file].
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+        let is_json = is_json_file(path);
+        if has_extension(path, "gz") {
+            let mut encoder = gzip::Encoder::new(file)?;
+            self.write_to(&mut encoder, is_json)?;
+            encoder.finish().into_result()?.flush()
+        } else {
+            let mut file = file;
+            self.write_to(&mut file, is_json)?;
+            file.flush()
+        }
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W, is_json: bool) -> io::Result<()> {
+        if is_json {
+            serde_json::to_writer(writer, self).map_err(io::Error::from)
+        } else {
+            self.serialize_binary(writer)
+        }
+    }
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().map_or(false, |ext| ext == extension)
+}
+
+fn is_json_file(path: &Path) -> bool {
+    if has_extension(path, "gz") {
+        has_extension(&path.with_extension(""), "json")
+    } else {
+        has_extension(path, "json")
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParsePreimageKeyError(String);
 
@@ -214,7 +436,50 @@ pub struct VmConfiguration {
     pub proof_fmt: String,
     pub snapshot_fmt: String,
     pub pprof_cpu: bool,
+    /// Prefix of the files of the reports of the profiler, see
+    /// [crate::profiler]. `None` disables the profiling.
+    pub profile: Option<String>,
+    /// File of the report of the statistics of the executed instructions,
+    /// see [crate::statistics]. `None` disables the statistics.
+    pub statistics: Option<String>,
+    /// Whether the standard output of the program is committed in the
+    /// proofs, see [crate::interpreters::mips::output].
+    pub commit_output: bool,
+    /// Address on which the debugger is waited for, see [crate::gdbstub].
+    /// `None` runs the program without a debugger.
+    pub gdb: Option<String>,
+    /// File in which the values given by the host to the execution are
+    /// recorded, see [crate::replay]. `None` disables the recording.
+    pub record: Option<String>,
+    /// Number of threads proving the chunks of the execution while it runs,
+    /// see [crate::pickles::pipeline]
+    pub prover_threads: usize,
+    /// Directory of the memory-mapped files storing the evaluations of the
+    /// columns while proving, see [crate::pickles::column_store]. `None`
+    /// keeps them in memory.
+    pub column_storage: Option<String>,
+    /// Symbol of the handler of the floating-point instructions registered
+    /// at the start, see
+    /// [crate::interpreters::mips::interpreter::SYSCALL_SET_FP_TRAP_HANDLER].
+    /// `None` lets the program register it.
+    pub fp_trap_handler: Option<String>,
     pub host: Option<HostProgram>,
+    pub preimage_oracle: PreImageOracleBackend,
+}
+
+/// The source of the preimages requested by the program, see
+/// [crate::preimage_oracle].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PreImageOracleBackend {
+    /// The host program of the configuration, spawned by the VM
+    #[default]
+    HostProgram,
+    /// A directory of preimage files
+    Directory(String),
+    /// A HTTP server, given by its URL
+    Http(String),
+    /// The trace file of a recorded execution, see [crate::replay]
+    Replay(String),
 }
 
 #[derive(Debug, Clone)]
@@ -282,6 +547,14 @@ impl Meta {
             )
             .map_or_else(|_| None, |idx| Some(self.symbols[idx].name.to_string()))
     }
+
+    /// The start address of the symbol `name`
+    pub fn find_symbol_address(&self, name: &str) -> Option<u32> {
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.name == name)
+            .map(|symbol| symbol.start)
+    }
 }
 
 #[cfg(test)]
@@ -456,6 +729,92 @@ mod tests {
         );
         assert!(PreimageKey::from_str("0x01").is_err());
     }
+
+    fn sample_state() -> State {
+        State {
+            memory: vec![
+                Page {
+                    index: 1,
+                    data: (0..PAGE_SIZE).map(|i| i as u8).collect(),
+                },
+                Page {
+                    index: 17,
+                    data: vec![0xab; PAGE_SIZE as usize],
+                },
+            ],
+            preimage_key: [3; 32],
+            preimage_offset: 12,
+            pc: 0x1000,
+            next_pc: 0x1004,
+            lo: 1,
+            hi: 2,
+            heap: 0x4000_0000,
+            brk: 0x4000_1000,
+            trap_handler: 0x2000,
+            exit: 0,
+            exited: false,
+            step: 1234,
+            registers: std::array::from_fn(|i| i as u32 * 7),
+            last_hint: Some(vec![0, 0, 0, 1, 42]),
+            preimage: None,
+        }
+    }
+
+    #[test]
+    fn test_state_binary_serialization() {
+        let state = sample_state();
+        let mut bytes = vec![];
+        state.serialize_binary(&mut bytes).unwrap();
+        assert_eq!(
+            bytes.len(),
+            1 + 4 + 2 * (4 + PAGE_SIZE as usize) + 32 + 8 * 4 + 2 + 8 + 32 * 4 + 4 + 5
+        );
+        // Version, then the number of pages and the index of the first one
+        assert_eq!(bytes[0..9], [2, 0, 0, 0, 2, 0, 0, 0, 1]);
+        let deserialized = State::deserialize_binary(&mut &bytes[..]).unwrap();
+        assert_eq!(deserialized, state);
+
+        // Truncated and unknown versions are rejected
+        assert!(State::deserialize_binary(&mut &bytes[..bytes.len() - 1]).is_err());
+        bytes[0] = 3;
+        assert!(State::deserialize_binary(&mut &bytes[..]).is_err());
+
+        // The version 1 does not have a trap handler
+        let brk_offset = 1 + 4 + 2 * (4 + PAGE_SIZE as usize) + 32 + 6 * 4;
+        bytes.drain(brk_offset + 4..brk_offset + 8);
+        bytes[0] = 1;
+        assert_eq!(
+            State::deserialize_binary(&mut &bytes[..]).unwrap(),
+            State {
+                trap_handler: 0,
+                ..state.clone()
+            }
+        );
+
+        // The states of Cannon do not have a program break
+        bytes.drain(brk_offset..brk_offset + 4);
+        bytes[0] = 0;
+        assert_eq!(
+            State::deserialize_binary(&mut &bytes[..]).unwrap(),
+            State {
+                brk: DEFAULT_PROGRAM_BREAK,
+                trap_handler: 0,
+                ..state
+            }
+        );
+    }
+
+    #[test]
+    fn test_state_files() {
+        let state = sample_state();
+        let directory = std::env::temp_dir();
+        for name in ["state.json", "state.json.gz", "state.bin", "state.bin.gz"] {
+            let path = directory.join(format!("o1vm-test-{name}"));
+            state.to_file(&path).unwrap();
+            assert_eq!(State::from_file(&path).unwrap(), state, "{name}");
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
 }
 
 pub const HINT_CLIENT_READ_FD: i32 = 3;
@@ -49,12 +49,83 @@ pub fn main_cli() -> clap::Command {
                 .long("pprof.cpu")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("PREFIX")
+                .help("write the folded stacks of cycles, syscalls and memory accesses per symbol to PREFIX.*.folded"),
+        )
+        .arg(
+            Arg::new("statistics")
+                .long("statistics")
+                .value_name("FILE")
+                .help("write the counts of the executed instructions, branches and syscalls, and the instructions never executed, to FILE"),
+        )
+        .arg(
+            Arg::new("commit-output")
+                .long("commit-output")
+                .help("commit to the standard output of the program in the proofs")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("prover-threads")
+                .long("prover-threads")
+                .value_name("N")
+                .default_value("1")
+                .value_parser(value_parser!(u64).range(1..))
+                .help("number of threads proving the chunks while the program runs"),
+        )
+        .arg(
+            Arg::new("column-storage")
+                .long("column-storage")
+                .value_name("DIR")
+                .help("store the evaluations of the columns in memory-mapped files in DIR while proving, to reduce the memory used"),
+        )
+        .arg(
+            Arg::new("fp-trap-handler")
+                .long("fp-trap-handler")
+                .value_name("SYMBOL")
+                .help("trap the floating-point instructions to the function SYMBOL of the program, which emulates them"),
+        )
+        .arg(
+            Arg::new("gdb")
+                .long("gdb")
+                .value_name("ADDRESS")
+                .help("wait for a GDB connection on ADDRESS (e.g. 127.0.0.1:9001) and run the program under its control, without proving"),
+        )
         .arg(
             arg!(host: [HOST] "host program specification <host program> [host program arguments]")
                 .num_args(1..)
                 .last(true)
                 .value_parser(value_parser!(String)),
         )
+        .arg(
+            Arg::new("preimage-dir")
+                .long("preimage-dir")
+                .value_name("DIR")
+                .help("read the preimages from a directory instead of the host program")
+                .conflicts_with("preimage-url"),
+        )
+        .arg(
+            Arg::new("preimage-url")
+                .long("preimage-url")
+                .value_name("URL")
+                .help("fetch the preimages from a HTTP server instead of the host program"),
+        )
+        .arg(
+            Arg::new("record")
+                .long("record")
+                .value_name("FILE")
+                .help("record the preimages and the results of the host calls of the execution to FILE")
+                .conflicts_with("replay"),
+        )
+        .arg(
+            Arg::new("replay")
+                .long("replay")
+                .value_name("FILE")
+                .help("replay the execution from the preimages and the results of the host calls recorded in FILE")
+                .conflicts_with_all(["preimage-dir", "preimage-url"]),
+        )
         .arg(
             Arg::new("snapshot-state-at")
                 .long("snapshot-state-at")
@@ -77,6 +148,24 @@ pub fn read_configuration(cli: &clap::ArgMatches) -> VmConfiguration {
     let proof_fmt = cli.get_one::<String>("proof-fmt").unwrap();
     let snapshot_fmt = cli.get_one::<String>("snapshot-fmt").unwrap();
     let pprof_cpu = cli.get_one::<bool>("pprof-cpu").unwrap();
+    let profile = cli
+        .get_one::<String>("profile")
+        .map(|prefix| prefix.to_string());
+    let statistics = cli
+        .get_one::<String>("statistics")
+        .map(|path| path.to_string());
+    let commit_output = cli.get_one::<bool>("commit-output").unwrap();
+    let gdb = cli
+        .get_one::<String>("gdb")
+        .map(|address| address.to_string());
+    let record = cli.get_one::<String>("record").map(|path| path.to_string());
+    let prover_threads = *cli.get_one::<u64>("prover-threads").unwrap() as usize;
+    let column_storage = cli
+        .get_one::<String>("column-storage")
+        .map(|path| path.to_string());
+    let fp_trap_handler = cli
+        .get_one::<String>("fp-trap-handler")
+        .map(|symbol| symbol.to_string());
 
     let host_spec = cli
         .get_many::<String>("host")
@@ -96,6 +185,17 @@ pub fn read_configuration(cli: &clap::ArgMatches) -> VmConfiguration {
         })
     };
 
+    let preimage_oracle = match (
+        cli.get_one::<String>("preimage-dir"),
+        cli.get_one::<String>("preimage-url"),
+        cli.get_one::<String>("replay"),
+    ) {
+        (Some(directory), _, _) => PreImageOracleBackend::Directory(directory.to_string()),
+        (None, Some(url), _) => PreImageOracleBackend::Http(url.to_string()),
+        (None, None, Some(path)) => PreImageOracleBackend::Replay(path.to_string()),
+        (None, None, None) => PreImageOracleBackend::HostProgram,
+    };
+
     VmConfiguration {
         input_state_file: input_state_file.to_string(),
         output_state_file: output_state_file.to_string(),
@@ -107,6 +207,15 @@ pub fn read_configuration(cli: &clap::ArgMatches) -> VmConfiguration {
         proof_fmt: proof_fmt.to_string(),
         snapshot_fmt: snapshot_fmt.to_string(),
         pprof_cpu: *pprof_cpu,
+        profile,
+        statistics,
+        commit_output: *commit_output,
+        gdb,
+        record,
+        prover_threads,
+        column_storage,
+        fp_trap_handler,
         host,
+        preimage_oracle,
     }
 }
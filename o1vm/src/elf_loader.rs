@@ -1,10 +1,19 @@
-use crate::cannon::{Page, State, PAGE_SIZE};
-use elf::{endian::LittleEndian, section::SectionHeader, ElfBytes};
+use crate::cannon::{Page, State, DEFAULT_PROGRAM_BREAK, PAGE_SIZE};
+use elf::{abi, endian::LittleEndian, file::Class, section::SectionHeader, ElfBytes};
 use log::debug;
-use std::{collections::HashMap, path::Path};
+use std::{collections::BTreeMap, path::Path};
 
 /// Parse an ELF file and return the parsed data as a structure that is expected
 /// by the o1vm RISC-V 32 bits edition.
+///
+/// The file must be a statically linked executable, possibly position
+/// independent. All the allocated sections are loaded in memory at their
+/// address, the `.bss`-like sections being zeroed. The thread-local storage
+/// sections are ignored, as the VM does not support threads. The relocations
+/// of position independent executables are applied for a program loaded at
+/// address 0.
+/// An error is returned for the files that can not be loaded, e.g. the
+/// dynamically linked ones.
 // FIXME: parametrize by an architecture. We should return a state depending on the
 // architecture. In the meantime, we can have parse_riscv32i and parse_mips.
 // FIXME: for now, we return a State structure, either for RISC-V 32i or MIPS.
@@ -13,97 +22,166 @@ use std::{collections::HashMap, path::Path};
 // and have a huge diff.
 pub fn parse_riscv32(path: &Path) -> Result<State, String> {
     debug!("Start parsing the ELF file to load a RISC-V 32i compatible state");
-    let file_data = std::fs::read(path).expect("Could not read file.");
+    parse_riscv(path, Class::ELF32)
+}
+
+/// Parse an ELF file and return the parsed data as a structure that is expected
+/// by the o1vm RISC-V 64 bits edition.
+// FIXME: the state is shared with Cannon, and holds 32 bits addresses. The
+// addresses of the program must fit in 32 bits, which is the case of the code
+// generated by the standard toolchains with the default linker scripts.
+pub fn parse_riscv64(path: &Path) -> Result<State, String> {
+    debug!("Start parsing the ELF file to load a RISC-V 64i compatible state");
+    parse_riscv(path, Class::ELF64)
+}
+
+/// Memory of the program being loaded, indexed by page
+type Pages = BTreeMap<u32, Vec<u8>>;
+
+/// Writes `bytes` in `pages` starting at `address`, allocating the pages if
+/// needed.
+fn write_bytes(pages: &mut Pages, address: u64, bytes: &[u8]) -> Result<(), String> {
+    let end_address = address + bytes.len() as u64;
+    if end_address > 1 << 32 {
+        return Err(format!(
+            "The address range {:#x}..{:#x} does not fit in 32 bits",
+            address, end_address
+        ));
+    }
+    let page_size = PAGE_SIZE as u64;
+    for (i, byte) in bytes.iter().enumerate() {
+        let address = address + i as u64;
+        let page = pages
+            .entry((address / page_size) as u32)
+            .or_insert_with(|| vec![0; PAGE_SIZE as usize]);
+        page[(address % page_size) as usize] = *byte;
+    }
+    Ok(())
+}
+
+fn parse_riscv(path: &Path, class: Class) -> Result<State, String> {
+    let file_data = std::fs::read(path)
+        .map_err(|e| format!("Could not read the file {}: {}", path.display(), e))?;
     let slice = file_data.as_slice();
-    let file = ElfBytes::<LittleEndian>::minimal_parse(slice).expect("Open ELF file failed.");
+    let file = ElfBytes::<LittleEndian>::minimal_parse(slice)
+        .map_err(|e| format!("Open ELF file failed: {}", e))?;
 
     // Checking it is RISC-V
-    assert_eq!(file.ehdr.e_machine, 243);
+    if file.ehdr.e_machine != abi::EM_RISCV {
+        return Err(format!(
+            "Expected a RISC-V file, got the machine {}",
+            file.ehdr.e_machine
+        ));
+    }
+    if file.ehdr.class != class {
+        return Err(format!(
+            "Expected an {:?} file, got an {:?} one",
+            class, file.ehdr.class
+        ));
+    }
+    // Only executables can be loaded, i.e. no object files nor core dumps.
+    // Position independent executables have the type of the shared objects.
+    if file.ehdr.e_type != abi::ET_EXEC && file.ehdr.e_type != abi::ET_DYN {
+        return Err(format!(
+            "Expected an executable file, got the type {}",
+            file.ehdr.e_type
+        ));
+    }
+    if let Some(segments) = file.segments() {
+        if segments.iter().any(|phdr| phdr.p_type == abi::PT_INTERP) {
+            return Err(
+                "The file is dynamically linked, only static executables are supported".to_string(),
+            );
+        }
+    }
 
     let (shdrs_opt, strtab_opt) = file
         .section_headers_with_strtab()
-        .expect("shdrs offsets should be valid");
+        .map_err(|e| format!("Invalid section headers: {}", e))?;
     let (shdrs, strtab) = (
-        shdrs_opt.expect("Should have shdrs"),
-        strtab_opt.expect("Should have strtab"),
+        shdrs_opt.ok_or("The file has no section headers")?,
+        strtab_opt.ok_or("The file has no section names")?,
     );
+    let section_name = |shdr: &SectionHeader| strtab.get(shdr.sh_name as usize).unwrap_or("");
 
-    // Parse the shdrs and collect them into a map keyed on their zero-copied name
-    let sections_by_name: HashMap<&str, SectionHeader> = shdrs
+    // Building the memory pages from the allocated sections, i.e. the ones
+    // which are in the memory of the program at runtime.
+    let mut pages = Pages::new();
+    for shdr in shdrs
         .iter()
-        .map(|shdr| {
-            (
-                strtab
-                    .get(shdr.sh_name as usize)
-                    .expect("Failed to get section name"),
-                shdr,
-            )
-        })
-        .collect();
-
-    debug!("Loading the text section, which contains the executable code.");
-    // Getting the executable code.
-    let text_section = sections_by_name
-        .get(".text")
-        .expect("Should have .text section");
-
-    let (text_section_data, _) = file
-        .section_data(text_section)
-        .expect("Failed to read data from .text section");
-
-    let code_section_starting_address = text_section.sh_addr as usize;
-    let code_section_size = text_section.sh_size as usize;
-    let code_section_end_address = code_section_starting_address + code_section_size;
-    debug!(
-        "The executable code starts at address {}, has size {} bytes, and ends at address {}.",
-        code_section_starting_address, code_section_size, code_section_end_address
-    );
-
-    // Building the memory pages
-    let mut memory: Vec<Page> = vec![];
-    let page_size_usize: usize = PAGE_SIZE.try_into().unwrap();
-    // Padding to get the right number of pages. We suppose that the memory
-    // index starts at 0.
-    let start_page_address: usize =
-        (code_section_starting_address / page_size_usize) * page_size_usize;
-    let end_page_address =
-        (code_section_end_address / (page_size_usize - 1)) * page_size_usize + page_size_usize;
-
-    let first_page_index = start_page_address / page_size_usize;
-    let last_page_index = (end_page_address - 1) / page_size_usize;
-    let mut data_offset = 0;
-    (first_page_index..=last_page_index).for_each(|page_index| {
-        let mut data = vec![0; page_size_usize];
-        // Special case of only one page
-        if first_page_index == last_page_index {
-            let data_length = code_section_end_address - code_section_starting_address;
-            let page_offset = code_section_starting_address - start_page_address;
-            data[page_offset..page_offset + data_length]
-                .copy_from_slice(&text_section_data[0..data_length]);
-            data_offset += data_length;
+        .filter(|shdr| shdr.sh_flags & abi::SHF_ALLOC as u64 != 0)
+    {
+        let name = section_name(&shdr);
+        if shdr.sh_flags & abi::SHF_TLS as u64 != 0 {
+            debug!("Ignoring the thread-local storage section {}.", name);
+            continue;
+        }
+        if shdr.sh_size == 0 {
+            continue;
+        }
+        if shdr.sh_addralign > 1 && shdr.sh_addr % shdr.sh_addralign != 0 {
+            return Err(format!(
+                "The section {} at address {:#x} is not aligned on {} bytes",
+                name, shdr.sh_addr, shdr.sh_addralign
+            ));
+        }
+        debug!(
+            "Loading the section {}, at address {:#x} with size {} bytes.",
+            name, shdr.sh_addr, shdr.sh_size
+        );
+        if shdr.sh_type == abi::SHT_NOBITS {
+            // Zero-initialised data, e.g. the .bss section
+            write_bytes(&mut pages, shdr.sh_addr, &vec![0; shdr.sh_size as usize])?;
         } else {
-            let data_length = if page_index == last_page_index {
-                code_section_end_address - (page_index * page_size_usize)
-            } else {
-                page_size_usize
-            };
-            let page_offset = if page_index == first_page_index {
-                code_section_starting_address - start_page_address
-            } else {
-                0
-            };
-            data[page_offset..]
-                .copy_from_slice(&text_section_data[data_offset..data_offset + data_length]);
-            data_offset += data_length;
+            let (data, compression) = file
+                .section_data(&shdr)
+                .map_err(|e| format!("Failed to read data from section {}: {}", name, e))?;
+            if compression.is_some() {
+                return Err(format!("The section {} is compressed", name));
+            }
+            write_bytes(&mut pages, shdr.sh_addr, data)?;
+        }
+    }
+
+    // Applying the relocations of position independent executables. They are
+    // the dynamic relocations, which are allocated.
+    for shdr in shdrs.iter().filter(|shdr| {
+        (shdr.sh_type == abi::SHT_RELA || shdr.sh_type == abi::SHT_REL)
+            && shdr.sh_flags & abi::SHF_ALLOC as u64 != 0
+    }) {
+        let name = section_name(&shdr);
+        if shdr.sh_type == abi::SHT_REL {
+            return Err(format!(
+                "The relocations without addend of section {} are not supported",
+                name
+            ));
         }
-        let page = Page {
-            index: page_index as u32,
-            data,
-        };
-        memory.push(page);
-    });
+        let relas = file
+            .section_data_as_relas(&shdr)
+            .map_err(|e| format!("Failed to read relocations from section {}: {}", name, e))?;
+        for rela in relas {
+            if rela.r_type != abi::R_RISCV_RELATIVE {
+                return Err(format!(
+                    "The relocation of type {} at address {:#x} is not supported",
+                    rela.r_type, rela.r_offset
+                ));
+            }
+            // The program is loaded at address 0, the relocated value is
+            // the addend.
+            let value = rela.r_addend as u64;
+            match class {
+                Class::ELF32 => {
+                    write_bytes(&mut pages, rela.r_offset, &(value as u32).to_le_bytes())?
+                }
+                Class::ELF64 => write_bytes(&mut pages, rela.r_offset, &value.to_le_bytes())?,
+            }
+        }
+    }
 
-    // FIXME: add data section into memory for static data saved in the binary
+    let memory: Vec<Page> = pages
+        .into_iter()
+        .map(|(index, data)| Page { index, data })
+        .collect();
 
     // FIXME: we're lucky that RISCV32i and MIPS have the same number of
     let registers: [u32; 32] = [0; 32];
@@ -113,9 +191,24 @@ pub fn parse_riscv32(path: &Path) -> Result<State, String> {
     // FIXME: it is only because we share the same structure for the state.
     let preimage_offset = 0;
 
-    // Entry point of the program
-    let pc: u32 = file.ehdr.e_entry as u32;
-    assert!(pc != 0, "Entry point is 0. The documentation of the ELF library says that it means the ELF doesn't have an entry point. This is not supported.");
+    // Entry point of the program. The documentation of the ELF library says
+    // that 0 means the ELF doesn't have an entry point, in which case we fall
+    // back to the symbol `_start`.
+    let entry = if file.ehdr.e_entry != 0 {
+        file.ehdr.e_entry
+    } else {
+        let (symtab, symtab_strtab) = file
+            .symbol_table()
+            .map_err(|e| format!("Invalid symbol table: {}", e))?
+            .ok_or("The file has no entry point and no symbol table")?;
+        symtab
+            .iter()
+            .find(|sym| matches!(symtab_strtab.get(sym.st_name as usize), Ok("_start")))
+            .map(|sym| sym.st_value)
+            .ok_or("The file has no entry point and no symbol _start")?
+    };
+    let pc: u32 = u32::try_from(entry)
+        .map_err(|_| format!("Entry point {} does not fit in 32 bits", entry))?;
     let next_pc: u32 = pc + 4u32;
 
     let state = State {
@@ -131,6 +224,8 @@ pub fn parse_riscv32(path: &Path) -> Result<State, String> {
         // FIXME: only because Cannon related
         hi: 0,
         heap: 0,
+        brk: DEFAULT_PROGRAM_BREAK,
+        trap_handler: 0,
         exit: 0,
         exited: false,
         step: 0,
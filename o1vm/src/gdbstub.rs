@@ -0,0 +1,520 @@
+//! A stub of the GDB remote serial protocol, to debug the programs executed by
+//! the MIPS interpreter with `gdb` or any tool speaking the protocol.
+//!
+//! The stub listens on a TCP address given by the option `--gdb`, and runs
+//! the program under the control of the debugger, without building any
+//! witness. For instance:
+//! ```text
+//! $ gdb-multiarch program.elf -ex "target remote 127.0.0.1:9001"
+//! ```
+//!
+//! The registers are exposed in the layout of GDB for 32 bits MIPS targets:
+//! the 32 general purpose registers, followed by `sr`, `lo`, `hi`, `bad`,
+//! `cause` and `pc`. The registers `sr`, `bad` and `cause` do not exist in the
+//! VM, and are always zero. The memory that has not been allocated yet is read
+//! as zero, as in Cannon.
+//!
+//! The supported requests are the queries of the registers and of the memory,
+//! their modification, the software and hardware breakpoints, the single step
+//! and the continuation of the execution. A continuation can be interrupted
+//! with `Ctrl-C`.
+//!
+//! The specification of the protocol is available at
+//! <https://sourceware.org/gdb/current/onlinedocs/gdb.html/Remote-Protocol.html>.
+
+use crate::{
+    cannon::{Meta, Start, VmConfiguration, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE},
+    interpreters::mips::witness::Env,
+    preimage_oracle::PreImageOracleT,
+};
+use ark_ff::Field;
+use log::info;
+use std::{
+    collections::BTreeSet,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+};
+
+/// Number of registers sent to the debugger, see the documentation of the
+/// module.
+pub const NUM_GDB_REGISTERS: usize = 38;
+
+const GDB_REGISTER_LO: usize = 33;
+const GDB_REGISTER_HI: usize = 34;
+const GDB_REGISTER_PC: usize = 37;
+
+const SIGINT: u8 = 2;
+const SIGTRAP: u8 = 5;
+
+/// The byte sent by the debugger to interrupt the execution.
+const INTERRUPT: u8 = 0x03;
+
+/// Number of steps between two checks of an interruption by the debugger while
+/// the execution is continued.
+const INTERRUPT_CHECK_PERIOD: u64 = 1 << 16;
+
+/// A message sent by the debugger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Incoming {
+    Packet(String),
+    /// A packet whose checksum does not match its content
+    BadChecksum,
+    Interrupt,
+}
+
+/// What the stub has to do after a packet of the debugger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Reply(String),
+    Step,
+    Continue,
+    /// Stops the session, after replying `OK`
+    Detach,
+    /// Stops the session, without replying
+    Kill,
+}
+
+pub struct GdbStub<'a, Fp, PreImageOracle: PreImageOracleT> {
+    env: &'a mut Env<Fp, PreImageOracle>,
+    config: &'a VmConfiguration,
+    meta: &'a Meta,
+    start: &'a Start,
+    breakpoints: BTreeSet<u32>,
+}
+
+impl<'a, Fp: Field, PreImageOracle: PreImageOracleT> GdbStub<'a, Fp, PreImageOracle> {
+    pub fn new(
+        env: &'a mut Env<Fp, PreImageOracle>,
+        config: &'a VmConfiguration,
+        meta: &'a Meta,
+        start: &'a Start,
+    ) -> Self {
+        GdbStub {
+            env,
+            config,
+            meta,
+            start,
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    fn read_register(&self, index: usize) -> u32 {
+        match index {
+            0..=31 => self.env.registers.general_purpose[index],
+            GDB_REGISTER_LO => self.env.registers.lo,
+            GDB_REGISTER_HI => self.env.registers.hi,
+            GDB_REGISTER_PC => self.env.registers.current_instruction_pointer,
+            _ => 0,
+        }
+    }
+
+    fn write_register(&mut self, index: usize, value: u32) {
+        match index {
+            // The register zero is hardwired
+            1..=31 => self.env.registers.general_purpose[index] = value,
+            GDB_REGISTER_LO => self.env.registers.lo = value,
+            GDB_REGISTER_HI => self.env.registers.hi = value,
+            GDB_REGISTER_PC => {
+                self.env.registers.current_instruction_pointer = value;
+                self.env.registers.next_instruction_pointer = value.wrapping_add(4);
+            }
+            _ => (),
+        }
+    }
+
+    fn read_memory(&self, address: u32) -> u8 {
+        let page = address >> PAGE_ADDRESS_SIZE;
+        let page_address = (address & PAGE_ADDRESS_MASK) as usize;
+        // The pages are not allocated by the debugger when reading
+        self.env
+            .memory
+            .iter()
+            .find(|(page_index, _)| *page_index == page)
+            .map_or(0, |(_, memory)| memory[page_address])
+    }
+
+    fn write_memory(&mut self, address: u32, value: u8) {
+        let page = address >> PAGE_ADDRESS_SIZE;
+        let page_address = (address & PAGE_ADDRESS_MASK) as usize;
+        // Keep the pages of the memory and of the write indices in sync
+        let memory_idx = self.env.get_memory_page_index(page);
+        self.env.get_memory_access_page_index(page);
+        self.env.memory[memory_idx].1[page_address] = value;
+    }
+
+    /// The reply to the debugger when the execution stops, because of `signal`
+    /// or because the program has exited.
+    fn stop_reply(&self, signal: u8) -> String {
+        if self.env.halt {
+            // The exit code is still in the first argument of the syscall
+            // `exit_group`
+            format!("W{:02x}", self.env.registers.general_purpose[4] & 0xff)
+        } else {
+            format!("S{:02x}", signal)
+        }
+    }
+
+    /// Handles a packet of the debugger, without its framing.
+    pub fn handle_packet(&mut self, packet: &str) -> Action {
+        self.try_handle_packet(packet)
+            // The errors of the stub do not carry a specific meaning
+            .unwrap_or_else(|| Action::Reply("E01".to_string()))
+    }
+
+    fn try_handle_packet(&mut self, packet: &str) -> Option<Action> {
+        let reply = |reply: &str| Some(Action::Reply(reply.to_string()));
+        let split = packet
+            .char_indices()
+            .nth(1)
+            .map_or(packet.len(), |(i, _)| i);
+        let (command, args) = packet.split_at(split);
+        match command {
+            "?" => Some(Action::Reply(self.stop_reply(SIGTRAP))),
+            "g" => Some(Action::Reply(
+                (0..NUM_GDB_REGISTERS)
+                    .map(|index| format!("{:08x}", self.read_register(index)))
+                    .collect(),
+            )),
+            "G" => {
+                let values = hex::decode(args).ok()?;
+                for (index, value) in values.chunks_exact(4).enumerate() {
+                    self.write_register(index, u32::from_be_bytes(value.try_into().unwrap()));
+                }
+                reply("OK")
+            }
+            "p" => {
+                let index = usize::from_str_radix(args, 16).ok()?;
+                if index < NUM_GDB_REGISTERS {
+                    Some(Action::Reply(format!("{:08x}", self.read_register(index))))
+                } else {
+                    // The registers of the floating point unit are unavailable
+                    reply("xxxxxxxx")
+                }
+            }
+            "P" => {
+                let (index, value) = args.split_once('=')?;
+                let index = usize::from_str_radix(index, 16).ok()?;
+                let value = u32::from_str_radix(value, 16).ok()?;
+                self.write_register(index, value);
+                reply("OK")
+            }
+            "m" => {
+                let (address, length) = parse_address_length(args)?;
+                let bytes: Vec<u8> = (0..length)
+                    .map(|i| self.read_memory(address.wrapping_add(i)))
+                    .collect();
+                Some(Action::Reply(hex::encode(bytes)))
+            }
+            "M" => {
+                let (range, data) = args.split_once(':')?;
+                let (address, length) = parse_address_length(range)?;
+                let bytes = hex::decode(data).ok()?;
+                if bytes.len() != length as usize {
+                    return None;
+                }
+                for (i, byte) in bytes.into_iter().enumerate() {
+                    self.write_memory(address.wrapping_add(i as u32), byte);
+                }
+                reply("OK")
+            }
+            "s" | "c" => {
+                // The execution can be resumed at a given address
+                if !args.is_empty() {
+                    let address = u32::from_str_radix(args, 16).ok()?;
+                    self.write_register(GDB_REGISTER_PC, address);
+                }
+                Some(if command == "s" {
+                    Action::Step
+                } else {
+                    Action::Continue
+                })
+            }
+            "Z" | "z" => {
+                let mut fields = args.split(',');
+                // Only the software and hardware breakpoints are supported,
+                // not the watchpoints
+                let kind = fields.next()?;
+                if kind != "0" && kind != "1" {
+                    return reply("");
+                }
+                let address = u32::from_str_radix(fields.next()?, 16).ok()?;
+                if command == "Z" {
+                    self.breakpoints.insert(address);
+                } else {
+                    self.breakpoints.remove(&address);
+                }
+                reply("OK")
+            }
+            "H" | "T" => reply("OK"),
+            "D" => Some(Action::Detach),
+            "k" => Some(Action::Kill),
+            _ => match packet {
+                "vCont?" => reply("vCont;c;s"),
+                // The VM has a single thread, the actions apply to it
+                _ if packet.starts_with("vCont;s") => Some(Action::Step),
+                _ if packet.starts_with("vCont;c") => Some(Action::Continue),
+                _ if packet.starts_with("qSupported") => reply("PacketSize=4000;vContSupported+"),
+                "qAttached" => reply("1"),
+                "qC" => reply("QC1"),
+                "qfThreadInfo" => reply("m1"),
+                "qsThreadInfo" => reply("l"),
+                // The empty reply signals an unsupported request
+                _ => reply(""),
+            },
+        }
+    }
+
+    /// Executes a single step of the program, if it has not exited.
+    pub fn step(&mut self) -> String {
+        if !self.env.halt {
+            self.env.step(self.config, self.meta, self.start);
+        }
+        self.stop_reply(SIGTRAP)
+    }
+
+    /// Continues the execution until a breakpoint is reached, the program
+    /// exits, or `interrupted` returns `true`.
+    pub fn continue_execution(&mut self, mut interrupted: impl FnMut() -> bool) -> String {
+        let mut steps: u64 = 0;
+        while !self.env.halt {
+            self.env.step(self.config, self.meta, self.start);
+            steps += 1;
+            if self
+                .breakpoints
+                .contains(&self.env.registers.current_instruction_pointer)
+            {
+                break;
+            }
+            if steps % INTERRUPT_CHECK_PERIOD == 0 && interrupted() {
+                return self.stop_reply(SIGINT);
+            }
+        }
+        self.stop_reply(SIGTRAP)
+    }
+
+    /// Serves the requests of the debugger connected by `stream`, until it
+    /// detaches or kills the program.
+    pub fn serve(&mut self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream;
+        while let Some(incoming) = read_packet(&mut reader)? {
+            let packet = match incoming {
+                Incoming::Packet(packet) => {
+                    writer.write_all(b"+")?;
+                    packet
+                }
+                Incoming::BadChecksum => {
+                    writer.write_all(b"-")?;
+                    continue;
+                }
+                Incoming::Interrupt => {
+                    write_packet(&mut writer, &self.stop_reply(SIGINT))?;
+                    continue;
+                }
+            };
+            let reply = match self.handle_packet(&packet) {
+                Action::Reply(reply) => reply,
+                Action::Step => self.step(),
+                Action::Continue => self.continue_execution(|| interrupted(&mut reader)),
+                Action::Detach => {
+                    write_packet(&mut writer, "OK")?;
+                    break;
+                }
+                Action::Kill => break,
+            };
+            write_packet(&mut writer, &reply)?;
+        }
+        Ok(())
+    }
+}
+
+/// Listens on `address` for a debugger, and serves its requests on the
+/// execution of the program of `env`.
+pub fn listen<Fp: Field, PreImageOracle: PreImageOracleT>(
+    address: &str,
+    env: &mut Env<Fp, PreImageOracle>,
+    config: &VmConfiguration,
+    meta: &Meta,
+    start: &Start,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    info!("Waiting for a GDB connection on {}", listener.local_addr()?);
+    let (stream, peer) = listener.accept()?;
+    info!("GDB connected from {}", peer);
+    GdbStub::new(env, config, meta, start).serve(stream)
+}
+
+fn parse_address_length(args: &str) -> Option<(u32, u32)> {
+    let (address, length) = args.split_once(',')?;
+    Some((
+        u32::from_str_radix(address, 16).ok()?,
+        u32::from_str_radix(length, 16).ok()?,
+    ))
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte))
+}
+
+/// Reads the next message of the debugger, skipping the acknowledgments.
+/// Returns `None` once the connection is closed.
+pub fn read_packet<R: BufRead>(reader: &mut R) -> io::Result<Option<Incoming>> {
+    let mut byte = [0u8];
+    loop {
+        if reader.read(&mut byte)? == 0 {
+            return Ok(None);
+        }
+        match byte[0] {
+            b'$' => break,
+            INTERRUPT => return Ok(Some(Incoming::Interrupt)),
+            // The acknowledgments and the noise between the packets
+            _ => (),
+        }
+    }
+    let mut data = vec![];
+    if reader.read_until(b'#', &mut data)? == 0 || data.pop() != Some(b'#') {
+        return Ok(None);
+    }
+    let mut expected = [0u8; 2];
+    reader.read_exact(&mut expected)?;
+    let expected = std::str::from_utf8(&expected)
+        .ok()
+        .and_then(|expected| u8::from_str_radix(expected, 16).ok());
+    if expected != Some(checksum(&data)) {
+        return Ok(Some(Incoming::BadChecksum));
+    }
+    match String::from_utf8(data) {
+        Ok(packet) => Ok(Some(Incoming::Packet(packet))),
+        Err(_) => Ok(Some(Incoming::BadChecksum)),
+    }
+}
+
+/// Writes `data` in a packet.
+pub fn write_packet<W: Write>(writer: &mut W, data: &str) -> io::Result<()> {
+    write!(writer, "${}#{:02x}", data, checksum(data.as_bytes()))?;
+    writer.flush()
+}
+
+/// Checks, without blocking, whether the debugger has sent an interruption.
+fn interrupted(reader: &mut BufReader<TcpStream>) -> bool {
+    if reader.get_ref().set_nonblocking(true).is_err() {
+        return false;
+    }
+    let mut byte = [0u8];
+    let result = reader.read(&mut byte);
+    let _ = reader.get_ref().set_nonblocking(false);
+    matches!(result, Ok(1) if byte[0] == INTERRUPT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cannon::PAGE_SIZE,
+        interpreters::mips::tests_helpers::{dummy_configuration, dummy_env},
+    };
+    use std::io::Cursor;
+
+    fn reply(action: Action) -> String {
+        match action {
+            Action::Reply(reply) => reply,
+            action => panic!("Expected a reply, got {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_gdbstub_packet_framing() {
+        let mut written = vec![];
+        write_packet(&mut written, "m1000,4").unwrap();
+        assert_eq!(written, b"$m1000,4#8e");
+
+        let mut reader = Cursor::new(b"+$m1000,4#8e$g#00\x03".to_vec());
+        assert_eq!(
+            read_packet(&mut reader).unwrap(),
+            Some(Incoming::Packet("m1000,4".to_string()))
+        );
+        assert_eq!(
+            read_packet(&mut reader).unwrap(),
+            Some(Incoming::BadChecksum)
+        );
+        assert_eq!(read_packet(&mut reader).unwrap(), Some(Incoming::Interrupt));
+        assert_eq!(read_packet(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_gdbstub_registers_and_memory() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut env = dummy_env(&mut rng);
+        let (config, meta, start) = (
+            dummy_configuration(),
+            Meta { symbols: vec![] },
+            Start::create(0),
+        );
+        let mut stub = GdbStub::new(&mut env, &config, &meta, &start);
+
+        assert_eq!(reply(stub.handle_packet("P3=deadbeef")), "OK");
+        assert_eq!(reply(stub.handle_packet("p3")), "deadbeef");
+        // The register zero is hardwired
+        let zero = reply(stub.handle_packet("p0"));
+        assert_eq!(reply(stub.handle_packet("P0=1")), "OK");
+        assert_eq!(reply(stub.handle_packet("p0")), zero);
+        let registers = reply(stub.handle_packet("g"));
+        assert_eq!(registers.len(), 8 * NUM_GDB_REGISTERS);
+        assert_eq!(&registers[24..32], "deadbeef");
+        assert_eq!(
+            &registers[8 * GDB_REGISTER_PC..],
+            &format!("{:08x}", PAGE_SIZE)
+        );
+
+        assert_eq!(reply(stub.handle_packet("M10,3:010203")), "OK");
+        assert_eq!(
+            reply(stub.handle_packet("mf,5")),
+            format!(
+                "{:02x}010203{:02x}",
+                stub.read_memory(0xf),
+                stub.read_memory(0x13)
+            )
+        );
+        // The unallocated memory is read as zero, without being allocated
+        let n_pages = stub.env.memory.len();
+        assert_eq!(reply(stub.handle_packet("m7fff0000,2")), "0000");
+        assert_eq!(stub.env.memory.len(), n_pages);
+
+        assert_eq!(reply(stub.handle_packet("m10")), "E01");
+        assert_eq!(reply(stub.handle_packet("Z2,10,4")), "");
+        assert_eq!(reply(stub.handle_packet("qUnknown")), "");
+    }
+
+    #[test]
+    fn test_gdbstub_breakpoints() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut env = dummy_env(&mut rng);
+        let (config, meta, start) = (
+            dummy_configuration(),
+            Meta { symbols: vec![] },
+            Start::create(0),
+        );
+        let mut stub = GdbStub::new(&mut env, &config, &meta, &start);
+        // The executable memory of the dummy environment only contains `nop`
+        let pc = PAGE_SIZE;
+
+        assert_eq!(stub.handle_packet("s"), Action::Step);
+        assert_eq!(stub.step(), "S05");
+        assert_eq!(stub.env.registers.current_instruction_pointer, pc + 4);
+
+        assert_eq!(
+            reply(stub.handle_packet(&format!("Z0,{:x},4", pc + 16))),
+            "OK"
+        );
+        assert_eq!(stub.handle_packet("c"), Action::Continue);
+        assert_eq!(stub.continue_execution(|| false), "S05");
+        assert_eq!(stub.env.registers.current_instruction_pointer, pc + 16);
+
+        assert_eq!(
+            reply(stub.handle_packet(&format!("z0,{:x},4", pc + 16))),
+            "OK"
+        );
+        assert!(stub.breakpoints.is_empty());
+        assert_eq!(stub.handle_packet("vCont;c"), Action::Continue);
+    }
+}
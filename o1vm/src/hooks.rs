@@ -0,0 +1,387 @@
+//! Programmatic hooks on the execution of the MIPS interpreter, to inspect or
+//! modify the environment when a condition is met, without a debugger.
+//!
+//! Three kinds of triggers are supported:
+//! - the breakpoints, triggered when the instruction pointer enters a range of
+//!   addresses,
+//! - the watchpoints, triggered when an instruction accesses, or changes, the
+//!   memory in a range of addresses,
+//! - the register conditions, triggered when an instruction sets a register to
+//!   a given value.
+//!
+//! The triggers are checked after each step, as the breakpoints of
+//! [crate::gdbstub]: a breakpoint is triggered once the instruction pointer
+//! reaches the address, before the instruction at this address is executed.
+//! The callback of a triggered hook receives the environment and the event,
+//! and decides whether the execution continues. For instance:
+//! ```text
+//! let mut hooks = Hooks::new();
+//! hooks.add_breakpoint(main..main + 4, |env, _| {
+//!     env.registers.general_purpose[4] = 42;
+//!     HookAction::Continue
+//! });
+//! hooks.add_watchpoint(buffer..buffer + 64, WatchKind::Change, |_, _| HookAction::Stop);
+//! let reason = hooks.run(&mut env, &config, &meta, &start);
+//! ```
+
+use crate::{
+    cannon::{Meta, Start, VmConfiguration},
+    interpreters::mips::{registers::Registers, witness::Env},
+    memory_argument::MemoryAccess,
+    preimage_oracle::PreImageOracleT,
+};
+use ark_ff::Field;
+use std::ops::Range;
+
+/// Identifier of a hook, returned when the hook is added
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HookId(pub usize);
+
+/// The accesses triggering a watchpoint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchKind {
+    /// Any access, i.e. a read or a write
+    Access,
+    /// An access changing the value in the memory
+    Change,
+}
+
+/// The condition triggering a hook
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Trigger {
+    Breakpoint(Range<u32>),
+    Watchpoint {
+        range: Range<u32>,
+        kind: WatchKind,
+    },
+    /// The register of index `index` (see
+    /// [crate::interpreters::mips::registers]) being set to `value`. The
+    /// condition is only triggered by the instruction setting the value, and
+    /// not while the register keeps it.
+    Register {
+        index: usize,
+        value: u32,
+    },
+}
+
+/// The event given to the callback of a triggered hook
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    Breakpoint { pc: u32 },
+    Watchpoint { access: MemoryAccess },
+    Register { index: usize, value: u32 },
+}
+
+/// Whether the execution continues after the callback of a hook
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookAction {
+    Continue,
+    Stop,
+}
+
+/// Why [Hooks::run] has returned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// The program has halted
+    Halted,
+    /// The callback of the hook has stopped the execution
+    Hook(HookId),
+}
+
+pub type HookCallback<'a, Fp, PreImageOracle> =
+    Box<dyn FnMut(&mut Env<Fp, PreImageOracle>, &HookEvent) -> HookAction + 'a>;
+
+struct Hook<'a, Fp, PreImageOracle: PreImageOracleT> {
+    id: HookId,
+    trigger: Trigger,
+    callback: HookCallback<'a, Fp, PreImageOracle>,
+}
+
+impl<Fp: Field, PreImageOracle: PreImageOracleT> Hook<'_, Fp, PreImageOracle> {
+    /// The events triggering the hook in the last step, see [Hooks::trigger]
+    fn events(
+        &self,
+        env: &Env<Fp, PreImageOracle>,
+        registers: &Registers<u32>,
+        accesses: &[MemoryAccess],
+    ) -> Vec<HookEvent> {
+        match &self.trigger {
+            Trigger::Breakpoint(range) => {
+                let pc = env.registers.current_instruction_pointer;
+                if range.contains(&pc) {
+                    vec![HookEvent::Breakpoint { pc }]
+                } else {
+                    vec![]
+                }
+            }
+            Trigger::Watchpoint { range, kind } => accesses
+                .iter()
+                .filter(|access| {
+                    (range.start as u64..range.end as u64).contains(&access.addr)
+                        && (*kind == WatchKind::Access || access.old_value != access.new_value)
+                })
+                .map(|access| HookEvent::Watchpoint { access: *access })
+                .collect(),
+            Trigger::Register { index, value } => {
+                if env.registers[*index] == *value && registers[*index] != *value {
+                    vec![HookEvent::Register {
+                        index: *index,
+                        value: *value,
+                    }]
+                } else {
+                    vec![]
+                }
+            }
+        }
+    }
+}
+
+/// A set of hooks, run on an environment with [Hooks::run].
+pub struct Hooks<'a, Fp, PreImageOracle: PreImageOracleT> {
+    hooks: Vec<Hook<'a, Fp, PreImageOracle>>,
+    next_id: usize,
+}
+
+impl<Fp: Field, PreImageOracle: PreImageOracleT> Default for Hooks<'_, Fp, PreImageOracle> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, Fp: Field, PreImageOracle: PreImageOracleT> Hooks<'a, Fp, PreImageOracle> {
+    pub fn new() -> Self {
+        Hooks {
+            hooks: vec![],
+            next_id: 0,
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        trigger: Trigger,
+        callback: impl FnMut(&mut Env<Fp, PreImageOracle>, &HookEvent) -> HookAction + 'a,
+    ) -> HookId {
+        let id = HookId(self.next_id);
+        self.next_id += 1;
+        self.hooks.push(Hook {
+            id,
+            trigger,
+            callback: Box::new(callback),
+        });
+        id
+    }
+
+    pub fn add_breakpoint(
+        &mut self,
+        range: Range<u32>,
+        callback: impl FnMut(&mut Env<Fp, PreImageOracle>, &HookEvent) -> HookAction + 'a,
+    ) -> HookId {
+        self.add(Trigger::Breakpoint(range), callback)
+    }
+
+    pub fn add_watchpoint(
+        &mut self,
+        range: Range<u32>,
+        kind: WatchKind,
+        callback: impl FnMut(&mut Env<Fp, PreImageOracle>, &HookEvent) -> HookAction + 'a,
+    ) -> HookId {
+        self.add(Trigger::Watchpoint { range, kind }, callback)
+    }
+
+    pub fn add_register_condition(
+        &mut self,
+        index: usize,
+        value: u32,
+        callback: impl FnMut(&mut Env<Fp, PreImageOracle>, &HookEvent) -> HookAction + 'a,
+    ) -> HookId {
+        self.add(Trigger::Register { index, value }, callback)
+    }
+
+    /// Removes the hook `id`. Returns whether the hook existed.
+    pub fn remove(&mut self, id: HookId) -> bool {
+        let len = self.hooks.len();
+        self.hooks.retain(|hook| hook.id != id);
+        self.hooks.len() != len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    fn has_watchpoints(&self) -> bool {
+        self.hooks
+            .iter()
+            .any(|hook| matches!(hook.trigger, Trigger::Watchpoint { .. }))
+    }
+
+    /// Executes the program until it halts, or until the callback of a hook
+    /// stops the execution. A step is executed before checking the hooks, so
+    /// that the execution can be resumed after a stop.
+    /// The accesses to the memory are recorded during the execution when
+    /// there are watchpoints, and [Env::memory_accesses] is left unchanged.
+    pub fn run(
+        &mut self,
+        env: &mut Env<Fp, PreImageOracle>,
+        config: &VmConfiguration,
+        meta: &Meta,
+        start: &Start,
+    ) -> StopReason {
+        let record_accesses = env.memory_accesses.is_none() && self.has_watchpoints();
+        if record_accesses {
+            env.memory_accesses = Some(vec![]);
+        }
+        let reason = loop {
+            if env.halt {
+                break StopReason::Halted;
+            }
+            let registers = env.registers.clone();
+            let n_accesses = env.memory_accesses.as_ref().map_or(0, Vec::len);
+            env.step(config, meta, start);
+            let accesses = match &mut env.memory_accesses {
+                Some(accesses) if record_accesses => std::mem::take(accesses),
+                Some(accesses) => accesses[n_accesses..].to_vec(),
+                None => vec![],
+            };
+            if let Some(id) = self.trigger(env, &registers, &accesses) {
+                break StopReason::Hook(id);
+            }
+        };
+        if record_accesses {
+            env.memory_accesses = None;
+        }
+        reason
+    }
+
+    /// Calls the callbacks of the hooks triggered by the last step, given
+    /// the registers before the step and the accesses to the memory of the
+    /// step. Returns the first hook stopping the execution.
+    fn trigger(
+        &mut self,
+        env: &mut Env<Fp, PreImageOracle>,
+        registers: &Registers<u32>,
+        accesses: &[MemoryAccess],
+    ) -> Option<HookId> {
+        // The triggers are checked before calling any callback, so that the
+        // modifications of the environment by the callbacks do not trigger
+        // other hooks.
+        let triggered: Vec<(usize, Vec<HookEvent>)> = self
+            .hooks
+            .iter()
+            .enumerate()
+            .map(|(i, hook)| (i, hook.events(env, registers, accesses)))
+            .filter(|(_, events)| !events.is_empty())
+            .collect();
+        let mut stop = None;
+        for (i, events) in triggered {
+            let hook = &mut self.hooks[i];
+            for event in events.iter() {
+                if (hook.callback)(env, event) == HookAction::Stop && stop.is_none() {
+                    stop = Some(hook.id);
+                }
+            }
+        }
+        stop
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreters::mips::{
+        interpreter::debugging::InstructionParts,
+        tests_helpers::{dummy_configuration, dummy_env, write_instruction},
+    };
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_hooks() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut env = dummy_env(&mut rng);
+        let config = dummy_configuration();
+        let meta = Meta { symbols: vec![] };
+        let start = Start::create(0);
+        let pc = env.registers.current_instruction_pointer;
+        // sw $t0, 0($t1), followed by nops
+        env.registers.general_purpose[8] = 0xdeadbeef;
+        env.registers.general_purpose[9] = 0x100;
+        env.memory[0].1[0x100..0x104].fill(0);
+        write_instruction(
+            &mut env,
+            InstructionParts {
+                op_code: 0b101011,
+                rs: 9,
+                rt: 8,
+                rd: 0,
+                shamt: 0,
+                funct: 0,
+            },
+        );
+
+        let events = RefCell::new(vec![]);
+        let mut hooks = Hooks::new();
+        let watchpoint = hooks.add_watchpoint(0x100..0x104, WatchKind::Change, |_, event| {
+            events.borrow_mut().push(*event);
+            HookAction::Stop
+        });
+        let breakpoint = hooks.add_breakpoint(pc + 8..pc + 12, |env, event| {
+            events.borrow_mut().push(*event);
+            // The callbacks can modify the environment
+            env.registers.general_purpose[10] = 42;
+            HookAction::Stop
+        });
+        let register = hooks.add_register_condition(10, 42, |_, event| {
+            events.borrow_mut().push(*event);
+            HookAction::Continue
+        });
+
+        assert_eq!(
+            hooks.run(&mut env, &config, &meta, &start),
+            StopReason::Hook(watchpoint)
+        );
+        assert!(env.memory_accesses.is_none());
+        assert_eq!(env.registers.current_instruction_pointer, pc + 4);
+        let changes: Vec<_> = events
+            .borrow()
+            .iter()
+            .map(|event| match event {
+                HookEvent::Watchpoint { access } => access.new_value,
+                event => panic!("Unexpected event {:?}", event),
+            })
+            .collect();
+        assert_eq!(changes, vec![0xde, 0xad, 0xbe, 0xef]);
+        events.borrow_mut().clear();
+
+        // The execution resumes after a stop
+        assert_eq!(
+            hooks.run(&mut env, &config, &meta, &start),
+            StopReason::Hook(breakpoint)
+        );
+        assert_eq!(*events.borrow(), vec![HookEvent::Breakpoint { pc: pc + 8 }]);
+        events.borrow_mut().clear();
+
+        // The register set by the callback has not triggered the condition,
+        // only an instruction setting it does.
+        assert_eq!(env.registers.general_purpose[10], 42);
+        assert!(hooks.remove(breakpoint));
+        assert!(!hooks.remove(breakpoint));
+        assert!(hooks.remove(register));
+        let register = hooks.add_register_condition(10, 0x1234, |_, _| HookAction::Stop);
+        // ori $t2, $zero, 0x1234
+        write_instruction(
+            &mut env,
+            InstructionParts {
+                op_code: 0b001101,
+                rs: 0,
+                rt: 10,
+                rd: 0x1234 >> 11,
+                shamt: (0x1234 >> 6) & 0x1F,
+                funct: 0x1234 & 0x3F,
+            },
+        );
+        assert_eq!(
+            hooks.run(&mut env, &config, &meta, &start),
+            StopReason::Hook(register)
+        );
+        assert!(events.borrow().is_empty());
+    }
+}
@@ -24,8 +24,8 @@ use super::column::N_MIPS_SEL_COLS;
 
 /// The environment keeping the constraints between the different polynomials
 pub struct Env<Fp> {
-    scratch_state_idx: usize,
-    scratch_state_idx_inverse: usize,
+    pub(crate) scratch_state_idx: usize,
+    pub(crate) scratch_state_idx_inverse: usize,
     /// A list of constraints, which are multi-variate polynomials over a field,
     /// represented using the expression framework of `kimchi`.
     constraints: Vec<E<Fp>>,
@@ -404,6 +404,14 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
 
     fn report_exit(&mut self, _exit_code: &Self::Variable) {}
 
+    fn report_output(
+        &mut self,
+        _fd: &Self::Variable,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+    ) {
+    }
+
     /// This function checks that the preimage is read correctly.
     /// It adds 13 constraints, and 5 lookups for the communication channel.
     /// In particular, at every step it writes the bytes of the preimage into
@@ -629,6 +637,123 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         // No-op, witness only
     }
 
+    fn request_host_call(
+        &mut self,
+        _syscall_num: &Self::Variable,
+        _args: &[Self::Variable; 4],
+        pos: [Self::Position; 2],
+    ) -> [Self::Variable; 2] {
+        // The outputs of the host functions are not constrained, see
+        // [crate::interpreters::mips::host_functions]
+        pos.map(|pos| self.variable(pos))
+    }
+
+    fn request_keccak_absorb(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        bytes: &[Self::Variable; 4],
+        pos: Self::Position,
+    ) -> Self::Variable {
+        // How many hashes have been performed so far in the circuit
+        let hash_counter = self.variable(Self::Position::ScratchState(MIPS_HASH_COUNTER_OFF));
+
+        // How many bytes have been absorbed in the current hash so far
+        let byte_counter = self.variable(Self::Position::ScratchState(MIPS_BYTE_COUNTER_OFF));
+
+        // Whether at least n bytes (1, 2, 3, or 4) are absorbed
+        let has_n_bytes: [_; MIPS_CHUNK_BYTES_LEN] = array::from_fn(|i| {
+            self.variable(Self::Position::ScratchState(MIPS_HAS_N_BYTES_OFF + i))
+        });
+
+        // The number of bytes absorbed in this instruction
+        let absorbed_bytes = self.variable(pos);
+
+        for var in has_n_bytes.iter() {
+            self.assert_boolean(var.clone());
+        }
+        // The bytes absorbed are a prefix of the bytes read
+        for i in 1..MIPS_CHUNK_BYTES_LEN {
+            self.add_constraint(
+                has_n_bytes[i].clone() * (Expr::from(1) - has_n_bytes[i - 1].clone()),
+            );
+        }
+        self.add_constraint(
+            absorbed_bytes.clone()
+                - has_n_bytes
+                    .iter()
+                    .fold(Expr::from(0), |acc, has| acc + has.clone()),
+        );
+        // The number of bytes absorbed is constrained by the caller with the
+        // address and the length
+
+        // COMMUNICATION CHANNEL: Write the bytes absorbed, as a preimage chunk
+        for i in 0..MIPS_CHUNK_BYTES_LEN {
+            self.add_lookup(Lookup::write_if(
+                has_n_bytes[i].clone(),
+                LookupTableIDs::SyscallLookup,
+                vec![
+                    hash_counter.clone(),
+                    byte_counter.clone() + Expr::from(i as u64),
+                    bytes[i].clone(),
+                ],
+            ));
+        }
+
+        absorbed_bytes
+    }
+
+    fn request_keccak_digest(
+        &mut self,
+        pos: [Self::Position; 8],
+    ) -> ([Self::Variable; 8], Self::Variable) {
+        // How many hashes have been performed so far in the circuit
+        let hash_counter = self.variable(Self::Position::ScratchState(MIPS_HASH_COUNTER_OFF));
+
+        // Whether this instruction finalizes the hash (boolean)
+        let end_of_preimage = self.variable(Self::Position::ScratchState(MIPS_END_OF_PREIMAGE_OFF));
+
+        // The digest without its most significant byte, as read from the
+        // Keccak circuit
+        let digest = self.variable(Self::Position::ScratchState(MIPS_PREIMAGE_KEY));
+
+        // The two most significant bytes of the digest
+        let msb = self.variable(Self::Position::ScratchState(MIPS_LENGTH_BYTES_OFF));
+        let second_byte = self.variable(Self::Position::ScratchState(MIPS_LENGTH_BYTES_OFF + 1));
+
+        let words = pos.map(|pos| self.variable(pos));
+
+        self.assert_boolean(end_of_preimage.clone());
+        // The words are range checked by the caller. The first one is the
+        // most significant byte followed by 24 bits.
+        self.lookup_8bits(&msb);
+        self.lookup_8bits(&second_byte);
+        self.lookup_16bits(
+            &(words[0].clone()
+                - msb.clone() * Expr::from(2u64.pow(24))
+                - second_byte * Expr::from(2u64.pow(16))),
+        );
+        // The words are the big-endian decomposition of the digest
+        // FIXME: the most significant byte is not output by the Keccak
+        // circuit, which only writes the 31 other bytes in the channel
+        {
+            let first_word = words[0].clone() - msb * Expr::from(2u64.pow(24));
+            let recomposed = words[1..].iter().fold(first_word, |acc, word| {
+                acc * Expr::from(2u64.pow(32)) + word.clone()
+            });
+            self.add_constraint(end_of_preimage.clone() * (recomposed - digest.clone()));
+        }
+
+        // COMMUNICATION CHANNEL: Read hash output
+        self.add_lookup(Lookup::read_if(
+            end_of_preimage.clone(),
+            LookupTableIDs::SyscallLookup,
+            vec![hash_counter, digest],
+        ));
+
+        (words, end_of_preimage)
+    }
+
     fn reset(&mut self) {
         self.scratch_state_idx = 0;
         self.scratch_state_idx_inverse = 0;
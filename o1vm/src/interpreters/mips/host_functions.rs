@@ -0,0 +1,193 @@
+//! Host functions, i.e. syscalls defined by the embedder of the VM.
+//!
+//! An application can extend the VM with domain-specific calls to the host
+//! (e.g. fetching a storage slot) by registering a [HostFunction] for a
+//! syscall number in the [HostFunctions] of the witness environment,
+//! without patching the interpreter.
+//! A registered syscall is decoded as
+//! [RTypeInstruction::SyscallHost](crate::interpreters::mips::RTypeInstruction::SyscallHost):
+//! the syscall number is read from `$v0`, the arguments from `$a0` to `$a3`,
+//! and the two values returned by the host function are written in `$v0`
+//! and `$a3`, the latter being the error code as for the other syscalls.
+//! Pointer arguments can be dereferenced through [GuestMemory].
+//!
+//! Every call is recorded as a [HostIO] with the memory it read, and the log
+//! of the calls is given to the embedder by [HostFunctions::io].
+//! The values returned to the guest are not constrained by the circuit, like
+//! the other values provided by the host (e.g. the preimages): the provers of
+//! the VM do not prove the lookups of the interpreter yet, so the calls cannot
+//! be read from the log in the circuit. The embedder is responsible for
+//! checking the log against the host functions.
+
+use crate::{
+    cannon::{PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE},
+    interpreters::mips::interpreter::{
+        SYSCALL_BRK, SYSCALL_CLONE, SYSCALL_EXIT_GROUP, SYSCALL_FCNTL, SYSCALL_KECCAK_ABSORB,
+        SYSCALL_KECCAK_SQUEEZE, SYSCALL_MMAP, SYSCALL_READ, SYSCALL_SET_FP_TRAP_HANDLER,
+        SYSCALL_WRITE,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, rc::Rc};
+use thiserror::Error;
+
+/// The syscalls handled by the interpreter, which cannot be redefined.
+pub const BUILTIN_SYSCALLS: [u32; 10] = [
+    SYSCALL_MMAP,
+    SYSCALL_BRK,
+    SYSCALL_CLONE,
+    SYSCALL_EXIT_GROUP,
+    SYSCALL_READ,
+    SYSCALL_WRITE,
+    SYSCALL_FCNTL,
+    SYSCALL_KECCAK_ABSORB,
+    SYSCALL_KECCAK_SQUEEZE,
+    SYSCALL_SET_FP_TRAP_HANDLER,
+];
+
+/// The inputs of a call to a host function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostCallArgs {
+    /// The syscall number, read from `$v0`
+    pub syscall_num: u32,
+    /// The arguments, read from `$a0` to `$a3`
+    pub args: [u32; 4],
+}
+
+/// The outputs of a call to a host function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostCallOutput {
+    /// The value written in `$v0`
+    pub value: u32,
+    /// The error code written in `$a3`, `0` on success
+    pub error: u32,
+}
+
+/// Read-only view of the memory of the guest, given to the host functions to
+/// dereference their pointer arguments. The bytes read are recorded in the
+/// [HostIO] of the call.
+pub struct GuestMemory<'a> {
+    memory: &'a [(u32, Vec<u8>)],
+    reads: Vec<(u32, Vec<u8>)>,
+}
+
+impl<'a> GuestMemory<'a> {
+    pub fn new(memory: &'a [(u32, Vec<u8>)]) -> Self {
+        GuestMemory {
+            memory,
+            reads: vec![],
+        }
+    }
+
+    /// Reads `len` bytes starting at `addr`. The pages that have not been
+    /// allocated yet read as zeros.
+    pub fn read(&mut self, addr: u32, len: u32) -> Vec<u8> {
+        let bytes: Vec<u8> = (0..len)
+            .map(|i| {
+                let addr = addr.wrapping_add(i);
+                let page = addr >> PAGE_ADDRESS_SIZE;
+                let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
+                self.memory
+                    .iter()
+                    .find(|(index, _)| *index == page)
+                    .map_or(0, |(_, data)| data[page_address])
+            })
+            .collect();
+        self.reads.push((addr, bytes.clone()));
+        bytes
+    }
+
+    /// Reads the big-endian word at `addr`.
+    pub fn read_u32(&mut self, addr: u32) -> u32 {
+        u32::from_be_bytes(self.read(addr, 4).try_into().unwrap())
+    }
+}
+
+/// A function of the host, called by the guest through a syscall.
+pub trait HostFunction {
+    fn call(&self, args: &HostCallArgs, memory: &mut GuestMemory) -> HostCallOutput;
+}
+
+impl<F: Fn(&HostCallArgs, &mut GuestMemory) -> HostCallOutput> HostFunction for F {
+    fn call(&self, args: &HostCallArgs, memory: &mut GuestMemory) -> HostCallOutput {
+        self(args, memory)
+    }
+}
+
+/// A call to a host function, as seen by the guest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostIO {
+    /// The normalized instruction counter of the syscall, i.e. the number of
+    /// instructions executed before it, which identifies the call
+    pub instruction_counter: u64,
+    pub args: HostCallArgs,
+    /// The address and the bytes of each read of the guest memory
+    pub memory_reads: Vec<(u32, Vec<u8>)>,
+    pub output: HostCallOutput,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HostFunctionError {
+    #[error("the syscall {0} is handled by the interpreter and cannot be redefined")]
+    BuiltinSyscall(u32),
+    #[error("a host function is already registered for the syscall {0}")]
+    AlreadyRegistered(u32),
+}
+
+/// The registry of the host functions, indexed by syscall number, with the
+/// log of the calls made so far.
+#[derive(Clone, Default)]
+pub struct HostFunctions {
+    functions: BTreeMap<u32, Rc<dyn HostFunction>>,
+    io: Vec<HostIO>,
+}
+
+impl HostFunctions {
+    /// Registers `function` as the handler of the syscall `syscall_num`.
+    pub fn register<F: HostFunction + 'static>(
+        &mut self,
+        syscall_num: u32,
+        function: F,
+    ) -> Result<(), HostFunctionError> {
+        if BUILTIN_SYSCALLS.contains(&syscall_num) {
+            return Err(HostFunctionError::BuiltinSyscall(syscall_num));
+        }
+        if self.functions.contains_key(&syscall_num) {
+            return Err(HostFunctionError::AlreadyRegistered(syscall_num));
+        }
+        self.functions.insert(syscall_num, Rc::new(function));
+        Ok(())
+    }
+
+    pub fn contains(&self, syscall_num: u32) -> bool {
+        self.functions.contains_key(&syscall_num)
+    }
+
+    /// Calls the host function registered for `args.syscall_num` and records
+    /// the call, made by the instruction `instruction_counter`.
+    pub fn call(
+        &mut self,
+        instruction_counter: u64,
+        args: HostCallArgs,
+        memory: &[(u32, Vec<u8>)],
+    ) -> HostCallOutput {
+        let function = self
+            .functions
+            .get(&args.syscall_num)
+            .unwrap_or_else(|| panic!("No host function for the syscall {}", args.syscall_num));
+        let mut memory = GuestMemory::new(memory);
+        let output = function.call(&args, &mut memory);
+        self.io.push(HostIO {
+            instruction_counter,
+            args,
+            memory_reads: memory.reads,
+            output,
+        });
+        output
+    }
+
+    /// The calls made so far, in order.
+    pub fn io(&self) -> &[HostIO] {
+        &self.io
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{
     cannon::PAGE_ADDRESS_SIZE,
     interpreters::mips::registers::{
-        REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER, REGISTER_HI, REGISTER_LO, REGISTER_NEXT_IP,
-        REGISTER_PREIMAGE_KEY_END, REGISTER_PREIMAGE_OFFSET,
+        REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER, REGISTER_HI, REGISTER_KECCAK_DIGEST_START,
+        REGISTER_LO, REGISTER_NEXT_IP, REGISTER_PREIMAGE_KEY_END, REGISTER_PREIMAGE_OFFSET,
+        REGISTER_PROGRAM_BREAK, REGISTER_TRAP_HANDLER,
     },
     lookups::{Lookup, LookupTableIDs},
 };
@@ -26,6 +27,40 @@ pub const SYSCALL_READ: u32 = 4003;
 pub const SYSCALL_WRITE: u32 = 4004;
 pub const SYSCALL_FCNTL: u32 = 4055;
 
+/// Absorbs bytes in the Keccak256 precompile: `$a0` is the address of the
+/// bytes and `$a1` their number. As for `read`, at most the bytes up to the
+/// end of the word containing the address are absorbed, and their number is
+/// returned in `$v0`. The first absorption after a digest starts a new hash.
+pub const SYSCALL_KECCAK_ABSORB: u32 = 5500;
+/// Returns in `$v0` the word `$a0` (from 0 to 7, in big-endian) of the
+/// Keccak256 digest of the bytes absorbed by [SYSCALL_KECCAK_ABSORB]. The
+/// first call finalizes the hash, which is proven by the Keccak circuit
+/// instead of the guest instructions, as for the preimages.
+pub const SYSCALL_KECCAK_SQUEEZE: u32 = 5501;
+/// Registers the address `$a0` as the handler of the floating-point
+/// instructions, zero unregistering it, and returns the previous handler in
+/// `$v0`.
+/// The floating-point instructions, which are not supported by the
+/// interpreter, then trap to the handler (see
+/// [RTypeInstruction::TrapFloatingPoint]) instead of aborting the execution.
+/// The handler, e.g. a soft-float library of the guest, finds the address of
+/// the trapping instruction in `$k0` and the address to resume the execution
+/// at in `$k1`. It emulates the instruction, then returns with `jr $k1`.
+pub const SYSCALL_SET_FP_TRAP_HANDLER: u32 = 5506;
+
+/// The general purpose registers in which the floating-point trap gives the
+/// address of the trapping instruction and the address to resume at, i.e.
+/// `$k0` and `$k1`, which are reserved for the kernel.
+pub const REGISTER_TRAP_ADDRESS: u32 = 26;
+pub const REGISTER_TRAP_RESUME_ADDRESS: u32 = 27;
+
+/// Whether the encoded `instruction` is a floating-point instruction, i.e.
+/// an instruction of the coprocessor 1 (COP1 and COP1X), or a load or a
+/// store of a floating-point register (`lwc1`, `ldc1`, `swc1` and `sdc1`).
+pub fn is_floating_point(instruction: u32) -> bool {
+    matches!(instruction >> 26, 0x11 | 0x13 | 0x31 | 0x35 | 0x39 | 0x3d)
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Hash, Ord, PartialOrd)]
 pub enum Instruction {
     RType(RTypeInstruction),
@@ -47,6 +82,7 @@ pub enum RTypeInstruction {
     JumpRegister,                 // jr
     JumpAndLinkRegister,          // jalr
     SyscallMmap,                  // syscall (Mmap)
+    SyscallBrk,                   // syscall (Brk)
     SyscallExitGroup,             // syscall (ExitGroup)
     SyscallReadHint,              // syscall (Read 3)
     SyscallReadPreimage,          // syscall (Read 5)
@@ -55,7 +91,12 @@ pub enum RTypeInstruction {
     SyscallWritePreimage,         // syscall (Write 6)
     SyscallWriteOther,            // syscall (Write ?)
     SyscallFcntl,                 // syscall (Fcntl)
-    SyscallOther,                 // syscall (Brk, Clone, ?)
+    SyscallOther,                 // syscall (Clone, ?)
+    SyscallHost,                  // syscall (registered host function)
+    SyscallKeccakAbsorb,          // syscall (Keccak256 absorb)
+    SyscallKeccakSqueeze,         // syscall (Keccak256 squeeze)
+    SyscallSetTrapHandler,        // syscall (set floating-point trap handler)
+    TrapFloatingPoint,            // floating-point instruction, trapped
     MoveZero,                     // movz
     MoveNonZero,                  // movn
     Sync,                         // sync
@@ -157,6 +198,42 @@ impl IntoIterator for Instruction {
     }
 }
 
+impl RTypeInstruction {
+    /// Whether the instruction is one of the kinds of `syscall`
+    pub fn is_syscall(&self) -> bool {
+        use RTypeInstruction::*;
+        matches!(
+            self,
+            SyscallMmap
+                | SyscallBrk
+                | SyscallExitGroup
+                | SyscallReadHint
+                | SyscallReadPreimage
+                | SyscallReadOther
+                | SyscallWriteHint
+                | SyscallWritePreimage
+                | SyscallWriteOther
+                | SyscallFcntl
+                | SyscallOther
+                | SyscallHost
+                | SyscallKeccakAbsorb
+                | SyscallKeccakSqueeze
+                | SyscallSetTrapHandler
+        )
+    }
+}
+
+impl ITypeInstruction {
+    /// Whether the instruction is a conditional branch
+    pub fn is_branch(&self) -> bool {
+        use ITypeInstruction::*;
+        matches!(
+            self,
+            BranchEq | BranchNeq | BranchLeqZero | BranchGtZero | BranchLtZero | BranchGeqZero
+        )
+    }
+}
+
 pub trait InterpreterEnv {
     /// A position can be seen as an indexed variable
     type Position;
@@ -459,6 +536,17 @@ pub trait InterpreterEnv {
             unsafe { self.fetch_memory_access(addr, last_accessed_location) }
         };
         let instruction_counter = self.instruction_counter();
+        // The access is given to the memory argument, see
+        // `crate::memory_argument::MemoryAccess::table_entry`.
+        self.add_lookup(Lookup::write_one(
+            LookupTableIDs::MemoryAccessLookup,
+            vec![
+                addr.clone(),
+                instruction_counter.clone(),
+                old_value.clone(),
+                new_value.clone(),
+            ],
+        ));
         let elapsed_time = instruction_counter.clone() - last_accessed.clone();
         let new_accessed = {
             // Here, we write as if the memory had been written *at the start of the next
@@ -704,6 +792,31 @@ pub trait InterpreterEnv {
         position: Self::Position,
     ) -> Self::Variable;
 
+    /// Returns 1 if `x < y` as unsigned 32-bits integers, or 0 otherwise.
+    /// Unlike [InterpreterEnv::test_less_than], the result is constrained:
+    /// `x - y + 2^32 * (x < y)` must be in `[0, 2^32)`, which is checked by
+    /// decomposing it into two 16-bits limbs. The values `x` and `y` must be
+    /// 32-bits values.
+    fn less_than(&mut self, x: &Self::Variable, y: &Self::Variable) -> Self::Variable {
+        let res = {
+            let pos = self.alloc_scratch();
+            unsafe { self.test_less_than(x, y, pos) }
+        };
+        self.assert_boolean(res.clone());
+        let two_to_16 = Self::constant(1 << 16);
+        let diff = x.clone() + res.clone() * two_to_16.clone() * two_to_16.clone() - y.clone();
+        let [low, high] = [(16, 0), (32, 16)].map(|(highest_bit, lowest_bit)| {
+            let limb = {
+                let pos = self.alloc_scratch();
+                unsafe { self.bitmask(&diff, highest_bit, lowest_bit, pos) }
+            };
+            self.lookup_16bits(&limb);
+            limb
+        });
+        self.add_constraint(diff - (high * two_to_16 + low));
+        res
+    }
+
     /// Returns 1 if `x < y` as signed integers, or 0 otherwise, storing the result in `position`.
     ///
     /// # Safety
@@ -953,6 +1066,50 @@ pub trait InterpreterEnv {
 
     fn request_hint_write(&mut self, addr: &Self::Variable, len: &Self::Variable);
 
+    /// Reports the write of `len` bytes starting at `addr` to the file
+    /// descriptor `fd`, captured when it is the standard output or error.
+    /// The bytes are not constrained, see
+    /// [crate::interpreters::mips::output].
+    fn report_output(&mut self, fd: &Self::Variable, addr: &Self::Variable, len: &Self::Variable);
+
+    /// Calls the host function registered for `syscall_num` with the
+    /// arguments `args`, and returns the value and the error code it returns,
+    /// which are set into `pos`.
+    /// The call is recorded in the log of the calls, and its outputs are not
+    /// constrained, see [crate::interpreters::mips::host_functions].
+    fn request_host_call(
+        &mut self,
+        syscall_num: &Self::Variable,
+        args: &[Self::Variable; 4],
+        pos: [Self::Position; 2],
+    ) -> [Self::Variable; 2];
+
+    /// Absorbs in the Keccak256 precompile the first bytes of `bytes`, read
+    /// from the memory at `addr`: at most `len`, and none past the end of the
+    /// word containing `addr`. The number of bytes absorbed is set into `pos`
+    /// and returned.
+    /// The bytes are written in the syscall channel, to be read by the Keccak
+    /// circuit as the bytes of a preimage.
+    fn request_keccak_absorb(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        bytes: &[Self::Variable; 4],
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    /// Returns the 8 big-endian words of the Keccak256 digest of the bytes
+    /// absorbed so far, which are set into `pos`, and whether this call
+    /// finalizes the hash.
+    /// The first call after an absorption finalizes the hash: it is computed
+    /// by the Keccak circuit, and its digest is read from the syscall
+    /// channel. The caller must constrain the words returned by the other
+    /// calls, which are the ones of the last digest.
+    fn request_keccak_digest(
+        &mut self,
+        pos: [Self::Position; 8],
+    ) -> ([Self::Variable; 8], Self::Variable);
+
     /// Reset the environment to handle the next instruction
     fn reset(&mut self);
 }
@@ -1138,6 +1295,42 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
+        RTypeInstruction::SyscallBrk => {
+            // The program break can grow to any address, the memory being
+            // allocated when accessed. Requests to shrink it, and `brk(0)`,
+            // leave it unchanged. In both cases, the new program break is
+            // returned.
+            // The heap allocated by `mmap` grows from the heap pointer, and
+            // reaches the program break after enough allocations, e.g. from
+            // the heap of Cannon to `DEFAULT_PROGRAM_BREAK`.
+            // When it does, the region above the program break is not free
+            // anymore, and the request fails, also leaving the program break
+            // unchanged, as `brk` does on Linux.
+            let address = env.read_register(&Env::constant(4));
+            let program_break = env.read_register(&Env::constant(REGISTER_PROGRAM_BREAK as u32));
+            let heap_pointer = env.read_register(&Env::constant(REGISTER_HEAP_POINTER as u32));
+            let grows = env.less_than(&program_break, &address);
+            let heap_overlaps = env.less_than(&program_break, &heap_pointer);
+            let moves = {
+                let pos = env.alloc_scratch();
+                env.copy(&(grows * (Env::constant(1) - heap_overlaps)), pos)
+            };
+            let new_program_break = {
+                let pos = env.alloc_scratch();
+                env.copy(
+                    &(moves.clone() * address + (Env::constant(1) - moves) * program_break),
+                    pos,
+                )
+            };
+            env.write_register(
+                &Env::constant(REGISTER_PROGRAM_BREAK as u32),
+                new_program_break.clone(),
+            );
+            env.write_register(&Env::constant(2), new_program_break);
+            env.write_register(&Env::constant(7), Env::constant(0));
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
         RTypeInstruction::SyscallExitGroup => {
             let exit_code = env.read_register(&Env::constant(4));
             env.report_exit(&exit_code);
@@ -1357,7 +1550,9 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
         }
         RTypeInstruction::SyscallWriteOther => {
             let fd_id = env.read_register(&Env::constant(4));
+            let addr = env.read_register(&Env::constant(5));
             let write_length = env.read_register(&Env::constant(6));
+            env.report_output(&fd_id, &addr, &write_length);
             let mut check_equal = |expected_fd_id: u32| {
                 // FIXME: Requires constraints
                 let pos = env.alloc_scratch();
@@ -1416,15 +1611,131 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
         }
         RTypeInstruction::SyscallOther => {
             let syscall_num = env.read_register(&Env::constant(2));
-            let is_sysbrk = env.equal(&syscall_num, &Env::constant(SYSCALL_BRK));
             let is_sysclone = env.equal(&syscall_num, &Env::constant(SYSCALL_CLONE));
-            let v0 = { is_sysbrk * Env::constant(0x40000000) + is_sysclone };
+            let v0 = is_sysclone;
             let v1 = Env::constant(0);
             env.write_register(&Env::constant(2), v0);
             env.write_register(&Env::constant(7), v1);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
+        RTypeInstruction::SyscallHost => {
+            let syscall_num = env.read_register(&Env::constant(2));
+            let args = [4, 5, 6, 7].map(|reg| env.read_register(&Env::constant(reg)));
+            let [value, error] = {
+                let pos = [env.alloc_scratch(), env.alloc_scratch()];
+                env.request_host_call(&syscall_num, &args, pos)
+            };
+            env.write_register(&Env::constant(2), value);
+            env.write_register(&Env::constant(7), error);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        RTypeInstruction::SyscallKeccakAbsorb => {
+            let addr = env.read_register(&Env::constant(4));
+            let length = env.read_register(&Env::constant(5));
+            let bytes = [0, 1, 2, 3].map(|i| env.read_memory(&(addr.clone() + Env::constant(i))));
+            // The i-th byte is absorbed when it is before the end of the word
+            // containing `addr`, i.e. `i < 4 - addr % 4`, and `i < length`
+            let [addr_bit_0, addr_bit_1] = [0, 1].map(|i| {
+                let bit = {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.bitmask(&addr, i + 1, i, pos) }
+                };
+                env.assert_boolean(bit.clone());
+                bit
+            });
+            let addr_middle = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&addr, 18, 2, pos) }
+            };
+            env.lookup_16bits(&addr_middle);
+            let addr_high = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&addr, 32, 18, pos) }
+            };
+            env.range_check16(&addr_high, 14);
+            env.add_constraint(
+                addr.clone()
+                    - (addr_high * Env::constant(1 << 18)
+                        + addr_middle * Env::constant(1 << 2)
+                        + addr_bit_1.clone() * Env::constant(2)
+                        + addr_bit_0.clone()),
+            );
+            let in_word = [
+                Env::constant(1),
+                Env::constant(1) - addr_bit_0.clone() * addr_bit_1.clone(),
+                Env::constant(1) - addr_bit_1.clone(),
+                (Env::constant(1) - addr_bit_0) * (Env::constant(1) - addr_bit_1),
+            ];
+            let in_input: [_; 4] = {
+                let mut is_shorter = Env::constant(0);
+                std::array::from_fn(|i| {
+                    is_shorter = is_shorter.clone() + env.equal(&length, &Env::constant(i as u32));
+                    Env::constant(1) - is_shorter.clone()
+                })
+            };
+            let absorbed = {
+                let pos = env.alloc_scratch();
+                env.request_keccak_absorb(&addr, &length, &bytes, pos)
+            };
+            env.add_constraint(
+                absorbed.clone()
+                    - in_word
+                        .into_iter()
+                        .zip(in_input)
+                        .fold(Env::constant(0), |acc, (in_word, in_input)| {
+                            acc + in_word * in_input
+                        }),
+            );
+            env.write_register(&Env::constant(2), absorbed);
+            env.write_register(&Env::constant(7), Env::constant(0));
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        RTypeInstruction::SyscallKeccakSqueeze => {
+            let index = env.read_register(&Env::constant(4));
+            let (words, finalized) = {
+                let pos = std::array::from_fn(|_| env.alloc_scratch());
+                env.request_keccak_digest(pos)
+            };
+            for (i, word) in words.iter().enumerate() {
+                // The words are 32 bits long
+                let high = {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.bitmask(word, 32, 16, pos) }
+                };
+                env.lookup_16bits(&high);
+                env.lookup_16bits(&(word.clone() - high * Env::constant(1 << 16)));
+                // The words of the last digest are kept in registers: the
+                // squeezes that do not finalize a hash return them again
+                let register = Env::constant((REGISTER_KECCAK_DIGEST_START + i) as u32);
+                let previous_word = {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.fetch_register(&register, pos) }
+                };
+                env.add_constraint(
+                    (Env::constant(1) - finalized.clone()) * word.clone()
+                        - (Env::constant(1) - finalized.clone()) * previous_word.clone(),
+                );
+                unsafe {
+                    env.access_register(&register, &previous_word, word);
+                };
+                unsafe {
+                    env.push_register(&register, word.clone());
+                };
+            }
+            // The words out of the digest are zero
+            let mut word = Env::constant(0);
+            for (i, digest_word) in words.into_iter().enumerate() {
+                let is_index = env.equal(&index, &Env::constant(i as u32));
+                word = word + is_index * digest_word;
+            }
+            env.write_register(&Env::constant(2), word);
+            env.write_register(&Env::constant(7), Env::constant(0));
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
         RTypeInstruction::MoveZero => {
             let rt = env.read_register(&rt);
             let is_zero = env.is_zero(&rt);
@@ -1441,6 +1752,27 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
+        RTypeInstruction::SyscallSetTrapHandler => {
+            let handler = env.read_register(&Env::constant(4));
+            let previous_handler = env.read_register(&Env::constant(REGISTER_TRAP_HANDLER as u32));
+            env.write_register(&Env::constant(REGISTER_TRAP_HANDLER as u32), handler);
+            env.write_register(&Env::constant(2), previous_handler);
+            env.write_register(&Env::constant(7), Env::constant(0));
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        RTypeInstruction::TrapFloatingPoint => {
+            // The instruction is only decoded as a trap when a handler is
+            // registered, see [SYSCALL_SET_FP_TRAP_HANDLER]
+            let handler = env.read_register(&Env::constant(REGISTER_TRAP_HANDLER as u32));
+            env.write_register(&Env::constant(REGISTER_TRAP_ADDRESS), instruction_pointer);
+            env.write_register(
+                &Env::constant(REGISTER_TRAP_RESUME_ADDRESS),
+                next_instruction_pointer,
+            );
+            env.set_instruction_pointer(handler.clone());
+            env.set_next_instruction_pointer(handler + Env::constant(4u32));
+        }
         RTypeInstruction::Sync => {
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
@@ -18,7 +18,9 @@
 
 pub mod column;
 pub mod constraints;
+pub mod host_functions;
 pub mod interpreter;
+pub mod output;
 pub mod registers;
 #[cfg(test)]
 pub mod tests;
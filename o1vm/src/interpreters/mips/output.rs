@@ -0,0 +1,98 @@
+//! Capture of the writes of the guest to the standard output and error.
+//!
+//! The bytes written by the guest to `stdout` and `stderr` are captured in a
+//! [GuestOutput], where the embedder can read them, and can be echoed to the
+//! standard output and error of the host for debugging.
+//!
+//! When enabled, the bytes written to `stdout` are also absorbed into a
+//! commitment, a hash chain where each write of the bytes `b` updates the
+//! commitment `c` to `keccak256(c || b)`, starting from zero.
+//!
+//! The bytes written and the commitment are not constrained by the circuit,
+//! so they do not prove yet that the program printed a given output.
+
+use crate::interpreters::mips::interpreter::{FD_STDERR, FD_STDOUT};
+use sha3::{Digest, Keccak256};
+use std::io::{self, Write};
+
+#[derive(Clone, Debug, Default)]
+pub struct GuestOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// Whether the writes are echoed to the standard output and error of the
+    /// host
+    pub echo: bool,
+    /// The commitment to the bytes written to `stdout` so far. `None`
+    /// disables the commitment.
+    commitment: Option<[u8; 32]>,
+}
+
+impl GuestOutput {
+    /// Starts absorbing the writes to `stdout` into the commitment.
+    pub fn enable_commitment(&mut self) {
+        self.commitment.get_or_insert([0u8; 32]);
+    }
+
+    /// The commitment to the bytes written to `stdout` so far, zero when the
+    /// commitment is disabled.
+    pub fn commitment(&self) -> [u8; 32] {
+        self.commitment.unwrap_or_default()
+    }
+
+    /// Captures the write of `bytes` to the file descriptor `fd`. The writes
+    /// to other file descriptors than `stdout` and `stderr` are ignored.
+    pub fn write(&mut self, fd: u32, bytes: &[u8]) {
+        match fd {
+            FD_STDOUT => {
+                self.stdout.extend_from_slice(bytes);
+                if let Some(commitment) = self.commitment.as_mut() {
+                    *commitment = absorb(commitment, bytes);
+                }
+                if self.echo {
+                    // The output of the host is only for debugging
+                    let _ = io::stdout().write_all(bytes);
+                }
+            }
+            FD_STDERR => {
+                self.stderr.extend_from_slice(bytes);
+                if self.echo {
+                    let _ = io::stderr().write_all(bytes);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Absorbs the bytes of a write into the commitment `commitment`.
+pub fn absorb(commitment: &[u8; 32], bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(commitment);
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guest_output_capture_and_commitment() {
+        let mut output = GuestOutput::default();
+        output.write(FD_STDOUT, b"not committed ");
+        output.enable_commitment();
+        output.write(FD_STDOUT, b"hello");
+        output.write(FD_STDERR, b"debug");
+        // Other file descriptors, e.g. the preimage oracle, are ignored
+        output.write(6, b"ignored");
+        output.write(FD_STDOUT, b" world");
+
+        assert_eq!(output.stdout, b"not committed hello world");
+        assert_eq!(output.stderr, b"debug");
+        assert_eq!(
+            output.commitment(),
+            absorb(&absorb(&[0u8; 32], b"hello"), b" world")
+        );
+        assert_eq!(GuestOutput::default().commitment(), [0u8; 32]);
+    }
+}
@@ -9,8 +9,12 @@ pub const REGISTER_HEAP_POINTER: usize = 36;
 pub const REGISTER_PREIMAGE_KEY_START: usize = 37;
 pub const REGISTER_PREIMAGE_KEY_END: usize = REGISTER_PREIMAGE_KEY_START + 8 /* 37 + 8 = 45 */;
 pub const REGISTER_PREIMAGE_OFFSET: usize = 45;
+pub const REGISTER_PROGRAM_BREAK: usize = 46;
+pub const REGISTER_TRAP_HANDLER: usize = 47;
+pub const REGISTER_KECCAK_DIGEST_START: usize = 48;
+pub const REGISTER_KECCAK_DIGEST_END: usize = REGISTER_KECCAK_DIGEST_START + 8 /* 48 + 8 = 56 */;
 
-pub const NUM_REGISTERS: usize = 46;
+pub const NUM_REGISTERS: usize = 56;
 
 /// This represents the internal state of the virtual machine.
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
@@ -23,6 +27,14 @@ pub struct Registers<T> {
     pub heap_pointer: T,
     pub preimage_key: [T; 8],
     pub preimage_offset: T,
+    pub program_break: T,
+    /// The address of the handler of the floating-point instructions, zero
+    /// when none is registered, see
+    /// [crate::interpreters::mips::interpreter::SYSCALL_SET_FP_TRAP_HANDLER]
+    pub trap_handler: T,
+    /// The 8 big-endian words of the last digest output by the Keccak256
+    /// precompile, returned again by the squeezes that do not finalize a hash
+    pub keccak_digest: [T; 8],
 }
 
 impl<T> Registers<T> {
@@ -37,7 +49,12 @@ impl<T> Registers<T> {
                 &self.heap_pointer,
             ])
             .chain(self.preimage_key.iter())
-            .chain([&self.preimage_offset])
+            .chain([
+                &self.preimage_offset,
+                &self.program_break,
+                &self.trap_handler,
+            ])
+            .chain(self.keccak_digest.iter())
     }
 }
 
@@ -61,6 +78,12 @@ impl<T: Clone> Index<usize> for Registers<T> {
             &self.preimage_key[index - REGISTER_PREIMAGE_KEY_START]
         } else if index == REGISTER_PREIMAGE_OFFSET {
             &self.preimage_offset
+        } else if index == REGISTER_PROGRAM_BREAK {
+            &self.program_break
+        } else if index == REGISTER_TRAP_HANDLER {
+            &self.trap_handler
+        } else if (REGISTER_KECCAK_DIGEST_START..REGISTER_KECCAK_DIGEST_END).contains(&index) {
+            &self.keccak_digest[index - REGISTER_KECCAK_DIGEST_START]
         } else {
             panic!("Index out of bounds");
         }
@@ -85,6 +108,12 @@ impl<T: Clone> IndexMut<usize> for Registers<T> {
             &mut self.preimage_key[index - REGISTER_PREIMAGE_KEY_START]
         } else if index == REGISTER_PREIMAGE_OFFSET {
             &mut self.preimage_offset
+        } else if index == REGISTER_PROGRAM_BREAK {
+            &mut self.program_break
+        } else if index == REGISTER_TRAP_HANDLER {
+            &mut self.trap_handler
+        } else if (REGISTER_KECCAK_DIGEST_START..REGISTER_KECCAK_DIGEST_END).contains(&index) {
+            &mut self.keccak_digest[index - REGISTER_KECCAK_DIGEST_START]
         } else {
             panic!("Index out of bounds");
         }
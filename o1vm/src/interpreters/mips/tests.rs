@@ -50,7 +50,10 @@ fn test_on_disk_preimage_can_read_file() {
         0x67, 0xbd, 0xa4, 0x08, 0x77, 0xa7, 0xe8, 0x5d, 0xce, 0xb6, 0xff, 0x1f, 0x37, 0x48, 0x0f,
         0xef, 0x3d,
     ];
-    let preimage = dummy_env.preimage_oracle.get_preimage(preimage_key_u8);
+    let preimage = dummy_env
+        .preimage_oracle
+        .get_preimage(preimage_key_u8)
+        .unwrap();
     let bytes = preimage.get();
     // Number of bytes inside the corresponding file (preimage)
     assert_eq!(bytes.len(), 358);
@@ -70,7 +73,28 @@ fn test_all_instructions_have_a_usize_representation_smaller_than_the_number_of_
 mod rtype {
 
     use super::*;
-    use crate::interpreters::mips::{interpreter::interpret_rtype, RTypeInstruction};
+    use crate::{
+        interpreters::mips::{interpreter::interpret_rtype, RTypeInstruction},
+        preimage_oracle::PreImageOracleError,
+    };
+
+    #[test]
+    fn test_unit_syscall_read_missing_preimage() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        dummy_env.registers.preimage_key = [0x0200_0000, 0, 0, 0, 0, 0, 0, 0];
+        dummy_env.registers[5] = 0x100;
+        dummy_env.registers[6] = 4;
+
+        interpret_rtype(&mut dummy_env, RTypeInstruction::SyscallReadPreimage);
+
+        // The execution halts with the error instead of panicking
+        assert!(dummy_env.halt);
+        assert!(matches!(
+            dummy_env.preimage_oracle_error,
+            Some(PreImageOracleError::MissingPreimage(key)) if key[0] == 2
+        ));
+    }
 
     #[test]
     fn test_unit_syscall_read_preimage() {
@@ -95,7 +119,11 @@ mod rtype {
         dummy_env.registers.preimage_key = std::array::from_fn(|i| chunks[i]);
 
         // The whole preimage
-        let preimage = dummy_env.preimage_oracle.get_preimage(preimage_key).get();
+        let preimage = dummy_env
+            .preimage_oracle
+            .get_preimage(preimage_key)
+            .unwrap()
+            .get();
 
         // Total number of bytes that need to be processed (includes length)
         let total_length = 8 + preimage.len() as u32;
@@ -152,6 +180,370 @@ mod rtype {
         }
     }
 
+    #[test]
+    fn test_resume_from_snapshot_during_preimage_read() {
+        use crate::interpreters::mips::witness::Env as WEnv;
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        let preimage_key =
+            hex::decode("022107307879258577230c5aa2f90567bda40877a7e85dceb6ff1f37480fef3d")
+                .unwrap();
+        dummy_env.registers.preimage_key = std::array::from_fn(|i| {
+            u32::from_be_bytes(preimage_key[4 * i..4 * (i + 1)].try_into().unwrap())
+        });
+        // Read the length and the first 8 bytes of the preimage
+        dummy_env.registers[5] = 0x100;
+        while dummy_env.registers.preimage_offset < 16 {
+            dummy_env.reset_scratch_state();
+            dummy_env.reset_scratch_state_inverse();
+            dummy_env.registers[6] = 4;
+            interpret_rtype(&mut dummy_env, RTypeInstruction::SyscallReadPreimage);
+            dummy_env.registers[5] = 0x100 + dummy_env.registers.preimage_offset;
+        }
+
+        // The binary format does not keep the preimage being read
+        let path = std::env::temp_dir().join("o1vm-test-resume-preimage-read.bin");
+        dummy_env.snapshot().to_file(&path).unwrap();
+        let mut resumed = WEnv::resume_from_snapshot(&path, OnDiskPreImageOracle).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.snapshot(), dummy_env.snapshot());
+        assert_eq!(resumed.preimage, dummy_env.preimage);
+        assert_eq!(resumed.preimage_key, dummy_env.preimage_key);
+        assert_eq!(resumed.preimage_bytes_read, 8);
+
+        // Both executions read the next bytes identically
+        for env in [&mut dummy_env, &mut resumed] {
+            env.reset_scratch_state();
+            env.reset_scratch_state_inverse();
+            env.registers[6] = 4;
+            interpret_rtype(env, RTypeInstruction::SyscallReadPreimage);
+        }
+        assert_eq!(resumed.snapshot(), dummy_env.snapshot());
+    }
+
+    #[test]
+    fn test_unit_syscall_host() {
+        use crate::interpreters::mips::host_functions::{
+            GuestMemory, HostCallArgs, HostCallOutput, HostFunctionError,
+        };
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        // A storage of 4-bytes slots, indexed by the key pointed by `$a0`
+        let storage_read = |args: &HostCallArgs, memory: &mut GuestMemory| {
+            let key = memory.read_u32(args.args[0]);
+            HostCallOutput {
+                value: key.wrapping_mul(3),
+                error: 0,
+            }
+        };
+        let host_functions = &mut dummy_env.syscall_env.host_functions;
+        host_functions.register(5000, storage_read).unwrap();
+        assert_eq!(
+            host_functions.register(5000, storage_read),
+            Err(HostFunctionError::AlreadyRegistered(5000))
+        );
+        assert_eq!(
+            host_functions.register(4090, storage_read),
+            Err(HostFunctionError::BuiltinSyscall(4090))
+        );
+
+        // Instruction: syscall, with the syscall number in $v0
+        write_instruction(
+            &mut dummy_env,
+            InstructionParts {
+                op_code: 0b000000,
+                rs: 0,
+                rt: 0,
+                rd: 0,
+                shamt: 0,
+                funct: 0b001100,
+            },
+        );
+        dummy_env.registers[2] = 5000;
+        dummy_env.registers[4] = 0x40;
+        dummy_env.memory[0].1[0x40..0x44].copy_from_slice(&[0, 0, 1, 2]);
+        let (instruction, _) = dummy_env.decode_instruction();
+        assert_eq!(
+            instruction,
+            Instruction::RType(RTypeInstruction::SyscallHost)
+        );
+
+        let args = [4, 5, 6, 7].map(|reg| dummy_env.registers[reg]);
+        let instruction_pointer = dummy_env.registers.current_instruction_pointer;
+        let instruction_counter = dummy_env.normalized_instruction_counter();
+        interpret_rtype(&mut dummy_env, RTypeInstruction::SyscallHost);
+        assert_eq!(dummy_env.registers[2], 0x0102 * 3);
+        assert_eq!(dummy_env.registers[7], 0);
+
+        let io = dummy_env.syscall_env.host_functions.io();
+        assert_eq!(io.len(), 1);
+        assert_eq!(
+            io[0].args,
+            HostCallArgs {
+                syscall_num: 5000,
+                args
+            }
+        );
+        assert_eq!(io[0].memory_reads, vec![(0x40, vec![0, 0, 1, 2])]);
+        assert_eq!(io[0].instruction_counter, instruction_counter);
+        assert_eq!(
+            io[0].output,
+            HostCallOutput {
+                value: 0x0102 * 3,
+                error: 0
+            }
+        );
+
+        // Unregistered syscalls keep their default behavior
+        dummy_env.registers.current_instruction_pointer = instruction_pointer;
+        dummy_env.registers[2] = 5001;
+        let (instruction, _) = dummy_env.decode_instruction();
+        assert_eq!(
+            instruction,
+            Instruction::RType(RTypeInstruction::SyscallOther)
+        );
+    }
+
+    #[test]
+    fn test_unit_report_output() {
+        use crate::interpreters::mips::{
+            interpreter::{InterpreterEnv, FD_PREIMAGE_WRITE, FD_STDERR, FD_STDOUT},
+            output::absorb,
+        };
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        dummy_env.syscall_env.output.enable_commitment();
+        dummy_env.memory[0].1[0x40..0x45].copy_from_slice(b"hello");
+        let (addr, len) = (0x40, 5);
+        for fd in [FD_STDOUT, FD_STDERR, FD_PREIMAGE_WRITE] {
+            dummy_env.report_output(&(fd as u64), &addr, &len);
+        }
+        // The pages read are not allocated
+        dummy_env.report_output(&(FD_STDOUT as u64), &0x7fff_0000, &2);
+
+        let output = &dummy_env.syscall_env.output;
+        assert_eq!(output.stdout, b"hello\0\0");
+        assert_eq!(output.stderr, b"hello");
+        assert_eq!(
+            output.commitment(),
+            absorb(&absorb(&[0u8; 32], b"hello"), &[0, 0])
+        );
+        assert_eq!(dummy_env.memory.len(), 5);
+    }
+
+    #[test]
+    fn test_unit_trap_floating_point() {
+        use crate::interpreters::mips::interpreter::{
+            InterpreterEnv, REGISTER_TRAP_ADDRESS, REGISTER_TRAP_RESUME_ADDRESS,
+            SYSCALL_SET_FP_TRAP_HANDLER,
+        };
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        let instruction_pointer = dummy_env.registers.current_instruction_pointer;
+        // Instruction: syscall, registering the handler
+        write_instruction(
+            &mut dummy_env,
+            InstructionParts {
+                op_code: 0b000000,
+                rs: 0,
+                rt: 0,
+                rd: 0,
+                shamt: 0,
+                funct: 0b001100,
+            },
+        );
+        dummy_env.registers.trap_handler = 0;
+        dummy_env.registers[2] = SYSCALL_SET_FP_TRAP_HANDLER;
+        dummy_env.registers[4] = 0x2000;
+        let (instruction, _) = dummy_env.decode_instruction();
+        assert_eq!(
+            instruction,
+            Instruction::RType(RTypeInstruction::SyscallSetTrapHandler)
+        );
+        interpret_rtype(&mut dummy_env, RTypeInstruction::SyscallSetTrapHandler);
+        assert_eq!(dummy_env.registers.trap_handler, 0x2000);
+        assert_eq!((dummy_env.registers[2], dummy_env.registers[7]), (0, 0));
+
+        // Instruction: 0b010001 10000 00010 00001 00000 000000
+        // add.s $f0, $f1, $f2
+        dummy_env.reset();
+        dummy_env.registers.current_instruction_pointer = instruction_pointer;
+        dummy_env.registers.next_instruction_pointer = instruction_pointer + 4;
+        write_instruction(
+            &mut dummy_env,
+            InstructionParts {
+                op_code: 0b010001,
+                rs: 0b10000,
+                rt: 2,
+                rd: 1,
+                shamt: 0,
+                funct: 0,
+            },
+        );
+        let (instruction, _) = dummy_env.decode_instruction();
+        assert_eq!(
+            instruction,
+            Instruction::RType(RTypeInstruction::TrapFloatingPoint)
+        );
+        interpret_rtype(&mut dummy_env, RTypeInstruction::TrapFloatingPoint);
+        assert_eq!(
+            dummy_env.registers[REGISTER_TRAP_ADDRESS as usize],
+            instruction_pointer
+        );
+        assert_eq!(
+            dummy_env.registers[REGISTER_TRAP_RESUME_ADDRESS as usize],
+            instruction_pointer + 4
+        );
+        assert_eq!(dummy_env.get_instruction_pointer(), 0x2000);
+        assert_eq!(dummy_env.get_next_instruction_pointer(), 0x2004);
+    }
+
+    #[test]
+    #[should_panic(expected = "no trap handler is registered")]
+    fn test_unit_floating_point_without_trap_handler() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        // Instruction: 0b110001 00000 00001 0000000000000000
+        // lwc1 $f1, 0($zero)
+        write_instruction(
+            &mut dummy_env,
+            InstructionParts {
+                op_code: 0b110001,
+                rs: 0,
+                rt: 1,
+                rd: 0,
+                shamt: 0,
+                funct: 0,
+            },
+        );
+        dummy_env.registers.trap_handler = 0;
+        dummy_env.decode_instruction();
+    }
+
+    #[test]
+    fn test_unit_syscall_brk() {
+        use crate::interpreters::mips::interpreter::SYSCALL_BRK;
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        // Instruction: syscall, with the syscall number in $v0
+        write_instruction(
+            &mut dummy_env,
+            InstructionParts {
+                op_code: 0b000000,
+                rs: 0,
+                rt: 0,
+                rd: 0,
+                shamt: 0,
+                funct: 0b001100,
+            },
+        );
+        dummy_env.registers.program_break = 0x4000_0000;
+        let instruction_pointer = dummy_env.registers.current_instruction_pointer;
+        let mut brk = |address: u32, heap_pointer: u32| {
+            dummy_env.reset_scratch_state();
+            dummy_env.reset_scratch_state_inverse();
+            dummy_env.registers.heap_pointer = heap_pointer;
+            dummy_env.registers.current_instruction_pointer = instruction_pointer;
+            dummy_env.registers.next_instruction_pointer = instruction_pointer + 4;
+            dummy_env.registers[2] = SYSCALL_BRK;
+            dummy_env.registers[4] = address;
+            let (instruction, _) = dummy_env.decode_instruction();
+            assert_eq!(
+                instruction,
+                Instruction::RType(RTypeInstruction::SyscallBrk)
+            );
+            interpret_rtype(&mut dummy_env, RTypeInstruction::SyscallBrk);
+            assert_eq!(dummy_env.registers[7], 0);
+            (dummy_env.registers[2], dummy_env.registers.program_break)
+        };
+
+        let heap = 0x2000_0000;
+        assert_eq!(brk(0, heap), (0x4000_0000, 0x4000_0000));
+        assert_eq!(brk(0x4000_1234, heap), (0x4000_1234, 0x4000_1234));
+        // The program break does not shrink
+        assert_eq!(brk(0x4000_1000, heap), (0x4000_1234, 0x4000_1234));
+        assert_eq!(brk(0, heap), (0x4000_1234, 0x4000_1234));
+        // The heap allocated by mmap above the program break is not
+        // overwritten
+        assert_eq!(brk(0x4000_3000, 0x4000_2000), (0x4000_1234, 0x4000_1234));
+        assert_eq!(brk(0x4000_3000, 0x4000_1234), (0x4000_3000, 0x4000_3000));
+    }
+
+    #[test]
+    fn test_unit_keccak_syscalls() {
+        use crate::interpreters::mips::{
+            interpreter::{InterpreterEnv, SYSCALL_KECCAK_ABSORB, SYSCALL_KECCAK_SQUEEZE},
+            witness::Env,
+        };
+        use sha3::{Digest, Keccak256};
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        // Instruction: syscall, with the syscall number in $v0
+        write_instruction(
+            &mut dummy_env,
+            InstructionParts {
+                op_code: 0b000000,
+                rs: 0,
+                rt: 0,
+                rd: 0,
+                shamt: 0,
+                funct: 0b001100,
+            },
+        );
+        let instruction_pointer = dummy_env.registers.current_instruction_pointer;
+        let syscall = |dummy_env: &mut Env<_, OnDiskPreImageOracle>, num, args: [u32; 2]| {
+            dummy_env.reset();
+            dummy_env.reset_scratch_state_inverse();
+            dummy_env.registers.current_instruction_pointer = instruction_pointer;
+            dummy_env.registers.next_instruction_pointer = instruction_pointer + 4;
+            dummy_env.registers[2] = num;
+            dummy_env.registers[4] = args[0];
+            dummy_env.registers[5] = args[1];
+            let (instruction, _) = dummy_env.decode_instruction();
+            let Instruction::RType(rtype) = instruction else {
+                panic!("syscall is not decoded as an R-type instruction")
+            };
+            interpret_rtype(dummy_env, rtype);
+            dummy_env.registers[2]
+        };
+
+        // Absorb "hello", the bytes being read until the end of the words
+        dummy_env.memory[0].1[0x42..0x47].copy_from_slice(b"hello");
+        let mut addr = 0x42;
+        while addr < 0x47 {
+            addr += syscall(&mut dummy_env, SYSCALL_KECCAK_ABSORB, [addr, 0x47 - addr]);
+        }
+        assert_eq!(dummy_env.syscall_env.keccak.input, b"hello");
+        assert!(dummy_env.keccak_env.is_none());
+
+        let hash_counter = dummy_env.hash_counter;
+        let digest: Vec<u32> = (0..8)
+            .map(|i| syscall(&mut dummy_env, SYSCALL_KECCAK_SQUEEZE, [i, 0]))
+            .collect();
+        let expected: Vec<u32> = Keccak256::digest(b"hello")
+            .chunks(4)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+            .collect();
+        assert_eq!(digest, expected);
+        // The digest is kept in registers for the next squeezes
+        assert_eq!(dummy_env.registers.keccak_digest.to_vec(), expected);
+        // The hash is proven once by the Keccak circuit
+        assert!(dummy_env.keccak_env.is_some());
+        assert_eq!(dummy_env.hash_counter, hash_counter + 1);
+        assert_eq!(syscall(&mut dummy_env, SYSCALL_KECCAK_SQUEEZE, [8, 0]), 0);
+
+        // The next absorption starts a new hash
+        syscall(&mut dummy_env, SYSCALL_KECCAK_ABSORB, [0x44, 1]);
+        assert_eq!(dummy_env.syscall_env.keccak.input, b"l");
+    }
+
     #[test]
     fn test_unit_sub_instruction() {
         let mut rng = o1_utils::tests::make_test_rng(None);
@@ -349,3 +741,30 @@ fn test_regression_selectors_for_instructions() {
         .iter()
         .for_each(|c| assert!(c.degree(1, 0) == 2 || c.degree(1, 0) == 1));
 }
+
+#[test]
+fn test_constraints_of_instructions_fit_in_scratch() {
+    use crate::interpreters::mips::{
+        column::{SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+        interpreter::interpret_instruction,
+    };
+
+    let instructions = RTypeInstruction::iter()
+        .map(Instruction::RType)
+        .chain(JTypeInstruction::iter().map(Instruction::JType))
+        .chain(ITypeInstruction::iter().map(Instruction::IType));
+    for instruction in instructions {
+        let mut env = constraints::Env::<Fp>::default();
+        interpret_instruction(&mut env, instruction);
+        assert!(
+            env.scratch_state_idx <= SCRATCH_SIZE,
+            "{instruction:?} uses {} scratch columns",
+            env.scratch_state_idx
+        );
+        assert!(
+            env.scratch_state_idx_inverse <= SCRATCH_SIZE_INVERSE,
+            "{instruction:?} uses {} scratch columns for the inverses",
+            env.scratch_state_idx_inverse
+        );
+    }
+}
@@ -1,14 +1,17 @@
 use crate::{
-    cannon::{Hint, Preimage, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE, PAGE_SIZE},
+    cannon::{
+        Hint, PreImageOracleBackend, Preimage, StepFrequency, VmConfiguration, PAGE_ADDRESS_MASK,
+        PAGE_ADDRESS_SIZE, PAGE_SIZE,
+    },
     interpreters::mips::{
         interpreter::{debugging::InstructionParts, InterpreterEnv},
         registers::Registers,
         witness::{Env as WEnv, SyscallEnv},
     },
-    preimage_oracle::PreImageOracleT,
+    preimage_oracle::{DirectoryPreImageOracle, PreImageOracleError, PreImageOracleT},
 };
 use rand::{CryptoRng, Rng, RngCore};
-use std::{fs, path::PathBuf};
+use std::path::PathBuf;
 
 // FIXME: we should parametrize the tests with different fields.
 use ark_bn254::Fr as Fp;
@@ -20,19 +23,14 @@ const PAGE_INDEX_EXECUTABLE_MEMORY: u32 = 1;
 pub(crate) struct OnDiskPreImageOracle;
 
 impl PreImageOracleT for OnDiskPreImageOracle {
-    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
-        let key_s = hex::encode(key);
-        let full_path = format!("resources/tests/0x{key_s}.txt");
-        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        d.push(full_path);
-        let contents = fs::read_to_string(d).expect("Should have been able to read the file");
-        // Decode String (ASCII) as Vec<u8> (hexadecimal bytes)
-        let bytes = hex::decode(contents)
-            .expect("Should have been able to decode the file as hexadecimal bytes");
-        Preimage::create(bytes)
+    fn get_preimage(&mut self, key: [u8; 32]) -> Result<Preimage, PreImageOracleError> {
+        let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources/tests");
+        DirectoryPreImageOracle::create(directory).get_preimage(key)
     }
 
-    fn hint(&mut self, _hint: Hint) {}
+    fn hint(&mut self, _hint: Hint) -> Result<(), PreImageOracleError> {
+        Ok(())
+    }
 }
 
 pub(crate) fn dummy_env<RNG>(rng: &mut RNG) -> WEnv<Fp, OnDiskPreImageOracle>
@@ -102,6 +100,11 @@ where
         preimage_key: None,
         keccak_env: None,
         hash_counter: 0,
+        memory_accesses: None,
+        profiler: None,
+        statistics: None,
+        recording: None,
+        preimage_oracle_error: None,
     };
     // Initialize general purpose registers with random values
     for reg in env.registers.general_purpose.iter_mut() {
@@ -112,6 +115,32 @@ where
     env
 }
 
+/// A configuration without any periodic action nor output
+pub(crate) fn dummy_configuration() -> VmConfiguration {
+    VmConfiguration {
+        input_state_file: "state.json".to_string(),
+        output_state_file: "out.json".to_string(),
+        metadata_file: "meta.json".to_string(),
+        proof_at: StepFrequency::Never,
+        stop_at: StepFrequency::Never,
+        snapshot_state_at: StepFrequency::Never,
+        info_at: StepFrequency::Never,
+        proof_fmt: "proof-%d.json".to_string(),
+        snapshot_fmt: "snapshot-%d.json".to_string(),
+        pprof_cpu: false,
+        profile: None,
+        statistics: None,
+        commit_output: false,
+        gdb: None,
+        record: None,
+        prover_threads: 1,
+        column_storage: None,
+        fp_trap_handler: None,
+        host: None,
+        preimage_oracle: PreImageOracleBackend::default(),
+    }
+}
+
 // Write the instruction to the location of the instruction pointer.
 pub(crate) fn write_instruction(
     env: &mut WEnv<Fp, OnDiskPreImageOracle>,
@@ -13,26 +13,30 @@ use crate::{
                 MIPS_LENGTH_BYTES_OFF, MIPS_NUM_BYTES_READ_OFF, MIPS_PREIMAGE_BYTES_OFF,
                 MIPS_PREIMAGE_CHUNK_OFF, MIPS_PREIMAGE_KEY,
             },
+            host_functions::{GuestMemory, HostCallArgs, HostFunctions},
             interpreter::{
                 self, ITypeInstruction, Instruction, InterpreterEnv, JTypeInstruction,
                 RTypeInstruction,
             },
+            output::GuestOutput,
             registers::Registers,
         },
     },
-    lookups::Lookup,
-    preimage_oracle::PreImageOracleT,
+    lookups::{Lookup, LookupTableIDs},
+    memory_argument::MemoryAccess,
+    preimage_oracle::{PreImageOracleError, PreImageOracleT},
+    profiler::Profiler,
+    replay::ExecutionTrace,
+    statistics::ExecutionStatistics,
     utils::memory_size,
+    RAMLookupMode,
 };
 use ark_ff::Field;
 use core::panic;
 use kimchi::o1_utils::Two;
-use log::{debug, info};
-use std::{
-    array,
-    fs::File,
-    io::{BufWriter, Write},
-};
+use log::{debug, error, info};
+use sha3::{Digest, Keccak256};
+use std::{array, path::Path};
 
 // TODO: do we want to be more restrictive and refer to the number of accesses
 //       to the SAME register/memory addrss?
@@ -54,12 +58,31 @@ pub const NUM_LOOKUP_TERMS: usize =
 #[derive(Clone, Default)]
 pub struct SyscallEnv {
     pub last_hint: Option<Vec<u8>>,
+    /// The syscalls defined by the embedder
+    pub host_functions: HostFunctions,
+    /// The writes to the standard output and error
+    pub output: GuestOutput,
+    /// The state of the Keccak256 precompile
+    pub keccak: KeccakPrecompile,
+}
+
+/// The state of the hash computed by the Keccak256 precompile, see
+/// [interpreter::SYSCALL_KECCAK_ABSORB].
+#[derive(Clone, Debug, Default)]
+pub struct KeccakPrecompile {
+    /// The bytes absorbed in the current hash
+    pub input: Vec<u8>,
+    /// The digest of the input, once the hash has been finalized
+    pub digest: Option<[u8; 32]>,
 }
 
 impl SyscallEnv {
     pub fn create(state: &State) -> Self {
         SyscallEnv {
             last_hint: state.last_hint.clone(),
+            host_functions: HostFunctions::default(),
+            output: GuestOutput::default(),
+            keccak: KeccakPrecompile::default(),
         }
     }
 }
@@ -90,6 +113,20 @@ pub struct Env<Fp, PreImageOracle: PreImageOracleT> {
     pub preimage_key: Option<[u8; 32]>,
     pub keccak_env: Option<KeccakEnv<Fp>>,
     pub hash_counter: u64,
+    /// The accesses to the memory, in the order of the execution, for the
+    /// memory argument. `None` disables the recording.
+    pub memory_accesses: Option<Vec<MemoryAccess>>,
+    /// Profiler of the execution. `None` disables the profiling.
+    pub profiler: Option<Profiler>,
+    /// Statistics of the executed instructions. `None` disables the
+    /// statistics.
+    pub statistics: Option<ExecutionStatistics>,
+    /// The values given by the host to the execution, to replay it, see
+    /// [crate::replay]. `None` disables the recording.
+    pub recording: Option<ExecutionTrace>,
+    /// The error of the preimage oracle that halted the execution, if any.
+    /// The state reached is not a valid one and must not be proven.
+    pub preimage_oracle_error: Option<PreImageOracleError>,
 }
 
 fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
@@ -142,9 +179,18 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreI
         }
     }
 
-    fn add_lookup(&mut self, _lookup: Lookup<Self::Variable>) {
-        // No-op, constraints only
+    fn add_lookup(&mut self, lookup: Lookup<Self::Variable>) {
         // TODO: keep track of multiplicities of fixed tables here as in Keccak?
+        if let (Some(profiler), LookupTableIDs::MemoryLookup, RAMLookupMode::Write) =
+            (&mut self.profiler, lookup.table_id, lookup.mode)
+        {
+            profiler.record_memory_access();
+        }
+        if let (Some(accesses), LookupTableIDs::MemoryAccessLookup) =
+            (&mut self.memory_accesses, lookup.table_id)
+        {
+            accesses.push(MemoryAccess::from_table_entry(&lookup.value));
+        }
     }
 
     fn instruction_counter(&self) -> Self::Variable {
@@ -606,6 +652,17 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreI
         );
     }
 
+    fn report_output(&mut self, fd: &Self::Variable, addr: &Self::Variable, len: &Self::Variable) {
+        let fd: u32 = (*fd).try_into().unwrap();
+        if fd != interpreter::FD_STDOUT && fd != interpreter::FD_STDERR {
+            return;
+        }
+        // Fetch the bytes without allocating witness columns nor pages
+        let bytes = GuestMemory::new(&self.memory)
+            .read((*addr).try_into().unwrap(), (*len).try_into().unwrap());
+        self.syscall_env.output.write(fd, &bytes);
+    }
+
     fn request_preimage_write(
         &mut self,
         addr: &Self::Variable,
@@ -621,7 +678,17 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreI
                     preimage_key[4 * i + j] = bytes[j]
                 }
             }
-            let preimage = self.preimage_oracle.get_preimage(preimage_key).get();
+            let preimage = match self.preimage_oracle.get_preimage(preimage_key) {
+                Ok(preimage) => preimage.get(),
+                Err(e) => {
+                    self.halt_on_preimage_oracle_error(e);
+                    self.write_column(pos, 0);
+                    return 0;
+                }
+            };
+            if let Some(recording) = self.recording.as_mut() {
+                recording.record_preimage(preimage_key, &preimage);
+            }
             self.preimage = Some(preimage.clone());
             self.preimage_key = Some(preimage_key);
         }
@@ -803,7 +870,9 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreI
             if idx + hint_len <= len {
                 let hint = last_hint[idx..idx + hint_len].to_vec();
                 idx += hint_len;
-                self.preimage_oracle.hint(Hint::create(hint));
+                if let Err(e) = self.preimage_oracle.hint(Hint::create(hint)) {
+                    self.halt_on_preimage_oracle_error(e);
+                }
             }
         }
 
@@ -812,6 +881,108 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreI
         self.syscall_env.last_hint = Some(remaining);
     }
 
+    fn request_host_call(
+        &mut self,
+        syscall_num: &Self::Variable,
+        args: &[Self::Variable; 4],
+        pos: [Self::Position; 2],
+    ) -> [Self::Variable; 2] {
+        let args = HostCallArgs {
+            syscall_num: (*syscall_num).try_into().unwrap(),
+            args: args.map(|arg| arg.try_into().unwrap()),
+        };
+        let instruction_counter = self.normalized_instruction_counter();
+        let output = self
+            .syscall_env
+            .host_functions
+            .call(instruction_counter, args, &self.memory);
+        if let Some(recording) = self.recording.as_mut() {
+            recording.record_host_call(args, output);
+        }
+        let value = output.value as u64;
+        let error = output.error as u64;
+        self.write_column(pos[0], value);
+        self.write_column(pos[1], error);
+        [value, error]
+    }
+
+    fn request_keccak_absorb(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        bytes: &[Self::Variable; 4],
+        pos: Self::Position,
+    ) -> Self::Variable {
+        let keccak = &mut self.syscall_env.keccak;
+        // The first absorption after a digest starts a new hash
+        if keccak.digest.take().is_some() {
+            keccak.input.clear();
+        }
+        let byte_counter = keccak.input.len() as u64;
+        let absorbed = std::cmp::min(*len, 4 - (*addr & 3));
+        keccak
+            .input
+            .extend(bytes[..absorbed as usize].iter().map(|byte| *byte as u8));
+
+        self.write_column(
+            Column::ScratchState(MIPS_HASH_COUNTER_OFF),
+            self.hash_counter,
+        );
+        self.write_column(Column::ScratchState(MIPS_BYTE_COUNTER_OFF), byte_counter);
+        for i in 0..absorbed as usize {
+            self.write_column(Column::ScratchState(MIPS_HAS_N_BYTES_OFF + i), 1);
+        }
+        self.write_column(pos, absorbed);
+        absorbed
+    }
+
+    fn request_keccak_digest(
+        &mut self,
+        pos: [Self::Position; 8],
+    ) -> ([Self::Variable; 8], Self::Variable) {
+        self.write_column(
+            Column::ScratchState(MIPS_HASH_COUNTER_OFF),
+            self.hash_counter,
+        );
+        let finalized = self.syscall_env.keccak.digest.is_none();
+        let digest = match self.syscall_env.keccak.digest {
+            Some(digest) => digest,
+            None => {
+                let digest: [u8; 32] = Keccak256::digest(&self.syscall_env.keccak.input).into();
+                self.write_column(Column::ScratchState(MIPS_END_OF_PREIMAGE_OFF), 1);
+                // As for the preimage keys, the digest is read from the Keccak
+                // circuit without its MSB
+                let bytes31 = (1..32).fold(Fp::zero(), |acc, i| {
+                    acc * Fp::two_pow(8) + Fp::from(digest[i])
+                });
+                self.write_field_column(Self::Position::ScratchState(MIPS_PREIMAGE_KEY), bytes31);
+
+                debug!("Keccak precompile finalized, triggering Keccak process");
+                self.keccak_env = Some(KeccakEnv::<Fp>::new(
+                    self.hash_counter,
+                    &self.syscall_env.keccak.input,
+                ));
+                self.hash_counter += 1;
+                self.syscall_env.keccak.digest = Some(digest);
+                digest
+            }
+        };
+        // The two most significant bytes, to range check the first word
+        for (i, byte) in digest[..2].iter().enumerate() {
+            self.write_column(
+                Column::ScratchState(MIPS_LENGTH_BYTES_OFF + i),
+                *byte as u64,
+            );
+        }
+        let words: [u64; 8] = array::from_fn(|i| {
+            u32::from_be_bytes(digest[4 * i..4 * i + 4].try_into().unwrap()) as u64
+        });
+        for (pos, word) in pos.into_iter().zip(words) {
+            self.write_column(pos, word);
+        }
+        (words, finalized as u64)
+    }
+
     fn reset(&mut self) {
         self.scratch_state_idx = 0;
         self.scratch_state = fresh_scratch_state();
@@ -820,7 +991,11 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreI
 }
 
 impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
-    pub fn create(page_size: usize, state: State, preimage_oracle: PreImageOracle) -> Self {
+    /// Creates the environment starting from `state`, which can be taken at
+    /// any step of an execution. When the state is taken in the middle of a
+    /// preimage read and does not contain the preimage, e.g. a state in the
+    /// binary format, the preimage is requested again to the oracle.
+    pub fn create(page_size: usize, state: State, mut preimage_oracle: PreImageOracle) -> Self {
         let initial_instruction_pointer = state.pc;
         let next_instruction_pointer = state.next_pc;
 
@@ -864,11 +1039,42 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
                 heap_pointer: state.heap,
                 preimage_key,
                 preimage_offset: state.preimage_offset,
+                program_break: state.brk,
+                trap_handler: state.trap_handler,
+                // Like the state of the Keccak256 precompile, see
+                // [SyscallEnv::create], the last digest is not part of the
+                // state and starts empty
+                keccak_digest: [0; 8],
             }
         };
 
+        let mut preimage = state.preimage;
+        let mut preimage_key = None;
+        let mut preimage_bytes_read = 0;
+        let mut preimage_oracle_error = None;
+        if state.preimage_offset > 0 {
+            let data = match preimage.take() {
+                Some(data) => Some(data),
+                None => match preimage_oracle.get_preimage(state.preimage_key) {
+                    Ok(data) => Some(data.get()),
+                    Err(e) => {
+                        preimage_oracle_error = Some(e);
+                        None
+                    }
+                },
+            };
+            if let Some(data) = data {
+                // The first 8 bytes read are the length of the preimage
+                if (state.preimage_offset as usize) < 8 + data.len() {
+                    preimage_key = Some(state.preimage_key);
+                    preimage_bytes_read = (state.preimage_offset as u64).saturating_sub(8);
+                }
+                preimage = Some(data);
+            }
+        }
+
         Env {
-            instruction_counter: state.step,
+            instruction_counter: state.step * MAX_ACC,
             memory: initial_memory.clone(),
             last_memory_accesses: [0usize; 3],
             memory_write_index: memory_offsets
@@ -882,18 +1088,31 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
             scratch_state_idx_inverse: 0,
             scratch_state: fresh_scratch_state(),
             scratch_state_inverse: fresh_scratch_state(),
-            halt: state.exited,
+            halt: state.exited || preimage_oracle_error.is_some(),
             syscall_env,
             selector,
             preimage_oracle,
-            preimage: state.preimage,
-            preimage_bytes_read: 0,
-            preimage_key: None,
+            preimage,
+            preimage_bytes_read,
+            preimage_key,
             keccak_env: None,
             hash_counter: 0,
+            memory_accesses: None,
+            profiler: None,
+            statistics: None,
+            recording: None,
+            preimage_oracle_error,
         }
     }
 
+    /// Halts the execution when the preimage oracle fails, keeping the error
+    /// for the caller, see [Env::preimage_oracle_error].
+    fn halt_on_preimage_oracle_error(&mut self, error: PreImageOracleError) {
+        error!("The preimage oracle failed: {error}");
+        self.halt = true;
+        self.preimage_oracle_error = Some(error);
+    }
+
     pub fn reset_scratch_state(&mut self) {
         self.scratch_state_idx = 0;
         self.scratch_state = fresh_scratch_state();
@@ -986,6 +1205,18 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
                 | ((self.get_memory_direct(self.registers.current_instruction_pointer + 2) as u32)
                     << 8)
                 | (self.get_memory_direct(self.registers.current_instruction_pointer + 3) as u32);
+        if interpreter::is_floating_point(instruction) {
+            if self.registers.trap_handler == 0 {
+                panic!(
+                    "Unhandled floating-point instruction {:#X}, no trap handler is registered",
+                    instruction
+                )
+            }
+            return (
+                Instruction::RType(RTypeInstruction::TrapFloatingPoint),
+                instruction,
+            );
+        }
         let opcode = {
             match instruction >> 26 {
                 0x00 => match instruction & 0x3F {
@@ -1001,10 +1232,7 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
                     0x0b => Instruction::RType(RTypeInstruction::MoveNonZero),
                     0x0c => match self.registers.general_purpose[2] {
                         4090 => Instruction::RType(RTypeInstruction::SyscallMmap),
-                        4045 => {
-                            // sysBrk
-                            Instruction::RType(RTypeInstruction::SyscallOther)
-                        }
+                        4045 => Instruction::RType(RTypeInstruction::SyscallBrk),
                         4120 => {
                             // sysClone
                             Instruction::RType(RTypeInstruction::SyscallOther)
@@ -1029,6 +1257,18 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
                             _ => Instruction::RType(RTypeInstruction::SyscallWriteOther),
                         },
                         4055 => Instruction::RType(RTypeInstruction::SyscallFcntl),
+                        interpreter::SYSCALL_KECCAK_ABSORB => {
+                            Instruction::RType(RTypeInstruction::SyscallKeccakAbsorb)
+                        }
+                        interpreter::SYSCALL_KECCAK_SQUEEZE => {
+                            Instruction::RType(RTypeInstruction::SyscallKeccakSqueeze)
+                        }
+                        interpreter::SYSCALL_SET_FP_TRAP_HANDLER => {
+                            Instruction::RType(RTypeInstruction::SyscallSetTrapHandler)
+                        }
+                        syscall_num if self.syscall_env.host_functions.contains(syscall_num) => {
+                            Instruction::RType(RTypeInstruction::SyscallHost)
+                        }
                         _ => {
                             // NB: This has well-defined behavior. Don't panic!
                             Instruction::RType(RTypeInstruction::SyscallOther)
@@ -1164,7 +1404,20 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
             return opcode;
         }
 
+        let pc = self.registers.current_instruction_pointer;
+        let syscall_number = self.registers.general_purpose[2];
         interpreter::interpret_instruction(self, opcode);
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.record_step(pc, opcode);
+        }
+        if let Some(statistics) = self.statistics.as_mut() {
+            statistics.record_step(
+                opcode,
+                pc,
+                syscall_number,
+                self.registers.next_instruction_pointer,
+            );
+        }
 
         self.instruction_counter = self.next_instruction_counter();
 
@@ -1218,52 +1471,83 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         None
     }
 
+    /// Starts recording the values given by the host, see [crate::replay].
+    /// The preimage being read, if any, has been requested before, when
+    /// creating the environment, and is recorded first.
+    pub fn start_recording(&mut self) {
+        let mut recording = ExecutionTrace::default();
+        if let Some(preimage) = &self.preimage {
+            let mut preimage_key = [0u8; 32];
+            for (i, word) in self.registers.preimage_key.iter().enumerate() {
+                preimage_key[4 * i..4 * i + 4].copy_from_slice(&word.to_be_bytes());
+            }
+            recording.record_preimage(preimage_key, preimage);
+        }
+        self.recording = Some(recording);
+    }
+
+    /// Returns the state of the VM, in the format of Cannon.
+    pub fn snapshot(&self) -> State {
+        let mut preimage_key = [0u8; 32];
+        for i in 0..8 {
+            let bytes = u32::to_be_bytes(self.registers.preimage_key[i]);
+            for j in 0..4 {
+                preimage_key[4 * i + j] = bytes[j]
+            }
+        }
+        let memory = self
+            .memory
+            .clone()
+            .into_iter()
+            .map(|(idx, data)| Page { index: idx, data })
+            .collect();
+        State {
+            pc: self.registers.current_instruction_pointer,
+            next_pc: self.registers.next_instruction_pointer,
+            step: self.normalized_instruction_counter(),
+            registers: self.registers.general_purpose,
+            lo: self.registers.lo,
+            hi: self.registers.hi,
+            heap: self.registers.heap_pointer,
+            brk: self.registers.program_break,
+            trap_handler: self.registers.trap_handler,
+            // FIXME: it should be the exit code. We do not keep it in the
+            // witness atm
+            exit: if self.halt { 1 } else { 0 },
+            last_hint: self.syscall_env.last_hint.clone(),
+            exited: self.halt,
+            preimage_offset: self.registers.preimage_offset,
+            preimage_key,
+            memory,
+            preimage: self.preimage.clone(),
+        }
+    }
+
+    /// Resumes an execution from a state file written by
+    /// [State::to_file], e.g. a snapshot of a previous run or a state of
+    /// Cannon.
+    pub fn resume_from_snapshot<P: AsRef<Path>>(
+        path: P,
+        preimage_oracle: PreImageOracle,
+    ) -> std::io::Result<Self> {
+        let state = State::from_file(path)?;
+        Ok(Self::create(PAGE_SIZE as usize, state, preimage_oracle))
+    }
+
     fn snapshot_state_at(&mut self, at: &StepFrequency) {
         if self.should_trigger_at(at) {
             let filename = format!(
                 "snapshot-state-{}.json",
                 self.normalized_instruction_counter()
             );
-            let file = File::create(filename.clone()).expect("Impossible to open file");
-            let mut writer = BufWriter::new(file);
-            let mut preimage_key = [0u8; 32];
-            for i in 0..8 {
-                let bytes = u32::to_be_bytes(self.registers.preimage_key[i]);
-                for j in 0..4 {
-                    preimage_key[4 * i + j] = bytes[j]
-                }
-            }
-            let memory = self
-                .memory
-                .clone()
-                .into_iter()
-                .map(|(idx, data)| Page { index: idx, data })
-                .collect();
-            let s: State = State {
-                pc: self.registers.current_instruction_pointer,
-                next_pc: self.registers.next_instruction_pointer,
-                step: self.normalized_instruction_counter(),
-                registers: self.registers.general_purpose,
-                lo: self.registers.lo,
-                hi: self.registers.hi,
-                heap: self.registers.heap_pointer,
-                // FIXME: it should be the exit code. We do not keep it in the
-                // witness atm
-                exit: if self.halt { 1 } else { 0 },
-                last_hint: self.syscall_env.last_hint.clone(),
-                exited: self.halt,
-                preimage_offset: self.registers.preimage_offset,
-                preimage_key,
-                memory,
-                preimage: self.preimage.clone(),
-            };
-            let _ = serde_json::to_writer(&mut writer, &s);
+            self.snapshot()
+                .to_file(&filename)
+                .expect("Impossible to write the snapshot");
             info!(
                 "Snapshot state in {}, step {}",
                 filename,
                 self.normalized_instruction_counter()
             );
-            writer.flush().expect("Flush writer failing")
         }
     }
 
@@ -8,3 +8,8 @@ pub mod mips;
 /// on
 /// [riscv.org](https://riscv.org/wp-content/uploads/2019/12/riscv-spec-20191213.pdf).
 pub mod riscv32im;
+
+/// An interpreter for the RISC-V 64IM instruction set, following the specification
+/// on
+/// [riscv.org](https://riscv.org/wp-content/uploads/2019/12/riscv-spec-20191213.pdf).
+pub mod riscv64im;
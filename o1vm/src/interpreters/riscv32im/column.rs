@@ -1,8 +1,12 @@
 use super::{
     interpreter::{
-        IInstruction,
-        Instruction::{self, IType, MType, RType, SBType, SType, SyscallType, UJType, UType},
-        RInstruction, SBInstruction, SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+        AInstruction, CInstruction, IInstruction, IWInstruction,
+        Instruction::{
+            self, AType, CType, IType, IWType, MType, MWType, RType, RWType, SBType, SType,
+            SyscallType, UJType, UType,
+        },
+        MInstruction, RInstruction, RWInstruction, SBInstruction, SInstruction, SyscallInstruction,
+        UInstruction, UJInstruction,
     },
     INSTRUCTION_SET_SIZE, SCRATCH_SIZE,
 };
@@ -96,6 +100,81 @@ impl From<Instruction> for usize {
                     + SyscallInstruction::COUNT
                     + mtype as usize
             }
+            CType(ctype) => {
+                SCRATCH_SIZE
+                    + 1
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + ctype as usize
+            }
+            AType(atype) => {
+                SCRATCH_SIZE
+                    + 1
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + CInstruction::COUNT
+                    + atype as usize
+            }
+            RWType(rwtype) => {
+                SCRATCH_SIZE
+                    + 1
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + CInstruction::COUNT
+                    + AInstruction::COUNT
+                    + rwtype as usize
+            }
+            IWType(iwtype) => {
+                SCRATCH_SIZE
+                    + 1
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + CInstruction::COUNT
+                    + AInstruction::COUNT
+                    + RWInstruction::COUNT
+                    + iwtype as usize
+            }
+            MWType(mwtype) => {
+                SCRATCH_SIZE
+                    + 1
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + CInstruction::COUNT
+                    + AInstruction::COUNT
+                    + RWInstruction::COUNT
+                    + IWInstruction::COUNT
+                    + mwtype as usize
+            }
         }
     }
 }
@@ -13,14 +13,16 @@ use kimchi::circuits::{
     gate::CurrOrNext,
 };
 
-pub struct Env<F: Field> {
+/// The environment building the constraints of the interpreter, for registers
+/// of `XLEN` bits.
+pub struct Env<F: Field, const XLEN: u32 = 32> {
     pub scratch_state_idx: usize,
     pub lookups: Vec<Lookup<E<F>>>,
     pub constraints: Vec<E<F>>,
     pub selector: Option<E<F>>,
 }
 
-impl<Fp: Field> Default for Env<Fp> {
+impl<Fp: Field, const XLEN: u32> Default for Env<Fp, XLEN> {
     fn default() -> Self {
         Self {
             scratch_state_idx: 0,
@@ -31,7 +33,9 @@ impl<Fp: Field> Default for Env<Fp> {
     }
 }
 
-impl<Fp: Field> InterpreterEnv for Env<Fp> {
+impl<Fp: Field, const XLEN: u32> InterpreterEnv for Env<Fp, XLEN> {
+    const XLEN: u32 = XLEN;
+
     /// In the concrete implementation for the constraints, the interpreter will
     /// work over columns. The position in this case can be seen as a new
     /// variable/input of our circuit.
@@ -151,7 +155,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         // No-op, witness only
     }
 
-    fn constant(x: u32) -> Self::Variable {
+    fn constant(x: u64) -> Self::Variable {
         Self::Variable::constant(Operations::from(Literal(Fp::from(x))))
     }
 
@@ -434,7 +438,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 }
 
-impl<Fp: Field> Env<Fp> {
+impl<Fp: Field, const XLEN: u32> Env<Fp, XLEN> {
     /// Return the constraints for the selector.
     /// Each selector must be a boolean.
     pub fn get_selector_constraints(&self) -> Vec<E<Fp>> {
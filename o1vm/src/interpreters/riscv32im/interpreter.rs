@@ -1,15 +1,26 @@
 //! This module implement an interpreter for the RISCV32 IM instruction set
-//! architecture.
+//! architecture, and for its 64-bit counterpart RISCV64 IM.
 //!
 //! The implementation mostly follows (and copy) code from the MIPS interpreter
 //! available [here](../mips/interpreter.rs).
 //!
+//! The interpreter is parameterized by the width of the registers,
+//! [InterpreterEnv::XLEN], which is 32 for RV32IM and 64 for RV64IM. The
+//! instructions of RV32IM operate on the `XLEN` bits of the registers. The
+//! instructions specific to RV64IM are the loads and stores of double words,
+//! the load of unsigned words, and the instructions with the suffix `W`, which
+//! operate on the lower 32 bits of the registers and sign-extend their 32-bit
+//! result to 64 bits.
+//!
 //! ## Credits
 //!
 //! We would like to thank the authors of the following documentations:
 //! - <https://msyksphinz-self.github.io/riscv-isadoc/html/rvm.html> ([CC BY
 //! 4.0](https://creativecommons.org/licenses/by/4.0/)) from
 //! [msyksphinz-self](https://github.com/msyksphinz-self/riscv-isadoc)
+//! - <https://msyksphinz-self.github.io/riscv-isadoc/html/rv64i.html> ([CC BY
+//! 4.0](https://creativecommons.org/licenses/by/4.0/)) from
+//! [msyksphinz-self](https://github.com/msyksphinz-self/riscv-isadoc)
 //! -
 //! <https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf>
 //! from the course [CS 3410: Computer System Organization and
@@ -28,6 +39,14 @@
 //! 32 bits value means that `v` must be interpreted as a i32 value in Rust, the
 //! most significant bit being the sign - 1 for negative, 0 for positive).
 //! By default, unsigned operations are performed.
+//! `sext32(v)` denotes the sign-extension to 64 bits of the lower 32 bits of
+//! `v`.
+//!
+//! The compressed (C) and atomic (A) extensions are supported for the integer
+//! instructions of RV32, following
+//! <https://msyksphinz-self.github.io/riscv-isadoc/html/rvc.html> and
+//! <https://msyksphinz-self.github.io/riscv-isadoc/html/rva.html> from the
+//! same authors.
 
 use super::registers::{REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER, REGISTER_NEXT_IP};
 use crate::lookups::{Lookup, LookupTableIDs};
@@ -45,6 +64,11 @@ pub enum Instruction {
     UJType(UJInstruction),
     SyscallType(SyscallInstruction),
     MType(MInstruction),
+    CType(CInstruction),
+    AType(AInstruction),
+    RWType(RWInstruction),
+    IWType(IWInstruction),
+    MWType(MWInstruction),
 }
 
 // See
@@ -70,7 +94,7 @@ pub enum RInstruction {
     /// Format: `sll rd, rs1, rs2`
     ///
     /// Description: Performs logical left shift on the value in register rs1 by
-    /// the shift amount held in the lower 5 bits of register rs2.
+    /// the shift amount held in the lower 5 bits (6 in RV64) of register rs2.
     ShiftLeftLogical, // sll
     /// Format: `slt rd, rs1, rs2`
     ///
@@ -92,12 +116,13 @@ pub enum RInstruction {
     /// Format: `srl rd, rs1, rs2`
     ///
     /// Description: Logical right shift on the value in register rs1 by the
-    /// shift amount held in the lower 5 bits of register rs2
+    /// shift amount held in the lower 5 bits (6 in RV64) of register rs2
     ShiftRightLogical, // srl
     /// Format: `sra rd, rs1, rs2`
     ///
     /// Description: Performs arithmetic right shift on the value in register
-    /// rs1 by the shift amount held in the lower 5 bits of register rs2
+    /// rs1 by the shift amount held in the lower 5 bits (6 in RV64) of
+    /// register rs2
     ShiftRightArithmetic, // sra
     /// Format: `or rd, rs1, rs2`
     ///
@@ -156,21 +181,33 @@ pub enum IInstruction {
     /// Description: Loads a 16-bit value from memory and zero-extends this to
     /// 32 bits before storing it in register rd.
     LoadHalfUnsigned, // lhu
+    /// Format: `lwu rd, offset(rs1)`
+    ///
+    /// Description: Loads a 32-bit value from memory and zero-extends this to
+    /// 64 bits before storing it in register rd. Only available in RV64.
+    LoadWordUnsigned, // lwu
+    /// Format: `ld rd, offset(rs1)`
+    ///
+    /// Description: Loads a 64-bit value from memory into register rd. Only
+    /// available in RV64.
+    LoadDouble, // ld
 
     /// Format: `slli rd, rs1, shamt`
     ///
     /// Description: Performs logical left shift on the value in register rs1 by
-    /// the shift amount held in the lower 5 bits of the immediate
+    /// the shift amount held in the lower 5 bits (6 in RV64) of the immediate
     ShiftLeftLogicalImmediate, // slli
     /// Format: `srli rd, rs1, shamt`
     ///
     /// Description: Performs logical right shift on the value in register rs1
-    /// by the shift amount held in the lower 5 bits of the immediate
+    /// by the shift amount held in the lower 5 bits (6 in RV64) of the
+    /// immediate
     ShiftRightLogicalImmediate, // srli
     /// Format: `srai rd, rs1, shamt`
     ///
     /// Description: Performs arithmetic right shift on the value in register
-    /// rs1 by the shift amount held in the lower 5 bits of the immediate
+    /// rs1 by the shift amount held in the lower 5 bits (6 in RV64) of the
+    /// immediate
     ShiftRightArithmeticImmediate, // srai
     /// Format: `slti rd, rs1, imm`
     ///
@@ -236,6 +273,11 @@ pub enum SInstruction {
     /// Description: Store 32-bit, values from the low bits of register rs2 to
     /// memory.
     StoreWord, // sw
+    /// Format: `sd rs2, offset(rs1)`
+    ///
+    /// Description: Store 64-bit, values from register rs2 to memory. Only
+    /// available in RV64.
+    StoreDouble, // sd
 }
 
 #[derive(
@@ -369,6 +411,395 @@ pub enum MInstruction {
     Remu, // remu
 }
 
+/// C extension instructions, i.e. the 16-bit encodings of the integer
+/// instructions of RV32C.
+/// The registers `rd'`, `rs1'` and `rs2'` are encoded on 3 bits, and
+/// correspond to the registers `x8` to `x15`.
+/// Following <https://msyksphinz-self.github.io/riscv-isadoc/html/rvc.html>
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum CInstruction {
+    #[default]
+    /// Format: `c.addi4spn rd', nzuimm`
+    ///
+    /// Description: Add a zero-extended non-zero immediate, scaled by 4, to
+    /// the stack pointer, x2, and writes the result to rd'.
+    /// Implementation: `x[8+rd'] = x[2] + nzuimm`
+    AddImmediate4SPN, // c.addi4spn
+    /// Format: `c.lw rd', uimm(rs1')`
+    ///
+    /// Description: Load a 32-bit value from memory into register rd'.
+    /// Implementation: `x[8+rd'] = sext(M[x[8+rs1'] + uimm][31:0])`
+    LoadWord, // c.lw
+    /// Format: `c.sw rs2', uimm(rs1')`
+    ///
+    /// Description: Store a 32-bit value in register rs2' to memory.
+    /// Implementation: `M[x[8+rs1'] + uimm][31:0] = x[8+rs2']`
+    StoreWord, // c.sw
+    /// Format: `c.addi rd, nzimm`
+    ///
+    /// Description: Add the non-zero sign-extended 6-bit immediate to the
+    /// value in register rd then writes the result to rd. `c.nop` is encoded
+    /// as `c.addi x0, 0`.
+    /// Implementation: `x[rd] = x[rd] + sext(nzimm)`
+    AddImmediate, // c.addi
+    /// Format: `c.jal offset`
+    ///
+    /// Description: Jump and link, writing the address of the instruction
+    /// following the jump (pc+2) to the link register, x1.
+    /// Implementation: `x[1] = pc+2; pc += sext(offset)`
+    JumpAndLink, // c.jal
+    /// Format: `c.li rd, imm`
+    ///
+    /// Description: Load the sign-extended 6-bit immediate into register rd.
+    /// Implementation: `x[rd] = sext(imm)`
+    LoadImmediate, // c.li
+    /// Format: `c.addi16sp nzimm`
+    ///
+    /// Description: Add the non-zero sign-extended 6-bit immediate, scaled
+    /// by 16, to the value in the stack pointer, x2.
+    /// Implementation: `x[2] = x[2] + sext(nzimm)`
+    AddImmediate16SP, // c.addi16sp
+    /// Format: `c.lui rd, nzimm`
+    ///
+    /// Description: Load the non-zero 6-bit immediate field into bits 17–12
+    /// of the destination register, clear the bottom 12 bits, and
+    /// sign-extend bit 17 into all higher bits of the destination.
+    /// Implementation: `x[rd] = sext(nzimm[17:12] << 12)`
+    LoadUpperImmediate, // c.lui
+    /// Format: `c.srli rd', shamt`
+    ///
+    /// Description: Performs a logical right shift of the value in register
+    /// rd' then writes the result to rd'.
+    /// Implementation: `x[8+rd'] = x[8+rd'] >>u shamt`
+    ShiftRightLogicalImmediate, // c.srli
+    /// Format: `c.srai rd', shamt`
+    ///
+    /// Description: Performs an arithmetic right shift of the value in
+    /// register rd' then writes the result to rd'.
+    /// Implementation: `x[8+rd'] = x[8+rd'] >>s shamt`
+    ShiftRightArithmeticImmediate, // c.srai
+    /// Format: `c.andi rd', imm`
+    ///
+    /// Description: Computes the bitwise AND of the value in register rd'
+    /// and the sign-extended 6-bit immediate, then writes the result to rd'.
+    /// Implementation: `x[8+rd'] = x[8+rd'] & sext(imm)`
+    AndImmediate, // c.andi
+    /// Format: `c.sub rd', rs2'`
+    ///
+    /// Description: Subtracts the value in register rs2' from the value in
+    /// register rd', then writes the result to register rd'.
+    /// Implementation: `x[8+rd'] = x[8+rd'] - x[8+rs2']`
+    Sub, // c.sub
+    /// Format: `c.xor rd', rs2'`
+    ///
+    /// Description: Computes the bitwise XOR of the values in registers rd'
+    /// and rs2', then writes the result to register rd'.
+    /// Implementation: `x[8+rd'] = x[8+rd'] ^ x[8+rs2']`
+    Xor, // c.xor
+    /// Format: `c.or rd', rs2'`
+    ///
+    /// Description: Computes the bitwise OR of the values in registers rd'
+    /// and rs2', then writes the result to register rd'.
+    /// Implementation: `x[8+rd'] = x[8+rd'] | x[8+rs2']`
+    Or, // c.or
+    /// Format: `c.and rd', rs2'`
+    ///
+    /// Description: Computes the bitwise AND of the values in registers rd'
+    /// and rs2', then writes the result to register rd'.
+    /// Implementation: `x[8+rd'] = x[8+rd'] & x[8+rs2']`
+    And, // c.and
+    /// Format: `c.j offset`
+    ///
+    /// Description: Unconditional control transfer.
+    /// Implementation: `pc += sext(offset)`
+    Jump, // c.j
+    /// Format: `c.beqz rs1', offset`
+    ///
+    /// Description: Take the branch if the value in register rs1' is zero.
+    /// Implementation: `if (x[8+rs1'] == 0) pc += sext(offset)`
+    BranchEqZero, // c.beqz
+    /// Format: `c.bnez rs1', offset`
+    ///
+    /// Description: Take the branch if the value in register rs1' is not
+    /// zero.
+    /// Implementation: `if (x[8+rs1'] != 0) pc += sext(offset)`
+    BranchNeqZero, // c.bnez
+    /// Format: `c.slli rd, shamt`
+    ///
+    /// Description: Performs a logical left shift of the value in register rd
+    /// then writes the result to rd.
+    /// Implementation: `x[rd] = x[rd] << shamt`
+    ShiftLeftLogicalImmediate, // c.slli
+    /// Format: `c.lwsp rd, uimm(x2)`
+    ///
+    /// Description: Load a 32-bit value from memory into register rd. It
+    /// computes an effective address by adding the zero-extended offset,
+    /// scaled by 4, to the stack pointer, x2.
+    /// Implementation: `x[rd] = sext(M[x[2] + uimm][31:0])`
+    LoadWordStackPointer, // c.lwsp
+    /// Format: `c.jr rs1`
+    ///
+    /// Description: Performs an unconditional control transfer to the
+    /// address in register rs1.
+    /// Implementation: `pc = x[rs1]`
+    JumpRegister, // c.jr
+    /// Format: `c.mv rd, rs2`
+    ///
+    /// Description: Copies the value in register rs2 into register rd.
+    /// Implementation: `x[rd] = x[rs2]`
+    Move, // c.mv
+    /// Format: `c.ebreak`
+    ///
+    /// Description: Cause control to be transferred back to the debugging
+    /// environment. There is no debugging environment in the VM, and the
+    /// execution is halted.
+    Breakpoint, // c.ebreak
+    /// Format: `c.jalr rs1`
+    ///
+    /// Description: Jump and link register, writing the address of the
+    /// instruction following the jump (pc+2) to the link register, x1.
+    /// Implementation: `t = pc+2; pc = x[rs1]; x[1] = t`
+    JumpAndLinkRegister, // c.jalr
+    /// Format: `c.add rd, rs2`
+    ///
+    /// Description: Add the values in registers rd and rs2 and writes the
+    /// result to register rd.
+    /// Implementation: `x[rd] = x[rd] + x[rs2]`
+    Add, // c.add
+    /// Format: `c.swsp rs2, uimm(x2)`
+    ///
+    /// Description: Store a 32-bit value in register rs2 to memory. It
+    /// computes an effective address by adding the zero-extended offset,
+    /// scaled by 4, to the stack pointer, x2.
+    /// Implementation: `M[x[2] + uimm][31:0] = x[rs2]`
+    StoreWordStackPointer, // c.swsp
+}
+
+/// A extension instructions.
+/// The VM runs a single hart, therefore the ordering bits `aq` and `rl` are
+/// ignored, and a reservation made by `lr.w` is never invalidated.
+/// Following <https://msyksphinz-self.github.io/riscv-isadoc/html/rva.html>
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum AInstruction {
+    #[default]
+    /// Format: `lr.w rd, (rs1)`
+    ///
+    /// Description: Load a word from the address in rs1, places the
+    /// sign-extended value in rd, and registers a reservation on the memory
+    /// address.
+    /// Implementation: `x[rd] = LoadReserved32(M[x[rs1]])`
+    LoadReserved, // lr.w
+    /// Format: `sc.w rd, rs2, (rs1)`
+    ///
+    /// Description: Write a word in rs2 to the address in rs1, provided a
+    /// valid reservation still exists on that address. SC writes zero to rd
+    /// on success or a nonzero code on failure. As the reservations are
+    /// never invalidated, it always succeeds.
+    /// Implementation: `x[rd] = StoreConditional32(M[x[rs1]], x[rs2])`
+    StoreConditional, // sc.w
+    /// Format: `amoswap.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, swap the loaded value and the value in
+    /// rs2, then store the result back to the address in rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] SWAP x[rs2])`
+    AmoSwap, // amoswap.w
+    /// Format: `amoadd.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, add the loaded value and the value in rs2,
+    /// then store the result back to the address in rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] + x[rs2])`
+    AmoAdd, // amoadd.w
+    /// Format: `amoxor.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, apply exclusive or to the loaded value and
+    /// the value in rs2, then store the result back to the address in rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] ^ x[rs2])`
+    AmoXor, // amoxor.w
+    /// Format: `amoand.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, apply and to the loaded value and the value
+    /// in rs2, then store the result back to the address in rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] & x[rs2])`
+    AmoAnd, // amoand.w
+    /// Format: `amoor.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, apply or to the loaded value and the value
+    /// in rs2, then store the result back to the address in rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] | x[rs2])`
+    AmoOr, // amoor.w
+    /// Format: `amomin.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, take the signed minimum of the loaded value
+    /// and the value in rs2, then store the result back to the address in
+    /// rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] MIN x[rs2])`
+    AmoMin, // amomin.w
+    /// Format: `amomax.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, take the signed maximum of the loaded value
+    /// and the value in rs2, then store the result back to the address in
+    /// rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] MAX x[rs2])`
+    AmoMax, // amomax.w
+    /// Format: `amominu.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, take the unsigned minimum of the loaded
+    /// value and the value in rs2, then store the result back to the address
+    /// in rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] MINU x[rs2])`
+    AmoMinUnsigned, // amominu.w
+    /// Format: `amomaxu.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically load a 32-bit value from the address in rs1,
+    /// place it into register rd, take the unsigned maximum of the loaded
+    /// value and the value in rs2, then store the result back to the address
+    /// in rs1.
+    /// Implementation: `x[rd] = AMO32(M[x[rs1]] MAXU x[rs2])`
+    AmoMaxUnsigned, // amomaxu.w
+}
+
+/// R-type instructions of RV64I operating on the lower 32 bits of the
+/// registers. The 32-bit result is sign-extended to 64 bits.
+/// Following <https://msyksphinz-self.github.io/riscv-isadoc/html/rv64i.html>
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum RWInstruction {
+    #[default]
+    /// Format: `addw rd, rs1, rs2`
+    ///
+    /// Description: Adds the 32-bit of registers rs1 and 32-bit of register
+    /// rs2 and stores the result in rd. Arithmetic overflow is ignored and
+    /// the low 32-bits of the result is sign-extended to 64-bits and written
+    /// to the destination register.
+    /// Implementation: `x[rd] = sext32(x[rs1] + x[rs2])`
+    AddWord, // addw
+    /// Format: `subw rd, rs1, rs2`
+    ///
+    /// Description: Subtract the 32-bit of registers rs1 and 32-bit of
+    /// register rs2 and stores the result in rd. Arithmetic overflow is
+    /// ignored and the low 32-bits of the result is sign-extended to 64-bits
+    /// and written to the destination register.
+    /// Implementation: `x[rd] = sext32(x[rs1] - x[rs2])`
+    SubWord, // subw
+    /// Format: `sllw rd, rs1, rs2`
+    ///
+    /// Description: Performs logical left shift on the low 32-bits value in
+    /// register rs1 by the shift amount held in the lower 5 bits of register
+    /// rs2 and produce 32-bit results and written to the destination register
+    /// rd.
+    /// Implementation: `x[rd] = sext32(x[rs1] << x[rs2][4:0])`
+    ShiftLeftLogicalWord, // sllw
+    /// Format: `srlw rd, rs1, rs2`
+    ///
+    /// Description: Performs logical right shift on the low 32-bits value in
+    /// register rs1 by the shift amount held in the lower 5 bits of register
+    /// rs2 and produce 32-bit results and written to the destination register
+    /// rd.
+    /// Implementation: `x[rd] = sext32(x[rs1][31:0] >> x[rs2][4:0])`
+    ShiftRightLogicalWord, // srlw
+    /// Format: `sraw rd, rs1, rs2`
+    ///
+    /// Description: Performs arithmetic right shift on the low 32-bits value
+    /// in register rs1 by the shift amount held in the lower 5 bits of
+    /// register rs2 and produce 32-bit results and written to the destination
+    /// register rd.
+    /// Implementation: `x[rd] = sext32(signed(x[rs1][31:0]) >> x[rs2][4:0])`
+    ShiftRightArithmeticWord, // sraw
+}
+
+/// I-type instructions of RV64I operating on the lower 32 bits of the
+/// registers. The 32-bit result is sign-extended to 64 bits.
+/// Following <https://msyksphinz-self.github.io/riscv-isadoc/html/rv64i.html>
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum IWInstruction {
+    #[default]
+    /// Format: `addiw rd, rs1, imm`
+    ///
+    /// Description: Adds the sign-extended 12-bit immediate to register rs1
+    /// and produces the proper sign-extension of a 32-bit result in rd.
+    /// Overflows are ignored and the result is the low 32 bits of the result
+    /// sign-extended to 64 bits.
+    /// Implementation: `x[rd] = sext32(x[rs1] + sext(immediate))`
+    AddImmediateWord, // addiw
+    /// Format: `slliw rd, rs1, shamt`
+    ///
+    /// Description: Performs logical left shift on the 32-bit of value in
+    /// register rs1 by the shift amount held in the lower 5 bits of the
+    /// immediate.
+    /// Implementation: `x[rd] = sext32(x[rs1] << shamt)`
+    ShiftLeftLogicalImmediateWord, // slliw
+    /// Format: `srliw rd, rs1, shamt`
+    ///
+    /// Description: Performs logical right shift on the 32-bit of value in
+    /// register rs1 by the shift amount held in the lower 5 bits of the
+    /// immediate.
+    /// Implementation: `x[rd] = sext32(x[rs1][31:0] >> shamt)`
+    ShiftRightLogicalImmediateWord, // srliw
+    /// Format: `sraiw rd, rs1, shamt`
+    ///
+    /// Description: Performs arithmetic right shift on the 32-bit of value in
+    /// register rs1 by the shift amount held in the lower 5 bits of the
+    /// immediate.
+    /// Implementation: `x[rd] = sext32(signed(x[rs1][31:0]) >> shamt)`
+    ShiftRightArithmeticImmediateWord, // sraiw
+}
+
+/// M extension instructions of RV64M operating on the lower 32 bits of the
+/// registers. The 32-bit result is sign-extended to 64 bits.
+/// Following <https://msyksphinz-self.github.io/riscv-isadoc/html/rvm.html>
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum MWInstruction {
+    #[default]
+    /// Format: `mulw rd, rs1, rs2`
+    ///
+    /// Description: Multiplies lower 32 bits of the source registers, placing
+    /// the sign-extension of the lower 32 bits of the result into the
+    /// destination register.
+    /// Implementation: `x[rd] = sext32(x[rs1] * x[rs2])`
+    MulWord, // mulw
+    /// Format: `divw rd, rs1, rs2`
+    ///
+    /// Description: perform an 32 bits by 32 bits signed integer division of
+    /// the lower 32 bits of rs1 by the lower 32 bits of rs2.
+    /// Implementation: `x[rd] = sext32(signed(x[rs1][31:0]) /s signed(x[rs2][31:0]))`
+    DivWord, // divw
+    /// Format: `divuw rd, rs1, rs2`
+    ///
+    /// Description: perform an 32 bits by 32 bits unsigned integer division
+    /// of the lower 32 bits of rs1 by the lower 32 bits of rs2.
+    /// Implementation: `x[rd] = sext32(x[rs1][31:0] /u x[rs2][31:0])`
+    DivUnsignedWord, // divuw
+    /// Format: `remw rd, rs1, rs2`
+    ///
+    /// Description: perform an 32 bits by 32 bits signed integer reminder of
+    /// the lower 32 bits of rs1 by the lower 32 bits of rs2.
+    /// Implementation: `x[rd] = sext32(signed(x[rs1][31:0]) %s signed(x[rs2][31:0]))`
+    RemWord, // remw
+    /// Format: `remuw rd, rs1, rs2`
+    ///
+    /// Description: perform an 32 bits by 32 bits unsigned integer reminder
+    /// of the lower 32 bits of rs1 by the lower 32 bits of rs2.
+    /// Implementation: `x[rd] = sext32(x[rs1][31:0] %u x[rs2][31:0])`
+    RemUnsignedWord, // remuw
+}
+
 impl IntoIterator for Instruction {
     type Item = Instruction;
     type IntoIter = std::vec::IntoIter<Instruction>;
@@ -431,6 +862,41 @@ impl IntoIterator for Instruction {
                 }
                 iter_contents.into_iter()
             }
+            Instruction::CType(_) => {
+                let mut iter_contents = Vec::with_capacity(CInstruction::COUNT);
+                for ctype in CInstruction::iter() {
+                    iter_contents.push(Instruction::CType(ctype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::AType(_) => {
+                let mut iter_contents = Vec::with_capacity(AInstruction::COUNT);
+                for atype in AInstruction::iter() {
+                    iter_contents.push(Instruction::AType(atype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::RWType(_) => {
+                let mut iter_contents = Vec::with_capacity(RWInstruction::COUNT);
+                for rwtype in RWInstruction::iter() {
+                    iter_contents.push(Instruction::RWType(rwtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::IWType(_) => {
+                let mut iter_contents = Vec::with_capacity(IWInstruction::COUNT);
+                for iwtype in IWInstruction::iter() {
+                    iter_contents.push(Instruction::IWType(iwtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::MWType(_) => {
+                let mut iter_contents = Vec::with_capacity(MWInstruction::COUNT);
+                for mwtype in MWInstruction::iter() {
+                    iter_contents.push(Instruction::MWType(mwtype));
+                }
+                iter_contents.into_iter()
+            }
         }
     }
 }
@@ -446,6 +912,11 @@ impl std::fmt::Display for Instruction {
             Instruction::UJType(ujtype) => write!(f, "{}", ujtype),
             Instruction::SyscallType(_syscall) => write!(f, "ecall"),
             Instruction::MType(mtype) => write!(f, "{}", mtype),
+            Instruction::CType(ctype) => write!(f, "{}", ctype),
+            Instruction::AType(atype) => write!(f, "{}", atype),
+            Instruction::RWType(rwtype) => write!(f, "{}", rwtype),
+            Instruction::IWType(iwtype) => write!(f, "{}", iwtype),
+            Instruction::MWType(mwtype) => write!(f, "{}", mwtype),
         }
     }
 }
@@ -477,6 +948,8 @@ impl std::fmt::Display for IInstruction {
             IInstruction::LoadWord => write!(f, "lw"),
             IInstruction::LoadByteUnsigned => write!(f, "lbu"),
             IInstruction::LoadHalfUnsigned => write!(f, "lhu"),
+            IInstruction::LoadWordUnsigned => write!(f, "lwu"),
+            IInstruction::LoadDouble => write!(f, "ld"),
             IInstruction::ShiftLeftLogicalImmediate => write!(f, "slli"),
             IInstruction::ShiftRightLogicalImmediate => write!(f, "srli"),
             IInstruction::ShiftRightArithmeticImmediate => write!(f, "srai"),
@@ -497,6 +970,7 @@ impl std::fmt::Display for SInstruction {
             SInstruction::StoreByte => write!(f, "sb"),
             SInstruction::StoreHalf => write!(f, "sh"),
             SInstruction::StoreWord => write!(f, "sw"),
+            SInstruction::StoreDouble => write!(f, "sd"),
         }
     }
 }
@@ -546,7 +1020,97 @@ impl std::fmt::Display for MInstruction {
     }
 }
 
+impl std::fmt::Display for CInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CInstruction::AddImmediate4SPN => write!(f, "c.addi4spn"),
+            CInstruction::LoadWord => write!(f, "c.lw"),
+            CInstruction::StoreWord => write!(f, "c.sw"),
+            CInstruction::AddImmediate => write!(f, "c.addi"),
+            CInstruction::JumpAndLink => write!(f, "c.jal"),
+            CInstruction::LoadImmediate => write!(f, "c.li"),
+            CInstruction::AddImmediate16SP => write!(f, "c.addi16sp"),
+            CInstruction::LoadUpperImmediate => write!(f, "c.lui"),
+            CInstruction::ShiftRightLogicalImmediate => write!(f, "c.srli"),
+            CInstruction::ShiftRightArithmeticImmediate => write!(f, "c.srai"),
+            CInstruction::AndImmediate => write!(f, "c.andi"),
+            CInstruction::Sub => write!(f, "c.sub"),
+            CInstruction::Xor => write!(f, "c.xor"),
+            CInstruction::Or => write!(f, "c.or"),
+            CInstruction::And => write!(f, "c.and"),
+            CInstruction::Jump => write!(f, "c.j"),
+            CInstruction::BranchEqZero => write!(f, "c.beqz"),
+            CInstruction::BranchNeqZero => write!(f, "c.bnez"),
+            CInstruction::ShiftLeftLogicalImmediate => write!(f, "c.slli"),
+            CInstruction::LoadWordStackPointer => write!(f, "c.lwsp"),
+            CInstruction::JumpRegister => write!(f, "c.jr"),
+            CInstruction::Move => write!(f, "c.mv"),
+            CInstruction::Breakpoint => write!(f, "c.ebreak"),
+            CInstruction::JumpAndLinkRegister => write!(f, "c.jalr"),
+            CInstruction::Add => write!(f, "c.add"),
+            CInstruction::StoreWordStackPointer => write!(f, "c.swsp"),
+        }
+    }
+}
+
+impl std::fmt::Display for AInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AInstruction::LoadReserved => write!(f, "lr.w"),
+            AInstruction::StoreConditional => write!(f, "sc.w"),
+            AInstruction::AmoSwap => write!(f, "amoswap.w"),
+            AInstruction::AmoAdd => write!(f, "amoadd.w"),
+            AInstruction::AmoXor => write!(f, "amoxor.w"),
+            AInstruction::AmoAnd => write!(f, "amoand.w"),
+            AInstruction::AmoOr => write!(f, "amoor.w"),
+            AInstruction::AmoMin => write!(f, "amomin.w"),
+            AInstruction::AmoMax => write!(f, "amomax.w"),
+            AInstruction::AmoMinUnsigned => write!(f, "amominu.w"),
+            AInstruction::AmoMaxUnsigned => write!(f, "amomaxu.w"),
+        }
+    }
+}
+
+impl std::fmt::Display for RWInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RWInstruction::AddWord => write!(f, "addw"),
+            RWInstruction::SubWord => write!(f, "subw"),
+            RWInstruction::ShiftLeftLogicalWord => write!(f, "sllw"),
+            RWInstruction::ShiftRightLogicalWord => write!(f, "srlw"),
+            RWInstruction::ShiftRightArithmeticWord => write!(f, "sraw"),
+        }
+    }
+}
+
+impl std::fmt::Display for IWInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IWInstruction::AddImmediateWord => write!(f, "addiw"),
+            IWInstruction::ShiftLeftLogicalImmediateWord => write!(f, "slliw"),
+            IWInstruction::ShiftRightLogicalImmediateWord => write!(f, "srliw"),
+            IWInstruction::ShiftRightArithmeticImmediateWord => write!(f, "sraiw"),
+        }
+    }
+}
+
+impl std::fmt::Display for MWInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MWInstruction::MulWord => write!(f, "mulw"),
+            MWInstruction::DivWord => write!(f, "divw"),
+            MWInstruction::DivUnsignedWord => write!(f, "divuw"),
+            MWInstruction::RemWord => write!(f, "remw"),
+            MWInstruction::RemUnsignedWord => write!(f, "remuw"),
+        }
+    }
+}
+
 pub trait InterpreterEnv {
+    /// The width of the registers, in bits, i.e. 32 for RV32IM and 64 for
+    /// RV64IM.
+    const XLEN: u32;
+
     /// A position can be seen as an indexed variable
     type Position;
 
@@ -846,6 +1410,17 @@ pub trait InterpreterEnv {
             unsafe { self.fetch_memory_access(addr, last_accessed_location) }
         };
         let instruction_counter = self.instruction_counter();
+        // The access is given to the memory argument, see
+        // `crate::memory_argument::MemoryAccess::table_entry`.
+        self.add_lookup(Lookup::write_one(
+            LookupTableIDs::MemoryAccessLookup,
+            vec![
+                addr.clone(),
+                instruction_counter.clone(),
+                old_value.clone(),
+                new_value.clone(),
+            ],
+        ));
         let elapsed_time = instruction_counter.clone() - last_accessed.clone();
         let new_accessed = {
             // Here, we write as if the memory had been written *at the start of the next
@@ -943,7 +1518,7 @@ pub trait InterpreterEnv {
     }
 
     fn set_instruction_pointer(&mut self, ip: Self::Variable) {
-        let idx = Self::constant(REGISTER_CURRENT_IP as u32);
+        let idx = Self::constant(REGISTER_CURRENT_IP as u64);
         let new_accessed = self.instruction_counter() + Self::constant(1);
         unsafe {
             self.push_register_access(&idx, new_accessed.clone());
@@ -958,7 +1533,7 @@ pub trait InterpreterEnv {
     }
 
     fn get_instruction_pointer(&mut self) -> Self::Variable {
-        let idx = Self::constant(REGISTER_CURRENT_IP as u32);
+        let idx = Self::constant(REGISTER_CURRENT_IP as u64);
         let ip = {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
@@ -971,7 +1546,7 @@ pub trait InterpreterEnv {
     }
 
     fn set_next_instruction_pointer(&mut self, ip: Self::Variable) {
-        let idx = Self::constant(REGISTER_NEXT_IP as u32);
+        let idx = Self::constant(REGISTER_NEXT_IP as u64);
         let new_accessed = self.instruction_counter() + Self::constant(1);
         unsafe {
             self.push_register_access(&idx, new_accessed.clone());
@@ -986,7 +1561,7 @@ pub trait InterpreterEnv {
     }
 
     fn get_next_instruction_pointer(&mut self) -> Self::Variable {
-        let idx = Self::constant(REGISTER_NEXT_IP as u32);
+        let idx = Self::constant(REGISTER_NEXT_IP as u64);
         let ip = {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
@@ -998,7 +1573,7 @@ pub trait InterpreterEnv {
         ip
     }
 
-    fn constant(x: u32) -> Self::Variable;
+    fn constant(x: u64) -> Self::Variable;
 
     /// Extract the bits from the variable `x` between `highest_bit` and `lowest_bit`, and store
     /// the result in `position`.
@@ -1368,7 +1943,7 @@ pub trait InterpreterEnv {
         by_amount: &Self::Variable,
         if_is_true: &Self::Variable,
     ) -> Self::Variable {
-        let idx = Self::constant(REGISTER_HEAP_POINTER as u32);
+        let idx = Self::constant(REGISTER_HEAP_POINTER as u64);
         let old_ptr = {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
@@ -1393,7 +1968,40 @@ pub trait InterpreterEnv {
             let pos = self.alloc_scratch();
             unsafe { self.bitmask(x, bitlength, bitlength - 1, pos) }
         };
-        high_bit * Self::constant(((1 << (32 - bitlength)) - 1) << bitlength) + x.clone()
+        high_bit * Self::constant(((1 << (Self::XLEN - bitlength)) - 1) << bitlength) + x.clone()
+    }
+
+    /// Given a variable `x`, this function returns its lower 32 bits
+    /// sign-extended to `XLEN` bits.
+    fn sign_extend_word(&mut self, x: &Self::Variable) -> Self::Variable {
+        let word = {
+            let pos = self.alloc_scratch();
+            unsafe { self.bitmask(x, 32, 0, pos) }
+        };
+        self.sign_extend(&word, 32)
+    }
+
+    /// Reads the `n_bytes` bytes of memory starting at address `addr`, and
+    /// returns the value they encode in little-endian.
+    fn read_memory_le(&mut self, addr: &Self::Variable, n_bytes: u32) -> Self::Variable {
+        (0..n_bytes).fold(Self::Variable::zero(), |value, i| {
+            let byte = self.read_memory(&(addr.clone() + Self::constant(i.into())));
+            value + byte * Self::constant(1 << (8 * i))
+        })
+    }
+
+    /// Writes the `n_bytes` lower bytes of `value` in memory starting at
+    /// address `addr`, in little-endian.
+    fn write_memory_le(&mut self, addr: &Self::Variable, value: &Self::Variable, n_bytes: u32) {
+        for i in 0..n_bytes {
+            // FIXME: constrain the decomposition of the value
+            let byte = {
+                let pos = self.alloc_scratch();
+                unsafe { self.bitmask(value, 8 * (i + 1), 8 * i, pos) }
+            };
+            self.range_check8(&byte, 8);
+            self.write_memory(&(addr.clone() + Self::constant(i.into())), byte);
+        }
     }
 
     fn report_exit(&mut self, exit_code: &Self::Variable);
@@ -1412,6 +2020,11 @@ pub fn interpret_instruction<Env: InterpreterEnv>(env: &mut Env, instr: Instruct
         Instruction::UJType(ujtype) => interpret_ujtype(env, ujtype),
         Instruction::SyscallType(syscall) => interpret_syscall(env, syscall),
         Instruction::MType(mtype) => interpret_mtype(env, mtype),
+        Instruction::CType(ctype) => interpret_ctype(env, ctype),
+        Instruction::AType(atype) => interpret_atype(env, atype),
+        Instruction::RWType(rwtype) => interpret_rwtype(env, rwtype),
+        Instruction::IWType(iwtype) => interpret_iwtype(env, iwtype),
+        Instruction::MWType(mwtype) => interpret_mwtype(env, mwtype),
     }
 }
 
@@ -1508,7 +2121,7 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::Sub => {
             /* sub: x[rd] = x[rs1] - x[rs2] */
@@ -1524,20 +2137,21 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::ShiftLeftLogical => {
-            /* sll: x[rd] = x[rs1] << x[rs2] */
+            /* sll: x[rd] = x[rs1] << x[rs2][log2(XLEN)-1:0] */
             let local_rs1 = env.read_register(&rs1);
             let local_rs2 = env.read_register(&rs2);
+            let shamt = shift_amount(env, &local_rs2);
             let local_rd = unsafe {
                 let rd_scratch = env.alloc_scratch();
-                env.shift_left(&local_rs1, &local_rs2, rd_scratch)
+                env.shift_left(&local_rs1, &shamt, rd_scratch)
             };
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::SetLessThan => {
             /* slt: x[rd] = (x[rs1] < x[rs2]) ? 1 : 0 */
@@ -1550,7 +2164,7 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::SetLessThanUnsigned => {
             /* sltu: x[rd] = (x[rs1] < (u)x[rs2]) ? 1 : 0 */
@@ -1563,7 +2177,7 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::Xor => {
             /* xor: x[rd] = x[rs1] ^ x[rs2] */
@@ -1576,33 +2190,35 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::ShiftRightLogical => {
-            /* srl: x[rd] = x[rs1] >> x[rs2] */
+            /* srl: x[rd] = x[rs1] >> x[rs2][log2(XLEN)-1:0] */
             let local_rs1 = env.read_register(&rs1);
             let local_rs2 = env.read_register(&rs2);
+            let shamt = shift_amount(env, &local_rs2);
             let local_rd = unsafe {
                 let pos = env.alloc_scratch();
-                env.shift_right(&local_rs1, &local_rs2, pos)
+                env.shift_right(&local_rs1, &shamt, pos)
             };
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::ShiftRightArithmetic => {
-            /* sra: x[rd] = x[rs1] >> x[rs2] */
+            /* sra: x[rd] = x[rs1] >> x[rs2][log2(XLEN)-1:0] */
             let local_rs1 = env.read_register(&rs1);
             let local_rs2 = env.read_register(&rs2);
+            let shamt = shift_amount(env, &local_rs2);
             let local_rd = unsafe {
                 let pos = env.alloc_scratch();
-                env.shift_right_arithmetic(&local_rs1, &local_rs2, pos)
+                env.shift_right_arithmetic(&local_rs1, &shamt, pos)
             };
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::Or => {
             /* or: x[rd] = x[rs1] | x[rs2] */
@@ -1615,7 +2231,7 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         RInstruction::And => {
             /* and: x[rd] = x[rs1] & x[rs2] */
@@ -1628,13 +2244,14 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
-        RInstruction::Fence => {
-            unimplemented!("Fence")
-        }
-        RInstruction::FenceI => {
-            unimplemented!("FenceI")
+        RInstruction::Fence | RInstruction::FenceI => {
+            // fence, fence.i: the VM runs a single hart without caches,
+            // therefore the memory accesses and the instruction fetches are
+            // always ordered, and the fences are no-ops.
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
     };
 }
@@ -1695,7 +2312,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
 
     // check correctness of decomposition
     env.add_constraint(
-        instruction
+        instruction.clone()
             - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
             - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
             - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
@@ -1721,7 +2338,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let value = env.sign_extend(&value, 8);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::LoadHalf => {
             // lh:  x[rd] = sext(M[x[rs1] + sext(offset)][15:0])
@@ -1736,13 +2353,11 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
                 address
             };
             // Add a range check here for address
-            let v0 = env.read_memory(&address);
-            let v1 = env.read_memory(&(address.clone() + Env::constant(1)));
-            let value = (v0 * Env::constant(1 << 8)) + v1;
+            let value = env.read_memory_le(&address, 2);
             let value = env.sign_extend(&value, 16);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::LoadWord => {
             // lw:  x[rd] = sext(M[x[rs1] + sext(offset)][31:0])
@@ -1756,18 +2371,11 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
                 address
             };
             // Add a range check here for address
-            let v0 = env.read_memory(&address);
-            let v1 = env.read_memory(&(address.clone() + Env::constant(1)));
-            let v2 = env.read_memory(&(address.clone() + Env::constant(2)));
-            let v3 = env.read_memory(&(address.clone() + Env::constant(3)));
-            let value = (v0 * Env::constant(1 << 24))
-                + (v1 * Env::constant(1 << 16))
-                + (v2 * Env::constant(1 << 8))
-                + v3;
+            let value = env.read_memory_le(&address, 4);
             let value = env.sign_extend(&value, 32);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::LoadByteUnsigned => {
             //lbu: x[rd] = M[x[rs1] + sext(offset)][7:0]
@@ -1785,7 +2393,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let value = env.read_memory(&address);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::LoadHalfUnsigned => {
             // lhu: x[rd] = M[x[rs1] + sext(offset)][15:0]
@@ -1800,27 +2408,107 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
                 address
             };
             // Add a range check here for address
-            let v0 = env.read_memory(&address);
-            let v1 = env.read_memory(&(address.clone() + Env::constant(1)));
-            let value = (v0 * Env::constant(1 << 8)) + v1;
+            let value = env.read_memory_le(&address, 2);
+            env.write_register(&rd, value);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
+        }
+        IInstruction::LoadWordUnsigned => {
+            // lwu: x[rd] = M[x[rs1] + sext(offset)][31:0]
+            let local_rs1 = env.read_register(&rs1);
+            let local_imm = env.sign_extend(&imm, 12);
+            let address = {
+                let address_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (address, _overflow) = unsafe {
+                    env.add_witness(&local_rs1, &local_imm, address_scratch, overflow_scratch)
+                };
+                address
+            };
+            // Add a range check here for address
+            let value = env.read_memory_le(&address, 4);
+            env.write_register(&rd, value);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
+        }
+        IInstruction::LoadDouble => {
+            // ld: x[rd] = M[x[rs1] + sext(offset)][63:0]
+            let local_rs1 = env.read_register(&rs1);
+            let local_imm = env.sign_extend(&imm, 12);
+            let address = {
+                let address_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (address, _overflow) = unsafe {
+                    env.add_witness(&local_rs1, &local_imm, address_scratch, overflow_scratch)
+                };
+                address
+            };
+            // Add a range check here for address
+            let value = env.read_memory_le(&address, 8);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::ShiftLeftLogicalImmediate => {
-            unimplemented!("ShiftLeftLogicalImmediate")
+            // slli: x[rd] = x[rs1] << shamt
+            let local_rs1 = env.read_register(&rs1);
+            // The shift amount is in the lower log2(XLEN) bits of the immediate
+            let shamt = shift_amount(env, &imm);
+            let local_rd = unsafe {
+                let rd_scratch = env.alloc_scratch();
+                env.shift_left(&local_rs1, &shamt, rd_scratch)
+            };
+            env.write_register(&rd, local_rd);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::ShiftRightLogicalImmediate => {
-            unimplemented!("ShiftRightLogicalImmediate")
+            // srli: x[rd] = x[rs1] >>u shamt
+            let local_rs1 = env.read_register(&rs1);
+            let shamt = shift_amount(env, &imm);
+            let local_rd = unsafe {
+                let rd_scratch = env.alloc_scratch();
+                env.shift_right(&local_rs1, &shamt, rd_scratch)
+            };
+            env.write_register(&rd, local_rd);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::ShiftRightArithmeticImmediate => {
-            unimplemented!("ShiftRightArithmeticImmediate")
+            // srai: x[rd] = x[rs1] >>s shamt
+            let local_rs1 = env.read_register(&rs1);
+            let shamt = shift_amount(env, &imm);
+            let local_rd = unsafe {
+                let rd_scratch = env.alloc_scratch();
+                env.shift_right_arithmetic(&local_rs1, &shamt, rd_scratch)
+            };
+            env.write_register(&rd, local_rd);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::SetLessThanImmediate => {
-            unimplemented!("SetLessThanImmediate")
+            // slti: x[rd] = (x[rs1] <s sext(immediate)) ? 1 : 0
+            let local_rs1 = env.read_register(&rs1);
+            let local_imm = env.sign_extend(&imm, 12);
+            let local_rd = {
+                let pos = env.alloc_scratch();
+                unsafe { env.test_less_than_signed(&local_rs1, &local_imm, pos) }
+            };
+            env.write_register(&rd, local_rd);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::SetLessThanImmediateUnsigned => {
-            unimplemented!("SetLessThanImmediateUnsigned")
+            // sltiu: x[rd] = (x[rs1] <u sext(immediate)) ? 1 : 0
+            let local_rs1 = env.read_register(&rs1);
+            let local_imm = env.sign_extend(&imm, 12);
+            let local_rd = {
+                let pos = env.alloc_scratch();
+                unsafe { env.test_less_than(&local_rs1, &local_imm, pos) }
+            };
+            env.write_register(&rd, local_rd);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::AddImmediate => {
             // addi: x[rd] = x[rs1] + sext(immediate)
@@ -1835,7 +2523,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::XorImmediate => {
             // xori: x[rd] = x[rs1] ^ sext(immediate)
@@ -1845,7 +2533,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let local_rd = unsafe { env.xor_witness(&local_rs1, &local_imm, rd_scratch) };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::OrImmediate => {
             // ori: x[rd] = x[rs1] | sext(immediate)
@@ -1855,7 +2543,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let local_rd = unsafe { env.or_witness(&local_rs1, &local_imm, rd_scratch) };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::AndImmediate => {
             // andi: x[rd] = x[rs1] & sext(immediate)
@@ -1865,7 +2553,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let local_rd = unsafe { env.and_witness(&local_rs1, &local_imm, rd_scratch) };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         IInstruction::JumpAndLinkRegister => {
             let addr = env.read_register(&rs1);
@@ -1884,7 +2572,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, next_instruction_pointer.clone());
             env.set_instruction_pointer(new_addr.clone());
-            env.set_next_instruction_pointer(new_addr.clone() + Env::constant(4u32));
+            env.set_next_instruction_pointer(new_addr.clone() + Env::constant(4));
         }
     };
 }
@@ -1899,7 +2587,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
 
     let instruction = {
         let v0 = env.read_memory(&instruction_pointer);
@@ -1948,17 +2636,36 @@ pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction)
     };
     env.range_check16(&imm2, 12);
 
-    match instr {
-        SInstruction::StoreByte => {
-            unimplemented!("StoreByte")
-        }
-        SInstruction::StoreHalf => {
-            unimplemented!("StoreHalf")
-        }
-        SInstruction::StoreWord => {
-            unimplemented!("StoreWord")
-        }
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (imm1.clone() * Env::constant(1 << 7))      // imm[4:0] at bits 7-11
+            - (funct3 * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+            - (imm2.clone() * Env::constant(1 << 25)), // imm[11:5] at bits 25-31
+    );
+
+    // M[x[rs1] + sext(offset)] = x[rs2], offset = imm[11:5] | imm[4:0]
+    let base = env.read_register(&rs1);
+    let offset = env.sign_extend(&(imm1 + imm2 * Env::constant(1 << 5)), 12);
+    let address = add_wrapping(env, &base, &offset);
+    let value = env.read_register(&rs2);
+    let n_bytes = match instr {
+        // sb: M[x[rs1] + sext(offset)] = x[rs2][7:0]
+        SInstruction::StoreByte => 1,
+        // sh: M[x[rs1] + sext(offset)] = x[rs2][15:0]
+        SInstruction::StoreHalf => 2,
+        // sw: M[x[rs1] + sext(offset)] = x[rs2][31:0]
+        SInstruction::StoreWord => 4,
+        // sd: M[x[rs1] + sext(offset)] = x[rs2][63:0]
+        SInstruction::StoreDouble => 8,
     };
+    env.write_memory_le(&address, &value, n_bytes);
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
 }
 
 /// Interpret an SB-type instruction.
@@ -1971,7 +2678,7 @@ pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction)
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
 
     let instruction = {
         let v0 = env.read_memory(&instruction_pointer);
@@ -1990,12 +2697,13 @@ pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction
     };
     env.range_check8(&opcode, 7);
 
-    // FIXME: trickier
-    let imm1 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 12, 7, pos) }
-    };
-    env.range_check8(&imm1, 5);
+    // The offset is scattered in the instruction as imm[12|10:5] at bits
+    // 25-31 and imm[4:1|11] at bits 7-11.
+    let imm_11 = instruction_bits(env, &instruction, 8, 7);
+    env.range_check8(&imm_11, 1);
+
+    let imm_4_1 = instruction_bits(env, &instruction, 12, 8);
+    env.range_check8(&imm_4_1, 4);
 
     let funct3 = {
         let pos = env.alloc_scratch();
@@ -2015,35 +2723,77 @@ pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction
     };
     env.range_check8(&rs2, 5);
 
-    // FIXME: trickier
-    let imm2 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 32, 25, pos) }
-    };
-    env.range_check16(&imm2, 12);
+    let imm_10_5 = instruction_bits(env, &instruction, 31, 25);
+    env.range_check8(&imm_10_5, 6);
 
-    // FIXME: check correctness of decomposition
+    let imm_12 = instruction_bits(env, &instruction, 32, 31);
+    env.range_check8(&imm_12, 1);
 
-    match instr {
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (imm_11.clone() * Env::constant(1 << 7))    // imm[11] at bit 7
+            - (imm_4_1.clone() * Env::constant(1 << 8))   // imm[4:1] at bits 8-11
+            - (funct3 * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+            - (imm_10_5.clone() * Env::constant(1 << 25)) // imm[10:5] at bits 25-30
+            - (imm_12.clone() * Env::constant(1 << 31)), // imm[12] at bit 31
+    );
+
+    let offset = {
+        let offset = (imm_4_1 * Env::constant(1 << 1))
+            + (imm_10_5 * Env::constant(1 << 5))
+            + (imm_11 * Env::constant(1 << 11))
+            + (imm_12 * Env::constant(1 << 12));
+        env.sign_extend(&offset, 13)
+    };
+
+    let local_rs1 = env.read_register(&rs1);
+    let local_rs2 = env.read_register(&rs2);
+    let take_branch = match instr {
         SBInstruction::BranchEq => {
-            unimplemented!("BranchEq")
+            // beq: if (x[rs1] == x[rs2]) pc += sext(offset)
+            env.equal(&local_rs1, &local_rs2)
         }
         SBInstruction::BranchNeq => {
-            unimplemented!("BranchNeq")
+            // bne: if (x[rs1] != x[rs2]) pc += sext(offset)
+            Env::constant(1) - env.equal(&local_rs1, &local_rs2)
         }
         SBInstruction::BranchLessThan => {
-            unimplemented!("BranchLessThan")
+            // blt: if (x[rs1] <s x[rs2]) pc += sext(offset)
+            let pos = env.alloc_scratch();
+            unsafe { env.test_less_than_signed(&local_rs1, &local_rs2, pos) }
         }
         SBInstruction::BranchGreaterThanEqual => {
-            unimplemented!("BranchGreaterThanEqual")
+            // bge: if (x[rs1] >=s x[rs2]) pc += sext(offset)
+            let pos = env.alloc_scratch();
+            Env::constant(1) - unsafe { env.test_less_than_signed(&local_rs1, &local_rs2, pos) }
         }
         SBInstruction::BranchLessThanUnsigned => {
-            unimplemented!("BranchLessThanUnsigned")
+            // bltu: if (x[rs1] <u x[rs2]) pc += sext(offset)
+            let pos = env.alloc_scratch();
+            unsafe { env.test_less_than(&local_rs1, &local_rs2, pos) }
         }
         SBInstruction::BranchGreaterThanEqualUnsigned => {
-            unimplemented!("BranchGreaterThanEqualUnsigned")
+            // bgeu: if (x[rs1] >=u x[rs2]) pc += sext(offset)
+            let pos = env.alloc_scratch();
+            Env::constant(1) - unsafe { env.test_less_than(&local_rs1, &local_rs2, pos) }
         }
     };
+
+    let branch_addr = add_wrapping(env, &instruction_pointer, &offset);
+    let new_instruction_pointer = {
+        let pos = env.alloc_scratch();
+        env.copy(
+            &(take_branch.clone() * branch_addr
+                + (Env::constant(1) - take_branch) * next_instruction_pointer),
+            pos,
+        )
+    };
+    env.set_instruction_pointer(new_instruction_pointer.clone());
+    env.set_next_instruction_pointer(new_instruction_pointer + Env::constant(4));
 }
 
 /// Interpret an U-type instruction.
@@ -2056,7 +2806,7 @@ pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
 
     let instruction = {
         let v0 = env.read_memory(&instruction_pointer);
@@ -2095,14 +2845,21 @@ pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction)
             - (imm.clone() * Env::constant(1 << 12)), // imm at bits 12-31
     );
 
-    match instr {
+    let upper_imm = imm * Env::constant(1 << 12);
+    let local_rd = match instr {
         UInstruction::LoadUpperImmediate => {
-            unimplemented!("LoadUpperImmediate")
+            // lui: x[rd] = sext(immediate[31:12] << 12)
+            upper_imm
         }
         UInstruction::AddUpperImmediate => {
-            unimplemented!("AddUpperImmediate")
+            // auipc: x[rd] = pc + sext(immediate[31:12] << 12)
+            add_wrapping(env, &instruction_pointer, &upper_imm)
         }
     };
+    env.write_register(&rd, local_rd);
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
 }
 
 /// Interpret an UJ-type instruction.
@@ -2115,7 +2872,7 @@ pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction)
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_ujtype<Env: InterpreterEnv>(env: &mut Env, instr: UJInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
 
     let instruction = {
         let v0 = env.read_memory(&instruction_pointer);
@@ -2140,16 +2897,45 @@ pub fn interpret_ujtype<Env: InterpreterEnv>(env: &mut Env, instr: UJInstruction
     };
     env.range_check8(&rd, 5);
 
-    // FIXME: trickier
-    let _imm = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 32, 12, pos) }
-    };
+    // The offset is scattered in the instruction as
+    // imm[20|10:1|11|19:12] at bits 12-31.
+    let imm_19_12 = instruction_bits(env, &instruction, 20, 12);
+    env.range_check8(&imm_19_12, 8);
 
-    // FIXME: check correctness of decomposition
-    match instr {
-        UJInstruction::JumpAndLink => {
-            unimplemented!("JumpAndLink")
+    let imm_11 = instruction_bits(env, &instruction, 21, 20);
+    env.range_check8(&imm_11, 1);
+
+    let imm_10_1 = instruction_bits(env, &instruction, 31, 21);
+    env.range_check16(&imm_10_1, 10);
+
+    let imm_20 = instruction_bits(env, &instruction, 32, 31);
+    env.range_check8(&imm_20, 1);
+
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+            - (imm_19_12.clone() * Env::constant(1 << 12)) // imm[19:12] at bits 12-19
+            - (imm_11.clone() * Env::constant(1 << 20))   // imm[11] at bit 20
+            - (imm_10_1.clone() * Env::constant(1 << 21)) // imm[10:1] at bits 21-30
+            - (imm_20.clone() * Env::constant(1 << 31)), // imm[20] at bit 31
+    );
+
+    match instr {
+        UJInstruction::JumpAndLink => {
+            // jal: x[rd] = pc+4; pc += sext(offset)
+            let offset = {
+                let offset = (imm_10_1 * Env::constant(1 << 1))
+                    + (imm_11 * Env::constant(1 << 11))
+                    + (imm_19_12 * Env::constant(1 << 12))
+                    + (imm_20 * Env::constant(1 << 20));
+                env.sign_extend(&offset, 21)
+            };
+            let new_addr = add_wrapping(env, &instruction_pointer, &offset);
+            env.write_register(&rd, next_instruction_pointer);
+            env.set_instruction_pointer(new_addr.clone());
+            env.set_next_instruction_pointer(new_addr + Env::constant(4));
         }
     };
 }
@@ -2251,7 +3037,7 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         MInstruction::Mulh => {
             // x[rd] = (signed(x[rs1]) * signed(x[rs2])) >> 32
@@ -2265,7 +3051,7 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         MInstruction::Mulhsu => {
             // x[rd] = (signed(x[rs1]) * x[rs2]) >> 32
@@ -2279,7 +3065,7 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         MInstruction::Mulhu => {
             // x[rd] = (x[rs1] * x[rs2]) >> 32
@@ -2293,7 +3079,7 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         MInstruction::Div => {
             // x[rd] = signed(x[rs1]) / signed(x[rs2])
@@ -2307,7 +3093,7 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         MInstruction::Divu => {
             // x[rd] = x[rs1] / x[rs2]
@@ -2321,7 +3107,7 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         MInstruction::Rem => {
             // x[rd] = signed(x[rs1]) % signed(x[rs2])
@@ -2335,7 +3121,7 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
         MInstruction::Remu => {
             // x[rd] = x[rs1] % x[rs2]
@@ -2349,7 +3135,977 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
         }
     }
 }
+
+/// Returns the bits `[lowest_bit, highest_bit)` of `instruction`.
+fn instruction_bits<Env: InterpreterEnv>(
+    env: &mut Env,
+    instruction: &Env::Variable,
+    highest_bit: u32,
+    lowest_bit: u32,
+) -> Env::Variable {
+    let pos = env.alloc_scratch();
+    unsafe { env.bitmask(instruction, highest_bit, lowest_bit, pos) }
+}
+
+/// Returns the lower `log2(XLEN)` bits of `x`, i.e. the amount by which the
+/// shift instructions shift their operand.
+fn shift_amount<Env: InterpreterEnv>(env: &mut Env, x: &Env::Variable) -> Env::Variable {
+    let bits = Env::XLEN.ilog2();
+    let shamt = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(x, bits, 0, pos) }
+    };
+    env.range_check8(&shamt, bits);
+    shamt
+}
+
+/// Returns `x + y`, ignoring the arithmetic overflow.
+fn add_wrapping<Env: InterpreterEnv>(
+    env: &mut Env,
+    x: &Env::Variable,
+    y: &Env::Variable,
+) -> Env::Variable {
+    let res_scratch = env.alloc_scratch();
+    let overflow_scratch = env.alloc_scratch();
+    let (res, _overflow) = unsafe { env.add_witness(x, y, res_scratch, overflow_scratch) };
+    res
+}
+
+/// Returns the sign-extended 6-bit immediate `imm[5] | imm[4:0]` of the
+/// CI-type compressed instructions, encoded at the bits `12 | 6-2`.
+fn compressed_immediate<Env: InterpreterEnv>(
+    env: &mut Env,
+    instruction: &Env::Variable,
+) -> Env::Variable {
+    let imm_4_0 = instruction_bits(env, instruction, 7, 2);
+    let imm_5 = instruction_bits(env, instruction, 13, 12);
+    env.sign_extend(&(imm_4_0 + imm_5 * Env::constant(1 << 5)), 6)
+}
+
+/// Returns the sign-extended offset `offset[11|4|9:8|10|6|7|3:1|5]` of the
+/// CJ-type compressed instructions, encoded at the bits 12-2.
+fn compressed_jump_offset<Env: InterpreterEnv>(
+    env: &mut Env,
+    instruction: &Env::Variable,
+) -> Env::Variable {
+    let offset_5 = instruction_bits(env, instruction, 3, 2);
+    let offset_3_1 = instruction_bits(env, instruction, 6, 3);
+    let offset_7 = instruction_bits(env, instruction, 7, 6);
+    let offset_6 = instruction_bits(env, instruction, 8, 7);
+    let offset_10 = instruction_bits(env, instruction, 9, 8);
+    let offset_9_8 = instruction_bits(env, instruction, 11, 9);
+    let offset_4 = instruction_bits(env, instruction, 12, 11);
+    let offset_11 = instruction_bits(env, instruction, 13, 12);
+    let offset = (offset_3_1 * Env::constant(1 << 1))
+        + (offset_4 * Env::constant(1 << 4))
+        + (offset_5 * Env::constant(1 << 5))
+        + (offset_6 * Env::constant(1 << 6))
+        + (offset_7 * Env::constant(1 << 7))
+        + (offset_9_8 * Env::constant(1 << 8))
+        + (offset_10 * Env::constant(1 << 10))
+        + (offset_11 * Env::constant(1 << 11));
+    env.sign_extend(&offset, 12)
+}
+
+/// Returns the zero-extended offset `uimm[5:3] | uimm[2|6]` of the `c.lw` and
+/// `c.sw` instructions, encoded at the bits `12-10 | 6-5`.
+fn compressed_word_offset<Env: InterpreterEnv>(
+    env: &mut Env,
+    instruction: &Env::Variable,
+) -> Env::Variable {
+    let uimm_6 = instruction_bits(env, instruction, 6, 5);
+    let uimm_2 = instruction_bits(env, instruction, 7, 6);
+    let uimm_5_3 = instruction_bits(env, instruction, 13, 10);
+    (uimm_2 * Env::constant(1 << 2))
+        + (uimm_5_3 * Env::constant(1 << 3))
+        + (uimm_6 * Env::constant(1 << 6))
+}
+
+/// Interpret a C-type instruction, i.e. a 16-bit instruction of the
+/// compressed extension.
+/// The fields of a C-type instruction depend on the instruction, the common
+/// parts being:
+/// ```text
+/// | 15        13 | 12                                     2 | 1      0 |
+/// |    funct3    |                operands                  |  opcode  |
+/// ```
+/// The instruction is executed as the 32-bit instruction it expands to, the
+/// address of the next instruction being `pc + 2`.
+/// Following the documentation found
+/// [here](https://msyksphinz-self.github.io/riscv-isadoc/html/rvc.html)
+pub fn interpret_ctype<Env: InterpreterEnv>(env: &mut Env, instr: CInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = instruction_pointer.clone() + Env::constant(2);
+
+    let instruction = {
+        let v0 = env.read_memory(&instruction_pointer);
+        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
+        (v1 * Env::constant(1 << 8)) + v0
+    };
+
+    let opcode = instruction_bits(env, &instruction, 2, 0);
+    env.range_check8(&opcode, 2);
+
+    let operands = instruction_bits(env, &instruction, 13, 2);
+    env.range_check16(&operands, 11);
+
+    let funct3 = instruction_bits(env, &instruction, 16, 13);
+    env.range_check8(&funct3, 3);
+
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction.clone()
+            - (opcode * Env::constant(1 << 0))    // opcode at bits 0-1
+            - (operands * Env::constant(1 << 2))  // operands at bits 2-12
+            - (funct3 * Env::constant(1 << 13)), // funct3 at bits 13-15
+    );
+
+    // FIXME: constrain the decomposition of the operands of each instruction
+    let stack_pointer = Env::constant(2);
+    let return_address = Env::constant(1);
+    let new_instruction_pointer = match instr {
+        CInstruction::AddImmediate4SPN => {
+            // c.addi4spn: x[8+rd'] = x[2] + nzuimm
+            let rd = instruction_bits(env, &instruction, 5, 2) + Env::constant(8);
+            let nzuimm = {
+                let uimm_3 = instruction_bits(env, &instruction, 6, 5);
+                let uimm_2 = instruction_bits(env, &instruction, 7, 6);
+                let uimm_9_6 = instruction_bits(env, &instruction, 11, 7);
+                let uimm_5_4 = instruction_bits(env, &instruction, 13, 11);
+                (uimm_2 * Env::constant(1 << 2))
+                    + (uimm_3 * Env::constant(1 << 3))
+                    + (uimm_5_4 * Env::constant(1 << 4))
+                    + (uimm_9_6 * Env::constant(1 << 6))
+            };
+            let local_sp = env.read_register(&stack_pointer);
+            let local_rd = add_wrapping(env, &local_sp, &nzuimm);
+            env.write_register(&rd, local_rd);
+            next_instruction_pointer
+        }
+        CInstruction::LoadWord => {
+            // c.lw: x[8+rd'] = sext(M[x[8+rs1'] + uimm][31:0])
+            let rd = instruction_bits(env, &instruction, 5, 2) + Env::constant(8);
+            let rs1 = instruction_bits(env, &instruction, 10, 7) + Env::constant(8);
+            let offset = compressed_word_offset(env, &instruction);
+            let base = env.read_register(&rs1);
+            let address = add_wrapping(env, &base, &offset);
+            let value = env.read_memory_le(&address, 4);
+            env.write_register(&rd, value);
+            next_instruction_pointer
+        }
+        CInstruction::StoreWord => {
+            // c.sw: M[x[8+rs1'] + uimm][31:0] = x[8+rs2']
+            let rs2 = instruction_bits(env, &instruction, 5, 2) + Env::constant(8);
+            let rs1 = instruction_bits(env, &instruction, 10, 7) + Env::constant(8);
+            let offset = compressed_word_offset(env, &instruction);
+            let base = env.read_register(&rs1);
+            let value = env.read_register(&rs2);
+            let address = add_wrapping(env, &base, &offset);
+            env.write_memory_le(&address, &value, 4);
+            next_instruction_pointer
+        }
+        CInstruction::AddImmediate => {
+            // c.addi: x[rd] = x[rd] + sext(nzimm)
+            let rd = instruction_bits(env, &instruction, 12, 7);
+            let imm = compressed_immediate(env, &instruction);
+            let local_rd = env.read_register(&rd);
+            let local_rd = add_wrapping(env, &local_rd, &imm);
+            env.write_register(&rd, local_rd);
+            next_instruction_pointer
+        }
+        CInstruction::JumpAndLink => {
+            // c.jal: x[1] = pc+2; pc += sext(offset)
+            let offset = compressed_jump_offset(env, &instruction);
+            let new_addr = add_wrapping(env, &instruction_pointer, &offset);
+            env.write_register(&return_address, next_instruction_pointer);
+            new_addr
+        }
+        CInstruction::LoadImmediate => {
+            // c.li: x[rd] = sext(imm)
+            let rd = instruction_bits(env, &instruction, 12, 7);
+            let imm = compressed_immediate(env, &instruction);
+            env.write_register(&rd, imm);
+            next_instruction_pointer
+        }
+        CInstruction::AddImmediate16SP => {
+            // c.addi16sp: x[2] = x[2] + sext(nzimm)
+            let nzimm = {
+                let nzimm_5 = instruction_bits(env, &instruction, 3, 2);
+                let nzimm_8_7 = instruction_bits(env, &instruction, 5, 3);
+                let nzimm_6 = instruction_bits(env, &instruction, 6, 5);
+                let nzimm_4 = instruction_bits(env, &instruction, 7, 6);
+                let nzimm_9 = instruction_bits(env, &instruction, 13, 12);
+                (nzimm_4 * Env::constant(1 << 4))
+                    + (nzimm_5 * Env::constant(1 << 5))
+                    + (nzimm_6 * Env::constant(1 << 6))
+                    + (nzimm_8_7 * Env::constant(1 << 7))
+                    + (nzimm_9 * Env::constant(1 << 9))
+            };
+            let nzimm = env.sign_extend(&nzimm, 10);
+            let local_sp = env.read_register(&stack_pointer);
+            let local_sp = add_wrapping(env, &local_sp, &nzimm);
+            env.write_register(&stack_pointer, local_sp);
+            next_instruction_pointer
+        }
+        CInstruction::LoadUpperImmediate => {
+            // c.lui: x[rd] = sext(nzimm[17:12] << 12)
+            let rd = instruction_bits(env, &instruction, 12, 7);
+            let nzimm_16_12 = instruction_bits(env, &instruction, 7, 2);
+            let nzimm_17 = instruction_bits(env, &instruction, 13, 12);
+            let nzimm =
+                (nzimm_16_12 * Env::constant(1 << 12)) + (nzimm_17 * Env::constant(1 << 17));
+            let local_rd = env.sign_extend(&nzimm, 18);
+            env.write_register(&rd, local_rd);
+            next_instruction_pointer
+        }
+        CInstruction::ShiftRightLogicalImmediate | CInstruction::ShiftRightArithmeticImmediate => {
+            // c.srli: x[8+rd'] = x[8+rd'] >>u shamt
+            // c.srai: x[8+rd'] = x[8+rd'] >>s shamt
+            let rd = instruction_bits(env, &instruction, 10, 7) + Env::constant(8);
+            // The bit 12, shamt[5], must be zero for RV32C
+            let shamt = instruction_bits(env, &instruction, 7, 2);
+            let local_rd = env.read_register(&rd);
+            let local_rd = {
+                let pos = env.alloc_scratch();
+                if instr == CInstruction::ShiftRightLogicalImmediate {
+                    unsafe { env.shift_right(&local_rd, &shamt, pos) }
+                } else {
+                    unsafe { env.shift_right_arithmetic(&local_rd, &shamt, pos) }
+                }
+            };
+            env.write_register(&rd, local_rd);
+            next_instruction_pointer
+        }
+        CInstruction::AndImmediate => {
+            // c.andi: x[8+rd'] = x[8+rd'] & sext(imm)
+            let rd = instruction_bits(env, &instruction, 10, 7) + Env::constant(8);
+            let imm = compressed_immediate(env, &instruction);
+            let local_rd = env.read_register(&rd);
+            let local_rd = {
+                let pos = env.alloc_scratch();
+                unsafe { env.and_witness(&local_rd, &imm, pos) }
+            };
+            env.write_register(&rd, local_rd);
+            next_instruction_pointer
+        }
+        CInstruction::Sub | CInstruction::Xor | CInstruction::Or | CInstruction::And => {
+            // c.sub: x[8+rd'] = x[8+rd'] - x[8+rs2']
+            // c.xor: x[8+rd'] = x[8+rd'] ^ x[8+rs2']
+            // c.or:  x[8+rd'] = x[8+rd'] | x[8+rs2']
+            // c.and: x[8+rd'] = x[8+rd'] & x[8+rs2']
+            let rs2 = instruction_bits(env, &instruction, 5, 2) + Env::constant(8);
+            let rd = instruction_bits(env, &instruction, 10, 7) + Env::constant(8);
+            let local_rd = env.read_register(&rd);
+            let local_rs2 = env.read_register(&rs2);
+            let local_rd = match instr {
+                CInstruction::Sub => {
+                    let rd_scratch = env.alloc_scratch();
+                    let underflow_scratch = env.alloc_scratch();
+                    let (local_rd, _underflow) = unsafe {
+                        env.sub_witness(&local_rd, &local_rs2, rd_scratch, underflow_scratch)
+                    };
+                    local_rd
+                }
+                CInstruction::Xor => {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.xor_witness(&local_rd, &local_rs2, pos) }
+                }
+                CInstruction::Or => {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.or_witness(&local_rd, &local_rs2, pos) }
+                }
+                _ => {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.and_witness(&local_rd, &local_rs2, pos) }
+                }
+            };
+            env.write_register(&rd, local_rd);
+            next_instruction_pointer
+        }
+        CInstruction::Jump => {
+            // c.j: pc += sext(offset)
+            let offset = compressed_jump_offset(env, &instruction);
+            add_wrapping(env, &instruction_pointer, &offset)
+        }
+        CInstruction::BranchEqZero | CInstruction::BranchNeqZero => {
+            // c.beqz: if (x[8+rs1'] == 0) pc += sext(offset)
+            // c.bnez: if (x[8+rs1'] != 0) pc += sext(offset)
+            let rs1 = instruction_bits(env, &instruction, 10, 7) + Env::constant(8);
+            let offset = {
+                let offset_5 = instruction_bits(env, &instruction, 3, 2);
+                let offset_2_1 = instruction_bits(env, &instruction, 5, 3);
+                let offset_7_6 = instruction_bits(env, &instruction, 7, 5);
+                let offset_4_3 = instruction_bits(env, &instruction, 12, 10);
+                let offset_8 = instruction_bits(env, &instruction, 13, 12);
+                (offset_2_1 * Env::constant(1 << 1))
+                    + (offset_4_3 * Env::constant(1 << 3))
+                    + (offset_5 * Env::constant(1 << 5))
+                    + (offset_7_6 * Env::constant(1 << 6))
+                    + (offset_8 * Env::constant(1 << 8))
+            };
+            let offset = env.sign_extend(&offset, 9);
+            let local_rs1 = env.read_register(&rs1);
+            let is_zero = env.is_zero(&local_rs1);
+            let take_branch = if instr == CInstruction::BranchEqZero {
+                is_zero
+            } else {
+                Env::constant(1) - is_zero
+            };
+            let branch_addr = add_wrapping(env, &instruction_pointer, &offset);
+            let pos = env.alloc_scratch();
+            env.copy(
+                &(take_branch.clone() * branch_addr
+                    + (Env::constant(1) - take_branch) * next_instruction_pointer),
+                pos,
+            )
+        }
+        CInstruction::ShiftLeftLogicalImmediate => {
+            // c.slli: x[rd] = x[rd] << shamt
+            let rd = instruction_bits(env, &instruction, 12, 7);
+            // The bit 12, shamt[5], must be zero for RV32C
+            let shamt = instruction_bits(env, &instruction, 7, 2);
+            let local_rd = env.read_register(&rd);
+            let local_rd = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_left(&local_rd, &shamt, pos) }
+            };
+            env.write_register(&rd, local_rd);
+            next_instruction_pointer
+        }
+        CInstruction::LoadWordStackPointer => {
+            // c.lwsp: x[rd] = sext(M[x[2] + uimm][31:0])
+            let rd = instruction_bits(env, &instruction, 12, 7);
+            let offset = {
+                let uimm_7_6 = instruction_bits(env, &instruction, 4, 2);
+                let uimm_4_2 = instruction_bits(env, &instruction, 7, 4);
+                let uimm_5 = instruction_bits(env, &instruction, 13, 12);
+                (uimm_4_2 * Env::constant(1 << 2))
+                    + (uimm_5 * Env::constant(1 << 5))
+                    + (uimm_7_6 * Env::constant(1 << 6))
+            };
+            let local_sp = env.read_register(&stack_pointer);
+            let address = add_wrapping(env, &local_sp, &offset);
+            let value = env.read_memory_le(&address, 4);
+            env.write_register(&rd, value);
+            next_instruction_pointer
+        }
+        CInstruction::JumpRegister => {
+            // c.jr: pc = x[rs1]
+            let rs1 = instruction_bits(env, &instruction, 12, 7);
+            env.read_register(&rs1)
+        }
+        CInstruction::Move => {
+            // c.mv: x[rd] = x[rs2]
+            let rs2 = instruction_bits(env, &instruction, 7, 2);
+            let rd = instruction_bits(env, &instruction, 12, 7);
+            let local_rs2 = env.read_register(&rs2);
+            env.write_register(&rd, local_rs2);
+            next_instruction_pointer
+        }
+        CInstruction::Breakpoint => {
+            // c.ebreak: there is no debugging environment, halt the execution
+            env.set_halted(Env::constant(1));
+            next_instruction_pointer
+        }
+        CInstruction::JumpAndLinkRegister => {
+            // c.jalr: t = pc+2; pc = x[rs1]; x[1] = t
+            let rs1 = instruction_bits(env, &instruction, 12, 7);
+            let new_addr = env.read_register(&rs1);
+            env.write_register(&return_address, next_instruction_pointer);
+            new_addr
+        }
+        CInstruction::Add => {
+            // c.add: x[rd] = x[rd] + x[rs2]
+            let rs2 = instruction_bits(env, &instruction, 7, 2);
+            let rd = instruction_bits(env, &instruction, 12, 7);
+            let local_rd = env.read_register(&rd);
+            let local_rs2 = env.read_register(&rs2);
+            let local_rd = add_wrapping(env, &local_rd, &local_rs2);
+            env.write_register(&rd, local_rd);
+            next_instruction_pointer
+        }
+        CInstruction::StoreWordStackPointer => {
+            // c.swsp: M[x[2] + uimm][31:0] = x[rs2]
+            let rs2 = instruction_bits(env, &instruction, 7, 2);
+            let offset = {
+                let uimm_7_6 = instruction_bits(env, &instruction, 9, 7);
+                let uimm_5_2 = instruction_bits(env, &instruction, 13, 9);
+                (uimm_5_2 * Env::constant(1 << 2)) + (uimm_7_6 * Env::constant(1 << 6))
+            };
+            let local_sp = env.read_register(&stack_pointer);
+            let value = env.read_register(&rs2);
+            let address = add_wrapping(env, &local_sp, &offset);
+            env.write_memory_le(&address, &value, 4);
+            next_instruction_pointer
+        }
+    };
+    env.set_instruction_pointer(new_instruction_pointer.clone());
+    env.set_next_instruction_pointer(new_instruction_pointer + Env::constant(4));
+}
+
+/// Interpret an A-type instruction.
+/// The encoding of an A-type instruction is as follows:
+/// ```text
+/// | 31    27 | 26 | 25 | 24    20 | 19    15 | 14        12 | 11    7 | 6      0 |
+/// |  funct5  | aq | rl |    rs2   |    rs1   |    funct3    |    rd   |  opcode  |
+/// ```
+/// Following the documentation found
+/// [here](https://msyksphinz-self.github.io/riscv-isadoc/html/rva.html)
+pub fn interpret_atype<Env: InterpreterEnv>(env: &mut Env, instr: AInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = {
+        let v0 = env.read_memory(&instruction_pointer);
+        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
+        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
+        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
+        (v3 * Env::constant(1 << 24))
+            + (v2 * Env::constant(1 << 16))
+            + (v1 * Env::constant(1 << 8))
+            + v0
+    };
+
+    let opcode = instruction_bits(env, &instruction, 7, 0);
+    env.range_check8(&opcode, 7);
+
+    let rd = instruction_bits(env, &instruction, 12, 7);
+    env.range_check8(&rd, 5);
+
+    let funct3 = instruction_bits(env, &instruction, 15, 12);
+    env.range_check8(&funct3, 3);
+
+    let rs1 = instruction_bits(env, &instruction, 20, 15);
+    env.range_check8(&rs1, 5);
+
+    let rs2 = instruction_bits(env, &instruction, 25, 20);
+    env.range_check8(&rs2, 5);
+
+    // The ordering bits are ignored, there is only one hart
+    let aq_rl = instruction_bits(env, &instruction, 27, 25);
+    env.range_check8(&aq_rl, 2);
+
+    let funct5 = instruction_bits(env, &instruction, 32, 27);
+    env.range_check8(&funct5, 5);
+
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+            - (funct3 * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+            - (aq_rl * Env::constant(1 << 25))    // aq and rl at bits 25-26
+            - (funct5 * Env::constant(1 << 27)), // funct5 at bits 27-31
+    );
+
+    // FIXME: check that the address is aligned on 4 bytes
+    let address = env.read_register(&rs1);
+    match instr {
+        AInstruction::LoadReserved => {
+            // lr.w: x[rd] = LoadReserved32(M[x[rs1]])
+            let value = env.read_memory_le(&address, 4);
+            env.write_register(&rd, value);
+        }
+        AInstruction::StoreConditional => {
+            // sc.w: x[rd] = StoreConditional32(M[x[rs1]], x[rs2])
+            // The reservation is always valid, the store succeeds.
+            let local_rs2 = env.read_register(&rs2);
+            env.write_memory_le(&address, &local_rs2, 4);
+            env.write_register(&rd, Env::constant(0));
+        }
+        _ => {
+            // amo<op>.w: x[rd] = AMO32(M[x[rs1]] <op> x[rs2])
+            let local_rs2 = env.read_register(&rs2);
+            let value = env.read_memory_le(&address, 4);
+            let new_value = match instr {
+                AInstruction::AmoSwap => local_rs2,
+                AInstruction::AmoAdd => add_wrapping(env, &value, &local_rs2),
+                AInstruction::AmoXor => {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.xor_witness(&value, &local_rs2, pos) }
+                }
+                AInstruction::AmoAnd => {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.and_witness(&value, &local_rs2, pos) }
+                }
+                AInstruction::AmoOr => {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.or_witness(&value, &local_rs2, pos) }
+                }
+                AInstruction::AmoMin
+                | AInstruction::AmoMax
+                | AInstruction::AmoMinUnsigned
+                | AInstruction::AmoMaxUnsigned => {
+                    let value_is_less = {
+                        let pos = env.alloc_scratch();
+                        if matches!(instr, AInstruction::AmoMin | AInstruction::AmoMax) {
+                            unsafe { env.test_less_than_signed(&value, &local_rs2, pos) }
+                        } else {
+                            unsafe { env.test_less_than(&value, &local_rs2, pos) }
+                        }
+                    };
+                    let (lower, greater) = (
+                        value_is_less.clone() * value.clone()
+                            + (Env::constant(1) - value_is_less.clone()) * local_rs2.clone(),
+                        value_is_less.clone() * local_rs2
+                            + (Env::constant(1) - value_is_less) * value.clone(),
+                    );
+                    let pos = env.alloc_scratch();
+                    if matches!(instr, AInstruction::AmoMin | AInstruction::AmoMinUnsigned) {
+                        env.copy(&lower, pos)
+                    } else {
+                        env.copy(&greater, pos)
+                    }
+                }
+                AInstruction::LoadReserved | AInstruction::StoreConditional => unreachable!(),
+            };
+            env.write_memory_le(&address, &new_value, 4);
+            env.write_register(&rd, value);
+        }
+    };
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
+}
+
+/// Interpret an RW-type instruction, i.e. a R-type instruction of RV64I
+/// operating on the lower 32 bits of the registers.
+/// The encoding of an RW-type instruction is the one of an R-type
+/// instruction, with the opcode `0111011`:
+/// ```text
+/// | 31               25 | 24      20 | 19     15 | 14        12 | 11    7 | 6      0 |
+/// | funct5 & funct 2    |     rs2    |    rs1    |    funct3    |    rd   |  opcode  |
+/// ```
+pub fn interpret_rwtype<Env: InterpreterEnv>(env: &mut Env, instr: RWInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = {
+        let v0 = env.read_memory(&instruction_pointer);
+        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
+        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
+        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
+        (v3 * Env::constant(1 << 24))
+            + (v2 * Env::constant(1 << 16))
+            + (v1 * Env::constant(1 << 8))
+            + v0
+    };
+
+    let opcode = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 7, 0, pos) }
+    };
+    env.range_check8(&opcode, 7);
+
+    let rd = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 12, 7, pos) }
+    };
+    env.range_check8(&rd, 5);
+
+    let funct3 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 15, 12, pos) }
+    };
+    env.range_check8(&funct3, 3);
+
+    let rs1 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 20, 15, pos) }
+    };
+    env.range_check8(&rs1, 5);
+
+    let rs2 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 25, 20, pos) }
+    };
+    env.range_check8(&rs2, 5);
+
+    let funct2 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 27, 25, pos) }
+    };
+    env.range_check8(&funct2, 2);
+
+    let funct5 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 32, 27, pos) }
+    };
+    env.range_check8(&funct5, 5);
+
+    // Check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+            - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+            - (funct2.clone() * Env::constant(1 << 25))   // funct2 at bits 25-26
+            - (funct5.clone() * Env::constant(1 << 27)), // funct5 at bits 27-31
+    );
+
+    let local_rs1 = env.read_register(&rs1);
+    let local_rs2 = env.read_register(&rs2);
+    let local_rd = match instr {
+        RWInstruction::AddWord => {
+            // addw: x[rd] = sext32(x[rs1] + x[rs2])
+            let res = unsafe {
+                let rd_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (res, _overflow) =
+                    env.add_witness(&local_rs1, &local_rs2, rd_scratch, overflow_scratch);
+                res
+            };
+            env.sign_extend_word(&res)
+        }
+        RWInstruction::SubWord => {
+            // subw: x[rd] = sext32(x[rs1] - x[rs2])
+            let res = unsafe {
+                let rd_scratch = env.alloc_scratch();
+                let underflow_scratch = env.alloc_scratch();
+                let (res, _underflow) =
+                    env.sub_witness(&local_rs1, &local_rs2, rd_scratch, underflow_scratch);
+                res
+            };
+            env.sign_extend_word(&res)
+        }
+        RWInstruction::ShiftLeftLogicalWord
+        | RWInstruction::ShiftRightLogicalWord
+        | RWInstruction::ShiftRightArithmeticWord => {
+            let shamt = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs2, 5, 0, pos) }
+            };
+            env.range_check8(&shamt, 5);
+            shift_word(env, instr.into(), &local_rs1, &shamt)
+        }
+    };
+    env.write_register(&rd, local_rd);
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
+}
+
+/// Interpret an IW-type instruction, i.e. a I-type instruction of RV64I
+/// operating on the lower 32 bits of the registers.
+/// The encoding of an IW-type instruction is the one of an I-type
+/// instruction, with the opcode `0011011`:
+/// ```text
+/// | 31     20 | 19     15 | 14    12 | 11    7 | 6      0 |
+/// | immediate |    rs1    |  funct3  |    rd   |  opcode  |
+/// ```
+pub fn interpret_iwtype<Env: InterpreterEnv>(env: &mut Env, instr: IWInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = {
+        let v0 = env.read_memory(&instruction_pointer);
+        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
+        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
+        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
+        (v3 * Env::constant(1 << 24))
+            + (v2 * Env::constant(1 << 16))
+            + (v1 * Env::constant(1 << 8))
+            + v0
+    };
+
+    let opcode = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 7, 0, pos) }
+    };
+    env.range_check8(&opcode, 7);
+
+    let rd = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 12, 7, pos) }
+    };
+    env.range_check8(&rd, 5);
+
+    let funct3 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 15, 12, pos) }
+    };
+    env.range_check8(&funct3, 3);
+
+    let rs1 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 20, 15, pos) }
+    };
+    env.range_check8(&rs1, 5);
+
+    let imm = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 32, 20, pos) }
+    };
+    env.range_check16(&imm, 12);
+
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+            - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (imm.clone() * Env::constant(1 << 20)), // imm at bits 20-31
+    );
+
+    let local_rs1 = env.read_register(&rs1);
+    let local_rd = match instr {
+        IWInstruction::AddImmediateWord => {
+            // addiw: x[rd] = sext32(x[rs1] + sext(immediate))
+            let local_imm = env.sign_extend(&imm, 12);
+            let res = unsafe {
+                let rd_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (res, _overflow) =
+                    env.add_witness(&local_rs1, &local_imm, rd_scratch, overflow_scratch);
+                res
+            };
+            env.sign_extend_word(&res)
+        }
+        IWInstruction::ShiftLeftLogicalImmediateWord
+        | IWInstruction::ShiftRightLogicalImmediateWord
+        | IWInstruction::ShiftRightArithmeticImmediateWord => {
+            let shamt = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&imm, 5, 0, pos) }
+            };
+            env.range_check8(&shamt, 5);
+            shift_word(env, instr.into(), &local_rs1, &shamt)
+        }
+    };
+    env.write_register(&rd, local_rd);
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
+}
+
+/// The kind of shifts on words, shared by the RW-type and IW-type
+/// instructions.
+enum ShiftWord {
+    Left,
+    RightLogical,
+    RightArithmetic,
+}
+
+impl From<RWInstruction> for ShiftWord {
+    fn from(instr: RWInstruction) -> Self {
+        match instr {
+            RWInstruction::ShiftLeftLogicalWord => ShiftWord::Left,
+            RWInstruction::ShiftRightLogicalWord => ShiftWord::RightLogical,
+            RWInstruction::ShiftRightArithmeticWord => ShiftWord::RightArithmetic,
+            _ => panic!("{instr} is not a shift"),
+        }
+    }
+}
+
+impl From<IWInstruction> for ShiftWord {
+    fn from(instr: IWInstruction) -> Self {
+        match instr {
+            IWInstruction::ShiftLeftLogicalImmediateWord => ShiftWord::Left,
+            IWInstruction::ShiftRightLogicalImmediateWord => ShiftWord::RightLogical,
+            IWInstruction::ShiftRightArithmeticImmediateWord => ShiftWord::RightArithmetic,
+            _ => panic!("{instr} is not a shift"),
+        }
+    }
+}
+
+/// Shifts the lower 32 bits of `x` by `shamt` (at most 31), and returns the
+/// result sign-extended to 64 bits.
+fn shift_word<Env: InterpreterEnv>(
+    env: &mut Env,
+    shift: ShiftWord,
+    x: &Env::Variable,
+    shamt: &Env::Variable,
+) -> Env::Variable {
+    match shift {
+        ShiftWord::Left => {
+            // x[rd] = sext32(x[rs1] << shamt)
+            let word = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(x, 32, 0, pos) }
+            };
+            let res = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_left(&word, shamt, pos) }
+            };
+            env.sign_extend_word(&res)
+        }
+        ShiftWord::RightLogical => {
+            // x[rd] = sext32(x[rs1][31:0] >>u shamt)
+            let word = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(x, 32, 0, pos) }
+            };
+            let res = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_right(&word, shamt, pos) }
+            };
+            env.sign_extend_word(&res)
+        }
+        ShiftWord::RightArithmetic => {
+            // x[rd] = signed(sext32(x[rs1])) >>s shamt, which is already
+            // sign-extended from the lower 32 bits
+            let word = env.sign_extend_word(x);
+            let pos = env.alloc_scratch();
+            unsafe { env.shift_right_arithmetic(&word, shamt, pos) }
+        }
+    }
+}
+
+/// Interpret an MW-type instruction, i.e. a M-type instruction of RV64M
+/// operating on the lower 32 bits of the registers.
+/// The encoding of an MW-type instruction is the one of an M-type
+/// instruction, with the opcode `0111011`:
+/// ```text
+/// | 31     27 | 26    25 | 24     20 | 19     15 | 14        12 | 11    7 | 6      0 |
+/// |   00000   |    01    |    rs2    |    rs1    |    funct3    |    rd   |  opcode  |
+/// ```
+pub fn interpret_mwtype<Env: InterpreterEnv>(env: &mut Env, instr: MWInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = {
+        let v0 = env.read_memory(&instruction_pointer);
+        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
+        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
+        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
+        (v3 * Env::constant(1 << 24))
+            + (v2 * Env::constant(1 << 16))
+            + (v1 * Env::constant(1 << 8))
+            + v0
+    };
+
+    let opcode = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 7, 0, pos) }
+    };
+    env.range_check8(&opcode, 7);
+
+    let rd = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 12, 7, pos) }
+    };
+    env.range_check8(&rd, 5);
+
+    let funct3 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 15, 12, pos) }
+    };
+    env.range_check8(&funct3, 3);
+
+    let rs1 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 20, 15, pos) }
+    };
+    env.range_check8(&rs1, 5);
+
+    let rs2 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 25, 20, pos) }
+    };
+    env.range_check8(&rs2, 5);
+
+    let funct2 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 27, 25, pos) }
+    };
+    env.range_check8(&funct2, 2);
+
+    let funct5 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 32, 27, pos) }
+    };
+    env.range_check8(&funct5, 5);
+
+    // Check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+            - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+            - (funct2.clone() * Env::constant(1 << 25))   // funct2 at bits 25-26
+            - (funct5.clone() * Env::constant(1 << 27)), // funct5 at bits 27-31
+    );
+
+    let local_rs1 = env.read_register(&rs1);
+    let local_rs2 = env.read_register(&rs2);
+    // FIXME: constrain
+    let local_rd = match instr {
+        MWInstruction::MulWord => {
+            // mulw: x[rd] = sext32(x[rs1] * x[rs2])
+            let word1 = env.sign_extend_word(&local_rs1);
+            let word2 = env.sign_extend_word(&local_rs2);
+            let res = {
+                let pos = env.alloc_scratch();
+                unsafe { env.mul_lo_signed(&word1, &word2, pos) }
+            };
+            env.sign_extend_word(&res)
+        }
+        MWInstruction::DivWord => {
+            // divw: x[rd] = sext32(signed(x[rs1][31:0]) /s signed(x[rs2][31:0]))
+            let word1 = env.sign_extend_word(&local_rs1);
+            let word2 = env.sign_extend_word(&local_rs2);
+            let res = {
+                let pos = env.alloc_scratch();
+                unsafe { env.div_signed(&word1, &word2, pos) }
+            };
+            env.sign_extend_word(&res)
+        }
+        MWInstruction::DivUnsignedWord => {
+            // divuw: x[rd] = sext32(x[rs1][31:0] /u x[rs2][31:0])
+            let word1 = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs1, 32, 0, pos) }
+            };
+            let word2 = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs2, 32, 0, pos) }
+            };
+            let res = {
+                let pos = env.alloc_scratch();
+                unsafe { env.div(&word1, &word2, pos) }
+            };
+            env.sign_extend_word(&res)
+        }
+        MWInstruction::RemWord => {
+            // remw: x[rd] = sext32(signed(x[rs1][31:0]) %s signed(x[rs2][31:0]))
+            let word1 = env.sign_extend_word(&local_rs1);
+            let word2 = env.sign_extend_word(&local_rs2);
+            let res = {
+                let pos = env.alloc_scratch();
+                unsafe { env.mod_signed(&word1, &word2, pos) }
+            };
+            env.sign_extend_word(&res)
+        }
+        MWInstruction::RemUnsignedWord => {
+            // remuw: x[rd] = sext32(x[rs1][31:0] %u x[rs2][31:0])
+            let word1 = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs1, 32, 0, pos) }
+            };
+            let word2 = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs2, 32, 0, pos) }
+            };
+            let res = {
+                let pos = env.alloc_scratch();
+                unsafe { env.mod_unsigned(&word1, &word2, pos) }
+            };
+            env.sign_extend_word(&res)
+        }
+    };
+    env.write_register(&rd, local_rd);
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4));
+}
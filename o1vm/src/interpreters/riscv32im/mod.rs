@@ -3,12 +3,12 @@
 // implemented. Using a small value for now.
 pub const SCRATCH_SIZE: usize = 80;
 
-/// Number of instructions in the ISA
-pub const INSTRUCTION_SET_SIZE: usize = 48;
+/// Number of instructions in the ISA, shared by RV32IM and RV64IM
+pub const INSTRUCTION_SET_SIZE: usize = 102;
 
-pub const PAGE_ADDRESS_SIZE: u32 = 12;
-pub const PAGE_SIZE: u32 = 1 << PAGE_ADDRESS_SIZE;
-pub const PAGE_ADDRESS_MASK: u32 = PAGE_SIZE - 1;
+pub const PAGE_ADDRESS_SIZE: u64 = 12;
+pub const PAGE_SIZE: u64 = 1 << PAGE_ADDRESS_SIZE;
+pub const PAGE_ADDRESS_MASK: u64 = PAGE_SIZE - 1;
 
 /// List all columns used by the interpreter
 pub mod column;
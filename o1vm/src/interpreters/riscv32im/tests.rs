@@ -2,14 +2,15 @@ use super::{registers::Registers, witness::Env, INSTRUCTION_SET_SIZE, PAGE_SIZE,
 use crate::interpreters::riscv32im::{
     constraints,
     interpreter::{
-        IInstruction, Instruction, InterpreterEnv, MInstruction, RInstruction, SBInstruction,
-        SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+        interpret_instruction, AInstruction, CInstruction, IInstruction, IWInstruction,
+        Instruction, InterpreterEnv, MInstruction, MWInstruction, RInstruction, RWInstruction,
+        SBInstruction, SInstruction, SyscallInstruction, UInstruction, UJInstruction,
     },
 };
 use ark_ff::Zero;
 use mina_curves::pasta::Fp;
 use rand::{CryptoRng, Rng, RngCore};
-use strum::EnumCount;
+use strum::{EnumCount, IntoEnumIterator};
 
 // Sanity check that we have as many selector as we have instructions
 #[test]
@@ -31,7 +32,13 @@ fn test_regression_selectors_for_instructions() {
             + UJInstruction::COUNT
             + SyscallInstruction::COUNT
             + MInstruction::COUNT
+            + CInstruction::COUNT
+            + AInstruction::COUNT
+            + RWInstruction::COUNT
+            + IWInstruction::COUNT
+            + MWInstruction::COUNT
     );
+    assert_eq!(constraints.len() - 1, INSTRUCTION_SET_SIZE);
     // All instructions are degree 1 or 2.
     constraints
         .iter()
@@ -447,3 +454,366 @@ pub fn test_witness_bitmask_bounds() {
         assert_eq!(output, exp_output);
     }
 }
+
+/// Writes the instruction at address 0, and returns the decoded instruction.
+fn load_instruction(env: &mut Env<Fp>, instruction: &[u8]) -> Instruction {
+    env.memory[0].1[0..instruction.len()].copy_from_slice(instruction);
+    env.registers.current_instruction_pointer = 0;
+    env.registers.next_instruction_pointer = 4;
+    env.decode_instruction().0
+}
+
+fn encode_atype(funct5: u32, rd: u32, rs1: u32, rs2: u32) -> [u8; 4] {
+    let instruction = 0b0101111 | (rd << 7) | (0b010 << 12) | (rs1 << 15) | (rs2 << 20);
+    (instruction | (funct5 << 27)).to_le_bytes()
+}
+
+#[test]
+pub fn test_instruction_decoding_compressed() {
+    let cases: [(u16, CInstruction); 26] = [
+        (0x0808, CInstruction::AddImmediate4SPN), // c.addi4spn a0, sp, 16
+        (0x41c8, CInstruction::LoadWord),         // c.lw a0, 4(a1)
+        (0xc1c8, CInstruction::StoreWord),        // c.sw a0, 4(a1)
+        (0x157d, CInstruction::AddImmediate),     // c.addi a0, -1
+        (0x2021, CInstruction::JumpAndLink),      // c.jal 8
+        (0x4515, CInstruction::LoadImmediate),    // c.li a0, 5
+        (0x717d, CInstruction::AddImmediate16SP), // c.addi16sp sp, -16
+        (0x6505, CInstruction::LoadUpperImmediate), // c.lui a0, 0x1
+        (0x8111, CInstruction::ShiftRightLogicalImmediate), // c.srli a0, 4
+        (0x8511, CInstruction::ShiftRightArithmeticImmediate), // c.srai a0, 4
+        (0x9979, CInstruction::AndImmediate),     // c.andi a0, -2
+        (0x8d0d, CInstruction::Sub),              // c.sub a0, a1
+        (0x8d2d, CInstruction::Xor),              // c.xor a0, a1
+        (0x8d4d, CInstruction::Or),               // c.or a0, a1
+        (0x8d6d, CInstruction::And),              // c.and a0, a1
+        (0xa021, CInstruction::Jump),             // c.j 8
+        (0xc501, CInstruction::BranchEqZero),     // c.beqz a0, 8
+        (0xe501, CInstruction::BranchNeqZero),    // c.bnez a0, 8
+        (0x050e, CInstruction::ShiftLeftLogicalImmediate), // c.slli a0, 3
+        (0x4512, CInstruction::LoadWordStackPointer), // c.lwsp a0, 4(sp)
+        (0x8082, CInstruction::JumpRegister),     // c.jr ra
+        (0x852e, CInstruction::Move),             // c.mv a0, a1
+        (0x9002, CInstruction::Breakpoint),       // c.ebreak
+        (0x9502, CInstruction::JumpAndLinkRegister), // c.jalr a0
+        (0x952e, CInstruction::Add),              // c.add a0, a1
+        (0xc42a, CInstruction::StoreWordStackPointer), // c.swsp a0, 8(sp)
+    ];
+    for (instruction, expected) in cases {
+        let mut env: Env<Fp> = dummy_env();
+        let opcode = load_instruction(&mut env, &instruction.to_le_bytes());
+        assert_eq!(opcode, Instruction::CType(expected));
+    }
+}
+
+#[test]
+pub fn test_instruction_decoding_atomic() {
+    let cases = [
+        (0b00010, AInstruction::LoadReserved),
+        (0b00011, AInstruction::StoreConditional),
+        (0b00001, AInstruction::AmoSwap),
+        (0b00000, AInstruction::AmoAdd),
+        (0b00100, AInstruction::AmoXor),
+        (0b01100, AInstruction::AmoAnd),
+        (0b01000, AInstruction::AmoOr),
+        (0b10000, AInstruction::AmoMin),
+        (0b10100, AInstruction::AmoMax),
+        (0b11000, AInstruction::AmoMinUnsigned),
+        (0b11100, AInstruction::AmoMaxUnsigned),
+    ];
+    for (funct5, expected) in cases {
+        let mut env: Env<Fp> = dummy_env();
+        let opcode = load_instruction(&mut env, &encode_atype(funct5, 10, 11, 12));
+        assert_eq!(opcode, Instruction::AType(expected));
+    }
+}
+
+/// Executes the instruction with the given register values, and returns the
+/// environment after the step.
+fn execute(instruction: &[u8], registers: &[(usize, u64)]) -> Env<Fp> {
+    let mut env: Env<Fp> = dummy_env();
+    load_instruction(&mut env, instruction);
+    for (idx, value) in registers {
+        env.registers[*idx] = *value;
+    }
+    env.step();
+    env
+}
+
+#[test]
+pub fn test_witness_compressed_arithmetic() {
+    // c.addi a0, -1
+    let env = execute(&0x157du16.to_le_bytes(), &[(10, 0)]);
+    assert_eq!(env.registers[10], u64::from(u32::MAX));
+    assert_eq!(env.registers.current_instruction_pointer, 2);
+    assert_eq!(env.registers.next_instruction_pointer, 6);
+
+    // c.li a0, 5
+    let env = execute(&0x4515u16.to_le_bytes(), &[]);
+    assert_eq!(env.registers[10], 5);
+
+    // c.lui a0, 0xfffff
+    let env = execute(&0x757du16.to_le_bytes(), &[]);
+    assert_eq!(env.registers[10], 0xfffff000);
+
+    // c.addi16sp sp, -16
+    let env = execute(&0x717du16.to_le_bytes(), &[(2, 0x100)]);
+    assert_eq!(env.registers[2], 0xf0);
+
+    // c.addi4spn a0, sp, 16
+    let env = execute(&0x0808u16.to_le_bytes(), &[(2, 0x100)]);
+    assert_eq!(env.registers[10], 0x110);
+
+    // c.srai a0, 4
+    let env = execute(&0x8511u16.to_le_bytes(), &[(10, 0x8000_0000)]);
+    assert_eq!(env.registers[10], 0xf800_0000);
+
+    // c.andi a0, -2
+    let env = execute(&0x9979u16.to_le_bytes(), &[(10, 0xff)]);
+    assert_eq!(env.registers[10], 0xfe);
+
+    // c.sub a0, a1
+    let env = execute(&0x8d0du16.to_le_bytes(), &[(10, 1), (11, 2)]);
+    assert_eq!(env.registers[10], u64::from(u32::MAX));
+
+    // c.mv a0, a1 and c.add a0, a1
+    let env = execute(&0x852eu16.to_le_bytes(), &[(10, 1), (11, 2)]);
+    assert_eq!(env.registers[10], 2);
+    let env = execute(&0x952eu16.to_le_bytes(), &[(10, 1), (11, 2)]);
+    assert_eq!(env.registers[10], 3);
+}
+
+#[test]
+pub fn test_witness_compressed_control_flow() {
+    // c.j -2, at address 0x10
+    let mut env: Env<Fp> = dummy_env();
+    env.memory[0].1[0x10..0x12].copy_from_slice(&0xbffdu16.to_le_bytes());
+    env.registers.current_instruction_pointer = 0x10;
+    env.registers.next_instruction_pointer = 0x14;
+    env.step();
+    assert_eq!(env.registers.current_instruction_pointer, 0xe);
+
+    // c.jal 8
+    let env = execute(&0x2021u16.to_le_bytes(), &[]);
+    assert_eq!(env.registers.current_instruction_pointer, 8);
+    assert_eq!(env.registers.next_instruction_pointer, 12);
+    assert_eq!(env.registers[1], 2);
+
+    // c.beqz a0, 8, taken and not taken
+    let env = execute(&0xc501u16.to_le_bytes(), &[(10, 0)]);
+    assert_eq!(env.registers.current_instruction_pointer, 8);
+    let env = execute(&0xc501u16.to_le_bytes(), &[(10, 1)]);
+    assert_eq!(env.registers.current_instruction_pointer, 2);
+
+    // c.bnez a0, 8
+    let env = execute(&0xe501u16.to_le_bytes(), &[(10, 1)]);
+    assert_eq!(env.registers.current_instruction_pointer, 8);
+
+    // c.jalr a0
+    let env = execute(&0x9502u16.to_le_bytes(), &[(10, 0x40)]);
+    assert_eq!(env.registers.current_instruction_pointer, 0x40);
+    assert_eq!(env.registers[1], 2);
+
+    // c.ebreak
+    let env = execute(&0x9002u16.to_le_bytes(), &[]);
+    assert!(env.halt);
+}
+
+#[test]
+pub fn test_witness_compressed_memory() {
+    // c.swsp a0, 8(sp)
+    let env = execute(&0xc42au16.to_le_bytes(), &[(2, 0x100), (10, 0x1234_5678)]);
+    assert_eq!(env.memory[0].1[0x108..0x10c], 0x1234_5678u32.to_le_bytes());
+
+    // c.lw a0, 4(a1)
+    let mut env: Env<Fp> = dummy_env();
+    env.memory[0].1[0x104..0x108].copy_from_slice(&0x8765_4321u32.to_le_bytes());
+    load_instruction(&mut env, &0x41c8u16.to_le_bytes());
+    env.registers[11] = 0x100;
+    env.step();
+    assert_eq!(env.registers[10], 0x8765_4321);
+
+    // c.sw a0, 4(a1), followed by c.lwsp a0, 4(sp)
+    let mut env: Env<Fp> = dummy_env();
+    load_instruction(&mut env, &[0xc8, 0xc1, 0x12, 0x45]);
+    env.registers[10] = 0xdead_beef;
+    env.registers[11] = 0x100;
+    env.registers[2] = 0x100;
+    env.step();
+    env.registers[10] = 0;
+    let opcode = env.step();
+    assert_eq!(
+        opcode,
+        Instruction::CType(CInstruction::LoadWordStackPointer)
+    );
+    assert_eq!(env.registers[10], 0xdead_beef);
+    assert_eq!(env.registers.current_instruction_pointer, 4);
+}
+
+#[test]
+pub fn test_witness_atomic() {
+    let value: u64 = 0xffff_fff0; // -16
+    let registers = [(11, 0x100), (12, 5)];
+    let run = |funct5: u32| {
+        let mut env: Env<Fp> = dummy_env();
+        env.memory[0].1[0x100..0x104].copy_from_slice(&(value as u32).to_le_bytes());
+        load_instruction(&mut env, &encode_atype(funct5, 10, 11, 12));
+        for (idx, value) in registers {
+            env.registers[idx] = value;
+        }
+        env.step();
+        let memory = u32::from_le_bytes(env.memory[0].1[0x100..0x104].try_into().unwrap());
+        let memory = u64::from(memory);
+        (env.registers[10], memory)
+    };
+    // lr.w and sc.w
+    assert_eq!(run(0b00010), (value, value));
+    assert_eq!(run(0b00011), (0, 5));
+    // The AMOs return the previous value
+    assert_eq!(run(0b00001), (value, 5));
+    assert_eq!(run(0b00000), (value, 0xffff_fff5));
+    assert_eq!(run(0b00100), (value, value ^ 5));
+    assert_eq!(run(0b01100), (value, value & 5));
+    assert_eq!(run(0b01000), (value, value | 5));
+    assert_eq!(run(0b10000), (value, value));
+    assert_eq!(run(0b10100), (value, 5));
+    assert_eq!(run(0b11000), (value, 5));
+    assert_eq!(run(0b11100), (value, value));
+}
+
+#[test]
+pub fn test_witness_base_shifts_and_upper_immediates() {
+    // slli a0, a0, 4
+    let env = execute(&0x0045_1513u32.to_le_bytes(), &[(10, 0x8000_0001)]);
+    assert_eq!(env.registers[10], 0x10);
+    assert_eq!(env.registers.current_instruction_pointer, 4);
+    assert_eq!(env.registers.next_instruction_pointer, 8);
+
+    // srli a0, a0, 4
+    let env = execute(&0x0045_5513u32.to_le_bytes(), &[(10, 0x8000_0010)]);
+    assert_eq!(env.registers[10], 0x0800_0001);
+
+    // srai a0, a0, 4
+    let env = execute(&0x4045_5513u32.to_le_bytes(), &[(10, 0x8000_0010)]);
+    assert_eq!(env.registers[10], 0xf800_0001);
+
+    // lui a0, 0xfffff
+    let env = execute(&0xffff_f537u32.to_le_bytes(), &[]);
+    assert_eq!(env.registers[10], 0xffff_f000);
+
+    // auipc a0, 1, at address 0x10
+    let mut env: Env<Fp> = dummy_env();
+    env.memory[0].1[0x10..0x14].copy_from_slice(&0x0000_1517u32.to_le_bytes());
+    env.registers.current_instruction_pointer = 0x10;
+    env.registers.next_instruction_pointer = 0x14;
+    env.step();
+    assert_eq!(env.registers[10], 0x1010);
+    assert_eq!(env.registers.current_instruction_pointer, 0x14);
+}
+
+#[test]
+pub fn test_witness_base_control_flow() {
+    // jal ra, 8
+    let env = execute(&0x0080_00efu32.to_le_bytes(), &[]);
+    assert_eq!(env.registers.current_instruction_pointer, 8);
+    assert_eq!(env.registers.next_instruction_pointer, 12);
+    assert_eq!(env.registers[1], 4);
+
+    // jal ra, 0x800 sets the offset bit 11
+    let env = execute(&0x0010_00efu32.to_le_bytes(), &[]);
+    assert_eq!(env.registers.current_instruction_pointer, 0x800);
+
+    // j -16 and beq a0, a1, -16, at address 0x10
+    for instruction in [0xff1f_f06fu32, 0xfeb5_08e3] {
+        let mut env: Env<Fp> = dummy_env();
+        env.memory[0].1[0x10..0x14].copy_from_slice(&instruction.to_le_bytes());
+        env.registers.current_instruction_pointer = 0x10;
+        env.registers.next_instruction_pointer = 0x14;
+        env.step();
+        assert_eq!(env.registers.current_instruction_pointer, 0);
+        assert_eq!(env.registers.next_instruction_pointer, 4);
+        assert_eq!(env.registers[0], 0);
+    }
+
+    // The branches to 8, with a0 = -1 and a1 = 1
+    let registers = [(10, u64::from(u32::MAX)), (11, 1)];
+    let cases = [
+        (0x00b5_0463u32, false), // beq
+        (0x00b5_1463, true),     // bne
+        (0x00b5_4463, true),     // blt
+        (0x00b5_5463, false),    // bge
+        (0x00b5_6463, false),    // bltu
+        (0x00b5_7463, true),     // bgeu
+    ];
+    for (instruction, taken) in cases {
+        let env = execute(&instruction.to_le_bytes(), &registers);
+        let expected = if taken { 8 } else { 4 };
+        assert_eq!(env.registers.current_instruction_pointer, expected);
+        assert_eq!(env.registers.next_instruction_pointer, expected + 4);
+    }
+}
+
+#[test]
+pub fn test_witness_set_less_than_immediate() {
+    // slti a0, a0, -1 and sltiu a0, a0, -1, where the immediate is
+    // sign-extended before the unsigned comparison
+    let (slti, sltiu) = (0xfff5_2513u32, 0xfff5_3513u32);
+    let cases = [(5, 0, 1), (u64::from(u32::MAX), 0, 0), (0x8000_0000, 1, 1)];
+    for (value, signed, unsigned) in cases {
+        let env = execute(&slti.to_le_bytes(), &[(10, value)]);
+        assert_eq!(env.registers[10], signed);
+        let env = execute(&sltiu.to_le_bytes(), &[(10, value)]);
+        assert_eq!(env.registers[10], unsigned);
+    }
+
+    // sltiu a0, a0, 1, i.e. seqz a0, a0
+    let env = execute(&0x0015_3513u32.to_le_bytes(), &[(10, 0)]);
+    assert_eq!(env.registers[10], 1);
+    assert_eq!(env.registers.current_instruction_pointer, 4);
+}
+
+#[test]
+pub fn test_witness_fence() {
+    // fence iorw, iorw only moves to the next instruction
+    let env = execute(&0x0ff0_000fu32.to_le_bytes(), &[(10, 42)]);
+    assert_eq!(env.registers[10], 42);
+    assert_eq!(env.registers.current_instruction_pointer, 4);
+    assert_eq!(env.registers.next_instruction_pointer, 8);
+}
+
+#[test]
+pub fn test_witness_base_stores() {
+    let registers = [(10, 0x1234_5678), (11, 0x102)];
+
+    // sw a0, 8(a1)
+    let env = execute(&0x00a5_a423u32.to_le_bytes(), &registers);
+    assert_eq!(env.memory[0].1[0x10a..0x10e], 0x1234_5678u32.to_le_bytes());
+    assert_eq!(env.registers.current_instruction_pointer, 4);
+
+    // sh a0, -2(a1)
+    let env = execute(&0xfea5_9f23u32.to_le_bytes(), &registers);
+    assert_eq!(env.memory[0].1[0x100..0x103], [0x78, 0x56, 0]);
+
+    // sb a0, -1(a1)
+    let env = execute(&0xfea5_8fa3u32.to_le_bytes(), &registers);
+    assert_eq!(env.memory[0].1[0x100..0x103], [0, 0x78, 0]);
+}
+
+#[test]
+pub fn test_constraints_of_instructions_fit_in_scratch() {
+    let instructions = CInstruction::iter()
+        .map(Instruction::CType)
+        .chain(AInstruction::iter().map(Instruction::AType))
+        .chain(SInstruction::iter().map(Instruction::SType))
+        .chain(SBInstruction::iter().map(Instruction::SBType))
+        .chain(UInstruction::iter().map(Instruction::UType))
+        .chain(UJInstruction::iter().map(Instruction::UJType));
+    for instruction in instructions {
+        let mut env = constraints::Env::<Fp>::default();
+        interpret_instruction(&mut env, instruction);
+        assert!(
+            env.scratch_state_idx <= SCRATCH_SIZE,
+            "{instruction:?} uses {} scratch columns",
+            env.scratch_state_idx
+        );
+    }
+}
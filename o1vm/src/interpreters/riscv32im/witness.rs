@@ -3,16 +3,14 @@
 use super::{
     column::Column,
     interpreter::{
-        self, IInstruction, Instruction, InterpreterEnv, RInstruction, SBInstruction, SInstruction,
+        self, AInstruction, CInstruction, IInstruction, IWInstruction, Instruction, InterpreterEnv,
+        MInstruction, MWInstruction, RInstruction, RWInstruction, SBInstruction, SInstruction,
         SyscallInstruction, UInstruction, UJInstruction,
     },
     registers::Registers,
-    INSTRUCTION_SET_SIZE, SCRATCH_SIZE,
-};
-use crate::{
-    cannon::{State, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE, PAGE_SIZE},
-    lookups::Lookup,
+    INSTRUCTION_SET_SIZE, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE, PAGE_SIZE, SCRATCH_SIZE,
 };
+use crate::{cannon::State, lookups::Lookup};
 use ark_ff::Field;
 use std::array;
 
@@ -36,13 +34,17 @@ pub const NUM_LOOKUP_TERMS: usize =
 /// machine has access to its internal state and some external memory. In
 /// addition to that, it has access to the environment of the Keccak interpreter
 /// that is used to verify the preimage requested during the execution.
-pub struct Env<Fp> {
+///
+/// The registers hold `XLEN` bits, i.e. 32 for RV32IM and 64 for RV64IM. The
+/// values are stored in `u64` in both cases, and the results of the operations
+/// are truncated to their lower `XLEN` bits.
+pub struct Env<Fp, const XLEN: u32 = 32> {
     pub instruction_counter: u64,
-    pub memory: Vec<(u32, Vec<u8>)>,
+    pub memory: Vec<(u64, Vec<u8>)>,
     pub last_memory_accesses: [usize; 3],
-    pub memory_write_index: Vec<(u32, Vec<u64>)>,
+    pub memory_write_index: Vec<(u64, Vec<u64>)>,
     pub last_memory_write_index_accesses: [usize; 3],
-    pub registers: Registers<u32>,
+    pub registers: Registers<u64>,
     pub registers_write_index: Registers<u64>,
     pub scratch_state_idx: usize,
     pub scratch_state: [Fp; SCRATCH_SIZE],
@@ -54,7 +56,9 @@ fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
     array::from_fn(|_| Fp::zero())
 }
 
-impl<Fp: Field> InterpreterEnv for Env<Fp> {
+impl<Fp: Field, const XLEN: u32> InterpreterEnv for Env<Fp, XLEN> {
+    const XLEN: u32 = XLEN;
+
     type Position = Column;
 
     fn alloc_scratch(&mut self) -> Self::Position {
@@ -112,7 +116,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         idx: &Self::Variable,
         output: Self::Position,
     ) -> Self::Variable {
-        let res = self.registers[*idx as usize] as u64;
+        let res = self.registers[*idx as usize];
         self.write_column(output, res);
         res
     }
@@ -123,7 +127,6 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         value: Self::Variable,
         if_is_true: &Self::Variable,
     ) {
-        let value: u32 = value.try_into().unwrap();
         if *if_is_true == 1 {
             self.registers[*idx as usize] = value
         } else if *if_is_true == 0 {
@@ -163,9 +166,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         addr: &Self::Variable,
         output: Self::Position,
     ) -> Self::Variable {
-        let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
+        let page = *addr >> PAGE_ADDRESS_SIZE;
+        let page_address = (*addr & PAGE_ADDRESS_MASK) as usize;
         let memory_page_idx = self.get_memory_page_index(page);
         let value = self.memory[memory_page_idx].1[page_address];
         self.write_column(output, value.into());
@@ -173,9 +175,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 
     unsafe fn push_memory(&mut self, addr: &Self::Variable, value: Self::Variable) {
-        let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
+        let page = *addr >> PAGE_ADDRESS_SIZE;
+        let page_address = (*addr & PAGE_ADDRESS_MASK) as usize;
         let memory_page_idx = self.get_memory_page_index(page);
         self.memory[memory_page_idx].1[page_address] =
             value.try_into().expect("push_memory values fit in a u8");
@@ -186,9 +187,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         addr: &Self::Variable,
         output: Self::Position,
     ) -> Self::Variable {
-        let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
+        let page = *addr >> PAGE_ADDRESS_SIZE;
+        let page_address = (*addr & PAGE_ADDRESS_MASK) as usize;
         let memory_write_index_page_idx = self.get_memory_access_page_index(page);
         let value = self.memory_write_index[memory_write_index_page_idx].1[page_address];
         self.write_column(output, value);
@@ -196,15 +196,14 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 
     unsafe fn push_memory_access(&mut self, addr: &Self::Variable, value: Self::Variable) {
-        let addr = *addr as u32;
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
+        let page = *addr >> PAGE_ADDRESS_SIZE;
+        let page_address = (*addr & PAGE_ADDRESS_MASK) as usize;
         let memory_write_index_page_idx = self.get_memory_access_page_index(page);
         self.memory_write_index[memory_write_index_page_idx].1[page_address] = value;
     }
 
-    fn constant(x: u32) -> Self::Variable {
-        x as u64
+    fn constant(x: u64) -> Self::Variable {
+        x
     }
 
     unsafe fn bitmask(
@@ -214,9 +213,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         lowest_bit: u32,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let res = (x >> lowest_bit) & ((1 << (highest_bit - lowest_bit)) - 1);
-        let res = res as u64;
+        let res = (*x >> lowest_bit) & ((1 << (highest_bit - lowest_bit)) - 1);
         self.write_column(position, res);
         res
     }
@@ -227,10 +224,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         by: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
         let by: u32 = (*by).try_into().unwrap();
-        let res = x << by;
-        let res = res as u64;
+        let res = (*x << by) & Self::MASK;
         self.write_column(position, res);
         res
     }
@@ -241,10 +236,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         by: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
         let by: u32 = (*by).try_into().unwrap();
-        let res = x >> by;
-        let res = res as u64;
+        let res = *x >> by;
         self.write_column(position, res);
         res
     }
@@ -255,10 +248,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         by: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
         let by: u32 = (*by).try_into().unwrap();
-        let res = ((x as i32) >> by) as u32;
-        let res = res as u64;
+        let res = ((Self::signed(*x) >> by) as u64) & Self::MASK;
         self.write_column(position, res);
         res
     }
@@ -301,7 +292,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 
     fn equal(&mut self, x: &Self::Variable, y: &Self::Variable) -> Self::Variable {
-        // To avoid subtraction overflow in the witness interpreter for u32
+        // To avoid subtraction overflow in the witness interpreter for u64
         if x > y {
             self.is_zero(&(*x - *y))
         } else {
@@ -315,10 +306,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = if x < y { 1 } else { 0 };
-        let res = res as u64;
+        let res = if *x < *y { 1 } else { 0 };
         self.write_column(position, res);
         res
     }
@@ -329,10 +317,11 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = if (x as i32) < (y as i32) { 1 } else { 0 };
-        let res = res as u64;
+        let res = if Self::signed(*x) < Self::signed(*y) {
+            1
+        } else {
+            0
+        };
         self.write_column(position, res);
         res
     }
@@ -343,10 +332,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x & y;
-        let res = res as u64;
+        let res = *x & *y;
         self.write_column(position, res);
         res
     }
@@ -357,10 +343,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = !(x | y);
-        let res = res as u64;
+        let res = !(*x | *y) & Self::MASK;
         self.write_column(position, res);
         res
     }
@@ -371,10 +354,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x | y;
-        let res = res as u64;
+        let res = *x | *y;
         self.write_column(position, res);
         res
     }
@@ -385,10 +365,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x ^ y;
-        let res = res as u64;
+        let res = *x ^ *y;
         self.write_column(position, res);
         res
     }
@@ -400,14 +377,11 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         out_position: Self::Position,
         overflow_position: Self::Position,
     ) -> (Self::Variable, Self::Variable) {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        // https://doc.rust-lang.org/std/primitive.u32.html#method.overflowing_add
-        let res = x.overflowing_add(y);
-        let (res_, overflow) = (res.0 as u64, res.1 as u64);
-        self.write_column(out_position, res_);
+        let res = x.wrapping_add(*y) & Self::MASK;
+        let overflow = ((u128::from(*x) + u128::from(*y)) >> XLEN) as u64;
+        self.write_column(out_position, res);
         self.write_column(overflow_position, overflow);
-        (res_, overflow)
+        (res, overflow)
     }
 
     unsafe fn sub_witness(
@@ -417,14 +391,11 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         out_position: Self::Position,
         underflow_position: Self::Position,
     ) -> (Self::Variable, Self::Variable) {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        // https://doc.rust-lang.org/std/primitive.u32.html#method.overflowing_sub
-        let res = x.overflowing_sub(y);
-        let (res_, underflow) = (res.0 as u64, res.1 as u64);
-        self.write_column(out_position, res_);
+        let res = x.wrapping_sub(*y) & Self::MASK;
+        let underflow = u64::from(*x < *y);
+        self.write_column(out_position, res);
         self.write_column(underflow_position, underflow);
-        (res_, underflow)
+        (res, underflow)
     }
 
     unsafe fn mul_signed_witness(
@@ -433,10 +404,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = ((x as i32) * (y as i32)) as u32;
-        let res = res as u64;
+        let res = (Self::signed(*x).wrapping_mul(Self::signed(*y)) as u64) & Self::MASK;
         self.write_column(position, res);
         res
     }
@@ -447,11 +415,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: i32 = (*x).try_into().unwrap();
-        let y: i32 = (*y).try_into().unwrap();
-        let res = (x as i64) * (y as i64);
-        let res = (res >> 32) as i32;
-        let res = res as u64;
+        let res = (i128::from(Self::signed(*x)) * i128::from(Self::signed(*y))) >> XLEN;
+        let res = (res as u64) & Self::MASK;
         self.write_column(position, res);
         res
     }
@@ -462,11 +427,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: i32 = (*x).try_into().unwrap();
-        let y: i32 = (*y).try_into().unwrap();
-        let res = ((x as i64) * (y as i64)) as u64;
-        let res = (res & ((1 << 32) - 1)) as u32;
-        let res = res as u64;
+        let res = (Self::signed(*x).wrapping_mul(Self::signed(*y)) as u64) & Self::MASK;
         self.write_column(position, res);
         res
     }
@@ -477,10 +438,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = (x as u64) * (y as u64);
-        let res = (res >> 32) as u32;
+        let res = (u128::from(*x) * u128::from(*y)) >> XLEN;
         let res = res as u64;
         self.write_column(position, res);
         res
@@ -492,11 +450,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = (((x as i32) as i64) * (y as i64)) as u64;
-        let res = (res >> 32) as u32;
-        let res = res as u64;
+        let res = (i128::from(Self::signed(*x)) * i128::from(*y)) >> XLEN;
+        let res = (res as u64) & Self::MASK;
         self.write_column(position, res);
         res
     }
@@ -507,10 +462,13 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: i32 = (*x).try_into().unwrap();
-        let y: i32 = (*y).try_into().unwrap();
-        let res = (x / y) as u32;
-        let res = res as u64;
+        // The division by zero gives -1, and the overflow of -2^(XLEN-1) / -1
+        // gives -2^(XLEN-1).
+        let res = if *y == 0 {
+            Self::MASK
+        } else {
+            (Self::signed(*x).wrapping_div(Self::signed(*y)) as u64) & Self::MASK
+        };
         self.write_column(position, res);
         res
     }
@@ -521,11 +479,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = (x as u64) * (y as u64);
-        let res = (res & ((1 << 32) - 1)) as u32;
-        let res = res as u64;
+        let res = x.wrapping_mul(*y) & Self::MASK;
         self.write_column(position, res);
         res
     }
@@ -536,10 +490,13 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: i32 = (*x).try_into().unwrap();
-        let y: i32 = (*y).try_into().unwrap();
-        let res = (x % y) as u32;
-        let res = res as u64;
+        // The remainder of the division by zero is the dividend, and the
+        // remainder of the overflowing -2^(XLEN-1) / -1 is 0.
+        let res = if *y == 0 {
+            *x
+        } else {
+            (Self::signed(*x).wrapping_rem(Self::signed(*y)) as u64) & Self::MASK
+        };
         self.write_column(position, res);
         res
     }
@@ -550,10 +507,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x / y;
-        let res = res as u64;
+        // The division by zero gives 2^XLEN - 1
+        let res = if *y == 0 { Self::MASK } else { *x / *y };
         self.write_column(position, res);
         res
     }
@@ -564,10 +519,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x % y;
-        let res = res as u64;
+        // The remainder of the division by zero is the dividend
+        let res = if *y == 0 { *x } else { *x % *y };
         self.write_column(position, res);
         res
     }
@@ -577,9 +530,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         x: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let res = x.leading_zeros();
-        let res = res as u64;
+        let res = u64::from((*x << (64 - XLEN)).leading_zeros().min(XLEN));
         self.write_column(position, res);
         res
     }
@@ -589,9 +540,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         x: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let res = x.leading_ones();
-        let res = res as u64;
+        let res = u64::from((*x << (64 - XLEN)).leading_ones());
         self.write_column(position, res);
         res
     }
@@ -626,18 +575,28 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 }
 
-impl<Fp: Field> Env<Fp> {
+impl<Fp: Field, const XLEN: u32> Env<Fp, XLEN> {
+    /// The mask of the lower `XLEN` bits.
+    const MASK: u64 = u64::MAX >> (64 - XLEN);
+
+    /// Returns the lower `XLEN` bits of `x`, interpreted as a signed integer.
+    fn signed(x: u64) -> i64 {
+        ((x << (64 - XLEN)) as i64) >> (64 - XLEN)
+    }
+
+    /// Creates the environment from a [State]. The 32-bit program counters,
+    /// registers and page indices of the state are zero-extended.
     pub fn create(page_size: usize, state: State) -> Self {
-        let initial_instruction_pointer = state.pc;
-        let next_instruction_pointer = state.next_pc;
+        let initial_instruction_pointer = u64::from(state.pc);
+        let next_instruction_pointer = u64::from(state.next_pc);
 
         let selector = INSTRUCTION_SET_SIZE;
 
-        let mut initial_memory: Vec<(u32, Vec<u8>)> = state
+        let mut initial_memory: Vec<(u64, Vec<u8>)> = state
             .memory
             .into_iter()
             // Check that the conversion from page data is correct
-            .map(|page| (page.index, page.data))
+            .map(|page| (u64::from(page.index), page.data))
             .collect();
 
         for (_address, initial_memory) in initial_memory.iter_mut() {
@@ -652,10 +611,10 @@ impl<Fp: Field> Env<Fp> {
 
         let initial_registers = {
             Registers {
-                general_purpose: state.registers,
+                general_purpose: state.registers.map(u64::from),
                 current_instruction_pointer: initial_instruction_pointer,
                 next_instruction_pointer,
-                heap_pointer: state.heap,
+                heap_pointer: u64::from(state.heap),
             }
         };
 
@@ -687,14 +646,23 @@ impl<Fp: Field> Env<Fp> {
 
     pub fn decode_instruction(&mut self) -> (Instruction, u32) {
         /* https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf */
+        // Instructions are 32 bits long and stored in little-endian
+        let pc = self.registers.current_instruction_pointer;
         let instruction =
-            ((self.get_memory_direct(self.registers.current_instruction_pointer) as u32) << 24)
-                | ((self.get_memory_direct(self.registers.current_instruction_pointer + 1) as u32)
-                    << 16)
-                | ((self.get_memory_direct(self.registers.current_instruction_pointer + 2) as u32)
-                    << 8)
-                | (self.get_memory_direct(self.registers.current_instruction_pointer + 3) as u32);
-        let instruction = instruction.to_be(); // convert to big endian for more straightforward decoding
+            u32::from_le_bytes(array::from_fn(|i| self.get_memory_direct(pc + i as u64)));
+        // The instructions of the C extension are 16 bits long, and are the
+        // only ones whose two lowest bits are not 0b11.
+        if instruction & 0b11 != 0b11 {
+            assert_eq!(
+                XLEN, 32,
+                "The compressed instructions are only supported in RV32"
+            );
+            let instruction = instruction & 0xffff;
+            return (
+                Self::decode_compressed_instruction(instruction),
+                instruction,
+            );
+        }
         let opcode = {
             match instruction & 0b1111111 // bits 0-6
             {
@@ -719,8 +687,10 @@ impl<Fp: Field> Env<Fp> {
                     0b000 => Instruction::IType(IInstruction::LoadByte),
                     0b001 => Instruction::IType(IInstruction::LoadHalf),
                     0b010 => Instruction::IType(IInstruction::LoadWord),
+                    0b011 if XLEN == 64 => Instruction::IType(IInstruction::LoadDouble),
                     0b100 => Instruction::IType(IInstruction::LoadByteUnsigned),
                     0b101 => Instruction::IType(IInstruction::LoadHalfUnsigned),
+                    0b110 if XLEN == 64 => Instruction::IType(IInstruction::LoadWordUnsigned),
                     _ => panic!("Unknown IType instruction with full inst {}", instruction),
                 },
                 0b0100011 =>
@@ -729,6 +699,7 @@ impl<Fp: Field> Env<Fp> {
                     0b000 => Instruction::SType(SInstruction::StoreByte),
                     0b001 => Instruction::SType(SInstruction::StoreHalf),
                     0b010 => Instruction::SType(SInstruction::StoreWord),
+                    0b011 if XLEN == 64 => Instruction::SType(SInstruction::StoreDouble),
                     _ => panic!("Unknown SType instruction with full inst {}", instruction),
                 },
                 0b0010011 =>
@@ -750,6 +721,63 @@ impl<Fp: Field> Env<Fp> {
                     },
                     _ => panic!("Unknown IType instruction with full inst {}", instruction),
                 },
+                0b0011011 if XLEN == 64 =>
+                match (instruction >> 12) & 0x7 // bits 12-14 for func3
+                {
+                    0b000 => Instruction::IWType(IWInstruction::AddImmediateWord),
+                    0b001 => Instruction::IWType(IWInstruction::ShiftLeftLogicalImmediateWord),
+                    0b101 =>
+                    match (instruction >> 30) & 0x1 // bit 30 in simm component of IType
+                    {
+                        0b0 => Instruction::IWType(IWInstruction::ShiftRightLogicalImmediateWord),
+                        0b1 => Instruction::IWType(IWInstruction::ShiftRightArithmeticImmediateWord),
+                        _ => panic!("Unknown IWType in shift right instructions with full inst {}", instruction),
+                    },
+                    _ => panic!("Unknown IWType instruction with full inst {}", instruction),
+                },
+                0b0111011 if XLEN == 64 && (instruction >> 25) == 0b0000001 => // funct7 of the M extension
+                match (instruction >> 12) & 0x7 // bits 12-14 for func3
+                {
+                    0b000 => Instruction::MWType(MWInstruction::MulWord),
+                    0b100 => Instruction::MWType(MWInstruction::DivWord),
+                    0b101 => Instruction::MWType(MWInstruction::DivUnsignedWord),
+                    0b110 => Instruction::MWType(MWInstruction::RemWord),
+                    0b111 => Instruction::MWType(MWInstruction::RemUnsignedWord),
+                    _ => panic!("Unknown MWType instruction with full inst {}", instruction),
+                },
+                0b0111011 if XLEN == 64 =>
+                match (instruction >> 12) & 0x7 // bits 12-14 for func3
+                {
+                    0b000 =>
+                    match (instruction >> 30) & 0x1 // bit 30 of funct5 component in RType
+                    {
+                        0b0 => Instruction::RWType(RWInstruction::AddWord),
+                        0b1 => Instruction::RWType(RWInstruction::SubWord),
+                        _ => panic!("Unknown RWType in add/sub instructions with full inst {}", instruction),
+                    },
+                    0b001 => Instruction::RWType(RWInstruction::ShiftLeftLogicalWord),
+                    0b101 =>
+                    match (instruction >> 30) & 0x1 // bit 30 of funct5 component in RType
+                    {
+                        0b0 => Instruction::RWType(RWInstruction::ShiftRightLogicalWord),
+                        0b1 => Instruction::RWType(RWInstruction::ShiftRightArithmeticWord),
+                        _ => panic!("Unknown RWType in shift right instructions with full inst {}", instruction),
+                    },
+                    _ => panic!("Unknown RWType instruction with full inst {}", instruction),
+                },
+                0b0110011 if (instruction >> 25) == 0b0000001 => // funct7 of the M extension
+                match (instruction >> 12) & 0x7 // bits 12-14 for func3
+                {
+                    0b000 => Instruction::MType(MInstruction::Mul),
+                    0b001 => Instruction::MType(MInstruction::Mulh),
+                    0b010 => Instruction::MType(MInstruction::Mulhsu),
+                    0b011 => Instruction::MType(MInstruction::Mulhu),
+                    0b100 => Instruction::MType(MInstruction::Div),
+                    0b101 => Instruction::MType(MInstruction::Divu),
+                    0b110 => Instruction::MType(MInstruction::Rem),
+                    0b111 => Instruction::MType(MInstruction::Remu),
+                    _ => panic!("Unknown MType instruction with full inst {}", instruction),
+                },
                 0b0110011 =>
                 match (instruction >> 12) & 0x7 // bits 12-14 for func3
                 {
@@ -775,6 +803,22 @@ impl<Fp: Field> Env<Fp> {
                     0b111 => Instruction::RType(RInstruction::And),
                     _ => panic!("Unknown RType 0110011 instruction with full inst {}", instruction),
                 },
+                0b0101111 if XLEN == 32 && (instruction >> 12) & 0x7 == 0b010 => // funct3 of the word operations
+                match (instruction >> 27) & 0x1f // bits 27-31 for funct5
+                {
+                    0b00010 => Instruction::AType(AInstruction::LoadReserved),
+                    0b00011 => Instruction::AType(AInstruction::StoreConditional),
+                    0b00001 => Instruction::AType(AInstruction::AmoSwap),
+                    0b00000 => Instruction::AType(AInstruction::AmoAdd),
+                    0b00100 => Instruction::AType(AInstruction::AmoXor),
+                    0b01100 => Instruction::AType(AInstruction::AmoAnd),
+                    0b01000 => Instruction::AType(AInstruction::AmoOr),
+                    0b10000 => Instruction::AType(AInstruction::AmoMin),
+                    0b10100 => Instruction::AType(AInstruction::AmoMax),
+                    0b11000 => Instruction::AType(AInstruction::AmoMinUnsigned),
+                    0b11100 => Instruction::AType(AInstruction::AmoMaxUnsigned),
+                    _ => panic!("Unknown AType instruction with full inst {}", instruction),
+                },
                 0b0001111 =>
                 match (instruction >> 12) & 0x7 // bits 12-14 for func3
                 {
@@ -792,7 +836,61 @@ impl<Fp: Field> Env<Fp> {
         (opcode, instruction)
     }
 
-    /// Execute a single step in the RISCV32i program
+    /// Decode a 16-bit instruction of the C extension.
+    /// Following the documentation found
+    /// [here](https://msyksphinz-self.github.io/riscv-isadoc/html/rvc.html)
+    pub fn decode_compressed_instruction(instruction: u32) -> Instruction {
+        // The instruction made of zeros is defined as illegal
+        assert_ne!(instruction, 0, "Illegal compressed instruction 0");
+        let rd = (instruction >> 7) & 0x1f; // bits 7-11 for rd or rs1
+        let rs2 = (instruction >> 2) & 0x1f; // bits 2-6 for rs2
+        let bit12 = (instruction >> 12) & 0x1;
+        let ctype = match (instruction & 0b11, (instruction >> 13) & 0x7) // opcode and funct3
+        {
+            (0b00, 0b000) => CInstruction::AddImmediate4SPN,
+            (0b00, 0b010) => CInstruction::LoadWord,
+            (0b00, 0b110) => CInstruction::StoreWord,
+            (0b01, 0b000) => CInstruction::AddImmediate,
+            (0b01, 0b001) => CInstruction::JumpAndLink,
+            (0b01, 0b010) => CInstruction::LoadImmediate,
+            (0b01, 0b011) if rd == 2 => CInstruction::AddImmediate16SP,
+            (0b01, 0b011) => CInstruction::LoadUpperImmediate,
+            (0b01, 0b100) =>
+            match (instruction >> 10) & 0x3 // bits 10-11 for funct2
+            {
+                0b00 => CInstruction::ShiftRightLogicalImmediate,
+                0b01 => CInstruction::ShiftRightArithmeticImmediate,
+                0b10 => CInstruction::AndImmediate,
+                _ =>
+                match (bit12, (instruction >> 5) & 0x3) // bits 5-6 for funct2
+                {
+                    (0, 0b00) => CInstruction::Sub,
+                    (0, 0b01) => CInstruction::Xor,
+                    (0, 0b10) => CInstruction::Or,
+                    (0, 0b11) => CInstruction::And,
+                    _ => panic!("Unknown CType arithmetic instruction with full inst {}", instruction),
+                },
+            },
+            (0b01, 0b101) => CInstruction::Jump,
+            (0b01, 0b110) => CInstruction::BranchEqZero,
+            (0b01, 0b111) => CInstruction::BranchNeqZero,
+            (0b10, 0b000) => CInstruction::ShiftLeftLogicalImmediate,
+            (0b10, 0b010) => CInstruction::LoadWordStackPointer,
+            (0b10, 0b100) =>
+            match (bit12, rd, rs2) {
+                (0, _, 0) => CInstruction::JumpRegister,
+                (0, _, _) => CInstruction::Move,
+                (_, 0, 0) => CInstruction::Breakpoint,
+                (_, _, 0) => CInstruction::JumpAndLinkRegister,
+                (_, _, _) => CInstruction::Add,
+            },
+            (0b10, 0b110) => CInstruction::StoreWordStackPointer,
+            _ => panic!("Unknown CType instruction with full inst {}", instruction),
+        };
+        Instruction::CType(ctype)
+    }
+
+    /// Execute a single step in the RISC-V program
     pub fn step(&mut self) -> Instruction {
         self.reset_scratch_state();
         let (opcode, _instruction) = self.decode_instruction();
@@ -835,9 +933,9 @@ impl<Fp: Field> Env<Fp> {
         self.last_memory_accesses = [i, i_0, i_1]
     }
 
-    pub fn get_memory_page_index(&mut self, page: u32) -> usize {
+    pub fn get_memory_page_index(&mut self, page: u64) -> usize {
         for &i in self.last_memory_accesses.iter() {
-            if self.memory_write_index[i].0 == page {
+            if self.memory[i].0 == page {
                 return i;
             }
         }
@@ -861,7 +959,7 @@ impl<Fp: Field> Env<Fp> {
         self.last_memory_write_index_accesses = [i, i_0, i_1]
     }
 
-    pub fn get_memory_access_page_index(&mut self, page: u32) -> usize {
+    pub fn get_memory_access_page_index(&mut self, page: u64) -> usize {
         for &i in self.last_memory_write_index_accesses.iter() {
             if self.memory_write_index[i].0 == page {
                 return i;
@@ -883,7 +981,7 @@ impl<Fp: Field> Env<Fp> {
         i
     }
 
-    pub fn get_memory_direct(&mut self, addr: u32) -> u8 {
+    pub fn get_memory_direct(&mut self, addr: u64) -> u8 {
         let page = addr >> PAGE_ADDRESS_SIZE;
         let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
         let memory_idx = self.get_memory_page_index(page);
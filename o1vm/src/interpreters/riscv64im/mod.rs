@@ -0,0 +1,34 @@
+//! RV64IM shares its interpreter with [RV32IM](super::riscv32im): the
+//! instruction set, the decoding and the constraints are parameterized by the
+//! width of the registers, and this module only instantiates them with
+//! [XLEN] = 64.
+
+/// The width of the registers, in bits
+pub const XLEN: u32 = 64;
+
+pub use super::riscv32im::{
+    column, interpreter, registers, INSTRUCTION_SET_SIZE, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE,
+    PAGE_SIZE, SCRATCH_SIZE,
+};
+
+pub mod constraints {
+    use super::XLEN;
+
+    /// The environment building the constraints of RV64IM
+    pub type Env<Fp> = crate::interpreters::riscv32im::constraints::Env<Fp, XLEN>;
+}
+
+pub mod witness {
+    use super::XLEN;
+
+    pub use crate::interpreters::riscv32im::witness::{
+        MAX_ACC, MAX_NB_MEM_ACC, MAX_NB_REG_ACC, NUM_DECODING_LOOKUP_TERMS,
+        NUM_GLOBAL_LOOKUP_TERMS, NUM_INSTRUCTION_LOOKUP_TERMS, NUM_LOOKUP_TERMS,
+    };
+
+    /// The environment executing RV64IM programs, holding 64-bit registers
+    pub type Env<Fp> = crate::interpreters::riscv32im::witness::Env<Fp, XLEN>;
+}
+
+#[cfg(test)]
+mod tests;
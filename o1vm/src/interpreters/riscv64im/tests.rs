@@ -0,0 +1,370 @@
+use super::{registers::Registers, witness::Env, INSTRUCTION_SET_SIZE, PAGE_SIZE, SCRATCH_SIZE};
+use crate::interpreters::riscv64im::{
+    constraints,
+    interpreter::{
+        interpret_instruction, AInstruction, CInstruction, IInstruction, IWInstruction,
+        Instruction, InterpreterEnv, MInstruction, MWInstruction, RInstruction, RWInstruction,
+        SBInstruction, SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+    },
+};
+use ark_ff::Zero;
+use mina_curves::pasta::Fp;
+use strum::{EnumCount, IntoEnumIterator};
+
+// Sanity check that we have as many selector as we have instructions
+#[test]
+fn test_regression_selectors_for_instructions() {
+    let con_env = constraints::Env::<Fp>::default();
+    let constraints = con_env.get_selector_constraints();
+    assert_eq!(
+        // We substract 1 as we have one boolean check per sel
+        // and 1 constraint to check that one and only one
+        // sel is activated
+        constraints.len() - 1,
+        // This should match the list in
+        // crate::interpreters::riscv32im::interpreter::Instruction, shared by
+        // RV32IM and RV64IM
+        RInstruction::COUNT
+            + IInstruction::COUNT
+            + SInstruction::COUNT
+            + SBInstruction::COUNT
+            + UInstruction::COUNT
+            + UJInstruction::COUNT
+            + SyscallInstruction::COUNT
+            + MInstruction::COUNT
+            + CInstruction::COUNT
+            + AInstruction::COUNT
+            + RWInstruction::COUNT
+            + IWInstruction::COUNT
+            + MWInstruction::COUNT
+    );
+    assert_eq!(constraints.len() - 1, INSTRUCTION_SET_SIZE);
+    // All instructions are degree 1 or 2.
+    constraints
+        .iter()
+        .for_each(|c| assert!(c.degree(1, 0) == 2 || c.degree(1, 0) == 1));
+}
+
+pub fn dummy_env() -> Env<Fp> {
+    Env {
+        instruction_counter: 0,
+        memory: vec![(0, vec![0; PAGE_SIZE.try_into().unwrap()])],
+        last_memory_accesses: [0; 3],
+        memory_write_index: vec![(0, vec![0; PAGE_SIZE.try_into().unwrap()])],
+        last_memory_write_index_accesses: [0; 3],
+        registers: Registers::default(),
+        registers_write_index: Registers::default(),
+        scratch_state_idx: 0,
+        scratch_state: [Fp::zero(); SCRATCH_SIZE],
+        halt: false,
+        selector: INSTRUCTION_SET_SIZE,
+    }
+}
+
+fn encode_rtype(opcode: u32, rd: u32, funct3: u32, rs1: u32, rs2: u32, funct7: u32) -> u32 {
+    opcode | (rd << 7) | (funct3 << 12) | (rs1 << 15) | (rs2 << 20) | (funct7 << 25)
+}
+
+fn encode_itype(opcode: u32, rd: u32, funct3: u32, rs1: u32, imm: i32) -> u32 {
+    opcode | (rd << 7) | (funct3 << 12) | (rs1 << 15) | (((imm as u32) & 0xfff) << 20)
+}
+
+fn encode_stype(funct3: u32, rs1: u32, rs2: u32, imm: i32) -> u32 {
+    let imm = (imm as u32) & 0xfff;
+    0b0100011
+        | ((imm & 0x1f) << 7)
+        | (funct3 << 12)
+        | (rs1 << 15)
+        | (rs2 << 20)
+        | ((imm >> 5) << 25)
+}
+
+/// Writes the instruction at address 0, in little-endian, and returns the
+/// decoded instruction.
+fn load_instruction(env: &mut Env<Fp>, instruction: u32) -> Instruction {
+    env.memory[0].1[0..4].copy_from_slice(&instruction.to_le_bytes());
+    env.registers.current_instruction_pointer = 0;
+    env.registers.next_instruction_pointer = 4;
+    env.decode_instruction().0
+}
+
+#[test]
+fn test_instruction_decoding_rv64() {
+    let cases = [
+        (
+            encode_rtype(0b0111011, 1, 0b000, 2, 3, 0b0000000),
+            Instruction::RWType(RWInstruction::AddWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b000, 2, 3, 0b0100000),
+            Instruction::RWType(RWInstruction::SubWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b001, 2, 3, 0b0000000),
+            Instruction::RWType(RWInstruction::ShiftLeftLogicalWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b101, 2, 3, 0b0000000),
+            Instruction::RWType(RWInstruction::ShiftRightLogicalWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b101, 2, 3, 0b0100000),
+            Instruction::RWType(RWInstruction::ShiftRightArithmeticWord),
+        ),
+        (
+            encode_itype(0b0011011, 1, 0b000, 2, -1),
+            Instruction::IWType(IWInstruction::AddImmediateWord),
+        ),
+        (
+            encode_itype(0b0011011, 1, 0b001, 2, 3),
+            Instruction::IWType(IWInstruction::ShiftLeftLogicalImmediateWord),
+        ),
+        (
+            encode_itype(0b0011011, 1, 0b101, 2, 3),
+            Instruction::IWType(IWInstruction::ShiftRightLogicalImmediateWord),
+        ),
+        (
+            encode_itype(0b0011011, 1, 0b101, 2, 0b0100000 << 5 | 3),
+            Instruction::IWType(IWInstruction::ShiftRightArithmeticImmediateWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b000, 2, 3, 0b0000001),
+            Instruction::MWType(MWInstruction::MulWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b100, 2, 3, 0b0000001),
+            Instruction::MWType(MWInstruction::DivWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b101, 2, 3, 0b0000001),
+            Instruction::MWType(MWInstruction::DivUnsignedWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b110, 2, 3, 0b0000001),
+            Instruction::MWType(MWInstruction::RemWord),
+        ),
+        (
+            encode_rtype(0b0111011, 1, 0b111, 2, 3, 0b0000001),
+            Instruction::MWType(MWInstruction::RemUnsignedWord),
+        ),
+        (
+            encode_rtype(0b0110011, 1, 0b000, 2, 3, 0b0000001),
+            Instruction::MType(MInstruction::Mul),
+        ),
+        (
+            encode_rtype(0b0110011, 1, 0b011, 2, 3, 0b0000001),
+            Instruction::MType(MInstruction::Mulhu),
+        ),
+        (
+            encode_rtype(0b0110011, 1, 0b000, 2, 3, 0b0000000),
+            Instruction::RType(RInstruction::Add),
+        ),
+        (
+            encode_itype(0b0000011, 1, 0b110, 2, 8),
+            Instruction::IType(IInstruction::LoadWordUnsigned),
+        ),
+        (
+            encode_itype(0b0000011, 1, 0b011, 2, 8),
+            Instruction::IType(IInstruction::LoadDouble),
+        ),
+        (
+            encode_stype(0b011, 2, 1, 8),
+            Instruction::SType(SInstruction::StoreDouble),
+        ),
+    ];
+    for (instruction, expected) in cases {
+        let mut env = dummy_env();
+        assert_eq!(load_instruction(&mut env, instruction), expected);
+    }
+}
+
+/// Executes the instruction with the given register values, and returns the
+/// environment after the step.
+fn execute(instruction: u32, registers: &[(usize, u64)]) -> Env<Fp> {
+    let mut env = dummy_env();
+    load_instruction(&mut env, instruction);
+    for (idx, value) in registers {
+        env.registers[*idx] = *value;
+    }
+    env.step();
+    assert_eq!(env.registers.current_instruction_pointer, 4);
+    assert_eq!(env.registers.next_instruction_pointer, 8);
+    env
+}
+
+#[test]
+fn test_witness_word_arithmetic_is_sign_extended() {
+    // addw x1, x2, x3
+    let addw = encode_rtype(0b0111011, 1, 0b000, 2, 3, 0b0000000);
+    let env = execute(addw, &[(2, 0x1234_5678_7fff_ffff), (3, 1)]);
+    assert_eq!(env.registers[1], 0xffff_ffff_8000_0000);
+
+    // subw x1, x2, x3
+    let subw = encode_rtype(0b0111011, 1, 0b000, 2, 3, 0b0100000);
+    let env = execute(subw, &[(2, 0xffff_0000_0000_0001), (3, 2)]);
+    assert_eq!(env.registers[1], u64::MAX);
+
+    // addiw x1, x2, -1
+    let addiw = encode_itype(0b0011011, 1, 0b000, 2, -1);
+    let env = execute(addiw, &[(2, 0xdead_beef_0000_0000)]);
+    assert_eq!(env.registers[1], u64::MAX);
+
+    // sraiw x1, x2, 4
+    let sraiw = encode_itype(0b0011011, 1, 0b101, 2, 0b0100000 << 5 | 4);
+    let env = execute(sraiw, &[(2, 0x0000_0001_8000_0000)]);
+    assert_eq!(env.registers[1], 0xffff_ffff_f800_0000);
+
+    // srlw x1, x2, x3, where only the 5 lower bits of x3 are used
+    let srlw = encode_rtype(0b0111011, 1, 0b101, 2, 3, 0b0000000);
+    let env = execute(srlw, &[(2, 0x0000_0001_8000_0000), (3, 0x24)]);
+    assert_eq!(env.registers[1], 0x0800_0000);
+}
+
+#[test]
+fn test_witness_shifts_use_six_bits_shamt() {
+    // slli x1, x2, 40
+    let slli = encode_itype(0b0010011, 1, 0b001, 2, 40);
+    let env = execute(slli, &[(2, 0x1ff)]);
+    assert_eq!(env.registers[1], 0x1ff << 40);
+
+    // srai x1, x2, 63
+    let srai = encode_itype(0b0010011, 1, 0b101, 2, 0b0100000 << 5 | 63);
+    let env = execute(srai, &[(2, 1 << 63)]);
+    assert_eq!(env.registers[1], u64::MAX);
+
+    // sll x1, x2, x3
+    let sll = encode_rtype(0b0110011, 1, 0b001, 2, 3, 0b0000000);
+    let env = execute(sll, &[(2, 1), (3, 63)]);
+    assert_eq!(env.registers[1], 1 << 63);
+}
+
+#[test]
+fn test_witness_store_and_load_double() {
+    let value = 0x8123_4567_89ab_cdef;
+    // sd x1, 16(x2)
+    let sd = encode_stype(0b011, 2, 1, 16);
+    let env = execute(sd, &[(1, value), (2, 0x100)]);
+    assert_eq!(
+        env.memory[0].1[0x110..0x118],
+        value.to_le_bytes(),
+        "Doubles are stored in little-endian"
+    );
+
+    let mut env = dummy_env();
+    env.memory[0].1[0x110..0x118].copy_from_slice(&value.to_le_bytes());
+    // ld x1, -16(x2)
+    load_instruction(&mut env, encode_itype(0b0000011, 1, 0b011, 2, -16));
+    env.registers[2] = 0x120;
+    env.step();
+    assert_eq!(env.registers[1], value);
+
+    // lwu x3, 4(x2) zero-extends the word
+    load_instruction(&mut env, encode_itype(0b0000011, 3, 0b110, 2, 4));
+    env.registers[2] = 0x110;
+    env.step();
+    assert_eq!(env.registers[3], 0x8123_4567);
+
+    // lw x4, 4(x2) sign-extends the word
+    load_instruction(&mut env, encode_itype(0b0000011, 4, 0b010, 2, 4));
+    env.step();
+    assert_eq!(env.registers[4], 0xffff_ffff_8123_4567);
+}
+
+#[test]
+fn test_witness_word_multiplication_and_division() {
+    let mulw = encode_rtype(0b0111011, 1, 0b000, 2, 3, 0b0000001);
+    let env = execute(mulw, &[(2, 0x1_0001_0000), (3, 0x8000)]);
+    assert_eq!(env.registers[1], 0xffff_ffff_8000_0000);
+
+    // Division by zero gives -1, and the remainder is the dividend
+    let divw = encode_rtype(0b0111011, 1, 0b100, 2, 3, 0b0000001);
+    let env = execute(divw, &[(2, 7), (3, 0xffff_ffff_0000_0000)]);
+    assert_eq!(env.registers[1], u64::MAX);
+    let remw = encode_rtype(0b0111011, 1, 0b110, 2, 3, 0b0000001);
+    let env = execute(remw, &[(2, 0x8000_0000), (3, 0)]);
+    assert_eq!(env.registers[1], 0xffff_ffff_8000_0000);
+
+    // The overflowing -2^31 / -1 gives -2^31
+    let env = execute(divw, &[(2, 0x8000_0000), (3, u64::MAX)]);
+    assert_eq!(env.registers[1], 0xffff_ffff_8000_0000);
+
+    // divuw treats the words as unsigned
+    let divuw = encode_rtype(0b0111011, 1, 0b101, 2, 3, 0b0000001);
+    let env = execute(divuw, &[(2, 0xffff_fffe), (3, 2)]);
+    assert_eq!(env.registers[1], 0x7fff_ffff);
+
+    // remuw x1, x2, x3
+    let remuw = encode_rtype(0b0111011, 1, 0b111, 2, 3, 0b0000001);
+    let env = execute(remuw, &[(2, 0xffff_ffff), (3, 0x10)]);
+    assert_eq!(env.registers[1], 0xf);
+}
+
+#[test]
+fn test_witness_double_multiplication_and_division() {
+    let mulhu = encode_rtype(0b0110011, 1, 0b011, 2, 3, 0b0000001);
+    let env = execute(mulhu, &[(2, u64::MAX), (3, u64::MAX)]);
+    assert_eq!(env.registers[1], u64::MAX - 1);
+
+    let mulh = encode_rtype(0b0110011, 1, 0b001, 2, 3, 0b0000001);
+    let env = execute(mulh, &[(2, u64::MAX), (3, 2)]);
+    assert_eq!(env.registers[1], u64::MAX);
+
+    let div = encode_rtype(0b0110011, 1, 0b100, 2, 3, 0b0000001);
+    let env = execute(div, &[(2, 1 << 63), (3, u64::MAX)]);
+    assert_eq!(env.registers[1], 1 << 63);
+    let env = execute(div, &[(2, 42), (3, 0)]);
+    assert_eq!(env.registers[1], u64::MAX);
+
+    let remu = encode_rtype(0b0110011, 1, 0b111, 2, 3, 0b0000001);
+    let env = execute(remu, &[(2, 42), (3, 0)]);
+    assert_eq!(env.registers[1], 42);
+}
+
+#[test]
+fn test_constraints_of_rv64_instructions_fit_in_scratch() {
+    let instructions = RWInstruction::iter()
+        .map(Instruction::RWType)
+        .chain(IWInstruction::iter().map(Instruction::IWType))
+        .chain(MWInstruction::iter().map(Instruction::MWType))
+        .chain([
+            Instruction::IType(IInstruction::LoadWordUnsigned),
+            Instruction::IType(IInstruction::LoadDouble),
+            Instruction::SType(SInstruction::StoreDouble),
+        ]);
+    for instruction in instructions {
+        let mut env = constraints::Env::<Fp>::default();
+        interpret_instruction(&mut env, instruction);
+        assert!(
+            env.scratch_state_idx <= SCRATCH_SIZE,
+            "{instruction:?} uses {} scratch columns",
+            env.scratch_state_idx
+        );
+    }
+}
+
+#[test]
+pub fn test_witness_bitmask_bounds() {
+    let mut env: Env<Fp> = dummy_env();
+    // Checking that the bit position given as upper bound is not included in
+    // the output, i.e. the output is v[LOWER_BOUND:UPPER_BOUND-1]
+    {
+        // We take only 4 bits on the 5.
+        let input = 0b10000;
+        let output = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&input, 4, 0, pos) }
+        };
+        let exp_output = 0b0000;
+        assert_eq!(output, exp_output);
+    }
+
+    {
+        // We take the 32 upper bits of a 64-bit value
+        let input = 0xdead_beef_0000_0001;
+        let output = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&input, 64, 32, pos) }
+        };
+        assert_eq!(output, 0xdead_beef);
+    }
+}
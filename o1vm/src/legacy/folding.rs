@@ -6,6 +6,7 @@ use folding::{
 };
 use kimchi::circuits::{berkeley_columns::BerkeleyChallengeTerm, gate::CurrOrNext};
 use kimchi_msm::witness::Witness as GenericWitness;
+use o1_utils::ExtendedEvaluations;
 use poly_commitment::commitment::CommitmentCurve;
 use std::{array, ops::Index};
 use strum::EnumCount;
@@ -175,7 +176,7 @@ where
         let mut next_witnesses = curr_witnesses.clone();
         for side in next_witnesses.iter_mut() {
             for col in side.witness.cols.iter_mut() {
-                col.evals.rotate_left(1);
+                *col = col.rotate(1);
             }
         }
         DecomposedFoldingEnvironment {
@@ -254,7 +255,7 @@ where
         let mut next_witnesses = curr_witnesses.clone();
         for side in next_witnesses.iter_mut() {
             for col in side.witness.cols.iter_mut() {
-                col.evals.rotate_left(1);
+                *col = col.rotate(1);
             }
         }
         FoldingEnvironment {
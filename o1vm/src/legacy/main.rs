@@ -2,10 +2,10 @@ use ark_ff::UniformRand;
 use folding::decomposable_folding::DecomposableFoldingScheme;
 use kimchi::o1_utils;
 use kimchi_msm::{proof::ProofInputs, prover::prove, verifier::verify, witness::Witness};
-use log::debug;
+use log::{debug, error};
 use o1vm::{
     cannon::{self, Meta, Start, State},
-    cannon_cli,
+    cannon_cli, gdbstub,
     interpreters::{
         keccak::{
             column::{Steps, N_ZKVM_KECCAK_COLS, N_ZKVM_KECCAK_REL_COLS, N_ZKVM_KECCAK_SEL_COLS},
@@ -28,7 +28,9 @@ use o1vm::{
         BaseSponge, Fp, OpeningProof, ScalarSponge,
     },
     lookups::LookupTableIDs,
-    preimage_oracle::PreImageOracle,
+    preimage_oracle::{create_preimage_oracle, PreImageOracleT},
+    profiler::Profiler,
+    statistics::ExecutionStatistics,
 };
 use poly_commitment::SRS as _;
 use std::{cmp::Ordering, collections::HashMap, fs::File, io::BufReader, process::ExitCode};
@@ -43,12 +45,10 @@ pub fn main() -> ExitCode {
 
     let configuration = cannon_cli::read_configuration(&cli.get_matches());
 
-    let file =
-        File::open(&configuration.input_state_file).expect("Error opening input state file ");
-
-    let reader = BufReader::new(file);
-    // Read the JSON contents of the file as an instance of `State`.
-    let state: State = serde_json::from_reader(reader).expect("Error reading input state file");
+    // Read the contents of the file, in the JSON or the binary format of
+    // Cannon, as an instance of `State`.
+    let state =
+        State::from_file(&configuration.input_state_file).expect("Error reading input state file");
 
     let meta_file = File::open(&configuration.metadata_file).unwrap_or_else(|_| {
         panic!(
@@ -64,8 +64,7 @@ pub fn main() -> ExitCode {
         )
     });
 
-    let mut po = PreImageOracle::create(&configuration.host);
-    let _child = po.start();
+    let (po, _child) = create_preimage_oracle(&configuration);
 
     // Initialize some data used for statistical computations
     let start = Start::create(state.step as usize);
@@ -85,8 +84,36 @@ pub fn main() -> ExitCode {
 
     // Initialize the environments
     // The Keccak environment is extracted inside the loop
-    let mut mips_wit_env =
-        mips_witness::Env::<Fp, PreImageOracle>::create(cannon::PAGE_SIZE as usize, state, po);
+    let mut mips_wit_env = mips_witness::Env::<Fp, Box<dyn PreImageOracleT>>::create(
+        cannon::PAGE_SIZE as usize,
+        state,
+        po,
+    );
+    if configuration.profile.is_some() {
+        mips_wit_env.profiler = Some(Profiler::new(&meta));
+    }
+    if configuration.statistics.is_some() {
+        mips_wit_env.statistics = Some(ExecutionStatistics::default());
+    }
+    if configuration.record.is_some() {
+        mips_wit_env.start_recording();
+    }
+    if let Some(symbol) = &configuration.fp_trap_handler {
+        mips_wit_env.registers.trap_handler = meta
+            .find_symbol_address(symbol)
+            .unwrap_or_else(|| panic!("Could not find the floating-point trap handler {symbol}"));
+    }
+    mips_wit_env.syscall_env.output.echo = true;
+    if let Some(e) = &mips_wit_env.preimage_oracle_error {
+        error!("Could not resume the execution: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    if let Some(address) = &configuration.gdb {
+        gdbstub::listen(address, &mut mips_wit_env, &configuration, &meta, &start)
+            .unwrap_or_else(|e| panic!("Could not serve the GDB connection: {e}"));
+        return ExitCode::SUCCESS;
+    }
     let mut mips_con_env = mips_constraints::Env::<Fp>::default();
     // The keccak environment is extracted inside the loop
 
@@ -127,6 +154,10 @@ pub fn main() -> ExitCode {
 
     while !mips_wit_env.halt {
         let instr = mips_wit_env.step(&configuration, &meta, &start);
+        if let Some(e) = &mips_wit_env.preimage_oracle_error {
+            error!("The execution stopped on an error of the preimage oracle: {e}");
+            return ExitCode::FAILURE;
+        }
 
         if let Some(ref mut keccak_env) = mips_wit_env.keccak_env {
             // Run all steps of hash
@@ -183,6 +214,27 @@ pub fn main() -> ExitCode {
         }
     }
 
+    if let (Some(prefix), Some(profiler)) = (&configuration.profile, &mips_wit_env.profiler) {
+        profiler
+            .write_reports(prefix)
+            .unwrap_or_else(|e| panic!("Could not write the reports of the profiler: {e}"));
+        debug!("Profiler reports written to {prefix}.*.folded");
+    }
+
+    if let (Some(path), Some(statistics)) = (&configuration.statistics, &mips_wit_env.statistics) {
+        statistics
+            .write_report_file(path)
+            .unwrap_or_else(|e| panic!("Could not write the statistics to {path}: {e}"));
+        debug!("Statistics written to {path}");
+    }
+
+    if let (Some(path), Some(recording)) = (&configuration.record, &mips_wit_env.recording) {
+        recording
+            .to_file(path)
+            .unwrap_or_else(|e| panic!("Could not write the trace file {path}: {e}"));
+        debug!("Execution trace written to {path}");
+    }
+
     // Pad any possible remaining rows if the execution was not a multiple of the domain size
     for instr in Instruction::iter().flat_map(|x| x.into_iter()) {
         // Start by padding with the first row
@@ -323,7 +323,7 @@ pub mod mips {
         let one = (folding_instance_one, folding_witness_one);
         let two = (folding_instance_two, folding_witness_two);
         let (_relaxed_instance, _relatex_witness) = folding_scheme
-            .fold_instance_witness_pair(one, two, &mut fq_sponge)
+            .fold_instance_witness_pair(one, two, &mut fq_sponge).unwrap()
             .pair();
 
         // FIXME: add IVC
@@ -535,7 +535,7 @@ pub mod keccak {
 
                     // Fold both sides and check the constraints ignoring the selector columns
                     let fout =
-                        scheme.fold_instance_witness_pair(left.clone(), right.clone(), fq_sponge);
+                        scheme.fold_instance_witness_pair(left.clone(), right.clone(), fq_sponge).unwrap();
 
                     // We should always have 0 as the degree of the constraints,
                     // without selectors, they are never higher than 2 in Keccak.
@@ -558,7 +558,7 @@ pub mod keccak {
                         right.clone(),
                         step,
                         fq_sponge,
-                    );
+                    ).unwrap();
 
                     let extra_cols = scheme.get_number_of_additional_columns();
                     if let Some(quadri_cols) = quadri_cols {
@@ -626,7 +626,7 @@ pub mod keccak {
                             keccak_trace[1].to_folding_pair(steps.0, fq_sponge, domain, &srs),
                             Some(steps.0),
                             fq_sponge,
-                        );
+                        ).unwrap();
                         let checker = ExtendedProvider::<KeccakConfig>::new(
                             fout.folded_instance,
                             fout.folded_witness,
@@ -639,14 +639,14 @@ pub mod keccak {
                             keccak_trace[1].to_folding_pair(steps.1, fq_sponge, domain, &srs),
                             Some(steps.1),
                             fq_sponge,
-                        );
+                        ).unwrap();
                         let checker = ExtendedProvider::<KeccakConfig>::new(
                             fout.folded_instance,
                             fout.folded_witness,
                         );
                         (checker.instance, checker.witness)
                     };
-                    let fout = dec_scheme.fold_instance_witness_pair(left, right, None, fq_sponge);
+                    let fout = dec_scheme.fold_instance_witness_pair(left, right, None, fq_sponge).unwrap();
                     let checker = ExtendedProvider::new(fout.folded_instance, fout.folded_witness);
                     checker.check(&dec_final_constraint, domain);
                 };
@@ -7,6 +7,12 @@ pub mod cannon_cli;
 /// A module to load ELF files.
 pub mod elf_loader;
 
+/// A stub of the GDB remote serial protocol to debug the programs.
+pub mod gdbstub;
+
+/// Programmatic hooks on the execution of the programs.
+pub mod hooks;
+
 pub mod interpreters;
 
 /// Legacy implementation of the recursive proof composition.
@@ -20,12 +26,27 @@ pub mod pickles;
 /// Instantiation of the lookups for the VM project.
 pub mod lookups;
 
+/// The memory consistency argument.
+pub mod memory_argument;
+
+/// Merkle tree of the memory pages.
+pub mod memory_tree;
+
 /// Preimage oracle interface used by the zkVM.
 pub mod preimage_oracle;
 
+/// Profiler of the programs executed by the VM.
+pub mod profiler;
+
 /// The RAM lookup argument.
 pub mod ramlookup;
 
+/// Record and replay of the executions.
+pub mod replay;
+
+/// Statistics of the instructions executed by the VM.
+pub mod statistics;
+
 pub mod utils;
 
 use kimchi::circuits::{
@@ -51,6 +51,10 @@ pub enum LookupTableIDs {
     SyscallLookup = 9,
     /// Input/Output of Keccak steps
     KeccakStepLookup = 10,
+    /// Accesses to the memory, in the order of the execution, written by the
+    /// interpreter and read by the memory argument, see
+    /// [crate::memory_argument]
+    MemoryAccessLookup = 11,
 }
 
 impl LookupTableID for LookupTableIDs {
@@ -71,6 +75,7 @@ impl LookupTableID for LookupTableIDs {
             8 => RegisterLookup,
             9 => SyscallLookup,
             10 => KeccakStepLookup,
+            11 => MemoryAccessLookup,
             _ => panic!("Invalid table ID"),
         }
     }
@@ -82,7 +87,8 @@ impl LookupTableID for LookupTableIDs {
             AtMost4Lookup => 5,
             ByteLookup => 1 << 8,
             RangeCheck16Lookup | SparseLookup | ResetLookup => 1 << 16,
-            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup => {
+            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup
+            | MemoryAccessLookup => {
                 panic!("RAM Tables do not have a fixed length")
             }
         }
@@ -92,7 +98,8 @@ impl LookupTableID for LookupTableIDs {
         match self {
             PadLookup | RoundConstantsLookup | AtMost4Lookup | ByteLookup | RangeCheck16Lookup
             | SparseLookup | ResetLookup => true,
-            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup => false,
+            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup
+            | MemoryAccessLookup => false,
         }
     }
 
@@ -117,6 +124,7 @@ impl LookupTableID for LookupTableIDs {
             Self::RegisterLookup,
             Self::SyscallLookup,
             Self::KeccakStepLookup,
+            Self::MemoryAccessLookup,
         ]
     }
 }
@@ -181,7 +189,8 @@ impl<F: Field> FixedLookupTables<F> for LookupTable<F> {
                     None
                 }
             }
-            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup => None,
+            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup
+            | MemoryAccessLookup => None,
         }
     }
 
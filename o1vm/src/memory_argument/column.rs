@@ -0,0 +1,74 @@
+use kimchi_msm::columns::{Column, ColumnIndexer};
+
+/// Number of bytes of the gap between two consecutive accesses of the sorted
+/// trace.
+pub const N_GAP_LIMBS: usize = 8;
+
+/// Number of relation columns: 4 for the access of the execution trace, 4 for
+/// the access of the sorted trace, 2 flags and the limbs of the gap.
+pub const N_MEMORY_REL_COLS: usize = 4 + 4 + 2 + N_GAP_LIMBS;
+
+/// Number of fixed selectors.
+pub const N_MEMORY_FSEL_COLS: usize = 1;
+
+/// Total number of columns, without the columns of the lookup argument.
+pub const N_MEMORY_COLS: usize = N_MEMORY_REL_COLS + N_MEMORY_FSEL_COLS;
+
+/// The columns of the memory argument. Each row contains one access of the
+/// execution trace, in the order of execution, and one access of the sorted
+/// trace, sorted by address and then by time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MemoryColumn {
+    /// Address of the access of the execution trace
+    ExecutionAddress,
+    /// Time of the access of the execution trace
+    ExecutionTime,
+    /// Value at the address before the access of the execution trace
+    ExecutionOldValue,
+    /// Value at the address after the access of the execution trace
+    ExecutionNewValue,
+    /// Address of the access of the sorted trace
+    Address,
+    /// Time of the access of the sorted trace
+    Time,
+    /// Value at the address before the access of the sorted trace
+    OldValue,
+    /// Value at the address after the access of the sorted trace
+    NewValue,
+    /// Whether the row contains an access or is padding
+    IsReal,
+    /// Whether the access of the sorted trace has the same address as the
+    /// access of the previous row
+    SameAddress,
+    /// The bytes, in little-endian, of the gap between the access of the
+    /// sorted trace and the access of the previous row, minus one: the gap
+    /// between the times if the address is the same, the gap between the
+    /// addresses otherwise
+    Gap(usize),
+    /// Fixed selector of the last row of the domain
+    IsLastRow,
+}
+
+impl ColumnIndexer for MemoryColumn {
+    const N_COL: usize = N_MEMORY_COLS;
+
+    fn to_column(self) -> Column {
+        match self {
+            MemoryColumn::ExecutionAddress => Column::Relation(0),
+            MemoryColumn::ExecutionTime => Column::Relation(1),
+            MemoryColumn::ExecutionOldValue => Column::Relation(2),
+            MemoryColumn::ExecutionNewValue => Column::Relation(3),
+            MemoryColumn::Address => Column::Relation(4),
+            MemoryColumn::Time => Column::Relation(5),
+            MemoryColumn::OldValue => Column::Relation(6),
+            MemoryColumn::NewValue => Column::Relation(7),
+            MemoryColumn::IsReal => Column::Relation(8),
+            MemoryColumn::SameAddress => Column::Relation(9),
+            MemoryColumn::Gap(i) => {
+                assert!(i < N_GAP_LIMBS);
+                Column::Relation(10 + i)
+            }
+            MemoryColumn::IsLastRow => Column::FixedSelector(0),
+        }
+    }
+}
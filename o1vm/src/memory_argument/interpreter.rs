@@ -0,0 +1,142 @@
+use super::{
+    column::{MemoryColumn, N_GAP_LIMBS},
+    lookups::MemoryLookupTable,
+    MemoryAccess,
+};
+use crate::E;
+use ark_ff::{One, PrimeField, Zero};
+use kimchi_msm::{
+    circuit_design::{ColAccessCap, ColWriteCap, LookupCap},
+    columns::ColumnIndexer,
+    expr::{curr_cell, next_cell},
+};
+
+/// Constrains a row of the memory argument:
+/// - the flags are boolean,
+/// - the limbs of the gap are bytes,
+/// - the access of the sorted trace is one of the accesses of the execution
+///   trace, and the other way around.
+///
+/// The flag [MemoryColumn::IsReal] is part of the accesses looked up, so that
+/// the padding rows cannot be matched with actual accesses.
+pub fn constrain_memory_row<F, Env>(env: &mut Env)
+where
+    F: PrimeField,
+    Env: ColAccessCap<F, MemoryColumn> + LookupCap<F, MemoryColumn, MemoryLookupTable>,
+{
+    let is_real = env.read_column(MemoryColumn::IsReal);
+    let same_address = env.read_column(MemoryColumn::SameAddress);
+    env.assert_zero(is_real.clone() * (Env::constant(F::one()) - is_real.clone()));
+    env.assert_zero(same_address.clone() * (Env::constant(F::one()) - same_address));
+
+    for i in 0..N_GAP_LIMBS {
+        let limb = env.read_column(MemoryColumn::Gap(i));
+        env.lookup(MemoryLookupTable::Byte, vec![limb]);
+    }
+
+    let execution_access = vec![
+        is_real.clone(),
+        env.read_column(MemoryColumn::ExecutionAddress),
+        env.read_column(MemoryColumn::ExecutionTime),
+        env.read_column(MemoryColumn::ExecutionOldValue),
+        env.read_column(MemoryColumn::ExecutionNewValue),
+    ];
+    let sorted_access = vec![
+        is_real,
+        env.read_column(MemoryColumn::Address),
+        env.read_column(MemoryColumn::Time),
+        env.read_column(MemoryColumn::OldValue),
+        env.read_column(MemoryColumn::NewValue),
+    ];
+    env.lookup_runtime_write(
+        MemoryLookupTable::ExecutionAccesses,
+        execution_access.clone(),
+    );
+    env.lookup(MemoryLookupTable::ExecutionAccesses, sorted_access.clone());
+    env.lookup_runtime_write(MemoryLookupTable::SortedAccesses, sorted_access);
+    env.lookup(MemoryLookupTable::SortedAccesses, execution_access);
+}
+
+/// The constraints between two consecutive rows of the sorted trace, except
+/// from the last row of the domain to the first one:
+/// - the actual accesses come first, followed by the padding,
+/// - if the next access is at the same address, its time is greater and its
+///   old value is the new value of the current access,
+/// - otherwise, its address is greater.
+///
+/// The accesses of the sorted trace are therefore distinct, and as they are
+/// all accesses of the execution trace and the other way around, the sorted
+/// trace is a permutation of the execution trace.
+pub fn transition_constraints<F: PrimeField>() -> Vec<E<F>> {
+    let curr = |column: MemoryColumn| curr_cell::<F>(column.to_column());
+    let next = |column: MemoryColumn| next_cell::<F>(column.to_column());
+
+    let not_last_row = E::one() - curr(MemoryColumn::IsLastRow);
+    let is_real_next = next(MemoryColumn::IsReal);
+    let same_address_next = next(MemoryColumn::SameAddress);
+    let gap_next = (0..N_GAP_LIMBS).rev().fold(E::zero(), |acc, i| {
+        acc * E::from(1u64 << 8) + next(MemoryColumn::Gap(i))
+    });
+    let transition = not_last_row.clone() * is_real_next.clone();
+
+    vec![
+        // The padding rows are at the end
+        not_last_row * (E::one() - curr(MemoryColumn::IsReal)) * is_real_next,
+        // The address does not change
+        transition.clone()
+            * same_address_next.clone()
+            * (next(MemoryColumn::Address) - curr(MemoryColumn::Address)),
+        // The old value of an access is the new value of the previous one
+        transition.clone()
+            * same_address_next.clone()
+            * (next(MemoryColumn::OldValue) - curr(MemoryColumn::NewValue)),
+        // The time increases at the same address, the address increases
+        // otherwise
+        transition
+            * (gap_next
+                - same_address_next.clone()
+                    * (next(MemoryColumn::Time) - curr(MemoryColumn::Time) - E::one())
+                - (E::one() - same_address_next)
+                    * (next(MemoryColumn::Address) - curr(MemoryColumn::Address) - E::one())),
+    ]
+}
+
+/// Writes a row of the memory argument, given the access of the execution
+/// trace, the access of the sorted trace and the access of the sorted trace on
+/// the previous row. The row is padding if there is no access.
+pub fn write_memory_row<F, Env>(
+    env: &mut Env,
+    execution_access: Option<&MemoryAccess>,
+    sorted_access: Option<&MemoryAccess>,
+    previous_sorted_access: Option<&MemoryAccess>,
+) where
+    F: PrimeField,
+    Env: ColWriteCap<F, MemoryColumn>,
+{
+    let mut write = |column, value: u64| env.write_column(column, &Env::constant(F::from(value)));
+    let (execution_access, sorted_access) = match (execution_access, sorted_access) {
+        (Some(execution_access), Some(sorted_access)) => (execution_access, sorted_access),
+        (None, None) => return,
+        _ => panic!("The execution and the sorted traces must have the same length"),
+    };
+    write(MemoryColumn::ExecutionAddress, execution_access.addr);
+    write(MemoryColumn::ExecutionTime, execution_access.time);
+    write(MemoryColumn::ExecutionOldValue, execution_access.old_value);
+    write(MemoryColumn::ExecutionNewValue, execution_access.new_value);
+    write(MemoryColumn::Address, sorted_access.addr);
+    write(MemoryColumn::Time, sorted_access.time);
+    write(MemoryColumn::OldValue, sorted_access.old_value);
+    write(MemoryColumn::NewValue, sorted_access.new_value);
+    write(MemoryColumn::IsReal, 1);
+    let (same_address, gap) = match previous_sorted_access {
+        Some(previous) if previous.addr == sorted_access.addr => {
+            (1, sorted_access.time - previous.time - 1)
+        }
+        Some(previous) => (0, sorted_access.addr - previous.addr - 1),
+        None => (0, 0),
+    };
+    write(MemoryColumn::SameAddress, same_address);
+    for (i, byte) in gap.to_le_bytes().into_iter().enumerate() {
+        write(MemoryColumn::Gap(i), byte as u64);
+    }
+}
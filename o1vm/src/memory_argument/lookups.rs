@@ -0,0 +1,88 @@
+use ark_ff::PrimeField;
+use kimchi::o1_utils::FieldHelpers;
+use kimchi_msm::LookupTableID;
+
+/// The lookup tables of the memory argument
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum MemoryLookupTable {
+    /// Fixed table of the values in [0, 2^8), used to range check the gaps
+    Byte,
+    /// Runtime table written with the accesses of the execution trace, and
+    /// read with the accesses of the sorted trace
+    ExecutionAccesses,
+    /// Runtime table written with the accesses of the sorted trace, and read
+    /// with the accesses of the execution trace
+    SortedAccesses,
+}
+
+impl LookupTableID for MemoryLookupTable {
+    fn to_u32(&self) -> u32 {
+        match self {
+            Self::Byte => 1,
+            Self::ExecutionAccesses => 2,
+            Self::SortedAccesses => 3,
+        }
+    }
+
+    fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::Byte,
+            2 => Self::ExecutionAccesses,
+            3 => Self::SortedAccesses,
+            _ => panic!("Invalid lookup table id {value}"),
+        }
+    }
+
+    fn is_fixed(&self) -> bool {
+        matches!(self, Self::Byte)
+    }
+
+    fn runtime_create_column(&self) -> bool {
+        match self {
+            Self::ExecutionAccesses | Self::SortedAccesses => false,
+            Self::Byte => panic!("runtime_create_column was called on a non-runtime table"),
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            Self::Byte => 1 << 8,
+            Self::ExecutionAccesses | Self::SortedAccesses => {
+                panic!("The length of a runtime table is not fixed")
+            }
+        }
+    }
+
+    fn ix_by_value<F: PrimeField>(&self, value: &[F]) -> Option<usize> {
+        match self {
+            Self::Byte => {
+                let index: u64 = value[0].to_biguint().try_into().unwrap();
+                assert!(index < 1 << 8, "{index} is not a byte");
+                Some(index as usize)
+            }
+            Self::ExecutionAccesses | Self::SortedAccesses => None,
+        }
+    }
+
+    fn all_variants() -> Vec<Self> {
+        vec![Self::Byte, Self::ExecutionAccesses, Self::SortedAccesses]
+    }
+}
+
+impl MemoryLookupTable {
+    /// The entries of the fixed table, padded with zeros up to the size of
+    /// the domain.
+    pub fn entries<F: PrimeField>(&self, domain_size: u64) -> Vec<F> {
+        match self {
+            Self::Byte => {
+                assert!(domain_size >= 1 << 8);
+                (0..domain_size)
+                    .map(|i| if i < 1 << 8 { F::from(i) } else { F::zero() })
+                    .collect()
+            }
+            Self::ExecutionAccesses | Self::SortedAccesses => {
+                panic!("The entries of a runtime table are given by the witness")
+            }
+        }
+    }
+}
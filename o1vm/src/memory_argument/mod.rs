@@ -0,0 +1,212 @@
+//! A read/write memory consistency argument based on an address-sorted trace
+//! and log-derivative lookups.
+//!
+//! The accesses to the memory are given in the order of the execution, as
+//! [MemoryAccess]es with the value at the address before and after the
+//! access (both are equal for a read). The prover commits to the same
+//! accesses sorted by address and then by time, and proves that:
+//! - the sorted trace is a permutation of the execution trace. Each trace is
+//!   written in a runtime lookup table and read from the other one, and the
+//!   accesses of the sorted trace are distinct (see below), so the inclusions
+//!   in both directions imply the permutation,
+//! - the accesses of the sorted trace are strictly increasing, by checking
+//!   that the gap to the previous access is in `[0, 2^64)` with 8 byte
+//!   lookups,
+//! - the old value of an access is the new value of the previous access to the
+//!   same address.
+//!
+//! The old value of the first access to an address is the initial value of
+//! the memory at this address, and the new value of the last one its final
+//! value. They are not constrained by the argument and must be checked
+//! against the initial and final memory.
+//!
+//! The execution trace is the sequence of the accesses of the interpreter.
+//! Each access to the memory writes its entry [MemoryAccess::table_entry] in
+//! the table [LookupTableIDs::MemoryAccessLookup] (see
+//! [InterpreterEnv::access_memory](crate::interpreters::mips::interpreter::InterpreterEnv::access_memory)),
+//! and [execution_trace_lookups] reads each access of the execution trace of
+//! the argument in the same table. The lookups balance only if the execution
+//! trace of the argument is the one of the interpreter.
+//!
+//! ## Column cost
+//!
+//! Each access takes one row. The argument uses:
+//! - [N_MEMORY_REL_COLS](column::N_MEMORY_REL_COLS) = 18 relation columns:
+//!   4 for the execution trace, 4 for the sorted trace, 2 flags and the 8
+//!   bytes of the gap,
+//! - 1 fixed selector for the last row,
+//! - 9 columns for the lookup argument: 3 multiplicities, one per table,
+//!   4 partial sums (2 for the 8 byte lookups and the fixed table, 1 per
+//!   runtime table), the aggregation, and the fixed byte table.
+//!
+//! That is 27 committed columns per proof, for one access per row.
+
+pub mod column;
+pub mod interpreter;
+pub mod lookups;
+
+use self::{
+    column::{MemoryColumn, N_MEMORY_FSEL_COLS, N_MEMORY_REL_COLS},
+    interpreter::{constrain_memory_row, transition_constraints, write_memory_row},
+    lookups::MemoryLookupTable,
+};
+use crate::{lookups::LookupTableIDs, ramlookup::RAMLookup, E};
+use ark_ff::{Field, PrimeField};
+use kimchi_msm::{
+    circuit_design::{ConstraintBuilderEnv, WitnessBuilderEnv},
+    proof::ProofInputs,
+};
+use std::collections::BTreeMap;
+
+/// An access to the memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MemoryAccess {
+    pub addr: u64,
+    /// The time of the access, distinct for all the accesses to an address
+    pub time: u64,
+    /// The value at `addr` before the access
+    pub old_value: u64,
+    /// The value at `addr` after the access, equal to `old_value` for a read
+    pub new_value: u64,
+}
+
+impl MemoryAccess {
+    /// The entry of the access in the table
+    /// [LookupTableIDs::MemoryAccessLookup]: the address, the time, and the
+    /// values before and after the access.
+    pub fn table_entry<F: Field>(&self) -> Vec<F> {
+        [self.addr, self.time, self.old_value, self.new_value]
+            .into_iter()
+            .map(F::from)
+            .collect()
+    }
+
+    /// The access described by an entry of the table
+    /// [LookupTableIDs::MemoryAccessLookup], see [MemoryAccess::table_entry].
+    pub fn from_table_entry(entry: &[u64]) -> Self {
+        let [addr, time, old_value, new_value]: [u64; 4] = entry
+            .try_into()
+            .expect("An entry of the memory accesses contains 4 values");
+        Self {
+            addr,
+            time,
+            old_value,
+            new_value,
+        }
+    }
+}
+
+pub type MemoryWitnessBuilderEnv<F> = WitnessBuilderEnv<
+    F,
+    MemoryColumn,
+    N_MEMORY_REL_COLS,
+    N_MEMORY_REL_COLS,
+    0,
+    N_MEMORY_FSEL_COLS,
+    MemoryLookupTable,
+>;
+
+/// Sorts the accesses by address and then by time.
+/// Panics if two accesses to the same address happen at the same time.
+pub fn sort_accesses(accesses: &[MemoryAccess]) -> Vec<MemoryAccess> {
+    let mut sorted = accesses.to_vec();
+    sorted.sort_by_key(|access| (access.addr, access.time));
+    for pair in sorted.windows(2) {
+        assert!(
+            (pair[0].addr, pair[0].time) != (pair[1].addr, pair[1].time),
+            "Two accesses to the address {} at the time {}",
+            pair[0].addr,
+            pair[0].time
+        );
+    }
+    sorted
+}
+
+/// The constraints of the memory argument, including the constraints of the
+/// lookup argument.
+pub fn constraints<F: PrimeField>() -> Vec<E<F>> {
+    let mut env = ConstraintBuilderEnv::<F, MemoryLookupTable>::create();
+    constrain_memory_row(&mut env);
+    let mut constraints = env.get_constraints();
+    constraints.extend(transition_constraints());
+    constraints
+}
+
+/// The fixed selectors of the memory argument for a domain of size
+/// `domain_size`.
+pub fn fixed_selectors<F: PrimeField>(domain_size: usize) -> Box<[Vec<F>; N_MEMORY_FSEL_COLS]> {
+    let is_last_row = (0..domain_size)
+        .map(|i| {
+            if i == domain_size - 1 {
+                F::one()
+            } else {
+                F::zero()
+            }
+        })
+        .collect();
+    Box::new([is_last_row])
+}
+
+/// Builds the witness of the memory argument for the accesses `accesses`,
+/// given in the order of the execution. The domain must have at least 2^8
+/// rows, for the byte table, and as many rows as accesses.
+pub fn build_witness<F: PrimeField>(
+    accesses: &[MemoryAccess],
+    domain_size: usize,
+) -> MemoryWitnessBuilderEnv<F> {
+    assert!(
+        accesses.len() <= domain_size,
+        "{} accesses do not fit in a domain of size {domain_size}",
+        accesses.len()
+    );
+    let sorted = sort_accesses(accesses);
+    let mut env = MemoryWitnessBuilderEnv::create();
+    env.set_fixed_selectors(fixed_selectors(domain_size).to_vec());
+    for i in 0..domain_size {
+        let previous = i.checked_sub(1).and_then(|i| sorted.get(i));
+        write_memory_row(&mut env, accesses.get(i), sorted.get(i), previous);
+        constrain_memory_row(&mut env);
+        if i < domain_size - 1 {
+            env.next_row();
+        }
+    }
+    env
+}
+
+// FIXME: the lookups of the VM, including its RAM tables, are not proven yet
+// by the proof system of the VM, see [crate::pickles]. Until they are, these
+// reads are not checked against the writes of the interpreter.
+/// The reads of the accesses of the execution trace `accesses` in the table
+/// [LookupTableIDs::MemoryAccessLookup]. They balance the writes of the
+/// interpreter only if `accesses` are the accesses of the execution.
+pub fn execution_trace_lookups<F: Field>(
+    accesses: &[MemoryAccess],
+) -> Vec<RAMLookup<F, LookupTableIDs>> {
+    accesses
+        .iter()
+        .map(|access| RAMLookup::read_one(LookupTableIDs::MemoryAccessLookup, access.table_entry()))
+        .collect()
+}
+
+/// The inputs of the proof of the memory argument for the accesses
+/// `accesses`, see [build_witness].
+pub fn proof_inputs<F: PrimeField>(
+    accesses: &[MemoryAccess],
+    domain_size: usize,
+) -> ProofInputs<N_MEMORY_REL_COLS, F, MemoryLookupTable> {
+    let env = build_witness(accesses, domain_size);
+    let mut lookup_tables_data: BTreeMap<MemoryLookupTable, Vec<Vec<Vec<F>>>> =
+        env.get_runtime_tables(domain_size);
+    lookup_tables_data.insert(
+        MemoryLookupTable::Byte,
+        vec![MemoryLookupTable::Byte
+            .entries(domain_size as u64)
+            .into_iter()
+            .map(|x| vec![x])
+            .collect()],
+    );
+    env.get_proof_inputs(domain_size, lookup_tables_data)
+}
+
+#[cfg(test)]
+mod tests;
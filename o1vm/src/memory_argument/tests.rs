@@ -0,0 +1,167 @@
+use super::{
+    column::{N_MEMORY_FSEL_COLS, N_MEMORY_REL_COLS},
+    constrain_memory_row, constraints, execution_trace_lookups, fixed_selectors,
+    lookups::MemoryLookupTable,
+    proof_inputs, MemoryAccess,
+};
+use crate::{
+    interpreters::mips::{interpreter::InterpreterEnv, tests_helpers::dummy_env},
+    legacy::{BaseSponge, Fp, OpeningProof, ScalarSponge},
+    lookups::LookupTableIDs,
+    ramlookup::LookupMode,
+};
+use kimchi::circuits::domains::EvaluationDomains;
+use kimchi_msm::{
+    circuit_design::ConstraintBuilderEnv,
+    prover::{prove, ProverError},
+    verifier::verify,
+    witness::Witness,
+};
+use o1_utils::tests::make_test_rng;
+use rand::Rng;
+
+const DOMAIN_SIZE: usize = 1 << 8;
+
+fn prove_and_verify(accesses: &[MemoryAccess]) -> Result<bool, ProverError> {
+    let mut rng = make_test_rng(None);
+    let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+    let srs = kimchi_msm::precomputed_srs::get_bn254_srs(domain);
+    let constraints = constraints::<Fp>();
+    let proof = prove::<
+        _,
+        OpeningProof,
+        BaseSponge,
+        ScalarSponge,
+        _,
+        N_MEMORY_REL_COLS,
+        N_MEMORY_REL_COLS,
+        0,
+        N_MEMORY_FSEL_COLS,
+        MemoryLookupTable,
+    >(
+        domain,
+        &srs,
+        &constraints,
+        fixed_selectors(DOMAIN_SIZE),
+        proof_inputs(accesses, DOMAIN_SIZE),
+        &mut rng,
+    )?;
+    Ok(verify::<
+        _,
+        OpeningProof,
+        BaseSponge,
+        ScalarSponge,
+        N_MEMORY_REL_COLS,
+        N_MEMORY_REL_COLS,
+        0,
+        N_MEMORY_FSEL_COLS,
+        0,
+        MemoryLookupTable,
+    >(
+        domain,
+        &srs,
+        &constraints,
+        fixed_selectors(DOMAIN_SIZE),
+        &proof,
+        Witness::zero_vec(DOMAIN_SIZE),
+    ))
+}
+
+/// Random consistent accesses to a few addresses
+fn random_accesses<RNG: Rng>(rng: &mut RNG, n: usize) -> Vec<MemoryAccess> {
+    let mut memory = [0u64; 8];
+    (0..n)
+        .map(|i| {
+            let addr = rng.gen_range(0..memory.len());
+            let old_value = memory[addr];
+            if rng.gen_bool(0.5) {
+                memory[addr] = rng.gen_range(0..256);
+            }
+            MemoryAccess {
+                addr: 0x1000 + addr as u64,
+                time: 3 * i as u64,
+                old_value,
+                new_value: memory[addr],
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_memory_argument_column_cost() {
+    let mut env = ConstraintBuilderEnv::<Fp, MemoryLookupTable>::create();
+    constrain_memory_row(&mut env);
+    // The 2 boolean flags
+    assert_eq!(env.get_relation_constraints().len(), 2);
+    // 2 partial sums for the byte table, 1 per runtime table, and the
+    // aggregation
+    assert_eq!(env.get_lookup_constraints().len(), 5);
+    // The 4 constraints between consecutive rows
+    assert_eq!(constraints::<Fp>().len(), 2 + 5 + 4);
+    assert_eq!(N_MEMORY_REL_COLS, 18);
+}
+
+#[test]
+fn test_memory_argument_completeness() {
+    let mut rng = make_test_rng(None);
+    let accesses = random_accesses(&mut rng, DOMAIN_SIZE - 10);
+    assert_eq!(
+        prove_and_verify(&accesses).map_err(|e| e.to_string()),
+        Ok(true)
+    );
+    // Without padding
+    let accesses = random_accesses(&mut rng, DOMAIN_SIZE);
+    assert_eq!(
+        prove_and_verify(&accesses).map_err(|e| e.to_string()),
+        Ok(true)
+    );
+}
+
+#[test]
+fn test_memory_argument_inconsistent_read() {
+    let mut rng = make_test_rng(None);
+    let mut accesses = random_accesses(&mut rng, 100);
+    // A later access to the same address reads another value
+    let addr = accesses[10].addr;
+    let next = accesses[11..]
+        .iter_mut()
+        .find(|access| access.addr == addr)
+        .unwrap();
+    next.old_value = (next.old_value + 1) % 256;
+    assert!(matches!(
+        prove_and_verify(&accesses),
+        Err(ProverError::ConstraintNotSatisfied(_))
+    ));
+}
+
+#[test]
+fn test_memory_argument_of_mips_accesses() {
+    let mut rng = make_test_rng(None);
+    let mut env = dummy_env(&mut rng);
+    env.memory_accesses = Some(vec![]);
+    for _ in 0..20 {
+        let addr = rng.gen_range(0..64u64);
+        if rng.gen_bool(0.5) {
+            env.write_memory(&addr, rng.gen_range(0..256));
+        } else {
+            env.read_memory(&addr);
+        }
+        env.reset_scratch_state();
+        env.reset_scratch_state_inverse();
+    }
+    let accesses = env.memory_accesses.take().unwrap();
+    assert_eq!(accesses.len(), 20);
+    // The execution trace is read from the entries written by the interpreter
+    execution_trace_lookups::<Fp>(&accesses)
+        .iter()
+        .zip(accesses.iter())
+        .for_each(|(lookup, access)| {
+            assert_eq!(lookup.table_id, LookupTableIDs::MemoryAccessLookup);
+            assert!(matches!(lookup.mode, LookupMode::Read));
+            assert_eq!(lookup.value, access.table_entry::<Fp>());
+        });
+    assert_eq!(
+        prove_and_verify(&accesses).map_err(|e| e.to_string()),
+        Ok(true)
+    );
+}
@@ -0,0 +1,194 @@
+//! Merkle tree of the memory of the VM, with one leaf per page.
+//!
+//! The tree is a sparse Keccak256 Merkle tree of fixed depth
+//! [MEMORY_TREE_DEPTH], covering all the pages of the 32 bits address space.
+//! The leaf of a page is the hash of its data, and the pages that have not
+//! been allocated are the same as the pages filled with zeros. The root of
+//! the tree therefore only depends on the content of the memory.
+//!
+//! The root commits to the memory of the VM, so that external systems can
+//! provide an initial memory image by its root, and audit the pages of the
+//! final memory with a [PageProof], without replaying the execution. The
+//! circuit does not constrain yet the memory to the root.
+
+use crate::cannon::{Page, PAGE_ADDRESS_SIZE, PAGE_SIZE};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::collections::BTreeMap;
+
+/// Depth of the tree, i.e. the number of bits of a page index.
+pub const MEMORY_TREE_DEPTH: usize = (32 - PAGE_ADDRESS_SIZE) as usize;
+
+/// Hash of the data of a page, i.e. a leaf of the tree.
+pub fn page_hash(data: &[u8]) -> [u8; 32] {
+    assert_eq!(data.len(), PAGE_SIZE as usize, "Invalid page size");
+    Keccak256::digest(data).into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The hashes of the subtrees of each level containing only zero pages, from
+/// the leaves to the root.
+fn zero_hashes() -> [[u8; 32]; MEMORY_TREE_DEPTH + 1] {
+    let mut hashes = [[0; 32]; MEMORY_TREE_DEPTH + 1];
+    hashes[0] = page_hash(&[0; PAGE_SIZE as usize]);
+    for level in 1..=MEMORY_TREE_DEPTH {
+        hashes[level] = node_hash(&hashes[level - 1], &hashes[level - 1]);
+    }
+    hashes
+}
+
+/// The Merkle tree of a memory. Only the nodes above non-zero pages are
+/// stored.
+#[derive(Clone, Debug)]
+pub struct MemoryTree {
+    /// The nodes of each level that are not the root of a zero subtree,
+    /// indexed by their position in the level, from the leaves to the root
+    levels: Vec<BTreeMap<u32, [u8; 32]>>,
+    zero_hashes: [[u8; 32]; MEMORY_TREE_DEPTH + 1],
+}
+
+impl MemoryTree {
+    /// Builds the tree of the memory given by its pages.
+    pub fn new(pages: &[Page]) -> Self {
+        Self::from_pages(pages.iter().map(|page| (page.index, page.data.as_slice())))
+    }
+
+    /// Builds the tree of the memory given by its pages as pairs of an index
+    /// and the data, as in the witness environment of the MIPS interpreter.
+    pub fn from_pages<'a>(pages: impl IntoIterator<Item = (u32, &'a [u8])>) -> Self {
+        let zero_hashes = zero_hashes();
+        let mut levels = vec![BTreeMap::new(); MEMORY_TREE_DEPTH + 1];
+        for (index, data) in pages {
+            assert!(
+                (index as usize) < 1 << MEMORY_TREE_DEPTH,
+                "Invalid page index {index}"
+            );
+            let hash = page_hash(data);
+            if hash != zero_hashes[0] {
+                levels[0].insert(index, hash);
+            }
+        }
+        for level in 1..=MEMORY_TREE_DEPTH {
+            let parents: BTreeMap<u32, [u8; 32]> = levels[level - 1]
+                .keys()
+                .map(|position| position / 2)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|parent| {
+                    let child = |position| {
+                        levels[level - 1]
+                            .get(&position)
+                            .unwrap_or(&zero_hashes[level - 1])
+                    };
+                    (parent, node_hash(child(2 * parent), child(2 * parent + 1)))
+                })
+                .collect();
+            levels[level] = parents;
+        }
+        MemoryTree {
+            levels,
+            zero_hashes,
+        }
+    }
+
+    fn node(&self, level: usize, position: u32) -> [u8; 32] {
+        *self.levels[level]
+            .get(&position)
+            .unwrap_or(&self.zero_hashes[level])
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.node(MEMORY_TREE_DEPTH, 0)
+    }
+
+    /// Proves the content of the page `index`, allocated or not.
+    pub fn open(&self, index: u32) -> PageProof {
+        let siblings = (0..MEMORY_TREE_DEPTH)
+            .map(|level| self.node(level, (index >> level) ^ 1))
+            .collect();
+        PageProof { index, siblings }
+    }
+}
+
+/// Proof that a page has a given content in the memory of a given root.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageProof {
+    pub index: u32,
+    /// The siblings of the path from the leaf of the page to the root, from
+    /// the leaf to the root
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl PageProof {
+    /// Checks that the page `self.index` contains `data` in the memory of root
+    /// `root`.
+    pub fn verify(&self, root: &[u8; 32], data: &[u8]) -> bool {
+        if self.siblings.len() != MEMORY_TREE_DEPTH || data.len() != PAGE_SIZE as usize {
+            return false;
+        }
+        let computed_root =
+            self.siblings
+                .iter()
+                .enumerate()
+                .fold(page_hash(data), |hash, (level, sibling)| {
+                    if (self.index >> level) & 1 == 0 {
+                        node_hash(&hash, sibling)
+                    } else {
+                        node_hash(sibling, &hash)
+                    }
+                });
+        &computed_root == root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(index: u32, byte: u8) -> Page {
+        Page {
+            index,
+            data: vec![byte; PAGE_SIZE as usize],
+        }
+    }
+
+    #[test]
+    fn test_memory_tree_root() {
+        // The root only depends on the content of the memory
+        let empty = MemoryTree::new(&[]).root();
+        assert_eq!(empty, zero_hashes()[MEMORY_TREE_DEPTH]);
+        assert_eq!(MemoryTree::new(&[page(7, 0)]).root(), empty);
+        assert_eq!(
+            MemoryTree::new(&[page(1, 1), page(3, 0), page(2, 2)]).root(),
+            MemoryTree::new(&[page(2, 2), page(1, 1)]).root()
+        );
+        assert_ne!(
+            MemoryTree::new(&[page(1, 1)]).root(),
+            MemoryTree::new(&[page(2, 1)]).root()
+        );
+    }
+
+    #[test]
+    fn test_memory_tree_openings() {
+        let pages = [page(0, 1), page(5, 2), page((1 << 20) - 1, 3)];
+        let tree = MemoryTree::new(&pages);
+        let root = tree.root();
+        for page in pages.iter() {
+            assert!(tree.open(page.index).verify(&root, &page.data));
+        }
+        // A page that has not been allocated contains zeros
+        let zeros = vec![0; PAGE_SIZE as usize];
+        assert!(tree.open(4).verify(&root, &zeros));
+        assert!(!tree.open(5).verify(&root, &zeros));
+        assert!(!tree.open(4).verify(&root, &pages[1].data));
+        let mut proof = tree.open(5);
+        proof.index = 4;
+        assert!(!proof.verify(&root, &pages[1].data));
+    }
+}
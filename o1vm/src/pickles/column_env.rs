@@ -1,10 +1,11 @@
 use ark_ff::FftField;
 use ark_poly::{Evaluations, Radix2EvaluationDomain};
 use kimchi_msm::columns::Column;
+use std::ops::Deref;
 
 use crate::{
     interpreters::mips::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
-    pickles::proof::WitnessColumns,
+    pickles::{column_store::StoredEvaluations, proof::WitnessColumns},
 };
 use kimchi::circuits::{
     berkeley_columns::{BerkeleyChallengeTerm, BerkeleyChallenges},
@@ -21,7 +22,7 @@ type Evals<F> = Evaluations<F, Radix2EvaluationDomain<F>>;
 pub struct ColumnEnvironment<'a, F: FftField> {
     /// The witness column polynomials. Includes relation columns and dynamic
     /// selector columns.
-    pub witness: &'a WitnessColumns<Evals<F>, [Evals<F>; N_MIPS_SEL_COLS]>,
+    pub witness: &'a WitnessColumns<StoredEvaluations<F>, [StoredEvaluations<F>; N_MIPS_SEL_COLS]>,
     /// The value `prod_{j != 1} (1 - ω^j)`, used for efficiently
     /// computing the evaluations of the unnormalized Lagrange basis
     /// polynomials.
@@ -95,7 +96,7 @@ impl<'a, F: FftField> TColumnEnvironment<'a, F, BerkeleyChallengeTerm, BerkeleyC
     type Column = Column;
 
     fn get_column(&self, col: &Self::Column) -> Option<&'a Evals<F>> {
-        self.witness.get_column(col)
+        self.witness.get_column(col).map(|evals| evals.deref())
     }
 
     fn get_domain(&self, d: Domain) -> Radix2EvaluationDomain<F> {
@@ -0,0 +1,158 @@
+//! Storage of the evaluations of the witness columns while proving.
+//!
+//! The prover evaluates each column on the domain `d8` to compute the
+//! quotient polynomial (see [super::DEGREE_QUOTIENT_POLYNOMIAL]). These
+//! evaluations are eight times larger than the trace itself, and keeping all
+//! of them in RAM dominates the memory used to prove a chunk.
+//!
+//! With [ColumnStorage::Mapped], the evaluations of each column are moved to
+//! a memory-mapped file as soon as they are computed. The quotient
+//! polynomial is then computed on the mappings, whose pages are loaded by
+//! the kernel when they are accessed, and evicted when the memory is needed,
+//! so that only a window of the columns is in RAM at a given time.
+//! The files are deleted as soon as they are mapped, and the space is
+//! released when the mappings are dropped, i.e. at the end of the proof.
+
+use ark_ff::FftField;
+use ark_poly::{Evaluations, Radix2EvaluationDomain as D};
+use std::{
+    fs::{self, File},
+    io,
+    mem::{self, ManuallyDrop},
+    ops::Deref,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Where the evaluations of the columns are stored while proving
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColumnStorage {
+    #[default]
+    Memory,
+    /// Memory-mapped files in the given directory
+    Mapped(PathBuf),
+}
+
+impl ColumnStorage {
+    /// Moves `evaluations` to the storage
+    pub fn store<F: FftField>(
+        &self,
+        evaluations: Evaluations<F, D<F>>,
+    ) -> io::Result<StoredEvaluations<F>> {
+        match self {
+            ColumnStorage::Memory => Ok(StoredEvaluations::Memory(evaluations)),
+            ColumnStorage::Mapped(directory) => Ok(StoredEvaluations::Mapped(
+                MappedEvaluations::create(directory, &evaluations)?,
+            )),
+        }
+    }
+}
+
+/// Evaluations of a column, read through [Deref] whatever the storage
+#[derive(Debug)]
+pub enum StoredEvaluations<F: FftField> {
+    Memory(Evaluations<F, D<F>>),
+    Mapped(MappedEvaluations<F>),
+}
+
+impl<F: FftField> Deref for StoredEvaluations<F> {
+    type Target = Evaluations<F, D<F>>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            StoredEvaluations::Memory(evaluations) => evaluations,
+            StoredEvaluations::Mapped(evaluations) => evaluations,
+        }
+    }
+}
+
+/// Number of the next file created by [MappedEvaluations::create], to have
+/// distinct names in a process
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// Evaluations of a column in a memory-mapped file, read-only once created.
+#[derive(Debug)]
+pub struct MappedEvaluations<F: FftField> {
+    /// The evaluations, whose vector is the mapping. The vector must never
+    /// be dropped nor reallocated, as it is not owned by the allocator.
+    evaluations: ManuallyDrop<Evaluations<F, D<F>>>,
+    address: *mut libc::c_void,
+    size: usize,
+}
+
+// The mapping is only read after its creation, and is not tied to a thread
+unsafe impl<F: FftField> Send for MappedEvaluations<F> {}
+unsafe impl<F: FftField> Sync for MappedEvaluations<F> {}
+
+impl<F: FftField> MappedEvaluations<F> {
+    /// Copies `evaluations` in a file mapped in memory, created in
+    /// `directory`
+    pub fn create(directory: &Path, evaluations: &Evaluations<F, D<F>>) -> io::Result<Self> {
+        let len = evaluations.evals.len();
+        let size = len * mem::size_of::<F>();
+        if size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot map empty evaluations",
+            ));
+        }
+        let path = directory.join(format!(
+            "o1vm-column-{}-{}",
+            std::process::id(),
+            NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        // The file is only reachable through the mapping
+        fs::remove_file(&path)?;
+        file.set_len(size as u64)?;
+        let address = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if address == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        // The field elements are plain data, and the mapping is aligned on a
+        // page
+        let evals = unsafe {
+            ptr::copy_nonoverlapping(evaluations.evals.as_ptr(), address as *mut F, len);
+            Vec::from_raw_parts(address as *mut F, len, len)
+        };
+        Ok(MappedEvaluations {
+            evaluations: ManuallyDrop::new(Evaluations::from_vec_and_domain(
+                evals,
+                evaluations.domain(),
+            )),
+            address,
+            size,
+        })
+    }
+}
+
+impl<F: FftField> Deref for MappedEvaluations<F> {
+    type Target = Evaluations<F, D<F>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.evaluations
+    }
+}
+
+impl<F: FftField> Drop for MappedEvaluations<F> {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.address, self.size);
+        }
+    }
+}
@@ -1,15 +1,17 @@
 use ark_ff::UniformRand;
 use kimchi::circuits::domains::EvaluationDomains;
+use kimchi::plonk_sponge::FrSponge;
 use kimchi_msm::expr::E;
-use log::debug;
+use log::{debug, error};
 use mina_curves::pasta::VestaParameters;
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
+    FqSponge,
 };
 use o1vm::{
-    cannon::{self, Meta, Start, State},
-    cannon_cli,
+    cannon::{self, Meta, Start, State, VmConfiguration},
+    cannon_cli, gdbstub,
     interpreters::mips::{
         column::N_MIPS_REL_COLS,
         constraints as mips_constraints,
@@ -17,30 +19,31 @@ use o1vm::{
         witness::{self as mips_witness},
         Instruction,
     },
-    pickles::{proof::ProofInputs, prover, verifier},
-    preimage_oracle::PreImageOracle,
+    pickles::{
+        column_store::ColumnStorage, pipeline::prove_chunks_pipelined, proof::ProofInputs,
+        verifier::verify,
+    },
+    preimage_oracle::{create_preimage_oracle, PreImageOracleT},
+    profiler::Profiler,
+    statistics::ExecutionStatistics,
 };
 use poly_commitment::{ipa::SRS, SRS as _};
 use std::{fs::File, io::BufReader, process::ExitCode, time::Instant};
 use strum::IntoEnumIterator;
 
-use mina_curves::pasta::{Fp, Vesta};
+use mina_curves::pasta::{Fp, Fq, Vesta};
 
 pub const DOMAIN_SIZE: usize = 1 << 15;
 
 pub fn main() -> ExitCode {
     let cli = cannon_cli::main_cli();
 
-    let mut rng = rand::thread_rng();
-
     let configuration = cannon_cli::read_configuration(&cli.get_matches());
 
-    let file =
-        File::open(&configuration.input_state_file).expect("Error opening input state file ");
-
-    let reader = BufReader::new(file);
-    // Read the JSON contents of the file as an instance of `State`.
-    let state: State = serde_json::from_reader(reader).expect("Error reading input state file");
+    // Read the contents of the file, in the JSON or the binary format of
+    // Cannon, as an instance of `State`.
+    let state =
+        State::from_file(&configuration.input_state_file).expect("Error reading input state file");
 
     let meta_file = File::open(&configuration.metadata_file).unwrap_or_else(|_| {
         panic!(
@@ -56,8 +59,7 @@ pub fn main() -> ExitCode {
         )
     });
 
-    let mut po = PreImageOracle::create(&configuration.host);
-    let _child = po.start();
+    let (po, _child) = create_preimage_oracle(&configuration);
 
     // Initialize some data used for statistical computations
     let start = Start::create(state.step as usize);
@@ -72,8 +74,39 @@ pub fn main() -> ExitCode {
     };
 
     // Initialize the environments
-    let mut mips_wit_env =
-        mips_witness::Env::<Fp, PreImageOracle>::create(cannon::PAGE_SIZE as usize, state, po);
+    let mut mips_wit_env = mips_witness::Env::<Fp, Box<dyn PreImageOracleT>>::create(
+        cannon::PAGE_SIZE as usize,
+        state,
+        po,
+    );
+    if let Some(e) = &mips_wit_env.preimage_oracle_error {
+        error!("Could not resume the execution: {e}");
+        return ExitCode::FAILURE;
+    }
+    if configuration.profile.is_some() {
+        mips_wit_env.profiler = Some(Profiler::new(&meta));
+    }
+    if configuration.statistics.is_some() {
+        mips_wit_env.statistics = Some(ExecutionStatistics::default());
+    }
+    if configuration.record.is_some() {
+        mips_wit_env.start_recording();
+    }
+    if let Some(symbol) = &configuration.fp_trap_handler {
+        mips_wit_env.registers.trap_handler = meta
+            .find_symbol_address(symbol)
+            .unwrap_or_else(|| panic!("Could not find the floating-point trap handler {symbol}"));
+    }
+    mips_wit_env.syscall_env.output.echo = true;
+    if configuration.commit_output {
+        mips_wit_env.syscall_env.output.enable_commitment();
+    }
+
+    if let Some(address) = &configuration.gdb {
+        gdbstub::listen(address, &mut mips_wit_env, &configuration, &meta, &start)
+            .unwrap_or_else(|e| panic!("Could not serve the GDB connection: {e}"));
+        return ExitCode::SUCCESS;
+    }
 
     let constraints = {
         let mut mips_con_env = mips_constraints::Env::<Fp>::default();
@@ -95,69 +128,152 @@ pub fn main() -> ExitCode {
         constraints
     };
 
-    let mut curr_proof_inputs: ProofInputs<Vesta> = ProofInputs::new(DOMAIN_SIZE);
-    while !mips_wit_env.halt {
-        let _instr: Instruction = mips_wit_env.step(&configuration, &meta, &start);
-        for (scratch, scratch_chunk) in mips_wit_env
-            .scratch_state
-            .iter()
-            .zip(curr_proof_inputs.evaluations.scratch.iter_mut())
-        {
-            scratch_chunk.push(*scratch);
-        }
-        for (scratch, scratch_chunk) in mips_wit_env
-            .scratch_state_inverse
-            .iter()
-            .zip(curr_proof_inputs.evaluations.scratch_inverse.iter_mut())
-        {
-            scratch_chunk.push(*scratch);
-        }
-        curr_proof_inputs
-            .evaluations
-            .instruction_counter
-            .push(Fp::from(mips_wit_env.instruction_counter));
-        // FIXME: Might be another value
-        curr_proof_inputs.evaluations.error.push(Fp::rand(&mut rng));
-
-        curr_proof_inputs
-            .evaluations
-            .selector
-            .push(Fp::from((mips_wit_env.selector - N_MIPS_REL_COLS) as u64));
-
-        if curr_proof_inputs.evaluations.instruction_counter.len() == DOMAIN_SIZE {
-            // FIXME
-            let start_iteration = Instant::now();
-            debug!("Limit of {DOMAIN_SIZE} reached. We make a proof, verify it (for testing) and start with a new chunk");
-            let proof = prover::prove::<
-                Vesta,
-                DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>,
-                DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>,
-                _,
-            >(domain_fp, &srs, curr_proof_inputs, &constraints, &mut rng)
-            .unwrap();
-            // FIXME: check that the proof is correct. This is for testing purposes.
-            // Leaving like this for now.
-            debug!(
-                "Proof generated in {elapsed} μs",
-                elapsed = start_iteration.elapsed().as_micros()
-            );
-            {
-                let start_iteration = Instant::now();
-                let verif = verifier::verify::<
-                    Vesta,
-                    DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>,
-                    DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>,
-                >(domain_fp, &srs, &constraints, &proof);
-                debug!(
-                    "Verification done in {elapsed} μs",
-                    elapsed = start_iteration.elapsed().as_micros()
-                );
-                assert!(verif);
-            }
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    prove_chunks::<BaseSponge, ScalarSponge>(
+        &mut mips_wit_env,
+        &configuration,
+        &meta,
+        &start,
+        domain_fp,
+        &srs,
+        &constraints,
+    );
+
+    if let Some(e) = &mips_wit_env.preimage_oracle_error {
+        error!("The execution stopped on an error of the preimage oracle: {e}");
+        return ExitCode::FAILURE;
+    }
 
-            curr_proof_inputs = ProofInputs::new(DOMAIN_SIZE);
-        }
+    if let (Some(prefix), Some(profiler)) = (&configuration.profile, &mips_wit_env.profiler) {
+        profiler
+            .write_reports(prefix)
+            .unwrap_or_else(|e| panic!("Could not write the reports of the profiler: {e}"));
+        debug!("Profiler reports written to {prefix}.*.folded");
+    }
+
+    if let (Some(path), Some(statistics)) = (&configuration.statistics, &mips_wit_env.statistics) {
+        statistics
+            .write_report_file(path)
+            .unwrap_or_else(|e| panic!("Could not write the statistics to {path}: {e}"));
+        debug!("Statistics written to {path}");
+    }
+
+    if let (Some(path), Some(recording)) = (&configuration.record, &mips_wit_env.recording) {
+        recording
+            .to_file(path)
+            .unwrap_or_else(|e| panic!("Could not write the trace file {path}: {e}"));
+        debug!("Execution trace written to {path}");
     }
     // TODO: Logic
     ExitCode::SUCCESS
 }
+
+/// Executes a step of the program, and adds the row of the step to the
+/// inputs of the proof of the current chunk.
+fn step(
+    mips_wit_env: &mut mips_witness::Env<Fp, Box<dyn PreImageOracleT>>,
+    configuration: &VmConfiguration,
+    meta: &Meta,
+    start: &Start,
+    curr_proof_inputs: &mut ProofInputs<Vesta>,
+    rng: &mut impl rand::Rng,
+) {
+    let _instr: Instruction = mips_wit_env.step(configuration, meta, start);
+    for (scratch, scratch_chunk) in mips_wit_env
+        .scratch_state
+        .iter()
+        .zip(curr_proof_inputs.evaluations.scratch.iter_mut())
+    {
+        scratch_chunk.push(*scratch);
+    }
+    for (scratch, scratch_chunk) in mips_wit_env
+        .scratch_state_inverse
+        .iter()
+        .zip(curr_proof_inputs.evaluations.scratch_inverse.iter_mut())
+    {
+        scratch_chunk.push(*scratch);
+    }
+    curr_proof_inputs
+        .evaluations
+        .instruction_counter
+        .push(Fp::from(mips_wit_env.instruction_counter));
+    // FIXME: Might be another value
+    curr_proof_inputs.evaluations.error.push(Fp::rand(rng));
+
+    curr_proof_inputs
+        .evaluations
+        .selector
+        .push(Fp::from((mips_wit_env.selector - N_MIPS_REL_COLS) as u64));
+}
+
+/// Proves each chunk of [DOMAIN_SIZE] steps of the execution independently,
+/// and verifies the proofs (for testing). The proofs are not linked to each
+/// other.
+fn prove_chunks<EFqSponge, EFrSponge>(
+    mips_wit_env: &mut mips_witness::Env<Fp, Box<dyn PreImageOracleT>>,
+    configuration: &VmConfiguration,
+    meta: &Meta,
+    start: &Start,
+    domain_fp: EvaluationDomains<Fp>,
+    srs: &SRS<Vesta>,
+    constraints: &[E<Fp>],
+) where
+    EFqSponge: FqSponge<Fq, Vesta, Fp> + Clone,
+    EFrSponge: FrSponge<Fp>,
+{
+    let mut rng = rand::thread_rng();
+    let storage = configuration
+        .column_storage
+        .as_ref()
+        .map_or(ColumnStorage::Memory, |directory| {
+            ColumnStorage::Mapped(directory.into())
+        });
+    // The chunks are proven by other threads while the execution continues
+    let proofs = prove_chunks_pipelined::<Vesta, EFqSponge, EFrSponge, _>(
+        domain_fp,
+        srs,
+        constraints,
+        &storage,
+        configuration.prover_threads,
+        configuration.prover_threads,
+        |chunks| {
+            let mut curr_proof_inputs: ProofInputs<Vesta> = ProofInputs::new(DOMAIN_SIZE);
+            while !mips_wit_env.halt {
+                step(
+                    mips_wit_env,
+                    configuration,
+                    meta,
+                    start,
+                    &mut curr_proof_inputs,
+                    &mut rng,
+                );
+                // The step is not a valid one, see [mips_witness::Env::preimage_oracle_error]
+                if mips_wit_env.preimage_oracle_error.is_some() {
+                    break;
+                }
+
+                if curr_proof_inputs.evaluations.instruction_counter.len() == DOMAIN_SIZE {
+                    debug!("Limit of {DOMAIN_SIZE} reached. We send the chunk to the provers and start with a new chunk");
+                    chunks.send(
+                        vec![],
+                        std::mem::replace(&mut curr_proof_inputs, ProofInputs::new(DOMAIN_SIZE)),
+                    );
+                }
+            }
+        },
+    )
+    .unwrap();
+
+    // Verify the proofs (for testing)
+    for proof in proofs.iter() {
+        let start_iteration = Instant::now();
+        let verif = verify::<Vesta, EFqSponge, EFrSponge>(domain_fp, srs, constraints, proof);
+        debug!(
+            "Verification done in {elapsed} μs",
+            elapsed = start_iteration.elapsed().as_micros()
+        );
+        assert!(verif);
+    }
+}
@@ -13,6 +13,8 @@
 //! ```
 
 pub mod column_env;
+pub mod column_store;
+pub mod pipeline;
 pub mod proof;
 pub mod prover;
 pub mod verifier;
@@ -31,7 +33,7 @@ pub const DEGREE_QUOTIENT_POLYNOMIAL: u64 = 7;
 
 /// Total number of constraints for all instructions, including the constraints
 /// added for the selectors.
-pub const TOTAL_NUMBER_OF_CONSTRAINTS: usize = 464;
+pub const TOTAL_NUMBER_OF_CONSTRAINTS: usize = 568;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,141 @@
+//! Pipelined proving of the chunks of an execution.
+//!
+//! The interpreter fills the inputs of the chunks one after the other, while
+//! proving a chunk is independent of the execution of the next ones. The
+//! chunks are therefore sent in a bounded channel to a pool of prover
+//! threads, which overlaps the proving with the execution. The bound limits
+//! the number of chunks waiting in memory when the provers are slower than
+//! the interpreter, the interpreter then blocking until a prover is free.
+
+use crate::{
+    pickles::{
+        column_store::ColumnStorage,
+        proof::{Proof, ProofInputs},
+        prover::{prove_with_statement, ProverError},
+    },
+    E,
+};
+use ark_ff::PrimeField;
+use kimchi::{circuits::domains::EvaluationDomains, curve::KimchiCurve, plonk_sponge::FrSponge};
+use log::debug;
+use mina_poseidon::FqSponge;
+use poly_commitment::ipa::SRS;
+use std::{
+    sync::{
+        mpsc::{self, SyncSender},
+        Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+/// Stack size of the prover threads, the prover recursing deeply on the
+/// expressions of the constraints
+pub const PROVER_STACK_SIZE: usize = 32 * 1024 * 1024;
+
+/// A chunk of the execution to be proven, see [prove_with_statement]
+pub struct ChunkJob<G: KimchiCurve> {
+    /// Position of the chunk in the execution
+    pub index: usize,
+    pub statement: Vec<G::BaseField>,
+    pub inputs: ProofInputs<G>,
+}
+
+/// The sending end of the pipeline, given to the interpreter.
+pub struct ChunkSender<G: KimchiCurve> {
+    sender: SyncSender<ChunkJob<G>>,
+    next_index: usize,
+}
+
+impl<G: KimchiCurve> ChunkSender<G> {
+    /// Sends the chunk given by `inputs`, to be proven for `statement`, to the
+    /// provers, blocking while the channel is full. The inputs must fill the
+    /// domain.
+    pub fn send(&mut self, statement: Vec<G::BaseField>, inputs: ProofInputs<G>) {
+        let job = ChunkJob {
+            index: self.next_index,
+            statement,
+            inputs,
+        };
+        self.next_index += 1;
+        // The provers only stop receiving when the sender is dropped
+        self.sender
+            .send(job)
+            .expect("The prover threads have stopped");
+    }
+}
+
+/// Proves the chunks produced by `execute` with `n_provers` threads, while
+/// `execute` runs on the current thread. At most `capacity` chunks wait to be
+/// proven. The evaluations of the columns are kept in `storage` while
+/// proving.
+/// Returns the proofs of the chunks in the order they have been sent, or the
+/// error of the first chunk whose proof failed.
+pub fn prove_chunks_pipelined<G, EFqSponge, EFrSponge, Execute>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &SRS<G>,
+    constraints: &[E<G::ScalarField>],
+    storage: &ColumnStorage,
+    n_provers: usize,
+    capacity: usize,
+    execute: Execute,
+) -> Result<Vec<Proof<G>>, ProverError>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: FqSponge<G::BaseField, G, G::ScalarField> + Clone,
+    EFrSponge: FrSponge<G::ScalarField>,
+    Execute: FnOnce(&mut ChunkSender<G>),
+{
+    assert!(n_provers > 0, "At least one prover thread is required");
+    let (sender, receiver) = mpsc::sync_channel::<ChunkJob<G>>(capacity);
+    let receiver = Mutex::new(receiver);
+    let (result_sender, result_receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..n_provers {
+            let result_sender = result_sender.clone();
+            let receiver = &receiver;
+            let prover = thread::Builder::new().stack_size(PROVER_STACK_SIZE);
+            prover
+                .spawn_scoped(scope, move || {
+                    let mut rng = rand::thread_rng();
+                    loop {
+                        // The lock is released before proving the chunk
+                        let job = receiver.lock().unwrap().recv();
+                        let Ok(job) = job else {
+                            break;
+                        };
+                        let start = Instant::now();
+                        let proof = prove_with_statement::<G, EFqSponge, EFrSponge, _>(
+                            domain,
+                            srs,
+                            &job.statement,
+                            job.inputs,
+                            constraints,
+                            storage,
+                            &mut rng,
+                        );
+                        debug!(
+                            "Proof of the chunk {} generated in {} μs",
+                            job.index,
+                            start.elapsed().as_micros()
+                        );
+                        result_sender.send((job.index, proof)).unwrap();
+                    }
+                })
+                .expect("Could not spawn a prover thread");
+        }
+        let mut chunk_sender = ChunkSender {
+            sender,
+            next_index: 0,
+        };
+        execute(&mut chunk_sender);
+        // Dropping the sender stops the provers once the channel is empty
+    });
+    drop(result_sender);
+
+    let mut proofs: Vec<_> = result_receiver.into_iter().collect();
+    proofs.sort_by_key(|(index, _)| *index);
+    proofs.into_iter().map(|(_, proof)| proof).collect()
+}
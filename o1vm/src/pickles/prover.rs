@@ -27,6 +27,7 @@ use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterato
 
 use super::{
     column_env::ColumnEnvironment,
+    column_store::ColumnStorage,
     proof::{Proof, ProofInputs, WitnessColumns},
     DEGREE_QUOTIENT_POLYNOMIAL,
 };
@@ -38,6 +39,8 @@ use thiserror::Error;
 pub enum ProverError {
     #[error("the provided constraint has degree {0} > allowed {1}; expr: {2}")]
     ConstraintDegreeTooHigh(u64, u64, String),
+    #[error("the evaluations of the columns could not be stored: {0}")]
+    ColumnStorage(String),
 }
 
 /// Make a PlonKish proof for the given circuit. As inputs, we get the execution
@@ -64,6 +67,43 @@ pub fn prove<
     constraints: &[E<G::ScalarField>],
     rng: &mut RNG,
 ) -> Result<Proof<G>, ProverError>
+where
+    G::BaseField: PrimeField,
+    RNG: RngCore + CryptoRng,
+{
+    prove_with_statement::<G, EFqSponge, EFrSponge, RNG>(
+        domain,
+        srs,
+        &[],
+        inputs,
+        constraints,
+        &ColumnStorage::Memory,
+        rng,
+    )
+}
+
+/// Same as [prove], with a statement absorbed before the commitments, which
+/// binds the transcript of the proof to it, e.g. the position of a chunk in
+/// the execution, see [super::pipeline].
+/// The statement is not constrained by the circuit.
+/// The proof must be verified with
+/// [super::verifier::verify_with_statement] and the same statement.
+/// The evaluations of the columns on `d8` are kept in `storage`, see
+/// [super::column_store].
+pub fn prove_with_statement<
+    G: KimchiCurve,
+    EFqSponge: FqSponge<G::BaseField, G, G::ScalarField> + Clone,
+    EFrSponge: FrSponge<G::ScalarField>,
+    RNG,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &SRS<G>,
+    statement: &[G::BaseField],
+    inputs: ProofInputs<G>,
+    constraints: &[E<G::ScalarField>],
+    storage: &ColumnStorage,
+    rng: &mut RNG,
+) -> Result<Proof<G>, ProverError>
 where
     G::BaseField: PrimeField,
     RNG: RngCore + CryptoRng,
@@ -72,6 +112,7 @@ where
     let omega = domain.d1.group_gen;
 
     let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    fq_sponge.absorb_fq(statement);
 
     ////////////////////////////////////////////////////////////////////////////
     // Round 1: Creating and absorbing column commitments
@@ -174,20 +215,31 @@ where
             error,
             selector,
         } = &polys;
-        let eval_d8 =
-            |poly: &DensePolynomial<G::ScalarField>| poly.evaluate_over_domain_by_ref(domain.d8);
+        // The evaluations are moved to the storage as soon as they are
+        // computed
+        let eval_d8 = |poly: &DensePolynomial<G::ScalarField>| {
+            storage
+                .store(poly.evaluate_over_domain_by_ref(domain.d8))
+                .map_err(|e| ProverError::ColumnStorage(e.to_string()))
+        };
         // Doing in parallel
-        let scratch = scratch.into_par_iter().map(eval_d8).collect::<Vec<_>>();
+        let scratch = scratch
+            .into_par_iter()
+            .map(eval_d8)
+            .collect::<Result<Vec<_>, _>>()?;
         let scratch_inverse = scratch_inverse
             .into_par_iter()
             .map(eval_d8)
-            .collect::<Vec<_>>();
-        let selector = selector.into_par_iter().map(eval_d8).collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
+        let selector = selector
+            .into_par_iter()
+            .map(eval_d8)
+            .collect::<Result<Vec<_>, _>>()?;
         WitnessColumns {
             scratch: scratch.try_into().unwrap(),
             scratch_inverse: scratch_inverse.try_into().unwrap(),
-            instruction_counter: eval_d8(instruction_counter),
-            error: eval_d8(error),
+            instruction_counter: eval_d8(instruction_counter)?,
+            error: eval_d8(error)?,
             selector: selector.try_into().unwrap(),
         }
     };
@@ -12,7 +12,10 @@ use crate::{
         interpreter::{self, InterpreterEnv},
         Instruction,
     },
-    pickles::{verifier::verify, MAXIMUM_DEGREE_CONSTRAINTS, TOTAL_NUMBER_OF_CONSTRAINTS},
+    pickles::{
+        column_store::ColumnStorage, verifier::verify, MAXIMUM_DEGREE_CONSTRAINTS,
+        TOTAL_NUMBER_OF_CONSTRAINTS,
+    },
 };
 use ark_ff::{Field, One, UniformRand, Zero};
 use kimchi::circuits::{domains::EvaluationDomains, expr::Expr, gate::CurrOrNext};
@@ -105,6 +108,89 @@ fn test_small_circuit() {
     assert!(verif, "Verification fails");
 }
 
+#[test]
+fn test_mapped_column_storage() {
+    use crate::pickles::{
+        column_store::{MappedEvaluations, StoredEvaluations},
+        prover::prove_with_statement,
+        verifier::verify_with_statement,
+    };
+    use ark_poly::{EvaluationDomain, Evaluations};
+
+    type BaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
+
+    let directory =
+        std::env::temp_dir().join(format!("o1vm-test-column-storage-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let storage = ColumnStorage::Mapped(directory.clone());
+    let mut rng = make_test_rng(None);
+
+    let domain = EvaluationDomains::<Fq>::create(8).unwrap();
+    let evaluations = Evaluations::from_vec_and_domain(
+        (0..domain.d8.size()).map(|_| Fq::rand(&mut rng)).collect(),
+        domain.d8,
+    );
+    let mapped = MappedEvaluations::create(&directory, &evaluations).unwrap();
+    assert_eq!(mapped.evals, evaluations.evals);
+    assert!(matches!(
+        storage.store(evaluations).unwrap(),
+        StoredEvaluations::Mapped(_)
+    ));
+    // The files are only reachable through the mappings
+    assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 0);
+    drop(mapped);
+
+    let srs = SRS::create(8);
+    let inputs = || ProofInputs::<Pallas> {
+        evaluations: WitnessColumns {
+            scratch: std::array::from_fn(|_| zero_to_n_minus_one(8)),
+            scratch_inverse: std::array::from_fn(|_| vec![Fq::zero(); 8]),
+            instruction_counter: zero_to_n_minus_one(8)
+                .into_iter()
+                .map(|x| x + Fq::one())
+                .collect(),
+            error: (0..8)
+                .map(|i| -Fq::from((i * SCRATCH_SIZE + (i + 1)) as u64))
+                .collect(),
+            selector: zero_to_n_minus_one(8),
+        },
+    };
+    let mut expr = Expr::zero();
+    for i in 0..SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 2 {
+        expr += Expr::cell(Column::Relation(i), CurrOrNext::Curr);
+    }
+    let constraints = [expr];
+    let statement = [Fp::from(42u64)];
+
+    let prove = |storage: &ColumnStorage, rng: &mut _| {
+        prove_with_statement::<Pallas, BaseSponge, ScalarSponge, _>(
+            domain,
+            &srs,
+            &statement,
+            inputs(),
+            &constraints,
+            storage,
+            rng,
+        )
+        .unwrap()
+    };
+    let proof = prove(&storage, &mut rng);
+    assert!(verify_with_statement::<Pallas, BaseSponge, ScalarSponge>(
+        domain,
+        &srs,
+        &constraints,
+        &statement,
+        &proof
+    ));
+    // The storage does not change the proof
+    let in_memory = prove(&ColumnStorage::Memory, &mut rng);
+    assert_eq!(in_memory.quotient_commitment, proof.quotient_commitment);
+    assert_eq!(in_memory.quotient_evaluations, proof.quotient_evaluations);
+
+    std::fs::remove_dir(&directory).unwrap();
+}
+
 #[test]
 fn test_arkworks_batch_inversion_with_only_zeroes() {
     let input = vec![Fq::zero(); 8];
@@ -132,3 +218,67 @@ fn test_arkworks_batch_inversion_with_zeroes_and_random() {
     ark_ff::batch_inversion::<Fq>(&mut output);
     assert_eq!(output, exp_output);
 }
+
+#[test]
+fn test_pipelined_proving() {
+    use crate::pickles::{pipeline::prove_chunks_pipelined, verifier::verify_with_statement};
+
+    type BaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
+
+    let domain = EvaluationDomains::<Fq>::create(8).unwrap();
+    let srs = SRS::create(8);
+    let mut expr = Expr::zero();
+    for i in 0..SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 2 {
+        expr += Expr::cell(Column::Relation(i), CurrOrNext::Curr);
+    }
+    let constraints = [expr];
+    // Rows satisfying the constraint of the test circuit
+    let inputs = || ProofInputs::<Pallas> {
+        evaluations: WitnessColumns {
+            scratch: std::array::from_fn(|_| zero_to_n_minus_one(8)),
+            scratch_inverse: std::array::from_fn(|_| vec![Fq::zero(); 8]),
+            instruction_counter: zero_to_n_minus_one(8)
+                .into_iter()
+                .map(|x| x + Fq::one())
+                .collect(),
+            error: (0..8)
+                .map(|i| -Fq::from((i * SCRATCH_SIZE + (i + 1)) as u64))
+                .collect(),
+            selector: zero_to_n_minus_one(8),
+        },
+    };
+    // The position of a chunk in the execution, as its statement
+    let statement = |i: u64| vec![Fp::from(i)];
+
+    // More chunks than provers, the execution waiting for a free prover
+    let proofs = prove_chunks_pipelined::<Pallas, BaseSponge, ScalarSponge, _>(
+        domain,
+        &srs,
+        &constraints,
+        &ColumnStorage::Memory,
+        2,
+        1,
+        |chunks| {
+            for i in 0..5 {
+                chunks.send(statement(i), inputs());
+            }
+        },
+    )
+    .unwrap();
+    // The proofs are in the order of the execution
+    assert_eq!(proofs.len(), 5);
+    for (i, proof) in proofs.iter().enumerate() {
+        let verify = |statement: &[Fp]| {
+            verify_with_statement::<Pallas, BaseSponge, ScalarSponge>(
+                domain,
+                &srs,
+                &constraints,
+                statement,
+                proof,
+            )
+        };
+        assert!(verify(&statement(i as u64)));
+        assert!(!verify(&statement(i as u64 + 1)));
+    }
+}
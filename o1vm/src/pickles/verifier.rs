@@ -72,6 +72,26 @@ pub fn verify<
     constraints: &[E<G::ScalarField>],
     proof: &Proof<G>,
 ) -> bool
+where
+    <G as AffineRepr>::BaseField: PrimeField,
+{
+    verify_with_statement::<G, EFqSponge, EFrSponge>(domain, srs, constraints, &[], proof)
+}
+
+/// Verifies a proof given by
+/// [prove_with_statement](super::prover::prove_with_statement) for the
+/// statement `statement`.
+pub fn verify_with_statement<
+    G: KimchiCurve,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &<OpeningProof<G> as OpenProof<G>>::SRS,
+    constraints: &[E<G::ScalarField>],
+    statement: &[G::BaseField],
+    proof: &Proof<G>,
+) -> bool
 where
     <G as AffineRepr>::BaseField: PrimeField,
 {
@@ -93,6 +113,7 @@ where
     ////////////////////////////////////////////////////////////////////////////
 
     let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    fq_sponge.absorb_fq(statement);
     for comm in commitments.scratch.iter() {
         absorb_commitment(&mut fq_sponge, comm)
     }
@@ -1,15 +1,25 @@
-use crate::cannon::{
-    Hint, HostProgram, Preimage, HINT_CLIENT_READ_FD, HINT_CLIENT_WRITE_FD,
-    PREIMAGE_CLIENT_READ_FD, PREIMAGE_CLIENT_WRITE_FD,
+use crate::{
+    cannon::{
+        Hint, HostProgram, PreImageOracleBackend, Preimage, VmConfiguration, HINT_CLIENT_READ_FD,
+        HINT_CLIENT_WRITE_FD, PREIMAGE_CLIENT_READ_FD, PREIMAGE_CLIENT_WRITE_FD,
+    },
+    replay::{ExecutionTrace, ReplayPreImageOracle},
 };
 use command_fds::{CommandFdExt, FdMapping};
 use log::debug;
 use os_pipe::{PipeReader, PipeWriter};
+use sha3::{Digest, Keccak256};
 use std::{
-    io::{Read, Write},
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
     os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    path::PathBuf,
     process::{Child, Command},
+    time::Duration,
 };
+use thiserror::Error;
 
 pub struct PreImageOracle {
     pub cmd: Command,
@@ -19,10 +29,33 @@ pub struct PreImageOracle {
     pub hint_server: RW,
 }
 
+/// Errors that can arise when requesting a preimage or sending a hint
+#[derive(Error, Debug)]
+pub enum PreImageOracleError {
+    #[error("no preimage for the key 0x{}", hex::encode(.0))]
+    MissingPreimage([u8; 32]),
+    #[error("the preimage of the key 0x{} is malformed: {1}", hex::encode(.0))]
+    MalformedPreimage([u8; 32], String),
+    #[error("the communication with the preimage oracle failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Http(#[from] HttpOracleError),
+}
+
 pub trait PreImageOracleT {
-    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage;
+    fn get_preimage(&mut self, key: [u8; 32]) -> Result<Preimage, PreImageOracleError>;
 
-    fn hint(&mut self, hint: Hint);
+    fn hint(&mut self, hint: Hint) -> Result<(), PreImageOracleError>;
+}
+
+impl<T: PreImageOracleT + ?Sized> PreImageOracleT for Box<T> {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Result<Preimage, PreImageOracleError> {
+        (**self).get_preimage(key)
+    }
+
+    fn hint(&mut self, hint: Hint) -> Result<(), PreImageOracleError> {
+        (**self).hint(hint)
+    }
 }
 
 pub struct ReadWrite<R, W> {
@@ -166,25 +199,20 @@ impl PreImageOracleT for PreImageOracle {
     //      +---------------------------------+
     //   a. a 64-bit integer indicating the length of the actual data
     //   b. the preimage data, with a size of <length> bits
-    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Result<Preimage, PreImageOracleError> {
         let RW(ReadWrite { reader, writer }) = &mut self.oracle_client;
 
-        let r = writer.write_all(&key);
-        assert!(r.is_ok());
-        let r = writer.flush();
-        assert!(r.is_ok());
+        writer.write_all(&key)?;
+        writer.flush()?;
 
         debug!("Reading response");
         let mut buf = [0_u8; 8];
-        let resp = reader.read_exact(&mut buf);
-        assert!(resp.is_ok());
+        reader.read_exact(&mut buf)?;
 
         debug!("Extracting contents");
         let length = u64::from_be_bytes(buf);
         let mut preimage = vec![0_u8; length as usize];
-        let resp = reader.read_exact(&mut preimage);
-
-        assert!(resp.is_ok());
+        reader.read_exact(&mut preimage)?;
 
         debug!(
             "Got preimage of length {}\n {}",
@@ -194,7 +222,7 @@ impl PreImageOracleT for PreImageOracle {
         // We should have read exactly <length> bytes
         assert_eq!(preimage.len(), length as usize);
 
-        Preimage::create(preimage)
+        Ok(Preimage::create(preimage))
     }
 
     // The hint protocol goes as follows:
@@ -204,7 +232,7 @@ impl PreImageOracleT for PreImageOracle {
     //       +----------------------------+
     //
     // 2. Get back a single ack byte informing the hint has been processed.
-    fn hint(&mut self, hint: Hint) {
+    fn hint(&mut self, hint: Hint) -> Result<(), PreImageOracleError> {
         let RW(ReadWrite { reader, writer }) = &mut self.hint_client;
 
         // Write hint request
@@ -221,6 +249,260 @@ impl PreImageOracleT for PreImageOracle {
         let mut buf = [0_u8];
         // And do nothing with it anyway
         let _ = reader.read_exact(&mut buf);
+        Ok(())
+    }
+}
+
+/// Preimage oracle answering from preimages given in advance, e.g. for
+/// tests. The hints are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryPreImageOracle {
+    pub preimages: HashMap<[u8; 32], Vec<u8>>,
+}
+
+impl InMemoryPreImageOracle {
+    pub fn insert(&mut self, key: [u8; 32], preimage: Vec<u8>) {
+        self.preimages.insert(key, preimage);
+    }
+
+    /// Inserts `preimage` under its global Keccak256 key, i.e. its hash
+    /// with the first byte set to the key type `2`, and returns the key.
+    pub fn insert_keccak256(&mut self, preimage: Vec<u8>) -> [u8; 32] {
+        let mut key: [u8; 32] = Keccak256::digest(&preimage).into();
+        key[0] = 2;
+        self.insert(key, preimage);
+        key
+    }
+}
+
+impl PreImageOracleT for InMemoryPreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Result<Preimage, PreImageOracleError> {
+        let preimage = self
+            .preimages
+            .get(&key)
+            .ok_or(PreImageOracleError::MissingPreimage(key))?;
+        Ok(Preimage::create(preimage.clone()))
+    }
+
+    fn hint(&mut self, _hint: Hint) -> Result<(), PreImageOracleError> {
+        Ok(())
+    }
+}
+
+/// Preimage oracle reading the preimages from a directory, where the
+/// preimage of the key `k` is hex-encoded in the file `0x<k>.txt`. The hints
+/// are ignored.
+#[derive(Clone, Debug)]
+pub struct DirectoryPreImageOracle {
+    pub directory: PathBuf,
+}
+
+impl DirectoryPreImageOracle {
+    pub fn create(directory: impl Into<PathBuf>) -> Self {
+        DirectoryPreImageOracle {
+            directory: directory.into(),
+        }
+    }
+}
+
+impl PreImageOracleT for DirectoryPreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Result<Preimage, PreImageOracleError> {
+        let path = self.directory.join(format!("0x{}.txt", hex::encode(key)));
+        let contents = fs::read_to_string(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => PreImageOracleError::MissingPreimage(key),
+            _ => PreImageOracleError::Io(e),
+        })?;
+        let preimage = hex::decode(contents.trim()).map_err(|e| {
+            PreImageOracleError::MalformedPreimage(
+                key,
+                format!("{} is not hexadecimal: {e}", path.display()),
+            )
+        })?;
+        Ok(Preimage::create(preimage))
+    }
+
+    fn hint(&mut self, _hint: Hint) -> Result<(), PreImageOracleError> {
+        Ok(())
+    }
+}
+
+/// The default timeout of the connection to the preimage server, and of the
+/// reads and writes of a request.
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors that can arise when querying the preimage server
+#[derive(Error, Debug)]
+pub enum HttpOracleError {
+    #[error("the connection to the preimage server failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("the request {0} to the preimage server failed: {1}")]
+    Status(String, String),
+    #[error("the transfer encoding {0} of the response of the preimage server is not supported")]
+    UnsupportedTransferEncoding(String),
+    #[error("the response of the preimage server is malformed: {0}")]
+    MalformedResponse(String),
+}
+
+/// Preimage oracle querying a HTTP server. The preimage of the key `k` is
+/// the body of the response to `GET <url>/0x<k>`, and the hints are sent as
+/// the body of `POST <url>/hint`.
+/// Only plain HTTP/1.1 is supported. The body of a response must be given
+/// in full, delimited by `Content-Length` or by the end of the connection:
+/// the responses using a `Transfer-Encoding`, e.g. `chunked`, are rejected.
+#[derive(Clone, Debug)]
+pub struct HttpPreImageOracle {
+    /// The `host:port` of the server
+    pub address: String,
+    /// The path prefixed to the requests, without a trailing `/`
+    pub base_path: String,
+    /// The timeout of the connection, and of the reads and writes
+    pub timeout: Duration,
+}
+
+impl HttpPreImageOracle {
+    /// Creates an oracle for a URL of the form `http://host[:port][/path]`,
+    /// using [DEFAULT_HTTP_TIMEOUT].
+    pub fn create(url: &str) -> Self {
+        let url = url.strip_prefix("http://").unwrap_or_else(|| {
+            panic!("Only http:// URLs are supported for the preimage server, got {url}")
+        });
+        let (authority, path) = url.split_at(url.find('/').unwrap_or(url.len()));
+        let address = if authority.contains(':') {
+            authority.to_string()
+        } else {
+            format!("{authority}:80")
+        };
+        HttpPreImageOracle {
+            address,
+            base_path: path.trim_end_matches('/').to_string(),
+            timeout: DEFAULT_HTTP_TIMEOUT,
+        }
+    }
+
+    /// Returns the preimage of the key `key`.
+    pub fn fetch_preimage(&self, key: [u8; 32]) -> Result<Preimage, HttpOracleError> {
+        let preimage = self.request("GET", &format!("/0x{}", hex::encode(key)), &[])?;
+        debug!("Got preimage of length {} over HTTP", preimage.len());
+        Ok(Preimage::create(preimage))
+    }
+
+    /// Sends the hint `hint` to the server.
+    pub fn send_hint(&self, hint: Hint) -> Result<(), HttpOracleError> {
+        self.request("POST", "/hint", &hint.get()).map(|_| ())
+    }
+
+    /// Sends a request and returns the body of the response.
+    fn request(&self, method: &str, path: &str, body: &[u8]) -> Result<Vec<u8>, HttpOracleError> {
+        let address = self.address.to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("could not resolve {}", self.address),
+            )
+        })?;
+        let mut stream = TcpStream::connect_timeout(&address, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        let header = format!(
+            "{method} {}{path} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.base_path,
+            self.address,
+            body.len()
+        );
+        stream.write_all(header.as_bytes())?;
+        stream.write_all(body)?;
+
+        let mut reader = BufReader::new(stream);
+        let status_line = read_header_line(&mut reader)?;
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(HttpOracleError::Status(
+                format!("{method} {path}"),
+                status_line,
+            ));
+        }
+
+        let mut content_length = None;
+        loop {
+            let line = read_header_line(&mut reader)?;
+            if line.is_empty() {
+                break;
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(HttpOracleError::MalformedResponse(format!(
+                    "invalid header {line}"
+                )));
+            };
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = Some(value.parse::<usize>().map_err(|_| {
+                    HttpOracleError::MalformedResponse(format!("invalid content length {value}"))
+                })?);
+            } else if name.eq_ignore_ascii_case("transfer-encoding")
+                && !value.eq_ignore_ascii_case("identity")
+            {
+                return Err(HttpOracleError::UnsupportedTransferEncoding(
+                    value.to_string(),
+                ));
+            }
+        }
+
+        match content_length {
+            Some(length) => {
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body)?;
+                Ok(body)
+            }
+            None => {
+                let mut body = vec![];
+                reader.read_to_end(&mut body)?;
+                Ok(body)
+            }
+        }
+    }
+}
+
+/// Reads a line of the status line or of the headers of a response, without
+/// the line terminator.
+fn read_header_line(reader: &mut impl BufRead) -> Result<String, HttpOracleError> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(HttpOracleError::MalformedResponse(
+            "unexpected end of the headers".to_string(),
+        ));
+    }
+    Ok(line.trim_end().to_string())
+}
+
+impl PreImageOracleT for HttpPreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Result<Preimage, PreImageOracleError> {
+        Ok(self.fetch_preimage(key)?)
+    }
+
+    fn hint(&mut self, hint: Hint) -> Result<(), PreImageOracleError> {
+        Ok(self.send_hint(hint)?)
+    }
+}
+
+/// Creates the preimage oracle selected by
+/// [VmConfiguration::preimage_oracle]. The host program is spawned when it
+/// is the selected backend, and its process is returned with the oracle.
+pub fn create_preimage_oracle(
+    configuration: &VmConfiguration,
+) -> (Box<dyn PreImageOracleT>, Option<Child>) {
+    match &configuration.preimage_oracle {
+        PreImageOracleBackend::HostProgram => {
+            let mut po = PreImageOracle::create(&configuration.host);
+            let child = po.start();
+            (Box::new(po), Some(child))
+        }
+        PreImageOracleBackend::Directory(directory) => {
+            (Box::new(DirectoryPreImageOracle::create(directory)), None)
+        }
+        PreImageOracleBackend::Http(url) => (Box::new(HttpPreImageOracle::create(url)), None),
+        PreImageOracleBackend::Replay(path) => {
+            let trace = ExecutionTrace::from_file(path)
+                .unwrap_or_else(|e| panic!("Could not read the trace file {path}: {e}"));
+            (Box::new(ReplayPreImageOracle::create(&trace)), None)
+        }
     }
 }
 
@@ -299,4 +581,129 @@ mod tests {
         assert_eq!(n, len);
         assert_eq!(data, msg2);
     }
+
+    #[test]
+    fn test_in_memory_oracle() {
+        let mut oracle = InMemoryPreImageOracle::default();
+        let key = oracle.insert_keccak256(b"hello".to_vec());
+        // keccak256("hello"), with the key type in the first byte
+        assert_eq!(
+            hex::encode(key),
+            "028aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8"
+        );
+        oracle.hint(Hint::create(b"ignored".to_vec())).unwrap();
+        assert_eq!(oracle.get_preimage(key).unwrap().get(), b"hello");
+        assert!(matches!(
+            oracle.get_preimage([0; 32]),
+            Err(PreImageOracleError::MissingPreimage(k)) if k == [0; 32]
+        ));
+    }
+
+    #[test]
+    fn test_directory_oracle() {
+        let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources/tests");
+        let mut oracle: Box<dyn PreImageOracleT> =
+            Box::new(DirectoryPreImageOracle::create(directory));
+        let key = hex::decode("022107307879258577230c5aa2f90567bda40877a7e85dceb6ff1f37480fef3d")
+            .unwrap();
+        let preimage = oracle.get_preimage(key.try_into().unwrap()).unwrap().get();
+        assert_eq!(preimage.len(), 358);
+    }
+
+    #[test]
+    fn test_http_oracle() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/oracle/", listener.local_addr().unwrap());
+        let key = [7u8; 32];
+
+        let server = std::thread::spawn(move || {
+            let mut requests = vec![];
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("Content-Length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                requests.push((request_line.trim_end().to_string(), body));
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\nabc")
+                    .unwrap();
+            }
+            requests
+        });
+
+        let mut oracle = HttpPreImageOracle::create(&url);
+        assert_eq!(oracle.get_preimage(key).unwrap().get(), b"abc");
+        oracle.hint(Hint::create(b"hint".to_vec())).unwrap();
+
+        let requests = server.join().unwrap();
+        assert_eq!(
+            requests[0].0,
+            format!("GET /oracle/0x{} HTTP/1.1", hex::encode(key))
+        );
+        assert_eq!(
+            requests[1],
+            ("POST /oracle/hint HTTP/1.1".to_string(), b"hint".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_http_oracle_errors() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let responses: [&[u8]; 3] = [
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n0\r\n\r\n",
+            b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 3",
+        ];
+
+        let server = std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                }
+                stream.write_all(response).unwrap();
+            }
+        });
+
+        let oracle = HttpPreImageOracle::create(&url);
+        let key = [7u8; 32];
+        assert!(matches!(
+            oracle.fetch_preimage(key),
+            Err(HttpOracleError::UnsupportedTransferEncoding(encoding)) if encoding == "chunked"
+        ));
+        assert!(matches!(
+            oracle.fetch_preimage(key),
+            Err(HttpOracleError::Status(_, _))
+        ));
+        assert!(matches!(
+            oracle.fetch_preimage(key),
+            Err(HttpOracleError::MalformedResponse(_))
+        ));
+        server.join().unwrap();
+
+        // The server is not listening anymore
+        assert!(matches!(
+            oracle.fetch_preimage(key),
+            Err(HttpOracleError::Io(_))
+        ));
+    }
 }
@@ -0,0 +1,290 @@
+//! A profiler of the programs executed by the MIPS interpreter.
+//!
+//! The profiler attributes each executed instruction to the symbol of the
+//! program containing its address, as given by the metadata file of Cannon
+//! (see [Meta]), and to the chain of its callers. The callers are tracked by a
+//! shadow call stack: a frame is pushed by the instructions `jal` and `jalr`,
+//! and popped when the execution reaches its return address.
+//!
+//! The reports are written in the folded stacks format, one line
+//! `caller;...;callee count` per call stack, that can be given directly to
+//! flamegraph tools. Three metrics are reported (see [Metric]): the number of
+//! cycles, the number of syscalls, and the number of bytes of memory accessed.
+//!
+//! The metadata only provide the symbols of the program, so the attribution is
+//! done at the granularity of the functions. Source lines from the DWARF
+//! debugging information are not supported.
+
+use crate::{
+    cannon::{Meta, Symbol},
+    interpreters::mips::interpreter::{Instruction, JTypeInstruction, RTypeInstruction},
+};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+/// Maximum depth of the shadow call stack. The outermost frames are dropped when
+/// the program does not return to its callers, e.g. when switching between
+/// goroutines.
+pub const MAX_CALL_DEPTH: usize = 256;
+
+/// Name of the frames whose address is not covered by any symbol.
+const UNKNOWN_SYMBOL: &str = "[unknown]";
+
+/// The quantities measured by the profiler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// The number of executed instructions
+    Cycles,
+    /// The number of syscalls, the last frame being the kind of syscall
+    Syscalls,
+    /// The number of bytes read or written in the memory
+    MemoryBytes,
+}
+
+impl Metric {
+    /// Suffix of the file of the report of the metric.
+    pub fn file_suffix(&self) -> &'static str {
+        match self {
+            Metric::Cycles => "cycles.folded",
+            Metric::Syscalls => "syscalls.folded",
+            Metric::MemoryBytes => "memory.folded",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// The symbol of the caller
+    symbol: usize,
+    return_address: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Counters {
+    cycles: u64,
+    memory_bytes: u64,
+    /// Indexed by the name of the syscall
+    syscalls: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    /// Sorted by start address
+    symbols: Vec<Symbol>,
+    stack: Vec<Frame>,
+    /// The frame of the last call, with the address of its delay slot. The
+    /// frame is pushed once the delay slot has been executed.
+    pending_call: Option<(Frame, u32)>,
+    /// Indexed by the symbols of the call stack, from the outermost caller to
+    /// the current function. The index `symbols.len()` is an unknown symbol.
+    counters: BTreeMap<Vec<usize>, Counters>,
+    /// The memory accesses of the current step, that are attributed when the
+    /// step is recorded
+    pending_memory_bytes: u64,
+}
+
+impl Profiler {
+    pub fn new(meta: &Meta) -> Self {
+        let mut symbols = meta.symbols.clone();
+        symbols.sort_by_key(|symbol| symbol.start);
+        Profiler {
+            symbols,
+            stack: vec![],
+            pending_call: None,
+            counters: BTreeMap::new(),
+            pending_memory_bytes: 0,
+        }
+    }
+
+    fn symbol_index(&self, address: u32) -> usize {
+        let next = self
+            .symbols
+            .partition_point(|symbol| symbol.start <= address);
+        if next > 0 {
+            let symbol = &self.symbols[next - 1];
+            if (address - symbol.start) as usize >= symbol.size {
+                self.symbols.len()
+            } else {
+                next - 1
+            }
+        } else {
+            self.symbols.len()
+        }
+    }
+
+    fn symbol_name(&self, index: usize) -> String {
+        self.symbols
+            .get(index)
+            .map_or(UNKNOWN_SYMBOL, |symbol| symbol.name.as_str())
+            // The frames are separated by semicolons in the reports
+            .replace(';', ":")
+    }
+
+    /// Records an access to one byte of the memory during the current step.
+    pub fn record_memory_access(&mut self) {
+        self.pending_memory_bytes += 1;
+    }
+
+    /// Records the execution of `instruction` at the address `pc`, with the
+    /// memory accesses recorded since the previous step.
+    pub fn record_step(&mut self, pc: u32, instruction: Instruction) {
+        if let Some((frame, delay_slot)) = self.pending_call {
+            if pc != delay_slot {
+                if self.stack.len() == MAX_CALL_DEPTH {
+                    self.stack.remove(0);
+                }
+                self.stack.push(frame);
+                self.pending_call = None;
+            }
+        }
+        // Returning to a caller, possibly skipping some frames
+        if let Some(depth) = self
+            .stack
+            .iter()
+            .rposition(|frame| frame.return_address == pc)
+        {
+            self.stack.truncate(depth);
+        }
+        let symbol = self.symbol_index(pc);
+        let key: Vec<usize> = self
+            .stack
+            .iter()
+            .map(|frame| frame.symbol)
+            .chain(std::iter::once(symbol))
+            .collect();
+        let counters = self.counters.entry(key).or_default();
+        counters.cycles += 1;
+        counters.memory_bytes += std::mem::take(&mut self.pending_memory_bytes);
+        if let Some(syscall) = syscall_name(instruction) {
+            *counters.syscalls.entry(syscall).or_default() += 1;
+        }
+        if matches!(
+            instruction,
+            Instruction::JType(JTypeInstruction::JumpAndLink)
+                | Instruction::RType(RTypeInstruction::JumpAndLinkRegister)
+        ) {
+            // The return address is after the delay slot
+            let frame = Frame {
+                symbol,
+                return_address: pc.wrapping_add(8),
+            };
+            self.pending_call = Some((frame, pc.wrapping_add(4)));
+        }
+    }
+
+    /// Writes the report of `metric` in the folded stacks format.
+    pub fn write_folded<W: Write>(&self, metric: Metric, writer: &mut W) -> io::Result<()> {
+        for (key, counters) in self.counters.iter() {
+            let stack = key
+                .iter()
+                .map(|&index| self.symbol_name(index))
+                .collect::<Vec<_>>()
+                .join(";");
+            match metric {
+                Metric::Cycles => writeln!(writer, "{} {}", stack, counters.cycles)?,
+                Metric::MemoryBytes if counters.memory_bytes > 0 => {
+                    writeln!(writer, "{} {}", stack, counters.memory_bytes)?
+                }
+                Metric::MemoryBytes => (),
+                Metric::Syscalls => {
+                    for (syscall, count) in counters.syscalls.iter() {
+                        writeln!(writer, "{};{} {}", stack, syscall, count)?
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the reports of all the metrics in the files `prefix.<metric>`,
+    /// see [Metric::file_suffix].
+    pub fn write_reports(&self, prefix: &str) -> io::Result<()> {
+        for metric in [Metric::Cycles, Metric::Syscalls, Metric::MemoryBytes] {
+            let path = format!("{}.{}", prefix, metric.file_suffix());
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.write_folded(metric, &mut writer)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+fn syscall_name(instruction: Instruction) -> Option<String> {
+    match instruction {
+        Instruction::RType(rtype) if rtype.is_syscall() => Some(format!("{:?}", rtype)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADD: Instruction = Instruction::RType(RTypeInstruction::Add);
+    const JAL: Instruction = Instruction::JType(JTypeInstruction::JumpAndLink);
+    const JR: Instruction = Instruction::RType(RTypeInstruction::JumpRegister);
+    const MMAP: Instruction = Instruction::RType(RTypeInstruction::SyscallMmap);
+
+    fn profiler() -> Profiler {
+        let symbol = |name: &str, start, size| Symbol {
+            name: name.to_string(),
+            start,
+            size,
+        };
+        Profiler::new(&Meta {
+            symbols: vec![
+                symbol("callee", 0x2000, 0x100),
+                symbol("main", 0x1000, 0x100),
+            ],
+        })
+    }
+
+    fn report(profiler: &Profiler, metric: Metric) -> String {
+        let mut report = vec![];
+        profiler.write_folded(metric, &mut report).unwrap();
+        String::from_utf8(report).unwrap()
+    }
+
+    #[test]
+    fn test_profiler_call_stacks() {
+        let mut profiler = profiler();
+        // main calls callee, which returns after a syscall and a store of
+        // 4 bytes
+        profiler.record_step(0x1000, ADD);
+        profiler.record_step(0x1004, JAL);
+        profiler.record_step(0x1008, ADD);
+        profiler.record_step(0x2000, MMAP);
+        (0..4).for_each(|_| profiler.record_memory_access());
+        profiler.record_step(0x2004, ADD);
+        profiler.record_step(0x2008, JR);
+        profiler.record_step(0x200c, ADD);
+        profiler.record_step(0x100c, ADD);
+        // Code outside of the symbols
+        profiler.record_step(0x3000, ADD);
+        assert_eq!(
+            report(&profiler, Metric::Cycles),
+            "main 4\nmain;callee 4\n[unknown] 1\n"
+        );
+        assert_eq!(
+            report(&profiler, Metric::Syscalls),
+            "main;callee;SyscallMmap 1\n"
+        );
+        assert_eq!(report(&profiler, Metric::MemoryBytes), "main;callee 4\n");
+    }
+
+    #[test]
+    fn test_profiler_max_call_depth() {
+        let mut profiler = profiler();
+        // A recursion that never returns
+        for _ in 0..2 * MAX_CALL_DEPTH {
+            profiler.record_step(0x2000, JAL);
+        }
+        assert_eq!(profiler.stack.len(), MAX_CALL_DEPTH);
+        // Returning to the first frame that is still on the stack
+        profiler.record_step(0x2008, ADD);
+        assert_eq!(profiler.stack.len(), MAX_CALL_DEPTH - 1);
+    }
+}
@@ -0,0 +1,215 @@
+//! Record and replay of the executions of the MIPS interpreter.
+//!
+//! An execution only depends on its initial state and on the values given by
+//! the host: the preimages returned by the oracle and the outputs of the host
+//! functions (see [crate::interpreters::mips::host_functions]). The VM has no
+//! other source of nondeterminism, e.g. no clock.
+//!
+//! While recording, the witness environment logs these values in an
+//! [ExecutionTrace], written in a compact file at the end of the run. The
+//! execution can then be replayed from the initial state and the trace alone,
+//! with [ReplayPreImageOracle] and [ExecutionTrace::register_host_functions],
+//! e.g. to generate the witness on a machine without access to the host
+//! program.
+
+use crate::{
+    cannon::{Hint, Preimage},
+    interpreters::mips::host_functions::{
+        GuestMemory, HostCallArgs, HostCallOutput, HostFunctionError, HostFunctions,
+    },
+    preimage_oracle::{PreImageOracleError, PreImageOracleT},
+};
+use libflate::gzip;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, Bytes};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+    rc::Rc,
+};
+
+/// Version of the format of the trace files.
+pub const TRACE_FORMAT_VERSION: u8 = 0;
+
+/// A preimage returned by the oracle.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecordedPreimage {
+    pub key: [u8; 32],
+    #[serde_as(as = "Bytes")]
+    pub preimage: Vec<u8>,
+}
+
+/// A call to a host function, without the memory it read, which is given by
+/// the execution.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordedHostCall {
+    pub args: HostCallArgs,
+    pub output: HostCallOutput,
+}
+
+/// The values given by the host to an execution, in the order they have been
+/// requested.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    pub preimages: Vec<RecordedPreimage>,
+    pub host_calls: Vec<RecordedHostCall>,
+}
+
+impl ExecutionTrace {
+    pub fn record_preimage(&mut self, key: [u8; 32], preimage: &[u8]) {
+        self.preimages.push(RecordedPreimage {
+            key,
+            preimage: preimage.to_vec(),
+        });
+    }
+
+    pub fn record_host_call(&mut self, args: HostCallArgs, output: HostCallOutput) {
+        self.host_calls.push(RecordedHostCall { args, output });
+    }
+
+    /// Reads a trace written by [ExecutionTrace::to_file].
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut reader = gzip::Decoder::new(BufReader::new(File::open(path)?))?;
+        let (version, trace): (u8, ExecutionTrace) = rmp_serde::from_read(&mut reader)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if version != TRACE_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported version {version} of the trace file"),
+            ));
+        }
+        Ok(trace)
+    }
+
+    /// Writes the trace in a gzip-compressed MessagePack file.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut encoder = gzip::Encoder::new(BufWriter::new(File::create(path)?))?;
+        rmp_serde::encode::write(&mut encoder, &(TRACE_FORMAT_VERSION, self))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        encoder.finish().into_result()?.flush()
+    }
+
+    /// Registers in `host_functions` the host functions of the recorded
+    /// calls, returning their recorded outputs in order. The calls of the
+    /// replayed execution must be the recorded ones.
+    pub fn register_host_functions(
+        &self,
+        host_functions: &mut HostFunctions,
+    ) -> Result<(), HostFunctionError> {
+        let mut calls: BTreeMap<u32, VecDeque<RecordedHostCall>> = BTreeMap::new();
+        for call in self.host_calls.iter() {
+            calls
+                .entry(call.args.syscall_num)
+                .or_default()
+                .push_back(*call);
+        }
+        for (syscall_num, calls) in calls {
+            let calls = Rc::new(RefCell::new(calls));
+            host_functions.register(syscall_num, move |args: &HostCallArgs, _: &mut GuestMemory| {
+                let call = calls.borrow_mut().pop_front().unwrap_or_else(|| {
+                    panic!("The trace has no more calls to the host function {syscall_num}")
+                });
+                assert_eq!(
+                    call.args, *args,
+                    "The execution diverged from the trace at a call to the host function {syscall_num}"
+                );
+                call.output
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Preimage oracle returning the preimages of an [ExecutionTrace]. The hints
+/// are ignored.
+pub struct ReplayPreImageOracle {
+    preimages: HashMap<[u8; 32], Vec<u8>>,
+}
+
+impl ReplayPreImageOracle {
+    pub fn create(trace: &ExecutionTrace) -> Self {
+        ReplayPreImageOracle {
+            preimages: trace
+                .preimages
+                .iter()
+                .map(|recorded| (recorded.key, recorded.preimage.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl PreImageOracleT for ReplayPreImageOracle {
+    // A key missing from the trace means that the execution diverged from
+    // the recorded one
+    fn get_preimage(&mut self, key: [u8; 32]) -> Result<Preimage, PreImageOracleError> {
+        let preimage = self
+            .preimages
+            .get(&key)
+            .ok_or(PreImageOracleError::MissingPreimage(key))?;
+        Ok(Preimage::create(preimage.clone()))
+    }
+
+    fn hint(&mut self, _hint: Hint) -> Result<(), PreImageOracleError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_trace() -> ExecutionTrace {
+        let mut trace = ExecutionTrace::default();
+        trace.record_preimage([1; 32], b"hello");
+        trace.record_preimage([2; 32], &[0xff; 100]);
+        for value in [1, 2] {
+            trace.record_host_call(
+                HostCallArgs {
+                    syscall_num: 9000,
+                    args: [value, 0, 0, 0],
+                },
+                HostCallOutput { value, error: 0 },
+            );
+        }
+        trace
+    }
+
+    #[test]
+    fn test_trace_file() {
+        let trace = sample_trace();
+        let path = std::env::temp_dir().join("o1vm-test-trace.bin.gz");
+        trace.to_file(&path).unwrap();
+        assert_eq!(ExecutionTrace::from_file(&path).unwrap(), trace);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_preimages_and_host_calls() {
+        let trace = sample_trace();
+        let mut oracle = ReplayPreImageOracle::create(&trace);
+        assert_eq!(oracle.get_preimage([1; 32]).unwrap().get(), b"hello");
+        // The same preimage can be requested again
+        assert_eq!(oracle.get_preimage([1; 32]).unwrap().get(), b"hello");
+
+        let mut host_functions = HostFunctions::default();
+        trace.register_host_functions(&mut host_functions).unwrap();
+        for (i, call) in trace.host_calls.iter().enumerate() {
+            assert_eq!(host_functions.call(i as u64, call.args, &[]), call.output);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "diverged")]
+    fn test_replay_diverging_host_call() {
+        let trace = sample_trace();
+        let mut host_functions = HostFunctions::default();
+        trace.register_host_functions(&mut host_functions).unwrap();
+        let mut args = trace.host_calls[0].args;
+        args.args[0] = 42;
+        host_functions.call(0, args, &[]);
+    }
+}
@@ -0,0 +1,189 @@
+//! Statistics of the instructions executed by the MIPS interpreter.
+//!
+//! The statistics count, for a run, the executed instructions by kind, the
+//! conditional branches taken and not taken, and the syscalls by number. They
+//! are useful to optimize the programs, and to track which instructions of the
+//! ISA have their constraints exercised by a set of programs: the
+//! instructions never executed are listed by
+//! [ExecutionStatistics::never_executed].
+
+use crate::interpreters::mips::interpreter::{ITypeInstruction, Instruction};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+use strum::IntoEnumIterator;
+
+/// The number of times a conditional branch has been taken or not
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BranchCounts {
+    pub taken: u64,
+    pub not_taken: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionStatistics {
+    instructions: BTreeMap<Instruction, u64>,
+    branches: BTreeMap<ITypeInstruction, BranchCounts>,
+    /// Indexed by the syscall number
+    syscalls: BTreeMap<u32, u64>,
+}
+
+impl ExecutionStatistics {
+    /// Records the execution of `instruction` at the address `pc`.
+    /// `syscall_number` is the value of `$v0` before the execution, and
+    /// `next_pc` is the address of the instruction following the delay slot
+    /// after the execution.
+    pub fn record_step(
+        &mut self,
+        instruction: Instruction,
+        pc: u32,
+        syscall_number: u32,
+        next_pc: u32,
+    ) {
+        *self.instructions.entry(instruction).or_default() += 1;
+        match instruction {
+            Instruction::RType(rtype) if rtype.is_syscall() => {
+                *self.syscalls.entry(syscall_number).or_default() += 1;
+            }
+            Instruction::IType(itype) if itype.is_branch() => {
+                let counts = self.branches.entry(itype).or_default();
+                // The branch is not taken when the execution continues after
+                // the delay slot
+                if next_pc == pc.wrapping_add(8) {
+                    counts.not_taken += 1;
+                } else {
+                    counts.taken += 1;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// The number of executed instructions
+    pub fn cycles(&self) -> u64 {
+        self.instructions.values().sum()
+    }
+
+    /// The number of executions of each kind of instruction
+    pub fn instructions(&self) -> &BTreeMap<Instruction, u64> {
+        &self.instructions
+    }
+
+    /// The number of executions of each conditional branch, taken or not
+    pub fn branches(&self) -> &BTreeMap<ITypeInstruction, BranchCounts> {
+        &self.branches
+    }
+
+    /// The number of calls of each syscall, indexed by its number
+    pub fn syscalls(&self) -> &BTreeMap<u32, u64> {
+        &self.syscalls
+    }
+
+    /// The instructions that have never been executed, i.e. whose
+    /// constraints have not been exercised by the run.
+    pub fn never_executed(&self) -> Vec<Instruction> {
+        Instruction::iter()
+            .flat_map(|x| x.into_iter())
+            .filter(|instruction| !self.instructions.contains_key(instruction))
+            .collect()
+    }
+
+    /// Writes the statistics in a human-readable format, the instructions
+    /// being sorted from the most executed one.
+    pub fn write_report<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let cycles = self.cycles();
+        writeln!(writer, "cycles: {}", cycles)?;
+
+        writeln!(writer, "instructions:")?;
+        let mut instructions: Vec<_> = self.instructions.iter().collect();
+        instructions.sort_by(|(_, a), (_, b)| b.cmp(a));
+        for (instruction, count) in instructions {
+            writeln!(
+                writer,
+                "  {:?} {} ({:.2}%)",
+                instruction,
+                count,
+                100.0 * *count as f64 / cycles as f64
+            )?;
+        }
+
+        writeln!(writer, "branches:")?;
+        for (branch, counts) in self.branches.iter() {
+            writeln!(
+                writer,
+                "  {:?} taken {} not taken {}",
+                branch, counts.taken, counts.not_taken
+            )?;
+        }
+
+        writeln!(writer, "syscalls:")?;
+        for (number, count) in self.syscalls.iter() {
+            writeln!(writer, "  {} {}", number, count)?;
+        }
+
+        writeln!(writer, "never executed:")?;
+        for instruction in self.never_executed() {
+            writeln!(writer, "  {:?}", instruction)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the report of [ExecutionStatistics::write_report] in the file
+    /// `path`.
+    pub fn write_report_file(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_report(&mut writer)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreters::mips::interpreter::{RTypeInstruction, SYSCALL_MMAP};
+
+    const ADD: Instruction = Instruction::RType(RTypeInstruction::Add);
+    const BEQ: Instruction = Instruction::IType(ITypeInstruction::BranchEq);
+    const SYSCALL: Instruction = Instruction::RType(RTypeInstruction::SyscallMmap);
+
+    #[test]
+    fn test_execution_statistics() {
+        let mut statistics = ExecutionStatistics::default();
+        statistics.record_step(ADD, 0x1000, SYSCALL_MMAP, 0x1008);
+        // Taken, then not taken
+        statistics.record_step(BEQ, 0x1004, 0, 0x2000);
+        statistics.record_step(BEQ, 0x2000, 0, 0x2008);
+        statistics.record_step(SYSCALL, 0x2004, SYSCALL_MMAP, 0x200c);
+
+        assert_eq!(statistics.cycles(), 4);
+        assert_eq!(statistics.instructions()[&BEQ], 2);
+        assert_eq!(
+            statistics.branches()[&ITypeInstruction::BranchEq],
+            BranchCounts {
+                taken: 1,
+                not_taken: 1
+            }
+        );
+        // Only the syscalls are counted by number
+        assert_eq!(
+            statistics.syscalls().iter().collect::<Vec<_>>(),
+            vec![(&SYSCALL_MMAP, &1)]
+        );
+        let never_executed = statistics.never_executed();
+        assert!(!never_executed.contains(&ADD));
+        assert!(never_executed.contains(&Instruction::RType(RTypeInstruction::Sub)));
+        assert_eq!(
+            never_executed.len(),
+            Instruction::iter().flat_map(|x| x.into_iter()).count() - 3
+        );
+
+        let mut report = vec![];
+        statistics.write_report(&mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.starts_with("cycles: 4\ninstructions:\n  IType(BranchEq) 2 (50.00%)\n"));
+        assert!(report.contains("  BranchEq taken 1 not taken 1\n"));
+        assert!(report.contains("syscalls:\n  4090 1\n"));
+    }
+}
@@ -75,10 +75,16 @@ fn main() -> ExitCode {
                 preimage_key, idx
             );
 
-            let expected = std::fs::read_to_string(path).unwrap();
+            let expected = std::fs::read_to_string(&path).unwrap();
 
             debug!("Asking for preimage");
-            let preimage = po.get_preimage(key);
+            let preimage = match po.get_preimage(key) {
+                Ok(preimage) => preimage,
+                Err(e) => {
+                    error!("Could not get the preimage of {}: {e}", preimage_key);
+                    return ExitCode::FAILURE;
+                }
+            };
             let got = hex::encode(preimage.get()).to_string();
 
             assert_eq!(expected, got);
@@ -17,3 +17,270 @@ fn test_correctly_parsing_elf() {
     // Which is the 17th
     assert_eq!(state.memory[0].index, 17);
 }
+
+#[test]
+fn test_parsing_elf_with_wrong_class() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/fibonacci",
+    ));
+    assert!(o1vm::elf_loader::parse_riscv64(&path).is_err());
+}
+
+/// A section of the ELF files built by [build_elf32]
+struct Section {
+    name: &'static str,
+    sh_type: u32,
+    flags: u32,
+    addr: u32,
+    data: Vec<u8>,
+    /// Size of the section in memory, used for the sections without data
+    size: u32,
+    align: u32,
+    link: u32,
+    entsize: u32,
+}
+
+impl Section {
+    fn new(name: &'static str, sh_type: u32, flags: u32, addr: u32, data: Vec<u8>) -> Self {
+        Section {
+            name,
+            sh_type,
+            flags,
+            addr,
+            size: data.len() as u32,
+            data,
+            align: 4,
+            link: 0,
+            entsize: 0,
+        }
+    }
+}
+
+/// Builds a little-endian RISC-V ELF32 file without program headers. The
+/// section names table is appended to the given sections.
+fn build_elf32(e_type: u16, entry: u32, sections: &[Section]) -> Vec<u8> {
+    let mut shstrtab = vec![0u8];
+    let mut names = vec![];
+    for name in sections.iter().map(|s| s.name).chain([".shstrtab"]) {
+        names.push(shstrtab.len() as u32);
+        shstrtab.extend(name.as_bytes());
+        shstrtab.push(0);
+    }
+
+    let mut data = vec![];
+    let mut offsets = vec![];
+    for section in sections {
+        offsets.push(52 + data.len() as u32);
+        data.extend(&section.data);
+    }
+    let shstrtab_offset = 52 + data.len() as u32;
+    data.extend(&shstrtab);
+    while data.len() % 4 != 0 {
+        data.push(0);
+    }
+    let shoff = 52 + data.len() as u32;
+    let shnum = sections.len() as u16 + 2;
+
+    let mut elf = vec![0x7f, b'E', b'L', b'F', 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    elf.extend(e_type.to_le_bytes());
+    elf.extend(243u16.to_le_bytes());
+    elf.extend(1u32.to_le_bytes());
+    elf.extend(entry.to_le_bytes());
+    elf.extend(0u32.to_le_bytes()); // e_phoff
+    elf.extend(shoff.to_le_bytes());
+    elf.extend(0u32.to_le_bytes()); // e_flags
+    elf.extend(52u16.to_le_bytes()); // e_ehsize
+    elf.extend(32u16.to_le_bytes()); // e_phentsize
+    elf.extend(0u16.to_le_bytes()); // e_phnum
+    elf.extend(40u16.to_le_bytes()); // e_shentsize
+    elf.extend(shnum.to_le_bytes());
+    elf.extend((shnum - 1).to_le_bytes()); // e_shstrndx
+    elf.extend(data);
+
+    let mut push_shdr = |fields: [u32; 10]| fields.iter().for_each(|f| elf.extend(f.to_le_bytes()));
+    push_shdr([0; 10]);
+    for (i, s) in sections.iter().enumerate() {
+        push_shdr([
+            names[i], s.sh_type, s.flags, s.addr, offsets[i], s.size, s.link, 0, s.align, s.entsize,
+        ]);
+    }
+    push_shdr([
+        names[sections.len()],
+        3, // SHT_STRTAB
+        0,
+        0,
+        shstrtab_offset,
+        shstrtab.len() as u32,
+        0,
+        0,
+        1,
+        0,
+    ]);
+    elf
+}
+
+fn parse_elf32(name: &str, elf: &[u8]) -> Result<o1vm::cannon::State, String> {
+    let path = std::env::temp_dir().join(format!("o1vm-test-elf-loader-{name}"));
+    std::fs::write(&path, elf).unwrap();
+    let state = o1vm::elf_loader::parse_riscv32(&path);
+    std::fs::remove_file(&path).unwrap();
+    state
+}
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+const SHT_NOBITS: u32 = 8;
+const SHF_WRITE: u32 = 1;
+const SHF_ALLOC: u32 = 2;
+const SHF_EXECINSTR: u32 = 4;
+const SHF_TLS: u32 = 1 << 10;
+const ET_EXEC: u16 = 2;
+const ET_DYN: u16 = 3;
+
+fn text_data_bss_sections() -> Vec<Section> {
+    let tbss = Section {
+        size: 0x100,
+        ..Section::new(
+            ".tbss",
+            SHT_NOBITS,
+            SHF_ALLOC | SHF_WRITE | SHF_TLS,
+            0x9000,
+            vec![],
+        )
+    };
+    let bss = Section {
+        size: 0x2000,
+        ..Section::new(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, 0x2100, vec![])
+    };
+    vec![
+        Section::new(
+            ".text",
+            SHT_PROGBITS,
+            SHF_ALLOC | SHF_EXECINSTR,
+            0x1000,
+            vec![0x13, 0, 0, 0],
+        ),
+        Section::new(
+            ".data",
+            SHT_PROGBITS,
+            SHF_ALLOC | SHF_WRITE,
+            0x2000,
+            vec![1, 2, 3, 4],
+        ),
+        bss,
+        tbss,
+        Section::new(".comment", SHT_PROGBITS, 0, 0, b"not loaded".to_vec()),
+    ]
+}
+
+#[test]
+fn test_loading_the_allocated_sections() {
+    let elf = build_elf32(ET_EXEC, 0x1000, &text_data_bss_sections());
+    let state = parse_elf32("sections", &elf).unwrap();
+    assert_eq!(state.pc, 0x1000);
+    assert_eq!(state.next_pc, 0x1004);
+
+    // The .bss section spans the pages 2 to 4, and the TLS section is ignored
+    let indices: Vec<_> = state.memory.iter().map(|page| page.index).collect();
+    assert_eq!(indices, vec![1, 2, 3, 4]);
+    assert_eq!(state.memory[0].data[0..4], [0x13, 0, 0, 0]);
+    assert_eq!(state.memory[1].data[0..4], [1, 2, 3, 4]);
+    assert!(state.memory[1].data[0x100..].iter().all(|b| *b == 0));
+    assert!(state.memory[3].data.iter().all(|b| *b == 0));
+}
+
+#[test]
+fn test_entry_point_from_start_symbol() {
+    let mut sections = text_data_bss_sections();
+    // The string table is the next section
+    let strtab_index = sections.len() as u32 + 2;
+    let mut symtab = vec![0u8; 16];
+    symtab.extend(1u32.to_le_bytes()); // st_name
+    symtab.extend(0x1000u32.to_le_bytes()); // st_value
+    symtab.extend(0u32.to_le_bytes()); // st_size
+    symtab.extend([0x12, 0]); // st_info, st_other
+    symtab.extend(1u16.to_le_bytes()); // st_shndx
+    sections.push(Section {
+        link: strtab_index,
+        entsize: 16,
+        ..Section::new(".symtab", SHT_SYMTAB, 0, 0, symtab)
+    });
+    sections.push(Section::new(
+        ".strtab",
+        SHT_STRTAB,
+        0,
+        0,
+        b"\0_start\0".to_vec(),
+    ));
+
+    let elf = build_elf32(ET_EXEC, 0, &sections);
+    let state = parse_elf32("start-symbol", &elf).unwrap();
+    assert_eq!(state.pc, 0x1000);
+
+    let elf = build_elf32(ET_EXEC, 0, &text_data_bss_sections());
+    assert!(parse_elf32("no-entry-point", &elf).is_err());
+}
+
+fn rela(r_offset: u32, r_type: u32, r_addend: i32) -> Vec<u8> {
+    [
+        r_offset.to_le_bytes(),
+        r_type.to_le_bytes(),
+        r_addend.to_le_bytes(),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_relative_relocations_of_static_pie() {
+    let mut sections = text_data_bss_sections();
+    sections.push(Section {
+        entsize: 12,
+        ..Section::new(
+            ".rela.dyn",
+            SHT_RELA,
+            SHF_ALLOC,
+            0x3000,
+            [rela(0x2000, 3, 0x1234), rela(0x2200, 3, 0x1000)].concat(),
+        )
+    });
+    let elf = build_elf32(ET_DYN, 0x1000, &sections);
+    let state = parse_elf32("static-pie", &elf).unwrap();
+    assert_eq!(state.memory[1].data[0..4], 0x1234u32.to_le_bytes());
+    assert_eq!(state.memory[1].data[0x200..0x204], 0x1000u32.to_le_bytes());
+
+    // R_RISCV_32 needs a symbol, and is not supported
+    let mut sections = text_data_bss_sections();
+    sections.push(Section {
+        entsize: 12,
+        ..Section::new(
+            ".rela.dyn",
+            SHT_RELA,
+            SHF_ALLOC,
+            0x3000,
+            rela(0x2000, (1 << 8) | 1, 0),
+        )
+    });
+    let elf = build_elf32(ET_DYN, 0x1000, &sections);
+    let err = parse_elf32("unsupported-relocation", &elf).unwrap_err();
+    assert!(err.contains("relocation of type"), "{err}");
+}
+
+#[test]
+fn test_unsupported_files() {
+    // Misaligned section
+    let mut sections = text_data_bss_sections();
+    sections[1].addr = 0x2002;
+    let elf = build_elf32(ET_EXEC, 0x1000, &sections);
+    let err = parse_elf32("misaligned", &elf).unwrap_err();
+    assert!(err.contains("not aligned"), "{err}");
+
+    // Relocatable object file
+    let elf = build_elf32(1, 0x1000, &text_data_bss_sections());
+    assert!(parse_elf32("object", &elf).is_err());
+
+    // Not an ELF file
+    assert!(parse_elf32("garbage", b"garbage").is_err());
+}
@@ -24,7 +24,7 @@ use groupmap::GroupMap;
 use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
 use o1_utils::{
     field_helpers::{inner_prod, pows},
-    math,
+    math, ExtendedDensePolynomial,
 };
 use rand::{CryptoRng, RngCore};
 use rayon::prelude::*;
@@ -406,22 +406,19 @@ where
         plnm: &DensePolynomial<G::ScalarField>,
         num_chunks: usize,
     ) -> PolyComm<G> {
-        let is_zero = plnm.is_zero();
-
-        let coeffs: Vec<_> = plnm.iter().map(|c| c.into_bigint()).collect();
-
-        // chunk while commiting
-        let mut chunks = vec![];
-        if is_zero {
-            chunks.push(G::zero());
-        } else {
-            coeffs.chunks(self.g.len()).for_each(|coeffs_chunk| {
-                let chunk = G::Group::msm_bigint(&self.g, coeffs_chunk);
-                chunks.push(chunk.into_affine());
-            });
-        }
-
-        for _ in chunks.len()..num_chunks {
+        // chunk while commiting, converting the coefficients of a single
+        // chunk at a time. Unused chunks are padded with zeros.
+        let mut chunks: Vec<G> = plnm
+            .chunks_iter(num_chunks, self.g.len())
+            .map(|coeffs_chunk| {
+                if coeffs_chunk.is_empty() {
+                    return G::zero();
+                }
+                let coeffs_chunk: Vec<_> = coeffs_chunk.iter().map(|c| c.into_bigint()).collect();
+                G::Group::msm_bigint(&self.g, &coeffs_chunk).into_affine()
+            })
+            .collect();
+        if chunks.is_empty() {
             chunks.push(G::zero());
         }
 
@@ -18,9 +18,10 @@ use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
 use ark_ff::{One, PrimeField, Zero};
 use ark_poly::{
     univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial, EvaluationDomain, Evaluations, Polynomial, Radix2EvaluationDomain as D,
+    DenseUVPolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
 };
 use mina_poseidon::FqSponge;
+use o1_utils::ExtendedDensePolynomial;
 use rand::thread_rng;
 use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
@@ -398,7 +399,7 @@ impl<
         polyscale: F,
     ) -> Option<Self> {
         let (p, blinding_factor) = combine_polys::<G, D>(plnms, polyscale, srs.full_srs.g.len());
-        let evals: Vec<_> = elm.iter().map(|pt| p.evaluate(pt)).collect();
+        let evals = p.par_evaluate_many(elm);
 
         let quotient_poly = {
             // This is where the condition on two points is enforced.
@@ -1,8 +1,8 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use mina_curves::pasta::Fp;
 use mina_poseidon::{
-    constants::PlonkSpongeConstantsKimchi,
-    pasta::fp_kimchi as SpongeParametersKimchi,
+    constants::{PlonkSpongeConstantsKimchi, PlonkSpongeConstantsPoseidon2},
+    pasta::{fp_kimchi as SpongeParametersKimchi, fp_poseidon2 as SpongeParametersPoseidon2},
     poseidon::{ArithmeticSponge as Poseidon, Sponge},
 };
 
@@ -23,6 +23,19 @@ pub fn bench_poseidon_kimchi(c: &mut Criterion) {
         })
     });
 
+    // Chain of hashes, starting from a random value
+    group.bench_function("poseidon_hash_poseidon2", |b| {
+        let mut hash: Fp = rand::random();
+        let mut poseidon = Poseidon::<Fp, PlonkSpongeConstantsPoseidon2>::new(
+            SpongeParametersPoseidon2::static_params(),
+        );
+
+        b.iter(|| {
+            poseidon.absorb(&[hash]);
+            hash = poseidon.squeeze();
+        })
+    });
+
     group.finish();
 }
 
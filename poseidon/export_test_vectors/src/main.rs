@@ -1,7 +1,7 @@
 mod vectors;
 use inner::*;
 
-/// "Usage: cargo run --all-features --bin export_test_vectors -- [hex|b10] [legacy|kimchi] <OUTPUT_FILE>",
+/// "Usage: cargo run --all-features --bin export_test_vectors -- [hex|b10] [legacy|kimchi|poseidon2] <OUTPUT_FILE>",
 fn main() {
     inner::main();
 }
@@ -37,6 +37,7 @@ mod inner {
     pub enum ParamType {
         Legacy,
         Kimchi,
+        Poseidon2,
     }
 
     impl FromStr for ParamType {
@@ -46,6 +47,7 @@ mod inner {
             match input.to_lowercase().as_str() {
                 "legacy" => Ok(ParamType::Legacy),
                 "kimchi" => Ok(ParamType::Kimchi),
+                "poseidon2" => Ok(ParamType::Poseidon2),
                 _ => Err(()),
             }
         }
@@ -80,7 +82,7 @@ mod inner {
             }
             _ => {
                 println!(
-                "usage: cargo run -p export_test_vectors -- [{:?}|{:?}] [legacy|kimchi] <OUTPUT_FILE>",
+                "usage: cargo run -p export_test_vectors -- [{:?}|{:?}] [legacy|kimchi|poseidon2] <OUTPUT_FILE>",
                 Mode::Hex,
                 Mode::B10,
             );
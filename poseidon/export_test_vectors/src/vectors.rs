@@ -71,6 +71,10 @@ pub fn generate(mode: Mode, param_type: ParamType) -> TestVectors {
                 &input,
                 pasta::fp_kimchi::static_params(),
             ),
+            ParamType::Poseidon2 => poseidon::<constants::PlonkSpongeConstantsPoseidon2>(
+                &input,
+                pasta::fp_poseidon2::static_params(),
+            ),
         };
 
         // serialize input & output
@@ -107,6 +111,7 @@ pub fn generate(mode: Mode, param_type: ParamType) -> TestVectors {
     let name = match param_type {
         ParamType::Legacy => "legacy",
         ParamType::Kimchi => "kimchi",
+        ParamType::Poseidon2 => "poseidon2",
     }
     .into();
 
@@ -181,15 +186,45 @@ mod tests {
             ],
         ];
 
+        let expected_output_bytes_poseidon2 = [
+            [
+                12, 55, 149, 218, 212, 238, 65, 167, 241, 156, 206, 159, 31, 199, 206, 245, 79, 62,
+                117, 20, 236, 178, 239, 36, 67, 230, 176, 146, 120, 242, 216, 36,
+            ],
+            [
+                87, 80, 11, 116, 79, 147, 12, 17, 132, 183, 79, 209, 36, 63, 61, 109, 224, 242,
+                181, 166, 185, 100, 71, 65, 135, 236, 97, 139, 195, 114, 164, 54,
+            ],
+            [
+                83, 62, 233, 206, 39, 1, 212, 6, 53, 199, 133, 119, 144, 248, 43, 224, 194, 239,
+                132, 102, 214, 1, 83, 207, 163, 250, 189, 143, 247, 94, 207, 2,
+            ],
+            [
+                143, 212, 222, 123, 196, 144, 54, 202, 163, 160, 225, 234, 234, 80, 214, 43, 30,
+                62, 47, 61, 200, 27, 36, 197, 67, 99, 124, 160, 32, 216, 201, 41,
+            ],
+            [
+                149, 32, 194, 66, 129, 197, 75, 202, 103, 57, 20, 47, 181, 179, 85, 61, 246, 181,
+                114, 69, 63, 6, 113, 62, 103, 212, 100, 126, 70, 211, 98, 25,
+            ],
+            [
+                118, 235, 7, 135, 111, 233, 152, 208, 75, 117, 34, 64, 186, 98, 228, 32, 245, 24,
+                169, 165, 203, 47, 122, 170, 69, 34, 204, 197, 80, 70, 228, 47,
+            ],
+        ];
+
         let expected_output_0_hex_legacy =
             "1b3251b6912d82edc78bbb0a5c88f0c6fde1781bc3e654123fa6862a4c63e617";
         let expected_output_0_hex_kimchi =
             "a8eb9ee0f30046308abbfa5d20af73c81bbdabc25b459785024d045228bead2f";
+        let expected_output_0_hex_poseidon2 =
+            "0c3795dad4ee41a7f19cce9f1fc7cef54f3e7514ecb2ef2443e6b09278f2d824";
 
-        for param_type in [ParamType::Legacy, ParamType::Kimchi] {
+        for param_type in [ParamType::Legacy, ParamType::Kimchi, ParamType::Poseidon2] {
             let expected_output_bytes = match param_type {
                 ParamType::Legacy => &expected_output_bytes_legacy,
                 ParamType::Kimchi => &expected_output_bytes_kimchi,
+                ParamType::Poseidon2 => &expected_output_bytes_poseidon2,
             };
 
             for length in 0..6 {
@@ -204,6 +239,10 @@ mod tests {
                         &input,
                         pasta::fp_kimchi::static_params(),
                     ),
+                    ParamType::Poseidon2 => poseidon::<constants::PlonkSpongeConstantsPoseidon2>(
+                        &input,
+                        pasta::fp_poseidon2::static_params(),
+                    ),
                 };
 
                 let mut output_bytes = vec![];
@@ -218,6 +257,7 @@ mod tests {
             let expected_output_0_hex = match param_type {
                 ParamType::Legacy => expected_output_0_hex_legacy,
                 ParamType::Kimchi => expected_output_0_hex_kimchi,
+                ParamType::Poseidon2 => expected_output_0_hex_poseidon2,
             };
 
             let test_vectors_hex = generate(Mode::Hex, param_type);
@@ -0,0 +1,30 @@
+//! Poseidon parameters for the BN254 fields, generated with the Grain LFSR of
+//! the reference implementation of Poseidon, see
+//! [crate::parameters::grain_params]. They are used with
+//! [crate::constants::PlonkSpongeConstantsBn254].
+//!
+//! The parameters of the scalar field are the ones of circomlib, and
+//! [circom_hash] computes the Poseidon hash of circomlib for two inputs.
+
+use crate::{
+    constants::PlonkSpongeConstantsBn254, permutation::poseidon_block_cipher,
+    sponge::DefaultFqSponge,
+};
+use ark_bn254::Fr;
+use ark_ff::Zero;
+
+pub mod fq;
+pub mod fr;
+
+/// The sponge absorbing the commitments to the polynomials over the scalar
+/// field of BN254, whose coordinates are in the base field
+pub type Bn254FqSponge = DefaultFqSponge<ark_bn254::g1::Config, PlonkSpongeConstantsBn254>;
+
+/// The Poseidon hash of circomlib (`Poseidon(2)`). Unlike
+/// [crate::poseidon::ArithmeticSponge], circomlib puts the capacity in the
+/// first element of the state, and the inputs in the others.
+pub fn circom_hash(inputs: [Fr; 2]) -> Fr {
+    let mut state = vec![Fr::zero(), inputs[0], inputs[1]];
+    poseidon_block_cipher::<Fr, PlonkSpongeConstantsBn254>(fr::static_params(), &mut state);
+    state[0]
+}
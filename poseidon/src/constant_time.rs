@@ -0,0 +1,335 @@
+//! This module implements the Poseidon permutation in constant time, for the
+//! prime fields in Montgomery form of arkworks.
+//!
+//! The arithmetic of arkworks reduces the results of the additions and
+//! multiplications with a conditional subtraction of the modulus, which
+//! depends on the values of the operands. The functions of this module select
+//! the reduced result with masks instead, so that the sequence of instructions
+//! and of memory accesses of the permutation only depends on the parameters,
+//! and not on the state.
+//!
+//! The masks go through [std::hint::black_box], so that the compiler does not
+//! turn the selections back into branches.
+//!
+//! When the feature `constant_time` is enabled,
+//! [crate::poseidon::ArithmeticSponge] uses this permutation, and the
+//! addition of this module to absorb the inputs, for the fields of
+//! [crate::pasta] and [crate::bn254].
+//!
+//! The sponges of the other fields still use the variable-time arithmetic of
+//! arkworks, which is rejected by a debug assertion. The squeezed elements
+//! are returned as they are, and what the callers do with them, e.g. the
+//! conversions out of the Montgomery form of the sponges of [crate::sponge],
+//! is not constant time either.
+
+use crate::{constants::SpongeConstants, permutation::nb_rounds, poseidon::ArithmeticSpongeParams};
+use ark_ff::{
+    fields::{Fp, MontBackend, MontConfig},
+    BigInt, One,
+};
+use std::{any::Any, hint::black_box};
+
+type MontFp<P, const N: usize> = Fp<MontBackend<P, N>, N>;
+
+/// Returns `a` if `choice` is 1 and `b` if `choice` is 0
+#[inline(always)]
+fn select<const N: usize>(choice: u64, a: &[u64; N], b: &[u64; N]) -> [u64; N] {
+    let mask = black_box(choice).wrapping_neg();
+    std::array::from_fn(|i| (a[i] & mask) | (b[i] & !mask))
+}
+
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+#[inline(always)]
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+#[inline(always)]
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Subtract the modulus from `(carry, a)` if it is greater than the modulus
+#[inline(always)]
+fn reduce<P: MontConfig<N>, const N: usize>(a: [u64; N], carry: u64) -> [u64; N] {
+    let mut b = [0u64; N];
+    let mut borrow = 0;
+    for i in 0..N {
+        (b[i], borrow) = sbb(a[i], P::MODULUS.0[i], borrow);
+    }
+    // Keep the subtraction if it did not underflow, or if there was a carry
+    let (_, borrow) = sbb(carry, 0, borrow);
+    select(black_box(borrow), &a, &b)
+}
+
+/// Constant-time addition of two field elements
+pub fn add<P: MontConfig<N>, const N: usize>(a: MontFp<P, N>, b: MontFp<P, N>) -> MontFp<P, N> {
+    let mut r = [0u64; N];
+    let mut carry = 0;
+    for (r_i, (a_i, b_i)) in r.iter_mut().zip(a.0 .0.iter().zip(b.0 .0.iter())) {
+        (*r_i, carry) = adc(*a_i, *b_i, carry);
+    }
+    Fp::new_unchecked(BigInt(reduce::<P, N>(r, carry)))
+}
+
+/// Constant-time Montgomery multiplication of two field elements (CIOS)
+pub fn mul<P: MontConfig<N>, const N: usize>(a: MontFp<P, N>, b: MontFp<P, N>) -> MontFp<P, N> {
+    let (a, b, modulus) = (a.0 .0, b.0 .0, P::MODULUS.0);
+    let mut t = [0u64; N];
+    let mut t_n = 0u64;
+    for b_i in b.iter() {
+        let mut carry = 0;
+        for j in 0..N {
+            (t[j], carry) = mac(t[j], a[j], *b_i, carry);
+        }
+        let (t_n_lo, t_n_hi) = adc(t_n, carry, 0);
+
+        let m = t[0].wrapping_mul(P::INV);
+        let (_, mut carry) = mac(t[0], m, modulus[0], 0);
+        for j in 1..N {
+            (t[j - 1], carry) = mac(t[j], m, modulus[j], carry);
+        }
+        let (lo, hi) = adc(t_n_lo, carry, 0);
+        t[N - 1] = lo;
+        t_n = t_n_hi + hi;
+    }
+    Fp::new_unchecked(BigInt(reduce::<P, N>(t, t_n)))
+}
+
+/// The S-box `x^PERM_SBOX`, with a square-and-multiply on the bits of the
+/// public exponent
+pub fn sbox<P: MontConfig<N>, const N: usize, SC: SpongeConstants>(
+    x: MontFp<P, N>,
+) -> MontFp<P, N> {
+    let mut res = MontFp::<P, N>::one();
+    for i in (0..u32::BITS - SC::PERM_SBOX.leading_zeros()).rev() {
+        res = mul(res, res);
+        if (SC::PERM_SBOX >> i) & 1 == 1 {
+            res = mul(res, x);
+        }
+    }
+    res
+}
+
+fn apply_mds_matrix<P: MontConfig<N>, const N: usize, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<MontFp<P, N>>,
+    state: &mut [MontFp<P, N>],
+) {
+    let res: Vec<_> = if SC::PERM_POSEIDON2 {
+        unreachable!("the linear layers of Poseidon2 are applied separately")
+    } else if SC::PERM_FULL_MDS {
+        params
+            .mds
+            .iter()
+            .map(|m| {
+                state
+                    .iter()
+                    .zip(m.iter())
+                    .fold(MontFp::<P, N>::default(), |x, (s, m)| add(mul(*m, *s), x))
+            })
+            .collect()
+    } else {
+        vec![
+            add(state[0], state[2]),
+            add(state[0], state[1]),
+            add(state[1], state[2]),
+        ]
+    };
+    state.copy_from_slice(&res);
+}
+
+fn add_round_constants<P: MontConfig<N>, const N: usize>(
+    params: &ArithmeticSpongeParams<MontFp<P, N>>,
+    state: &mut [MontFp<P, N>],
+    r: usize,
+) {
+    for (state_i, x) in state.iter_mut().zip(params.round_constants[r].iter()) {
+        *state_i = add(*state_i, *x);
+    }
+}
+
+fn poseidon2_external_matrix<P: MontConfig<N>, const N: usize>(state: &mut [MontFp<P, N>]) {
+    let sum = state
+        .iter()
+        .fold(MontFp::<P, N>::default(), |x, y| add(x, *y));
+    for state_i in state.iter_mut() {
+        *state_i = add(*state_i, sum);
+    }
+}
+
+fn poseidon2_internal_matrix<P: MontConfig<N>, const N: usize>(
+    params: &ArithmeticSpongeParams<MontFp<P, N>>,
+    state: &mut [MontFp<P, N>],
+) {
+    let sum = state
+        .iter()
+        .fold(MontFp::<P, N>::default(), |x, y| add(x, *y));
+    for (i, state_i) in state.iter_mut().enumerate() {
+        // The parameters are public
+        let diagonal = params.mds[i][i] - MontFp::<P, N>::one();
+        *state_i = add(sum, mul(diagonal, *state_i));
+    }
+}
+
+/// Apply the permutation described by `SC` to `state` in constant time. It
+/// computes the same function as [crate::permutation::poseidon_block_cipher].
+pub fn poseidon_block_cipher<P: MontConfig<N>, const N: usize, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<MontFp<P, N>>,
+    state: &mut [MontFp<P, N>],
+) {
+    let is_partial = |r: usize| {
+        (SC::PERM_HALF_ROUNDS_FULL..SC::PERM_HALF_ROUNDS_FULL + SC::PERM_ROUNDS_PARTIAL)
+            .contains(&r)
+    };
+
+    if SC::PERM_POSEIDON2 {
+        poseidon2_external_matrix(state);
+        for r in 0..nb_rounds::<SC>() {
+            if is_partial(r) {
+                state[0] = sbox::<P, N, SC>(add(state[0], params.round_constants[r][0]));
+                poseidon2_internal_matrix(params, state);
+            } else {
+                add_round_constants(params, state, r);
+                for state_i in state.iter_mut() {
+                    *state_i = sbox::<P, N, SC>(*state_i);
+                }
+                poseidon2_external_matrix(state);
+            }
+        }
+    } else if SC::PERM_HALF_ROUNDS_FULL != 0 {
+        for r in 0..nb_rounds::<SC>() {
+            add_round_constants(params, state, r);
+            if is_partial(r) {
+                state[0] = sbox::<P, N, SC>(state[0]);
+            } else {
+                for state_i in state.iter_mut() {
+                    *state_i = sbox::<P, N, SC>(*state_i);
+                }
+            }
+            apply_mds_matrix::<P, N, SC>(params, state);
+        }
+    } else {
+        let offset = usize::from(SC::PERM_INITIAL_ARK);
+        if SC::PERM_INITIAL_ARK {
+            add_round_constants(params, state, 0);
+        }
+        for r in 0..SC::PERM_ROUNDS_FULL {
+            for state_i in state.iter_mut() {
+                *state_i = sbox::<P, N, SC>(*state_i);
+            }
+            apply_mds_matrix::<P, N, SC>(params, state);
+            add_round_constants(params, state, r + offset);
+        }
+    }
+}
+
+/// Overwrite `state` with zeros, in a way that is not optimized away by the
+/// compiler, to erase secret data once it has been hashed.
+pub fn zeroize<F: Default>(state: &mut [F]) {
+    for x in state.iter_mut() {
+        // SAFETY: `x` is a valid and aligned reference
+        unsafe { std::ptr::write_volatile(x, F::default()) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// The fields in Montgomery form, whatever their number of limbs
+trait ConstantTimeField: ark_ff::Field {
+    fn add_ct(self, other: Self) -> Self;
+
+    fn poseidon_block_cipher<SC: SpongeConstants>(
+        params: &ArithmeticSpongeParams<Self>,
+        state: &mut [Self],
+    );
+}
+
+impl<P: MontConfig<N>, const N: usize> ConstantTimeField for MontFp<P, N> {
+    fn add_ct(self, other: Self) -> Self {
+        add(self, other)
+    }
+
+    fn poseidon_block_cipher<SC: SpongeConstants>(
+        params: &ArithmeticSpongeParams<Self>,
+        state: &mut [Self],
+    ) {
+        poseidon_block_cipher::<P, N, SC>(params, state)
+    }
+}
+
+/// Expand `$try_field` for each field of [crate::pasta] and [crate::bn254]
+macro_rules! for_each_field {
+    ($try_field:ident) => {
+        $try_field!(mina_curves::pasta::Fp);
+        $try_field!(mina_curves::pasta::Fq);
+        $try_field!(ark_bn254::Fr);
+        $try_field!(ark_bn254::Fq);
+    };
+}
+
+/// Apply the permutation in constant time if `F` is one of the fields of
+/// [crate::pasta] or [crate::bn254], and with [crate::permutation] otherwise.
+///
+/// # Panics
+///
+/// In debug builds, if `F` is not one of these fields.
+#[cfg_attr(not(feature = "constant_time"), allow(dead_code))]
+pub(crate) fn dispatch<F: ark_ff::Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut Vec<F>,
+) {
+    macro_rules! try_field {
+        ($field:ty) => {
+            if let (Some(params), Some(state)) = (
+                (params as &dyn Any).downcast_ref::<ArithmeticSpongeParams<$field>>(),
+                (&mut *state as &mut dyn Any).downcast_mut::<Vec<$field>>(),
+            ) {
+                return <$field as ConstantTimeField>::poseidon_block_cipher::<SC>(params, state);
+            }
+        };
+    }
+    for_each_field!(try_field);
+
+    debug_assert!(
+        false,
+        "No constant-time permutation for the field {}",
+        std::any::type_name::<F>()
+    );
+    crate::permutation::poseidon_block_cipher::<F, SC>(params, state)
+}
+
+/// Add `x` to `y` in constant time if `F` is one of the fields of
+/// [crate::pasta] or [crate::bn254], and with the arithmetic of arkworks
+/// otherwise.
+///
+/// # Panics
+///
+/// In debug builds, if `F` is not one of these fields.
+#[cfg_attr(not(feature = "constant_time"), allow(dead_code))]
+pub(crate) fn add_assign<F: ark_ff::Field>(y: &mut F, x: &F) {
+    macro_rules! try_field {
+        ($field:ty) => {
+            if let (Some(y), Some(x)) = (
+                (&mut *y as &mut dyn Any).downcast_mut::<$field>(),
+                (x as &dyn Any).downcast_ref::<$field>(),
+            ) {
+                *y = y.add_ct(*x);
+                return;
+            }
+        };
+    }
+    for_each_field!(try_field);
+
+    debug_assert!(
+        false,
+        "No constant-time addition for the field {}",
+        std::any::type_name::<F>()
+    );
+    *y += x
+}
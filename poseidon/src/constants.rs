@@ -8,6 +8,9 @@ pub trait SpongeConstants {
     const PERM_SBOX: u32;
     const PERM_FULL_MDS: bool;
     const PERM_INITIAL_ARK: bool;
+    /// Use the Poseidon2 round structure, see
+    /// [crate::permutation::poseidon2_block_cipher].
+    const PERM_POSEIDON2: bool = false;
 }
 
 #[derive(Clone)]
@@ -39,3 +42,39 @@ impl SpongeConstants for PlonkSpongeConstantsKimchi {
     const PERM_FULL_MDS: bool = true;
     const PERM_INITIAL_ARK: bool = false;
 }
+
+/// Poseidon2 over the Pasta fields, with 8 full rounds and 56 partial rounds.
+/// The round constants are given by [crate::pasta::fp_poseidon2] and
+/// [crate::pasta::fq_poseidon2].
+#[derive(Clone)]
+pub struct PlonkSpongeConstantsPoseidon2 {}
+
+impl SpongeConstants for PlonkSpongeConstantsPoseidon2 {
+    const SPONGE_CAPACITY: usize = 1;
+    const SPONGE_WIDTH: usize = 3;
+    const SPONGE_RATE: usize = 2;
+    const PERM_ROUNDS_FULL: usize = 8;
+    const PERM_ROUNDS_PARTIAL: usize = 56;
+    const PERM_HALF_ROUNDS_FULL: usize = 4;
+    const PERM_SBOX: u32 = 5;
+    const PERM_FULL_MDS: bool = false;
+    const PERM_INITIAL_ARK: bool = false;
+    const PERM_POSEIDON2: bool = true;
+}
+
+/// Poseidon over the BN254 fields, with 8 full rounds and 57 partial rounds.
+/// The parameters are given by [crate::bn254::fr] and [crate::bn254::fq].
+#[derive(Clone)]
+pub struct PlonkSpongeConstantsBn254 {}
+
+impl SpongeConstants for PlonkSpongeConstantsBn254 {
+    const SPONGE_CAPACITY: usize = 1;
+    const SPONGE_WIDTH: usize = 3;
+    const SPONGE_RATE: usize = 2;
+    const PERM_ROUNDS_FULL: usize = 8;
+    const PERM_ROUNDS_PARTIAL: usize = 57;
+    const PERM_HALF_ROUNDS_FULL: usize = 4;
+    const PERM_SBOX: u32 = 5;
+    const PERM_FULL_MDS: bool = true;
+    const PERM_INITIAL_ARK: bool = false;
+}
@@ -1,8 +1,13 @@
+pub mod bn254;
+pub mod constant_time;
 pub mod constants;
 pub mod dummy_values;
+pub mod merkle;
+pub mod parameters;
 pub mod pasta;
 pub mod permutation;
 pub mod poseidon;
 pub mod sponge;
+pub mod streaming;
 
 pub use sponge::FqSponge; // Commonly used so reexported for convenience
@@ -0,0 +1,90 @@
+//! This module implements an incremental binary Merkle tree hashed with
+//! Poseidon. A parent is the hash of its two children, and the last node of an
+//! odd layer is paired with zero.
+//! Appending leaves only rehashes the nodes whose children changed, i.e. the
+//! new nodes and the last node of each layer.
+
+use crate::{
+    constants::SpongeConstants,
+    poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge},
+};
+use ark_ff::Field;
+use rayon::prelude::*;
+use std::marker::PhantomData;
+
+/// Hash a layer of the tree into its parent layer, in parallel
+pub fn hash_layer<F: Field, SC: SpongeConstants>(
+    params: &'static ArithmeticSpongeParams<F>,
+    layer: &[F],
+) -> Vec<F> {
+    layer
+        .par_chunks(2)
+        .map(|children| {
+            let mut sponge = ArithmeticSponge::<F, SC>::new(params);
+            sponge.absorb(children);
+            if children.len() == 1 {
+                sponge.absorb(&[F::zero()]);
+            }
+            sponge.squeeze()
+        })
+        .collect()
+}
+
+/// An incremental binary Merkle tree, see the documentation of the module
+#[derive(Clone)]
+pub struct MerkleTree<F: Field, SC: SpongeConstants> {
+    params: &'static ArithmeticSpongeParams<F>,
+    /// The layers of the tree, starting from the leaves. The last layer
+    /// contains the root, unless the tree is empty.
+    layers: Vec<Vec<F>>,
+    _constants: PhantomData<SC>,
+}
+
+impl<F: Field, SC: SpongeConstants> MerkleTree<F, SC> {
+    /// Create an empty tree
+    pub fn new(params: &'static ArithmeticSpongeParams<F>) -> Self {
+        MerkleTree {
+            params,
+            layers: vec![vec![]],
+            _constants: PhantomData,
+        }
+    }
+
+    /// Append `leaves` to the tree and update the parents of the new leaves
+    pub fn extend(&mut self, leaves: &[F]) {
+        // The index of the first node of the current layer that has changed
+        let mut first_changed = self.layers[0].len();
+        self.layers[0].extend_from_slice(leaves);
+
+        let mut depth = 0;
+        while self.layers[depth].len() > 1 {
+            if self.layers.len() == depth + 1 {
+                self.layers.push(vec![]);
+            }
+            // The sibling of the first changed node has to be rehashed too
+            let first_parent = first_changed / 2;
+            let parents = hash_layer::<F, SC>(self.params, &self.layers[depth][2 * first_parent..]);
+            self.layers[depth + 1].truncate(first_parent);
+            self.layers[depth + 1].extend(parents);
+
+            first_changed = first_parent;
+            depth += 1;
+        }
+    }
+
+    /// The leaves of the tree
+    pub fn leaves(&self) -> &[F] {
+        &self.layers[0]
+    }
+
+    /// The layers of the tree, starting from the leaves
+    pub fn layers(&self) -> &[Vec<F>] {
+        &self.layers
+    }
+
+    /// The root of the tree, or `None` if the tree is empty. The root of a tree
+    /// with a single leaf is the leaf itself.
+    pub fn root(&self) -> Option<F> {
+        self.layers.last().and_then(|layer| layer.first()).copied()
+    }
+}
@@ -0,0 +1,351 @@
+//! This module generates the round constants and the MDS matrix of a Poseidon
+//! instance over an arbitrary prime field. It follows the procedure of the
+//! script `pasta/params.sage`, which has been used to generate the Pasta
+//! parameters shipped in [crate::pasta]:
+//! - each field element is derived from a seed and an index by hashing them
+//!   with SHA-256, rejecting the digests which are not smaller than the
+//!   modulus.
+//! - the MDS matrix is a Cauchy matrix `1 / (x_i - y_j)` where the `x_i` and
+//!   `y_j` are pairwise distinct, which makes it invertible and MDS. Candidate
+//!   matrices having an eigenvalue in the field are rejected.
+//!
+//! For instance, the parameters of [crate::pasta::fp_kimchi] are obtained with
+//! `params::<Fp>("Pasta_p_kimchi", 3, 55)`.
+//!
+//! The parameters can also be generated with the Grain LFSR of the reference
+//! implementation of Poseidon, see [grain_params], which is the procedure used
+//! by most other implementations, e.g. circomlib.
+
+use crate::poseidon::ArithmeticSpongeParams;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_poly::{
+    univariate::{DenseOrSparsePolynomial, DensePolynomial},
+    DenseUVPolynomial, Polynomial,
+};
+use sha2::{Digest, Sha256};
+
+/// The prefix of the seeds of the named parameter sets
+pub const SEED_PREFIX: &str = "CodaRescue";
+
+/// The maximum number of candidates tried for the MDS matrix
+pub const MDS_ATTEMPTS: usize = 100;
+
+/// Derive a field element from `seed` and `index`. The digests of
+/// `"{seed}{index}_{j}"` for `j = 0, 1, ...` are read as big-endian integers
+/// until one of them is smaller than the modulus.
+/// For fields of at most 192 bits, only the lowest `64 * N` bits of the
+/// digests are kept, where `N` is the number of limbs of [PrimeField::BigInt],
+/// so that sampling in small fields like Goldilocks stays efficient.
+pub fn random_value<F: PrimeField>(seed: &str, index: usize) -> F {
+    let nb_bits = std::cmp::min(256, 64 * F::BigInt::NUM_LIMBS);
+    for j in 0.. {
+        let digest = Sha256::digest(format!("{seed}{index}_{j}").as_bytes());
+        let bits: Vec<bool> = digest
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+            .collect();
+        let bigint = F::BigInt::from_bits_be(&bits[256 - nb_bits..]);
+        if let Some(x) = F::from_bigint(bigint) {
+            return x;
+        }
+    }
+    unreachable!()
+}
+
+/// Generate `rounds` rows of `width` round constants, the `i`-th constant of
+/// the round `r` being derived from `seed` and the index `r * width + i`.
+pub fn round_constants<F: PrimeField>(seed: &str, width: usize, rounds: usize) -> Vec<Vec<F>> {
+    (0..rounds)
+        .map(|r| {
+            (0..width)
+                .map(|i| random_value(seed, r * width + i))
+                .collect()
+        })
+        .collect()
+}
+
+/// Generate a `width x width` MDS matrix from `seed`. Returns `None` if no
+/// suitable matrix has been found after [MDS_ATTEMPTS] candidates.
+pub fn mds<F: PrimeField>(seed: &str, width: usize) -> Option<Vec<Vec<F>>> {
+    (0..MDS_ATTEMPTS).find_map(|attempt| {
+        let xs: Vec<F> = (0..width)
+            .map(|i| random_value(&format!("{seed}x"), attempt * width + i))
+            .collect();
+        let ys: Vec<F> = (0..width)
+            .map(|i| random_value(&format!("{seed}y"), attempt * width + i))
+            .collect();
+
+        // The Cauchy matrix is invertible if and only if the values are
+        // pairwise distinct.
+        let values: Vec<&F> = xs.iter().chain(ys.iter()).collect();
+        let distinct = values
+            .iter()
+            .enumerate()
+            .all(|(i, x)| values[i + 1..].iter().all(|y| x != y));
+        if !distinct {
+            return None;
+        }
+
+        let mds: Vec<Vec<F>> = xs
+            .iter()
+            .map(|x| ys.iter().map(|y| (*x - y).inverse().unwrap()).collect())
+            .collect();
+
+        if has_root_in_field(&characteristic_polynomial(&mds)) {
+            None
+        } else {
+            Some(mds)
+        }
+    })
+}
+
+/// Generate the parameters of the named Poseidon instance `name`, with a state
+/// of `width` elements and `rounds` rounds. The seeds are prefixed by
+/// [SEED_PREFIX], as in the named mode of `pasta/params.sage`.
+pub fn params<F: PrimeField>(
+    name: &str,
+    width: usize,
+    rounds: usize,
+) -> Option<ArithmeticSpongeParams<F>> {
+    let seed = format!("{SEED_PREFIX}{name}");
+    Some(ArithmeticSpongeParams {
+        round_constants: round_constants(&format!("{seed}RoundConstants"), width, rounds),
+        mds: mds(&format!("{seed}MDS"), width)?,
+    })
+}
+
+/// Generate the parameters of a Poseidon2 instance with a state of 3 elements
+/// with the Grain LFSR, as in the reference implementation of Poseidon2
+/// (`poseidon2_rust_params.sage`). The full rounds have one round constant per
+/// element of the state and the partial rounds only one, all sampled in order
+/// from the same stream. The MDS matrix is the internal matrix of Poseidon2,
+/// see [crate::permutation::poseidon2_block_cipher].
+pub fn poseidon2_params<F: PrimeField>(
+    full_rounds: usize,
+    partial_rounds: usize,
+) -> ArithmeticSpongeParams<F> {
+    let width = 3;
+    let half_full_rounds = full_rounds / 2;
+    let nb_bits = F::MODULUS_BIT_SIZE as usize;
+    let mut grain = Grain::new(nb_bits, width, full_rounds, partial_rounds);
+    let round_constants = (0..full_rounds + partial_rounds)
+        .map(|r| {
+            let is_partial = (half_full_rounds..half_full_rounds + partial_rounds).contains(&r);
+            let nb_constants = if is_partial { 1 } else { width };
+            (0..nb_constants)
+                .map(|_| grain.next_canonical_field_element(nb_bits))
+                .collect()
+        })
+        .collect();
+    let mds = [[2u64, 1, 1], [1, 2, 1], [1, 1, 3]]
+        .iter()
+        .map(|row| row.iter().map(|x| F::from(*x)).collect())
+        .collect();
+    ArithmeticSpongeParams {
+        round_constants,
+        mds,
+    }
+}
+
+/// The Grain LFSR in self-shrinking mode, used as a pseudo-random bit
+/// generator by the reference implementation of Poseidon
+struct Grain {
+    state: std::collections::VecDeque<bool>,
+}
+
+impl Grain {
+    /// Initialize the LFSR for a prime field of `nb_bits` bits and the S-box
+    /// `x^alpha`
+    fn new(nb_bits: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let bits = |x: usize, n: usize| (0..n).rev().map(move |i| (x >> i) & 1 == 1);
+        let state = bits(1, 2)
+            .chain(bits(0, 4))
+            .chain(bits(nb_bits, 12))
+            .chain(bits(width, 12))
+            .chain(bits(full_rounds, 10))
+            .chain(bits(partial_rounds, 10))
+            .chain(std::iter::repeat(true).take(30))
+            .collect();
+        let mut grain = Grain { state };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.pop_front();
+        self.state.push_back(bit);
+        bit
+    }
+
+    /// Output the second bit of the pairs whose first bit is set
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let (keep, bit) = (self.step(), self.step());
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Read `nb_bits` bits as a big-endian integer, reduced modulo the order of
+    /// the field
+    fn next_field_element<F: PrimeField>(&mut self, nb_bits: usize) -> F {
+        let bits: Vec<bool> = (0..nb_bits).map(|_| self.next_bit()).collect();
+        F::from_be_bytes_mod_order(&bits_to_be_bytes(&bits))
+    }
+
+    /// Read integers of `nb_bits` bits until one is smaller than the modulus
+    fn next_canonical_field_element<F: PrimeField>(&mut self, nb_bits: usize) -> F {
+        loop {
+            let bits: Vec<bool> = (0..nb_bits).map(|_| self.next_bit()).collect();
+            if let Some(x) = F::from_bigint(F::BigInt::from_bits_be(&bits)) {
+                return x;
+            }
+        }
+    }
+}
+
+fn bits_to_be_bytes(bits: &[bool]) -> Vec<u8> {
+    let padding = (8 - bits.len() % 8) % 8;
+    let padded: Vec<bool> = std::iter::repeat(false)
+        .take(padding)
+        .chain(bits.iter().copied())
+        .collect();
+    padded
+        .chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .fold(0u8, |acc, bit| (acc << 1) | u8::from(*bit))
+        })
+        .collect()
+}
+
+/// Generate the parameters of a Poseidon instance with the S-box `x^alpha`
+/// with the Grain LFSR, as in the reference implementation of Poseidon
+/// (`generate_parameters_grain.sage`). The round constants are sampled first,
+/// then the candidate Cauchy matrices `1 / (x_i + y_j)`. Candidates are
+/// rejected until the matrix and its powers up to `2 * width` have no
+/// eigenvalue in the field, which rules out the invariant subspaces of
+/// Algorithm 1 of the reference implementation for a width of 3.
+///
+/// For instance, the parameters of [crate::bn254::fr], which are the ones of
+/// circomlib for two inputs, are obtained with
+/// `grain_params::<ark_bn254::Fr>(3, 8, 57)`.
+pub fn grain_params<F: PrimeField>(
+    width: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+) -> ArithmeticSpongeParams<F> {
+    let nb_bits = F::MODULUS_BIT_SIZE as usize;
+    let mut grain = Grain::new(nb_bits, width, full_rounds, partial_rounds);
+
+    let round_constants = (0..full_rounds + partial_rounds)
+        .map(|_| {
+            (0..width)
+                .map(|_| grain.next_canonical_field_element(nb_bits))
+                .collect()
+        })
+        .collect();
+
+    let mds = loop {
+        let values: Vec<F> = loop {
+            let values: Vec<F> = (0..2 * width)
+                .map(|_| grain.next_field_element(nb_bits))
+                .collect();
+            let distinct = values
+                .iter()
+                .enumerate()
+                .all(|(i, x)| values[i + 1..].iter().all(|y| x != y));
+            if distinct {
+                break values;
+            }
+        };
+        let (xs, ys) = values.split_at(width);
+        let Some(mds) = xs
+            .iter()
+            .map(|x| ys.iter().map(|y| (*x + y).inverse()).collect())
+            .collect::<Option<Vec<Vec<F>>>>()
+        else {
+            continue;
+        };
+
+        let mut power = mds.clone();
+        let secure = (0..2 * width).all(|_| {
+            let secure = !has_root_in_field(&characteristic_polynomial(&power));
+            power = matrix_mul(&power, &mds);
+            secure
+        });
+        if secure {
+            break mds;
+        }
+    };
+
+    ArithmeticSpongeParams {
+        round_constants,
+        mds,
+    }
+}
+
+fn matrix_mul<F: PrimeField>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = a.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| (0..n).map(|k| a[i][k] * b[k][j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+/// Compute the characteristic polynomial of the square matrix `m` with the
+/// Faddeev-LeVerrier algorithm. The size of the matrix must be smaller than the
+/// characteristic of the field.
+fn characteristic_polynomial<F: PrimeField>(m: &[Vec<F>]) -> DensePolynomial<F> {
+    let n = m.len();
+    // coeffs[k] is the coefficient of X^k
+    let mut coeffs = vec![F::zero(); n + 1];
+    coeffs[n] = F::one();
+    let mut mk = vec![vec![F::zero(); n]; n];
+    for k in 1..=n {
+        for (i, row) in mk.iter_mut().enumerate() {
+            row[i] += coeffs[n - k + 1];
+        }
+        mk = matrix_mul(m, &mk);
+        let trace: F = (0..n).map(|i| mk[i][i]).sum();
+        coeffs[n - k] = -trace / F::from(k as u64);
+    }
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+/// Check if the polynomial `p` has a root in the field, i.e. if `p` and
+/// `X^q - X` have a non-trivial common divisor.
+fn has_root_in_field<F: PrimeField>(p: &DensePolynomial<F>) -> bool {
+    let rem = |a: &DensePolynomial<F>, b: &DensePolynomial<F>| {
+        DenseOrSparsePolynomial::from(a)
+            .divide_with_q_and_r(&b.into())
+            .unwrap()
+            .1
+    };
+    let x = DensePolynomial::from_coefficients_vec(vec![F::zero(), F::one()]);
+
+    // X^q mod p
+    let mut x_q = DensePolynomial::from_coefficients_vec(vec![F::one()]);
+    for bit in F::MODULUS.to_bits_be() {
+        x_q = rem(&x_q.naive_mul(&x_q), p);
+        if bit {
+            x_q = rem(&x_q.naive_mul(&x), p);
+        }
+    }
+
+    let (mut a, mut b) = (p.clone(), &x_q - &x);
+    while !b.is_zero() {
+        let r = rem(&a, &b);
+        a = b;
+        b = r;
+    }
+    a.degree() > 0
+}
@@ -1,4 +1,6 @@
 pub mod fp_kimchi;
 pub mod fp_legacy;
+pub mod fp_poseidon2;
 pub mod fq_kimchi;
 pub mod fq_legacy;
+pub mod fq_poseidon2;
@@ -51,52 +51,60 @@ pub fn full_round<F: Field, SC: SpongeConstants>(
     }
 }
 
+/// Apply a full round of the permutation with the round constants added first,
+/// as in the first and last rounds of [half_rounds].
+fn ark_first_full_round<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut [F],
+    r: usize,
+) {
+    for (i, x) in params.round_constants[r].iter().enumerate() {
+        state[i].add_assign(x);
+    }
+    for state_i in state.iter_mut() {
+        *state_i = sbox::<F, SC>(*state_i);
+    }
+    let res = apply_mds_matrix::<F, SC>(params, state);
+    for (i, state_i) in state.iter_mut().enumerate() {
+        *state_i = res[i]
+    }
+}
+
+/// Apply a partial round of the permutation, where the S-box is only applied to
+/// the first element of the state.
+fn partial_round<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut [F],
+    r: usize,
+) {
+    for (i, x) in params.round_constants[r].iter().enumerate() {
+        state[i].add_assign(x);
+    }
+    state[0] = sbox::<F, SC>(state[0]);
+    let res = apply_mds_matrix::<F, SC>(params, state);
+    res.iter().enumerate().for_each(|(i, x)| {
+        state[i] = *x;
+    });
+}
+
 pub fn half_rounds<F: Field, SC: SpongeConstants>(
     params: &ArithmeticSpongeParams<F>,
     state: &mut [F],
 ) {
     for r in 0..SC::PERM_HALF_ROUNDS_FULL {
-        for (i, x) in params.round_constants[r].iter().enumerate() {
-            state[i].add_assign(x);
-        }
-        for state_i in state.iter_mut() {
-            *state_i = sbox::<F, SC>(*state_i);
-        }
-        let res = apply_mds_matrix::<F, SC>(params, state);
-        for (i, state_i) in state.iter_mut().enumerate() {
-            *state_i = res[i]
-        }
+        ark_first_full_round::<F, SC>(params, state, r);
     }
 
     for r in 0..SC::PERM_ROUNDS_PARTIAL {
-        for (i, x) in params.round_constants[SC::PERM_HALF_ROUNDS_FULL + r]
-            .iter()
-            .enumerate()
-        {
-            state[i].add_assign(x);
-        }
-        state[0] = sbox::<F, SC>(state[0]);
-        let res = apply_mds_matrix::<F, SC>(params, state);
-        res.iter().enumerate().for_each(|(i, x)| {
-            state[i] = *x;
-        });
+        partial_round::<F, SC>(params, state, SC::PERM_HALF_ROUNDS_FULL + r);
     }
 
     for r in 0..SC::PERM_HALF_ROUNDS_FULL {
-        for (i, x) in params.round_constants
-            [SC::PERM_HALF_ROUNDS_FULL + SC::PERM_ROUNDS_PARTIAL + r]
-            .iter()
-            .enumerate()
-        {
-            state[i].add_assign(x);
-        }
-        for state_i in state.iter_mut() {
-            *state_i = sbox::<F, SC>(*state_i);
-        }
-        let res = apply_mds_matrix::<F, SC>(params, state);
-        res.iter().enumerate().for_each(|(i, x)| {
-            state[i] = *x;
-        });
+        ark_first_full_round::<F, SC>(
+            params,
+            state,
+            SC::PERM_HALF_ROUNDS_FULL + SC::PERM_ROUNDS_PARTIAL + r,
+        );
     }
 }
 
@@ -104,7 +112,9 @@ pub fn poseidon_block_cipher<F: Field, SC: SpongeConstants>(
     params: &ArithmeticSpongeParams<F>,
     state: &mut Vec<F>,
 ) {
-    if SC::PERM_HALF_ROUNDS_FULL == 0 {
+    if SC::PERM_POSEIDON2 {
+        poseidon2_block_cipher::<F, SC>(params, state);
+    } else if SC::PERM_HALF_ROUNDS_FULL == 0 {
         if SC::PERM_INITIAL_ARK {
             for (i, x) in params.round_constants[0].iter().enumerate() {
                 state[i].add_assign(x);
@@ -121,3 +131,132 @@ pub fn poseidon_block_cipher<F: Field, SC: SpongeConstants>(
         half_rounds::<F, SC>(params, state);
     }
 }
+
+/// Apply the external linear layer of Poseidon2, i.e. the circulant matrix
+/// `circ(2, 1, ..., 1)`. It is only MDS for a state of width 2 or 3.
+fn apply_poseidon2_external_matrix<F: Field>(state: &mut [F]) {
+    debug_assert!(state.len() <= 3);
+    let sum: F = state.iter().sum();
+    for state_i in state.iter_mut() {
+        *state_i += sum;
+    }
+}
+
+/// Apply the internal linear layer of Poseidon2. The MDS matrix of the
+/// parameters is expected to be of the form `J + D` where `J` is the matrix
+/// filled with ones and `D` is diagonal, so only the diagonal is read.
+fn apply_poseidon2_internal_matrix<F: Field>(params: &ArithmeticSpongeParams<F>, state: &mut [F]) {
+    let sum: F = state.iter().sum();
+    for (i, state_i) in state.iter_mut().enumerate() {
+        *state_i = sum + (params.mds[i][i] - F::one()) * *state_i;
+    }
+}
+
+/// The Poseidon2 permutation, see <https://eprint.iacr.org/2023/323>.
+/// It is composed of the following steps:
+/// - Apply the external linear layer to the state.
+/// - Run `PERM_HALF_ROUNDS_FULL` full rounds: add the round constants, apply
+///   the S-box to each element and apply the external linear layer.
+/// - Run `PERM_ROUNDS_PARTIAL` partial rounds: add a single round constant to
+///   the first element, apply the S-box to it and apply the internal linear
+///   layer.
+/// - Run `PERM_HALF_ROUNDS_FULL` full rounds again.
+///
+/// The round constants of the partial rounds only contain one element.
+/// The function has side-effect and the parameter state is modified.
+pub fn poseidon2_block_cipher<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut [F],
+) {
+    apply_poseidon2_external_matrix(state);
+
+    for r in 0..SC::PERM_HALF_ROUNDS_FULL {
+        poseidon2_external_round::<F, SC>(params, state, r);
+    }
+
+    for r in 0..SC::PERM_ROUNDS_PARTIAL {
+        poseidon2_internal_round::<F, SC>(params, state, SC::PERM_HALF_ROUNDS_FULL + r);
+    }
+
+    for r in 0..SC::PERM_HALF_ROUNDS_FULL {
+        poseidon2_external_round::<F, SC>(
+            params,
+            state,
+            SC::PERM_HALF_ROUNDS_FULL + SC::PERM_ROUNDS_PARTIAL + r,
+        );
+    }
+}
+
+fn poseidon2_external_round<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut [F],
+    r: usize,
+) {
+    for (state_i, x) in state.iter_mut().zip(params.round_constants[r].iter()) {
+        *state_i = sbox::<F, SC>(*state_i + x);
+    }
+    apply_poseidon2_external_matrix(state);
+}
+
+fn poseidon2_internal_round<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut [F],
+    r: usize,
+) {
+    state[0] = sbox::<F, SC>(state[0] + params.round_constants[r][0]);
+    apply_poseidon2_internal_matrix(params, state);
+}
+
+/// The number of rounds of the permutation described by `SC`, as indexed by
+/// [round].
+pub fn nb_rounds<SC: SpongeConstants>() -> usize {
+    if SC::PERM_POSEIDON2 || SC::PERM_HALF_ROUNDS_FULL != 0 {
+        2 * SC::PERM_HALF_ROUNDS_FULL + SC::PERM_ROUNDS_PARTIAL
+    } else {
+        SC::PERM_ROUNDS_FULL
+    }
+}
+
+/// Apply the layer preceding the first round of the permutation, i.e. the
+/// initial round constants if `PERM_INITIAL_ARK` is set, or the external linear
+/// layer of Poseidon2.
+pub fn initial_layer<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut [F],
+) {
+    if SC::PERM_POSEIDON2 {
+        apply_poseidon2_external_matrix(state);
+    } else if SC::PERM_HALF_ROUNDS_FULL == 0 && SC::PERM_INITIAL_ARK {
+        for (i, x) in params.round_constants[0].iter().enumerate() {
+            state[i].add_assign(x);
+        }
+    }
+}
+
+/// Apply the round `r` of the permutation described by `SC`, whatever its
+/// round structure is. Applying [initial_layer] and then the rounds `0` to
+/// [nb_rounds] is equivalent to [poseidon_block_cipher].
+pub fn round<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut Vec<F>,
+    r: usize,
+) {
+    let is_partial = (SC::PERM_HALF_ROUNDS_FULL
+        ..SC::PERM_HALF_ROUNDS_FULL + SC::PERM_ROUNDS_PARTIAL)
+        .contains(&r);
+    if SC::PERM_POSEIDON2 {
+        if is_partial {
+            poseidon2_internal_round::<F, SC>(params, state, r);
+        } else {
+            poseidon2_external_round::<F, SC>(params, state, r);
+        }
+    } else if SC::PERM_HALF_ROUNDS_FULL != 0 {
+        if is_partial {
+            partial_round::<F, SC>(params, state, r);
+        } else {
+            ark_first_full_round::<F, SC>(params, state, r);
+        }
+    } else {
+        full_round::<F, SC>(params, state, r + usize::from(SC::PERM_INITIAL_ARK));
+    }
+}
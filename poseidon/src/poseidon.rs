@@ -1,11 +1,9 @@
 //! This module implements Poseidon Hash Function primitive
 
-use crate::{
-    constants::SpongeConstants,
-    permutation::{full_round, poseidon_block_cipher},
-};
+use crate::{constants::SpongeConstants, permutation::full_round};
 use ark_ff::Field;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -35,6 +33,12 @@ pub fn sbox<F: Field, SC: SpongeConstants>(mut x: F) -> F {
         square.square_in_place();
         x *= square;
         x
+    } else if SC::PERM_SBOX == 5 {
+        let mut square = x;
+        square.square_in_place();
+        square.square_in_place();
+        x *= square;
+        x
     } else {
         x.pow([SC::PERM_SBOX as u64])
     }
@@ -66,12 +70,55 @@ pub struct ArithmeticSponge<F: Field, SC: SpongeConstants> {
 }
 
 impl<F: Field, SC: SpongeConstants> ArithmeticSponge<F, SC> {
+    /// Create a sponge absorbing and squeezing `rate` elements per permutation.
+    /// The width of the state is given by the MDS matrix of `params`, and the
+    /// rate must leave at least one element for the capacity: with the 3-wide
+    /// parameters of [crate::pasta], the rate is at most 2.
+    pub fn with_rate(params: &'static ArithmeticSpongeParams<F>, rate: usize) -> Self {
+        let width = params.mds.len();
+        assert!(
+            rate > 0 && rate < width,
+            "the rate must be between 1 and {} for a state of width {width}",
+            width - 1
+        );
+        ArithmeticSponge {
+            state: vec![F::zero(); width],
+            rate,
+            sponge_state: SpongeState::Absorbed(0),
+            params,
+            constants: std::marker::PhantomData,
+        }
+    }
+
+    /// The number of elements absorbed or squeezed per permutation
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+
     pub fn full_round(&mut self, r: usize) {
         full_round::<F, SC>(self.params, &mut self.state, r);
     }
 
     fn poseidon_block_cipher(&mut self) {
-        poseidon_block_cipher::<F, SC>(self.params, &mut self.state);
+        #[cfg(feature = "constant_time")]
+        crate::constant_time::dispatch::<F, SC>(self.params, &mut self.state);
+        #[cfg(not(feature = "constant_time"))]
+        crate::permutation::poseidon_block_cipher::<F, SC>(self.params, &mut self.state);
+    }
+
+    /// Add the absorbed element `x` to the `i`-th element of the state
+    fn add_to_state(&mut self, i: usize, x: &F) {
+        #[cfg(feature = "constant_time")]
+        crate::constant_time::add_assign(&mut self.state[i], x);
+        #[cfg(not(feature = "constant_time"))]
+        self.state[i].add_assign(x);
+    }
+
+    /// Erase the state of the sponge, e.g. after hashing secret data, and
+    /// reset it as in [Sponge::reset]
+    pub fn zeroize(&mut self) {
+        crate::constant_time::zeroize(&mut self.state);
+        self.sponge_state = SpongeState::Absorbed(0);
     }
 }
 
@@ -102,14 +149,14 @@ impl<F: Field, SC: SpongeConstants> Sponge<F, F> for ArithmeticSponge<F, SC> {
                     if n == self.rate {
                         self.poseidon_block_cipher();
                         self.sponge_state = SpongeState::Absorbed(1);
-                        self.state[0].add_assign(x);
+                        self.add_to_state(0, x);
                     } else {
                         self.sponge_state = SpongeState::Absorbed(n + 1);
-                        self.state[n].add_assign(x);
+                        self.add_to_state(n, x);
                     }
                 }
                 SpongeState::Squeezed(_n) => {
-                    self.state[0].add_assign(x);
+                    self.add_to_state(0, x);
                     self.sponge_state = SpongeState::Absorbed(1);
                 }
             }
@@ -141,3 +188,19 @@ impl<F: Field, SC: SpongeConstants> Sponge<F, F> for ArithmeticSponge<F, SC> {
         self.sponge_state = SpongeState::Absorbed(0);
     }
 }
+
+/// Hash each input of `inputs` with a fresh sponge, in parallel. The sponges
+/// share the parameters `params`.
+pub fn batch_hash<F: Field, SC: SpongeConstants>(
+    params: &'static ArithmeticSpongeParams<F>,
+    inputs: &[Vec<F>],
+) -> Vec<F> {
+    inputs
+        .par_iter()
+        .map(|input| {
+            let mut sponge = ArithmeticSponge::<F, SC>::new(params);
+            sponge.absorb(input);
+            sponge.squeeze()
+        })
+        .collect()
+}
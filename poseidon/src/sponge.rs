@@ -37,6 +37,62 @@ pub trait FqSponge<Fq: Field, G, Fr> {
 
     /// Returns a scalar field digest using the binary representation technique.
     fn digest(self) -> Fr;
+
+    /// Absorbs a domain separation tag, encoded with [domain_tag_to_fields].
+    /// Protocols sharing the same sponge (signatures, Fiat-Shamir transcripts,
+    /// commitments, ...) should start with different tags so that their
+    /// challenges can not collide.
+    fn absorb_domain_tag(&mut self, tag: &str)
+    where
+        Fq: PrimeField,
+    {
+        self.absorb_fq(&domain_tag_to_fields(tag));
+    }
+
+    /// Creates a new sponge and absorbs the domain separation tag `tag`, see
+    /// [FqSponge::absorb_domain_tag].
+    fn new_with_domain_tag(p: &'static ArithmeticSpongeParams<Fq>, tag: &str) -> Self
+    where
+        Self: Sized,
+        Fq: PrimeField,
+    {
+        let mut sponge = Self::new(p);
+        sponge.absorb_domain_tag(tag);
+        sponge
+    }
+
+    /// Creates a new sponge and absorbs the domain separation tag of the
+    /// protocol `D`.
+    fn new_with_domain<D: DomainSeparator>(p: &'static ArithmeticSpongeParams<Fq>) -> Self
+    where
+        Self: Sized,
+        Fq: PrimeField,
+    {
+        Self::new_with_domain_tag(p, D::DOMAIN_TAG)
+    }
+}
+
+/// A protocol label, to be used as a domain separator for sponges, see
+/// [FqSponge::new_with_domain].
+pub trait DomainSeparator {
+    /// The tag absorbed at the beginning of the protocol. It must be unique
+    /// among the protocols using the same sponge.
+    const DOMAIN_TAG: &'static str;
+}
+
+/// Encodes a domain separation tag into field elements. The first element is
+/// the length in bytes of the tag, followed by the bytes of the tag packed in
+/// little-endian order into elements of `(MODULUS_BIT_SIZE - 1) / 8` bytes,
+/// making the encoding injective.
+pub fn domain_tag_to_fields<F: PrimeField>(tag: &str) -> Vec<F> {
+    let bytes_per_field = (F::MODULUS_BIT_SIZE as usize - 1) / 8;
+    std::iter::once(F::from(tag.len() as u64))
+        .chain(
+            tag.as_bytes()
+                .chunks(bytes_per_field)
+                .map(F::from_le_bytes_mod_order),
+        )
+        .collect()
 }
 
 pub const CHALLENGE_LENGTH_IN_LIMBS: usize = 2;
@@ -0,0 +1,130 @@
+//! This module implements a sponge absorbing streams of bytes, so that large
+//! byte strings can be hashed in several pieces without packing them into
+//! field elements by hand.
+//!
+//! A byte string is packed into chunks of `(MODULUS_BIT_SIZE - 1) / 8` bytes
+//! (31 bytes for the fields of [crate::pasta] and [crate::bn254]), each chunk
+//! being read as a little-endian field element. It is padded with the
+//! `10*` scheme: the byte `0x01` is appended, followed by as many zeros as
+//! needed to fill the last chunk. As the padding is always present, two
+//! different byte strings are never absorbed as the same field elements.
+//!
+//! The byte string ends when field elements are absorbed, or when the sponge
+//! is squeezed. Absorbing `a` then `b` is the same as absorbing the
+//! concatenation of `a` and `b`, as long as nothing happens in between.
+
+use crate::{
+    constants::SpongeConstants,
+    poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge},
+};
+use ark_ff::PrimeField;
+
+/// The byte appended to a byte string before the zeros of the padding
+pub const PADDING_BYTE: u8 = 0x01;
+
+/// The number of bytes packed into a field element
+pub fn bytes_per_element<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize - 1) / 8
+}
+
+/// Pack `bytes` into field elements, padding included
+pub fn bytes_to_fields<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+    let mut padded = bytes.to_vec();
+    padded.push(PADDING_BYTE);
+    padded
+        .chunks(bytes_per_element::<F>())
+        .map(F::from_le_bytes_mod_order)
+        .collect()
+}
+
+/// A sponge absorbing both field elements and streams of bytes, see the
+/// documentation of the module
+#[derive(Clone)]
+pub struct StreamingSponge<F: PrimeField, SC: SpongeConstants> {
+    sponge: ArithmeticSponge<F, SC>,
+    /// The bytes of the current string that do not fill a chunk yet
+    pending: Vec<u8>,
+    /// Whether a byte string has been started and not padded yet
+    in_bytes: bool,
+}
+
+impl<F: PrimeField, SC: SpongeConstants> StreamingSponge<F, SC> {
+    /// Create a sponge with the rate given by `SC`
+    pub fn new(params: &'static ArithmeticSpongeParams<F>) -> Self {
+        Self::from_sponge(ArithmeticSponge::new(params))
+    }
+
+    /// Create a sponge with the given rate, see [ArithmeticSponge::with_rate]
+    pub fn with_rate(params: &'static ArithmeticSpongeParams<F>, rate: usize) -> Self {
+        Self::from_sponge(ArithmeticSponge::with_rate(params, rate))
+    }
+
+    fn from_sponge(sponge: ArithmeticSponge<F, SC>) -> Self {
+        StreamingSponge {
+            sponge,
+            pending: Vec::with_capacity(bytes_per_element::<F>()),
+            in_bytes: false,
+        }
+    }
+
+    /// Absorb the next bytes of the current byte string, or start a new one.
+    /// Only full chunks are absorbed, the remaining bytes are kept until more
+    /// bytes arrive or the string ends.
+    pub fn absorb_bytes(&mut self, bytes: &[u8]) {
+        let chunk_size = bytes_per_element::<F>();
+        self.in_bytes = true;
+
+        let mut bytes = bytes;
+        if !self.pending.is_empty() {
+            let missing = (chunk_size - self.pending.len()).min(bytes.len());
+            self.pending.extend_from_slice(&bytes[..missing]);
+            bytes = &bytes[missing..];
+            if self.pending.len() < chunk_size {
+                return;
+            }
+            let x = F::from_le_bytes_mod_order(&self.pending);
+            self.sponge.absorb(&[x]);
+            self.pending.clear();
+        }
+
+        let mut chunks = bytes.chunks_exact(chunk_size);
+        for chunk in chunks.by_ref() {
+            self.sponge.absorb(&[F::from_le_bytes_mod_order(chunk)]);
+        }
+        self.pending.extend_from_slice(chunks.remainder());
+    }
+
+    /// Pad and absorb the end of the current byte string, if any
+    fn finish_bytes(&mut self) {
+        if !self.in_bytes {
+            return;
+        }
+        self.pending.push(PADDING_BYTE);
+        let x = F::from_le_bytes_mod_order(&self.pending);
+        self.sponge.absorb(&[x]);
+        self.pending.clear();
+        self.in_bytes = false;
+    }
+}
+
+impl<F: PrimeField, SC: SpongeConstants> Sponge<F, F> for StreamingSponge<F, SC> {
+    fn new(params: &'static ArithmeticSpongeParams<F>) -> Self {
+        Self::new(params)
+    }
+
+    fn absorb(&mut self, x: &[F]) {
+        self.finish_bytes();
+        self.sponge.absorb(x);
+    }
+
+    fn squeeze(&mut self) -> F {
+        self.finish_bytes();
+        self.sponge.squeeze()
+    }
+
+    fn reset(&mut self) {
+        self.sponge.reset();
+        self.pending.clear();
+        self.in_bytes = false;
+    }
+}
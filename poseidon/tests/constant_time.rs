@@ -0,0 +1,110 @@
+use ark_ff::{Field, Zero};
+use mina_curves::pasta::{Fp, Fq};
+use mina_poseidon::{
+    bn254,
+    constant_time::{self, add, mul},
+    constants::{
+        PlonkSpongeConstantsBn254, PlonkSpongeConstantsKimchi, PlonkSpongeConstantsLegacy,
+        PlonkSpongeConstantsPoseidon2, SpongeConstants,
+    },
+    pasta::{fp_kimchi, fp_legacy, fp_poseidon2, fq_kimchi},
+    permutation,
+    poseidon::{ArithmeticSponge, Sponge as _},
+};
+
+/// Random elements, and elements close to zero and to the modulus
+fn test_elements<F: Field>() -> Vec<F> {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let mut elements = vec![
+        F::zero(),
+        F::one(),
+        -F::one(),
+        F::from(2u64),
+        -F::from(2u64),
+    ];
+    elements.extend((0..20).map(|_| F::rand(rng)));
+    elements
+}
+
+#[test]
+fn test_constant_time_arithmetic() {
+    let elements = test_elements::<Fp>();
+    for a in elements.iter() {
+        for b in elements.iter() {
+            assert_eq!(add(*a, *b), *a + b);
+            assert_eq!(mul(*a, *b), *a * b);
+        }
+    }
+
+    let elements = test_elements::<ark_bn254::Fq>();
+    for a in elements.iter() {
+        for b in elements.iter() {
+            assert_eq!(add(*a, *b), *a + b);
+            assert_eq!(mul(*a, *b), *a * b);
+        }
+    }
+}
+
+macro_rules! check_permutation {
+    ($field:ty, $sc:ty, $params:expr) => {
+        let elements = test_elements::<$field>();
+        for state in elements.chunks_exact(3) {
+            let mut expected = state.to_vec();
+            permutation::poseidon_block_cipher::<$field, $sc>($params, &mut expected);
+            let mut state = state.to_vec();
+            constant_time::poseidon_block_cipher::<_, 4, $sc>($params, &mut state);
+            assert_eq!(state, expected);
+        }
+    };
+}
+
+/// Full and partial rounds, without the Poseidon2 linear layers
+struct PlonkSpongeConstantsPartial {}
+
+impl SpongeConstants for PlonkSpongeConstantsPartial {
+    const PERM_ROUNDS_FULL: usize = 8;
+    const PERM_ROUNDS_PARTIAL: usize = 56;
+    const PERM_HALF_ROUNDS_FULL: usize = 4;
+    const PERM_SBOX: u32 = 5;
+    const PERM_FULL_MDS: bool = true;
+    const PERM_INITIAL_ARK: bool = false;
+}
+
+#[test]
+fn test_constant_time_permutation() {
+    check_permutation!(Fp, PlonkSpongeConstantsLegacy, fp_legacy::static_params());
+    check_permutation!(Fp, PlonkSpongeConstantsKimchi, fp_kimchi::static_params());
+    check_permutation!(Fq, PlonkSpongeConstantsKimchi, fq_kimchi::static_params());
+    check_permutation!(Fp, PlonkSpongeConstantsPartial, fp_legacy::static_params());
+    check_permutation!(
+        Fp,
+        PlonkSpongeConstantsPoseidon2,
+        fp_poseidon2::static_params()
+    );
+    check_permutation!(
+        ark_bn254::Fr,
+        PlonkSpongeConstantsBn254,
+        bn254::fr::static_params()
+    );
+    check_permutation!(
+        ark_bn254::Fq,
+        PlonkSpongeConstantsBn254,
+        bn254::fq::static_params()
+    );
+}
+
+#[test]
+fn test_zeroize() {
+    let mut sponge =
+        ArithmeticSponge::<Fp, PlonkSpongeConstantsKimchi>::new(fp_kimchi::static_params());
+    sponge.absorb(&[Fp::from(42u64)]);
+    let digest = sponge.squeeze();
+    assert!(!sponge.state.iter().all(|x| x.is_zero()));
+
+    sponge.zeroize();
+    assert!(sponge.state.iter().all(|x| x.is_zero()));
+
+    // The sponge can be reused
+    sponge.absorb(&[Fp::from(42u64)]);
+    assert_eq!(sponge.squeeze(), digest);
+}
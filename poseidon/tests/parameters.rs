@@ -0,0 +1,147 @@
+use ark_ff::{
+    fields::{Fp64, MontBackend, MontConfig},
+    Field, One, Zero,
+};
+use mina_curves::pasta::{Fp, Fq};
+use mina_poseidon::{
+    bn254,
+    constants::PlonkSpongeConstantsKimchi,
+    parameters::{grain_params, mds, params, poseidon2_params, round_constants},
+    pasta::{fp_kimchi, fp_legacy, fp_poseidon2, fq_kimchi, fq_legacy, fq_poseidon2},
+    permutation::poseidon_block_cipher,
+    poseidon::ArithmeticSpongeParams,
+};
+
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct GoldilocksConfig;
+type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+fn determinant<F: Field>(m: &[Vec<F>]) -> F {
+    // Gaussian elimination
+    let mut m = m.to_vec();
+    let n = m.len();
+    let mut det = F::one();
+    for i in 0..n {
+        let Some(pivot) = (i..n).find(|j| !m[*j][i].is_zero()) else {
+            return F::zero();
+        };
+        if pivot != i {
+            m.swap(i, pivot);
+            det = -det;
+        }
+        det *= m[i][i];
+        let inv = m[i][i].inverse().unwrap();
+        for j in i + 1..n {
+            let factor = m[j][i] * inv;
+            for k in i..n {
+                let x = m[i][k];
+                m[j][k] -= factor * x;
+            }
+        }
+    }
+    det
+}
+
+#[test]
+fn test_generate_pasta_kimchi_params() {
+    let fp_params = params::<Fp>("Pasta_p_kimchi", 3, 55).unwrap();
+    assert_eq!(fp_params.mds, fp_kimchi::params().mds);
+    assert_eq!(
+        fp_params.round_constants,
+        fp_kimchi::params().round_constants
+    );
+
+    let fq_params = params::<Fq>("Pasta_q_kimchi", 3, 55).unwrap();
+    assert_eq!(fq_params.mds, fq_kimchi::params().mds);
+    assert_eq!(
+        fq_params.round_constants,
+        fq_kimchi::params().round_constants
+    );
+}
+
+#[test]
+fn test_generate_pasta_legacy_params() {
+    // The legacy parameters are not named: the round constants are not
+    // prefixed and the MDS matrix is shared by the two fields.
+    assert_eq!(
+        round_constants::<Fp>("Pasta_pRoundConstants", 3, 100),
+        fp_legacy::static_params().round_constants
+    );
+    assert_eq!(
+        mds::<Fp>("CodaRescueMDS", 3).unwrap(),
+        fp_legacy::static_params().mds
+    );
+
+    assert_eq!(
+        round_constants::<Fq>("Pasta_qRoundConstants", 3, 100),
+        fq_legacy::static_params().round_constants
+    );
+    assert_eq!(
+        mds::<Fq>("CodaRescueMDS", 3).unwrap(),
+        fq_legacy::static_params().mds
+    );
+}
+
+#[test]
+fn test_generate_pasta_poseidon2_params() {
+    let fp_params = poseidon2_params::<Fp>(8, 56);
+    assert_eq!(fp_params.mds, fp_poseidon2::params().mds);
+    assert_eq!(
+        fp_params.round_constants,
+        fp_poseidon2::params().round_constants
+    );
+
+    let fq_params = poseidon2_params::<Fq>(8, 56);
+    assert_eq!(fq_params.mds, fq_poseidon2::params().mds);
+    assert_eq!(
+        fq_params.round_constants,
+        fq_poseidon2::params().round_constants
+    );
+}
+
+#[test]
+fn test_generate_bn254_grain_params() {
+    let fr_params = grain_params::<ark_bn254::Fr>(3, 8, 57);
+    assert_eq!(fr_params.mds, bn254::fr::params().mds);
+    assert_eq!(
+        fr_params.round_constants,
+        bn254::fr::params().round_constants
+    );
+
+    let fq_params = grain_params::<ark_bn254::Fq>(3, 8, 57);
+    assert_eq!(fq_params.mds, bn254::fq::params().mds);
+    assert_eq!(
+        fq_params.round_constants,
+        bn254::fq::params().round_constants
+    );
+}
+
+#[test]
+fn test_generate_bn254_params() {
+    let params = params::<ark_bn254::Fr>("BN254_r_kimchi", 3, 55).unwrap();
+    assert_eq!(params.round_constants.len(), 55);
+    assert!(params.round_constants.iter().all(|rc| rc.len() == 3));
+    assert!(!determinant(&params.mds).is_zero());
+}
+
+#[test]
+fn test_generate_goldilocks_params() {
+    let params: ArithmeticSpongeParams<Goldilocks> = params("Goldilocks_kimchi", 3, 55).unwrap();
+    assert!(!determinant(&params.mds).is_zero());
+    // The constants are not all drawn from a small subset of the field
+    assert!(params
+        .round_constants
+        .iter()
+        .flatten()
+        .any(|x| *x > Goldilocks::from(u32::MAX)));
+
+    // The permutation is used directly, as the sponges only support the
+    // fields of the crate with the feature `constant_time`
+    let mut state = vec![Goldilocks::one(), Goldilocks::zero(), Goldilocks::zero()];
+    poseidon_block_cipher::<Goldilocks, PlonkSpongeConstantsKimchi>(&params, &mut state);
+    let mut other = vec![Goldilocks::zero(), Goldilocks::one(), Goldilocks::zero()];
+    poseidon_block_cipher::<Goldilocks, PlonkSpongeConstantsKimchi>(&params, &mut other);
+    assert_ne!(state, other);
+}
@@ -1,11 +1,26 @@
-use mina_curves::pasta::Fp;
+use ark_ec::AffineRepr;
+use ark_ff::UniformRand;
+use mina_curves::pasta::{Fp, Fq, VestaParameters};
 use mina_poseidon::{
-    constants::{PlonkSpongeConstantsKimchi, PlonkSpongeConstantsLegacy},
-    pasta::{fp_kimchi as SpongeParametersKimchi, fp_legacy as SpongeParametersLegacy},
-    poseidon::{ArithmeticSponge as Poseidon, Sponge as _},
+    bn254::{circom_hash, Bn254FqSponge},
+    constants::{
+        PlonkSpongeConstantsBn254, PlonkSpongeConstantsKimchi, PlonkSpongeConstantsLegacy,
+        PlonkSpongeConstantsPoseidon2, SpongeConstants,
+    },
+    merkle::MerkleTree,
+    pasta::{
+        fp_kimchi as SpongeParametersKimchi, fp_legacy as SpongeParametersLegacy,
+        fp_poseidon2 as SpongeParametersPoseidon2, fq_kimchi, fq_poseidon2,
+    },
+    permutation::{initial_layer, nb_rounds, poseidon_block_cipher, round},
+    poseidon::{batch_hash, ArithmeticSponge as Poseidon, ArithmeticSpongeParams, Sponge as _},
+    sponge::{domain_tag_to_fields, DefaultFqSponge, DomainSeparator},
+    streaming::{bytes_per_element, bytes_to_fields, StreamingSponge},
+    FqSponge,
 };
 use o1_utils::FieldHelpers;
 use serde::Deserialize;
+use std::str::FromStr;
 use std::{fs::File, path::PathBuf}; // needed for ::new() sponge
 
 //
@@ -78,3 +93,306 @@ fn poseidon_test_vectors_kimchi() {
     }
     test_vectors("kimchi.json", hash);
 }
+
+#[test]
+fn poseidon_test_vectors_poseidon2() {
+    fn hash(input: &[Fp]) -> Fp {
+        let mut hash = Poseidon::<Fp, PlonkSpongeConstantsPoseidon2>::new(
+            SpongeParametersPoseidon2::static_params(),
+        );
+        hash.absorb(input);
+        hash.squeeze()
+    }
+    test_vectors("poseidon2.json", hash);
+}
+
+/// Known-answer tests of the permutation, from the reference implementation of
+/// Poseidon2, https://github.com/HorizenLabs/poseidon2
+#[test]
+fn poseidon2_reference_permutation() {
+    let mut state = vec![Fp::from(0u64), Fp::from(1u64), Fp::from(2u64)];
+    poseidon_block_cipher::<Fp, PlonkSpongeConstantsPoseidon2>(
+        SpongeParametersPoseidon2::static_params(),
+        &mut state,
+    );
+    let expected = [
+        "12034580478475899756768852307737011850845987783813919900518943507286591966586",
+        "12793588015935436972406883162492490643371701127091833715009315828048670032380",
+        "3445110498342580915003896963218627245598739827334005084754590007820223034369",
+    ];
+    assert_eq!(state, expected.map(|x| Fp::from_str(x).unwrap()));
+
+    let mut state = vec![Fq::from(0u64), Fq::from(1u64), Fq::from(2u64)];
+    poseidon_block_cipher::<Fq, PlonkSpongeConstantsPoseidon2>(
+        fq_poseidon2::static_params(),
+        &mut state,
+    );
+    let expected = [
+        "17242300747239067162598679661883726010595470725927247522813204773213848872850",
+        "20110601776785301845784077236520917651993463881458952614501114449858670413423",
+        "17249884929888930719727146739709554376568891774225041385253561222176908575167",
+    ];
+    assert_eq!(state, expected.map(|x| Fq::from_str(x).unwrap()));
+}
+
+#[test]
+fn poseidon2_fq_sponge() {
+    let input = [Fq::from(1u64), Fq::from(2u64), Fq::from(3u64)];
+
+    let mut hash =
+        Poseidon::<Fq, PlonkSpongeConstantsPoseidon2>::new(fq_poseidon2::static_params());
+    hash.absorb(&input);
+
+    let mut sponge = DefaultFqSponge::<VestaParameters, PlonkSpongeConstantsPoseidon2>::new(
+        fq_poseidon2::static_params(),
+    );
+    sponge.absorb_fq(&input);
+    assert_eq!(sponge.challenge_fq(), hash.squeeze());
+}
+
+/// Full and partial rounds, without the Poseidon2 linear layers
+#[derive(Clone)]
+struct PlonkSpongeConstantsPartial {}
+
+impl SpongeConstants for PlonkSpongeConstantsPartial {
+    const PERM_ROUNDS_FULL: usize = 8;
+    const PERM_ROUNDS_PARTIAL: usize = 56;
+    const PERM_HALF_ROUNDS_FULL: usize = 4;
+    const PERM_SBOX: u32 = 5;
+    const PERM_FULL_MDS: bool = true;
+    const PERM_INITIAL_ARK: bool = false;
+}
+
+fn check_rounds<SC: SpongeConstants>(params: &ArithmeticSpongeParams<Fp>) {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let mut state: Vec<Fp> = (0..3).map(|_| Fp::rand(rng)).collect();
+
+    let mut by_rounds = state.clone();
+    initial_layer::<Fp, SC>(params, &mut by_rounds);
+    for r in 0..nb_rounds::<SC>() {
+        round::<Fp, SC>(params, &mut by_rounds, r);
+    }
+
+    poseidon_block_cipher::<Fp, SC>(params, &mut state);
+    assert_eq!(state, by_rounds);
+}
+
+#[test]
+fn poseidon_rounds_are_block_cipher() {
+    check_rounds::<PlonkSpongeConstantsLegacy>(SpongeParametersLegacy::static_params());
+    check_rounds::<PlonkSpongeConstantsKimchi>(SpongeParametersKimchi::static_params());
+    check_rounds::<PlonkSpongeConstantsPartial>(SpongeParametersLegacy::static_params());
+    check_rounds::<PlonkSpongeConstantsPoseidon2>(SpongeParametersPoseidon2::static_params());
+}
+
+struct SignatureDomain;
+
+impl DomainSeparator for SignatureDomain {
+    const DOMAIN_TAG: &'static str = "mina-poseidon/signature";
+}
+
+#[test]
+fn poseidon_domain_separation() {
+    type Sponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    let params = fq_kimchi::static_params();
+    let input = [Fq::from(42u64)];
+    let challenge = |mut sponge: Sponge| {
+        sponge.absorb_fq(&input);
+        sponge.challenge()
+    };
+
+    let tagged = challenge(Sponge::new_with_domain::<SignatureDomain>(params));
+    let mut sponge = Sponge::new(params);
+    sponge.absorb_domain_tag(SignatureDomain::DOMAIN_TAG);
+    assert_eq!(tagged, challenge(sponge));
+
+    // Different protocols get different challenges on the same input
+    assert_ne!(tagged, challenge(Sponge::new(params)));
+    assert_ne!(
+        tagged,
+        challenge(Sponge::new_with_domain_tag(
+            params,
+            "mina-poseidon/commitment"
+        ))
+    );
+
+    // The tag is not confused with a shorter tag followed by some input
+    assert_ne!(
+        domain_tag_to_fields::<Fq>(""),
+        domain_tag_to_fields::<Fq>("\0")
+    );
+    let long_tag = "a".repeat(40);
+    let fields = domain_tag_to_fields::<Fq>(&long_tag);
+    assert_eq!(fields.len(), 3);
+    assert_eq!(fields[0], Fq::from(40u64));
+    assert_ne!(fields, domain_tag_to_fields::<Fq>(&long_tag[..31]));
+}
+
+fn kimchi_hash(input: &[Fp]) -> Fp {
+    let mut hash =
+        Poseidon::<Fp, PlonkSpongeConstantsKimchi>::new(SpongeParametersKimchi::static_params());
+    hash.absorb(input);
+    hash.squeeze()
+}
+
+#[test]
+fn poseidon_batch_hash() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let inputs: Vec<Vec<Fp>> = (0..20)
+        .map(|i| (0..i).map(|_| Fp::rand(rng)).collect())
+        .collect();
+    let hashes = batch_hash::<Fp, PlonkSpongeConstantsKimchi>(
+        SpongeParametersKimchi::static_params(),
+        &inputs,
+    );
+    assert_eq!(hashes.len(), inputs.len());
+    for (input, hash) in inputs.iter().zip(hashes) {
+        assert_eq!(kimchi_hash(input), hash);
+    }
+}
+
+#[test]
+fn poseidon_merkle_tree() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let leaves: Vec<Fp> = (0..5).map(|_| Fp::rand(rng)).collect();
+    let params = SpongeParametersKimchi::static_params();
+
+    let mut tree = MerkleTree::<Fp, PlonkSpongeConstantsKimchi>::new(params);
+    assert_eq!(tree.root(), None);
+    tree.extend(&leaves[..1]);
+    assert_eq!(tree.root(), Some(leaves[0]));
+
+    // Appending the leaves one by one or all at once gives the same tree
+    for leaf in &leaves[1..] {
+        tree.extend(&[*leaf]);
+    }
+    let mut full_tree = MerkleTree::<Fp, PlonkSpongeConstantsKimchi>::new(params);
+    full_tree.extend(&leaves);
+    assert_eq!(tree.layers(), full_tree.layers());
+
+    let zero = Fp::from(0u64);
+    let h01 = kimchi_hash(&[leaves[0], leaves[1]]);
+    let h23 = kimchi_hash(&[leaves[2], leaves[3]]);
+    let h4 = kimchi_hash(&[leaves[4], zero]);
+    let root = kimchi_hash(&[kimchi_hash(&[h01, h23]), kimchi_hash(&[h4, zero])]);
+    assert_eq!(tree.root(), Some(root));
+    assert_eq!(tree.layers().len(), 4);
+}
+
+#[test]
+fn poseidon_bn254_circom() {
+    // Poseidon([1, 2]) in circomlib
+    let expected = ark_bn254::Fr::from_str(
+        "7853200120776062878684798364095072458815029376092732009249414926327459813530",
+    )
+    .unwrap();
+    assert_eq!(
+        circom_hash([ark_bn254::Fr::from(1u64), ark_bn254::Fr::from(2u64)]),
+        expected
+    );
+}
+
+#[test]
+fn poseidon_bn254_fq_sponge() {
+    let input = [ark_bn254::Fq::from(1u64), ark_bn254::Fq::from(2u64)];
+
+    let mut hash = Poseidon::<ark_bn254::Fq, PlonkSpongeConstantsBn254>::new(
+        mina_poseidon::bn254::fq::static_params(),
+    );
+    hash.absorb(&input);
+
+    let mut sponge = Bn254FqSponge::new(mina_poseidon::bn254::fq::static_params());
+    sponge.absorb_fq(&input);
+    assert_eq!(sponge.challenge_fq(), hash.squeeze());
+
+    // The coordinates of the points are absorbed
+    let g = ark_bn254::G1Affine::generator();
+    let mut sponge = Bn254FqSponge::new(mina_poseidon::bn254::fq::static_params());
+    sponge.absorb_g(&[g]);
+    hash.reset();
+    hash.absorb(&[g.x, g.y]);
+    assert_eq!(sponge.challenge_fq(), hash.squeeze());
+}
+
+#[test]
+fn poseidon_configurable_rate() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let input: Vec<Fp> = (0..7).map(|_| Fp::rand(rng)).collect();
+    let params = SpongeParametersKimchi::static_params();
+
+    // The default rate of the 3-wide state is 2
+    let mut hash = Poseidon::<Fp, PlonkSpongeConstantsKimchi>::with_rate(params, 2);
+    hash.absorb(&input);
+    assert_eq!(hash.squeeze(), kimchi_hash(&input));
+
+    let mut hash = Poseidon::<Fp, PlonkSpongeConstantsKimchi>::with_rate(params, 1);
+    hash.absorb(&input);
+    assert_ne!(hash.squeeze(), kimchi_hash(&input));
+
+    // A rate of 3 needs a 4-wide state, to keep one element of capacity
+    let params4: &'static ArithmeticSpongeParams<Fp> = Box::leak(Box::new(
+        mina_poseidon::parameters::params("rate3", 4, PlonkSpongeConstantsKimchi::PERM_ROUNDS_FULL)
+            .unwrap(),
+    ));
+    let mut hash = Poseidon::<Fp, PlonkSpongeConstantsKimchi>::with_rate(params4, 3);
+    assert_eq!(hash.rate(), 3);
+    hash.absorb(&input[..3]);
+    let mut state = vec![input[0], input[1], input[2], Fp::from(0u64)];
+    poseidon_block_cipher::<Fp, PlonkSpongeConstantsKimchi>(params4, &mut state);
+    assert_eq!(hash.squeeze(), state[0]);
+}
+
+#[test]
+#[should_panic]
+fn poseidon_rate_without_capacity() {
+    Poseidon::<Fp, PlonkSpongeConstantsKimchi>::with_rate(
+        SpongeParametersKimchi::static_params(),
+        3,
+    );
+}
+
+#[test]
+fn poseidon_absorb_bytes() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let bytes: Vec<u8> = (0..100).map(|_| rand::Rng::gen(rng)).collect();
+    let params = SpongeParametersKimchi::static_params();
+    let hash_pieces = |pieces: &[&[u8]]| {
+        let mut sponge = StreamingSponge::<Fp, PlonkSpongeConstantsKimchi>::new(params);
+        for piece in pieces {
+            sponge.absorb_bytes(piece);
+        }
+        sponge.squeeze()
+    };
+
+    // The bytes are packed in chunks of 31 bytes, and padded
+    assert_eq!(bytes_per_element::<Fp>(), 31);
+    let fields = bytes_to_fields::<Fp>(&bytes);
+    assert_eq!(fields.len(), 4);
+    assert_eq!(hash_pieces(&[&bytes]), kimchi_hash(&fields));
+    assert_eq!(bytes_to_fields::<Fp>(&bytes[..62]).len(), 3);
+    assert_eq!(bytes_to_fields::<Fp>(&[]), vec![Fp::from(1u64)]);
+
+    // Streaming does not depend on how the bytes are split
+    let expected = hash_pieces(&[&bytes]);
+    for split in [0, 1, 30, 31, 32, 62, 99, 100] {
+        let (a, b) = bytes.split_at(split);
+        assert_eq!(hash_pieces(&[a, b]), expected);
+    }
+    assert_eq!(hash_pieces(&bytes.chunks(7).collect::<Vec<_>>()), expected);
+
+    // The padding distinguishes trailing zeros
+    assert_ne!(hash_pieces(&[&[]]), hash_pieces(&[&[0]]));
+    assert_ne!(hash_pieces(&[&[0]]), hash_pieces(&[&[0, 0]]));
+    assert_ne!(hash_pieces(&[&[0; 31]]), hash_pieces(&[&[0; 30]]));
+
+    // Absorbing field elements ends the byte string
+    let x = Fp::rand(rng);
+    let mut sponge = StreamingSponge::<Fp, PlonkSpongeConstantsKimchi>::new(params);
+    sponge.absorb_bytes(&bytes[..40]);
+    sponge.absorb(&[x]);
+    sponge.absorb_bytes(&bytes[40..]);
+    let mut fields = bytes_to_fields::<Fp>(&bytes[..40]);
+    fields.push(x);
+    fields.extend(bytes_to_fields::<Fp>(&bytes[40..]));
+    assert_eq!(sponge.squeeze(), kimchi_hash(&fields));
+}
@@ -82,9 +82,9 @@ impl PubKey {
         if bytes.len() != BaseField::size_in_bytes() * 2 {
             return Err(PubKeyError::YCoordinateBytes);
         }
-        let x = BaseField::from_bytes(&bytes[0..BaseField::size_in_bytes()])
+        let x = BaseField::from_bytes_checked(&bytes[0..BaseField::size_in_bytes()])
             .map_err(|_| PubKeyError::XCoordinateBytes)?;
-        let y = BaseField::from_bytes(&bytes[BaseField::size_in_bytes()..])
+        let y = BaseField::from_bytes_checked(&bytes[BaseField::size_in_bytes()..])
             .map_err(|_| PubKeyError::YCoordinateBytes)?;
         let pt = CurvePoint::get_point_from_x_unchecked(x, y.0.is_odd())
             .ok_or(PubKeyError::XCoordinate)?;
@@ -162,7 +162,8 @@ impl PubKey {
             return Err(PubKeyError::AddressVersion);
         }
 
-        let x = BaseField::from_bytes(x_bytes).map_err(|_| PubKeyError::XCoordinateBytes)?;
+        let x =
+            BaseField::from_bytes_checked(x_bytes).map_err(|_| PubKeyError::XCoordinateBytes)?;
         let mut pt =
             CurvePoint::get_point_from_x_unchecked(x, y_parity).ok_or(PubKeyError::XCoordinate)?;
 
@@ -264,7 +265,7 @@ impl CompressedPubKey {
     ///
     /// Will give error if `bytes` do not match certain requirements.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        let x = BaseField::from_bytes(&bytes[0..BaseField::size_in_bytes()])
+        let x = BaseField::from_bytes_checked(&bytes[0..BaseField::size_in_bytes()])
             .map_err(|_| PubKeyError::XCoordinateBytes)?;
         let parity_bytes = &bytes[BaseField::size_in_bytes()..];
         if parity_bytes.len() != 1 {
@@ -65,7 +65,7 @@ impl SecKey {
         sec_bytes.clone_from_slice(bytes);
         sec_bytes.reverse(); // mina scalars hex format is in big-endian order
         let secret =
-            ScalarField::from_bytes(&sec_bytes).map_err(|_| SecKeyError::SecretKeyBytes)?;
+            ScalarField::from_bytes_checked(&sec_bytes).map_err(|_| SecKeyError::SecretKeyBytes)?;
         Ok(SecKey(secret))
     }
 
@@ -0,0 +1,359 @@
+//! This module contains the builtins of Cairo, which the programs use to
+//! perform operations that would be expensive with instructions only.
+//!
+//! A builtin owns a dedicated memory segment, split into instances of a fixed
+//! number of cells. The program writes the input cells of an instance, and the
+//! runner deduces the output cells from them, so that the program can read
+//! them. The builtin also validates its instances, e.g. the range-check
+//! builtin rejects the values larger than `2^128`.
+//!
+//! The [crate::vm::CairoRunner] gives each builtin its own segment of the
+//! segmented memory. With the flat memory of [CairoMemory], a [BuiltinSegment]
+//! is a contiguous region of the memory reserved before the execution. In both
+//! cases, the program receives the base address of the segment as an
+//! argument, as with the Cairo runner, and the segment is checked at the end
+//! of the execution.
+
+use crate::{helper::CairoFieldHelpers, memory::CairoMemory};
+use ark_ff::Field;
+use o1_utils::FieldHelpers;
+use thiserror::Error;
+
+/// Number of bits of the values accepted by the range-check builtin
+pub const RANGE_CHECK_BITS: usize = 128;
+
+/// Number of bits of the inputs of the bitwise builtin
+pub const BITWISE_BITS: usize = 251;
+
+/// The errors raised by the builtins
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuiltinError {
+    /// An instance has an invalid input
+    #[error("invalid input of the {builtin} builtin at address {addr}: {reason}")]
+    InvalidInput {
+        builtin: &'static str,
+        addr: u64,
+        reason: String,
+    },
+    /// An output cell has been written with a value different from the
+    /// deduced one
+    #[error("inconsistent output of the {builtin} builtin at address {addr}")]
+    InconsistentOutput { builtin: &'static str, addr: u64 },
+    /// Some input cells of an instance have been written, but not all
+    #[error("incomplete instance of the {builtin} builtin at address {addr}")]
+    IncompleteInstance { builtin: &'static str, addr: u64 },
+}
+
+/// This trait describes the instances of a builtin
+pub trait Builtin<F> {
+    /// Returns the name of the builtin, as in the `%builtins` directive
+    fn name(&self) -> &'static str;
+
+    /// Returns the number of cells of an instance
+    fn cells_per_instance(&self) -> u64;
+
+    /// Returns the number of input cells of an instance, which come first
+    fn n_input_cells(&self) -> u64;
+
+    /// Checks the inputs of an instance
+    fn validate(&self, inputs: &[F]) -> Result<(), String>;
+
+    /// Computes the output cells of an instance from its validated inputs
+    fn deduce(&self, inputs: &[F]) -> Vec<F>;
+}
+
+/// Returns an error if `x` has bits set at or above the position `bits`
+fn check_bits<F: Field>(x: F, bits: usize) -> Result<(), String> {
+    if x.to_bits().iter().skip(bits).any(|b| *b) {
+        Err(format!("0x{} is not lower than 2^{bits}", x.to_hex_be()))
+    } else {
+        Ok(())
+    }
+}
+
+/// The range-check builtin, whose instances are a single value lower than
+/// `2^128`
+pub struct RangeCheckBuiltin;
+
+impl<F: Field> Builtin<F> for RangeCheckBuiltin {
+    fn name(&self) -> &'static str {
+        "range_check"
+    }
+
+    fn cells_per_instance(&self) -> u64 {
+        1
+    }
+
+    fn n_input_cells(&self) -> u64 {
+        1
+    }
+
+    fn validate(&self, inputs: &[F]) -> Result<(), String> {
+        check_bits(inputs[0], RANGE_CHECK_BITS)
+    }
+
+    fn deduce(&self, _inputs: &[F]) -> Vec<F> {
+        vec![]
+    }
+}
+
+/// The bitwise builtin, whose instances are `x, y, x & y, x ^ y, x | y` for
+/// inputs lower than `2^251`
+pub struct BitwiseBuiltin;
+
+impl<F: Field> Builtin<F> for BitwiseBuiltin {
+    fn name(&self) -> &'static str {
+        "bitwise"
+    }
+
+    fn cells_per_instance(&self) -> u64 {
+        5
+    }
+
+    fn n_input_cells(&self) -> u64 {
+        2
+    }
+
+    fn validate(&self, inputs: &[F]) -> Result<(), String> {
+        inputs.iter().try_for_each(|x| check_bits(*x, BITWISE_BITS))
+    }
+
+    fn deduce(&self, inputs: &[F]) -> Vec<F> {
+        let (x, y) = (inputs[0].to_bits(), inputs[1].to_bits());
+        let op = |f: fn(bool, bool) -> bool| {
+            let bits: Vec<bool> = x.iter().zip(y.iter()).map(|(a, b)| f(*a, *b)).collect();
+            F::from_bits(&bits).expect("the result is lower than 2^251")
+        };
+        vec![op(|a, b| a & b), op(|a, b| a ^ b), op(|a, b| a | b)]
+    }
+}
+
+/// The Pedersen builtin, whose instances are `x, y, H(x, y)`.
+///
+/// Cairo uses the Pedersen hash over the STARK curve, whose base field is the
+/// native field of Cairo. As turshi runs over other fields, the hash function
+/// is given by the user.
+pub struct PedersenBuiltin<F> {
+    hash: fn(F, F) -> F,
+}
+
+impl<F> PedersenBuiltin<F> {
+    /// Creates the builtin with the hash function `hash`
+    pub fn new(hash: fn(F, F) -> F) -> Self {
+        PedersenBuiltin { hash }
+    }
+}
+
+impl<F: Field> Builtin<F> for PedersenBuiltin<F> {
+    fn name(&self) -> &'static str {
+        "pedersen"
+    }
+
+    fn cells_per_instance(&self) -> u64 {
+        3
+    }
+
+    fn n_input_cells(&self) -> u64 {
+        2
+    }
+
+    fn validate(&self, _inputs: &[F]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn deduce(&self, inputs: &[F]) -> Vec<F> {
+        vec![(self.hash)(inputs[0], inputs[1])]
+    }
+}
+
+/// The Poseidon builtin of Cairo 1, whose instances are `x, y, z, x', y', z'`
+/// where `x', y', z'` is the Poseidon permutation of `x, y, z`.
+///
+/// As for [PedersenBuiltin], the permutation over the native field of Cairo is
+/// given by the user.
+pub struct PoseidonBuiltin<F> {
+    permutation: fn([F; 3]) -> [F; 3],
+}
+
+impl<F> PoseidonBuiltin<F> {
+    /// Creates the builtin with the permutation `permutation`
+    pub fn new(permutation: fn([F; 3]) -> [F; 3]) -> Self {
+        PoseidonBuiltin { permutation }
+    }
+}
+
+impl<F: Field> Builtin<F> for PoseidonBuiltin<F> {
+    fn name(&self) -> &'static str {
+        "poseidon"
+    }
+
+    fn cells_per_instance(&self) -> u64 {
+        6
+    }
+
+    fn n_input_cells(&self) -> u64 {
+        3
+    }
+
+    fn validate(&self, _inputs: &[F]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn deduce(&self, inputs: &[F]) -> Vec<F> {
+        (self.permutation)([inputs[0], inputs[1], inputs[2]]).to_vec()
+    }
+}
+
+/// The elliptic curve operation builtin, whose instances are
+/// `p.x, p.y, q.x, q.y, m, r.x, r.y` where `r = p + m * q` over the STARK
+/// curve.
+///
+/// The operation is given by the user, and returns `None` when the inputs are
+/// not valid, e.g. when a point is not on the curve.
+pub struct EcOpBuiltin<F> {
+    op: EcOp<F>,
+}
+
+/// The operation of [EcOpBuiltin], computing `p + m * q` from `p`, `q` and `m`
+pub type EcOp<F> = fn((F, F), (F, F), F) -> Option<(F, F)>;
+
+impl<F> EcOpBuiltin<F> {
+    /// Creates the builtin with the operation `op`
+    pub fn new(op: EcOp<F>) -> Self {
+        EcOpBuiltin { op }
+    }
+}
+
+impl<F: Field> Builtin<F> for EcOpBuiltin<F> {
+    fn name(&self) -> &'static str {
+        "ec_op"
+    }
+
+    fn cells_per_instance(&self) -> u64 {
+        7
+    }
+
+    fn n_input_cells(&self) -> u64 {
+        5
+    }
+
+    fn validate(&self, inputs: &[F]) -> Result<(), String> {
+        (self.op)((inputs[0], inputs[1]), (inputs[2], inputs[3]), inputs[4])
+            .map(|_| ())
+            .ok_or_else(|| "invalid points or scalar".to_string())
+    }
+
+    fn deduce(&self, inputs: &[F]) -> Vec<F> {
+        let (x, y) = (self.op)((inputs[0], inputs[1]), (inputs[2], inputs[3]), inputs[4])
+            .expect("the inputs have been validated");
+        vec![x, y]
+    }
+}
+
+/// The memory segment of a builtin, and the progress of the deduction of its
+/// instances
+pub struct BuiltinSegment<F> {
+    builtin: Box<dyn Builtin<F>>,
+    /// address of the first cell of the segment
+    base: u64,
+    /// maximum number of instances in the segment
+    capacity: u64,
+    /// number of instances already validated and deduced
+    used: u64,
+}
+
+impl<F: Field> BuiltinSegment<F> {
+    /// Reserves a segment for `capacity` instances of `builtin` at the end of
+    /// the memory
+    pub fn new(mem: &mut CairoMemory<F>, builtin: Box<dyn Builtin<F>>, capacity: u64) -> Self {
+        let base = mem.reserve(capacity * builtin.cells_per_instance());
+        BuiltinSegment {
+            builtin,
+            base,
+            capacity,
+            used: 0,
+        }
+    }
+
+    /// Returns the builtin of the segment
+    pub fn builtin(&self) -> &dyn Builtin<F> {
+        self.builtin.as_ref()
+    }
+
+    /// Returns the address of the first cell of the segment, to be passed to
+    /// the program
+    pub fn base(&self) -> F {
+        F::from(self.base)
+    }
+
+    /// Returns the number of instances used by the program so far
+    pub fn used_instances(&self) -> u64 {
+        self.used
+    }
+
+    /// Returns the address following the last used instance
+    pub fn stop_ptr(&self) -> F {
+        F::from(self.base + self.used * self.builtin.cells_per_instance())
+    }
+
+    fn instance_addr(&self, instance: u64) -> u64 {
+        self.base + instance * self.builtin.cells_per_instance()
+    }
+
+    /// Validates the instances whose inputs have been written since the last
+    /// call, and writes their outputs
+    pub fn deduce(&mut self, mem: &mut CairoMemory<F>) -> Result<(), BuiltinError> {
+        let (cells, n_inputs) = (
+            self.builtin.cells_per_instance(),
+            self.builtin.n_input_cells(),
+        );
+        while self.used < self.capacity {
+            let addr = self.instance_addr(self.used);
+            let inputs: Option<Vec<F>> = (addr..addr + n_inputs)
+                .map(|a| mem.read(F::from(a)))
+                .collect();
+            let Some(inputs) = inputs else {
+                return Ok(());
+            };
+            self.builtin
+                .validate(&inputs)
+                .map_err(|reason| BuiltinError::InvalidInput {
+                    builtin: self.builtin.name(),
+                    addr,
+                    reason,
+                })?;
+            let outputs = self.builtin.deduce(&inputs);
+            for (a, output) in (addr + n_inputs..addr + cells).zip(outputs) {
+                match mem.read(F::from(a)) {
+                    None => mem.write(F::from(a), output),
+                    Some(x) if x == output => (),
+                    Some(_) => {
+                        return Err(BuiltinError::InconsistentOutput {
+                            builtin: self.builtin.name(),
+                            addr: a,
+                        })
+                    }
+                }
+            }
+            self.used += 1;
+        }
+        Ok(())
+    }
+
+    /// Checks that the used instances are complete at the end of the
+    /// execution
+    pub fn finalize(&mut self, mem: &mut CairoMemory<F>) -> Result<(), BuiltinError> {
+        self.deduce(mem)?;
+        if self.used < self.capacity {
+            let addr = self.instance_addr(self.used);
+            let n_inputs = self.builtin.n_input_cells();
+            if let Some(a) = (addr..addr + n_inputs).find(|a| mem.read(F::from(*a)).is_some()) {
+                return Err(BuiltinError::IncompleteInstance {
+                    builtin: self.builtin.name(),
+                    addr: a,
+                });
+            }
+        }
+        Ok(())
+    }
+}
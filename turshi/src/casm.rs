@@ -0,0 +1,559 @@
+//! This module runs the programs of the Cairo 1 toolchain. Cairo 1 sources
+//! are compiled to Sierra, which `starknet-sierra-compile` compiles to CASM,
+//! the assembly of the Cairo machine. The CASM artifact is a JSON file
+//! containing the bytecode, its entry points and its hints.
+//!
+//! Contrary to Cairo 0, the hints are not Python snippets but structured
+//! objects, e.g. `{"AllocSegment": {"dst": {"register": "AP", "offset": 0}}}`,
+//! whose semantics is fixed by the compiler. They are executed by the
+//! [CairoRunner] over the segmented memory, as some of them create segments.
+//! The hints that are not supported, e.g. the system calls of Starknet, are
+//! loaded but make the execution fail if they are reached.
+//!
+//! An entry point is called with the following arguments:
+//! - the pointers of the builtin segments, in the order of
+//!   [EntryPoint::builtins];
+//! - the gas counter, which the program decreases to pay for its steps;
+//! - the system pointer, to an empty segment as system calls are not supported;
+//! - the calldata, as the pointers to its start and end in a dedicated segment.
+//!
+//! It returns the stop pointers of the builtins, the remaining gas, the
+//! system pointer, and a panic flag followed by the pointers to the start and
+//! end of the returned data.
+//!
+//! The bytecode is loaded in the program segment, so the program counters of
+//! the hints and entry points are offsets in that segment.
+
+use crate::{
+    builtins::Builtin,
+    loader::{parse_word, CAIRO_PRIME},
+    segments::{MaybeRelocatable, Relocatable, SegmentedMemory},
+    vm::{to_signed, CairoRunner, Registers, VmError},
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use o1_utils::FieldHelpers;
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Number of values returned by an entry point after the builtin stop
+/// pointers: the remaining gas, the system pointer, the panic flag and the
+/// pointers to the returned data
+const RETURN_VALUES: i64 = 5;
+
+/// The errors raised while loading or running a CASM program
+#[derive(Error, Debug)]
+pub enum CasmError {
+    /// The file is not a valid CASM artifact
+    #[error("invalid CASM program: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The program has been compiled for another field
+    #[error("unsupported prime {0}, expected {CAIRO_PRIME}")]
+    UnsupportedPrime(String),
+    /// A word or an immediate value of the program is not a valid hexadecimal
+    /// number, or does not fit in the field
+    #[error("invalid word {0} in the program")]
+    InvalidWord(String),
+    /// The builtins given to the runner are not the ones of the entry point
+    #[error("the entry point uses the builtins {expected:?}, got {got:?}")]
+    BuiltinMismatch {
+        expected: Vec<String>,
+        got: Vec<String>,
+    },
+    /// The execution failed
+    #[error(transparent)]
+    Vm(#[from] VmError),
+    /// A value returned by the entry point is invalid
+    #[error("invalid return value: {0}")]
+    InvalidReturn(&'static str),
+}
+
+/// A register of the Cairo machine
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum Register {
+    AP,
+    FP,
+}
+
+/// The address of a cell, relative to a register
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub struct CellRef {
+    pub register: Register,
+    pub offset: i16,
+}
+
+/// An immediate value of a hint, as a signed hexadecimal number
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Immediate(pub String);
+
+/// A cell or an immediate value
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum DerefOrImmediate {
+    Deref(CellRef),
+    Immediate(Immediate),
+}
+
+/// The operation of a [ResOperand::BinOp]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Mul,
+}
+
+/// A binary operation between a cell and a cell or an immediate value
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct BinOp {
+    pub op: Operation,
+    pub a: CellRef,
+    pub b: DerefOrImmediate,
+}
+
+/// An operand of a hint
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum ResOperand {
+    /// The content of a cell, `[cell]`
+    Deref(CellRef),
+    /// The content of a cell pointed to by a cell, `[[cell] + offset]`
+    DoubleDeref(CellRef, i16),
+    /// An immediate value
+    Immediate(Immediate),
+    /// A binary operation
+    BinOp(BinOp),
+}
+
+/// A hint of a CASM program
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum CasmHint {
+    /// Writes a pointer to a new segment in `dst`
+    AllocSegment { dst: CellRef },
+    /// Writes 1 in `dst` if `lhs < rhs`, 0 otherwise
+    TestLessThan {
+        lhs: ResOperand,
+        rhs: ResOperand,
+        dst: CellRef,
+    },
+    /// Writes 1 in `dst` if `lhs <= rhs`, 0 otherwise
+    TestLessThanOrEqual {
+        lhs: ResOperand,
+        rhs: ResOperand,
+        dst: CellRef,
+    },
+    /// Writes the quotient and remainder of the division of `lhs` by `rhs`
+    DivMod {
+        lhs: ResOperand,
+        rhs: ResOperand,
+        quotient: CellRef,
+        remainder: CellRef,
+    },
+    /// Splits `value` into `x * scalar + y`, with `x` lower or equal to
+    /// `max_x`
+    LinearSplit {
+        value: ResOperand,
+        scalar: ResOperand,
+        max_x: ResOperand,
+        x: CellRef,
+        y: CellRef,
+    },
+    /// A hint that is not supported, with its name
+    #[serde(skip)]
+    Unsupported(String),
+}
+
+/// A hint as found in the artifact, which may not be supported
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CasmHintJson {
+    Supported(CasmHint),
+    Unsupported(HashMap<String, serde_json::Value>),
+}
+
+impl From<CasmHintJson> for CasmHint {
+    fn from(hint: CasmHintJson) -> Self {
+        match hint {
+            CasmHintJson::Supported(hint) => hint,
+            CasmHintJson::Unsupported(hint) => {
+                CasmHint::Unsupported(hint.into_keys().next().unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// Reads the content of the cell at address `addr`
+fn read<F: PrimeField>(
+    memory: &SegmentedMemory<F>,
+    addr: Relocatable,
+) -> Result<MaybeRelocatable<F>, String> {
+    memory
+        .read(addr)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| format!("unknown value at {addr}"))
+}
+
+/// Returns the field element contained in `value`
+fn int<F>(value: MaybeRelocatable<F>) -> Result<F, String> {
+    match value {
+        MaybeRelocatable::Int(x) => Ok(x),
+        MaybeRelocatable::Pointer(addr) => Err(format!("unexpected pointer {addr}")),
+    }
+}
+
+impl CellRef {
+    /// Returns the address of the cell with the registers `regs`
+    pub fn address(&self, regs: &Registers) -> Result<Relocatable, String> {
+        let base = match self.register {
+            Register::AP => regs.ap,
+            Register::FP => regs.fp,
+        };
+        base.add_offset(self.offset.into())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Immediate {
+    /// Returns the value as an element of `F`
+    pub fn value<F: PrimeField>(&self) -> Result<F, String> {
+        let prime = BigUint::parse_bytes(CAIRO_PRIME[2..].as_bytes(), 16).unwrap();
+        match self.0.strip_prefix('-') {
+            Some(abs) => parse_word::<F>(abs, &prime).map(|x| -x),
+            None => parse_word(&self.0, &prime),
+        }
+        .map_err(|err| err.to_string())
+    }
+}
+
+impl ResOperand {
+    /// Computes the value of the operand with the registers `regs`
+    pub fn value<F: PrimeField>(
+        &self,
+        memory: &SegmentedMemory<F>,
+        regs: &Registers,
+    ) -> Result<MaybeRelocatable<F>, String> {
+        match self {
+            ResOperand::Deref(cell) => read(memory, cell.address(regs)?),
+            ResOperand::DoubleDeref(cell, offset) => match read(memory, cell.address(regs)?)? {
+                MaybeRelocatable::Pointer(addr) => read(
+                    memory,
+                    addr.add_offset((*offset).into())
+                        .map_err(|err| err.to_string())?,
+                ),
+                MaybeRelocatable::Int(_) => Err("double dereference of a field element".into()),
+            },
+            ResOperand::Immediate(x) => x.value().map(MaybeRelocatable::Int),
+            ResOperand::BinOp(BinOp { op, a, b }) => {
+                let a = read(memory, a.address(regs)?)?;
+                let b = match b {
+                    DerefOrImmediate::Deref(cell) => int(read(memory, cell.address(regs)?)?)?,
+                    DerefOrImmediate::Immediate(x) => x.value()?,
+                };
+                match (op, a) {
+                    (Operation::Add, MaybeRelocatable::Int(a)) => Ok(MaybeRelocatable::Int(a + b)),
+                    (Operation::Mul, MaybeRelocatable::Int(a)) => Ok(MaybeRelocatable::Int(a * b)),
+                    (Operation::Add, MaybeRelocatable::Pointer(addr)) => {
+                        let offset = to_signed(b).ok_or("invalid pointer offset")?;
+                        addr.add_offset(offset)
+                            .map(MaybeRelocatable::Pointer)
+                            .map_err(|err| err.to_string())
+                    }
+                    (Operation::Mul, MaybeRelocatable::Pointer(addr)) => {
+                        Err(format!("multiplication of the pointer {addr}"))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Computes the value of the operand as an integer
+    fn integer<F: PrimeField>(
+        &self,
+        memory: &SegmentedMemory<F>,
+        regs: &Registers,
+    ) -> Result<BigUint, String> {
+        Ok(int(self.value(memory, regs)?)?.to_biguint())
+    }
+}
+
+impl CasmHint {
+    /// Executes the hint with the registers `regs` of the instruction it is
+    /// attached to
+    pub fn execute<F: PrimeField>(
+        &self,
+        memory: &mut SegmentedMemory<F>,
+        regs: &Registers,
+    ) -> Result<(), String> {
+        let write = |memory: &mut SegmentedMemory<F>, cell: &CellRef, value| {
+            memory
+                .write(cell.address(regs)?, value)
+                .map_err(|err| err.to_string())
+        };
+        let integer = |x: BigUint| {
+            F::try_from_biguint(&x)
+                .map(MaybeRelocatable::Int)
+                .map_err(|err| err.to_string())
+        };
+        match self {
+            CasmHint::AllocSegment { dst } => {
+                let segment = memory.add_segment();
+                write(memory, dst, segment.into())
+            }
+            CasmHint::TestLessThan { lhs, rhs, dst } => {
+                let res = lhs.integer(memory, regs)? < rhs.integer(memory, regs)?;
+                write(memory, dst, MaybeRelocatable::Int(F::from(res)))
+            }
+            CasmHint::TestLessThanOrEqual { lhs, rhs, dst } => {
+                let res = lhs.integer(memory, regs)? <= rhs.integer(memory, regs)?;
+                write(memory, dst, MaybeRelocatable::Int(F::from(res)))
+            }
+            CasmHint::DivMod {
+                lhs,
+                rhs,
+                quotient,
+                remainder,
+            } => {
+                let (lhs, rhs) = (lhs.integer(memory, regs)?, rhs.integer(memory, regs)?);
+                if rhs == BigUint::default() {
+                    return Err("division by zero".into());
+                }
+                write(memory, quotient, integer(&lhs / &rhs)?)?;
+                write(memory, remainder, integer(lhs % rhs)?)
+            }
+            CasmHint::LinearSplit {
+                value,
+                scalar,
+                max_x,
+                x,
+                y,
+            } => {
+                let value = value.integer(memory, regs)?;
+                let scalar = scalar.integer(memory, regs)?;
+                let max_x = max_x.integer(memory, regs)?;
+                if scalar == BigUint::default() {
+                    return Err("division by zero".into());
+                }
+                let res_x = std::cmp::min(&value / &scalar, max_x);
+                let res_y = value - &res_x * scalar;
+                write(memory, x, integer(res_x)?)?;
+                write(memory, y, integer(res_y)?)
+            }
+            CasmHint::Unsupported(name) => Err(format!("unsupported hint {name}")),
+        }
+    }
+}
+
+/// An entry point of a CASM program
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct EntryPoint {
+    /// The selector of the entry point, in hexadecimal
+    pub selector: String,
+    /// The offset of the entry point in the bytecode
+    pub offset: u64,
+    /// The builtins used by the entry point, in the order of its arguments
+    pub builtins: Vec<String>,
+}
+
+/// The entry points of a CASM program, by type
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct EntryPoints {
+    #[serde(rename = "EXTERNAL", default)]
+    pub external: Vec<EntryPoint>,
+    #[serde(rename = "L1_HANDLER", default)]
+    pub l1_handler: Vec<EntryPoint>,
+    #[serde(rename = "CONSTRUCTOR", default)]
+    pub constructor: Vec<EntryPoint>,
+}
+
+#[derive(Deserialize)]
+struct CasmProgramJson {
+    prime: String,
+    #[serde(default)]
+    compiler_version: String,
+    bytecode: Vec<String>,
+    #[serde(default)]
+    hints: Vec<(u64, Vec<CasmHintJson>)>,
+    #[serde(default)]
+    entry_points_by_type: EntryPoints,
+}
+
+/// A program compiled by the Cairo 1 toolchain
+pub struct CasmProgram<F> {
+    /// The version of the compiler
+    pub compiler_version: String,
+    /// The bytecode of the program
+    pub bytecode: Vec<F>,
+    /// The hints of the program, indexed by their offset in the bytecode
+    pub hints: HashMap<u64, Vec<CasmHint>>,
+    /// The entry points of the program
+    pub entry_points: EntryPoints,
+}
+
+/// The result of the execution of an entry point
+pub struct CasmExecution<F> {
+    /// The runner, containing the memory and the trace of the execution
+    pub runner: CairoRunner<F>,
+    /// The gas consumed by the execution
+    pub gas_used: u64,
+    /// Whether the entry point panicked, in which case the returned data is
+    /// the panic reason
+    pub panicked: bool,
+    /// The returned data
+    pub retdata: Vec<F>,
+}
+
+impl<F: PrimeField> CasmProgram<F> {
+    /// Loads a program from the CASM artifact produced by
+    /// `starknet-sierra-compile`
+    pub fn from_json(json: &str) -> Result<Self, CasmError> {
+        let program: CasmProgramJson = serde_json::from_str(json)?;
+        let prime = BigUint::parse_bytes(CAIRO_PRIME[2..].as_bytes(), 16).unwrap();
+        let is_cairo_prime = program
+            .prime
+            .strip_prefix("0x")
+            .and_then(|p| BigUint::parse_bytes(p.as_bytes(), 16))
+            .is_some_and(|p| p == prime);
+        if !is_cairo_prime {
+            return Err(CasmError::UnsupportedPrime(program.prime));
+        }
+
+        let bytecode = program
+            .bytecode
+            .iter()
+            .map(|word| parse_word(word, &prime).map_err(|_| CasmError::InvalidWord(word.clone())))
+            .collect::<Result<_, _>>()?;
+        let hints = program
+            .hints
+            .into_iter()
+            .map(|(pc, hints)| (pc, hints.into_iter().map(CasmHint::from).collect()))
+            .collect();
+
+        Ok(CasmProgram {
+            compiler_version: program.compiler_version,
+            bytecode,
+            hints,
+            entry_points: program.entry_points_by_type,
+        })
+    }
+
+    /// Returns the external entry point whose selector is `selector`
+    pub fn external(&self, selector: &str) -> Option<&EntryPoint> {
+        let selector = BigUint::parse_bytes(selector.trim_start_matches("0x").as_bytes(), 16)?;
+        self.entry_points.external.iter().find(|entry| {
+            BigUint::parse_bytes(entry.selector.trim_start_matches("0x").as_bytes(), 16)
+                .is_some_and(|s| s == selector)
+        })
+    }
+
+    /// Runs the entry point `entry` with `gas` units of gas and the calldata
+    /// `calldata`, for at most `max_steps` steps. `builtins` are the builtins
+    /// of the entry point, in the order of [EntryPoint::builtins].
+    pub fn run(
+        &self,
+        entry: &EntryPoint,
+        builtins: Vec<Box<dyn Builtin<F>>>,
+        gas: u64,
+        calldata: &[F],
+        max_steps: u64,
+    ) -> Result<CasmExecution<F>, CasmError> {
+        let names: Vec<String> = builtins.iter().map(|b| b.name().to_string()).collect();
+        if names != entry.builtins {
+            return Err(CasmError::BuiltinMismatch {
+                expected: entry.builtins.clone(),
+                got: names,
+            });
+        }
+        let n_builtins = builtins.len() as i64;
+        let mut runner = CairoRunner::new(&self.bytecode, builtins);
+
+        let system = runner.memory.add_segment();
+        let calldata_start = runner.memory.add_segment();
+        let calldata: Vec<_> = calldata.iter().map(|x| MaybeRelocatable::Int(*x)).collect();
+        let calldata_end = runner
+            .memory
+            .load(calldata_start, &calldata)
+            .map_err(VmError::from)?;
+        runner
+            .memory
+            .mark_public(calldata_start, calldata.len() as u64);
+
+        let mut args: Vec<MaybeRelocatable<F>> = runner
+            .builtin_bases()
+            .into_iter()
+            .map(MaybeRelocatable::from)
+            .collect();
+        args.push(MaybeRelocatable::Int(F::from(gas)));
+        args.extend([system, calldata_start, calldata_end].map(MaybeRelocatable::from));
+
+        let program_segment = runner.program_base.segment;
+        let regs = runner.run_function(entry.offset, &args, max_steps, &mut |memory, regs| {
+            if regs.pc.segment != program_segment {
+                return Ok(());
+            }
+            for hint in self.hints.get(&regs.pc.offset).into_iter().flatten() {
+                hint.execute(memory, regs)
+                    .map_err(|err| VmError::Hint(regs.pc, err))?;
+            }
+            Ok(())
+        })?;
+
+        let ret = regs
+            .ap
+            .add_offset(-(n_builtins + RETURN_VALUES))
+            .map_err(VmError::from)?;
+        runner.finalize_builtins(ret)?;
+        let value = |i: i64| -> Result<MaybeRelocatable<F>, CasmError> {
+            let addr = ret.add_offset(n_builtins + i).map_err(VmError::from)?;
+            runner
+                .memory
+                .read(addr)
+                .map_err(VmError::from)?
+                .ok_or(CasmError::InvalidReturn("uninitialized return value"))
+        };
+
+        let remaining: u64 = match value(0)? {
+            MaybeRelocatable::Int(x) => x.to_biguint().try_into().ok(),
+            MaybeRelocatable::Pointer(_) => None,
+        }
+        .ok_or(CasmError::InvalidReturn("gas"))?;
+        let gas_used = gas
+            .checked_sub(remaining)
+            .ok_or(CasmError::InvalidReturn("gas"))?;
+        let panicked = match value(2)? {
+            MaybeRelocatable::Int(x) if x.is_zero() => false,
+            MaybeRelocatable::Int(x) if x.is_one() => true,
+            _ => return Err(CasmError::InvalidReturn("panic flag")),
+        };
+        let (start, end) = match (value(3)?, value(4)?) {
+            (MaybeRelocatable::Pointer(start), MaybeRelocatable::Pointer(end))
+                if start.segment == end.segment && start.offset <= end.offset =>
+            {
+                (start, end)
+            }
+            _ => return Err(CasmError::InvalidReturn("returned data")),
+        };
+        let retdata = (start.offset..end.offset)
+            .map(|offset| {
+                match runner
+                    .memory
+                    .read(Relocatable::new(start.segment, offset))
+                    .map_err(VmError::from)?
+                {
+                    Some(MaybeRelocatable::Int(x)) => Ok(x),
+                    _ => Err(CasmError::InvalidReturn("returned data")),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        // the returned values and data are public, as the arguments
+        runner.memory.mark_public(
+            ret.add_offset(n_builtins).map_err(VmError::from)?,
+            RETURN_VALUES as u64,
+        );
+        runner.memory.mark_public(start, end.offset - start.offset);
+
+        Ok(CasmExecution {
+            runner,
+            gas_used,
+            panicked,
+            retdata,
+        })
+    }
+}
@@ -0,0 +1,201 @@
+//! This module contains the execution of the hints of a Cairo program.
+//!
+//! A hint is a piece of code attached to an instruction, which the runner
+//! executes before the instruction. Hints are not part of the statement being
+//! proven: they write the values of the memory that the program could not
+//! compute efficiently by itself, such as the result of a division or a new
+//! memory segment. In the compiled programs, hints are Python snippets. They
+//! are given to a [HintProcessor], which decides which snippets it supports and
+//! how to run them.
+//!
+//! [BuiltinHintProcessor] maps the code of each supported hint to a Rust
+//! function, and can be extended with [BuiltinHintProcessor::add_hint]. It
+//! only supports the scope management hints of the standard library by
+//! default.
+
+use crate::{memory::CairoMemory, runner::CairoState};
+use ark_ff::Field;
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// The position of `ap` relatively to the beginning of a function, used to
+/// resolve the references to `ap`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct ApTracking {
+    pub group: u64,
+    pub offset: u64,
+}
+
+/// A hint attached to an instruction of a program
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    /// The source code of the hint
+    pub code: String,
+    /// The scopes whose identifiers are visible from the hint, e.g.
+    /// `__main__.main`
+    pub accessible_scopes: Vec<String>,
+    /// The position of `ap` when the hint is executed
+    pub ap_tracking: ApTracking,
+    /// The variables `ids.<name>` of the hint, mapped to the indices of their
+    /// references in [crate::loader::CompiledProgram::references]
+    pub reference_ids: HashMap<String, usize>,
+}
+
+impl Hint {
+    /// Creates a hint from its source code, without metadata
+    pub fn new(code: &str) -> Self {
+        Hint {
+            code: code.to_string(),
+            accessible_scopes: vec![],
+            ap_tracking: ApTracking::default(),
+            reference_ids: HashMap::new(),
+        }
+    }
+}
+
+/// The errors that can happen while executing a hint
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HintError {
+    /// The hint processor does not support the hint
+    #[error("unknown hint: {0}")]
+    UnknownHint(String),
+    /// The hint has been run, but failed
+    #[error("the hint {0} failed: {1}")]
+    Failed(String, String),
+}
+
+/// The hints of a program, indexed by the memory address of the instruction
+/// they are attached to. The hints of an instruction are executed in order.
+pub type Hints = HashMap<u64, Vec<Hint>>;
+
+/// The variables of the hints, organized in a stack of scopes. The bottom scope
+/// is the one of the main function and is never exited.
+pub struct ExecutionScopes<F> {
+    scopes: Vec<HashMap<String, F>>,
+}
+
+impl<F: Field> Default for ExecutionScopes<F> {
+    fn default() -> Self {
+        ExecutionScopes {
+            scopes: vec![HashMap::new()],
+        }
+    }
+}
+
+impl<F: Field> ExecutionScopes<F> {
+    /// Enters a new empty scope
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    /// Exits the current scope, and drops its variables
+    pub fn exit_scope(&mut self) -> Result<(), String> {
+        if self.scopes.len() == 1 {
+            return Err("cannot exit the main scope".to_string());
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+
+    /// Returns the number of scopes, including the main one
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Returns the value of the variable `name` in the current scope
+    pub fn get(&self, name: &str) -> Option<F> {
+        self.current().get(name).copied()
+    }
+
+    /// Sets the variable `name` in the current scope
+    pub fn insert(&mut self, name: &str, value: F) {
+        self.scopes
+            .last_mut()
+            .expect("the main scope is never exited")
+            .insert(name.to_string(), value);
+    }
+
+    fn current(&self) -> &HashMap<String, F> {
+        self.scopes.last().expect("the main scope is never exited")
+    }
+}
+
+/// This trait is implemented by the executors of hints, which the runner calls
+/// before executing an instruction with hints
+pub trait HintProcessor<F> {
+    /// Executes `hint` with the registers `state` of the instruction it is
+    /// attached to. The hint can read and write the memory `mem`.
+    fn execute_hint(
+        &mut self,
+        hint: &Hint,
+        mem: &mut CairoMemory<F>,
+        state: &CairoState<F>,
+    ) -> Result<(), HintError>;
+}
+
+/// The Rust implementation of a hint, see [BuiltinHintProcessor::add_hint]
+pub type HintFunction<F> = Box<
+    dyn FnMut(&mut CairoMemory<F>, &CairoState<F>, &mut ExecutionScopes<F>) -> Result<(), String>,
+>;
+
+/// A hint processor running the hints whose code it knows with Rust functions
+pub struct BuiltinHintProcessor<F> {
+    /// The implementations of the hints, indexed by their code
+    functions: HashMap<String, HintFunction<F>>,
+    /// The variables of the hints
+    pub scopes: ExecutionScopes<F>,
+}
+
+impl<F: Field> Default for BuiltinHintProcessor<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> BuiltinHintProcessor<F> {
+    /// Creates a processor supporting the scope hints `vm_enter_scope()` and
+    /// `vm_exit_scope()`
+    pub fn new() -> Self {
+        let mut processor = BuiltinHintProcessor {
+            functions: HashMap::new(),
+            scopes: ExecutionScopes::default(),
+        };
+        processor.add_hint(
+            "vm_enter_scope()",
+            Box::new(|_, _, scopes| {
+                scopes.enter_scope();
+                Ok(())
+            }),
+        );
+        processor.add_hint(
+            "vm_exit_scope()",
+            Box::new(|_, _, scopes| scopes.exit_scope()),
+        );
+        processor
+    }
+
+    /// Registers the implementation of the hint with the source code `code`.
+    /// The code is compared after removing its leading and trailing
+    /// whitespace. It replaces the previous implementation of the hint, if any.
+    pub fn add_hint(&mut self, code: &str, function: HintFunction<F>) {
+        self.functions.insert(code.trim().to_string(), function);
+    }
+}
+
+impl<F: Field> HintProcessor<F> for BuiltinHintProcessor<F> {
+    fn execute_hint(
+        &mut self,
+        hint: &Hint,
+        mem: &mut CairoMemory<F>,
+        state: &CairoState<F>,
+    ) -> Result<(), HintError> {
+        let code = hint.code.trim();
+        let function = self
+            .functions
+            .get_mut(code)
+            .ok_or_else(|| HintError::UnknownHint(code.to_string()))?;
+        function(mem, state, &mut self.scopes)
+            .map_err(|err| HintError::Failed(code.to_string(), err))
+    }
+}
@@ -6,14 +6,29 @@
 //! logic which is represented as steps of computation making up the full
 //! program.
 
+pub mod builtins;
+pub mod casm;
 pub mod flags;
 pub mod helper;
+pub mod hints;
+pub mod loader;
 pub mod memory;
 pub mod runner;
+pub mod segments;
+pub mod vm;
 pub mod word;
 
 pub use self::{
+    builtins::{
+        BitwiseBuiltin, Builtin, BuiltinSegment, EcOpBuiltin, PedersenBuiltin, PoseidonBuiltin,
+        RangeCheckBuiltin,
+    },
+    casm::{CasmExecution, CasmProgram},
+    hints::{BuiltinHintProcessor, Hint, HintProcessor, Hints},
+    loader::CompiledProgram,
     memory::CairoMemory,
-    runner::{CairoInstruction, CairoProgram, Pointers},
+    runner::{CairoInstruction, CairoProgram, CairoState, Pointers},
+    segments::{MaybeRelocatable, Relocatable, RelocatedMemory, SegmentedMemory},
+    vm::{CairoRunner, VmError},
     word::{FlagBits, Offsets},
 };
@@ -0,0 +1,221 @@
+//! This module loads the programs compiled with `cairo-compile`, whose output
+//! is a JSON file containing the bytecode of the program, its identifiers,
+//! its hints and the references used by the hints.
+//!
+//! The memory of turshi starts with a dummy cell, therefore the program is
+//! loaded at address 1, and the program counters of the compiled program
+//! (entry points and hints) are shifted by 1.
+//!
+//! The words of the bytecode are elements of the field of Cairo, whose prime
+//! is [CAIRO_PRIME]. A word larger than half of the prime is a negative value,
+//! e.g. the offset of a backward jump, and is converted into the opposite of
+//! its absolute value in `F`.
+
+use crate::{
+    hints::{ApTracking, Hint, Hints},
+    memory::CairoMemory,
+};
+use ark_ff::Field;
+use num_bigint::BigUint;
+use o1_utils::FieldHelpers;
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// The prime of the field of Cairo, `2^251 + 17 * 2^192 + 1`
+pub const CAIRO_PRIME: &str = "0x800000000000011000000000000000000000000000000000000000000000001";
+
+/// The errors raised while loading a compiled program
+#[derive(Error, Debug)]
+pub enum LoaderError {
+    /// The file is not a valid compiled program
+    #[error("invalid compiled program: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The program has been compiled for another field
+    #[error("unsupported prime {0}, expected {CAIRO_PRIME}")]
+    UnsupportedPrime(String),
+    /// A word of the program is not a valid hexadecimal number, or does not fit
+    /// in the field
+    #[error("invalid word {0} in the program")]
+    InvalidWord(String),
+    /// A hint is attached to an invalid program counter
+    #[error("invalid program counter {0} for a hint")]
+    InvalidHintPc(String),
+}
+
+/// A reference of the program, i.e. an expression giving the value of a
+/// variable, like `[cast(fp + (-3), felt*)]`
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Reference {
+    /// The program counter from which the reference is valid, in the compiled
+    /// program
+    pub pc: Option<u64>,
+    /// The expression of the reference
+    pub value: String,
+    pub ap_tracking_data: ApTracking,
+}
+
+/// An identifier of the program: a function, a label, a constant, a type, ...
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Identifier {
+    /// The kind of identifier, e.g. `function`, `label`, `const` or `alias`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The program counter of the functions and labels, in the compiled program
+    pub pc: Option<u64>,
+    /// The identifier an alias points to
+    pub destination: Option<String>,
+    #[serde(default)]
+    pub decorators: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct FlowTrackingData {
+    ap_tracking: ApTracking,
+    #[serde(default)]
+    reference_ids: HashMap<String, usize>,
+}
+
+#[derive(Deserialize)]
+struct CompiledHint {
+    code: String,
+    #[serde(default)]
+    accessible_scopes: Vec<String>,
+    flow_tracking_data: FlowTrackingData,
+}
+
+#[derive(Deserialize, Default)]
+struct ReferenceManager {
+    references: Vec<Reference>,
+}
+
+#[derive(Deserialize)]
+struct CompiledProgramJson {
+    prime: String,
+    data: Vec<String>,
+    #[serde(default)]
+    builtins: Vec<String>,
+    #[serde(default)]
+    hints: HashMap<String, Vec<CompiledHint>>,
+    #[serde(default)]
+    identifiers: HashMap<String, Identifier>,
+    #[serde(default)]
+    main_scope: String,
+    #[serde(default)]
+    reference_manager: ReferenceManager,
+}
+
+/// A program compiled with `cairo-compile`, ready to be run
+pub struct CompiledProgram<F> {
+    /// The bytecode of the program
+    pub data: Vec<F>,
+    /// The builtins used by the program, in the order of the arguments of the
+    /// main function
+    pub builtins: Vec<String>,
+    /// The hints of the program, indexed by their address in the memory of
+    /// turshi
+    pub hints: Hints,
+    /// The identifiers of the program, indexed by their full name, e.g.
+    /// `__main__.main`
+    pub identifiers: HashMap<String, Identifier>,
+    /// The scope of the main module, usually `__main__`
+    pub main_scope: String,
+    /// The references used by the hints, see [Hint::reference_ids]
+    pub references: Vec<Reference>,
+}
+
+/// Converts the hexadecimal word `word` of the field of Cairo into `F`
+pub(crate) fn parse_word<F: Field>(word: &str, prime: &BigUint) -> Result<F, LoaderError> {
+    let invalid = || LoaderError::InvalidWord(word.to_string());
+    let digits = word.strip_prefix("0x").ok_or_else(invalid)?;
+    let value = BigUint::parse_bytes(digits.as_bytes(), 16).ok_or_else(invalid)?;
+    if &value >= prime {
+        return Err(invalid());
+    }
+    let (value, negative) = if value > prime >> 1 {
+        (prime - value, true)
+    } else {
+        (value, false)
+    };
+    let mut bytes = value.to_bytes_le();
+    let size = F::zero().to_bytes().len();
+    if bytes.len() > size {
+        return Err(invalid());
+    }
+    bytes.resize(size, 0);
+    let x = F::from_bytes(&bytes).map_err(|_| invalid())?;
+    Ok(if negative { -x } else { x })
+}
+
+impl<F: Field> CompiledProgram<F> {
+    /// Loads a program from the JSON output of `cairo-compile`
+    pub fn from_json(json: &str) -> Result<Self, LoaderError> {
+        let program: CompiledProgramJson = serde_json::from_str(json)?;
+        let prime = BigUint::parse_bytes(CAIRO_PRIME[2..].as_bytes(), 16).unwrap();
+        let is_cairo_prime = program
+            .prime
+            .strip_prefix("0x")
+            .and_then(|p| BigUint::parse_bytes(p.as_bytes(), 16))
+            .is_some_and(|p| p == prime);
+        if !is_cairo_prime {
+            return Err(LoaderError::UnsupportedPrime(program.prime));
+        }
+
+        let data = program
+            .data
+            .iter()
+            .map(|word| parse_word(word, &prime))
+            .collect::<Result<_, _>>()?;
+
+        let mut hints = Hints::new();
+        for (pc, compiled_hints) in program.hints {
+            let pc: u64 = pc.parse().map_err(|_| LoaderError::InvalidHintPc(pc))?;
+            let hints_at_pc = compiled_hints
+                .into_iter()
+                .map(|hint| Hint {
+                    code: hint.code,
+                    accessible_scopes: hint.accessible_scopes,
+                    ap_tracking: hint.flow_tracking_data.ap_tracking,
+                    reference_ids: hint.flow_tracking_data.reference_ids,
+                })
+                .collect();
+            hints.insert(pc + 1, hints_at_pc);
+        }
+
+        Ok(CompiledProgram {
+            data,
+            builtins: program.builtins,
+            hints,
+            identifiers: program.identifiers,
+            main_scope: program.main_scope,
+            references: program.reference_manager.references,
+        })
+    }
+
+    /// Returns the address of the function or label `name` in the memory of
+    /// turshi, where `name` is relative to the main scope, e.g. `main`.
+    /// Aliases are followed.
+    pub fn entry_point(&self, name: &str) -> Option<u64> {
+        let mut full_name = format!("{}.{name}", self.main_scope);
+        // The aliases form a chain, bounded by the number of identifiers
+        for _ in 0..=self.identifiers.len() {
+            let identifier = self.identifiers.get(&full_name)?;
+            match (&identifier.pc, &identifier.destination) {
+                (Some(pc), _) => return Some(pc + 1),
+                (None, Some(destination)) => full_name = destination.clone(),
+                (None, None) => return None,
+            }
+        }
+        None
+    }
+
+    /// Returns the address of the `main` function in the memory of turshi
+    pub fn main(&self) -> Option<u64> {
+        self.entry_point("main")
+    }
+
+    /// Creates a memory containing the bytecode of the program
+    pub fn memory(&self) -> CairoMemory<F> {
+        CairoMemory::new(self.data.clone())
+    }
+}
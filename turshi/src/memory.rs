@@ -64,6 +64,14 @@ impl<F: Field> CairoMemory<F> {
         }
     }
 
+    /// Create a memory from its cells, following the dummy 0th entry, whose
+    /// first `codelen` cells are the public memory
+    pub fn from_cells(codelen: usize, cells: Vec<Option<F>>) -> CairoMemory<F> {
+        let mut data = vec![Some(CairoWord::new(F::zero()))];
+        data.extend(cells.into_iter().map(|x| x.map(CairoWord::new)));
+        CairoMemory { codelen, data }
+    }
+
     /// Get size of the public memory
     pub fn get_codelen(&self) -> usize {
         self.codelen
@@ -89,6 +97,14 @@ impl<F: Field> CairoMemory<F> {
         }
     }
 
+    /// Reserves `size` uninitialized cells at the end of the memory, and
+    /// returns the address of the first one
+    pub fn reserve(&mut self, size: u64) -> u64 {
+        let base = self.len();
+        self.data.extend(repeat(None).take(size as usize));
+        base
+    }
+
     /// Write u64 element in memory address
     pub fn write(&mut self, addr: F, elem: F) {
         self[addr] = Some(CairoWord::new(elem));
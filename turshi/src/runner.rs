@@ -2,7 +2,10 @@
 //! execution steps, each of which define the execution logic of Cairo instructions
 
 use crate::{
+    builtins::BuiltinSegment,
     flags::*,
+    helper::CairoFieldHelpers,
+    hints::{HintProcessor, Hints},
     memory::CairoMemory,
     word::{CairoWord, FlagBits, FlagSets, Offsets},
 };
@@ -504,16 +507,66 @@ pub struct CairoProgram<'a, F> {
 impl<'a, F: Field> CairoProgram<'a, F> {
     /// Creates a Cairo execution from the public information (memory and initial pointers)
     pub fn new(mem: &mut CairoMemory<F>, pc: u64) -> CairoProgram<F> {
+        CairoProgram::run(mem, pc, None, &mut [])
+    }
+
+    /// Creates a Cairo execution of a program with hints. Before executing an
+    /// instruction, the hints attached to its address in `hints` are executed
+    /// by `processor`.
+    /// Panics if a hint is not supported by the processor or fails
+    pub fn new_with_hints(
+        mem: &'a mut CairoMemory<F>,
+        pc: u64,
+        hints: &Hints,
+        processor: &mut dyn HintProcessor<F>,
+    ) -> CairoProgram<'a, F> {
+        CairoProgram::run(mem, pc, Some((hints, processor)), &mut [])
+    }
+
+    /// Creates a Cairo execution of a program using the builtins of
+    /// `builtins`, whose segments must have been reserved in `mem` before the
+    /// arguments of the program. The outputs of the builtins are deduced after
+    /// each step.
+    /// Panics if an instance of a builtin is invalid
+    pub fn new_with_builtins(
+        mem: &'a mut CairoMemory<F>,
+        pc: u64,
+        builtins: &mut [BuiltinSegment<F>],
+    ) -> CairoProgram<'a, F> {
+        CairoProgram::run(mem, pc, None, builtins)
+    }
+
+    /// Creates a Cairo execution starting from the registers `ini`, e.g. the
+    /// initial registers of a relocated run of [crate::vm::CairoRunner]
+    pub fn new_from_state(mem: &mut CairoMemory<F>, ini: CairoState<F>) -> CairoProgram<F> {
+        let mut prog = CairoProgram::init(mem, 0);
+        prog.ini = ini;
+        prog.execute(None, &mut []);
+        prog
+    }
+
+    /// Creates a Cairo execution of a program with hints and builtins, see
+    /// [CairoProgram::new_with_hints] and [CairoProgram::new_with_builtins]
+    pub fn run(
+        mem: &'a mut CairoMemory<F>,
+        pc: u64,
+        hints: Option<(&Hints, &mut dyn HintProcessor<F>)>,
+        builtins: &mut [BuiltinSegment<F>],
+    ) -> CairoProgram<'a, F> {
+        let mut prog = CairoProgram::init(mem, pc);
+        prog.execute(hints, builtins);
+        prog
+    }
+
+    fn init(mem: &mut CairoMemory<F>, pc: u64) -> CairoProgram<F> {
         let ap = mem.len();
-        let mut prog = CairoProgram {
+        CairoProgram {
             steps: F::zero(),
             mem,
             ini: CairoState::new(F::from(pc), F::from(ap), F::from(ap)),
             fin: CairoState::new(F::zero(), F::zero(), F::zero()),
             trace: Vec::new(),
-        };
-        prog.execute();
-        prog
+        }
     }
 
     /// Outputs the total number of steps of the execution carried out by the runner
@@ -538,7 +591,11 @@ impl<'a, F: Field> CairoProgram<'a, F> {
 
     /// This function simulates an execution of the Cairo program received as input.
     /// It generates the full memory stack and the execution trace
-    fn execute(&mut self) {
+    fn execute(
+        &mut self,
+        mut hints: Option<(&Hints, &mut dyn HintProcessor<F>)>,
+        builtins: &mut [BuiltinSegment<F>],
+    ) {
         // set finishing flag to false, as it just started
         let mut end = false;
         // saves local copy of the initial (claimed) pointers of the program
@@ -548,6 +605,14 @@ impl<'a, F: Field> CairoProgram<'a, F> {
         let mut n: u64 = 0;
         // keep executing steps until the end is reached
         while !end {
+            // run the hints of the instruction, which can write the memory it reads
+            if let Some((hints, processor)) = hints.as_mut() {
+                for hint in hints.get(&next.pc.to_u64()).into_iter().flatten() {
+                    processor
+                        .execute_hint(hint, self.mem, &next)
+                        .unwrap_or_else(|err| panic!("Hint execution failed: {err}"));
+                }
+            }
             // create current step of computation
             let mut step = CairoStep::new(self.mem, next);
             // save current value of the pointers
@@ -569,6 +634,17 @@ impl<'a, F: Field> CairoProgram<'a, F> {
                     }
                 }
             }
+            // deduce the outputs of the builtins whose inputs have been written
+            for builtin in builtins.iter_mut() {
+                builtin
+                    .deduce(self.mem)
+                    .unwrap_or_else(|err| panic!("Builtin execution failed: {err}"));
+            }
+        }
+        for builtin in builtins.iter_mut() {
+            builtin
+                .finalize(self.mem)
+                .unwrap_or_else(|err| panic!("Builtin execution failed: {err}"));
         }
         self.steps = F::from(n);
         self.fin = CairoState::new(curr.pc, curr.ap, curr.fp);
@@ -0,0 +1,262 @@
+//! This module contains the segmented memory of Cairo, following the memory
+//! model of the Cairo whitepaper.
+//!
+//! During the execution, the memory is split into segments whose sizes are
+//! unknown in advance: the program, the execution stack, one segment per
+//! builtin, ... An address is a [Relocatable] value, i.e. a segment index and
+//! an offset in that segment, and a memory cell contains either a field
+//! element or an address. The memory is write-once: a cell can only be written
+//! again with the same value.
+//!
+//! At the end of the execution, the segments are relocated one after the
+//! other into a contiguous [CairoMemory], starting at address 1 as the dummy
+//! cell 0 of turshi is kept. The addresses stored in the memory are replaced
+//! by their relocated value, so that the relocated memory can be given to the
+//! prover. The ranges of the memory marked as public, e.g. the program and the
+//! arguments of the main function, are relocated as well.
+
+use crate::memory::CairoMemory;
+use ark_ff::Field;
+use std::{
+    fmt::{Display, Formatter},
+    ops::Range,
+};
+use thiserror::Error;
+
+/// An address of the segmented memory
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Relocatable {
+    /// index of the segment
+    pub segment: usize,
+    /// offset of the address in the segment
+    pub offset: u64,
+}
+
+impl Relocatable {
+    /// Creates the address of the cell `offset` of the segment `segment`
+    pub fn new(segment: usize, offset: u64) -> Self {
+        Relocatable { segment, offset }
+    }
+
+    /// Returns the address `offset` cells after this one, which can be
+    /// negative
+    pub fn add_offset(self, offset: i64) -> Result<Self, MemoryError> {
+        self.offset
+            .checked_add_signed(offset)
+            .map(|offset| Relocatable::new(self.segment, offset))
+            .ok_or(MemoryError::NegativeOffset(self, offset))
+    }
+}
+
+impl Display for Relocatable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.segment, self.offset)
+    }
+}
+
+/// The content of a memory cell: a field element or an address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaybeRelocatable<F> {
+    /// A field element
+    Int(F),
+    /// An address of the memory
+    Pointer(Relocatable),
+}
+
+impl<F> From<Relocatable> for MaybeRelocatable<F> {
+    fn from(addr: Relocatable) -> Self {
+        MaybeRelocatable::Pointer(addr)
+    }
+}
+
+/// The errors raised by the accesses to the segmented memory
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MemoryError {
+    /// The segment of an address has not been created
+    #[error("unknown segment {0}")]
+    UnknownSegment(usize),
+    /// An address would be before the beginning of its segment
+    #[error("the address {0} + {1} is before the beginning of its segment")]
+    NegativeOffset(Relocatable, i64),
+    /// A cell has been written twice with different values
+    #[error("inconsistent write at address {0}")]
+    InconsistentWrite(Relocatable),
+}
+
+/// The write-once memory of Cairo, split into segments
+#[derive(Default)]
+pub struct SegmentedMemory<F> {
+    /// cells of each segment, None if not initialized
+    segments: Vec<Vec<Option<MaybeRelocatable<F>>>>,
+    /// ranges of the memory marked as public, with their first address and
+    /// their size
+    public: Vec<(Relocatable, u64)>,
+}
+
+impl<F: Field> SegmentedMemory<F> {
+    /// Creates an empty memory, without segments
+    pub fn new() -> Self {
+        SegmentedMemory {
+            segments: vec![],
+            public: vec![],
+        }
+    }
+
+    /// Creates a new empty segment, and returns its first address
+    pub fn add_segment(&mut self) -> Relocatable {
+        self.segments.push(vec![]);
+        Relocatable::new(self.segments.len() - 1, 0)
+    }
+
+    /// Returns the number of segments
+    pub fn n_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns the size of the segment `segment`, i.e. the offset following
+    /// its last written cell
+    pub fn segment_size(&self, segment: usize) -> Result<u64, MemoryError> {
+        self.segments
+            .get(segment)
+            .map(|cells| cells.len() as u64)
+            .ok_or(MemoryError::UnknownSegment(segment))
+    }
+
+    /// Reads the cell at address `addr`, None if it is not initialized
+    pub fn read(&self, addr: Relocatable) -> Result<Option<MaybeRelocatable<F>>, MemoryError> {
+        let cells = self
+            .segments
+            .get(addr.segment)
+            .ok_or(MemoryError::UnknownSegment(addr.segment))?;
+        Ok(cells.get(addr.offset as usize).copied().flatten())
+    }
+
+    /// Writes `value` at address `addr`. Returns an error if the cell already
+    /// contains another value.
+    pub fn write(
+        &mut self,
+        addr: Relocatable,
+        value: impl Into<MaybeRelocatable<F>>,
+    ) -> Result<(), MemoryError> {
+        let value = value.into();
+        let cells = self
+            .segments
+            .get_mut(addr.segment)
+            .ok_or(MemoryError::UnknownSegment(addr.segment))?;
+        let offset = addr.offset as usize;
+        if cells.len() <= offset {
+            cells.resize(offset + 1, None);
+        }
+        match cells[offset] {
+            None => cells[offset] = Some(value),
+            Some(x) if x == value => (),
+            Some(_) => return Err(MemoryError::InconsistentWrite(addr)),
+        }
+        Ok(())
+    }
+
+    /// Writes `values` from address `base`, and returns the address following
+    /// the last written cell
+    pub fn load(
+        &mut self,
+        base: Relocatable,
+        values: &[MaybeRelocatable<F>],
+    ) -> Result<Relocatable, MemoryError> {
+        for (i, value) in values.iter().enumerate() {
+            self.write(
+                Relocatable::new(base.segment, base.offset + i as u64),
+                *value,
+            )?;
+        }
+        Ok(Relocatable::new(
+            base.segment,
+            base.offset + values.len() as u64,
+        ))
+    }
+
+    /// Marks the `size` cells from address `base` as public
+    pub fn mark_public(&mut self, base: Relocatable, size: u64) {
+        self.public.push((base, size));
+    }
+
+    /// Returns the relocated address of the first cell of each segment
+    pub fn segment_bases(&self) -> Vec<u64> {
+        let mut bases = Vec::with_capacity(self.segments.len());
+        // the address 0 is the dummy cell of turshi
+        let mut base = 1;
+        for cells in &self.segments {
+            bases.push(base);
+            base += cells.len() as u64;
+        }
+        bases
+    }
+
+    /// Relocates the segments into a contiguous memory, see the module
+    /// documentation
+    pub fn relocate(&self) -> Result<RelocatedMemory<F>, MemoryError> {
+        let bases = self.segment_bases();
+        let relocate_addr = |addr: Relocatable| {
+            bases
+                .get(addr.segment)
+                .map(|base| base + addr.offset)
+                .ok_or(MemoryError::UnknownSegment(addr.segment))
+        };
+        let mut cells = vec![];
+        for segment in &self.segments {
+            for cell in segment {
+                cells.push(match cell {
+                    None => None,
+                    Some(MaybeRelocatable::Int(x)) => Some(*x),
+                    Some(MaybeRelocatable::Pointer(addr)) => Some(F::from(relocate_addr(*addr)?)),
+                });
+            }
+        }
+        let codelen = self.segments.first().map_or(0, |cells| cells.len());
+        let public_ranges = self
+            .public
+            .iter()
+            .map(|(base, size)| relocate_addr(*base).map(|start| start..start + size))
+            .collect::<Result<_, _>>()?;
+        Ok(RelocatedMemory {
+            memory: CairoMemory::from_cells(codelen, cells),
+            segment_bases: bases,
+            public_ranges,
+        })
+    }
+}
+
+/// The contiguous memory obtained by relocating a [SegmentedMemory]
+pub struct RelocatedMemory<F> {
+    /// the relocated memory, whose public part is the first segment
+    pub memory: CairoMemory<F>,
+    /// relocated address of the first cell of each segment
+    pub segment_bases: Vec<u64>,
+    /// relocated ranges of the memory marked as public
+    pub public_ranges: Vec<Range<u64>>,
+}
+
+impl<F: Field> RelocatedMemory<F> {
+    /// Returns the relocated value of the address `addr`
+    pub fn address(&self, addr: Relocatable) -> u64 {
+        self.segment_bases[addr.segment] + addr.offset
+    }
+
+    /// Returns the public memory, i.e. the addresses and values of the cells
+    /// in the public ranges, sorted by address.
+    /// Panics if a public cell is not initialized
+    pub fn public_memory(&mut self) -> Vec<(u64, F)> {
+        let mut addresses: Vec<u64> = self.public_ranges.iter().cloned().flatten().collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+        addresses
+            .into_iter()
+            .map(|addr| {
+                let value = self
+                    .memory
+                    .read(F::from(addr))
+                    .unwrap_or_else(|| panic!("Uninitialized public cell {addr}"));
+                (addr, value)
+            })
+            .collect()
+    }
+}
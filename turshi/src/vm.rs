@@ -0,0 +1,476 @@
+//! This module contains the Cairo runner over the segmented memory of
+//! [crate::segments], which follows the execution model of the Cairo
+//! whitepaper.
+//!
+//! The program is loaded in the first segment, the execution stack in the
+//! second one, and each builtin gets its own segment. The main function is
+//! called with the base addresses of the builtin segments, followed by its
+//! arguments, and returns into an empty segment whose address marks the end
+//! of the execution. The registers and the memory only contain [Relocatable]
+//! addresses, so that no segment needs to be sized in advance.
+//!
+//! After the execution, [CairoRunner::relocate] produces the contiguous memory
+//! and the trace of registers consumed by the prover. As the relocated memory
+//! is fully initialized, it can be executed again by [CairoProgram] from the
+//! relocated initial registers to obtain the trace of instructions.
+//!
+//! [CairoProgram]: crate::runner::CairoProgram
+
+use crate::{
+    builtins::{Builtin, BuiltinError},
+    flags::*,
+    helper::CairoFieldHelpers,
+    runner::CairoState,
+    segments::{MaybeRelocatable, MemoryError, Relocatable, RelocatedMemory, SegmentedMemory},
+    word::{CairoWord, FlagSets, Offsets},
+};
+use ark_ff::Field;
+use o1_utils::FieldHelpers;
+use thiserror::Error;
+
+/// The errors raised by the execution of a program
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VmError {
+    /// An access to the memory failed
+    #[error(transparent)]
+    Memory(#[from] MemoryError),
+    /// A builtin rejected one of its instances, whose address is its offset
+    /// in the segment of the builtin
+    #[error(transparent)]
+    Builtin(#[from] BuiltinError),
+    /// The word at the program counter is not a valid instruction
+    #[error("invalid instruction at {0}")]
+    InvalidInstruction(Relocatable),
+    /// An operand needed by an instruction is not initialized
+    #[error("the {0} of the instruction at {1} is unknown")]
+    UnknownValue(&'static str, Relocatable),
+    /// An operand of an instruction is an address where a field element is
+    /// expected, or conversely
+    #[error("the {0} of the instruction at {1} has an invalid type")]
+    InvalidType(&'static str, Relocatable),
+    /// The program did not reach the end within the maximum number of steps
+    #[error("the execution did not end after {0} steps")]
+    StepLimit(u64),
+    /// The pointer returned by the program for a builtin is not consistent
+    /// with the instances of its segment
+    #[error("invalid stop pointer of the {0} builtin")]
+    InvalidStopPointer(&'static str),
+    /// A hint attached to the instruction at the given address failed
+    #[error("the hint at {0} failed: {1}")]
+    Hint(Relocatable, String),
+}
+
+/// The function called by [CairoRunner::run_function] before each step, with
+/// the memory and the registers, to execute the hints of the instruction
+pub type HintCallback<'a, F> =
+    dyn FnMut(&mut SegmentedMemory<F>, &Registers) -> Result<(), VmError> + 'a;
+
+/// The registers of the runner
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Registers {
+    /// Program counter
+    pub pc: Relocatable,
+    /// Allocation pointer
+    pub ap: Relocatable,
+    /// Frame pointer
+    pub fp: Relocatable,
+}
+
+/// Converts a field element into a signed integer, if it is small enough
+pub(crate) fn to_signed<F: Field>(x: F) -> Option<i64> {
+    let is_small = |x: F| x.to_bits().iter().skip(63).all(|b| !b);
+    if is_small(x) {
+        Some(x.to_u64() as i64)
+    } else if is_small(-x) {
+        Some(-((-x).to_u64() as i64))
+    } else {
+        None
+    }
+}
+
+/// A builtin and the progress of the deduction of its segment
+struct BuiltinRunner<F> {
+    builtin: Box<dyn Builtin<F>>,
+    base: Relocatable,
+    /// number of instances already validated and deduced
+    used: u64,
+}
+
+impl<F: Field> BuiltinRunner<F> {
+    fn instance_addr(&self, instance: u64) -> Relocatable {
+        Relocatable::new(
+            self.base.segment,
+            self.base.offset + instance * self.builtin.cells_per_instance(),
+        )
+    }
+
+    fn read_int(
+        &self,
+        mem: &SegmentedMemory<F>,
+        addr: Relocatable,
+    ) -> Result<Option<F>, BuiltinError> {
+        match mem.read(addr) {
+            Ok(Some(MaybeRelocatable::Int(x))) => Ok(Some(x)),
+            Ok(None) => Ok(None),
+            _ => Err(BuiltinError::InvalidInput {
+                builtin: self.builtin.name(),
+                addr: addr.offset,
+                reason: "not a field element".to_string(),
+            }),
+        }
+    }
+
+    /// Validates the instances whose inputs have been written since the last
+    /// call, and writes their outputs
+    fn deduce(&mut self, mem: &mut SegmentedMemory<F>) -> Result<(), BuiltinError> {
+        let n_inputs = self.builtin.n_input_cells();
+        loop {
+            let addr = self.instance_addr(self.used);
+            let mut inputs = Vec::with_capacity(n_inputs as usize);
+            for i in 0..n_inputs {
+                match self.read_int(mem, Relocatable::new(addr.segment, addr.offset + i))? {
+                    Some(x) => inputs.push(x),
+                    None => return Ok(()),
+                }
+            }
+            let invalid = |reason| BuiltinError::InvalidInput {
+                builtin: self.builtin.name(),
+                addr: addr.offset,
+                reason,
+            };
+            self.builtin.validate(&inputs).map_err(invalid)?;
+            for (i, output) in self.builtin.deduce(&inputs).into_iter().enumerate() {
+                let output_addr = Relocatable::new(addr.segment, addr.offset + n_inputs + i as u64);
+                mem.write(output_addr, MaybeRelocatable::Int(output))
+                    .map_err(|_| BuiltinError::InconsistentOutput {
+                        builtin: self.builtin.name(),
+                        addr: output_addr.offset,
+                    })?;
+            }
+            self.used += 1;
+        }
+    }
+
+    /// Checks that the written cells of the segment form complete instances,
+    /// which have all been validated, and that `stop_ptr` follows the last one
+    fn finalize(
+        &mut self,
+        mem: &mut SegmentedMemory<F>,
+        stop_ptr: Option<MaybeRelocatable<F>>,
+    ) -> Result<(), VmError> {
+        self.deduce(mem)?;
+        let end = self.instance_addr(self.used);
+        // a cell written after the deduced instances belongs to an incomplete
+        // instance, or follows one
+        if mem.segment_size(end.segment)? > end.offset {
+            return Err(BuiltinError::IncompleteInstance {
+                builtin: self.builtin.name(),
+                addr: end.offset,
+            }
+            .into());
+        }
+        match stop_ptr {
+            Some(MaybeRelocatable::Pointer(ptr)) if ptr == end => Ok(()),
+            _ => Err(VmError::InvalidStopPointer(self.builtin.name())),
+        }
+    }
+}
+
+/// A runner executing a program over the segmented memory
+pub struct CairoRunner<F> {
+    /// memory of the execution
+    pub memory: SegmentedMemory<F>,
+    /// first address of the program
+    pub program_base: Relocatable,
+    /// first address of the execution stack
+    pub execution_base: Relocatable,
+    builtins: Vec<BuiltinRunner<F>>,
+    /// registers before each executed step
+    trace: Vec<Registers>,
+}
+
+impl<F: Field> CairoRunner<F> {
+    /// Creates a runner for the bytecode `program`, whose main function uses
+    /// `builtins` in that order
+    pub fn new(program: &[F], builtins: Vec<Box<dyn Builtin<F>>>) -> Self {
+        let mut memory = SegmentedMemory::new();
+        let program_base = memory.add_segment();
+        let execution_base = memory.add_segment();
+        let program: Vec<_> = program.iter().map(|x| MaybeRelocatable::Int(*x)).collect();
+        memory
+            .load(program_base, &program)
+            .expect("the program segment is empty");
+        memory.mark_public(program_base, program.len() as u64);
+        let builtins = builtins
+            .into_iter()
+            .map(|builtin| BuiltinRunner {
+                builtin,
+                base: memory.add_segment(),
+                used: 0,
+            })
+            .collect();
+        CairoRunner {
+            memory,
+            program_base,
+            execution_base,
+            builtins,
+            trace: vec![],
+        }
+    }
+
+    /// Returns the first address of the segment of each builtin
+    pub fn builtin_bases(&self) -> Vec<Relocatable> {
+        self.builtins.iter().map(|b| b.base).collect()
+    }
+
+    /// Returns the number of instances used by each builtin
+    pub fn used_instances(&self) -> Vec<u64> {
+        self.builtins.iter().map(|b| b.used).collect()
+    }
+
+    /// Returns the registers before each executed step
+    pub fn trace(&self) -> &[Registers] {
+        &self.trace
+    }
+
+    /// Runs the function at offset `entry` of the program with the arguments
+    /// `args`, until it returns or after `max_steps` steps. The function
+    /// receives the pointers of the builtin segments before `args`, and must
+    /// return their stop pointers as its last return values.
+    pub fn run(
+        &mut self,
+        entry: u64,
+        args: &[MaybeRelocatable<F>],
+        max_steps: u64,
+    ) -> Result<(), VmError> {
+        let mut stack: Vec<MaybeRelocatable<F>> =
+            self.builtins.iter().map(|b| b.base.into()).collect();
+        stack.extend_from_slice(args);
+        let regs = self.run_function(entry, &stack, max_steps, &mut |_, _| Ok(()))?;
+        // the stop pointers of the builtins are the last return values
+        let n_builtins = self.builtins.len() as i64;
+        self.finalize_builtins(regs.ap.add_offset(-n_builtins)?)
+    }
+
+    /// Runs the function at offset `entry` of the program with the arguments
+    /// `args`, which must include the builtin pointers, until it returns or
+    /// after `max_steps` steps. Before each step, `hints` is called with the
+    /// memory and the registers. Returns the registers at the end of the
+    /// execution, whose `ap` follows the return values.
+    pub fn run_function(
+        &mut self,
+        entry: u64,
+        args: &[MaybeRelocatable<F>],
+        max_steps: u64,
+        hints: &mut HintCallback<'_, F>,
+    ) -> Result<Registers, VmError> {
+        let return_fp = self.memory.add_segment();
+        let end = self.memory.add_segment();
+        let mut stack = args.to_vec();
+        stack.extend([return_fp, end].map(MaybeRelocatable::from));
+        let ap = self.memory.load(self.execution_base, &stack)?;
+        self.memory
+            .mark_public(self.execution_base, stack.len() as u64);
+
+        let mut regs = Registers {
+            pc: self.program_base.add_offset(entry as i64)?,
+            ap,
+            fp: ap,
+        };
+        while regs.pc != end {
+            if self.trace.len() as u64 >= max_steps {
+                return Err(VmError::StepLimit(max_steps));
+            }
+            hints(&mut self.memory, &regs)?;
+            self.trace.push(regs);
+            regs = self.step(regs)?;
+            for builtin in self.builtins.iter_mut() {
+                builtin.deduce(&mut self.memory)?;
+            }
+        }
+        Ok(regs)
+    }
+
+    /// Checks the segments of the builtins, whose stop pointers are stored in
+    /// order from address `stop_ptrs`, and marks the stop pointers as public
+    pub fn finalize_builtins(&mut self, stop_ptrs: Relocatable) -> Result<(), VmError> {
+        for (i, builtin) in self.builtins.iter_mut().enumerate() {
+            let stop_ptr = self.memory.read(stop_ptrs.add_offset(i as i64)?)?;
+            builtin.finalize(&mut self.memory, stop_ptr)?;
+        }
+        self.memory
+            .mark_public(stop_ptrs, self.builtins.len() as u64);
+        Ok(())
+    }
+
+    /// Reads the operand `name` of the instruction at `pc`, which must be
+    /// initialized
+    fn operand(
+        name: &'static str,
+        pc: Relocatable,
+        value: Option<MaybeRelocatable<F>>,
+    ) -> Result<MaybeRelocatable<F>, VmError> {
+        value.ok_or(VmError::UnknownValue(name, pc))
+    }
+
+    /// Returns the address contained in the operand `name`
+    fn pointer(
+        name: &'static str,
+        pc: Relocatable,
+        value: Option<MaybeRelocatable<F>>,
+    ) -> Result<Relocatable, VmError> {
+        match Self::operand(name, pc, value)? {
+            MaybeRelocatable::Pointer(addr) => Ok(addr),
+            MaybeRelocatable::Int(_) => Err(VmError::InvalidType(name, pc)),
+        }
+    }
+
+    /// Returns the offset contained in the operand `name`
+    fn offset(
+        name: &'static str,
+        pc: Relocatable,
+        value: Option<MaybeRelocatable<F>>,
+    ) -> Result<i64, VmError> {
+        match Self::operand(name, pc, value)? {
+            MaybeRelocatable::Int(x) => to_signed(x).ok_or(VmError::InvalidType(name, pc)),
+            MaybeRelocatable::Pointer(_) => Err(VmError::InvalidType(name, pc)),
+        }
+    }
+
+    /// Executes the instruction at `regs.pc`, and returns the next registers
+    fn step(&mut self, regs: Registers) -> Result<Registers, VmError> {
+        let Registers { pc, ap, fp } = regs;
+        let instr = match self.memory.read(pc)? {
+            Some(MaybeRelocatable::Int(word)) => CairoWord::new(word),
+            _ => return Err(VmError::InvalidInstruction(pc)),
+        };
+        let off = |x: F| to_signed(x).ok_or(VmError::InvalidInstruction(pc));
+
+        let adr_dst =
+            if instr.dst_reg() == DST_AP { ap } else { fp }.add_offset(off(instr.off_dst())?)?;
+        let adr_op0 =
+            if instr.op0_reg() == OP0_AP { ap } else { fp }.add_offset(off(instr.off_op0())?)?;
+        let op0 = self.memory.read(adr_op0)?;
+        let (op1_base, size) = match instr.op1_src() {
+            OP1_DBL => (Self::pointer("op0", pc, op0)?, 1),
+            OP1_VAL => (pc, 2),
+            OP1_FP => (fp, 1),
+            OP1_AP => (ap, 1),
+            _ => return Err(VmError::InvalidInstruction(pc)),
+        };
+        let adr_op1 = op1_base.add_offset(off(instr.off_op1())?)?;
+        let op1 = self.memory.read(adr_op1)?;
+        let dst = self.memory.read(adr_dst)?;
+
+        let res = if instr.pc_up() == PC_JNZ {
+            if instr.res_log() != RES_ONE
+                || instr.opcode() != OPC_JMP_INC
+                || instr.ap_up() == AP_ADD
+            {
+                return Err(VmError::InvalidInstruction(pc));
+            }
+            None
+        } else {
+            match instr.res_log() {
+                RES_ONE => op1,
+                RES_ADD => Some(
+                    match (
+                        Self::operand("op0", pc, op0)?,
+                        Self::operand("op1", pc, op1)?,
+                    ) {
+                        (MaybeRelocatable::Int(x), MaybeRelocatable::Int(y)) => {
+                            MaybeRelocatable::Int(x + y)
+                        }
+                        (MaybeRelocatable::Pointer(addr), y)
+                        | (y, MaybeRelocatable::Pointer(addr)) => {
+                            addr.add_offset(Self::offset("res", pc, Some(y))?)?.into()
+                        }
+                    },
+                ),
+                RES_MUL => match (
+                    Self::operand("op0", pc, op0)?,
+                    Self::operand("op1", pc, op1)?,
+                ) {
+                    (MaybeRelocatable::Int(x), MaybeRelocatable::Int(y)) => {
+                        Some(MaybeRelocatable::Int(x * y))
+                    }
+                    _ => return Err(VmError::InvalidType("res", pc)),
+                },
+                _ => return Err(VmError::InvalidInstruction(pc)),
+            }
+        };
+
+        let next_pc = match instr.pc_up() {
+            PC_SIZ => pc.add_offset(size)?,
+            PC_ABS => Self::pointer("res", pc, res)?,
+            PC_REL => pc.add_offset(Self::offset("res", pc, res)?)?,
+            PC_JNZ => {
+                if Self::operand("dst", pc, dst)? == MaybeRelocatable::Int(F::zero()) {
+                    pc.add_offset(size)?
+                } else {
+                    pc.add_offset(Self::offset("op1", pc, op1)?)?
+                }
+            }
+            _ => return Err(VmError::InvalidInstruction(pc)),
+        };
+
+        let (next_ap, next_fp) = match instr.opcode() {
+            OPC_CALL => {
+                if instr.ap_up() != AP_Z2 {
+                    return Err(VmError::InvalidInstruction(pc));
+                }
+                // save the frame pointer and the return address
+                self.memory.write(ap, fp)?;
+                self.memory.write(ap.add_offset(1)?, pc.add_offset(size)?)?;
+                (ap.add_offset(2)?, ap.add_offset(2)?)
+            }
+            OPC_JMP_INC | OPC_RET | OPC_AEQ => {
+                let next_ap = match instr.ap_up() {
+                    AP_Z2 => ap,
+                    AP_ADD => ap.add_offset(Self::offset("res", pc, res)?)?,
+                    AP_ONE => ap.add_offset(1)?,
+                    _ => return Err(VmError::InvalidInstruction(pc)),
+                };
+                let next_fp = match instr.opcode() {
+                    OPC_RET => Self::pointer("dst", pc, dst)?,
+                    OPC_AEQ => {
+                        // as in the flat runner, when the result is the
+                        // unknown second operand, it is deduced from dst
+                        match res {
+                            Some(res) => self.memory.write(adr_dst, res)?,
+                            None => self.memory.write(adr_op1, Self::operand("dst", pc, dst)?)?,
+                        }
+                        fp
+                    }
+                    _ => fp,
+                };
+                (next_ap, next_fp)
+            }
+            _ => return Err(VmError::InvalidInstruction(pc)),
+        };
+
+        Ok(Registers {
+            pc: next_pc,
+            ap: next_ap,
+            fp: next_fp,
+        })
+    }
+
+    /// Relocates the memory and returns it with the relocated trace of
+    /// registers
+    pub fn relocate(&self) -> Result<(RelocatedMemory<F>, Vec<CairoState<F>>), VmError> {
+        let memory = self.memory.relocate()?;
+        let trace = self
+            .trace
+            .iter()
+            .map(|regs| {
+                CairoState::new(
+                    F::from(memory.address(regs.pc)),
+                    F::from(memory.address(regs.ap)),
+                    F::from(memory.address(regs.fp)),
+                )
+            })
+            .collect();
+        Ok((memory, trace))
+    }
+}
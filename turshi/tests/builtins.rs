@@ -0,0 +1,164 @@
+use ark_ff::{One, Zero};
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    BitwiseBuiltin, BuiltinSegment, CairoMemory, CairoProgram, EcOpBuiltin, PedersenBuiltin,
+    Pointers, PoseidonBuiltin, RangeCheckBuiltin,
+};
+
+/// Encodes an instruction from its offsets and the positions of its flags
+fn instr(off_dst: i64, off_op0: i64, off_op1: i64, flags: &[usize]) -> i64 {
+    let flags: i64 = flags.iter().map(|f| 1 << f).sum();
+    (flags << 48)
+        | ((off_op1 + (1 << 15)) << 32)
+        | ((off_op0 + (1 << 15)) << 16)
+        | (off_dst + (1 << 15))
+}
+
+// The program
+//    func main{range_check_ptr}() {
+//        tempvar x = <value>;
+//        assert [range_check_ptr] = x;
+//        let range_check_ptr = range_check_ptr + 1;
+//        return ();
+//    }
+// with the range-check segment reserved after the code, and the arguments
+// written after the segment.
+fn range_check_program(value: i64) -> (CairoMemory<F>, BuiltinSegment<F>) {
+    let instrs = [
+        0x480680017fff8000,
+        value,
+        // [ap - 1] = [[fp - 3]]
+        instr(-1, -3, 0, &[1, 14]),
+        // [ap] = [fp - 3] + 1; ap++
+        instr(0, -3, 1, &[1, 2, 5, 11, 14]),
+        1,
+        0x208b7fff7fff7ffe,
+    ]
+    .iter()
+    .map(|&i: &i64| F::from(i))
+    .collect();
+    let mut mem = CairoMemory::<F>::new(instrs);
+    let segment = BuiltinSegment::new(&mut mem, Box::new(RangeCheckBuiltin), 4);
+    assert_eq!(segment.base(), F::from(7u32));
+    mem.write(F::from(11u32), segment.base()); // range_check_ptr
+    mem.write(F::from(12u32), F::from(16u32)); // previous frame pointer
+    mem.write(F::from(13u32), F::from(16u32)); // return address
+    (mem, segment)
+}
+
+#[test]
+fn test_cairo_range_check() {
+    let (mut mem, segment) = range_check_program(7);
+    let mut builtins = [segment];
+    let prog = CairoProgram::new_with_builtins(&mut mem, 1, &mut builtins);
+    assert_eq!(prog.fin().ap(), F::from(16u32));
+    assert_eq!(prog.mem.read(F::from(7u32)).unwrap(), F::from(7u32));
+    // The program returns the updated pointer
+    assert_eq!(
+        prog.mem.read(F::from(15u32)).unwrap(),
+        builtins[0].stop_ptr()
+    );
+    assert_eq!(builtins[0].used_instances(), 1);
+}
+
+#[test]
+#[should_panic(expected = "invalid input of the range_check builtin")]
+fn test_cairo_range_check_out_of_range() {
+    let (mut mem, segment) = range_check_program(-1);
+    CairoProgram::new_with_builtins(&mut mem, 1, &mut [segment]);
+}
+
+#[test]
+fn test_cairo_bitwise() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    let mut segment = BuiltinSegment::new(&mut mem, Box::new(BitwiseBuiltin), 2);
+    let base = segment.base();
+    mem.write(base, F::from(0b1100u32));
+    segment.deduce(&mut mem).unwrap();
+    assert_eq!(segment.used_instances(), 0);
+    mem.write(base + F::one(), F::from(0b1010u32));
+    segment.deduce(&mut mem).unwrap();
+    assert_eq!(segment.used_instances(), 1);
+    assert_eq!(mem.read(base + F::from(2u32)).unwrap(), F::from(0b1000u32));
+    assert_eq!(mem.read(base + F::from(3u32)).unwrap(), F::from(0b0110u32));
+    assert_eq!(mem.read(base + F::from(4u32)).unwrap(), F::from(0b1110u32));
+
+    // The inputs must be lower than 2^251
+    mem.write(base + F::from(5u32), -F::one());
+    mem.write(base + F::from(6u32), F::zero());
+    assert!(segment.deduce(&mut mem).is_err());
+}
+
+#[test]
+fn test_cairo_pedersen() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    let mut segment = BuiltinSegment::new(
+        &mut mem,
+        Box::new(PedersenBuiltin::new(|x, y| x * x + y)),
+        2,
+    );
+    let base = segment.base();
+    mem.write(base, F::from(3u32));
+    mem.write(base + F::one(), F::from(4u32));
+    // The output can be written by the program, if it is consistent
+    mem.write(base + F::from(2u32), F::from(13u32));
+    segment.deduce(&mut mem).unwrap();
+    assert_eq!(segment.used_instances(), 1);
+
+    mem.write(base + F::from(3u32), F::from(1u32));
+    mem.write(base + F::from(4u32), F::from(1u32));
+    mem.write(base + F::from(5u32), F::from(3u32));
+    assert!(segment.deduce(&mut mem).is_err());
+}
+
+#[test]
+fn test_cairo_poseidon() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    let mut segment = BuiltinSegment::new(
+        &mut mem,
+        Box::new(PoseidonBuiltin::new(|[x, y, z]| [x + y, y + z, z + x])),
+        1,
+    );
+    let base = segment.base();
+    for (i, x) in [1u32, 2, 4].into_iter().enumerate() {
+        mem.write(base + F::from(i as u64), F::from(x));
+    }
+    segment.deduce(&mut mem).unwrap();
+    assert_eq!(segment.used_instances(), 1);
+    assert_eq!(mem.read(base + F::from(3u32)), Some(F::from(3u32)));
+    assert_eq!(mem.read(base + F::from(5u32)), Some(F::from(5u32)));
+}
+
+#[test]
+fn test_cairo_ec_op() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    // an operation over the "points" of F^2 with the addition as group law,
+    // where the points with a zero coordinate are invalid
+    let mut segment = BuiltinSegment::new(
+        &mut mem,
+        Box::new(EcOpBuiltin::new(|p: (F, F), q: (F, F), m| {
+            (!p.0.is_zero() && !q.0.is_zero()).then_some((p.0 + m * q.0, p.1 + m * q.1))
+        })),
+        2,
+    );
+    let base = segment.base();
+    for (i, x) in [1u32, 2, 3, 4, 2].into_iter().enumerate() {
+        mem.write(base + F::from(i as u64), F::from(x));
+    }
+    segment.deduce(&mut mem).unwrap();
+    assert_eq!(mem.read(base + F::from(5u32)), Some(F::from(7u32)));
+    assert_eq!(mem.read(base + F::from(6u32)), Some(F::from(10u32)));
+
+    for i in 7..12u32 {
+        mem.write(base + F::from(i), F::zero());
+    }
+    assert!(segment.deduce(&mut mem).is_err());
+}
+
+#[test]
+fn test_cairo_incomplete_instance() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    let mut segment = BuiltinSegment::new(&mut mem, Box::new(BitwiseBuiltin), 1);
+    mem.write(segment.base(), F::one());
+    assert!(segment.finalize(&mut mem).is_err());
+}
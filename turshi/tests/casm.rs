@@ -0,0 +1,167 @@
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    casm::{CasmError, CasmHint},
+    vm::{Registers, VmError},
+    Builtin, CasmProgram, MaybeRelocatable, RangeCheckBuiltin, Relocatable, SegmentedMemory,
+};
+
+fn range_check() -> Vec<Box<dyn Builtin<F>>> {
+    vec![Box::new(RangeCheckBuiltin)]
+}
+
+#[test]
+fn test_casm_load_program() {
+    let program = CasmProgram::<F>::from_json(include_str!("programs/square.casm.json")).unwrap();
+    assert_eq!(program.compiler_version, "2.6.0");
+    assert_eq!(program.bytecode.len(), 31);
+    // negative words are mapped to their opposite in F
+    assert_eq!(program.bytecode[15], -F::from(100u32));
+    assert_eq!(program.hints.len(), 3);
+    assert!(matches!(
+        program.hints[&11][0],
+        CasmHint::AllocSegment { .. }
+    ));
+    let entry = program
+        .external("0x1c4ed0b5bb2f6bea4ab2ab9e6b4d4c0fb2d5d8a6e26b26d6e6f0bf3b4a8d9a2")
+        .unwrap();
+    assert_eq!(entry.offset, 0);
+    assert_eq!(entry.builtins, vec!["range_check".to_string()]);
+}
+
+#[test]
+fn test_casm_run_entry_point() {
+    // fn square(x: felt252) -> felt252 {
+    //     x * x
+    // }
+    // with a fixed cost of 100 units of gas
+    let program = CasmProgram::<F>::from_json(include_str!("programs/square.casm.json")).unwrap();
+    let entry = &program.entry_points.external[0];
+
+    let execution = program
+        .run(entry, range_check(), 1000, &[F::from(7u32)], 100)
+        .unwrap();
+    assert!(!execution.panicked);
+    assert_eq!(execution.retdata, vec![F::from(49u32)]);
+    assert_eq!(execution.gas_used, 100);
+    assert_eq!(execution.runner.used_instances(), vec![1]);
+
+    // the relocated execution can be replayed by the flat runner
+    let (mut relocated, trace) = execution.runner.relocate().unwrap();
+    let prog = turshi::CairoProgram::new_from_state(&mut relocated.memory, trace[0]);
+    assert_eq!(prog.trace().len(), trace.len());
+    // the calldata and the returned data are public
+    let public: Vec<F> = relocated
+        .public_memory()
+        .into_iter()
+        .map(|(_, x)| x)
+        .collect();
+    assert!(public.contains(&F::from(7u32)));
+    assert!(public.contains(&F::from(49u32)));
+}
+
+#[test]
+fn test_casm_out_of_gas() {
+    let program = CasmProgram::<F>::from_json(include_str!("programs/square.casm.json")).unwrap();
+    let entry = &program.entry_points.external[0];
+
+    let execution = program
+        .run(entry, range_check(), 99, &[F::from(7u32)], 100)
+        .unwrap();
+    assert!(execution.panicked);
+    assert!(execution.retdata.is_empty());
+    assert_eq!(execution.gas_used, 0);
+}
+
+#[test]
+fn test_casm_errors() {
+    let json = include_str!("programs/square.casm.json");
+    let program = CasmProgram::<F>::from_json(json).unwrap();
+    let entry = &program.entry_points.external[0];
+    assert!(matches!(
+        program.run(entry, vec![], 1000, &[F::from(7u32)], 100),
+        Err(CasmError::BuiltinMismatch { .. })
+    ));
+    assert!(matches!(
+        program.run(entry, range_check(), 1000, &[F::from(7u32)], 10),
+        Err(CasmError::Vm(VmError::StepLimit(10)))
+    ));
+
+    // the unsupported hints are loaded, but fail when executed
+    let json = json.replace("\"AllocSegment\"", "\"SystemCall\"");
+    let program = CasmProgram::<F>::from_json(&json).unwrap();
+    assert_eq!(
+        program.hints[&19],
+        vec![CasmHint::Unsupported("SystemCall".to_string())]
+    );
+    assert!(matches!(
+        program.run(entry, range_check(), 1000, &[F::from(7u32)], 100),
+        Err(CasmError::Vm(VmError::Hint(pc, _))) if pc == Relocatable::new(0, 19)
+    ));
+
+    let json = json.replace("0x800000000000011", "0x800000000000012");
+    assert!(matches!(
+        CasmProgram::<F>::from_json(&json),
+        Err(CasmError::UnsupportedPrime(_))
+    ));
+}
+
+#[test]
+fn test_casm_hints() {
+    let mut memory = SegmentedMemory::<F>::new();
+    let base = memory.add_segment();
+    memory
+        .load(
+            base,
+            &[
+                MaybeRelocatable::Int(F::from(47u32)),
+                MaybeRelocatable::Int(F::from(10u32)),
+            ],
+        )
+        .unwrap();
+    let regs = Registers {
+        pc: base,
+        ap: base.add_offset(2).unwrap(),
+        fp: base.add_offset(2).unwrap(),
+    };
+    let read = |memory: &SegmentedMemory<F>, offset| {
+        memory.read(Relocatable::new(0, offset)).unwrap().unwrap()
+    };
+
+    let hint: CasmHint = serde_json::from_str(
+        r#"{"DivMod": {
+            "lhs": {"Deref": {"register": "FP", "offset": -2}},
+            "rhs": {"Deref": {"register": "FP", "offset": -1}},
+            "quotient": {"register": "AP", "offset": 0},
+            "remainder": {"register": "AP", "offset": 1}
+        }}"#,
+    )
+    .unwrap();
+    hint.execute(&mut memory, &regs).unwrap();
+    assert_eq!(read(&memory, 2), MaybeRelocatable::Int(F::from(4u32)));
+    assert_eq!(read(&memory, 3), MaybeRelocatable::Int(F::from(7u32)));
+
+    // 47 = 3 * 13 + 8 with x bounded by 3
+    let hint: CasmHint = serde_json::from_str(
+        r#"{"LinearSplit": {
+            "value": {"Deref": {"register": "FP", "offset": -2}},
+            "scalar": {"Immediate": "0xd"},
+            "max_x": {"BinOp": {"op": "Add", "a": {"register": "AP", "offset": 1}, "b": {"Immediate": "-0x4"}}},
+            "x": {"register": "AP", "offset": 2},
+            "y": {"register": "AP", "offset": 3}
+        }}"#,
+    )
+    .unwrap();
+    hint.execute(&mut memory, &regs).unwrap();
+    assert_eq!(read(&memory, 4), MaybeRelocatable::Int(F::from(3u32)));
+    assert_eq!(read(&memory, 5), MaybeRelocatable::Int(F::from(8u32)));
+
+    let hint: CasmHint = serde_json::from_str(
+        r#"{"TestLessThan": {
+            "lhs": {"DoubleDeref": [{"register": "AP", "offset": 4}, 0]},
+            "rhs": {"Immediate": "0x1"},
+            "dst": {"register": "AP", "offset": 4}
+        }}"#,
+    )
+    .unwrap();
+    assert!(hint.execute(&mut memory, &regs).is_err());
+}
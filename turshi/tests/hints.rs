@@ -0,0 +1,77 @@
+use ark_ff::One;
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    hints::{ExecutionScopes, HintError, HintProcessor},
+    runner::CairoState,
+    BuiltinHintProcessor, CairoMemory, CairoProgram, Hint, Hints, Pointers,
+};
+
+// The program
+//    ap += 1          (the hint writes [ap] = 21 before this instruction)
+//    [ap] = [ap - 1] + [ap - 1]; ap++
+//    ret
+fn program() -> CairoMemory<F> {
+    let instrs = [0x40780017fff7fff, 1, 0x48307fff7fff8000, 0x208b7fff7fff7ffe]
+        .iter()
+        .map(|&i: &i64| F::from(i))
+        .collect();
+    let mut mem = CairoMemory::<F>::new(instrs);
+    mem.write(F::from(5u32), F::from(9u32)); // previous frame pointer
+    mem.write(F::from(6u32), F::from(9u32)); // return address
+    mem
+}
+
+fn hints(code: &str) -> Hints {
+    Hints::from([(1, vec![Hint::new(code)])])
+}
+
+#[test]
+fn test_cairo_hint() {
+    let mut mem = program();
+    let mut processor = BuiltinHintProcessor::new();
+    processor.add_hint(
+        "memory[ap] = 21",
+        Box::new(|mem, state, _| {
+            mem.write(state.ap(), F::from(21u32));
+            Ok(())
+        }),
+    );
+    let prog =
+        CairoProgram::new_with_hints(&mut mem, 1, &hints(" memory[ap] = 21\n"), &mut processor);
+    assert_eq!(prog.fin().ap(), F::from(9u32));
+    assert_eq!(prog.mem.read(F::from(7u32)).unwrap(), F::from(21u32));
+    assert_eq!(prog.mem.read(F::from(8u32)).unwrap(), F::from(42u32));
+}
+
+#[test]
+#[should_panic(expected = "unknown hint")]
+fn test_cairo_unknown_hint() {
+    let mut mem = program();
+    let mut processor = BuiltinHintProcessor::new();
+    CairoProgram::new_with_hints(&mut mem, 1, &hints("memory[ap] = 21"), &mut processor);
+}
+
+#[test]
+fn test_cairo_hint_scopes() {
+    let mut mem = program();
+    let state = CairoState::new(F::one(), F::from(7u32), F::from(7u32));
+    let mut processor = BuiltinHintProcessor::<F>::new();
+    processor.scopes.insert("n", F::from(3u32));
+
+    processor
+        .execute_hint(&Hint::new("vm_enter_scope()"), &mut mem, &state)
+        .unwrap();
+    assert_eq!(processor.scopes.depth(), 2);
+    assert_eq!(processor.scopes.get("n"), None);
+    processor
+        .execute_hint(&Hint::new("vm_exit_scope()"), &mut mem, &state)
+        .unwrap();
+    assert_eq!(processor.scopes.get("n"), Some(F::from(3u32)));
+
+    // The main scope cannot be exited
+    assert!(matches!(
+        processor.execute_hint(&Hint::new("vm_exit_scope()"), &mut mem, &state),
+        Err(HintError::Failed(_, _))
+    ));
+    assert_eq!(ExecutionScopes::<F>::default().depth(), 1);
+}
@@ -0,0 +1,92 @@
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    hints::ApTracking,
+    loader::{LoaderError, CAIRO_PRIME},
+    CairoProgram, CompiledProgram, Hint, Pointers,
+};
+
+#[test]
+fn test_cairo_load_program() {
+    let program = CompiledProgram::<F>::from_json(include_str!("programs/output.json")).unwrap();
+    assert_eq!(program.data.len(), 20);
+    // Negative words are converted into negative field elements
+    assert_eq!(program.data[12], -F::from(11u32));
+    assert_eq!(program.builtins, vec!["output".to_string()]);
+    assert_eq!(program.references.len(), 1);
+    // Entry points are shifted by the dummy cell of the memory
+    assert_eq!(program.main(), Some(5));
+    assert_eq!(program.entry_point("serialize_word"), Some(1));
+    assert_eq!(program.entry_point("SOME_CONSTANT"), None);
+    assert_eq!(program.entry_point("missing"), None);
+
+    // Same run as in test_cairo_output
+    let mut mem = program.memory();
+    mem.write(F::from(21u32), F::from(41u32)); // beginning of outputs
+    mem.write(F::from(22u32), F::from(44u32)); // end of outputs
+    mem.write(F::from(23u32), F::from(44u32)); // end of program
+    let prog = CairoProgram::new(&mut mem, program.main().unwrap());
+    assert_eq!(prog.fin().pc(), F::from(20u32));
+    assert_eq!(prog.fin().ap(), F::from(41u32));
+    assert_eq!(prog.mem.read(F::from(41u32)).unwrap(), F::from(10u32));
+}
+
+#[test]
+fn test_cairo_load_hints() {
+    let json = format!(
+        r#"{{
+            "prime": "{CAIRO_PRIME}",
+            "data": ["0x40780017fff7fff", "0x1", "0x208b7fff7fff7ffe"],
+            "hints": {{
+                "0": [{{
+                    "accessible_scopes": ["__main__", "__main__.main"],
+                    "code": "memory[ap] = segments.add()",
+                    "flow_tracking_data": {{
+                        "ap_tracking": {{"group": 1, "offset": 2}},
+                        "reference_ids": {{"__main__.main.x": 0}}
+                    }}
+                }}]
+            }},
+            "identifiers": {{}},
+            "main_scope": "__main__",
+            "reference_manager": {{"references": []}}
+        }}"#
+    );
+    let program = CompiledProgram::<F>::from_json(&json).unwrap();
+    let hints = &program.hints[&1];
+    assert_eq!(hints.len(), 1);
+    assert_eq!(
+        hints[0],
+        Hint {
+            code: "memory[ap] = segments.add()".to_string(),
+            accessible_scopes: vec!["__main__".to_string(), "__main__.main".to_string()],
+            ap_tracking: ApTracking {
+                group: 1,
+                offset: 2
+            },
+            reference_ids: [("__main__.main.x".to_string(), 0)].into(),
+        }
+    );
+}
+
+#[test]
+fn test_cairo_load_errors() {
+    let json = |prime: &str, word: &str| {
+        format!(r#"{{"prime": "{prime}", "data": ["{word}"], "identifiers": {{}}}}"#)
+    };
+    assert!(matches!(
+        CompiledProgram::<F>::from_json(&json("0x7", "0x1")),
+        Err(LoaderError::UnsupportedPrime(_))
+    ));
+    assert!(matches!(
+        CompiledProgram::<F>::from_json(&json(CAIRO_PRIME, "12")),
+        Err(LoaderError::InvalidWord(_))
+    ));
+    assert!(matches!(
+        CompiledProgram::<F>::from_json(&json(CAIRO_PRIME, CAIRO_PRIME)),
+        Err(LoaderError::InvalidWord(_))
+    ));
+    assert!(matches!(
+        CompiledProgram::<F>::from_json("{}"),
+        Err(LoaderError::Json(_))
+    ));
+}
@@ -0,0 +1,178 @@
+use ark_ff::Zero;
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    builtins::BuiltinError, segments::MemoryError, CairoProgram, CairoRunner, MaybeRelocatable,
+    Pointers, RangeCheckBuiltin, Relocatable, SegmentedMemory, VmError,
+};
+
+/// Encodes an instruction from its offsets and the positions of its flags
+fn instr(off_dst: i64, off_op0: i64, off_op1: i64, flags: &[usize]) -> F {
+    let flags: i64 = flags.iter().map(|f| 1 << f).sum();
+    F::from(
+        ((flags << 48)
+            | ((off_op1 + (1 << 15)) << 32)
+            | ((off_op0 + (1 << 15)) << 16)
+            | (off_dst + (1 << 15))) as u64,
+    )
+}
+
+/// ret
+fn ret() -> F {
+    F::from(0x208b7fff7fff7ffeu64)
+}
+
+#[test]
+fn test_segmented_memory() {
+    let mut mem = SegmentedMemory::<F>::new();
+    let a = mem.add_segment();
+    let b = mem.add_segment();
+    mem.write(a, MaybeRelocatable::Int(F::from(5u32))).unwrap();
+    mem.write(a.add_offset(2).unwrap(), b.add_offset(1).unwrap())
+        .unwrap();
+    mem.write(
+        b.add_offset(1).unwrap(),
+        MaybeRelocatable::Int(F::from(6u32)),
+    )
+    .unwrap();
+    // the memory is write-once
+    mem.write(a, MaybeRelocatable::Int(F::from(5u32))).unwrap();
+    assert_eq!(
+        mem.write(a, MaybeRelocatable::Int(F::from(4u32))),
+        Err(MemoryError::InconsistentWrite(a))
+    );
+    assert!(matches!(
+        a.add_offset(-1),
+        Err(MemoryError::NegativeOffset(_, -1))
+    ));
+    assert_eq!(mem.segment_size(0), Ok(3));
+    assert_eq!(mem.segment_size(1), Ok(2));
+    mem.mark_public(a, 1);
+
+    let mut relocated = mem.relocate().unwrap();
+    assert_eq!(relocated.segment_bases, vec![1, 4]);
+    assert_eq!(relocated.address(Relocatable::new(1, 1)), 5);
+    assert_eq!(relocated.memory.get_codelen(), 3);
+    // the pointer is replaced by its relocated value
+    assert_eq!(relocated.memory.read(F::from(3u32)), Some(F::from(5u32)));
+    assert_eq!(relocated.memory.read(F::from(2u32)), None);
+    assert_eq!(relocated.memory.read(F::from(5u32)), Some(F::from(6u32)));
+    assert_eq!(relocated.public_memory(), vec![(1, F::from(5u32))]);
+}
+
+#[test]
+fn test_runner_relocation() {
+    // func main(x) -> (y: felt) {
+    //     return (y=x * x);
+    // }
+    let program = [instr(0, -3, -3, &[1, 3, 6, 11, 14]), ret()];
+    let mut runner = CairoRunner::new(&program, vec![]);
+    runner
+        .run(0, &[MaybeRelocatable::Int(F::from(7u32))], 10)
+        .unwrap();
+    assert_eq!(runner.trace().len(), 2);
+    assert_eq!(
+        runner
+            .memory
+            .read(runner.execution_base.add_offset(3).unwrap()),
+        Ok(Some(MaybeRelocatable::Int(F::from(49u32))))
+    );
+
+    let (mut relocated, trace) = runner.relocate().unwrap();
+    // program, execution, return frame and end segments
+    assert_eq!(relocated.segment_bases, vec![1, 3, 7, 7]);
+    assert_eq!(
+        relocated.public_memory(),
+        vec![
+            (1, program[0]),
+            (2, program[1]),
+            (3, F::from(7u32)),
+            (4, F::from(7u32)),
+            (5, F::from(7u32)),
+        ]
+    );
+
+    // the relocated memory is executed again by the flat runner
+    let prog = CairoProgram::new_from_state(&mut relocated.memory, trace[0]);
+    assert_eq!(prog.steps(), F::from(2u32));
+    for (instr, state) in prog.trace().iter().zip(trace.iter()) {
+        assert_eq!(instr.pc(), state.pc());
+        assert_eq!(instr.ap(), state.ap());
+        assert_eq!(instr.fp(), state.fp());
+    }
+    assert_eq!(prog.mem.read(F::from(6u32)), Some(F::from(49u32)));
+}
+
+// func main{range_check_ptr}() {
+//     tempvar x = <value>;
+//     assert [range_check_ptr] = x;
+//     let range_check_ptr = range_check_ptr + <increment>;
+//     return ();
+// }
+fn range_check_program(value: i64, increment: i64) -> Vec<F> {
+    vec![
+        instr(0, -1, 1, &[0, 1, 2, 11, 14]),
+        if value < 0 {
+            -F::from(-value as u64)
+        } else {
+            F::from(value as u64)
+        },
+        // [ap - 1] = [[fp - 3]]
+        instr(-1, -3, 0, &[1, 14]),
+        // [ap] = [fp - 3] + <increment>; ap++
+        instr(0, -3, 1, &[1, 2, 5, 11, 14]),
+        F::from(increment as u64),
+        ret(),
+    ]
+}
+
+#[test]
+fn test_runner_range_check() {
+    let program = range_check_program(7, 1);
+    let mut runner = CairoRunner::new(&program, vec![Box::new(RangeCheckBuiltin)]);
+    runner.run(0, &[], 10).unwrap();
+    let base = runner.builtin_bases()[0];
+    assert_eq!(base, Relocatable::new(2, 0));
+    assert_eq!(runner.used_instances(), vec![1]);
+    assert_eq!(
+        runner.memory.read(base),
+        Ok(Some(MaybeRelocatable::Int(F::from(7u32))))
+    );
+
+    let (mut relocated, _) = runner.relocate().unwrap();
+    // the range-check segment follows the execution segment
+    assert_eq!(relocated.segment_bases[2], 1 + 6 + 5);
+    assert_eq!(relocated.memory.read(F::from(12u32)), Some(F::from(7u32)));
+    // the stop pointer returned by the program is public
+    let public = relocated.public_memory();
+    assert_eq!(public.last(), Some(&(11, F::from(13u32))));
+}
+
+#[test]
+fn test_runner_range_check_out_of_range() {
+    let program = range_check_program(-1, 1);
+    let mut runner = CairoRunner::new(&program, vec![Box::new(RangeCheckBuiltin)]);
+    assert!(matches!(
+        runner.run(0, &[], 10),
+        Err(VmError::Builtin(BuiltinError::InvalidInput { addr: 0, .. }))
+    ));
+}
+
+#[test]
+fn test_runner_invalid_stop_pointer() {
+    // the program uses an instance but returns the initial pointer
+    let program = range_check_program(7, 0);
+    let mut runner = CairoRunner::new(&program, vec![Box::new(RangeCheckBuiltin)]);
+    assert_eq!(
+        runner.run(0, &[], 10),
+        Err(VmError::InvalidStopPointer("range_check"))
+    );
+}
+
+#[test]
+fn test_runner_step_limit() {
+    // jmp rel 0
+    let program = [instr(-1, -1, 1, &[0, 1, 2, 8]), F::zero()];
+    let mut runner = CairoRunner::new(&program, vec![]);
+    assert_eq!(runner.run(0, &[], 10), Err(VmError::StepLimit(10)));
+    assert_eq!(runner.trace().len(), 10);
+}
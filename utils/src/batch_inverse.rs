@@ -0,0 +1,22 @@
+//! This module implements the batch inversion of field elements, with
+//! Montgomery's trick as implemented by [ark_ff::batch_inversion].
+
+use ark_ff::Field;
+
+/// Replaces each element of `v` by its inverse, in parallel.
+/// The zero elements are left unchanged.
+///
+/// ```
+/// use ark_ff::{Field, One};
+/// use mina_curves::pasta::Fp;
+/// use o1_utils::batch_inverse::batch_inverse;
+///
+/// let mut v = vec![Fp::from(2u64), Fp::from(0u64), Fp::from(5u64)];
+/// batch_inverse(&mut v);
+/// assert_eq!(v[0] * Fp::from(2u64), Fp::one());
+/// assert_eq!(v[1], Fp::from(0u64));
+/// assert_eq!(v[2], Fp::from(5u64).inverse().unwrap());
+/// ```
+pub fn batch_inverse<F: Field>(v: &mut [F]) {
+    ark_ff::batch_inversion(v)
+}
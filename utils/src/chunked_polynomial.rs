@@ -4,6 +4,7 @@
 
 use ark_ff::Field;
 use ark_poly::polynomial::{univariate::DensePolynomial, Polynomial};
+use std::slice::Chunks;
 
 /// This struct contains multiple chunk polynomials with degree `size-1`.
 pub struct ChunkedPolynomial<F: Field> {
@@ -48,3 +49,47 @@ impl<F: Field> ChunkedPolynomial<F> {
         DensePolynomial { coeffs }
     }
 }
+
+/// An iterator over the chunks of the coefficients of a polynomial, borrowing
+/// them instead of allocating a polynomial per chunk.
+/// The last chunk may be shorter than `size`, and empty chunks are yielded
+/// after the coefficients until at least `num_chunks` chunks were returned.
+pub struct ChunksIter<'a, F> {
+    chunks: Chunks<'a, F>,
+    padding: usize,
+}
+
+impl<'a, F> ChunksIter<'a, F> {
+    /// Creates an iterator over the chunks of size `size` of `coeffs`, padded
+    /// with empty chunks to return at least `num_chunks` chunks.
+    /// Panics if `size` is zero.
+    pub fn new(coeffs: &'a [F], num_chunks: usize, size: usize) -> Self {
+        let chunks = coeffs.chunks(size);
+        let padding = num_chunks.saturating_sub(chunks.len());
+        ChunksIter { chunks, padding }
+    }
+}
+
+impl<'a, F> Iterator for ChunksIter<'a, F> {
+    type Item = &'a [F];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().or_else(|| {
+            (self.padding > 0).then(|| {
+                self.padding -= 1;
+                &[][..]
+            })
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, F> ExactSizeIterator for ChunksIter<'a, F> {
+    fn len(&self) -> usize {
+        self.chunks.len() + self.padding
+    }
+}
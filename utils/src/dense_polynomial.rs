@@ -1,10 +1,13 @@
 //! This adds a few utility functions for the [DensePolynomial] arkworks type.
 
-use ark_ff::Field;
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_ff::{FftField, Field, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
+    Radix2EvaluationDomain as D,
+};
 use rayon::prelude::*;
 
-use crate::chunked_polynomial::ChunkedPolynomial;
+use crate::chunked_polynomial::{ChunkedPolynomial, ChunksIter};
 
 //
 // ExtendedDensePolynomial trait
@@ -24,6 +27,36 @@ pub trait ExtendedDensePolynomial<F: Field> {
     /// Convert a polynomial into chunks.
     /// Implementors must ensure that the result contains exactly num_chunks.
     fn to_chunked_polynomial(&self, num_chunks: usize, size: usize) -> ChunkedPolynomial<F>;
+
+    /// Iterates over the chunks of size `size` of the coefficients, without
+    /// allocating them. Empty chunks are yielded after the coefficients until
+    /// at least `num_chunks` chunks were returned.
+    fn chunks_iter(&self, num_chunks: usize, size: usize) -> ChunksIter<'_, F>;
+
+    /// Evaluates the polynomial at each of the `points`, in parallel.
+    fn par_evaluate_many(&self, points: &[F]) -> Vec<F>;
+
+    /// Multiplies two polynomials with FFTs over the precomputed `domain`.
+    /// Panics if the domain is too small for the product, i.e. if its size is
+    /// not larger than the sum of the degrees.
+    fn mul_with_domain(&self, other: &Self, domain: D<F>) -> Self
+    where
+        F: FftField;
+
+    /// Multiplies two polynomials with FFTs over the smallest domain large
+    /// enough for the product.
+    fn mul_fft(&self, other: &Self) -> Self
+    where
+        F: FftField;
+
+    /// Multiplies all the `polys` with FFTs over the precomputed `domain`,
+    /// with a single interpolation. Returns the constant polynomial one if
+    /// `polys` is empty.
+    /// Panics if the domain is too small for the product.
+    fn product_with_domain(polys: &[Self], domain: D<F>) -> Self
+    where
+        F: FftField,
+        Self: Sized;
 }
 
 impl<F: Field> ExtendedDensePolynomial<F> for DensePolynomial<F> {
@@ -47,15 +80,11 @@ impl<F: Field> ExtendedDensePolynomial<F> for DensePolynomial<F> {
     }
 
     fn to_chunked_polynomial(&self, num_chunks: usize, chunk_size: usize) -> ChunkedPolynomial<F> {
-        let mut chunk_polys: Vec<DensePolynomial<F>> = Vec::with_capacity(num_chunks);
-        for chunk in self.coeffs.chunks(chunk_size) {
-            chunk_polys.push(DensePolynomial::from_coefficients_slice(chunk));
-        }
-
-        // Pad unused chunks with zeros.
-        for _ in chunk_polys.len()..num_chunks {
-            chunk_polys.push(DensePolynomial::from_coefficients_vec(vec![]));
-        }
+        // Unused chunks are padded with zeros.
+        let chunk_polys: Vec<DensePolynomial<F>> = self
+            .chunks_iter(num_chunks, chunk_size)
+            .map(DensePolynomial::from_coefficients_slice)
+            .collect();
 
         // Ensuring that the number of chunks is the one requested, following
         // trait documentation
@@ -66,4 +95,60 @@ impl<F: Field> ExtendedDensePolynomial<F> for DensePolynomial<F> {
             size: chunk_size,
         }
     }
+
+    fn chunks_iter(&self, num_chunks: usize, size: usize) -> ChunksIter<'_, F> {
+        ChunksIter::new(&self.coeffs, num_chunks, size)
+    }
+
+    fn par_evaluate_many(&self, points: &[F]) -> Vec<F> {
+        points.par_iter().map(|x| self.evaluate(x)).collect()
+    }
+
+    fn mul_with_domain(&self, other: &Self, domain: D<F>) -> Self
+    where
+        F: FftField,
+    {
+        Self::product_with_domain(&[self.clone(), other.clone()], domain)
+    }
+
+    fn mul_fft(&self, other: &Self) -> Self
+    where
+        F: FftField,
+    {
+        if self.is_zero() || other.is_zero() {
+            return DensePolynomial::zero();
+        }
+        let domain = D::new(self.degree() + other.degree() + 1)
+            .expect("the product is too large for the FFT domains of the field");
+        self.mul_with_domain(other, domain)
+    }
+
+    fn product_with_domain(polys: &[Self], domain: D<F>) -> Self
+    where
+        F: FftField,
+    {
+        if polys.iter().any(|p| p.is_zero()) {
+            return DensePolynomial::zero();
+        }
+        let degree: usize = polys.iter().map(|p| p.degree()).sum();
+        assert!(
+            degree < domain.size(),
+            "the domain of size {} is too small for a product of degree {degree}",
+            domain.size()
+        );
+        let evals =
+            polys
+                .par_iter()
+                .map(|p| domain.fft(&p.coeffs))
+                .reduce_with(|mut acc, evals| {
+                    acc.par_iter_mut()
+                        .zip(evals.par_iter())
+                        .for_each(|(a, b)| *a *= b);
+                    acc
+                });
+        match evals {
+            None => DensePolynomial::from_coefficients_vec(vec![F::one()]),
+            Some(evals) => DensePolynomial::from_coefficients_vec(domain.ifft(&evals)),
+        }
+    }
 }
@@ -1,7 +1,7 @@
 //! This adds a few utility functions for the [Evaluations] arkworks type.
 
 use ark_ff::FftField;
-use ark_poly::{Evaluations, Radix2EvaluationDomain};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
 use rayon::prelude::*;
 
 /// An extension for the [Evaluations] type.
@@ -18,6 +18,27 @@ pub trait ExtendedEvaluations<F: FftField> {
 
     /// Utility function for shifting poly along domain coordinate
     fn shift(&self, len: usize) -> Self;
+
+    /// Rotates the evaluations cyclically by `rows`, which can be negative:
+    /// if `self` are the evaluations of `f` over the domain generated by `g`,
+    /// the result are the evaluations of `f(g^rows x)`.
+    fn rotate(&self, rows: isize) -> Self;
+
+    /// Returns the evaluations of `f(ωx)`, where `self` are the evaluations of
+    /// `f` and `ω` is the generator of the subdomain of size `base_size`,
+    /// e.g. the evaluations of the next row of a witness column evaluated
+    /// over an extended domain.
+    /// Panics if `base_size` does not divide the size of the domain.
+    fn next_row(&self, base_size: usize) -> Self;
+
+    /// Returns the evaluations of `f(offset x)` over the same domain, i.e. the
+    /// evaluations of `f` over the coset `offset H` of the domain `H`
+    fn coset_shift(&self, offset: F) -> Self;
+
+    /// Pads the evaluations with zeros up to the size of the larger `domain`.
+    /// Note that the result are not the evaluations of the same polynomial.
+    /// Panics if `domain` is smaller than the domain of the evaluations.
+    fn pad_to_domain(&self, domain: Radix2EvaluationDomain<F>) -> Self;
 }
 
 impl<F: FftField> ExtendedEvaluations<F> for Evaluations<F, Radix2EvaluationDomain<F>> {
@@ -53,4 +74,41 @@ impl<F: FftField> ExtendedEvaluations<F> for Evaluations<F, Radix2EvaluationDoma
         result.evals.append(&mut tail);
         result
     }
+
+    fn rotate(&self, rows: isize) -> Self {
+        let n = self.evals.len() as isize;
+        self.shift(rows.rem_euclid(n) as usize)
+    }
+
+    fn next_row(&self, base_size: usize) -> Self {
+        let size = self.domain().size();
+        assert_eq!(
+            size % base_size,
+            0,
+            "the base size {base_size} does not divide the domain size {size}"
+        );
+        self.shift(size / base_size)
+    }
+
+    fn coset_shift(&self, offset: F) -> Self {
+        let mut poly = self.interpolate_by_ref();
+        let mut power = F::one();
+        for coeff in poly.coeffs.iter_mut() {
+            *coeff *= power;
+            power *= offset;
+        }
+        Evaluations::from_vec_and_domain(self.domain().fft(&poly.coeffs), self.domain())
+    }
+
+    fn pad_to_domain(&self, domain: Radix2EvaluationDomain<F>) -> Self {
+        assert!(
+            domain.size() >= self.evals.len(),
+            "cannot pad {} evaluations to a domain of size {}",
+            self.evals.len(),
+            domain.size()
+        );
+        let mut evals = self.evals.clone();
+        evals.resize(domain.size(), F::zero());
+        Evaluations::from_vec_and_domain(evals, domain)
+    }
 }
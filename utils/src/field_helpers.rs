@@ -18,6 +18,12 @@ pub enum FieldHelpersError {
     DecodeHex,
     #[error("failed to convert BigUint into field element")]
     FromBigToField,
+    #[error("invalid radix {0}, expected a radix between 2 and 36")]
+    InvalidRadix(u32),
+    #[error("failed to parse a number in radix {0}")]
+    ParseRadix(u32),
+    #[error("the value is not smaller than the modulus")]
+    NonCanonical,
 }
 
 /// Result alias using [FieldHelpersError]
@@ -89,6 +95,30 @@ pub trait FieldHelpers<F> {
             .map_err(|_| FieldHelpersError::DeserializeBytes)
     }
 
+    /// Deserialize from BigUint, failing if the value is not smaller than
+    /// the modulus instead of reducing it
+    fn try_from_biguint(big: &BigUint) -> Result<F>
+    where
+        F: PrimeField,
+    {
+        if big >= &Self::modulus_biguint() {
+            return Err(FieldHelpersError::NonCanonical);
+        }
+        Self::from_biguint(big)
+    }
+
+    /// Deserialize from exactly [FieldHelpers::size_in_bytes] bytes, failing
+    /// if the value is not smaller than the modulus
+    fn from_bytes_checked(bytes: &[u8]) -> Result<F>
+    where
+        F: PrimeField,
+    {
+        if bytes.len() != Self::size_in_bytes() {
+            return Err(FieldHelpersError::DeserializeBytes);
+        }
+        Self::try_from_biguint(&BigUint::from_bytes_le(bytes))
+    }
+
     /// Serialize to bytes
     fn to_bytes(&self) -> Vec<u8>;
 
@@ -125,6 +155,32 @@ pub trait FieldHelpers<F> {
         (F::MODULUS_BIT_SIZE / 8) as usize + (F::MODULUS_BIT_SIZE % 8 != 0) as usize
     }
 
+    /// Deserialize from a string of digits in the given radix, between 2 and
+    /// 36, without prefix. Fails if the value is not smaller than the modulus.
+    fn from_str_radix(digits: &str, radix: u32) -> Result<F>
+    where
+        F: PrimeField,
+    {
+        if !(2..=36).contains(&radix) {
+            return Err(FieldHelpersError::InvalidRadix(radix));
+        }
+        let big = BigUint::parse_bytes(digits.as_bytes(), radix)
+            .ok_or(FieldHelpersError::ParseRadix(radix))?;
+        F::try_from_biguint(&big)
+    }
+
+    /// Serialize to a string of lowercase digits in the given radix, between
+    /// 2 and 36, without prefix
+    fn to_str_radix(&self, radix: u32) -> Result<String>
+    where
+        F: PrimeField,
+    {
+        if !(2..=36).contains(&radix) {
+            return Err(FieldHelpersError::InvalidRadix(radix));
+        }
+        Ok(self.to_biguint().to_str_radix(radix))
+    }
+
     /// Get the modulus as `BigUint`
     fn modulus_biguint() -> BigUint
     where
@@ -0,0 +1,167 @@
+//! This module provides a lightweight instrumentation of the phases of a
+//! computation, e.g. the phases of a prover.
+//!
+//! A [Phase] is a scoped timer: it is started with a name, can count the
+//! bytes allocated for its outputs, and reports when it is finished or
+//! dropped its elapsed time, its byte counter and the resident set size of the
+//! process to the global [InstrumentationSink], if one is installed with
+//! [set_sink]. Without a sink, phases do not record anything.
+//!
+//! ```
+//! use o1_utils::instrumentation::{set_sink, clear_sink, MemorySink, Phase};
+//! use std::sync::Arc;
+//!
+//! let sink = Arc::new(MemorySink::default());
+//! set_sink(sink.clone());
+//!
+//! let mut phase = Phase::start("setup");
+//! let v = vec![0u64; 1024];
+//! phase.add_bytes(8 * v.len() as u64);
+//! let phase = phase.next("compute");
+//! let _sum: u64 = v.iter().sum();
+//! phase.finish();
+//!
+//! clear_sink();
+//! let reports = sink.take();
+//! assert_eq!(reports[0].name, "setup");
+//! assert_eq!(reports[0].bytes, 8192);
+//! assert_eq!(reports[1].name, "compute");
+//! ```
+
+use std::{
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+/// The report of a finished [Phase]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseReport {
+    /// name of the phase
+    pub name: &'static str,
+    /// time elapsed between the start and the end of the phase
+    pub elapsed: Duration,
+    /// bytes counted with [Phase::add_bytes]
+    pub bytes: u64,
+    /// resident set size of the process at the end of the phase, in bytes,
+    /// if available
+    pub rss: Option<u64>,
+    /// peak resident set size of the process at the end of the phase, in
+    /// bytes, if available
+    pub peak_rss: Option<u64>,
+}
+
+/// A destination for the reports of the phases
+pub trait InstrumentationSink: Send + Sync {
+    /// Records the report of a finished phase
+    fn report(&self, report: PhaseReport);
+}
+
+impl<F: Fn(PhaseReport) + Send + Sync> InstrumentationSink for F {
+    fn report(&self, report: PhaseReport) {
+        self(report)
+    }
+}
+
+/// A sink keeping the reports in memory
+#[derive(Default)]
+pub struct MemorySink {
+    reports: Mutex<Vec<PhaseReport>>,
+}
+
+impl MemorySink {
+    /// Takes the reports recorded so far
+    pub fn take(&self) -> Vec<PhaseReport> {
+        std::mem::take(&mut self.reports.lock().unwrap())
+    }
+}
+
+impl InstrumentationSink for MemorySink {
+    fn report(&self, report: PhaseReport) {
+        self.reports.lock().unwrap().push(report)
+    }
+}
+
+/// The global sink
+static SINK: RwLock<Option<Arc<dyn InstrumentationSink>>> = RwLock::new(None);
+
+/// Installs the global sink, replacing the previous one
+pub fn set_sink(sink: Arc<dyn InstrumentationSink>) {
+    *SINK.write().unwrap() = Some(sink);
+}
+
+/// Removes the global sink, which disables the instrumentation
+pub fn clear_sink() {
+    *SINK.write().unwrap() = None;
+}
+
+/// Returns the global sink, if any
+fn sink() -> Option<Arc<dyn InstrumentationSink>> {
+    SINK.read().unwrap().clone()
+}
+
+/// A scoped timer of a phase, see the module documentation
+#[must_use = "a phase is finished when it is dropped"]
+pub struct Phase {
+    name: &'static str,
+    /// start of the phase and sink, None if the instrumentation is disabled
+    state: Option<(Instant, Arc<dyn InstrumentationSink>)>,
+    bytes: u64,
+}
+
+impl Phase {
+    /// Starts the phase `name`
+    pub fn start(name: &'static str) -> Self {
+        Phase {
+            name,
+            state: sink().map(|sink| (Instant::now(), sink)),
+            bytes: 0,
+        }
+    }
+
+    /// Adds `bytes` to the byte counter of the phase
+    pub fn add_bytes(&mut self, bytes: u64) {
+        self.bytes += bytes;
+    }
+
+    /// Finishes the phase and starts the phase `name`
+    pub fn next(self, name: &'static str) -> Self {
+        self.finish();
+        Phase::start(name)
+    }
+
+    /// Finishes the phase, which is equivalent to dropping it
+    pub fn finish(self) {}
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        if let Some((start, sink)) = self.state.take() {
+            let (rss, peak_rss) = memory_usage();
+            sink.report(PhaseReport {
+                name: self.name,
+                elapsed: start.elapsed(),
+                bytes: self.bytes,
+                rss,
+                peak_rss,
+            });
+        }
+    }
+}
+
+/// Returns the current and peak resident set sizes of the process in bytes,
+/// or None if they are not available on this platform
+pub fn memory_usage() -> (Option<u64>, Option<u64>) {
+    #[cfg(target_os = "linux")]
+    if let Ok(status) = std::fs::read_to_string("/proc/self/status") {
+        // the sizes are given in kB, e.g. `VmHWM:     1234 kB`
+        let field = |key: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(key))
+                .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+                .map(|kb: u64| kb * 1024)
+        };
+        return (field("VmRSS:"), field("VmHWM:"));
+    }
+    (None, None)
+}
@@ -3,6 +3,7 @@
 
 pub mod adjacent_pairs;
 pub mod array;
+pub mod batch_inverse;
 pub mod biguint_helpers;
 pub mod bitwise_operations;
 pub mod chunked_evaluations;
@@ -12,6 +13,7 @@ pub mod evaluations;
 pub mod field_helpers;
 pub mod foreign_field;
 pub mod hasher;
+pub mod instrumentation;
 pub mod math;
 pub mod serialization;
 
@@ -1,8 +1,11 @@
 //! This adds a few utility functions for serializing and deserializing
 //! [arkworks](http://arkworks.rs/) types that implement [CanonicalSerialize] and [CanonicalDeserialize].
 
+use crate::field_helpers::FieldHelpers;
+use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Write};
-use serde_with::Bytes;
+use serde::Deserialize;
+use serde_with::{Bytes, DeserializeAs, SerializeAs};
 use std::io::BufReader;
 
 //
@@ -46,13 +49,38 @@ pub mod ser {
 // Serialization with [serde_with]
 //
 
-/// You can use [SerdeAs] with [serde_with] in order to serialize and deserialize types that implement [CanonicalSerialize] and [CanonicalDeserialize],
-/// or containers of types that implement these traits (Vec, arrays, etc.)
-/// Simply add annotations like `#[serde_as(as = "o1_utils::serialization::SerdeAs")]`
+/// Serializes `bytes` as hex when the serializer is human-readable, as bytes
+/// otherwise
+fn serialize_bytes<S: serde::Serializer>(bytes: Vec<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        hex::serde::serialize(bytes, serializer)
+    } else {
+        Bytes::serialize_as(&bytes, serializer)
+    }
+}
+
+/// Deserializes the bytes serialized by [serialize_bytes]
+fn deserialize_bytes<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        hex::serde::deserialize(deserializer)
+    } else {
+        Bytes::deserialize_as(deserializer)
+    }
+}
+
+/// You can use [SerdeAsCompressed] with [serde_with] in order to serialize and
+/// deserialize any type that implements [CanonicalSerialize] and
+/// [CanonicalDeserialize], e.g. the affine and projective points of arkworks
+/// curves, or containers of these types (Vec, arrays, etc.), using their
+/// compressed encoding. The values are checked on deserialization, e.g. the
+/// points must be on the curve and in the prime-order subgroup.
+/// Simply add annotations like `#[serde_as(as = "o1_utils::serialization::SerdeAsCompressed")]`
 /// See <https://docs.rs/serde_with/1.10.0/serde_with/guide/serde_as/index.html#switching-from-serdes-with-to-serde_as>
-pub struct SerdeAs;
+pub struct SerdeAsCompressed;
 
-impl<T> serde_with::SerializeAs<T> for SerdeAs
+impl<T> serde_with::SerializeAs<T> for SerdeAsCompressed
 where
     T: CanonicalSerialize,
 {
@@ -63,16 +91,11 @@ where
         let mut bytes = vec![];
         val.serialize_compressed(&mut bytes)
             .map_err(serde::ser::Error::custom)?;
-
-        if serializer.is_human_readable() {
-            hex::serde::serialize(bytes, serializer)
-        } else {
-            Bytes::serialize_as(&bytes, serializer)
-        }
+        serialize_bytes(bytes, serializer)
     }
 }
 
-impl<'de, T> serde_with::DeserializeAs<'de, T> for SerdeAs
+impl<'de, T> serde_with::DeserializeAs<'de, T> for SerdeAsCompressed
 where
     T: CanonicalDeserialize,
 {
@@ -80,19 +103,17 @@ where
     where
         D: serde::Deserializer<'de>,
     {
-        let bytes: Vec<u8> = if deserializer.is_human_readable() {
-            hex::serde::deserialize(deserializer)?
-        } else {
-            Bytes::deserialize_as(deserializer)?
-        };
+        let bytes = deserialize_bytes(deserializer)?;
         T::deserialize_compressed(&mut &bytes[..]).map_err(serde::de::Error::custom)
     }
 }
 
-/// Same as `SerdeAs` but using unchecked and uncompressed (de)serialization.
-pub struct SerdeAsUnchecked;
+/// Same as [SerdeAsCompressed] but using the uncompressed encoding, which is
+/// larger but faster to deserialize. The values are still checked on
+/// deserialization, see [SerdeAsUnchecked] to skip the checks.
+pub struct SerdeAsUncompressed;
 
-impl<T> serde_with::SerializeAs<T> for SerdeAsUnchecked
+impl<T> serde_with::SerializeAs<T> for SerdeAsUncompressed
 where
     T: CanonicalSerialize,
 {
@@ -103,12 +124,39 @@ where
         let mut bytes = vec![];
         val.serialize_uncompressed(&mut bytes)
             .map_err(serde::ser::Error::custom)?;
+        serialize_bytes(bytes, serializer)
+    }
+}
 
-        if serializer.is_human_readable() {
-            hex::serde::serialize(bytes, serializer)
-        } else {
-            Bytes::serialize_as(&bytes, serializer)
-        }
+impl<'de, T> serde_with::DeserializeAs<'de, T> for SerdeAsUncompressed
+where
+    T: CanonicalDeserialize,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let bytes = deserialize_bytes(deserializer)?;
+        T::deserialize_uncompressed(&mut &bytes[..]).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The historical name of [SerdeAsCompressed], whose encoding is the same
+pub type SerdeAs = SerdeAsCompressed;
+
+/// Same as [SerdeAsUncompressed] but without checking the values on
+/// deserialization.
+pub struct SerdeAsUnchecked;
+
+impl<T> serde_with::SerializeAs<T> for SerdeAsUnchecked
+where
+    T: CanonicalSerialize,
+{
+    fn serialize_as<S>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        SerdeAsUncompressed::serialize_as(val, serializer)
     }
 }
 
@@ -120,15 +168,149 @@ where
     where
         D: serde::Deserializer<'de>,
     {
-        let bytes: Vec<u8> = if deserializer.is_human_readable() {
-            hex::serde::deserialize(deserializer)?
-        } else {
-            Bytes::deserialize_as(deserializer)?
-        };
+        let bytes = deserialize_bytes(deserializer)?;
         T::deserialize_uncompressed_unchecked(&mut &bytes[..]).map_err(serde::de::Error::custom)
     }
 }
 
+//
+// Human-readable serialization
+//
+
+/// Serializes the compressed encoding of `val`, with `human_readable` when
+/// the serializer is human-readable, as bytes otherwise
+fn serialize_human_readable<S, T>(
+    val: &T,
+    serializer: S,
+    human_readable: impl FnOnce(&T) -> Result<String, S::Error>,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: CanonicalSerialize,
+{
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&human_readable(val)?)
+    } else {
+        let mut bytes = vec![];
+        val.serialize_compressed(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        Bytes::serialize_as(&bytes, serializer)
+    }
+}
+
+/// Deserializes a value with `human_readable` when the deserializer is
+/// human-readable, from its compressed encoding otherwise
+fn deserialize_human_readable<'de, D, T>(
+    deserializer: D,
+    human_readable: impl FnOnce(&str) -> Result<T, D::Error>,
+) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: CanonicalDeserialize,
+{
+    if deserializer.is_human_readable() {
+        let s = String::deserialize(deserializer)?;
+        human_readable(&s)
+    } else {
+        let bytes: Vec<u8> = Bytes::deserialize_as(deserializer)?;
+        T::deserialize_compressed(&mut &bytes[..]).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Strips the `0x` prefix of a hex string
+fn strip_hex_prefix<E: serde::de::Error>(s: &str) -> Result<&str, E> {
+    s.strip_prefix("0x")
+        .ok_or_else(|| E::custom(format!("missing 0x prefix in {s:?}")))
+}
+
+/// You can use [SerdeAsFieldHex] with [serde_with] to serialize a field
+/// element as a `0x`-prefixed big-endian hex number when the format is
+/// human-readable (JSON, TOML, ...), and as its compressed encoding otherwise.
+/// Simply add annotations like `#[serde_as(as = "o1_utils::serialization::SerdeAsFieldHex")]`
+pub struct SerdeAsFieldHex;
+
+impl<F: PrimeField> serde_with::SerializeAs<F> for SerdeAsFieldHex {
+    fn serialize_as<S>(val: &F, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_human_readable(val, serializer, |x| {
+            let digits = x.to_str_radix(16).map_err(serde::ser::Error::custom)?;
+            Ok(format!("0x{digits}"))
+        })
+    }
+}
+
+impl<'de, F: PrimeField> serde_with::DeserializeAs<'de, F> for SerdeAsFieldHex {
+    fn deserialize_as<D>(deserializer: D) -> Result<F, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_human_readable(deserializer, |s| {
+            F::from_str_radix(strip_hex_prefix(s)?, 16).map_err(serde::de::Error::custom)
+        })
+    }
+}
+
+/// Same as [SerdeAsFieldHex], but using a decimal number when the format is
+/// human-readable
+pub struct SerdeAsFieldDecimal;
+
+impl<F: PrimeField> serde_with::SerializeAs<F> for SerdeAsFieldDecimal {
+    fn serialize_as<S>(val: &F, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_human_readable(val, serializer, |x| {
+            x.to_str_radix(10).map_err(serde::ser::Error::custom)
+        })
+    }
+}
+
+impl<'de, F: PrimeField> serde_with::DeserializeAs<'de, F> for SerdeAsFieldDecimal {
+    fn deserialize_as<D>(deserializer: D) -> Result<F, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_human_readable(deserializer, |s| {
+            F::from_str_radix(s, 10).map_err(serde::de::Error::custom)
+        })
+    }
+}
+
+/// You can use [SerdeAsPointHex] with [serde_with] to serialize a curve point,
+/// or any type implementing [CanonicalSerialize] and [CanonicalDeserialize],
+/// as the `0x`-prefixed hex string of its compressed encoding when the format
+/// is human-readable, and as its compressed encoding otherwise.
+/// Simply add annotations like `#[serde_as(as = "o1_utils::serialization::SerdeAsPointHex")]`
+pub struct SerdeAsPointHex;
+
+impl<T: CanonicalSerialize> serde_with::SerializeAs<T> for SerdeAsPointHex {
+    fn serialize_as<S>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_human_readable(val, serializer, |x| {
+            let mut bytes = vec![];
+            x.serialize_compressed(&mut bytes)
+                .map_err(serde::ser::Error::custom)?;
+            Ok(format!("0x{}", hex::encode(bytes)))
+        })
+    }
+}
+
+impl<'de, T: CanonicalDeserialize> serde_with::DeserializeAs<'de, T> for SerdeAsPointHex {
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_human_readable(deserializer, |s| {
+            let bytes = hex::decode(strip_hex_prefix(s)?).map_err(serde::de::Error::custom)?;
+            T::deserialize_compressed(&mut &bytes[..]).map_err(serde::de::Error::custom)
+        })
+    }
+}
+
 /// A generic regression serialization test for serialization via
 /// `CanonicalSerialize` and `CanonicalDeserialize`.
 pub fn test_generic_serialization_regression_canonical<
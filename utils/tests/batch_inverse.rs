@@ -0,0 +1,32 @@
+use ark_ff::{Field, One, UniformRand, Zero};
+use mina_curves::pasta::Fp;
+use o1_utils::batch_inverse::batch_inverse;
+
+#[test]
+fn test_batch_inverse() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    for size in [0, 1, 2, 17, 5000] {
+        let mut v: Vec<Fp> = (0..size).map(|_| Fp::rand(&mut rng)).collect();
+        if size > 1 {
+            v[size / 2] = Fp::zero();
+        }
+        let expected: Vec<Fp> = v
+            .iter()
+            .map(|x| x.inverse().unwrap_or_else(Fp::zero))
+            .collect();
+
+        batch_inverse(&mut v);
+        assert_eq!(v, expected);
+    }
+}
+
+#[test]
+fn test_batch_inverse_zeros() {
+    let mut v = vec![Fp::zero(); 3];
+    batch_inverse(&mut v);
+    assert_eq!(v, vec![Fp::zero(); 3]);
+
+    let mut v = vec![Fp::zero(), Fp::one(), Fp::zero()];
+    batch_inverse(&mut v);
+    assert_eq!(v, vec![Fp::zero(), Fp::one(), Fp::zero()]);
+}
@@ -24,3 +24,27 @@ fn test_chunk_poly() {
 
     assert!(eval == res);
 }
+
+#[test]
+fn test_chunks_iter() {
+    let coeffs: Vec<Fp> = (1..=5u64).map(Fp::from).collect();
+    let f = DensePolynomial::from_coefficients_slice(&coeffs);
+
+    // the last chunk is shorter, and the chunks are padded with empty ones
+    let chunks = f.chunks_iter(4, 2);
+    assert_eq!(chunks.len(), 4);
+    let chunks: Vec<&[Fp]> = chunks.collect();
+    assert_eq!(
+        chunks,
+        vec![&coeffs[0..2], &coeffs[2..4], &coeffs[4..5], &[]]
+    );
+
+    // the chunks are not truncated to the requested number
+    assert_eq!(f.chunks_iter(1, 2).count(), 3);
+
+    // the chunks match the ones of the allocated chunked polynomial
+    let chunked = f.to_chunked_polynomial(4, 2);
+    for (poly, chunk) in chunked.polys.iter().zip(f.chunks_iter(4, 2)) {
+        assert_eq!(poly.coeffs, chunk);
+    }
+}
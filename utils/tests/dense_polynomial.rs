@@ -1,5 +1,8 @@
-use ark_ff::One;
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_ff::{One, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
+    Radix2EvaluationDomain as D,
+};
 use mina_curves::pasta::Fp;
 use o1_utils::ExtendedDensePolynomial;
 
@@ -19,3 +22,54 @@ fn test_chunk() {
         assert!(eval == three);
     }
 }
+
+#[test]
+fn test_par_evaluate_many() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let f = DensePolynomial::<Fp>::rand(20, &mut rng);
+    let points: Vec<Fp> = (0..50).map(|_| Fp::rand(&mut rng)).collect();
+    let evals = f.par_evaluate_many(&points);
+    assert_eq!(evals.len(), points.len());
+    for (x, y) in points.iter().zip(evals) {
+        assert_eq!(f.evaluate(x), y);
+    }
+    assert!(f.par_evaluate_many(&[]).is_empty());
+}
+
+#[test]
+fn test_mul_fft() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let f = DensePolynomial::<Fp>::rand(17, &mut rng);
+    let g = DensePolynomial::<Fp>::rand(30, &mut rng);
+    let h = DensePolynomial::<Fp>::rand(0, &mut rng);
+    let zero = DensePolynomial::<Fp>::zero();
+
+    assert_eq!(f.mul_fft(&g), &f * &g);
+    assert_eq!(g.mul_fft(&h), &g * &h);
+    assert_eq!(f.mul_fft(&zero), zero);
+
+    let domain = D::new(64).unwrap();
+    assert_eq!(f.mul_with_domain(&g, domain), &f * &g);
+
+    assert_eq!(
+        DensePolynomial::product_with_domain(&[f.clone(), g.clone(), h.clone()], domain),
+        &(&f * &g) * &h
+    );
+    assert_eq!(
+        DensePolynomial::product_with_domain(&[f.clone(), zero, g.clone()], domain),
+        DensePolynomial::zero()
+    );
+    assert_eq!(
+        DensePolynomial::<Fp>::product_with_domain(&[], domain),
+        DensePolynomial::from_coefficients_vec(vec![Fp::one()])
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_mul_with_small_domain() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let f = DensePolynomial::<Fp>::rand(17, &mut rng);
+    let g = DensePolynomial::<Fp>::rand(30, &mut rng);
+    f.mul_with_domain(&g, D::new(32).unwrap());
+}
@@ -0,0 +1,82 @@
+use ark_ff::{One, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations, Polynomial,
+    Radix2EvaluationDomain as D,
+};
+use mina_curves::pasta::Fp;
+use o1_utils::ExtendedEvaluations;
+
+#[test]
+fn test_rotate() {
+    let domain = D::<Fp>::new(8).unwrap();
+    let evals = Evaluations::from_vec_and_domain((0..8u64).map(Fp::from).collect(), domain);
+
+    let rotated = evals.rotate(3);
+    assert_eq!(rotated.evals[0], Fp::from(3u64));
+    assert_eq!(rotated.evals[7], Fp::from(2u64));
+    assert_eq!(evals.rotate(-1).evals[0], Fp::from(7u64));
+    assert_eq!(evals.rotate(-1), evals.rotate(7));
+    assert_eq!(evals.rotate(8), evals);
+    assert_eq!(evals.rotate(-11), evals.rotate(5));
+}
+
+#[test]
+fn test_next_row() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let d1 = D::<Fp>::new(8).unwrap();
+    let d4 = D::<Fp>::new(32).unwrap();
+    let f = DensePolynomial::<Fp>::rand(7, &mut rng);
+    let f4 = f.evaluate_over_domain_by_ref(d4);
+
+    // f(ωx) with ω the generator of d1
+    let next = f4.next_row(d1.size());
+    for (i, x) in d4.elements().enumerate() {
+        assert_eq!(next.evals[i], f.evaluate(&(d1.group_gen * x)));
+    }
+    assert_eq!(f4.next_row(d4.size()), f4.rotate(1));
+}
+
+#[test]
+#[should_panic]
+fn test_next_row_invalid_base() {
+    let domain = D::<Fp>::new(8).unwrap();
+    Evaluations::from_vec_and_domain(vec![Fp::zero(); 8], domain).next_row(3);
+}
+
+#[test]
+fn test_coset_shift() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let domain = D::<Fp>::new(16).unwrap();
+    let f = DensePolynomial::<Fp>::rand(15, &mut rng);
+    let evals = f.evaluate_over_domain_by_ref(domain);
+    let offset = Fp::rand(&mut rng);
+
+    let shifted = evals.coset_shift(offset);
+    for (i, x) in domain.elements().enumerate() {
+        assert_eq!(shifted.evals[i], f.evaluate(&(offset * x)));
+    }
+    assert_eq!(evals.coset_shift(Fp::one()), evals);
+    // shifting by the generator is a rotation
+    assert_eq!(evals.coset_shift(domain.group_gen), evals.rotate(1));
+}
+
+#[test]
+fn test_pad_to_domain() {
+    let d1 = D::<Fp>::new(4).unwrap();
+    let d2 = D::<Fp>::new(8).unwrap();
+    let evals = Evaluations::from_vec_and_domain((1..5u64).map(Fp::from).collect(), d1);
+
+    let padded = evals.pad_to_domain(d2);
+    assert_eq!(padded.domain(), d2);
+    assert_eq!(&padded.evals[..4], &evals.evals[..]);
+    assert!(padded.evals[4..].iter().all(|x| x.is_zero()));
+    assert_eq!(evals.pad_to_domain(d1), evals);
+}
+
+#[test]
+#[should_panic]
+fn test_pad_to_smaller_domain() {
+    let d1 = D::<Fp>::new(4).unwrap();
+    let d2 = D::<Fp>::new(8).unwrap();
+    Evaluations::from_vec_and_domain(vec![Fp::zero(); 8], d2).pad_to_domain(d1);
+}
@@ -154,3 +154,87 @@ fn field_big() {
     assert_eq!(fe.to_biguint(), bi);
     assert_eq!(bi.to_field::<BaseField>().unwrap(), fe);
 }
+
+#[test]
+fn field_str_radix() {
+    let x = BaseField::from(255u32);
+    assert_eq!(x.to_str_radix(16), Ok("ff".to_string()));
+    assert_eq!(x.to_str_radix(10), Ok("255".to_string()));
+    assert_eq!(x.to_str_radix(2), Ok("11111111".to_string()));
+    assert_eq!(BaseField::from_str_radix("ff", 16), Ok(x));
+    assert_eq!(BaseField::from_str_radix("FF", 16), Ok(x));
+    assert_eq!(BaseField::from_str_radix("73", 36), Ok(x));
+
+    let y = -BaseField::one();
+    for radix in [2, 10, 16, 36] {
+        let digits = y.to_str_radix(radix).unwrap();
+        assert_eq!(BaseField::from_str_radix(&digits, radix), Ok(y));
+    }
+
+    assert_eq!(x.to_str_radix(1), Err(FieldHelpersError::InvalidRadix(1)));
+    assert_eq!(
+        BaseField::from_str_radix("ff", 37),
+        Err(FieldHelpersError::InvalidRadix(37))
+    );
+    assert_eq!(
+        BaseField::from_str_radix("fg", 16),
+        Err(FieldHelpersError::ParseRadix(16))
+    );
+    assert_eq!(
+        BaseField::from_str_radix("", 10),
+        Err(FieldHelpersError::ParseRadix(10))
+    );
+    // the modulus is not a canonical field element
+    assert_eq!(
+        BaseField::from_str_radix(&BaseField::modulus_biguint().to_str_radix(10), 10),
+        Err(FieldHelpersError::NonCanonical)
+    );
+}
+
+#[test]
+fn field_checked_conversions() {
+    let modulus = BaseField::modulus_biguint();
+    let max = &modulus - 1u32;
+    assert_eq!(BaseField::try_from_biguint(&max), Ok(-BaseField::one()));
+    assert_eq!(
+        BaseField::try_from_biguint(&BigUint::from(0u32)),
+        Ok(BaseField::from(0u32))
+    );
+    assert_eq!(
+        BaseField::try_from_biguint(&modulus),
+        Err(FieldHelpersError::NonCanonical)
+    );
+    assert_eq!(
+        BaseField::try_from_biguint(&(&modulus + 5u32)),
+        Err(FieldHelpersError::NonCanonical)
+    );
+    // the unchecked conversion reduces the value
+    assert_eq!(
+        BaseField::from_biguint(&(&modulus + 5u32)),
+        Ok(BaseField::from(5u32))
+    );
+
+    let mut bytes = max.to_bytes_le();
+    bytes.resize(BaseField::size_in_bytes(), 0);
+    assert_eq!(BaseField::from_bytes_checked(&bytes), Ok(-BaseField::one()));
+    let mut bytes = modulus.to_bytes_le();
+    bytes.resize(BaseField::size_in_bytes(), 0);
+    assert_eq!(
+        BaseField::from_bytes_checked(&bytes),
+        Err(FieldHelpersError::NonCanonical)
+    );
+    assert_eq!(
+        BaseField::from_bytes_checked(&[0xff; 32]),
+        Err(FieldHelpersError::NonCanonical)
+    );
+    // the encoding has exactly the size of a field element
+    assert_eq!(
+        BaseField::from_bytes_checked(&[1; 31]),
+        Err(FieldHelpersError::DeserializeBytes)
+    );
+    assert_eq!(
+        BaseField::from_bytes_checked(&[1; 33]),
+        Err(FieldHelpersError::DeserializeBytes)
+    );
+    assert!(BaseField::from_bytes(&[1; 33]).is_ok());
+}
@@ -0,0 +1,54 @@
+use o1_utils::instrumentation::{clear_sink, memory_usage, set_sink, MemorySink, Phase};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+// The sink is global, so the scenarios run in a single test
+#[test]
+fn test_phases() {
+    // without a sink, nothing is recorded
+    let sink = Arc::new(MemorySink::default());
+    let phase = Phase::start("disabled");
+    set_sink(sink.clone());
+    phase.finish();
+    assert!(sink.take().is_empty());
+
+    // phases report when finished, dropped or followed by another phase
+    let mut phase = Phase::start("first");
+    phase.add_bytes(3);
+    phase.add_bytes(4);
+    let phase = phase.next("second");
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    drop(phase);
+    let reports = sink.take();
+    assert_eq!(reports.len(), 2);
+    assert_eq!((reports[0].name, reports[0].bytes), ("first", 7));
+    assert_eq!((reports[1].name, reports[1].bytes), ("second", 0));
+    assert!(reports[1].elapsed >= std::time::Duration::from_millis(5));
+    if cfg!(target_os = "linux") {
+        let (rss, peak_rss) = (reports[1].rss.unwrap(), reports[1].peak_rss.unwrap());
+        assert!(rss > 0 && rss <= peak_rss);
+    }
+
+    // any closure can be a sink
+    let count = Arc::new(AtomicUsize::new(0));
+    let counter = count.clone();
+    set_sink(Arc::new(move |_| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }));
+    Phase::start("closure").finish();
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+
+    clear_sink();
+    Phase::start("cleared").finish();
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+    assert!(sink.take().is_empty());
+}
+
+#[test]
+fn test_memory_usage() {
+    let (rss, peak_rss) = memory_usage();
+    assert_eq!(rss.is_some(), cfg!(target_os = "linux"));
+    assert_eq!(peak_rss.is_some(), cfg!(target_os = "linux"));
+}
@@ -3,6 +3,7 @@ use mina_curves::pasta::{Pallas, PallasParameters, Vesta, VestaParameters};
 use o1_utils::serialization::{
     test_generic_serialization_regression_canonical, test_generic_serialization_regression_serde,
 };
+use o1_utils::FieldHelpers;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -74,3 +75,136 @@ pub fn ser_regression_canonical_pasta() {
 
     test_generic_serialization_regression_serde(data_expected, buf_expected);
 }
+
+#[test]
+pub fn ser_human_readable() {
+    use mina_curves::pasta::Fp;
+    use o1_utils::serialization::SerdeAs;
+
+    #[serde_as]
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct TestStruct {
+        #[serde_as(as = "o1_utils::serialization::SerdeAsFieldHex")]
+        hex: Fp,
+        #[serde_as(as = "o1_utils::serialization::SerdeAsFieldDecimal")]
+        decimal: Fp,
+        #[serde_as(as = "o1_utils::serialization::SerdeAsPointHex")]
+        point: Pallas,
+    }
+
+    #[serde_as]
+    #[derive(Serialize)]
+    struct CompactStruct {
+        #[serde_as(as = "SerdeAs")]
+        hex: Fp,
+        #[serde_as(as = "SerdeAs")]
+        decimal: Fp,
+        #[serde_as(as = "SerdeAs")]
+        point: Pallas,
+    }
+
+    let data = TestStruct {
+        hex: Fp::from(0x1234u64),
+        decimal: -Fp::from(1u64),
+        point: PallasParameters::GENERATOR,
+    };
+
+    let json = serde_json::to_string(&data).unwrap();
+    let point_hex = format!("0x01{}", "00".repeat(32));
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"hex":"0x1234","decimal":"{}","point":"{point_hex}"}}"#,
+            (Fp::modulus_biguint() - 1u64)
+        )
+    );
+    assert_eq!(serde_json::from_str::<TestStruct>(&json).unwrap(), data);
+
+    // the binary form is the same as with SerdeAs
+    let compact = CompactStruct {
+        hex: data.hex,
+        decimal: data.decimal,
+        point: data.point,
+    };
+    let bytes = rmp_serde::to_vec(&data).unwrap();
+    assert_eq!(bytes, rmp_serde::to_vec(&compact).unwrap());
+    assert_eq!(rmp_serde::from_slice::<TestStruct>(&bytes).unwrap(), data);
+
+    // malformed or non-canonical inputs are rejected
+    let modulus = Fp::modulus_biguint().to_string();
+    for json in [
+        format!(r#"{{"hex":"1234","decimal":"1","point":"{point_hex}"}}"#),
+        format!(r#"{{"hex":"0xzz","decimal":"1","point":"{point_hex}"}}"#),
+        format!(r#"{{"hex":"0x1","decimal":"{modulus}","point":"{point_hex}"}}"#),
+        r#"{"hex":"0x1","decimal":"1","point":"0x01"}"#.to_string(),
+    ] {
+        assert!(serde_json::from_str::<TestStruct>(&json).is_err());
+    }
+}
+
+#[test]
+pub fn ser_compressed_and_uncompressed() {
+    use ark_ec::{AffineRepr, CurveGroup};
+    use mina_curves::pasta::Fq;
+    use mina_curves::pasta::ProjectivePallas;
+    use o1_utils::serialization::{
+        SerdeAs, SerdeAsCompressed, SerdeAsUnchecked, SerdeAsUncompressed,
+    };
+    use serde_with::{ser::SerializeAsWrap, DeserializeAs};
+
+    fn encode<T, U: serde_with::SerializeAs<T>>(val: &T) -> Vec<u8> {
+        rmp_serde::to_vec(&SerializeAsWrap::<T, U>::new(val)).unwrap()
+    }
+    fn decode<T, U: for<'de> DeserializeAs<'de, T>>(bytes: &[u8]) -> Option<T> {
+        U::deserialize_as(&mut rmp_serde::Deserializer::new(bytes)).ok()
+    }
+
+    let point = (PallasParameters::GENERATOR * Fq::from(42u64)).into_affine();
+    let projective: ProjectivePallas = point.into_group();
+
+    // the compressed encoding is the historical one
+    let compressed = encode::<_, SerdeAsCompressed>(&point);
+    assert_eq!(compressed, encode::<_, SerdeAs>(&point));
+    assert_eq!(
+        decode::<Pallas, SerdeAsCompressed>(&compressed),
+        Some(point)
+    );
+    // projective points use the same encoding
+    assert_eq!(encode::<_, SerdeAsCompressed>(&projective), compressed);
+    assert_eq!(
+        decode::<ProjectivePallas, SerdeAsCompressed>(&compressed),
+        Some(projective)
+    );
+
+    let uncompressed = encode::<_, SerdeAsUncompressed>(&point);
+    assert!(uncompressed.len() > compressed.len());
+    assert_eq!(uncompressed, encode::<_, SerdeAsUnchecked>(&point));
+    assert_eq!(
+        decode::<Pallas, SerdeAsUncompressed>(&uncompressed),
+        Some(point)
+    );
+    assert_eq!(
+        decode::<Pallas, SerdeAsUnchecked>(&uncompressed),
+        Some(point)
+    );
+
+    // a point which is not on the curve is only accepted without checks
+    let mut invalid = uncompressed.clone();
+    invalid[4] ^= 1;
+    assert_eq!(decode::<Pallas, SerdeAsUncompressed>(&invalid), None);
+    assert!(decode::<Pallas, SerdeAsUnchecked>(&invalid).is_some_and(|p| !p.is_on_curve()));
+
+    // human-readable formats use hex
+    #[serde_as]
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct TestStruct {
+        #[serde_as(as = "SerdeAsUncompressed")]
+        point: Pallas,
+    }
+    let json = serde_json::to_string(&TestStruct { point }).unwrap();
+    assert!(json.starts_with(r#"{"point":""#));
+    assert_eq!(
+        serde_json::from_str::<TestStruct>(&json).unwrap(),
+        TestStruct { point }
+    );
+}